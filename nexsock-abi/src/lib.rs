@@ -16,6 +16,28 @@ use std::path::PathBuf;
 
 pub type PreHooks = HashMap<PathBuf, AbiConnection<dyn PreHook>>;
 
+/// The version of the plugin ABI, bumped whenever [`PreHook`] or the types it takes change.
+///
+/// The daemon skips native plugins that were built against another version.
+pub const ABI_VERSION: u32 = 1;
+
+/// Exports the [`ABI_VERSION`] the plugin is built against, the daemon refuses to load native
+/// plugins that don't export it.
+///
+/// ```ignore
+/// savefile_abi_export!(MyPlugin, PreHook);
+/// nexsock_abi::export_abi_version!();
+/// ```
+#[macro_export]
+macro_rules! export_abi_version {
+    () => {
+        #[no_mangle]
+        pub extern "C" fn nexsock_abi_version() -> u32 {
+            $crate::ABI_VERSION
+        }
+    };
+}
+
 #[savefile_abi_exportable(version = 0)]
 pub trait PreHook: Send + Sync {
     /// Read the incoming command before the daemon handles it, at the moment we cant send the payload
@@ -1,19 +1,32 @@
 use anyhow::{anyhow, bail, Context, Result};
 use bincode::Encode;
 use deadpool::managed::{Manager, Metrics, RecycleResult};
+use futures::stream::{self, Stream};
 use nexsock_protocol::commands::error::ErrorPayload;
+use nexsock_protocol::commands::event::{SequencedEvent, SubscribeCommand, SubscribePayload};
+use nexsock_protocol::commands::list_services::{ListServicesCommand, ListServicesResponse};
+use nexsock_protocol::commands::manage_service::{
+    RestartServiceCommand, ServiceRef, StartServiceCommand, StartServicePayload, StopServiceCommand,
+};
+use nexsock_protocol::commands::service_status::{GetServiceStatus, ServiceStatus};
+use nexsock_protocol::commands::stdout::{
+    GetServiceStdout, GetServiceStdoutPayload, ServiceStdout,
+};
 use nexsock_protocol::commands::{Command, CommandPayload, PingCommand};
 use nexsock_protocol::header::MessageFlags;
 use nexsock_protocol::protocol::Protocol;
 use nexsock_protocol::traits::ServiceCommand;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
 #[cfg(unix)]
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::io::{BufReader, BufWriter};
 #[cfg(windows)]
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 #[cfg(windows)]
 use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::time::sleep;
 use tracing::{debug, error};
 
 use nexsock_config::{NexsockConfig, SocketRef};
@@ -44,7 +57,7 @@ impl Manager for ClientManager {
 
     #[tracing::instrument(level = "trace", skip(self))]
     async fn create(&self) -> std::result::Result<Self::Type, Self::Error> {
-        Ok(match self.config.socket() {
+        let client = match self.config.socket() {
             SocketRef::Port(_port) => {
                 #[cfg(unix)]
                 bail!("When on Unix Tcp sockets are not available, please modify config to be a path to the socket file");
@@ -57,7 +70,9 @@ impl Manager for ClientManager {
                 #[cfg(unix)]
                 Client::connect(_path).await?
             }
-        })
+        };
+
+        Ok(client.with_checksums(self.config.server().checksums))
     }
 
     #[tracing::instrument(level = "trace", skip(self, client))]
@@ -76,6 +91,26 @@ impl Manager for ClientManager {
     }
 }
 
+/// How often [`Client::follow_stdout`] asks the daemon for new output once it caught up.
+pub const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A line of output of a service, yielded by [`Client::follow_stdout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLine {
+    /// The line without its trailing newline.
+    pub content: String,
+}
+
+/// What [`Client::follow_stdout`] keeps between polls.
+struct FollowState<'a> {
+    client: &'a mut Client,
+    service: ServiceRef,
+    /// The cursor of the next poll, `None` until the buffered output was fetched.
+    after_seq: Option<u64>,
+    /// Lines fetched by the last poll that were not yielded yet.
+    pending: VecDeque<LogLine>,
+}
+
 #[derive(Debug)]
 pub struct Client {
     reader: BufReader<OwnedReadHalf>,
@@ -115,6 +150,12 @@ impl Client {
         })
     }
 
+    /// Follows the payloads sent to the daemon with a CRC32, see [`Protocol::with_checksums`].
+    pub fn with_checksums(mut self, checksums: bool) -> Self {
+        self.protocol = self.protocol.with_checksums(checksums);
+        self
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     /// Sends a command to the daemon and returns the decoded response payload.
     ///
@@ -132,8 +173,9 @@ impl Client {
         C::Input: Encode + Debug,
     {
         let payload = command.into_payload();
+        let request_id = self.protocol.next_request_id();
 
-        debug!("Sending command: {:?}", C::COMMAND);
+        debug!(request_id, "Sending command: {:?}", C::COMMAND);
         self.protocol
             .write_command_with_payload(
                 &mut self.writer,
@@ -146,13 +188,202 @@ impl Client {
 
         debug!("Awaiting response");
 
-        self.handle_response().await
+        self.handle_response(request_id).await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    /// Sends a command to the daemon and converts the response into the output of the command.
+    ///
+    /// Errors the daemon answers with are returned as errors, the same way
+    /// [`Client::execute_command`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut client = Client::connect("/tmp/daemon.sock").await?;
+    /// let services: ListServicesResponse = client.execute(ListServicesCommand::new()).await?;
+    /// ```
+    pub async fn execute<C>(&mut self, command: C) -> Result<C::Output>
+    where
+        C: ServiceCommand,
+        C::Input: Encode + Debug,
+        C::Output: TryFrom<CommandPayload, Error = anyhow::Error>,
+    {
+        let payload = self.execute_command(command).await?;
+
+        C::Output::try_from(payload)
+            .with_context(|| format!("Unexpected response to {:?}", C::COMMAND))
+    }
+
+    /// Lists every service known to the daemon.
+    pub async fn list_services(&mut self) -> Result<ListServicesResponse> {
+        self.execute(ListServicesCommand::new()).await
+    }
+
+    /// Fetches the status of `service`, including its configuration and dependencies.
+    pub async fn status(&mut self, service: impl Into<ServiceRef>) -> Result<ServiceStatus> {
+        self.execute(GetServiceStatus::new(service.into())).await
+    }
+
+    /// Starts `service` with the extra environment variables in `env_vars`.
+    ///
+    /// Returns once the process is running, use [`StartServiceCommand`] with
+    /// [`Client::execute`] to wait for the service to be ready instead.
+    pub async fn start(
+        &mut self,
+        service: impl Into<ServiceRef>,
+        env_vars: HashMap<String, String>,
+    ) -> Result<()> {
+        self.execute(StartServiceCommand::from(StartServicePayload {
+            service: service.into(),
+            env_vars,
+            ..Default::default()
+        }))
+        .await
+    }
+
+    /// Stops `service`.
+    pub async fn stop(&mut self, service: impl Into<ServiceRef>) -> Result<()> {
+        self.execute(StopServiceCommand::new(service.into())).await
+    }
+
+    /// Restarts `service` with the extra environment variables in `env_vars`.
+    pub async fn restart(
+        &mut self,
+        service: impl Into<ServiceRef>,
+        env_vars: HashMap<String, String>,
+    ) -> Result<()> {
+        self.execute(RestartServiceCommand::from(StartServicePayload {
+            service: service.into(),
+            env_vars,
+            ..Default::default()
+        }))
+        .await
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    /// Subscribes to the service events matching `payload`, received with [`Client::next_event`].
+    ///
+    /// The connection is taken by the subscription, other commands can't be run on it anymore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut client = Client::connect("/tmp/daemon.sock").await?;
+    /// client.subscribe(SubscribePayload::default()).await?;
+    /// let event = client.next_event().await?;
+    /// ```
+    pub async fn subscribe(&mut self, payload: SubscribePayload) -> Result<()> {
+        self.execute_command(SubscribeCommand::from(payload))
+            .await
+            .context("Failed to subscribe to events")?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    /// Waits for the next event of the subscription made with [`Client::subscribe`].
+    ///
+    /// Returns an error once the daemon closed the connection.
+    pub async fn next_event(&mut self) -> Result<SequencedEvent> {
+        let request_id = self.protocol.request_id();
+        let payload = self.handle_response(request_id).await?;
+
+        SequencedEvent::try_from(payload)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    /// Subscribes to the service events matching `payload` and returns them as a stream.
+    ///
+    /// The subscription is made before this returns, so a refused subscription, e.g. for an
+    /// unknown service, is returned as an error right away. The stream yields an error and ends
+    /// once the daemon closed the connection, which is taken by the subscription.
+    ///
+    /// To resume after reconnecting, subscribe again with `after_seq` set to the `seq` of the last
+    /// event received, the daemon then first replays the recent events that were missed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut client = Client::connect("/tmp/daemon.sock").await?;
+    /// let mut events = pin!(client.subscribe_events(SubscribePayload::default()).await?);
+    /// while let Some(event) = events.try_next().await? {
+    ///     println!("{}: {}", event.seq, event.event);
+    /// }
+    /// ```
+    pub async fn subscribe_events(
+        &mut self,
+        payload: SubscribePayload,
+    ) -> Result<impl Stream<Item = Result<SequencedEvent>> + '_> {
+        self.subscribe(payload).await?;
+
+        Ok(stream::try_unfold(self, |client| async move {
+            let event = client.next_event().await;
+
+            event.map(|event| Some((event, client)))
+        }))
+    }
+
+    /// Follows the captured output of `service`, yielding every line as the service writes it.
+    ///
+    /// Starts with the output that is still buffered, then polls the daemon for new output every
+    /// [`FOLLOW_POLL_INTERVAL`]. The stream yields an error and ends if a poll fails, e.g. once
+    /// the service was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut client = Client::connect("/tmp/daemon.sock").await?;
+    /// let mut lines = pin!(client.follow_stdout(ServiceRef::Name("web".into())));
+    /// while let Some(line) = lines.try_next().await? {
+    ///     println!("{}", line.content);
+    /// }
+    /// ```
+    pub fn follow_stdout(
+        &mut self,
+        service: ServiceRef,
+    ) -> impl Stream<Item = Result<LogLine>> + '_ {
+        let state = FollowState {
+            client: self,
+            service,
+            after_seq: None,
+            pending: VecDeque::new(),
+        };
+
+        stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(line) = state.pending.pop_front() {
+                    return anyhow::Ok(Some((line, state)));
+                }
+
+                let output = state
+                    .client
+                    .execute(GetServiceStdout::new(GetServiceStdoutPayload {
+                        service: state.service.clone(),
+                        after_seq: state.after_seq,
+                    }))
+                    .await?;
+
+                let ServiceStdout { content, last_seq } = output;
+                state.pending.extend(content.lines().map(|line| LogLine {
+                    content: line.to_string(),
+                }));
+
+                state.after_seq = Some(last_seq);
+
+                if state.pending.is_empty() {
+                    sleep(FOLLOW_POLL_INTERVAL).await;
+                }
+            }
+        })
     }
 
     #[tracing::instrument(level = "debug", skip_all, err)]
     /// Handles and decodes a response from the daemon.
     ///
     /// Reads a message from the socket, interprets the response command, and returns the decoded payload.
+    /// Heartbeats the daemon sent while the connection was idle are acknowledged and skipped.
+    /// A response carrying a different request id than `request_id` is treated as an error.
     /// Returns an error if the response indicates a server error, is malformed, or contains an unexpected command.
     ///
     /// # Returns
@@ -162,14 +393,33 @@ impl Client {
     ///
     /// ```
     /// let mut client = Client::connect(...).await?;
-    /// let payload = client.handle_response().await?;
+    /// let payload = client.handle_response(request_id).await?;
     /// ```
-    async fn handle_response(&mut self) -> Result<CommandPayload> {
-        let (header, payload) = self
-            .protocol
-            .read_message(&mut self.reader)
-            .await
-            .context("Failed to read message")?;
+    async fn handle_response(&mut self, request_id: u64) -> Result<CommandPayload> {
+        let (header, payload) = loop {
+            let (header, payload) = self
+                .protocol
+                .read_message(&mut self.reader)
+                .await
+                .context("Failed to read message")?;
+
+            if !matches!(header.command, Command::Heartbeat) {
+                break (header, payload);
+            }
+
+            debug!("Acknowledging heartbeat from daemon");
+            self.protocol
+                .write_heartbeat_ack(&mut self.writer)
+                .await
+                .context("Failed to acknowledge heartbeat")?;
+        };
+
+        if header.request_id() != request_id {
+            bail!(
+                "Response request id `{}` does not match request id `{request_id}`",
+                header.request_id()
+            )
+        }
 
         match header.command {
             Command::Success => {
@@ -0,0 +1,50 @@
+//! # Prelude Module
+//!
+//! This module re-exports the client together with the commands and payloads it is most
+//! commonly used with, so a single import is enough to talk to the daemon.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use nexsock_client::prelude::*;
+//! use std::collections::HashMap;
+//!
+//! let start = StartServiceCommand::from(StartServicePayload {
+//!     service: ServiceRef::Name("api".to_string()),
+//!     env_vars: HashMap::from([("PORT".to_string(), "8080".to_string())]),
+//!     ..Default::default()
+//! });
+//! assert!(matches!(StartServiceCommand::COMMAND, Command::StartService));
+//! assert_eq!(start.into_payload().env_vars["PORT"], "8080");
+//!
+//! /// Names of the services the daemon is running, with the typed result of `Client::execute`.
+//! async fn running(client: &mut Client) -> anyhow::Result<Vec<String>> {
+//!     let services: ListServicesResponse = client.execute(ListServicesCommand::new()).await?;
+//!
+//!     Ok(services
+//!         .services
+//!         .into_iter()
+//!         .filter(|service| service.state == ServiceState::Running)
+//!         .map(|service| service.name)
+//!         .collect())
+//! }
+//! ```
+
+pub use crate::client::{Client, ClientManager, LogLine, FOLLOW_POLL_INTERVAL};
+pub use nexsock_config::NexsockConfig;
+pub use nexsock_protocol::commands::error::ErrorPayload;
+pub use nexsock_protocol::commands::event::{
+    SequencedEvent, ServiceEvent, ServiceEventKind, SubscribePayload,
+};
+pub use nexsock_protocol::commands::list_services::{
+    ListServicesCommand, ListServicesResponse, ServiceInfo,
+};
+pub use nexsock_protocol::commands::manage_service::{
+    CloneServiceCommand, ReadyCondition, RemovalPlan, RemoveServiceCommand, RestartServiceCommand,
+    ServiceRef, StartServiceCommand, StartServicePayload, StopServiceCommand,
+};
+pub use nexsock_protocol::commands::service_status::{
+    GetServiceStatus, ServiceState, ServiceStatus,
+};
+pub use nexsock_protocol::commands::{Command, CommandPayload};
+pub use nexsock_protocol::traits::ServiceCommand;
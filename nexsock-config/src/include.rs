@@ -0,0 +1,120 @@
+//! Support for splitting the configuration across several files.
+//!
+//! The top-level `include` key of `config.toml` lists glob patterns, relative patterns are resolved
+//! against the config directory. Matched files are merged after `config.toml` in the order the
+//! patterns are listed, files matched by a single pattern are merged in alphabetical order, and
+//! later files override values from earlier ones. Includes inside included files are ignored.
+
+use crate::{ConfigResult, NexsockConfigError};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Returns the files referenced by the `include` key of the config document in `contents`.
+///
+/// Patterns that match nothing and paths that can't be read while globbing are logged and skipped.
+///
+/// # Errors
+///
+/// Returns an error if `include` is not a list of strings or if a pattern is malformed.
+pub(crate) fn resolve_includes(config_dir: &Path, contents: &str) -> ConfigResult<Vec<PathBuf>> {
+    let document: toml::Value = toml::from_str(contents).map_err(|e| {
+        NexsockConfigError::InvalidPath(format!("Failed to parse config file: {e}"))
+    })?;
+
+    let Some(include) = document.get("include") else {
+        return Ok(Vec::new());
+    };
+
+    let patterns = include
+        .as_array()
+        .and_then(|patterns| patterns.iter().map(toml::Value::as_str).collect::<Option<Vec<_>>>())
+        .ok_or_else(|| {
+            NexsockConfigError::InvalidPath("`include` must be a list of glob patterns".to_string())
+        })?;
+
+    let mut files = Vec::new();
+
+    for pattern in patterns {
+        let full_pattern = config_dir.join(pattern);
+
+        let entries = glob::glob(&full_pattern.to_string_lossy()).map_err(|e| {
+            NexsockConfigError::InvalidPath(format!("Invalid include pattern `{pattern}`: {e}"))
+        })?;
+
+        let matched = files.len();
+
+        for entry in entries {
+            match entry {
+                Ok(path) if path.is_file() => files.push(path),
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(pattern, error = %e, "Skipping unreadable config include");
+                }
+            }
+        }
+
+        if files.len() == matched {
+            warn!(pattern, "Config include did not match any files");
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NexsockConfig;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn included_files_are_merged_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+
+        write(
+            dir.path(),
+            "config.toml",
+            r#"
+include = ["missing.d/*.toml", "services.d/*.toml"]
+log_str = "base"
+
+[server]
+cleanup_interval = 100
+heartbeat_interval = 10
+"#,
+        );
+        write(
+            dir.path(),
+            "services.d/10-first.toml",
+            r#"
+log_str = "first"
+
+[server]
+cleanup_interval = 200
+"#,
+        );
+        write(dir.path(), "services.d/20-second.toml", r#"log_str = "second""#);
+
+        let config = NexsockConfig::from_file(Some(dir.path())).unwrap();
+
+        assert_eq!(config.inner.log_str, "second");
+        assert_eq!(config.server().cleanup_interval, 200);
+        assert_eq!(config.server().heartbeat_interval, 10);
+    }
+
+    #[test]
+    fn include_must_be_a_list_of_valid_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(resolve_includes(dir.path(), r#"include = "services.d/*.toml""#).is_err());
+        assert!(resolve_includes(dir.path(), r#"include = ["[services.d"]"#).is_err());
+        assert!(resolve_includes(dir.path(), r#"log_str = "info""#)
+            .unwrap()
+            .is_empty());
+    }
+}
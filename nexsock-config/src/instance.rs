@@ -0,0 +1,193 @@
+//! Namespacing of the daemon state so several daemons can run side by side.
+//!
+//! An instance is selected with `--instance <name>` or the [`INSTANCE_ENV`] environment
+//! variable. It changes the defaults of everything daemons would otherwise share:
+//!
+//! | Default      | Without an instance   | Instance `<name>`          |
+//! |--------------|-----------------------|----------------------------|
+//! | Socket       | `<tmp>/nexsock.sock`  | `<tmp>/nexsock-<name>.sock` |
+//! | Data dir     | `<data>`              | `<data>/<name>`            |
+//! | Database     | `<data>/db/state.db`  | `<data>/<name>/state.db`   |
+//!
+//! On Windows the socket is a TCP port, instances listen on a port derived from their name.
+//! Values set in the config file or through `DATABASE_URL` are used as they are.
+
+use crate::{ConfigResult, NexsockConfigError, SocketRef, PROJECT_DIRECTORIES};
+use std::path::{Path, PathBuf};
+
+/// Environment variable selecting the instance, an empty value selects the default instance.
+pub const INSTANCE_ENV: &str = "NEXSOCK_INSTANCE";
+
+/// Port of the default instance on platforms without Unix sockets.
+const DEFAULT_PORT: u16 = 50505;
+
+/// Number of ports after [`DEFAULT_PORT`] that instances are spread over.
+const INSTANCE_PORTS: u16 = 1000;
+
+/// The paths a daemon instance keeps its state in.
+#[derive(Debug, Clone)]
+pub struct InstancePaths {
+    /// The socket the daemon listens on.
+    pub socket: SocketRef,
+    /// The directory holding the data of the instance.
+    pub data_dir: PathBuf,
+    /// The SQLite database of the instance.
+    pub database: PathBuf,
+}
+
+impl InstancePaths {
+    /// Returns the default paths of `instance`, `None` being the default instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `instance` is not a valid instance name, see [`validate_instance`].
+    pub fn resolve(instance: Option<&str>) -> ConfigResult<Self> {
+        Self::under(&std::env::temp_dir(), PROJECT_DIRECTORIES.data_dir(), instance)
+    }
+
+    /// Like [`InstancePaths::resolve`], with sockets placed in `socket_dir` and data in
+    /// `data_root`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nexsock_config::InstancePaths;
+    /// use std::path::Path;
+    ///
+    /// let paths = InstancePaths::under(Path::new("/tmp"), Path::new("/data"), Some("ci")).unwrap();
+    ///
+    /// assert_eq!(paths.data_dir, Path::new("/data/ci"));
+    /// assert_eq!(paths.database, Path::new("/data/ci/state.db"));
+    /// ```
+    pub fn under(
+        socket_dir: &Path,
+        data_root: &Path,
+        instance: Option<&str>,
+    ) -> ConfigResult<Self> {
+        let Some(name) = instance else {
+            return Ok(Self {
+                socket: default_socket(socket_dir.join("nexsock.sock"), DEFAULT_PORT),
+                data_dir: data_root.to_path_buf(),
+                database: data_root.join("db/state.db"),
+            });
+        };
+
+        validate_instance(name)?;
+
+        let data_dir = data_root.join(name);
+
+        Ok(Self {
+            socket: default_socket(
+                socket_dir.join(format!("nexsock-{name}.sock")),
+                instance_port(name),
+            ),
+            database: data_dir.join("state.db"),
+            data_dir,
+        })
+    }
+}
+
+/// Returns the instance selected through [`INSTANCE_ENV`], `None` when it is unset or empty.
+///
+/// # Errors
+///
+/// Returns an error if the variable holds an invalid instance name.
+pub fn instance_from_env() -> ConfigResult<Option<String>> {
+    match std::env::var(INSTANCE_ENV) {
+        Ok(name) if !name.is_empty() => {
+            validate_instance(&name)?;
+            Ok(Some(name))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Checks that `name` can be used in file names, it may only hold ASCII letters, digits, `-`
+/// and `_`.
+///
+/// # Errors
+///
+/// Returns [`NexsockConfigError::InvalidInstance`] if it can't.
+pub fn validate_instance(name: &str) -> ConfigResult<()> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(NexsockConfigError::InvalidInstance(name.to_string()))
+    }
+}
+
+fn default_socket(path: PathBuf, port: u16) -> SocketRef {
+    if cfg!(unix) {
+        SocketRef::Path(path)
+    } else {
+        SocketRef::Port(port)
+    }
+}
+
+/// Derives the port of an instance from its name with FNV-1a, which unlike the std hasher is
+/// stable across builds so the CLI and the daemon agree on it.
+fn instance_port(name: &str) -> u16 {
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+
+    DEFAULT_PORT + 1 + (hash % u32::from(INSTANCE_PORTS)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NexsockConfig;
+
+    #[test]
+    fn test_instances_do_not_share_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let (sockets, data) = (dir.path().join("run"), dir.path().join("data"));
+
+        let first = InstancePaths::under(&sockets, &data, Some("first")).unwrap();
+        let second = InstancePaths::under(&sockets, &data, Some("second")).unwrap();
+        let default = InstancePaths::under(&sockets, &data, None).unwrap();
+
+        if cfg!(unix) {
+            assert_eq!(first.socket.clone().unwrap_path(), sockets.join("nexsock-first.sock"));
+            assert_eq!(default.socket.clone().unwrap_path(), sockets.join("nexsock.sock"));
+        }
+        assert_eq!(first.database, data.join("first/state.db"));
+        assert_eq!(default.database, data.join("db/state.db"));
+
+        for (a, b) in [(&first, &second), (&first, &default), (&second, &default)] {
+            assert_ne!(a.socket.to_string(), b.socket.to_string());
+            assert_ne!(a.database, b.database);
+            assert_ne!(a.data_dir, b.data_dir);
+        }
+    }
+
+    #[test]
+    fn test_config_of_each_instance_uses_its_socket() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let first = NexsockConfig::for_instance(Some(dir.path()), Some("first")).unwrap();
+        let second = NexsockConfig::for_instance(Some(dir.path()), Some("second")).unwrap();
+
+        assert_eq!(first.instance(), Some("first"));
+        assert_ne!(first.socket().to_string(), second.socket().to_string());
+        assert_ne!(first.server().socket.to_string(), second.server().socket.to_string());
+    }
+
+    #[test]
+    fn test_invalid_instance_names_are_rejected() {
+        for name in ["", "../escape", "with space", "a/b"] {
+            assert!(matches!(
+                validate_instance(name),
+                Err(NexsockConfigError::InvalidInstance(_))
+            ));
+        }
+
+        assert!(validate_instance("ci-runner_2").is_ok());
+    }
+}
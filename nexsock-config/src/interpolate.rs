@@ -0,0 +1,182 @@
+//! Expansion of environment variable references in configuration values.
+//!
+//! String values may reference environment variables as `${VAR}`, or as `${VAR:-default}` to fall
+//! back to `default` when the variable is unset or empty. A literal `${` can be written as `$${`.
+
+use crate::{ConfigResult, NexsockConfigError};
+
+/// Expands every `${VAR}` and `${VAR:-default}` reference in the string values of a TOML document.
+///
+/// Keys and non-string values are left as they are, so an expanded value can never change the
+/// structure of the document.
+pub(crate) fn interpolate_toml(contents: &str) -> ConfigResult<String> {
+    let mut document: toml::Value = toml::from_str(contents).map_err(|e| {
+        NexsockConfigError::Interpolation(format!("Failed to parse config file: {e}"))
+    })?;
+
+    interpolate_value(&mut document, &|name| std::env::var(name).ok())?;
+
+    toml::to_string(&document).map_err(|e| {
+        NexsockConfigError::Interpolation(format!("Failed to serialize config file: {e}"))
+    })
+}
+
+fn interpolate_value(
+    value: &mut toml::Value,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> ConfigResult<()> {
+    match value {
+        toml::Value::String(string) => *string = interpolate_str(string, lookup)?,
+        toml::Value::Array(values) => {
+            for value in values {
+                interpolate_value(value, lookup)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate_value(value, lookup)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Expands the variable references in a single string, resolving names with `lookup`.
+fn interpolate_str(input: &str, lookup: &impl Fn(&str) -> Option<String>) -> ConfigResult<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            output.push_str("${");
+            rest = escaped;
+            continue;
+        }
+
+        let Some(reference) = rest.strip_prefix("${") else {
+            output.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let end = reference.find('}').ok_or_else(|| {
+            NexsockConfigError::Interpolation(format!(
+                "Unterminated variable reference in `{input}`"
+            ))
+        })?;
+
+        let (name, default) = match reference[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&reference[..end], None),
+        };
+
+        if name.is_empty() {
+            return Err(NexsockConfigError::Interpolation(format!(
+                "Empty variable name in `{input}`"
+            )));
+        }
+
+        match (lookup(name).filter(|value| !value.is_empty()), default) {
+            (Some(value), _) => output.push_str(&value),
+            (None, Some(default)) => output.push_str(default),
+            (None, None) => {
+                return Err(NexsockConfigError::MissingConfig(format!(
+                    "environment variable `{name}` is not set"
+                )))
+            }
+        }
+
+        rest = &reference[end + 1..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NexsockConfig;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn expands_variables_and_defaults() {
+        let lookup = lookup(&[("HOME_DIR", "/home/nexsock"), ("EMPTY", "")]);
+
+        assert_eq!(
+            interpolate_str("${HOME_DIR}/nexsock.sock", &lookup).unwrap(),
+            "/home/nexsock/nexsock.sock"
+        );
+        assert_eq!(
+            interpolate_str("${UNSET:-/tmp}/db/${EMPTY:-state}.db", &lookup).unwrap(),
+            "/tmp/db/state.db"
+        );
+        assert_eq!(
+            interpolate_str("${HOME_DIR:-/tmp}", &lookup).unwrap(),
+            "/home/nexsock"
+        );
+        assert_eq!(
+            interpolate_str("cost: $5, literal: $${HOME_DIR}", &lookup).unwrap(),
+            "cost: $5, literal: ${HOME_DIR}"
+        );
+    }
+
+    #[test]
+    fn rejects_unset_and_malformed_references() {
+        let lookup = lookup(&[]);
+
+        assert!(matches!(
+            interpolate_str("${UNSET}", &lookup),
+            Err(NexsockConfigError::MissingConfig(_))
+        ));
+        assert!(matches!(
+            interpolate_str("${UNTERMINATED", &lookup),
+            Err(NexsockConfigError::Interpolation(_))
+        ));
+        assert!(matches!(
+            interpolate_str("${:-default}", &lookup),
+            Err(NexsockConfigError::Interpolation(_))
+        ));
+    }
+
+    #[test]
+    fn config_file_values_are_interpolated() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            r#"
+log_str = "${NEXSOCK_TEST_INTERPOLATE_UNSET:-debug}"
+
+[database]
+path = "${NEXSOCK_TEST_INTERPOLATE_DIR}/state.db"
+"#,
+        )
+        .unwrap();
+
+        std::env::set_var("NEXSOCK_TEST_INTERPOLATE_DIR", "/var/lib/nexsock");
+        std::env::remove_var("NEXSOCK_TEST_INTERPOLATE_UNSET");
+
+        let config = NexsockConfig::from_file(Some(dir.path())).unwrap();
+
+        assert_eq!(
+            config.database().path,
+            std::path::PathBuf::from("/var/lib/nexsock/state.db")
+        );
+        assert_eq!(config.inner.log_str, "debug");
+    }
+}
@@ -1,7 +1,14 @@
+mod include;
+mod instance;
+mod interpolate;
+mod logging;
 pub mod traits;
 
+pub use instance::*;
+pub use logging::*;
+
 use anyhow::Context;
-use config::{Config, Environment, File, Map, Value, ValueKind};
+use config::{Config, Environment, File, FileFormat, Map, Value, ValueKind};
 use derive_more::{
     AsMut, AsRef, Deref, DerefMut, From, Into, IsVariant, TryFrom, TryInto, TryUnwrap, Unwrap,
 };
@@ -58,6 +65,13 @@ fn get_database_path() -> anyhow::Result<PathBuf> {
             data_dir.join("db/state.db")
         });
 
+    create_database_dir(&path)?;
+
+    Ok(path)
+}
+
+/// Creates the parent directory of the database at `path` if it doesn't exist yet.
+fn create_database_dir(path: &Path) -> anyhow::Result<()> {
     if let Some(parent) = path.parent() {
         if !parent.exists() {
             info!(
@@ -69,7 +83,7 @@ fn get_database_path() -> anyhow::Result<PathBuf> {
         }
     }
 
-    Ok(path)
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -82,6 +96,10 @@ pub enum NexsockConfigError {
     InvalidPath(String),
     #[error("Missing required configuration: {0}")]
     MissingConfig(String),
+    #[error("Invalid variable interpolation: {0}")]
+    Interpolation(String),
+    #[error("Invalid instance name `{0}`, only ASCII letters, digits, `-` and `_` are allowed")]
+    InvalidInstance(String),
 }
 
 #[derive(
@@ -123,6 +141,45 @@ impl Display for SocketRef {
 pub struct ServerConfig {
     pub cleanup_interval: u64,
     pub socket: SocketRef,
+    /// Seconds a client connection may sit idle before the daemon sends a heartbeat, `0` disables heartbeats.
+    pub heartbeat_interval: u64,
+    /// Number of unanswered heartbeats after which the daemon drops the connection.
+    pub max_missed_heartbeats: u32,
+    /// Seconds a client connection may go without sending a frame before the daemon closes it,
+    /// `0` disables the timeout.
+    pub idle_timeout: u64,
+    /// Number of client connections handled at once, `0` removes the limit.
+    pub max_connections: u32,
+    /// Seconds a stop waits for the port of the service to be released once its processes are
+    /// gone.
+    pub port_free_timeout: u64,
+    /// Seconds a service may stay starting before the cleanup considers it failed and stops it,
+    /// `0` disables the timeout.
+    pub startup_timeout: u64,
+    /// Bytes of a single line of service output kept in the logs, the rest of a longer line is
+    /// dropped and replaced with a `…[truncated N bytes]` marker.
+    pub max_log_line_bytes: u64,
+    /// File that every handled command is appended to as a JSON line, access logging is disabled
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_log: Option<PathBuf>,
+    /// Socket of an additional listener speaking JSON-RPC 2.0 instead of the native protocol, a
+    /// path on Unix and a port on Windows. The listener is disabled when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_rpc_socket: Option<SocketRef>,
+    /// Whether clients may request a debug dump of the internal state of the daemon. Secrets are
+    /// redacted from it, but it still lists the environment and config of the services.
+    #[serde(default)]
+    pub debug_dump: bool,
+    /// Whether payloads sent over the native protocol are followed by a CRC32 checksum, letting
+    /// the peer reject frames corrupted on the way. Checksummed frames are verified either way.
+    #[serde(default)]
+    pub checksums: bool,
+    /// OTLP/HTTP endpoint the spans of the daemon are exported to, e.g.
+    /// `http://localhost:4318/v1/traces`. Only used when the daemon is built with the `otel`
+    /// feature, spans are not exported when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp_endpoint: Option<String>,
 }
 
 impl Default for ServerConfig {
@@ -144,6 +201,18 @@ impl Default for ServerConfig {
             } else {
                 SocketRef::Port(50505)
             },
+            heartbeat_interval: 30,
+            max_missed_heartbeats: 3,
+            idle_timeout: 300,
+            max_connections: 128,
+            port_free_timeout: 5,
+            startup_timeout: 120,
+            max_log_line_bytes: 64 * 1024,
+            access_log: None,
+            json_rpc_socket: None,
+            debug_dump: false,
+            checksums: false,
+            otlp_endpoint: None,
         }
     }
 }
@@ -162,13 +231,48 @@ impl From<ServerConfig> for Value {
     /// assert!(matches!(value, Value::Table(_)));
     /// ```
     fn from(val: ServerConfig) -> Self {
-        Self::new(
-            None,
-            ValueKind::Table(Map::from_iter(vec![
-                ("cleanup_interval".to_string(), val.cleanup_interval.into()),
-                ("socket".to_string(), val.socket.into()),
-            ])),
-        )
+        let mut table = Map::from_iter(vec![
+            ("cleanup_interval".to_string(), val.cleanup_interval.into()),
+            ("socket".to_string(), val.socket.into()),
+            (
+                "heartbeat_interval".to_string(),
+                val.heartbeat_interval.into(),
+            ),
+            (
+                "max_missed_heartbeats".to_string(),
+                val.max_missed_heartbeats.into(),
+            ),
+            ("idle_timeout".to_string(), val.idle_timeout.into()),
+            ("max_connections".to_string(), val.max_connections.into()),
+            (
+                "port_free_timeout".to_string(),
+                val.port_free_timeout.into(),
+            ),
+            ("startup_timeout".to_string(), val.startup_timeout.into()),
+            (
+                "max_log_line_bytes".to_string(),
+                val.max_log_line_bytes.into(),
+            ),
+            ("debug_dump".to_string(), val.debug_dump.into()),
+            ("checksums".to_string(), val.checksums.into()),
+        ]);
+
+        if let Some(access_log) = val.access_log {
+            table.insert(
+                "access_log".to_string(),
+                access_log.display().to_string().into(),
+            );
+        }
+
+        if let Some(json_rpc_socket) = val.json_rpc_socket {
+            table.insert("json_rpc_socket".to_string(), json_rpc_socket.into());
+        }
+
+        if let Some(otlp_endpoint) = val.otlp_endpoint {
+            table.insert("otlp_endpoint".to_string(), otlp_endpoint.into());
+        }
+
+        Self::new(None, ValueKind::Table(table))
     }
 }
 
@@ -216,12 +320,111 @@ impl Default for DatabaseConfig {
     }
 }
 
+/// Settings of the web interface.
+#[derive(Debug, Clone, Serialize, Deserialize, Into, From, AsRef, AsMut)]
+pub struct WebConfig {
+    /// Origins allowed to read from the web API with `GET` and `HEAD`, `*` allows any origin.
+    /// Cross-origin requests are refused when empty.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    /// Methods other than `GET` and `HEAD`, such as `POST`, that the origins in `cors_origins` may
+    /// use. These are only granted to origins listed by name, never through `*`.
+    #[serde(default)]
+    pub cors_methods: Vec<String>,
+    /// Key required by requests that change state, sent as `Authorization: Bearer <key>` or as the
+    /// password of basic auth. Every route is public when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Whether `GET` and `HEAD` requests are allowed without `api_key`.
+    #[serde(default = "default_public_reads")]
+    pub public_reads: bool,
+}
+
+fn default_public_reads() -> bool {
+    true
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        Self {
+            cors_origins: Vec::new(),
+            cors_methods: Vec::new(),
+            api_key: None,
+            public_reads: default_public_reads(),
+        }
+    }
+}
+
+impl From<WebConfig> for Value {
+    fn from(val: WebConfig) -> Self {
+        let mut table = Map::from_iter(vec![
+            ("cors_origins".to_string(), val.cors_origins.into()),
+            ("cors_methods".to_string(), val.cors_methods.into()),
+            ("public_reads".to_string(), val.public_reads.into()),
+        ]);
+
+        if let Some(api_key) = val.api_key {
+            table.insert("api_key".to_string(), api_key.into());
+        }
+
+        Self::new(None, ValueKind::Table(table))
+    }
+}
+
+/// Settings of the Lua plugins.
+#[derive(Debug, Clone, Serialize, Deserialize, Into, From, AsRef, AsMut)]
+pub struct PluginsConfig {
+    /// Whether Lua plugins run without the parts of the standard library that reach outside the
+    /// daemon, such as `io`, `os.execute` and `require`.
+    #[serde(default)]
+    pub sandbox: bool,
+    /// File names of the Lua plugins, e.g. `deploy.lua`, that keep the full standard library when
+    /// `sandbox` is enabled.
+    #[serde(default)]
+    pub trusted: Vec<String>,
+    /// Milliseconds a single call into a Lua plugin may run before it's aborted with an error,
+    /// `0` disables the timeout.
+    #[serde(default = "default_plugin_call_timeout_ms")]
+    pub call_timeout_ms: u64,
+}
+
+fn default_plugin_call_timeout_ms() -> u64 {
+    5000
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            sandbox: false,
+            trusted: Vec::new(),
+            call_timeout_ms: default_plugin_call_timeout_ms(),
+        }
+    }
+}
+
+impl From<PluginsConfig> for Value {
+    fn from(val: PluginsConfig) -> Self {
+        Self::new(
+            None,
+            ValueKind::Table(Map::from_iter(vec![
+                ("sandbox".to_string(), val.sandbox.into()),
+                ("trusted".to_string(), val.trusted.into()),
+                ("call_timeout_ms".to_string(), val.call_timeout_ms.into()),
+            ])),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Into, From, AsRef, AsMut)]
 pub struct AppConfig {
     pub socket: SocketRef,
     pub log_str: String,
     pub server: ServerConfig,
     pub database: DatabaseConfig,
+    #[serde(default)]
+    pub web: WebConfig,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
 }
 
 impl Default for AppConfig {
@@ -242,9 +445,11 @@ impl Default for AppConfig {
             } else {
                 SocketRef::Port(50505)
             },
-            log_str: "info,sqlx=error,sea_orm=error,sea_orm_migration=error".to_string(),
+            log_str: DEFAULT_LOG_STR.to_string(),
             server: Default::default(),
             database: Default::default(),
+            web: Default::default(),
+            plugins: Default::default(),
         }
     }
 }
@@ -257,6 +462,9 @@ pub struct NexsockConfig {
     #[deref(ignore)]
     #[deref_mut(ignore)]
     config_dir: PathBuf,
+    #[deref(ignore)]
+    #[deref_mut(ignore)]
+    instance: Option<String>,
     config: Config,
 }
 
@@ -269,11 +477,15 @@ impl NexsockConfig {
     ///
     /// If a path is provided, loads the configuration from that directory; otherwise, uses the default project config directory.
     /// Applies default values for all configuration fields, and merges values from "config.toml" if it exists.
+    /// String values in the file may reference environment variables as `${VAR}` or `${VAR:-default}`,
+    /// these are expanded before the file is deserialized. Files matched by the glob patterns in the
+    /// top-level `include` key are merged on top of "config.toml", later files overriding earlier ones.
     /// Returns a `NexsockConfig` instance containing the loaded configuration and the directory path.
     ///
     /// # Errors
     ///
-    /// Returns an error if the config directory cannot be created, the configuration file is invalid, or deserialization fails.
+    /// Returns an error if the config directory cannot be created, the configuration file is invalid, references an
+    /// unset environment variable without a default, or deserialization fails.
     ///
     /// # Examples
     ///
@@ -281,7 +493,25 @@ impl NexsockConfig {
     /// let config = NexsockConfig::from_file(None).unwrap();
     /// assert!(config.server().cleanup_interval > 0);
     /// ```
+    ///
+    /// The defaults are those of the instance named by [`INSTANCE_ENV`], see
+    /// [`NexsockConfig::for_instance`].
     pub fn from_file(path: Option<&Path>) -> ConfigResult<Self> {
+        Self::for_instance(path, instance_from_env()?.as_deref())
+    }
+
+    /// Like [`NexsockConfig::from_file`], with the socket and database defaulting to those of
+    /// `instance` so that several daemons can run side by side. `None` selects the default
+    /// instance.
+    ///
+    /// A socket or database set in the config file, or a database set through `DATABASE_URL`,
+    /// still takes precedence.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `instance` is not a valid instance name, or for the reasons listed on
+    /// [`NexsockConfig::from_file`].
+    pub fn for_instance(path: Option<&Path>, instance: Option<&str>) -> ConfigResult<Self> {
         let config_path = path.unwrap_or_else(|| PROJECT_DIRECTORIES.config_dir());
 
         std::fs::create_dir_all(config_path).map_err(|e| {
@@ -292,16 +522,46 @@ impl NexsockConfig {
 
         info!(config_file = %config_file.display(), "Loading config from file");
 
-        let defaults: AppConfig = AppConfig::default();
+        let mut defaults: AppConfig = AppConfig::default();
+
+        if instance.is_some() {
+            let paths = InstancePaths::resolve(instance)?;
+
+            defaults.socket = paths.socket.clone();
+            defaults.server.socket = paths.socket;
+
+            if std::env::var_os("DATABASE_URL").is_none() {
+                if !cfg!(test) {
+                    create_database_dir(&paths.database)
+                        .map_err(|e| NexsockConfigError::InvalidPath(format!("{e:#}")))?;
+                }
+
+                defaults.database.path = paths.database;
+            }
+        }
 
         let builder = Config::builder()
             .set_default("socket", defaults.socket)?
             .set_default("server", defaults.server)?
             .set_default("log_str", defaults.log_str)?
-            .set_default("database", defaults.database)?;
+            .set_default("database", defaults.database)?
+            .set_default("web", defaults.web)?
+            .set_default("plugins", defaults.plugins)?;
 
         let builder = if config_file.exists() {
-            builder.add_source(File::from(config_file))
+            let contents = read_config_file(&config_file)?;
+            let includes = include::resolve_includes(config_path, &contents)?;
+
+            let mut builder = builder.add_source(File::from_str(&contents, FileFormat::Toml));
+
+            for include in includes {
+                debug!(include = %include.display(), "Merging included config file");
+
+                let contents = read_config_file(&include)?;
+                builder = builder.add_source(File::from_str(&contents, FileFormat::Toml));
+            }
+
+            builder
         } else {
             builder
         };
@@ -316,6 +576,7 @@ impl NexsockConfig {
             inner,
             config,
             config_dir: config_path.to_path_buf(),
+            instance: instance.map(ToString::to_string),
         })
     }
 
@@ -365,16 +626,44 @@ impl NexsockConfig {
         &self.inner.database
     }
 
+    /// Returns a reference to the web interface configuration.
+    pub fn web(&self) -> &WebConfig {
+        &self.inner.web
+    }
+
+    /// Returns a reference to the Lua plugins configuration.
+    pub fn plugins(&self) -> &PluginsConfig {
+        &self.inner.plugins
+    }
+
     /// Returns the path to the configuration directory used by this configuration instance.
     pub fn config_dir(&self) -> &Path {
         &self.config_dir
     }
 
+    /// Returns the name of the instance this configuration belongs to, `None` for the default
+    /// instance.
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
     pub fn config(&self) -> &Config {
         &self.config
     }
 }
 
+/// Reads a TOML config file and expands the environment variable references in its values.
+fn read_config_file(path: &Path) -> ConfigResult<String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        NexsockConfigError::InvalidPath(format!(
+            "Failed to read config file '{}': {e}",
+            path.display()
+        ))
+    })?;
+
+    interpolate::interpolate_toml(&contents)
+}
+
 impl From<SocketRef> for Value {
     fn from(value: SocketRef) -> Self {
         match value {
@@ -0,0 +1,82 @@
+//! Log filter configuration.
+
+use crate::NexsockConfig;
+use tracing_subscriber::filter::ParseError;
+use tracing_subscriber::EnvFilter;
+
+/// The filter used when neither `RUST_LOG` nor `log_str` provide one.
+pub const DEFAULT_LOG_STR: &str = "info,sqlx=error,sea_orm=error,sea_orm_migration=error";
+
+/// Parses a filter string such as `info,sqlx=error` into an [`EnvFilter`].
+///
+/// # Errors
+///
+/// Returns an error if any of the comma separated directives is invalid.
+pub fn parse_log_filter(log_str: &str) -> Result<EnvFilter, ParseError> {
+    EnvFilter::try_new(log_str)
+}
+
+impl NexsockConfig {
+    /// Returns the configured default log filter string.
+    pub fn log_str(&self) -> &str {
+        &self.inner.log_str
+    }
+
+    /// Builds the log filter for this configuration.
+    ///
+    /// `RUST_LOG` takes precedence when it is set, otherwise the configured `log_str` is used.
+    /// An invalid `log_str` is replaced by [`DEFAULT_LOG_STR`] and reported on stderr, this runs
+    /// before the tracing subscriber is installed so it can't be logged.
+    pub fn to_env_filter(&self) -> EnvFilter {
+        if let Ok(filter) = EnvFilter::try_from_default_env() {
+            return filter;
+        }
+
+        parse_log_filter(self.log_str()).unwrap_or_else(|e| {
+            eprintln!(
+                "Invalid `log_str` `{}`, using the default filter: {e}",
+                self.log_str()
+            );
+            EnvFilter::new(DEFAULT_LOG_STR)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn enabled(filter: EnvFilter, target_enabled: impl FnOnce() -> bool) -> bool {
+        let subscriber = tracing_subscriber::registry().with(filter);
+        tracing::subscriber::with_default(subscriber, target_enabled)
+    }
+
+    #[test]
+    fn per_target_levels_are_applied() {
+        let log_str = "info,sqlx=error,nexsockd=trace";
+
+        assert!(enabled(parse_log_filter(log_str).unwrap(), || {
+            tracing::enabled!(target: "nexsock_db", Level::INFO)
+        }));
+        assert!(!enabled(parse_log_filter(log_str).unwrap(), || {
+            tracing::enabled!(target: "nexsock_db", Level::DEBUG)
+        }));
+        assert!(enabled(parse_log_filter(log_str).unwrap(), || {
+            tracing::enabled!(target: "sqlx", Level::ERROR)
+        }));
+        assert!(!enabled(parse_log_filter(log_str).unwrap(), || {
+            tracing::enabled!(target: "sqlx", Level::WARN)
+        }));
+        assert!(enabled(parse_log_filter(log_str).unwrap(), || {
+            tracing::enabled!(target: "nexsockd", Level::TRACE)
+        }));
+    }
+
+    #[test]
+    fn invalid_filters_are_rejected() {
+        assert!(parse_log_filter(DEFAULT_LOG_STR).is_ok());
+        assert!(parse_log_filter("sqlx=loud").is_err());
+    }
+}
@@ -8,6 +8,18 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_base_service_tables;
 mod m20250605_000002_add_git_columns;
+mod m20261014_000003_cascade_service_dependencies;
+mod m20261014_000004_add_service_config_log_format;
+mod m20261014_000005_add_service_config_strip_ansi;
+mod m20261014_000006_add_service_version;
+mod m20261014_000007_add_service_startup_failure;
+mod m20261014_000008_add_service_stop_signal;
+mod m20261014_000009_add_service_profile;
+mod m20261014_000010_create_service_label;
+mod m20261014_000011_add_service_config_capture_output;
+mod m20261015_000012_create_service_config_template;
+mod m20261015_000013_add_service_config_log_timestamp_format;
+mod m20261015_000014_add_service_dependency_optional;
 
 /// The main migrator struct that collects all defined migrations.
 ///
@@ -38,6 +50,18 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_create_base_service_tables::Migration),
             Box::new(m20250605_000002_add_git_columns::Migration),
+            Box::new(m20261014_000003_cascade_service_dependencies::Migration),
+            Box::new(m20261014_000004_add_service_config_log_format::Migration),
+            Box::new(m20261014_000005_add_service_config_strip_ansi::Migration),
+            Box::new(m20261014_000006_add_service_version::Migration),
+            Box::new(m20261014_000007_add_service_startup_failure::Migration),
+            Box::new(m20261014_000008_add_service_stop_signal::Migration),
+            Box::new(m20261014_000009_add_service_profile::Migration),
+            Box::new(m20261014_000010_create_service_label::Migration),
+            Box::new(m20261014_000011_add_service_config_capture_output::Migration),
+            Box::new(m20261015_000012_create_service_config_template::Migration),
+            Box::new(m20261015_000013_add_service_config_log_timestamp_format::Migration),
+            Box::new(m20261015_000014_add_service_dependency_optional::Migration),
         ]
     }
 }
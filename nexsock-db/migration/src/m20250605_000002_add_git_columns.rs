@@ -12,6 +12,14 @@ macro_rules! add_column {
     };
 }
 
+macro_rules! drop_column {
+    ($manager:expr, $table:expr, $column:expr) => {
+        $manager
+            .alter_table(Table::alter().table($table).drop_column($column).to_owned())
+            .await?;
+    };
+}
+
 /// Defines the migration for adding Git-related columns to the service table.
 ///
 /// This migration adds columns to track Git repository state including:
@@ -123,17 +131,10 @@ impl MigrationTrait for Migration {
             )
             .await?;
 
-        // Drop columns
-        manager
-            .alter_table(
-                Table::alter()
-                    .table(Service::Table)
-                    .drop_column(Service::GitAuthType)
-                    .drop_column(Service::GitCommitHash)
-                    .drop_column(Service::GitBranch)
-                    .to_owned(),
-            )
-            .await?;
+        // Drop columns, SQLite only allows a single alteration per statement
+        drop_column!(manager, Service::Table, Service::GitAuthType);
+        drop_column!(manager, Service::Table, Service::GitCommitHash);
+        drop_column!(manager, Service::Table, Service::GitBranch);
 
         Ok(())
     }
@@ -0,0 +1,200 @@
+//! This migration rebuilds the `service_dependency` table so that both of its
+//! foreign keys to `service` use `ON DELETE CASCADE`.
+//!
+//! SQLite can't alter the constraints of an existing table, so the table is
+//! recreated under a temporary name, the rows are copied over, and the new
+//! table replaces the old one.
+
+use sea_orm_migration::prelude::*;
+
+const TEMP_TABLE: &str = "service_dependency_new";
+
+/// Defines the migration that makes dependency rows follow the services they reference.
+///
+/// Without cascading deletes a service referenced as a dependent by others leaves
+/// dangling `service_dependency` rows behind when it is removed.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, recreating `service_dependency` with cascading foreign keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_dependency_table(manager, ForeignKeyAction::Cascade).await
+    }
+
+    /// Reverts the migration, recreating `service_dependency` without cascading foreign keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_dependency_table(manager, ForeignKeyAction::NoAction).await
+    }
+}
+
+/// Recreates the `service_dependency` table with `on_delete` applied to both foreign keys,
+/// keeping all existing rows and indexes.
+async fn rebuild_dependency_table(
+    manager: &SchemaManager<'_>,
+    on_delete: ForeignKeyAction,
+) -> Result<(), DbErr> {
+    let temp_table = Alias::new(TEMP_TABLE);
+
+    manager
+        .create_table(
+            Table::create()
+                .table(temp_table.clone())
+                .col(
+                    ColumnDef::new(ServiceDependency::Id)
+                        .big_integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(ServiceDependency::ServiceId)
+                        .big_integer()
+                        .not_null()
+                        .check(
+                            Expr::col(ServiceDependency::ServiceId)
+                                .not_equals(ServiceDependency::DependentServiceId),
+                        ),
+                )
+                .col(
+                    ColumnDef::new(ServiceDependency::DependentServiceId)
+                        .big_integer()
+                        .not_null()
+                        .check(
+                            Expr::col(ServiceDependency::DependentServiceId)
+                                .not_equals(ServiceDependency::ServiceId),
+                        ),
+                )
+                .col(
+                    ColumnDef::new(ServiceDependency::TunnelEnabled)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(temp_table.clone(), ServiceDependency::ServiceId)
+                        .to(Service::Table, Service::Id)
+                        .on_delete(on_delete),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(temp_table.clone(), ServiceDependency::DependentServiceId)
+                        .to(Service::Table, Service::Id)
+                        .on_delete(on_delete),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    let columns = [
+        ServiceDependency::Id,
+        ServiceDependency::ServiceId,
+        ServiceDependency::DependentServiceId,
+        ServiceDependency::TunnelEnabled,
+    ];
+
+    let copy_rows = Query::insert()
+        .into_table(temp_table.clone())
+        .columns(columns)
+        .select_from(
+            Query::select()
+                .columns(columns)
+                .from(ServiceDependency::Table)
+                .to_owned(),
+        )
+        .map_err(|e| DbErr::Migration(e.to_string()))?
+        .to_owned();
+
+    manager.exec_stmt(copy_rows).await?;
+
+    manager
+        .drop_table(Table::drop().table(ServiceDependency::Table).to_owned())
+        .await?;
+
+    manager
+        .rename_table(
+            Table::rename()
+                .table(temp_table, ServiceDependency::Table)
+                .to_owned(),
+        )
+        .await?;
+
+    // The indexes were dropped together with the old table
+    manager
+        .create_index(
+            Index::create()
+                .if_not_exists()
+                .unique()
+                .name("service_dep_idx")
+                .table(ServiceDependency::Table)
+                .col(ServiceDependency::ServiceId)
+                .col(ServiceDependency::DependentServiceId)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .if_not_exists()
+                .name("idx_dependency_service_id")
+                .table(ServiceDependency::Table)
+                .col(ServiceDependency::ServiceId)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_index(
+            Index::create()
+                .if_not_exists()
+                .name("idx_dependency_dependent_service_id")
+                .table(ServiceDependency::Table)
+                .col(ServiceDependency::DependentServiceId)
+                .to_owned(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Defines identifiers for the `service` table and the columns referenced by this migration.
+#[derive(Iden)]
+enum Service {
+    /// The name of the `service` table.
+    Table,
+    /// The `id` column, storing the primary key.
+    Id,
+}
+
+/// Defines identifiers for the `service_dependency` table and its columns.
+#[derive(Iden, Clone, Copy)]
+enum ServiceDependency {
+    /// The name of the `service_dependency` table.
+    Table,
+    /// The `id` column, storing the primary key.
+    Id,
+    /// The `service_id` column, a foreign key referencing `service(id)`.
+    ServiceId,
+    /// The `dependent_service_id` column, a foreign key referencing `service(id)`.
+    DependentServiceId,
+    /// The `tunnel_enabled` column, a boolean indicating if a tunnel is enabled for this dependency.
+    TunnelEnabled,
+}
@@ -0,0 +1,69 @@
+//! This migration adds a `log_format` column to the `service_config` table, controlling how the
+//! captured output of a service is parsed into leveled log entries.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the log format setting to service configurations.
+///
+/// Existing configurations default to `Raw`, which keeps their output unparsed.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `log_format` column to the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .add_column(
+                        ColumnDef::new(ServiceConfig::LogFormat)
+                            .string()
+                            .not_null()
+                            .default("Raw")
+                            .check(Expr::col(ServiceConfig::LogFormat).is_in(vec![
+                                "Raw", "Json", "Text",
+                            ])),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `log_format` column from the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .drop_column(ServiceConfig::LogFormat)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service_config` table and its log format column.
+#[derive(Iden)]
+enum ServiceConfig {
+    /// The name of the `service_config` table.
+    Table,
+    /// The `log_format` column, storing how service output is parsed.
+    LogFormat,
+}
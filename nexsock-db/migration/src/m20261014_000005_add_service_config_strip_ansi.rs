@@ -0,0 +1,66 @@
+//! This migration adds a `strip_ansi` column to the `service_config` table, controlling whether
+//! ANSI escape sequences are removed from the captured output of a service.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the ANSI stripping setting to service configurations.
+///
+/// Existing configurations default to `false`, which keeps their output unchanged.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `strip_ansi` column to the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .add_column(
+                        ColumnDef::new(ServiceConfig::StripAnsi)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `strip_ansi` column from the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .drop_column(ServiceConfig::StripAnsi)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service_config` table and its ANSI stripping column.
+#[derive(Iden)]
+enum ServiceConfig {
+    /// The name of the `service_config` table.
+    Table,
+    /// The `strip_ansi` column, storing whether escape sequences are removed from service output.
+    StripAnsi,
+}
@@ -0,0 +1,66 @@
+//! This migration adds a `version` column to the `service` table, used to detect concurrent
+//! updates of the same service.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the optimistic concurrency version to services.
+///
+/// Existing services start at version `0`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `version` column to the `service` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .add_column(
+                        ColumnDef::new(Service::Version)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `version` column from the `service` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .drop_column(Service::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service` table and its version column.
+#[derive(Iden)]
+enum Service {
+    /// The name of the `service` table.
+    Table,
+    /// The `version` column, incremented every time the service row is updated.
+    Version,
+}
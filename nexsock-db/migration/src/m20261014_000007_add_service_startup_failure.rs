@@ -0,0 +1,84 @@
+//! This migration adds the `last_exit_code` and `last_error` columns to the `service` table,
+//! recording why the last start of a service failed.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding startup failure diagnostics to services.
+///
+/// Both columns are nullable, existing services have no recorded failure.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `last_exit_code` and `last_error` columns to the
+    /// `service` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite can only add one column per ALTER TABLE statement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .add_column(ColumnDef::new(Service::LastExitCode).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .add_column(ColumnDef::new(Service::LastError).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `last_exit_code` and `last_error` columns from the
+    /// `service` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .drop_column(Service::LastError)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .drop_column(Service::LastExitCode)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service` table and its startup failure columns.
+#[derive(Iden)]
+enum Service {
+    /// The name of the `service` table.
+    Table,
+    /// The `last_exit_code` column, the exit code of the last failed start.
+    LastExitCode,
+    /// The `last_error` column, the output the service wrote before its last start failed.
+    LastError,
+}
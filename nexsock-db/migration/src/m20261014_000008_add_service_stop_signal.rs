@@ -0,0 +1,69 @@
+//! This migration adds a `stop_signal` column to the `service` table, the signal a service is
+//! sent first when it is stopped.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the stop signal setting to services.
+///
+/// Existing services default to `Term`, the signal they were stopped with so far.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `stop_signal` column to the `service` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .add_column(
+                        ColumnDef::new(Service::StopSignal)
+                            .string()
+                            .not_null()
+                            .default("Term")
+                            .check(Expr::col(Service::StopSignal).is_in(vec![
+                                "Term", "Int", "Quit", "Hup",
+                            ])),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `stop_signal` column from the `service` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .drop_column(Service::StopSignal)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service` table and its stop signal column.
+#[derive(Iden)]
+enum Service {
+    /// The name of the `service` table.
+    Table,
+    /// The `stop_signal` column, storing the signal the service is stopped with.
+    StopSignal,
+}
@@ -0,0 +1,83 @@
+//! This migration adds a `profile` column to the `service` table, a label grouping services that
+//! are started and stopped together.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding profiles to services.
+///
+/// The column is nullable, existing services belong to no profile.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `profile` column to the `service` table and an index
+    /// on it for the profile-scoped queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .add_column(ColumnDef::new(Service::Profile).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_service_profile")
+                    .table(Service::Table)
+                    .col(Service::Profile)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the index and the `profile` column from the `service`
+    /// table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_service_profile")
+                    .table(Service::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Service::Table)
+                    .drop_column(Service::Profile)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service` table and its profile column.
+#[derive(Iden)]
+enum Service {
+    /// The name of the `service` table.
+    Table,
+    /// The `profile` column, the label of the group the service belongs to.
+    Profile,
+}
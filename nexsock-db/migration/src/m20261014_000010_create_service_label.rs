@@ -0,0 +1,114 @@
+//! This migration creates the `service_label` table, holding the `key=value` labels services are
+//! tagged with for filtering and display.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for creating the `service_label` table.
+///
+/// Each service has at most one value per label key, and its labels are removed together with
+/// the service.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, creating the `service_label` table and an index on its key and
+    /// value for the label selector queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ServiceLabel::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ServiceLabel::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ServiceLabel::ServiceId).big_integer().not_null())
+                    .col(ColumnDef::new(ServiceLabel::Key).text().not_null())
+                    .col(ColumnDef::new(ServiceLabel::Value).text().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ServiceLabel::Table, ServiceLabel::ServiceId)
+                            .to(Service::Table, Service::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .unique()
+                    .name("idx_service_label_service_key")
+                    .table(ServiceLabel::Table)
+                    .col(ServiceLabel::ServiceId)
+                    .col(ServiceLabel::Key)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_service_label_key_value")
+                    .table(ServiceLabel::Table)
+                    .col(ServiceLabel::Key)
+                    .col(ServiceLabel::Value)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, dropping the `service_label` table together with its indexes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ServiceLabel::Table).to_owned())
+            .await
+    }
+}
+
+/// Defines identifiers for the `service` table and its primary key.
+#[derive(Iden)]
+enum Service {
+    /// The name of the `service` table.
+    Table,
+    /// The `id` column, storing the primary key.
+    Id,
+}
+
+/// Defines identifiers for the `service_label` table and its columns.
+#[derive(Iden)]
+enum ServiceLabel {
+    /// The name of the `service_label` table.
+    Table,
+    /// The `id` column, storing the primary key.
+    Id,
+    /// The `service_id` column, a foreign key referencing `service(id)`.
+    ServiceId,
+    /// The `key` column, storing the name of the label.
+    Key,
+    /// The `value` column, storing the value of the label.
+    Value,
+}
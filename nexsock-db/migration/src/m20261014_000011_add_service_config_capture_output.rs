@@ -0,0 +1,66 @@
+//! This migration adds a `capture_output` column to the `service_config` table, controlling whether
+//! the output of a service is captured by the daemon or written straight to the daemon's stdio.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the output capture setting to service configurations.
+///
+/// Existing configurations default to `true`, which keeps capturing their output.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `capture_output` column to the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .add_column(
+                        ColumnDef::new(ServiceConfig::CaptureOutput)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `capture_output` column from the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .drop_column(ServiceConfig::CaptureOutput)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service_config` table and its output capture column.
+#[derive(Iden)]
+enum ServiceConfig {
+    /// The name of the `service_config` table.
+    Table,
+    /// The `capture_output` column, storing whether the output of the service is captured.
+    CaptureOutput,
+}
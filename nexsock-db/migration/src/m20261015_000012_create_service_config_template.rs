@@ -0,0 +1,147 @@
+//! This migration creates the `service_config_template` table, holding named configurations
+//! services can inherit from, and adds the `template_id` column referencing one to the
+//! `service_config` table.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for creating the `service_config_template` table.
+///
+/// Existing configurations reference no template and keep using their own values.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, creating the `service_config_template` table and adding the
+    /// `template_id` column to the `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ServiceConfigTemplate::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ServiceConfigTemplate::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ServiceConfigTemplate::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ServiceConfigTemplate::Format)
+                            .string()
+                            .not_null()
+                            .default("Env")
+                            .check(
+                                Expr::col(ServiceConfigTemplate::Format)
+                                    .is_in(vec!["Env", "Properties"]),
+                            ),
+                    )
+                    .col(ColumnDef::new(ServiceConfigTemplate::RunCommand).string())
+                    .col(
+                        ColumnDef::new(ServiceConfigTemplate::LogFormat)
+                            .string()
+                            .not_null()
+                            .default("Raw")
+                            .check(
+                                Expr::col(ServiceConfigTemplate::LogFormat)
+                                    .is_in(vec!["Raw", "Json", "Text"]),
+                            ),
+                    )
+                    .col(
+                        ColumnDef::new(ServiceConfigTemplate::StripAnsi)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ServiceConfigTemplate::CaptureOutput)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // SQLite can't add a foreign key to an existing table, the repository detaches the
+        // configurations of a template before deleting it instead
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .add_column(ColumnDef::new(ServiceConfig::TemplateId).big_integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `template_id` column from the `service_config` table
+    /// and dropping the `service_config_template` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .drop_column(ServiceConfig::TemplateId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ServiceConfigTemplate::Table).to_owned())
+            .await
+    }
+}
+
+/// Defines identifiers for the `service_config` table and its template column.
+#[derive(Iden)]
+enum ServiceConfig {
+    /// The name of the `service_config` table.
+    Table,
+    /// The `template_id` column, referencing the `service_config_template` the configuration
+    /// inherits from.
+    TemplateId,
+}
+
+/// Defines identifiers for the `service_config_template` table and its columns.
+#[derive(Iden)]
+enum ServiceConfigTemplate {
+    /// The name of the `service_config_template` table.
+    Table,
+    /// The `id` column, storing the primary key.
+    Id,
+    /// The `name` column, storing the unique name services reference the template by.
+    Name,
+    /// The `format` column, storing the format of the configuration file.
+    Format,
+    /// The `run_command` column, storing the command the services are run with.
+    RunCommand,
+    /// The `log_format` column, storing how service output is parsed.
+    LogFormat,
+    /// The `strip_ansi` column, storing whether ANSI escape sequences are removed from the output.
+    StripAnsi,
+    /// The `capture_output` column, storing whether the output of the services is captured.
+    CaptureOutput,
+}
@@ -0,0 +1,69 @@
+//! This migration adds a `log_timestamp_format` column to the `service_config` table, holding the
+//! format of the timestamp a service starts its log lines with.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the log timestamp format to service configurations.
+///
+/// Existing configurations default to `NULL`, which keeps timestamping their log entries with
+/// when the line was captured.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `log_timestamp_format` column to the `service_config`
+    /// table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .add_column(
+                        ColumnDef::new(ServiceConfig::LogTimestampFormat)
+                            .text()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `log_timestamp_format` column from the
+    /// `service_config` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceConfig::Table)
+                    .drop_column(ServiceConfig::LogTimestampFormat)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service_config` table and its log timestamp format column.
+#[derive(Iden)]
+enum ServiceConfig {
+    /// The name of the `service_config` table.
+    Table,
+    /// The `log_timestamp_format` column, storing the format of the timestamp the service starts
+    /// its log lines with.
+    LogTimestampFormat,
+}
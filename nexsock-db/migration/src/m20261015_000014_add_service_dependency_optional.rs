@@ -0,0 +1,68 @@
+//! This migration adds an `optional` column to the `service_dependency` table, marking
+//! dependencies a service can start without.
+
+use sea_orm_migration::prelude::*;
+
+/// Defines the migration for adding the optional flag to service dependencies.
+///
+/// Existing dependencies default to `false`, which keeps them required.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Applies the migration, adding the `optional` column to the `service_dependency` table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sea_orm_migration::SchemaManager;
+    /// let migration = Migration;
+    /// migration.up(&schema_manager).await.unwrap();
+    /// ```
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceDependency::Table)
+                    .add_column(
+                        ColumnDef::new(ServiceDependency::Optional)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Reverts the migration, removing the `optional` column from the `service_dependency`
+    /// table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Revert the migration
+    /// migration.down(&manager).await?;
+    /// ```
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ServiceDependency::Table)
+                    .drop_column(ServiceDependency::Optional)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Defines identifiers for the `service_dependency` table and its optional column.
+#[derive(Iden)]
+enum ServiceDependency {
+    /// The name of the `service_dependency` table.
+    Table,
+    /// The `optional` column, a boolean indicating if the service may start without this
+    /// dependency.
+    Optional,
+}
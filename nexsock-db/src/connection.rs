@@ -0,0 +1,247 @@
+//! The global database connection, kept usable by a periodic health check.
+
+use crate::DatabaseError;
+use crate::{create_database_connection, run_database_migrations, validate_database_url};
+use anyhow::Context;
+use sea_orm::{
+    AccessMode, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    ExecResult, IsolationLevel, QueryResult, RuntimeErr, Statement, TransactionError,
+    TransactionTrait,
+};
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How often the daemon checks that the database is still reachable.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A database connection pool that is re-established when the database becomes unavailable.
+///
+/// Repositories hold on to the connection they were created with, so instead of replacing the
+/// connection this swaps the pool it runs queries on. While the database is unavailable every
+/// query fails with [`DatabaseError::Unavailable`] instead of whatever error the broken pool
+/// reports.
+#[derive(Debug)]
+pub struct ManagedConnection {
+    url: String,
+    run_migrations: bool,
+    /// The SQLite file the database lives in, `None` for in-memory databases.
+    path: Option<PathBuf>,
+    backend: DbBackend,
+    pool: RwLock<DatabaseConnection>,
+    available: AtomicBool,
+}
+
+impl ManagedConnection {
+    /// Connects to the database at `url`, running the migrations if `run_migrations` is set.
+    ///
+    /// The migrations are run again whenever the connection is re-established.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the url is invalid, or the connection or migrations fail.
+    pub async fn connect(url: impl Into<String>, run_migrations: bool) -> anyhow::Result<Self> {
+        let url = url.into();
+        let pool = open_pool(&url, run_migrations).await?;
+
+        let path = url::Url::parse(&url)
+            .ok()
+            .filter(|parsed| parsed.scheme() == "sqlite" && !crate::is_in_memory_database(&url))
+            .and_then(|parsed| crate::decode_sqlite_path(&parsed).ok())
+            .map(PathBuf::from);
+
+        Ok(Self {
+            url,
+            run_migrations,
+            path,
+            backend: pool.get_database_backend(),
+            pool: RwLock::new(pool),
+            available: AtomicBool::new(true),
+        })
+    }
+
+    /// Returns whether the last health check found the database reachable.
+    pub fn is_available(&self) -> bool {
+        self.available.load(Ordering::Acquire)
+    }
+
+    /// Returns the pool queries currently run on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::Unavailable`] while the connection is being re-established.
+    pub fn pool(&self) -> Result<DatabaseConnection, DatabaseError> {
+        if !self.is_available() {
+            return Err(DatabaseError::Unavailable);
+        }
+
+        Ok(self
+            .pool
+            .read()
+            .expect("database pool lock poisoned")
+            .clone())
+    }
+
+    /// Checks that the database is reachable and re-establishes the pool if it isn't.
+    ///
+    /// A SQLite database is also considered unreachable when its file is gone, the new pool then
+    /// starts from a new file holding only the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::Unavailable`] if the database is unreachable and reconnecting
+    /// failed, queries keep failing until a later check succeeds.
+    pub async fn check_health(&self) -> Result<(), DatabaseError> {
+        let pool = self
+            .pool
+            .read()
+            .expect("database pool lock poisoned")
+            .clone();
+
+        let file_exists = self.path.as_ref().is_none_or(|path| path.is_file());
+        if file_exists && pool.ping().await.is_ok() {
+            self.available.store(true, Ordering::Release);
+            return Ok(());
+        }
+
+        if self.available.swap(false, Ordering::AcqRel) {
+            warn!(file_exists, "Database is unavailable, reconnecting");
+        }
+
+        match open_pool(&self.url, self.run_migrations).await {
+            Ok(new_pool) => {
+                *self.pool.write().expect("database pool lock poisoned") = new_pool;
+                self.available.store(true, Ordering::Release);
+                info!("Reconnected to the database");
+
+                if let Err(e) = pool.close().await {
+                    warn!(error = %e, "Failed to close the previous database pool");
+                }
+
+                Ok(())
+            }
+            Err(e) => {
+                warn!(error = ?e, "Failed to reconnect to the database");
+                Err(DatabaseError::Unavailable)
+            }
+        }
+    }
+
+    /// Spawns a task running [`check_health`](Self::check_health) every `interval`.
+    pub fn spawn_health_check(&'static self, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            // The first tick completes right away and the pool was just opened
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let _ = self.check_health().await;
+            }
+        })
+    }
+}
+
+/// Validates `url` and opens a pool on it, running the migrations if `run_migrations` is set.
+async fn open_pool(url: &str, run_migrations: bool) -> anyhow::Result<DatabaseConnection> {
+    validate_database_url(url)
+        .await
+        .context("Invalid database url")?;
+
+    let pool = create_database_connection(url).await?;
+
+    if run_migrations {
+        run_database_migrations(&pool).await?;
+    }
+
+    Ok(pool)
+}
+
+impl From<DatabaseError> for DbErr {
+    fn from(error: DatabaseError) -> Self {
+        DbErr::Conn(RuntimeErr::Internal(error.to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionTrait for ManagedConnection {
+    fn get_database_backend(&self) -> DbBackend {
+        self.backend
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.pool()?.execute(stmt).await
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.pool()?.execute_unprepared(sql).await
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.pool()?.query_one(stmt).await
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.pool()?.query_all(stmt).await
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionTrait for ManagedConnection {
+    async fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
+        self.pool()?.begin().await
+    }
+
+    async fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        self.pool()?
+            .begin_with_config(isolation_level, access_mode)
+            .await
+    }
+
+    async fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let pool = self
+            .pool()
+            .map_err(|e| TransactionError::Connection(e.into()))?;
+
+        pool.transaction(callback).await
+    }
+
+    async fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        let pool = self
+            .pool()
+            .map_err(|e| TransactionError::Connection(e.into()))?;
+
+        pool.transaction_with_config(callback, isolation_level, access_mode)
+            .await
+    }
+}
@@ -20,4 +20,8 @@ pub enum DatabaseError {
     SqlitePathIsDir(String),
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Service with ID `{id}` was updated concurrently, expected version {expected} but found {actual}")]
+    VersionConflict { id: i64, expected: i64, actual: i64 },
+    #[error("Database is unavailable, the connection is being re-established")]
+    Unavailable,
 }
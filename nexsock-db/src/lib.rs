@@ -1,5 +1,4 @@
 use crate::error::DatabaseError;
-use anyhow::Context;
 use migration::{Migrator, MigratorTrait};
 use sea_orm::ConnectOptions;
 use sea_orm::{Database, DatabaseConnection};
@@ -10,21 +9,41 @@ use std::time::Duration;
 use tokio::fs::{create_dir_all, File};
 use tracing::debug;
 
+mod connection;
+mod manifest;
+mod migration_status;
 pub mod models;
 mod repositories;
+mod retry;
+mod transaction;
+
+pub use connection::*;
+pub use error::DatabaseError;
+pub use manifest::*;
+pub use migration_status::*;
+pub use retry::RetryBoundary;
+pub use transaction::*;
 
 pub mod prelude {
+    pub use crate::connection::*;
+    pub use crate::manifest::*;
+    pub use crate::migration_status::*;
     pub use crate::models::prelude::*;
     pub use crate::repositories::*;
+    pub use crate::retry::RetryBoundary;
+    pub use crate::transaction::*;
     pub use migration::*;
 }
 
-static DB_CONNECTION: OnceLock<DatabaseConnection> = OnceLock::new();
+static DB_CONNECTION: OnceLock<ManagedConnection> = OnceLock::new();
 
 /// Initializes the global database connection and optionally runs migrations.
 ///
 /// Establishes a singleton database connection using configuration settings. If `run_migrations` is true, applies all pending migrations before making the connection available.
 ///
+/// The connection is re-established by [`ManagedConnection::check_health`] if the database becomes
+/// unavailable, see [`ManagedConnection::spawn_health_check`].
+///
 /// # Parameters
 /// - `run_migrations`: If true, runs database migrations after connecting.
 ///
@@ -48,18 +67,8 @@ static DB_CONNECTION: OnceLock<DatabaseConnection> = OnceLock::new();
 pub async fn initialize_db(
     url: impl AsRef<str> + Debug,
     run_migrations: bool,
-) -> anyhow::Result<&'static DatabaseConnection> {
-    let url = url.as_ref();
-
-    validate_database_url(url)
-        .await
-        .context("Invalid database url")?;
-
-    let conn = create_database_connection(url).await?;
-
-    if run_migrations {
-        run_database_migrations(&conn).await?;
-    }
+) -> anyhow::Result<&'static ManagedConnection> {
+    let conn = ManagedConnection::connect(url.as_ref(), run_migrations).await?;
 
     let db = DB_CONNECTION.get_or_init(|| conn);
     Ok(db)
@@ -73,7 +82,9 @@ async fn create_database_connection(url: &str) -> anyhow::Result<DatabaseConnect
         .min_connections(5)
         .connect_timeout(Duration::from_secs(20))
         .idle_timeout(Duration::from_secs(10 * 60))
-        .max_lifetime(Duration::from_secs(60 * 60 * 24));
+        .max_lifetime(Duration::from_secs(60 * 60 * 24))
+        // Dependency rows rely on `ON DELETE CASCADE`, which SQLite only enforces with foreign keys on
+        .map_sqlx_sqlite_opts(|opts| opts.foreign_keys(true));
 
     Ok(Database::connect(opt).await?)
 }
@@ -233,7 +244,7 @@ async fn ensure_sqlite_file_exists(path: &Path) -> Result<(), DatabaseError> {
 /// let conn = get_db_connection();
 /// // Use `conn` for database operations
 /// ```
-pub fn get_db_connection() -> &'static DatabaseConnection {
+pub fn get_db_connection() -> &'static ManagedConnection {
     DB_CONNECTION
         .get()
         .expect("Database connection not initialized")
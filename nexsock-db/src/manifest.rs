@@ -0,0 +1,306 @@
+//! Applying declarative service manifests to the database.
+
+use crate::models::prelude::*;
+use crate::repositories::{ServiceConfigRepository, ServiceDependencyRepository, ServiceRepository};
+use crate::retry::RetryBoundary;
+use crate::transaction::with_transaction;
+use anyhow::{anyhow, bail};
+use nexsock_protocol::commands::manifest::{
+    ApplyManifestResponse, ManifestAction, ManifestConfig, ServiceManifest,
+};
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use std::collections::{BTreeMap, HashSet};
+use tracing::debug;
+
+/// Upserts every service and dependency in `manifest` in a single transaction.
+///
+/// Services are matched by name: missing ones are created and existing ones are updated in
+/// place whenever their details differ from the manifest. When `prune` is set, services and
+/// dependencies that aren't listed in the manifest are removed as well. Applying the same
+/// manifest twice makes no changes the second time.
+///
+/// Nothing is written if any step fails, the returned response lists every change made.
+///
+/// # Errors
+///
+/// Returns an error if the manifest lists a service more than once, if a dependency refers to a
+/// service that is neither in the manifest nor in the database, or if a database operation fails.
+///
+/// # Examples
+///
+/// ```ignore
+/// let response = apply_manifest(get_db_connection(), &manifest, false).await?;
+/// for action in &response.actions {
+///     println!("{action}");
+/// }
+/// ```
+pub async fn apply_manifest<C: TransactionTrait + RetryBoundary>(
+    db: &C,
+    manifest: &ServiceManifest,
+    prune: bool,
+) -> anyhow::Result<ApplyManifestResponse> {
+    validate_manifest(manifest)?;
+
+    let manifest = manifest.clone();
+
+    with_transaction(db, move |txn| {
+        let manifest = manifest.clone();
+
+        Box::pin(async move { apply_in_transaction(txn, &manifest, prune).await })
+    })
+    .await
+}
+
+/// Rejects manifests that can't be applied regardless of the database contents.
+fn validate_manifest(manifest: &ServiceManifest) -> anyhow::Result<()> {
+    let mut names = HashSet::new();
+
+    for service in &manifest.services {
+        if !names.insert(service.name.as_str()) {
+            bail!("Service `{}` is listed more than once in the manifest", service.name);
+        }
+
+        if service
+            .dependencies
+            .iter()
+            .any(|dependency| dependency.name == service.name)
+        {
+            bail!("Service `{}` cannot depend on itself", service.name);
+        }
+    }
+
+    Ok(())
+}
+
+async fn apply_in_transaction(
+    txn: &DatabaseTransaction,
+    manifest: &ServiceManifest,
+    prune: bool,
+) -> anyhow::Result<ApplyManifestResponse> {
+    let service_repository = ServiceRepository::new(txn);
+    let config_repository = ServiceConfigRepository::new(txn);
+    let dependency_repository = ServiceDependencyRepository::new(txn);
+
+    let mut actions = Vec::new();
+
+    let existing = service_repository
+        .get_all()
+        .await?
+        .into_iter()
+        .map(|service| (service.name.clone(), service))
+        .collect::<BTreeMap<_, _>>();
+
+    let declared = manifest
+        .services
+        .iter()
+        .map(|service| service.name.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut ids = existing
+        .iter()
+        .map(|(name, service)| (name.clone(), service.id))
+        .collect::<BTreeMap<_, _>>();
+
+    for entry in &manifest.services {
+        match existing.get(&entry.name) {
+            Some(service) => {
+                let mut service = service.clone();
+
+                let mut changed = service.repo_url != entry.repo_url
+                    || service.port != entry.port
+                    || service.repo_path != entry.repo_path;
+
+                service.repo_url = entry.repo_url.clone();
+                service.port = entry.port;
+                service.repo_path = entry.repo_path.clone();
+
+                if let Some(config) = &entry.config {
+                    changed |= sync_config(&config_repository, &mut service, config).await?;
+                }
+
+                if changed {
+                    service_repository.save(&mut service).await?;
+                    actions.push(ManifestAction::UpdateService {
+                        name: entry.name.clone(),
+                    });
+                }
+            }
+            None => {
+                let config_id = match &entry.config {
+                    Some(config) => {
+                        let mut record = ServiceConfig::new(
+                            config.filename.clone(),
+                            config.format,
+                            config.run_command.clone(),
+                        );
+                        record.log_format = config.log_format;
+                        record.strip_ansi = config.strip_ansi;
+                        record.capture_output = config.capture_output;
+                        record.log_timestamp_format = config.log_timestamp_format.clone();
+                        config_repository.save(&mut record).await?;
+                        Some(record.id)
+                    }
+                    None => None,
+                };
+
+                let mut record = Service::new(
+                    entry.name.clone(),
+                    entry.repo_url.clone(),
+                    entry.port,
+                    entry.repo_path.clone(),
+                    config_id,
+                );
+                service_repository.save(&mut record).await?;
+
+                ids.insert(entry.name.clone(), record.id);
+                actions.push(ManifestAction::CreateService {
+                    name: entry.name.clone(),
+                });
+            }
+        }
+    }
+
+    // Dependencies are synced once every service exists so they can refer to each other freely
+    for entry in &manifest.services {
+        let service_id = ids[&entry.name];
+
+        let mut current = dependency_repository
+            .get_by_service_id(service_id)
+            .await?
+            .into_iter()
+            .map(|dependency| (dependency.name.clone(), dependency))
+            .collect::<BTreeMap<_, _>>();
+
+        for dependency in &entry.dependencies {
+            if prune && !declared.contains(dependency.name.as_str()) {
+                bail!(
+                    "Service `{}` depends on `{}` which is not part of the manifest and would be pruned",
+                    entry.name,
+                    dependency.name
+                );
+            }
+
+            let dependency_id = *ids.get(&dependency.name).ok_or_else(|| {
+                anyhow!(
+                    "Service `{}` depends on unknown service `{}`",
+                    entry.name,
+                    dependency.name
+                )
+            })?;
+
+            let existing_id = match current.remove(&dependency.name) {
+                Some(existing)
+                    if existing.tunnel_enabled == dependency.tunnel_enabled
+                        && existing.optional == dependency.optional =>
+                {
+                    continue
+                }
+                Some(existing) => existing.id,
+                None => 0,
+            };
+
+            let mut record = ServiceDependency {
+                id: existing_id,
+                service_id,
+                dependent_service_id: dependency_id,
+                tunnel_enabled: dependency.tunnel_enabled,
+                optional: dependency.optional,
+            };
+            dependency_repository.save(&mut record).await?;
+
+            let service = entry.name.clone();
+            let dependency = dependency.name.clone();
+
+            actions.push(if existing_id == 0 {
+                ManifestAction::AddDependency {
+                    service,
+                    dependency,
+                }
+            } else {
+                ManifestAction::UpdateDependency {
+                    service,
+                    dependency,
+                }
+            });
+        }
+
+        if prune {
+            for (name, stale) in current {
+                dependency_repository.delete_by_id(stale.id).await?;
+                actions.push(ManifestAction::RemoveDependency {
+                    service: entry.name.clone(),
+                    dependency: name,
+                });
+            }
+        }
+    }
+
+    if prune {
+        for (name, service) in existing {
+            if declared.contains(name.as_str()) {
+                continue;
+            }
+
+            // Dependency rows referencing the service are removed through `ON DELETE CASCADE`
+            service_repository.delete_by_id(service.id).await?;
+
+            if let Some(config_id) = service.config_id {
+                config_repository.delete_by_id(config_id).await?;
+            }
+
+            actions.push(ManifestAction::RemoveService { name });
+        }
+    }
+
+    debug!(actions = actions.len(), prune, "Applied service manifest");
+
+    Ok(ApplyManifestResponse { actions })
+}
+
+/// Brings the configuration of `service` in line with `config`, creating it if the service has
+/// none yet.
+///
+/// Returns whether anything was changed.
+async fn sync_config(
+    repository: &ServiceConfigRepository<'_, DatabaseTransaction>,
+    service: &mut Service,
+    config: &ManifestConfig,
+) -> anyhow::Result<bool> {
+    let existing = match service.config_id {
+        Some(config_id) => repository.get_by_id(config_id).await?,
+        None => None,
+    };
+
+    let mut record = match existing {
+        Some(record)
+            if record.filename == config.filename
+                && record.format == config.format
+                && record.run_command == config.run_command
+                && record.log_format == config.log_format
+                && record.strip_ansi == config.strip_ansi
+                && record.capture_output == config.capture_output
+                && record.log_timestamp_format == config.log_timestamp_format =>
+        {
+            return Ok(false);
+        }
+        Some(record) => record,
+        None => ServiceConfig::new(
+            config.filename.clone(),
+            config.format,
+            config.run_command.clone(),
+        ),
+    };
+
+    record.filename = config.filename.clone();
+    record.format = config.format;
+    record.run_command = config.run_command.clone();
+    record.log_format = config.log_format;
+    record.strip_ansi = config.strip_ansi;
+    record.capture_output = config.capture_output;
+    record.log_timestamp_format = config.log_timestamp_format.clone();
+
+    repository.save(&mut record).await?;
+    service.config_id = Some(record.id);
+
+    Ok(true)
+}
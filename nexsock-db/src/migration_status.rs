@@ -0,0 +1,72 @@
+//! Reporting which migrations were applied to the database, and reverting them.
+
+use anyhow::{bail, Context};
+use migration::{MigrationName, Migrator, MigratorTrait};
+use nexsock_protocol::commands::migration::{MigrationInfo, MigrationStatusResponse};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use std::collections::HashMap;
+
+/// Lists every migration known to this build in the order they are applied, with when each was
+/// applied to `db`.
+///
+/// # Errors
+///
+/// Returns an error if the applied migrations can't be read from the database.
+///
+/// # Examples
+///
+/// ```ignore
+/// let status = migration_status(get_db_connection()).await?;
+/// let pending = status.pending().count();
+/// ```
+pub async fn migration_status<C: ConnectionTrait>(
+    db: &C,
+) -> anyhow::Result<MigrationStatusResponse> {
+    let applied: HashMap<String, i64> = Migrator::get_migration_models(db)
+        .await
+        .context("Database error while reading the applied migrations")?
+        .into_iter()
+        .map(|model| (model.version, model.applied_at))
+        .collect();
+
+    let migrations = Migrator::migrations()
+        .iter()
+        .map(|migration| {
+            let name = migration.name().to_string();
+
+            MigrationInfo {
+                applied_at: applied.get(&name).copied(),
+                name,
+            }
+        })
+        .collect();
+
+    Ok(MigrationStatusResponse { migrations })
+}
+
+/// Reverts the last `steps` applied migrations of `db`, newest first, and returns the resulting
+/// status.
+///
+/// # Errors
+///
+/// Returns an error if `steps` is zero or if a migration fails to revert.
+///
+/// # Examples
+///
+/// ```ignore
+/// let status = rollback_migrations(&get_db_connection().pool()?, 1).await?;
+/// ```
+pub async fn rollback_migrations(
+    db: &DatabaseConnection,
+    steps: u32,
+) -> anyhow::Result<MigrationStatusResponse> {
+    if steps == 0 {
+        bail!("At least one migration must be rolled back");
+    }
+
+    Migrator::down(db, Some(steps))
+        .await
+        .context("Database error while rolling back migrations")?;
+
+    migration_status(db).await
+}
@@ -7,9 +7,13 @@ pub mod prelude;
 pub mod service;
 /// Defines the `ServiceConfig` entity and related components.
 pub mod service_config;
+/// Defines the `ServiceConfigTemplate` entity and related components.
+pub mod service_config_template;
 /// Defines the `ServiceDep` entity and related components.
 pub mod service_dep;
 /// Defines the `ServiceDependency` entity and related components.
 pub mod service_dependency;
+/// Defines the `ServiceLabel` entity and related components.
+pub mod service_label;
 /// Defines the `ServiceRecord` entity and related components.
 pub mod service_record;
@@ -17,6 +17,13 @@ pub use super::service_config::Model as ServiceConfig;
 pub use super::service_config::PrimaryKey as ServiceConfigPrimaryKey;
 pub use super::service_config::Relation as ServiceConfigRelation;
 
+pub use super::service_config_template::ActiveModel as ServiceConfigTemplateActiveModel;
+pub use super::service_config_template::Column as ServiceConfigTemplateColumn;
+pub use super::service_config_template::Entity as ServiceConfigTemplateEntity;
+pub use super::service_config_template::Model as ServiceConfigTemplate;
+pub use super::service_config_template::PrimaryKey as ServiceConfigTemplatePrimaryKey;
+pub use super::service_config_template::Relation as ServiceConfigTemplateRelation;
+
 pub use super::service_dependency::ActiveModel as ServiceDependencyActiveModel;
 pub use super::service_dependency::Column as ServiceDependencyColumn;
 pub use super::service_dependency::Entity as ServiceDependencyEntity;
@@ -24,5 +31,12 @@ pub use super::service_dependency::Model as ServiceDependency;
 pub use super::service_dependency::PrimaryKey as ServiceDependencyPrimaryKey;
 pub use super::service_dependency::Relation as ServiceDependencyRelation;
 
+pub use super::service_label::ActiveModel as ServiceLabelActiveModel;
+pub use super::service_label::Column as ServiceLabelColumn;
+pub use super::service_label::Entity as ServiceLabelEntity;
+pub use super::service_label::Model as ServiceLabel;
+pub use super::service_label::PrimaryKey as ServiceLabelPrimaryKey;
+pub use super::service_label::Relation as ServiceLabelRelation;
+
 pub use super::service_dep::*;
 pub use super::service_record::*;
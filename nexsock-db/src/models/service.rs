@@ -3,6 +3,7 @@
 use crate::error::DatabaseError;
 use crate::models::prelude::*;
 use derive_more::Display;
+use nexsock_protocol::commands::manage_service::StopSignal;
 use nexsock_protocol::commands::service_status::ServiceState;
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{ArrayType, ValueType, ValueTypeErr};
@@ -42,6 +43,20 @@ pub struct Model {
     /// The Git authentication type used for this service.
     #[sea_orm(column_type = "Text")]
     pub git_auth_type: Option<String>,
+    /// Incremented on every update, an update only succeeds if the row is still at the version
+    /// the service was read at.
+    pub version: i64,
+    /// The exit code of the process the last time the service failed to start, `None` if it was
+    /// killed by a signal or hasn't failed since it last started.
+    pub last_exit_code: Option<i32>,
+    /// The output the service wrote before it last failed to start.
+    #[sea_orm(column_type = "Text")]
+    pub last_error: Option<String>,
+    /// The signal the service is sent first when it is stopped.
+    pub stop_signal: StopSignal,
+    /// The profile grouping the service with others that are started and stopped together.
+    #[sea_orm(column_type = "Text")]
+    pub profile: Option<String>,
 }
 
 /// Git-related parameters for service creation.
@@ -101,6 +116,11 @@ impl Model {
             git_branch: None,
             git_commit_hash: None,
             git_auth_type: None,
+            version: 0,
+            last_exit_code: None,
+            last_error: None,
+            stop_signal: StopSignal::default(),
+            profile: None,
         }
     }
 
@@ -156,6 +176,11 @@ impl Model {
             git_branch: git_params.branch,
             git_commit_hash: git_params.commit_hash,
             git_auth_type: git_params.auth_type,
+            version: 0,
+            last_exit_code: None,
+            last_error: None,
+            stop_signal: StopSignal::default(),
+            profile: None,
         }
     }
 
@@ -195,6 +220,13 @@ impl Model {
             git_branch: self.git_branch.clone(),
             git_commit_hash: self.git_commit_hash.clone(),
             git_auth_type: self.git_auth_type.clone(),
+            last_exit_code: self.last_exit_code,
+            last_error: self.last_error.clone(),
+            stop_signal: self.stop_signal,
+            profile: self.profile.clone(),
+            labels: Default::default(),
+            cpu_percent: None,
+            memory_bytes: None,
         }
     }
 }
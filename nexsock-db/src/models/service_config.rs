@@ -1,5 +1,5 @@
-use crate::models::prelude::ServiceEntity;
-pub(crate) use nexsock_protocol::commands::config::ConfigFormat;
+use crate::models::prelude::{ServiceConfigTemplateEntity, ServiceEntity};
+pub(crate) use nexsock_protocol::commands::config::{ConfigFormat, LogFormat};
 use nexsock_protocol::commands::service_status::ServiceConfig;
 use sea_orm::entity::prelude::*;
 
@@ -17,6 +17,18 @@ pub struct Model {
     pub format: ConfigFormat,
     /// An optional command to run the service.
     pub run_command: Option<String>,
+    /// How the captured output of the service is parsed into log entries.
+    pub log_format: LogFormat,
+    /// Whether ANSI escape sequences are removed from the captured output of the service.
+    pub strip_ansi: bool,
+    /// Whether the output of the service is captured, it's written straight to the daemon's
+    /// stdio otherwise.
+    pub capture_output: bool,
+    /// The chrono format of the timestamp the service starts its lines with, the entries are
+    /// timestamped with when the line was captured if unset.
+    pub log_timestamp_format: Option<String>,
+    /// The template the configuration inherits the fields it doesn't override from.
+    pub template_id: Option<i64>,
 }
 
 impl From<Model> for ServiceConfig {
@@ -43,6 +55,13 @@ pub enum Relation {
     /// Defines a "has_many" relationship with the `Service` entity.
     #[sea_orm(has_many = "super::service::Entity")]
     Services,
+    /// Defines a "belongs_to" relationship with the `ServiceConfigTemplate` it inherits from.
+    #[sea_orm(
+        belongs_to = "super::service_config_template::Entity",
+        from = "Column::TemplateId",
+        to = "super::service_config_template::Column::Id"
+    )]
+    Template,
 }
 
 impl Related<ServiceEntity> for Entity {
@@ -52,6 +71,13 @@ impl Related<ServiceEntity> for Entity {
     }
 }
 
+impl Related<ServiceConfigTemplateEntity> for Entity {
+    /// Returns the relation definition for the "Template" association.
+    fn to() -> RelationDef {
+        Relation::Template.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 // Helper for creating a new model
@@ -65,7 +91,9 @@ impl Model {
     ///
     /// Creates a new `Model` instance representing a service configuration.
     ///
-    /// The `id` field is initialized to 0 and is intended to be set by the database.
+    /// The `id` field is initialized to 0 and is intended to be set by the database, and the
+    /// log format defaults to [`LogFormat::Raw`] without ANSI stripping. The output is captured
+    /// with the capture time as timestamp, and no template is referenced.
     ///
     /// # Parameters
     /// - `filename`: The name of the configuration file.
@@ -88,6 +116,11 @@ impl Model {
             filename,
             format,
             run_command,
+            log_format: LogFormat::default(),
+            strip_ansi: false,
+            capture_output: true,
+            log_timestamp_format: None,
+            template_id: None,
         }
     }
 
@@ -122,6 +155,10 @@ impl Model {
             filename: self.filename.clone(),
             format: self.format,
             run_command: self.run_command.clone().unwrap_or_default(),
+            log_format: self.log_format,
+            strip_ansi: self.strip_ansi,
+            capture_output: self.capture_output,
+            log_timestamp_format: self.log_timestamp_format.clone(),
         }
     }
 }
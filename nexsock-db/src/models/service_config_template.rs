@@ -0,0 +1,113 @@
+use super::service_config::{ConfigFormat, Entity as ServiceConfigEntity, LogFormat};
+use crate::models::service_config::Model as ServiceConfig;
+use sea_orm::entity::prelude::*;
+
+/// Represents a named configuration services inherit from instead of repeating it.
+///
+/// A service configuration referencing a template through its `template_id` only has to set the
+/// fields it overrides, see [`Model::resolve`].
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "service_config_template")]
+pub struct Model {
+    /// The unique identifier for the template.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// The unique name configurations reference the template by.
+    #[sea_orm(unique)]
+    pub name: String,
+    /// The format of the configuration file.
+    pub format: ConfigFormat,
+    /// An optional command to run the services with.
+    pub run_command: Option<String>,
+    /// How the captured output of the services is parsed into log entries.
+    pub log_format: LogFormat,
+    /// Whether ANSI escape sequences are removed from the captured output of the services.
+    pub strip_ansi: bool,
+    /// Whether the output of the services is captured.
+    pub capture_output: bool,
+}
+
+/// Defines the relationships between the `ServiceConfigTemplate` entity and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Defines a "has_many" relationship with the `ServiceConfig` entities inheriting from it.
+    #[sea_orm(has_many = "super::service_config::Entity")]
+    ServiceConfigs,
+}
+
+impl Related<ServiceConfigEntity> for Entity {
+    /// Returns the relation definition for the "ServiceConfigs" association.
+    fn to() -> RelationDef {
+        Relation::ServiceConfigs.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Creates a new template named `name`.
+    ///
+    /// The `id` field is initialized to 0 and is intended to be set by the database, the other
+    /// settings start at the same defaults as a new service configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nexsock_db::models::service_config_template::Model;
+    /// # use nexsock_protocol::commands::config::ConfigFormat;
+    /// let template = Model::new("node".to_string(), ConfigFormat::Env, Some("npm start".to_string()));
+    /// assert_eq!(template.name, "node");
+    /// ```
+    pub fn new(name: String, format: ConfigFormat, run_command: Option<String>) -> Self {
+        let defaults = ServiceConfig::new(String::new(), format, run_command);
+
+        Self {
+            id: 0, // Will be set by the database
+            name,
+            format: defaults.format,
+            run_command: defaults.run_command,
+            log_format: defaults.log_format,
+            strip_ansi: defaults.strip_ansi,
+            capture_output: defaults.capture_output,
+        }
+    }
+
+    /// Returns the effective configuration of `config` inheriting from this template.
+    ///
+    /// Every field `config` leaves at the default of [`ServiceConfig::new`] is taken from the
+    /// template, the fields set to anything else override it. The filename is always the one of
+    /// `config`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let template = Model::new("node".to_string(), ConfigFormat::Properties, Some("npm start".to_string()));
+    /// let mut config = ServiceConfig::new("app.env".to_string(), ConfigFormat::Env, None);
+    /// config.strip_ansi = true;
+    ///
+    /// let effective = template.resolve(config);
+    /// assert_eq!(effective.format, ConfigFormat::Properties);
+    /// assert_eq!(effective.run_command.as_deref(), Some("npm start"));
+    /// assert!(effective.strip_ansi);
+    /// ```
+    pub fn resolve(&self, config: ServiceConfig) -> ServiceConfig {
+        let defaults = ServiceConfig::new(String::new(), ConfigFormat::default(), None);
+
+        ServiceConfig {
+            format: if config.format == defaults.format {
+                self.format
+            } else {
+                config.format
+            },
+            run_command: config.run_command.or_else(|| self.run_command.clone()),
+            log_format: if config.log_format == defaults.log_format {
+                self.log_format
+            } else {
+                config.log_format
+            },
+            strip_ansi: config.strip_ansi || self.strip_ansi,
+            capture_output: config.capture_output && self.capture_output,
+            ..config
+        }
+    }
+}
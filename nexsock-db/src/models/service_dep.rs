@@ -66,6 +66,8 @@ pub struct JoinedDependency {
     pub dependent_service_id: i64,
     /// Indicates whether a tunnel is enabled for this dependency.
     pub tunnel_enabled: bool,
+    /// Indicates whether the service may start without this dependency being ready.
+    pub optional: bool,
     /// The name of the dependent service.
     pub name: String,
     /// The repository URL of the dependent service.
@@ -3,6 +3,7 @@ use super::{
     service::{Entity as Service, ServiceStatus},
 };
 use nexsock_protocol::commands::dependency_info::DependencyInfo;
+use nexsock_protocol::commands::service_status::ServiceState;
 use sea_orm::{entity::prelude::*, FromJsonQueryResult};
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +31,8 @@ pub struct Model {
     pub dependent_service_id: i64,
     /// Indicates whether a tunnel is enabled for this dependency.
     pub tunnel_enabled: bool,
+    /// Indicates whether the service may start without this dependency being ready.
+    pub optional: bool,
 }
 
 /// Defines the relationships for the `ServiceDependency` entity.
@@ -41,7 +44,7 @@ pub enum Relation {
         from = "Column::ServiceId",
         to = "super::service::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     ParentService,
     /// Defines a "belongs_to" relationship with the `Service` entity, representing the dependent service.
@@ -50,7 +53,7 @@ pub enum Relation {
         from = "Column::DependentServiceId",
         to = "super::service::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     DependentService,
 }
@@ -76,6 +79,8 @@ impl From<JoinedDependency> for DependencyInfo {
             name: value.name,
             tunnel_enabled: value.tunnel_enabled,
             state: value.status.into(),
+            dependent_state: ServiceState::default(),
+            optional: value.optional,
         }
     }
 }
@@ -111,6 +116,7 @@ impl JoinedDependency {
             service_id: parent_service_id,
             dependent_service_id,
             tunnel_enabled,
+            optional: false,
             name: String::default(),
             repo_url: String::default(),
             port: 0,
@@ -0,0 +1,43 @@
+use super::service::Entity as Service;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+/// Represents a `key=value` label a service is tagged with.
+#[sea_orm(table_name = "service_label")]
+pub struct Model {
+    /// The unique identifier for the label record.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// The ID of the service the label belongs to.
+    pub service_id: i64,
+    /// The name of the label, unique per service.
+    #[sea_orm(column_type = "Text")]
+    pub key: String,
+    /// The value of the label.
+    #[sea_orm(column_type = "Text")]
+    pub value: String,
+}
+
+/// Defines the relationships for the `ServiceLabel` entity.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Defines a "belongs_to" relationship with the `Service` entity the label is attached to.
+    #[sea_orm(
+        belongs_to = "Service",
+        from = "Column::ServiceId",
+        to = "super::service::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Service,
+}
+
+impl Related<Service> for Entity {
+    /// Returns the relation definition linking a label to the service it is attached to.
+    fn to() -> RelationDef {
+        Relation::Service.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -48,6 +48,13 @@ impl From<DetailedServiceRecord> for ServiceStatus {
             git_branch: record.service.git_branch,
             git_commit_hash: record.service.git_commit_hash,
             git_auth_type: record.service.git_auth_type,
+            last_exit_code: record.service.last_exit_code,
+            last_error: record.service.last_error,
+            stop_signal: record.service.stop_signal,
+            profile: record.service.profile,
+            labels: Default::default(),
+            cpu_percent: None,
+            memory_bytes: None,
         }
     }
 }
@@ -4,8 +4,12 @@
 
 mod service;
 mod service_config;
+mod service_config_template;
 mod service_dependency;
+mod service_label;
 
 pub use service::*;
 pub use service_config::*;
+pub use service_config_template::*;
 pub use service_dependency::*;
+pub use service_label::*;
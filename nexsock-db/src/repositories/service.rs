@@ -1,14 +1,19 @@
-use crate::get_db_connection;
 use crate::models::prelude::*;
+use crate::repositories::{ServiceConfigRepository, ServiceLabelRepository};
+use crate::retry::{retry_busy, RetryBoundary};
+use crate::DatabaseError;
+use crate::{get_db_connection, ManagedConnection};
 use anyhow::{anyhow, bail, Context};
 use nexsock_protocol::commands::list_services::ListServicesResponse;
 use nexsock_protocol::commands::manage_service::ServiceRef;
 use nexsock_protocol::commands::service_status::ServiceStatus;
+use sea_orm::sea_query::LikeExpr;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter,
-    QuerySelect, RelationTrait, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, JoinType,
+    QueryFilter, QuerySelect, RelationTrait, Set,
 };
-use sea_orm::{NotSet, PaginatorTrait};
+use sea_orm::{Condition, NotSet, PaginatorTrait, QueryOrder, QueryTrait};
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 use tracing::debug;
 
@@ -18,11 +23,11 @@ use tracing::debug;
 /// as well as fetching detailed service information including configurations
 /// and dependencies.
 #[derive(Debug)]
-pub struct ServiceRepository<'a> {
-    connection: &'a DatabaseConnection,
+pub struct ServiceRepository<'a, C = DatabaseConnection> {
+    connection: &'a C,
 }
 
-impl<'a> ServiceRepository<'a> {
+impl<'a, C: ConnectionTrait> ServiceRepository<'a, C> {
     /// Creates a new `ServiceRepository` with a given database connection.
     ///
     /// # Arguments
@@ -35,12 +40,17 @@ impl<'a> ServiceRepository<'a> {
     /// let db_conn = get_test_db_connection();
     /// let repo = ServiceRepository::new(&db_conn);
     /// ```
-    pub fn new(connection: &'a DatabaseConnection) -> Self {
+    pub fn new(connection: &'a C) -> Self {
         Self { connection }
     }
+
+    /// Returns the connection this repository runs its queries on.
+    pub fn connection(&self) -> &'a C {
+        self.connection
+    }
 }
 
-impl ServiceRepository<'static> {
+impl ServiceRepository<'static, ManagedConnection> {
     /// Creates a new `ServiceRepository` using a globally available static database connection.
     ///
     /// This method is typically used when a `'static` lifetime is required for the repository.
@@ -69,7 +79,7 @@ impl ServiceRepository<'static> {
     /// # Examples
     ///
     /// ```
-    /// static REPO: LazyLock<ServiceRepository<'static>> = ServiceRepository::new_const();
+    /// static REPO: LazyLock<ServiceRepository<'static, ManagedConnection>> = ServiceRepository::new_const();
     /// let repo = &*REPO;
     /// ```
     pub const fn new_const() -> LazyLock<Self> {
@@ -77,10 +87,11 @@ impl ServiceRepository<'static> {
     }
 }
 
-impl ServiceRepository<'_> {
+impl<C: RetryBoundary> ServiceRepository<'_, C> {
     /// Constructs a `DetailedServiceRecord` for a service, including its configuration and all dependencies.
     ///
-    /// Returns an error with the provided message if the service is not found.
+    /// Returns an error with the provided message if the service is not found. The configuration
+    /// is the effective one, with the fields it inherits from its template resolved.
     ///
     /// # Arguments
     ///
@@ -129,6 +140,11 @@ impl ServiceRepository<'_> {
                 .await
                 .context("Database error while fetching dependencies for service")?;
 
+            let config = match config {
+                Some(config) => Some(ServiceConfigRepository::new(db).resolve(config).await?),
+                None => None,
+            };
+
             Ok(DetailedServiceRecord {
                 service,
                 config,
@@ -396,9 +412,13 @@ impl ServiceRepository<'_> {
     ///
     /// If the service's `id` is zero, a new record is inserted and the `id` field is updated with the generated value. Otherwise, the existing service record is updated with the provided data.
     ///
+    /// Updates are optimistic: the row is only written if it is still at `service.version`, after
+    /// which the version of both the row and `service` is incremented.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the database operation fails.
+    /// Returns an error if the database operation fails, or a [`DatabaseError::VersionConflict`] if
+    /// the service was updated since it was read.
     ///
     /// # Examples
     ///
@@ -424,18 +444,22 @@ impl ServiceRepository<'_> {
                 git_branch: Set(service.git_branch.clone()),
                 git_commit_hash: Set(service.git_commit_hash.clone()),
                 git_auth_type: Set(service.git_auth_type.clone()),
+                version: Set(0),
+                last_exit_code: Set(service.last_exit_code),
+                last_error: Set(service.last_error.clone()),
+                stop_signal: Set(service.stop_signal),
+                profile: Set(service.profile.clone()),
             };
 
-            let result = active_model
-                .insert(db)
+            let result = retry_busy(db, || active_model.clone().insert(db))
                 .await
                 .context("Database error while inserting new service")?;
             service.id = result.id;
+            service.version = result.version;
         } else {
             // Update existing record
-            let original_id = service.id; // Store original ID for context message
             let active_model = ServiceActiveModel {
-                id: Set(service.id),
+                id: NotSet,
                 config_id: Set(service.config_id),
                 name: Set(service.name.clone()),
                 repo_url: Set(service.repo_url.clone()),
@@ -445,11 +469,60 @@ impl ServiceRepository<'_> {
                 git_branch: Set(service.git_branch.clone()),
                 git_commit_hash: Set(service.git_commit_hash.clone()),
                 git_auth_type: Set(service.git_auth_type.clone()),
+                version: NotSet,
+                last_exit_code: Set(service.last_exit_code),
+                last_error: Set(service.last_error.clone()),
+                stop_signal: Set(service.stop_signal),
+                profile: Set(service.profile.clone()),
             };
 
-            active_model.update(db).await.with_context(|| {
-                format!("Database error while updating service with ID `{original_id}`")
-            })?;
+            self.update_versioned(service.id, service.version, active_model).await?;
+            service.version += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the set columns of `active_model` to the service with `id` if it is still at
+    /// `expected_version`, incrementing its version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service does not exist or the database operation fails, or a
+    /// [`DatabaseError::VersionConflict`] if the service is at another version.
+    async fn update_versioned(
+        &self,
+        id: i64,
+        expected_version: i64,
+        mut active_model: ServiceActiveModel,
+    ) -> anyhow::Result<()> {
+        let db = self.connection;
+
+        active_model.id = NotSet;
+        active_model.version = Set(expected_version + 1);
+
+        let result = retry_busy(db, || {
+            ServiceEntity::update_many()
+                .set(active_model.clone())
+                .filter(ServiceColumn::Id.eq(id))
+                .filter(ServiceColumn::Version.eq(expected_version))
+                .exec(db)
+        })
+        .await
+        .with_context(|| format!("Database error while updating service with ID `{id}`"))?;
+
+        if result.rows_affected == 0 {
+            let current = self
+                .get_by_id(id)
+                .await?
+                .ok_or_else(|| anyhow!("Service with ID `{}` not found", id))?;
+
+            return Err(DatabaseError::VersionConflict {
+                id,
+                expected: expected_version,
+                actual: current.version,
+            }
+            .into());
         }
 
         Ok(())
@@ -484,8 +557,7 @@ impl ServiceRepository<'_> {
             .ok_or_else(|| anyhow!("Cannot delete service: Service with ID `{}` not found", id))?;
 
         let model: ServiceActiveModel = service_to_delete.into();
-        model
-            .delete(db)
+        retry_busy(db, || model.clone().delete(db))
             .await
             .with_context(|| format!("Database error while deleting service with ID `{id}`"))?;
 
@@ -522,7 +594,12 @@ impl ServiceRepository<'_> {
     pub async fn get_status(&self, service_ref: &ServiceRef) -> anyhow::Result<ServiceStatus> {
         let service = self.get_detailed_by_ref(service_ref).await?;
 
-        Ok(service.into())
+        let mut status: ServiceStatus = service.into();
+        status.labels = ServiceLabelRepository::new(self.connection)
+            .get_labels(status.id)
+            .await?;
+
+        Ok(status)
     }
 
     /// Fetches a list of all services, indicating whether each has dependencies.
@@ -546,13 +623,78 @@ impl ServiceRepository<'_> {
     /// }
     /// ```
     pub async fn get_all_with_dependencies(&self) -> anyhow::Result<ListServicesResponse> {
-        let db = self.connection;
-
         let services = self
             .get_all()
             .await
             .context("Database error while fetching all services for dependency check")?;
 
+        self.with_dependency_flags(services).await
+    }
+
+    /// Retrieves the services of a profile with a flag indicating whether each service has
+    /// dependencies, like [`Self::get_all_with_dependencies`].
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile to list the services of.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a database operation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db_connection);
+    /// let response = repo.get_profile_with_dependencies("backend").await?;
+    /// ```
+    pub async fn get_profile_with_dependencies(
+        &self,
+        profile: &str,
+    ) -> anyhow::Result<ListServicesResponse> {
+        let services = self.find_by_profile(profile).await?;
+
+        self.with_dependency_flags(services).await
+    }
+
+    /// Retrieves the services carrying all of the `selector` labels with a flag indicating
+    /// whether each service has dependencies, like [`Self::get_all_with_dependencies`].
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The `key=value` labels the services must all carry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a database operation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db_connection);
+    /// let selector = BTreeMap::from([("team".to_string(), "payments".to_string())]);
+    /// let response = repo.get_labeled_with_dependencies(&selector).await?;
+    /// ```
+    pub async fn get_labeled_with_dependencies(
+        &self,
+        selector: &BTreeMap<String, String>,
+    ) -> anyhow::Result<ListServicesResponse> {
+        let services = self.find_by_labels(selector).await?;
+
+        self.with_dependency_flags(services).await
+    }
+
+    /// Converts `services` into a `ListServicesResponse`, looking up their labels and whether
+    /// each of them has dependencies.
+    async fn with_dependency_flags(
+        &self,
+        services: Vec<Service>,
+    ) -> anyhow::Result<ListServicesResponse> {
+        let db = self.connection;
+
+        let ids = services.iter().map(|service| service.id).collect::<Vec<_>>();
+        let mut labels = ServiceLabelRepository::new(db).get_labels_of(&ids).await?;
+
         let mut result_services = Vec::new();
 
         for service in services {
@@ -574,6 +716,7 @@ impl ServiceRepository<'_> {
                 state: service.status.into(),
                 port: service.port,
                 has_dependencies,
+                labels: labels.remove(&service.id).unwrap_or_default(),
             };
 
             result_services.push(service_info);
@@ -672,14 +815,17 @@ impl ServiceRepository<'_> {
             })?
             .ok_or_else(|| anyhow!("Service with ID `{}` not found", service_id))?;
 
+        let version = service.version;
         let mut active_service: ServiceActiveModel = service.into();
         active_service.git_branch = Set(git_branch);
         active_service.git_commit_hash = Set(git_commit_hash);
         active_service.git_auth_type = Set(git_auth_type);
 
-        active_service.update(db).await.with_context(|| {
-            format!("Failed to update Git information for service with ID `{service_id}`")
-        })?;
+        self.update_versioned(service_id, version, active_service)
+            .await
+            .with_context(|| {
+                format!("Failed to update Git information for service with ID `{service_id}`")
+            })?;
 
         Ok(())
     }
@@ -718,12 +864,15 @@ impl ServiceRepository<'_> {
             })?
             .ok_or_else(|| anyhow!("Service with ID `{}` not found", service_id))?;
 
+        let version = service.version;
         let mut active_service: ServiceActiveModel = service.into();
         active_service.git_branch = Set(git_branch);
 
-        active_service.update(db).await.with_context(|| {
-            format!("Failed to update Git branch for service with ID `{service_id}`")
-        })?;
+        self.update_versioned(service_id, version, active_service)
+            .await
+            .with_context(|| {
+                format!("Failed to update Git branch for service with ID `{service_id}`")
+            })?;
 
         Ok(())
     }
@@ -762,12 +911,63 @@ impl ServiceRepository<'_> {
             })?
             .ok_or_else(|| anyhow!("Service with ID `{}` not found", service_id))?;
 
+        let version = service.version;
         let mut active_service: ServiceActiveModel = service.into();
         active_service.git_commit_hash = Set(git_commit_hash);
 
-        active_service.update(db).await.with_context(|| {
-            format!("Failed to update Git commit hash for service with ID `{service_id}`")
-        })?;
+        self.update_versioned(service_id, version, active_service)
+            .await
+            .with_context(|| {
+                format!("Failed to update Git commit hash for service with ID `{service_id}`")
+            })?;
+
+        Ok(())
+    }
+
+    /// Records why the last start of a service failed, `None` for both clears the record.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The ID of the service to update
+    /// * `last_exit_code` - The exit code of the failed start, `None` if it was killed by a signal
+    /// * `last_error` - What the service wrote to stderr before it exited
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service does not exist or if the database update fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db);
+    /// repo.update_startup_failure(42, Some(1), Some("address in use".to_string())).await?;
+    /// ```
+    pub async fn update_startup_failure(
+        &self,
+        service_id: i64,
+        last_exit_code: Option<i32>,
+        last_error: Option<String>,
+    ) -> anyhow::Result<()> {
+        let db = self.connection;
+
+        let service = ServiceEntity::find_by_id(service_id)
+            .one(db)
+            .await
+            .with_context(|| {
+                format!("Database error while fetching service with ID `{service_id}`")
+            })?
+            .ok_or_else(|| anyhow!("Service with ID `{}` not found", service_id))?;
+
+        let version = service.version;
+        let mut active_service: ServiceActiveModel = service.into();
+        active_service.last_exit_code = Set(last_exit_code);
+        active_service.last_error = Set(last_error);
+
+        self.update_versioned(service_id, version, active_service)
+            .await
+            .with_context(|| {
+                format!("Failed to record startup failure for service with ID `{service_id}`")
+            })?;
 
         Ok(())
     }
@@ -844,4 +1044,175 @@ impl ServiceRepository<'_> {
                 format!("Database error while searching for services on commit `{commit_hash}`")
             })
     }
+
+    /// Returns all services in the specified profile, in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The name of the profile to search for.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `Service` instances tagged with the given profile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db_connection);
+    /// let services = repo.find_by_profile("backend").await?;
+    /// assert!(services.iter().all(|s| s.profile.as_deref() == Some("backend")));
+    /// ```
+    pub async fn find_by_profile(&self, profile: &str) -> anyhow::Result<Vec<Service>> {
+        let db = self.connection;
+
+        ServiceEntity::find()
+            .filter(ServiceColumn::Profile.eq(profile))
+            .order_by_asc(ServiceColumn::Id)
+            .all(db)
+            .await
+            .with_context(|| {
+                format!("Database error while searching for services in profile `{profile}`")
+            })
+    }
+
+    /// Returns all services carrying every label of `selector`, in the order they were added.
+    ///
+    /// An empty selector matches all services.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The `key=value` labels the services must all carry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db_connection);
+    /// let selector = BTreeMap::from([("env".to_string(), "staging".to_string())]);
+    /// let services = repo.find_by_labels(&selector).await?;
+    /// ```
+    pub async fn find_by_labels(
+        &self,
+        selector: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<Service>> {
+        let db = self.connection;
+
+        let mut query = ServiceEntity::find();
+        for (key, value) in selector {
+            query = query.filter(
+                ServiceColumn::Id.in_subquery(
+                    ServiceLabelEntity::find()
+                        .select_only()
+                        .column(ServiceLabelColumn::ServiceId)
+                        .filter(ServiceLabelColumn::Key.eq(key.as_str()))
+                        .filter(ServiceLabelColumn::Value.eq(value.as_str()))
+                        .into_query(),
+                ),
+            );
+        }
+
+        query
+            .order_by_asc(ServiceColumn::Id)
+            .all(db)
+            .await
+            .context("Database error while searching for services by labels")
+    }
+
+    /// Searches the services for `query`, a case-insensitive substring of their name, repository
+    /// URL or of one of their label keys and values.
+    ///
+    /// Services matching on their name come first, followed by those matching on their repository
+    /// URL and then those only matching on a label, in the order they were added within each
+    /// group.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The text to search for, `%` and `_` match themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db_connection);
+    /// let services = repo.search("api").await?;
+    /// ```
+    pub async fn search(&self, query: &str) -> anyhow::Result<Vec<Service>> {
+        let db = self.connection;
+        let pattern = like_pattern(query);
+
+        let label_matches = ServiceLabelEntity::find()
+            .select_only()
+            .column(ServiceLabelColumn::ServiceId)
+            .filter(
+                Condition::any()
+                    .add(ServiceLabelColumn::Key.like(pattern.clone()))
+                    .add(ServiceLabelColumn::Value.like(pattern.clone())),
+            )
+            .into_query();
+
+        let mut services = ServiceEntity::find()
+            .filter(
+                Condition::any()
+                    .add(ServiceColumn::Name.like(pattern.clone()))
+                    .add(ServiceColumn::RepoUrl.like(pattern))
+                    .add(ServiceColumn::Id.in_subquery(label_matches)),
+            )
+            .order_by_asc(ServiceColumn::Id)
+            .all(db)
+            .await
+            .with_context(|| format!("Database error while searching for services by `{query}`"))?;
+
+        // The sort is stable, so each group stays in the order the services were added
+        let query = query.to_lowercase();
+        services.sort_by_key(|service| search_rank(service, &query));
+
+        Ok(services)
+    }
+
+    /// Searches the services for `query` like [`Self::search`], with a flag indicating whether
+    /// each service has dependencies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a database operation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceRepository::new(&db_connection);
+    /// let response = repo.search_with_dependencies("api").await?;
+    /// ```
+    pub async fn search_with_dependencies(
+        &self,
+        query: &str,
+    ) -> anyhow::Result<ListServicesResponse> {
+        let services = self.search(query).await?;
+
+        self.with_dependency_flags(services).await
+    }
+}
+
+/// Builds a `LIKE` pattern matching `query` anywhere, escaping the wildcards in it.
+fn like_pattern(query: &str) -> LikeExpr {
+    let mut pattern = String::with_capacity(query.len() + 2);
+    pattern.push('%');
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+
+    LikeExpr::new(pattern).escape('\\')
+}
+
+/// Ranks a service found by [`ServiceRepository::search`] by the field `query` matched, lower is
+/// better. `query` must be lowercase.
+fn search_rank(service: &Service, query: &str) -> u8 {
+    if service.name.to_lowercase().contains(query) {
+        0
+    } else if service.repo_url.to_lowercase().contains(query) {
+        1
+    } else {
+        2
+    }
 }
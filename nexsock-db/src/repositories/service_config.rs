@@ -1,17 +1,24 @@
-use crate::get_db_connection;
-use crate::models::prelude::{ServiceConfig, ServiceConfigActiveModel, ServiceConfigEntity};
+use crate::models::prelude::{
+    ServiceColumn, ServiceConfig, ServiceConfigActiveModel, ServiceConfigEntity, ServiceEntity,
+};
+use crate::repositories::ServiceConfigTemplateRepository;
+use crate::retry::{retry_busy, RetryBoundary};
+use crate::{get_db_connection, ManagedConnection};
 use anyhow::{anyhow, Context};
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, NotSet, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, NotSet,
+    QueryFilter, QuerySelect, Set,
+};
 
 /// Repository for managing `ServiceConfig` entities in the database.
 ///
 /// Provides methods for creating, reading, updating, and deleting service configurations.
 #[derive(Debug)]
-pub struct ServiceConfigRepository<'a> {
-    connection: &'a DatabaseConnection,
+pub struct ServiceConfigRepository<'a, C = DatabaseConnection> {
+    connection: &'a C,
 }
 
-impl<'a> ServiceConfigRepository<'a> {
+impl<'a, C: ConnectionTrait> ServiceConfigRepository<'a, C> {
     /// Creates a new `ServiceConfigRepository` with a given database connection.
     ///
     /// # Arguments
@@ -23,12 +30,17 @@ impl<'a> ServiceConfigRepository<'a> {
     /// ```
     /// let repo = ServiceConfigRepository::new(&db_connection);
     /// ```
-    pub fn new(connection: &'a DatabaseConnection) -> Self {
+    pub fn new(connection: &'a C) -> Self {
         Self { connection }
     }
+
+    /// Returns the connection this repository runs its queries on.
+    pub fn connection(&self) -> &'a C {
+        self.connection
+    }
 }
 
-impl ServiceConfigRepository<'static> {
+impl ServiceConfigRepository<'static, ManagedConnection> {
     /// Creates a new `ServiceConfigRepository` using a globally available static database connection.
     ///
     /// This method is typically used when a `'static` lifetime is required for the repository.
@@ -46,7 +58,7 @@ impl ServiceConfigRepository<'static> {
     }
 }
 
-impl ServiceConfigRepository<'_> {
+impl<C: RetryBoundary> ServiceConfigRepository<'_, C> {
     /// Fetches a service configuration by its ID.
     ///
     /// # Arguments
@@ -79,6 +91,86 @@ impl ServiceConfigRepository<'_> {
             })
     }
 
+    /// Retrieves the configuration of the service with the ID `service_id`.
+    ///
+    /// The configuration is read through the `config_id` of the service in a single query,
+    /// without loading the service itself or its dependencies.
+    ///
+    /// Returns `Ok(None)` if the service doesn't exist or has no configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigRepository::new(&db_connection);
+    /// if let Some(config) = repo.get_by_service_id(7).await? {
+    ///     println!("{}", config.filename);
+    /// }
+    /// ```
+    pub async fn get_by_service_id(
+        &self,
+        service_id: i64,
+    ) -> anyhow::Result<Option<ServiceConfig>> {
+        let db = self.connection;
+        ServiceConfigEntity::find()
+            .inner_join(ServiceEntity)
+            .filter(ServiceColumn::Id.eq(service_id))
+            .one(db)
+            .await
+            .with_context(|| {
+                format!(
+                    "Database error while fetching the configuration of service with ID `{service_id}`"
+                )
+            })
+    }
+
+    /// Retrieves the effective configuration of the service with the ID `service_id`, with the
+    /// fields it inherits from its template resolved.
+    ///
+    /// Returns `Ok(None)` if the service doesn't exist or has no configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigRepository::new(&db_connection);
+    /// let config = repo.get_effective_by_service_id(7).await?;
+    /// ```
+    pub async fn get_effective_by_service_id(
+        &self,
+        service_id: i64,
+    ) -> anyhow::Result<Option<ServiceConfig>> {
+        match self.get_by_service_id(service_id).await? {
+            Some(config) => self.resolve(config).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the effective configuration of `config`, see
+    /// [`ServiceConfigTemplate::resolve`](crate::models::service_config_template::Model::resolve).
+    ///
+    /// A configuration without a template, or whose template no longer exists, is returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigRepository::new(&db_connection);
+    /// let config = repo.get_by_id(42).await?.unwrap();
+    /// let effective = repo.resolve(config).await?;
+    /// ```
+    pub async fn resolve(&self, config: ServiceConfig) -> anyhow::Result<ServiceConfig> {
+        let Some(template_id) = config.template_id else {
+            return Ok(config);
+        };
+
+        let template = ServiceConfigTemplateRepository::new(self.connection)
+            .get_by_id(template_id)
+            .await?;
+
+        Ok(match template {
+            Some(template) => template.resolve(config),
+            None => config,
+        })
+    }
+
     /// Saves a service configuration to the database.
     ///
     /// If the configuration's `id` is 0, a new record is inserted. Otherwise, the
@@ -121,10 +213,14 @@ impl ServiceConfigRepository<'_> {
                 filename: Set(config.filename.clone()),
                 format: Set(config.format),
                 run_command: Set(config.run_command.clone()),
+                log_format: Set(config.log_format),
+                strip_ansi: Set(config.strip_ansi),
+                capture_output: Set(config.capture_output),
+                log_timestamp_format: Set(config.log_timestamp_format.clone()),
+                template_id: Set(config.template_id),
             };
 
-            let result = active_model
-                .insert(db)
+            let result = retry_busy(db, || active_model.clone().insert(db))
                 .await
                 .context("Database error while inserting new service configuration")?;
             config.id = result.id;
@@ -136,13 +232,20 @@ impl ServiceConfigRepository<'_> {
                 filename: Set(config.filename.clone()),
                 format: Set(config.format),
                 run_command: Set(config.run_command.clone()),
+                log_format: Set(config.log_format),
+                strip_ansi: Set(config.strip_ansi),
+                capture_output: Set(config.capture_output),
+                log_timestamp_format: Set(config.log_timestamp_format.clone()),
+                template_id: Set(config.template_id),
             };
 
-            active_model.update(db).await.with_context(|| {
-                format!(
-                    "Database error while updating service configuration with ID `{original_id}`"
-                )
-            })?;
+            retry_busy(db, || active_model.clone().update(db))
+                .await
+                .with_context(|| {
+                    format!(
+                        "Database error while updating service configuration with ID `{original_id}`"
+                    )
+                })?;
         }
 
         Ok(())
@@ -177,9 +280,11 @@ impl ServiceConfigRepository<'_> {
             .ok_or_else(|| anyhow!("Cannot delete service configuration: Service configuration with ID `{}` not found", id))?;
 
         let model: ServiceConfigActiveModel = config_to_delete.into();
-        model.delete(db).await.with_context(|| {
-            format!("Database error while deleting service configuration with ID `{id}`")
-        })?;
+        retry_busy(db, || model.clone().delete(db))
+            .await
+            .with_context(|| {
+                format!("Database error while deleting service configuration with ID `{id}`")
+            })?;
 
         Ok(())
     }
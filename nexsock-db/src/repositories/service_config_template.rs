@@ -0,0 +1,198 @@
+use crate::models::prelude::*;
+use crate::retry::{retry_busy, RetryBoundary};
+use crate::{get_db_connection, ManagedConnection};
+use anyhow::{anyhow, Context};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, NotSet,
+    QueryFilter, Set,
+};
+
+/// Repository for managing `ServiceConfigTemplate` entities in the database.
+///
+/// Templates are named configurations that service configurations reference to inherit the
+/// fields they don't override.
+#[derive(Debug)]
+pub struct ServiceConfigTemplateRepository<'a, C = DatabaseConnection> {
+    connection: &'a C,
+}
+
+impl<'a, C: ConnectionTrait> ServiceConfigTemplateRepository<'a, C> {
+    /// Creates a new `ServiceConfigTemplateRepository` with the provided database connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigTemplateRepository::new(&db_connection);
+    /// ```
+    pub fn new(connection: &'a C) -> Self {
+        Self { connection }
+    }
+
+    /// Returns the connection this repository runs its queries on.
+    pub fn connection(&self) -> &'a C {
+        self.connection
+    }
+}
+
+impl ServiceConfigTemplateRepository<'static, ManagedConnection> {
+    /// Creates a new repository instance using a globally available static database connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigTemplateRepository::new_from_static();
+    /// ```
+    pub fn new_from_static() -> Self {
+        let connection = get_db_connection();
+
+        Self { connection }
+    }
+}
+
+impl<C: RetryBoundary> ServiceConfigTemplateRepository<'_, C> {
+    /// Retrieves a template by its ID, `Ok(None)` if it doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigTemplateRepository::new(&db_connection);
+    /// let template = repo.get_by_id(3).await?;
+    /// ```
+    pub async fn get_by_id(&self, id: i64) -> anyhow::Result<Option<ServiceConfigTemplate>> {
+        ServiceConfigTemplateEntity::find_by_id(id)
+            .one(self.connection)
+            .await
+            .with_context(|| {
+                format!(
+                    "Database error while fetching service configuration template with ID `{id}`"
+                )
+            })
+    }
+
+    /// Retrieves a template by its name, `Ok(None)` if it doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigTemplateRepository::new(&db_connection);
+    /// let template = repo.get_by_name("node").await?;
+    /// ```
+    pub async fn get_by_name(
+        &self,
+        name: impl AsRef<str>,
+    ) -> anyhow::Result<Option<ServiceConfigTemplate>> {
+        let name = name.as_ref();
+
+        ServiceConfigTemplateEntity::find()
+            .filter(ServiceConfigTemplateColumn::Name.eq(name))
+            .one(self.connection)
+            .await
+            .with_context(|| {
+                format!("Database error while fetching service configuration template `{name}`")
+            })
+    }
+
+    /// Saves a template to the database.
+    ///
+    /// If the template's `id` is 0, a new record is inserted and its `id` is updated with the
+    /// generated value. Otherwise, the existing record with the matching `id` is updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another template has the same name or the database operation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut template = ServiceConfigTemplate::new("node".to_string(), ConfigFormat::Env, Some("npm start".to_string()));
+    /// repo.save(&mut template).await?;
+    /// assert!(template.id > 0);
+    /// ```
+    pub async fn save(&self, template: &mut ServiceConfigTemplate) -> anyhow::Result<()> {
+        let db = self.connection;
+
+        let active_model = ServiceConfigTemplateActiveModel {
+            id: if template.id == 0 {
+                NotSet
+            } else {
+                Set(template.id)
+            },
+            name: Set(template.name.clone()),
+            format: Set(template.format),
+            run_command: Set(template.run_command.clone()),
+            log_format: Set(template.log_format),
+            strip_ansi: Set(template.strip_ansi),
+            capture_output: Set(template.capture_output),
+        };
+
+        if template.id == 0 {
+            let result = retry_busy(db, || active_model.clone().insert(db))
+                .await
+                .with_context(|| {
+                    format!(
+                        "Database error while inserting service configuration template `{}`",
+                        template.name
+                    )
+                })?;
+            template.id = result.id;
+        } else {
+            let id = template.id;
+            retry_busy(db, || active_model.clone().update(db))
+                .await
+                .with_context(|| {
+                    format!(
+                        "Database error while updating service configuration template with ID `{id}`"
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes a template by its ID.
+    ///
+    /// The configurations referencing the template stop inheriting from it and keep only their
+    /// own values, run this on a transaction to make the deletion atomic.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template does not exist or a database error occurs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceConfigTemplateRepository::new(&db_connection);
+    /// repo.delete_by_id(3).await?;
+    /// ```
+    pub async fn delete_by_id(&self, id: i64) -> anyhow::Result<()> {
+        let db = self.connection;
+
+        let template = self.get_by_id(id).await?.ok_or_else(|| {
+            anyhow!(
+                "Cannot delete service configuration template: Template with ID `{id}` not found"
+            )
+        })?;
+
+        ServiceConfigEntity::update_many()
+            .col_expr(
+                ServiceConfigColumn::TemplateId,
+                Expr::value(Option::<i64>::None),
+            )
+            .filter(ServiceConfigColumn::TemplateId.eq(id))
+            .exec(db)
+            .await
+            .with_context(|| {
+                format!(
+                    "Database error while detaching configurations from template with ID `{id}`"
+                )
+            })?;
+
+        let model: ServiceConfigTemplateActiveModel = template.into();
+        model.delete(db).await.with_context(|| {
+            format!("Database error while deleting service configuration template with ID `{id}`")
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,11 +1,13 @@
-use crate::get_db_connection;
 use crate::models::prelude::*;
+use crate::retry::{retry_busy, RetryBoundary};
+use crate::{get_db_connection, ManagedConnection};
 use anyhow::{anyhow, Context};
-use nexsock_protocol::commands::dependency::ListDependenciesResponse;
+use nexsock_protocol::commands::dependency::{ListDependenciesResponse, ListDependentsResponse};
 use nexsock_protocol::commands::dependency_info::DependencyInfo;
+use nexsock_protocol::commands::service_status::ServiceState;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter,
-    QuerySelect, QueryTrait, RelationTrait, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, NotSet,
+    QueryFilter, QuerySelect, QueryTrait, RelationTrait, Set, TransactionTrait,
 };
 use tracing::debug;
 
@@ -14,11 +16,11 @@ use tracing::debug;
 /// Provides methods for creating, reading, updating, and deleting service dependencies.
 /// It also includes methods for fetching detailed dependency information.
 #[derive(Debug)]
-pub struct ServiceDependencyRepository<'a> {
-    connection: &'a DatabaseConnection,
+pub struct ServiceDependencyRepository<'a, C = DatabaseConnection> {
+    connection: &'a C,
 }
 
-impl<'a> ServiceDependencyRepository<'a> {
+impl<'a, C: ConnectionTrait> ServiceDependencyRepository<'a, C> {
     /// Creates a new `ServiceDependencyRepository` with a given database connection.
     ///
     /// # Arguments
@@ -30,12 +32,17 @@ impl<'a> ServiceDependencyRepository<'a> {
     /// ```
     /// let repo = ServiceDependencyRepository::new(&db_connection);
     /// ```
-    pub fn new(connection: &'a DatabaseConnection) -> Self {
+    pub fn new(connection: &'a C) -> Self {
         Self { connection }
     }
+
+    /// Returns the connection this repository runs its queries on.
+    pub fn connection(&self) -> &'a C {
+        self.connection
+    }
 }
 
-impl ServiceDependencyRepository<'static> {
+impl ServiceDependencyRepository<'static, ManagedConnection> {
     /// Creates a new `ServiceDependencyRepository` using a globally available static database connection.
     ///
     /// This method is typically used when a `'static` lifetime is required for the repository.
@@ -53,7 +60,7 @@ impl ServiceDependencyRepository<'static> {
     }
 }
 
-impl ServiceDependencyRepository<'_> {
+impl<C: RetryBoundary> ServiceDependencyRepository<'_, C> {
     /// Fetches a service dependency by its ID.
     ///
     /// This method also performs a left join to include information about the dependent service.
@@ -156,6 +163,49 @@ impl ServiceDependencyRepository<'_> {
         Ok(dependencies)
     }
 
+    /// Fetches the dependency of `service_id` on `dependent_service_id`, joined with details of
+    /// the dependent service like [`get_by_service_id`](Self::get_by_service_id) does.
+    ///
+    /// Returns `Ok(None)` if `service_id` doesn't depend on `dependent_service_id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceDependencyRepository::new(&connection);
+    /// if let Some(dependency) = repo.get_dependency(1, 2).await? {
+    ///     println!("{} runs on port {}", dependency.name, dependency.port);
+    /// }
+    /// ```
+    pub async fn get_dependency(
+        &self,
+        service_id: i64,
+        dependent_service_id: i64,
+    ) -> anyhow::Result<Option<JoinedDependency>> {
+        let db = self.connection;
+        let dependency = ServiceDependencyEntity::find()
+            .filter(ServiceDependencyColumn::ServiceId.eq(service_id))
+            .filter(ServiceDependencyColumn::DependentServiceId.eq(dependent_service_id))
+            .join(
+                sea_orm::JoinType::LeftJoin,
+                ServiceDependencyRelation::DependentService.def(),
+            )
+            .column_as(ServiceColumn::Name, "name")
+            .column_as(ServiceColumn::RepoUrl, "repo_url")
+            .column_as(ServiceColumn::Port, "port")
+            .column_as(ServiceColumn::RepoPath, "repo_path")
+            .column_as(ServiceColumn::Status, "status")
+            .into_model::<JoinedDependency>()
+            .one(db)
+            .await
+            .with_context(|| {
+                format!(
+                    "Database error while fetching the dependency of service ID `{service_id}` on service ID `{dependent_service_id}`"
+                )
+            })?;
+
+        Ok(dependency)
+    }
+
     /// Saves a service dependency to the database.
     ///
     /// If the dependency's `id` is 0, a new record is inserted. Otherwise, the existing
@@ -184,6 +234,7 @@ impl ServiceDependencyRepository<'_> {
     ///     service_id: 1,
     ///     dependent_service_id: 2,
     ///     tunnel_enabled: false,
+    ///     optional: false,
     /// };
     /// repo.save(&mut dependency).await?;
     /// assert!(dependency.id > 0);
@@ -198,9 +249,10 @@ impl ServiceDependencyRepository<'_> {
                 service_id: Set(dependency.service_id),
                 dependent_service_id: Set(dependency.dependent_service_id),
                 tunnel_enabled: Set(dependency.tunnel_enabled),
+                optional: Set(dependency.optional),
             };
 
-            let result = active_model.insert(db).await.with_context(||
+            let result = retry_busy(db, || active_model.clone().insert(db)).await.with_context(||
                 format!("Database error while inserting new service dependency for service ID `{}` and dependent service ID `{}`", dependency.service_id, dependency.dependent_service_id)
             )?;
             dependency.id = result.id;
@@ -212,11 +264,16 @@ impl ServiceDependencyRepository<'_> {
                 service_id: Set(dependency.service_id),
                 dependent_service_id: Set(dependency.dependent_service_id),
                 tunnel_enabled: Set(dependency.tunnel_enabled),
+                optional: Set(dependency.optional),
             };
 
-            active_model.update(db).await.with_context(|| {
-                format!("Database error while updating service dependency with ID `{original_id}`")
-            })?;
+            retry_busy(db, || active_model.clone().update(db))
+                .await
+                .with_context(|| {
+                    format!(
+                        "Database error while updating service dependency with ID `{original_id}`"
+                    )
+                })?;
         }
 
         Ok(())
@@ -260,9 +317,11 @@ impl ServiceDependencyRepository<'_> {
             })?;
 
         let model: ServiceDependencyActiveModel = dependency_to_delete.into();
-        model.delete(db).await.with_context(|| {
-            format!("Database error while deleting service dependency with ID `{id}`")
-        })?;
+        retry_busy(db, || model.clone().delete(db))
+            .await
+            .with_context(|| {
+                format!("Database error while deleting service dependency with ID `{id}`")
+            })?;
 
         Ok(())
     }
@@ -291,7 +350,10 @@ impl ServiceDependencyRepository<'_> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn delete_many(&self, ids: Vec<i64>) -> anyhow::Result<()> {
+    pub async fn delete_many(&self, ids: Vec<i64>) -> anyhow::Result<()>
+    where
+        C: TransactionTrait,
+    {
         let db = self.connection;
 
         // Start a transaction
@@ -400,4 +462,77 @@ impl ServiceDependencyRepository<'_> {
             dependencies,
         })
     }
+
+    /// Fetches every dependency row where the given service is the dependency, joined with
+    /// details of the service that depends on it.
+    ///
+    /// This is the reverse of [`get_by_service_id`](Self::get_by_service_id): the joined
+    /// `name`, `repo_url`, `port`, `repo_path` and `status` describe the parent service
+    /// (`service_id`), not the service passed in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceDependencyRepository::new(&db_connection);
+    /// // Everything that would break if service 42 went away
+    /// let dependents = repo.list_dependents(42).await?;
+    /// ```
+    pub async fn list_dependents(&self, service_id: i64) -> anyhow::Result<Vec<JoinedDependency>> {
+        let db = self.connection;
+        let dependents = ServiceDependencyEntity::find()
+            .filter(ServiceDependencyColumn::DependentServiceId.eq(service_id))
+            .join(
+                sea_orm::JoinType::LeftJoin,
+                ServiceDependencyRelation::ParentService.def(),
+            )
+            .column_as(ServiceColumn::Name, "name")
+            .column_as(ServiceColumn::RepoUrl, "repo_url")
+            .column_as(ServiceColumn::Port, "port")
+            .column_as(ServiceColumn::RepoPath, "repo_path")
+            .column_as(ServiceColumn::Status, "status")
+            .into_model::<JoinedDependency>()
+            .all(db)
+            .await
+            .with_context(|| {
+                format!("Database error while fetching dependents for service ID `{service_id}`")
+            })?;
+
+        Ok(dependents)
+    }
+
+    /// Constructs a `ListDependentsResponse` listing the services that depend on the given service.
+    ///
+    /// Each entry's `id` and `name` refer to the dependent (parent) service.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceDependencyRepository::new(&db_connection);
+    /// let response = repo.get_dependents_response(2, "database".to_string()).await?;
+    /// assert_eq!(response.service_name, "database");
+    /// ```
+    pub async fn get_dependents_response(
+        &self,
+        service_id: i64,
+        service_name: String,
+    ) -> anyhow::Result<ListDependentsResponse> {
+        let dependents = self
+            .list_dependents(service_id)
+            .await?
+            .into_iter()
+            .map(|dependent| DependencyInfo {
+                id: dependent.service_id,
+                name: dependent.name,
+                tunnel_enabled: dependent.tunnel_enabled,
+                state: dependent.status.into(),
+                dependent_state: ServiceState::default(),
+                optional: dependent.optional,
+            })
+            .collect();
+
+        Ok(ListDependentsResponse {
+            service_name,
+            dependents,
+        })
+    }
 }
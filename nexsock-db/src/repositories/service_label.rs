@@ -0,0 +1,173 @@
+use crate::models::prelude::*;
+use crate::retry::{retry_busy, RetryBoundary};
+use crate::{get_db_connection, ManagedConnection};
+use anyhow::{bail, Context};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter, Set,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Repository for managing the `ServiceLabel` entities in the database.
+///
+/// Labels are free-form `key=value` metadata attached to services, a service has at most one
+/// value per key.
+#[derive(Debug)]
+pub struct ServiceLabelRepository<'a, C = DatabaseConnection> {
+    connection: &'a C,
+}
+
+impl<'a, C: ConnectionTrait> ServiceLabelRepository<'a, C> {
+    /// Creates a new `ServiceLabelRepository` with the provided database connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceLabelRepository::new(&db_connection);
+    /// ```
+    pub fn new(connection: &'a C) -> Self {
+        Self { connection }
+    }
+
+    /// Returns the connection this repository runs its queries on.
+    pub fn connection(&self) -> &'a C {
+        self.connection
+    }
+}
+
+impl ServiceLabelRepository<'static, ManagedConnection> {
+    /// Creates a new repository instance using a globally available static database connection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceLabelRepository::new_from_static();
+    /// ```
+    pub fn new_from_static() -> Self {
+        let connection = get_db_connection();
+
+        Self { connection }
+    }
+}
+
+impl<C: RetryBoundary> ServiceLabelRepository<'_, C> {
+    /// Replaces the labels of a service with `labels`, an empty map removes all of them.
+    ///
+    /// The old labels are removed before the new ones are inserted, run this on a transaction
+    /// to make the replacement atomic.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a label has an empty key, the service does not exist or a database
+    /// operation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceLabelRepository::new(&db_connection);
+    /// let labels = BTreeMap::from([("team".to_string(), "payments".to_string())]);
+    /// repo.set_labels(service.id, &labels).await?;
+    /// ```
+    pub async fn set_labels(
+        &self,
+        service_id: i64,
+        labels: &BTreeMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let db = self.connection;
+
+        if labels.keys().any(|key| key.is_empty()) {
+            bail!("Label keys of service ID `{service_id}` must not be empty");
+        }
+
+        retry_busy(db, || {
+            ServiceLabelEntity::delete_many()
+                .filter(ServiceLabelColumn::ServiceId.eq(service_id))
+                .exec(db)
+        })
+        .await
+        .with_context(|| {
+            format!("Database error while removing the labels of service ID `{service_id}`")
+        })?;
+
+        if labels.is_empty() {
+            return Ok(());
+        }
+
+        let models: Vec<_> = labels
+            .iter()
+            .map(|(key, value)| ServiceLabelActiveModel {
+                id: NotSet,
+                service_id: Set(service_id),
+                key: Set(key.clone()),
+                value: Set(value.clone()),
+            })
+            .collect();
+
+        retry_busy(db, || {
+            ServiceLabelEntity::insert_many(models.clone()).exec(db)
+        })
+        .await
+        .with_context(|| {
+            format!("Database error while inserting the labels of service ID `{service_id}`")
+        })?;
+
+        Ok(())
+    }
+
+    /// Returns the labels of a service, empty if it has none or does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceLabelRepository::new(&db_connection);
+    /// let labels = repo.get_labels(service.id).await?;
+    /// assert_eq!(labels.get("team").map(String::as_str), Some("payments"));
+    /// ```
+    pub async fn get_labels(&self, service_id: i64) -> anyhow::Result<BTreeMap<String, String>> {
+        let db = self.connection;
+
+        let labels = ServiceLabelEntity::find()
+            .filter(ServiceLabelColumn::ServiceId.eq(service_id))
+            .all(db)
+            .await
+            .with_context(|| {
+                format!("Database error while fetching the labels of service ID `{service_id}`")
+            })?;
+
+        Ok(labels
+            .into_iter()
+            .map(|label| (label.key, label.value))
+            .collect())
+    }
+
+    /// Returns the labels of each of `service_ids` in a single query, services without labels
+    /// are left out of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let repo = ServiceLabelRepository::new(&db_connection);
+    /// let labels = repo.get_labels_of(&[1, 2]).await?;
+    /// ```
+    pub async fn get_labels_of(
+        &self,
+        service_ids: &[i64],
+    ) -> anyhow::Result<HashMap<i64, BTreeMap<String, String>>> {
+        let db = self.connection;
+
+        let labels = ServiceLabelEntity::find()
+            .filter(ServiceLabelColumn::ServiceId.is_in(service_ids.iter().copied()))
+            .all(db)
+            .await
+            .context("Database error while fetching the labels of services")?;
+
+        let mut by_service: HashMap<i64, BTreeMap<String, String>> = HashMap::new();
+        for label in labels {
+            by_service
+                .entry(label.service_id)
+                .or_default()
+                .insert(label.key, label.value);
+        }
+
+        Ok(by_service)
+    }
+}
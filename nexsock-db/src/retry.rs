@@ -0,0 +1,116 @@
+//! Retrying of database operations that fail because the database is busy.
+//!
+//! SQLite allows a single writer at a time, a write issued while another connection holds the
+//! write lock fails with `SQLITE_BUSY` or `SQLITE_LOCKED` instead of waiting. Those failures are
+//! transient, so repositories run their writes through [`retry_busy`].
+//!
+//! Only top-level operations are retried. A statement inside a transaction is not, the transaction
+//! may hold the lock the other connection waits on, so [`with_transaction`](crate::with_transaction)
+//! reruns the whole transaction instead.
+
+use crate::ManagedConnection;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbErr, RuntimeErr};
+use std::future::Future;
+use std::time::Duration;
+use tracing::debug;
+
+/// How many times an operation is retried before its error is returned.
+pub(crate) const MAX_BUSY_RETRIES: u32 = 5;
+
+/// The wait before the first retry, doubled on every further retry.
+pub(crate) const INITIAL_BUSY_BACKOFF: Duration = Duration::from_millis(10);
+
+/// The primary result codes of SQLite for a database or table locked by another connection.
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+/// A connection repositories run their statements on, deciding whether a statement that failed
+/// because the database is locked can be retried on its own.
+pub trait RetryBoundary: ConnectionTrait {
+    /// Whether statements on this connection run outside of any transaction of the caller.
+    const IS_TOP_LEVEL: bool;
+}
+
+impl RetryBoundary for DatabaseConnection {
+    const IS_TOP_LEVEL: bool = true;
+}
+
+impl RetryBoundary for ManagedConnection {
+    const IS_TOP_LEVEL: bool = true;
+}
+
+impl RetryBoundary for DatabaseTransaction {
+    const IS_TOP_LEVEL: bool = false;
+}
+
+/// Runs `operation` on `connection`, retrying it with an exponential backoff while it fails
+/// because the database is locked.
+///
+/// Operations on a transaction are run once, their error is left to the top-level transaction.
+///
+/// # Errors
+///
+/// Returns the error of `operation` if it isn't a locked database, or the last one after
+/// [`MAX_BUSY_RETRIES`] retries.
+pub(crate) async fn retry_busy<C, F, Fut, T>(_connection: &C, mut operation: F) -> Result<T, DbErr>
+where
+    C: RetryBoundary,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, DbErr>>,
+{
+    if !C::IS_TOP_LEVEL {
+        return operation().await;
+    }
+
+    retry_while(operation, is_busy).await
+}
+
+/// Runs `operation`, retrying it with an exponential backoff while its error is one `busy` holds
+/// for.
+///
+/// # Errors
+///
+/// Returns the error of `operation` if `busy` doesn't hold for it, or the last one after
+/// [`MAX_BUSY_RETRIES`] retries.
+pub(crate) async fn retry_while<F, Fut, T, E>(
+    mut operation: F,
+    busy: impl Fn(&E) -> bool,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let mut backoff = INITIAL_BUSY_BACKOFF;
+
+    for retry in 1..=MAX_BUSY_RETRIES {
+        match operation().await {
+            Err(e) if busy(&e) => {
+                debug!(error = %e, retry, ?backoff, "Database is locked, retrying");
+
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    operation().await
+}
+
+/// Returns whether `error` was caused by the database being locked by another connection.
+pub(crate) fn is_busy(error: &DbErr) -> bool {
+    let (DbErr::Conn(RuntimeErr::SqlxError(error))
+    | DbErr::Exec(RuntimeErr::SqlxError(error))
+    | DbErr::Query(RuntimeErr::SqlxError(error))) = error
+    else {
+        return false;
+    };
+
+    // SQLite reports extended result codes, the primary code is in the lowest byte
+    error
+        .as_database_error()
+        .and_then(|error| error.code())
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+}
@@ -2,6 +2,7 @@
 
 use migration::{Migrator, MigratorTrait}; // Assuming migration crate is accessible
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use std::path::Path;
 use std::time::Duration;
 
 /// Sets up an in-memory SQLite database for testing.
@@ -29,18 +30,44 @@ use std::time::Duration;
 /// // Use `db` for test queries...
 /// ```
 pub async fn setup_in_memory_db() -> anyhow::Result<DatabaseConnection> {
+    let conn = connect_in_memory_db().await?;
+
+    // Run migrations to set up the schema
+    // The `migration` crate needs to be a dependency of `nexsock-db`
+    // or otherwise accessible in the test context.
+    Migrator::up(&conn, None).await?;
+
+    Ok(conn)
+}
+
+/// Creates an in-memory SQLite database connection without running any migrations.
+pub async fn connect_in_memory_db() -> anyhow::Result<DatabaseConnection> {
     let db_url = "sqlite::memory:"; // Standard DSN for in-memory SQLite
 
     let mut opt = ConnectOptions::new(db_url.to_string());
     opt.connect_timeout(Duration::from_secs(10)) // Shorter timeout for tests
         .idle_timeout(Duration::from_secs(5 * 60))
-        .sqlx_logging(false); // Optionally disable SQLx logging for cleaner test output
+        .sqlx_logging(false) // Optionally disable SQLx logging for cleaner test output
+        .map_sqlx_sqlite_opts(|opts| opts.foreign_keys(true));
 
-    let conn = Database::connect(opt).await?;
+    Ok(Database::connect(opt).await?)
+}
 
-    // Run migrations to set up the schema
-    // The `migration` crate needs to be a dependency of `nexsock-db`
-    // or otherwise accessible in the test context.
+/// Opens the SQLite database file at `path`, creating it and running the migrations if needed.
+///
+/// Unlike the in-memory database, several connections opened on the same file share the
+/// database. The busy timeout is disabled so a write on a locked database fails right away
+/// instead of waiting for the lock.
+pub async fn setup_file_db(path: &Path) -> anyhow::Result<DatabaseConnection> {
+    let db_url = format!("sqlite://{}?mode=rwc", path.display());
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.max_connections(1)
+        .connect_timeout(Duration::from_secs(10))
+        .sqlx_logging(false)
+        .map_sqlx_sqlite_opts(|opts| opts.foreign_keys(true).busy_timeout(Duration::ZERO));
+
+    let conn = Database::connect(opt).await?;
     Migrator::up(&conn, None).await?;
 
     Ok(conn)
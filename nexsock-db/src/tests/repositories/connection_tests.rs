@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::service::Model as Service;
+    use crate::repositories::ServiceRepository;
+    use crate::{DatabaseError, ManagedConnection};
+
+    fn service(name: &str, port: i64) -> Service {
+        Service::new(
+            name.to_string(),
+            format!("git://reconnect.com/{name}.git"),
+            port,
+            format!("/tmp/{name}"),
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_healthy_connection_stays_available() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let url = format!("sqlite://{}", dir.path().join("healthy.sqlite").display());
+        let db = ManagedConnection::connect(url, true)
+            .await
+            .expect("Failed to connect");
+
+        db.check_health().await.expect("Database should be healthy");
+        assert!(db.is_available());
+    }
+
+    #[tokio::test]
+    async fn test_connection_recovers_after_being_invalidated() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("reconnect.sqlite");
+        let db = ManagedConnection::connect(format!("sqlite://{}", path.display()), true)
+            .await
+            .expect("Failed to connect");
+        let repo = ServiceRepository::new(&db);
+
+        repo.save(&mut service("reconnect_before", 22001))
+            .await
+            .expect("Failed to save service");
+
+        // Close the pool and put a directory where the database file was, so reconnecting fails
+        db.pool()
+            .expect("Database should be available")
+            .close()
+            .await
+            .expect("Failed to close pool");
+        std::fs::remove_file(&path).expect("Failed to remove database file");
+        std::fs::create_dir(&path).expect("Failed to create directory");
+
+        assert!(matches!(
+            db.check_health().await,
+            Err(DatabaseError::Unavailable)
+        ));
+        assert!(!db.is_available());
+        assert!(matches!(db.pool(), Err(DatabaseError::Unavailable)));
+
+        let error = repo
+            .get_all()
+            .await
+            .expect_err("Queries should fail while the database is unavailable");
+        assert!(format!("{error:?}").contains("unavailable"), "{error:?}");
+
+        std::fs::remove_dir(&path).expect("Failed to remove directory");
+        db.check_health().await.expect("Database should reconnect");
+        assert!(db.is_available());
+
+        // The new database only holds the schema, and the repository created before keeps working
+        let mut after = service("reconnect_after", 22002);
+        repo.save(&mut after).await.expect("Failed to save service");
+
+        let names: Vec<_> = repo
+            .get_all()
+            .await
+            .expect("Failed to get services")
+            .into_iter()
+            .map(|service| service.name)
+            .collect();
+        assert_eq!(names, vec!["reconnect_after".to_string()]);
+    }
+}
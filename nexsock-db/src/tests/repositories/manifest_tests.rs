@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use crate::apply_manifest;
+    use crate::repositories::{
+        ServiceConfigRepository, ServiceDependencyRepository, ServiceRepository,
+    };
+    use crate::tests::common::setup_in_memory_db;
+    use nexsock_protocol::commands::config::{ConfigFormat, LogFormat};
+    use nexsock_protocol::commands::manifest::{
+        ManifestAction, ManifestConfig, ManifestDependency, ManifestService, ServiceManifest,
+    };
+
+    /// Builds a manifest with a `web` service depending on a `database` service.
+    fn test_manifest() -> ServiceManifest {
+        ServiceManifest {
+            services: vec![
+                ManifestService {
+                    name: "manifest_web".to_string(),
+                    repo_url: "git://test.com/web.git".to_string(),
+                    port: 10201,
+                    repo_path: "/tmp/manifest_web".to_string(),
+                    config: Some(ManifestConfig {
+                        filename: ".env".to_string(),
+                        format: ConfigFormat::Env,
+                        run_command: Some("cargo run".to_string()),
+                        log_format: LogFormat::Json,
+                        strip_ansi: true,
+                        capture_output: false,
+                        log_timestamp_format: Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()),
+                    }),
+                    dependencies: vec![ManifestDependency {
+                        name: "manifest_database".to_string(),
+                        tunnel_enabled: false,
+                        optional: false,
+                    }],
+                },
+                ManifestService {
+                    name: "manifest_database".to_string(),
+                    repo_url: "git://test.com/database.git".to_string(),
+                    port: 10202,
+                    repo_path: "/tmp/manifest_database".to_string(),
+                    config: None,
+                    dependencies: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    #[tokio::test]
+    /// Tests that applying a manifest creates its services and dependencies, and that applying
+    /// the same manifest again is a no-op.
+    async fn test_apply_manifest_twice_is_noop() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+        let manifest = test_manifest();
+
+        let first = apply_manifest(&db, &manifest, false)
+            .await
+            .expect("Failed to apply manifest");
+
+        assert_eq!(
+            first.actions,
+            vec![
+                ManifestAction::CreateService {
+                    name: "manifest_web".to_string()
+                },
+                ManifestAction::CreateService {
+                    name: "manifest_database".to_string()
+                },
+                ManifestAction::AddDependency {
+                    service: "manifest_web".to_string(),
+                    dependency: "manifest_database".to_string()
+                },
+            ]
+        );
+
+        let web = service_repo
+            .get_by_name("manifest_web")
+            .await
+            .expect("Failed to get web service")
+            .expect("Web service was not created");
+        assert!(web.config_id.is_some(), "Web service should have a config");
+
+        let dependencies = dep_repo
+            .get_by_service_id(web.id)
+            .await
+            .expect("Failed to get dependencies");
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].name, "manifest_database");
+
+        let second = apply_manifest(&db, &manifest, false)
+            .await
+            .expect("Failed to re-apply manifest");
+
+        assert!(
+            second.is_noop(),
+            "Re-applying the manifest should not change anything, got {:?}",
+            second.actions
+        );
+        assert_eq!(service_repo.get_all().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    /// Tests that a modified manifest updates the existing service, its config and its
+    /// dependency in place instead of creating new rows.
+    async fn test_apply_modified_manifest_updates_service() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let config_repo = ServiceConfigRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+        let mut manifest = test_manifest();
+
+        apply_manifest(&db, &manifest, false)
+            .await
+            .expect("Failed to apply manifest");
+
+        let original = service_repo
+            .get_by_name("manifest_web")
+            .await
+            .unwrap()
+            .expect("Web service was not created");
+
+        let web = &mut manifest.services[0];
+        web.port = 10301;
+        web.config.as_mut().unwrap().run_command = Some("cargo run --release".to_string());
+        web.dependencies[0].tunnel_enabled = true;
+
+        let response = apply_manifest(&db, &manifest, false)
+            .await
+            .expect("Failed to apply modified manifest");
+
+        assert_eq!(
+            response.actions,
+            vec![
+                ManifestAction::UpdateService {
+                    name: "manifest_web".to_string()
+                },
+                ManifestAction::UpdateDependency {
+                    service: "manifest_web".to_string(),
+                    dependency: "manifest_database".to_string()
+                },
+            ]
+        );
+
+        let updated = service_repo
+            .get_by_name("manifest_web")
+            .await
+            .unwrap()
+            .expect("Web service disappeared");
+        assert_eq!(updated.id, original.id, "Service should be updated in place");
+        assert_eq!(updated.port, 10301);
+        assert_eq!(updated.config_id, original.config_id);
+
+        let config = config_repo
+            .get_by_id(updated.config_id.unwrap())
+            .await
+            .unwrap()
+            .expect("Config disappeared");
+        assert_eq!(config.run_command.as_deref(), Some("cargo run --release"));
+
+        let dependencies = dep_repo.get_by_service_id(updated.id).await.unwrap();
+        assert_eq!(dependencies.len(), 1);
+        assert!(dependencies[0].tunnel_enabled);
+    }
+
+    #[tokio::test]
+    /// Tests that pruning removes services that are no longer in the manifest, while a failing
+    /// manifest leaves the database untouched.
+    async fn test_apply_manifest_prune() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let mut manifest = test_manifest();
+
+        apply_manifest(&db, &manifest, false)
+            .await
+            .expect("Failed to apply manifest");
+
+        // The web service still depends on the database, so pruning it must fail
+        let database = manifest.services.pop().unwrap();
+        assert!(apply_manifest(&db, &manifest, true).await.is_err());
+        assert_eq!(service_repo.get_all().await.unwrap().len(), 2);
+
+        manifest.services[0].dependencies.clear();
+        let response = apply_manifest(&db, &manifest, true)
+            .await
+            .expect("Failed to apply pruning manifest");
+
+        assert_eq!(
+            response.actions,
+            vec![
+                ManifestAction::RemoveDependency {
+                    service: "manifest_web".to_string(),
+                    dependency: database.name.clone()
+                },
+                ManifestAction::RemoveService {
+                    name: database.name.clone()
+                },
+            ]
+        );
+        assert!(service_repo
+            .get_by_name(&database.name)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::common::connect_in_memory_db;
+    use crate::{migration_status, rollback_migrations};
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+    async fn service_columns(db: &DatabaseConnection) -> Vec<String> {
+        db.query_all(Statement::from_string(
+            DbBackend::Sqlite,
+            "PRAGMA table_info(service)",
+        ))
+        .await
+        .expect("Failed to read the service columns")
+        .iter()
+        .map(|row| row.try_get("", "name").expect("Column without a name"))
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_status_of_partially_migrated_database() {
+        let db = connect_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        Migrator::up(&db, Some(2))
+            .await
+            .expect("Failed to apply migrations");
+
+        let status = migration_status(&db)
+            .await
+            .expect("Failed to get migration status");
+
+        let names: Vec<_> = status
+            .migrations
+            .iter()
+            .map(|migration| migration.name.as_str())
+            .collect();
+        assert_eq!(names.len(), Migrator::migrations().len());
+        assert_eq!(names[0], "m20220101_000001_create_base_service_tables");
+        assert_eq!(names[1], "m20250605_000002_add_git_columns");
+
+        let (applied, pending) = status.migrations.split_at(2);
+        assert!(applied
+            .iter()
+            .all(|migration| migration.applied_at.is_some_and(|at| at > 0)));
+        assert!(pending.iter().all(|migration| !migration.is_applied()));
+        assert_eq!(status.pending().count(), names.len() - 2);
+
+        Migrator::up(&db, None)
+            .await
+            .expect("Failed to apply migrations");
+
+        let status = migration_status(&db)
+            .await
+            .expect("Failed to get migration status");
+        assert_eq!(status.pending().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_status_of_unmigrated_database() {
+        let db = connect_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+
+        let status = migration_status(&db)
+            .await
+            .expect("Failed to get migration status");
+
+        assert_eq!(status.pending().count(), Migrator::migrations().len());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_reverts_git_columns() {
+        let db = connect_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        Migrator::up(&db, Some(2))
+            .await
+            .expect("Failed to apply migrations");
+
+        let columns = service_columns(&db).await;
+        for column in ["git_branch", "git_commit_hash", "git_auth_type"] {
+            assert!(columns.iter().any(|name| name == column), "{columns:?}");
+        }
+
+        let status = rollback_migrations(&db, 1)
+            .await
+            .expect("Failed to roll back the git columns");
+
+        assert!(status.migrations[0].is_applied());
+        assert_eq!(status.pending().count(), Migrator::migrations().len() - 1);
+
+        let columns = service_columns(&db).await;
+        assert!(
+            !columns.iter().any(|name| name.starts_with("git_")),
+            "{columns:?}"
+        );
+        assert!(columns.iter().any(|name| name == "repo_url"), "{columns:?}");
+
+        // Reapplying after the rollback brings the columns back
+        Migrator::up(&db, Some(1))
+            .await
+            .expect("Failed to reapply the git columns");
+        assert!(service_columns(&db)
+            .await
+            .iter()
+            .any(|name| name == "git_branch"));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_requires_a_step() {
+        let db = connect_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        Migrator::up(&db, None)
+            .await
+            .expect("Failed to apply migrations");
+
+        assert!(rollback_migrations(&db, 0).await.is_err());
+
+        let status = migration_status(&db)
+            .await
+            .expect("Failed to get migration status");
+        assert_eq!(status.pending().count(), 0);
+    }
+}
@@ -1,6 +1,22 @@
 #[cfg(test)]
+mod connection_tests;
+#[cfg(test)]
+mod manifest_tests;
+#[cfg(test)]
+mod migration_status_tests;
+#[cfg(test)]
+mod retry_tests;
+#[cfg(test)]
 mod service_config_tests;
 #[cfg(test)]
+mod service_config_template_tests;
+#[cfg(test)]
 mod service_dependency_tests;
 #[cfg(test)]
+mod service_label_tests;
+#[cfg(test)]
+mod service_search_tests;
+#[cfg(test)]
 mod service_tests;
+#[cfg(test)]
+mod transaction_tests;
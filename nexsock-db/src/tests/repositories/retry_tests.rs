@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::service::Model as Service;
+    use crate::repositories::ServiceRepository;
+    use crate::retry::{is_busy, retry_busy, MAX_BUSY_RETRIES};
+    use crate::tests::common::setup_file_db;
+    use crate::transaction::with_transaction;
+    use sea_orm::{
+        ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbErr, TransactionTrait,
+    };
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    fn service(name: &str, port: i64) -> Service {
+        Service::new(
+            name.to_string(),
+            format!("git://retry.com/{name}.git"),
+            port,
+            format!("/tmp/{name}"),
+            None,
+        )
+    }
+
+    /// Starts a transaction on `db` holding the write lock of the database until it ends.
+    async fn lock_database(db: &DatabaseConnection) -> DatabaseTransaction {
+        let txn = db.begin().await.expect("Failed to begin transaction");
+        ServiceRepository::new(&txn)
+            .save(&mut service("retry_lock_holder", 21001))
+            .await
+            .expect("Failed to save service holding the lock");
+
+        txn
+    }
+
+    #[tokio::test]
+    async fn test_write_is_retried_until_the_lock_is_released() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("retry.sqlite");
+        let holder = setup_file_db(&path).await.expect("Failed to setup DB");
+        let writer = setup_file_db(&path).await.expect("Failed to setup DB");
+
+        let txn = lock_database(&holder).await;
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            txn.commit().await.expect("Failed to commit transaction");
+        });
+
+        let repo = ServiceRepository::new(&writer);
+        let mut contender = service("retry_contender", 21002);
+        repo.save(&mut contender)
+            .await
+            .expect("Save should succeed once the lock is released");
+        release.await.expect("Lock holder panicked");
+
+        assert!(contender.id > 0);
+        assert!(repo
+            .get_by_name("retry_lock_holder")
+            .await
+            .expect("Failed to get service")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_write_fails_when_the_lock_is_held_too_long() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("retry.sqlite");
+        let holder = setup_file_db(&path).await.expect("Failed to setup DB");
+        let writer = setup_file_db(&path).await.expect("Failed to setup DB");
+
+        let _txn = lock_database(&holder).await;
+
+        let error = ServiceRepository::new(&writer)
+            .save(&mut service("retry_contender", 21002))
+            .await
+            .expect_err("Save should fail while the lock is held");
+        assert!(
+            error
+                .chain()
+                .any(|cause| cause.downcast_ref::<DbErr>().is_some_and(is_busy)),
+            "{error:?}"
+        );
+
+        let mut attempts = 0;
+        let result = retry_busy(&writer, || {
+            attempts += 1;
+            writer.execute_unprepared("CREATE TABLE retry_probe (id INTEGER)")
+        })
+        .await;
+
+        let error = result.expect_err("Write should fail while the lock is held");
+        assert!(is_busy(&error), "{error}");
+        assert_eq!(attempts, MAX_BUSY_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_in_a_transaction_is_not_retried() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("retry.sqlite");
+        let holder = setup_file_db(&path).await.expect("Failed to setup DB");
+        let writer = setup_file_db(&path).await.expect("Failed to setup DB");
+
+        let _lock = lock_database(&holder).await;
+        let txn = writer.begin().await.expect("Failed to begin transaction");
+
+        let mut attempts = 0;
+        let result = retry_busy(&txn, || {
+            attempts += 1;
+            txn.execute_unprepared("CREATE TABLE retry_probe (id INTEGER)")
+        })
+        .await;
+
+        let error = result.expect_err("Write should fail while the lock is held");
+        assert!(is_busy(&error), "{error}");
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_is_rerun_until_the_lock_is_released() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("retry.sqlite");
+        let holder = setup_file_db(&path).await.expect("Failed to setup DB");
+        let writer = setup_file_db(&path).await.expect("Failed to setup DB");
+
+        let txn = lock_database(&holder).await;
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            txn.commit().await.expect("Failed to commit transaction");
+        });
+
+        let attempts = AtomicU32::new(0);
+        let id = with_transaction(&writer, |txn| {
+            attempts.fetch_add(1, Ordering::Relaxed);
+
+            Box::pin(async move {
+                let mut contender = service("retry_contender", 21002);
+                ServiceRepository::new(txn).save(&mut contender).await?;
+
+                Ok(contender.id)
+            })
+        })
+        .await
+        .expect("Transaction should succeed once the lock is released");
+        release.await.expect("Lock holder panicked");
+
+        assert!(id > 0);
+        assert!(attempts.load(Ordering::Relaxed) > 1);
+    }
+
+    #[test]
+    fn test_other_errors_are_not_retried() {
+        assert!(!is_busy(&DbErr::RecordNotFound("service".to_string())));
+        assert!(!is_busy(&DbErr::Custom("database is locked".to_string())));
+    }
+}
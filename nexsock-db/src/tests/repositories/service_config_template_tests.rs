@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::service::Model as Service;
+    use crate::models::service_config::{ConfigFormat, LogFormat, Model as ServiceConfig};
+    use crate::models::service_config_template::Model as ServiceConfigTemplate;
+    use crate::repositories::{
+        ServiceConfigRepository, ServiceConfigTemplateRepository, ServiceRepository,
+    };
+    use crate::tests::common::setup_in_memory_db;
+
+    /// Saves a template shared by node services.
+    async fn save_template(repo: &ServiceConfigTemplateRepository<'_>) -> ServiceConfigTemplate {
+        let mut template = ServiceConfigTemplate::new(
+            "node".to_string(),
+            ConfigFormat::Properties,
+            Some("npm start -- --port {{port}}".to_string()),
+        );
+        template.log_format = LogFormat::Json;
+        template.strip_ansi = true;
+
+        repo.save(&mut template)
+            .await
+            .expect("Failed to save template");
+
+        template
+    }
+
+    #[tokio::test]
+    async fn test_save_and_get_template() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceConfigTemplateRepository::new(&db);
+
+        let template = save_template(&repo).await;
+        assert_ne!(template.id, 0, "Template ID should be populated after save");
+
+        let by_id = repo
+            .get_by_id(template.id)
+            .await
+            .expect("Failed to get template by ID");
+        assert_eq!(by_id.as_ref(), Some(&template));
+
+        let by_name = repo
+            .get_by_name("node")
+            .await
+            .expect("Failed to get template by name");
+        assert_eq!(by_name, Some(template));
+
+        let mut duplicate = ServiceConfigTemplate::new("node".to_string(), ConfigFormat::Env, None);
+        assert!(
+            repo.save(&mut duplicate).await.is_err(),
+            "Template names should be unique"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_effective_config_merges_template_and_overrides() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let template = save_template(&ServiceConfigTemplateRepository::new(&db)).await;
+        let config_repo = ServiceConfigRepository::new(&db);
+
+        // Only the run command is overridden, everything else comes from the template
+        let mut config = ServiceConfig::new(
+            "api.env".to_string(),
+            ConfigFormat::Env,
+            Some("node api.js".to_string()),
+        );
+        config.template_id = Some(template.id);
+        config_repo
+            .save(&mut config)
+            .await
+            .expect("Failed to save config");
+
+        let mut service = Service::new(
+            "templated_api".to_string(),
+            "git://template.com/api.git".to_string(),
+            34567,
+            "/tmp/templated_api".to_string(),
+            Some(config.id),
+        );
+        let service_repo = ServiceRepository::new(&db);
+        service_repo
+            .save(&mut service)
+            .await
+            .expect("Failed to save service");
+
+        let effective = config_repo
+            .get_effective_by_service_id(service.id)
+            .await
+            .expect("Failed to get effective config")
+            .expect("Effective config not found");
+
+        assert_eq!(effective.id, config.id);
+        assert_eq!(effective.filename, "api.env");
+        assert_eq!(effective.run_command.as_deref(), Some("node api.js"));
+        assert_eq!(effective.format, ConfigFormat::Properties);
+        assert_eq!(effective.log_format, LogFormat::Json);
+        assert!(effective.strip_ansi);
+        assert!(effective.capture_output);
+        assert_eq!(effective.template_id, Some(template.id));
+
+        // The stored config keeps only its own values
+        let stored = config_repo
+            .get_by_service_id(service.id)
+            .await
+            .expect("Failed to get config")
+            .expect("Config not found");
+        assert_eq!(stored, config);
+
+        // The detailed record used to start the service carries the effective config
+        let detailed = service_repo
+            .get_detailed_by_id(service.id)
+            .await
+            .expect("Failed to get detailed service");
+        assert_eq!(detailed.config, Some(effective));
+    }
+
+    #[tokio::test]
+    async fn test_deleting_template_detaches_configs() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let template_repo = ServiceConfigTemplateRepository::new(&db);
+        let template = save_template(&template_repo).await;
+        let config_repo = ServiceConfigRepository::new(&db);
+
+        let mut config = ServiceConfig::new("worker.env".to_string(), ConfigFormat::Env, None);
+        config.template_id = Some(template.id);
+        config_repo
+            .save(&mut config)
+            .await
+            .expect("Failed to save config");
+
+        template_repo
+            .delete_by_id(template.id)
+            .await
+            .expect("Failed to delete template");
+
+        let stored = config_repo
+            .get_by_id(config.id)
+            .await
+            .expect("Failed to get config")
+            .expect("Config should survive its template");
+        assert_eq!(stored.template_id, None);
+        assert_eq!(stored.run_command, None);
+
+        assert!(
+            template_repo.delete_by_id(template.id).await.is_err(),
+            "Deleting a missing template should fail"
+        );
+    }
+}
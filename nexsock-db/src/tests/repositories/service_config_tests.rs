@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
+    use crate::models::service::Model as Service;
     use crate::models::service_config::{ConfigFormat, Model as ServiceConfig};
-    use crate::repositories::ServiceConfigRepository;
+    use crate::repositories::{ServiceConfigRepository, ServiceRepository};
     use crate::tests::common::setup_in_memory_db;
 
     #[tokio::test]
@@ -129,4 +130,77 @@ mod tests {
             "Deleting a non-existent config should return an error"
         );
     }
+
+    #[tokio::test]
+    async fn test_get_by_service_id() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceConfigRepository::new(&db);
+        let service_repo = ServiceRepository::new(&db);
+
+        let mut other_config = ServiceConfig::new("other.env".to_string(), ConfigFormat::Env, None);
+        repo.save(&mut other_config)
+            .await
+            .expect("Failed to save other config");
+
+        let mut config = ServiceConfig::new(
+            "service.env".to_string(),
+            ConfigFormat::Env,
+            Some("cargo run".to_string()),
+        );
+        repo.save(&mut config).await.expect("Failed to save config");
+
+        let mut service = Service::new(
+            "config_lookup".to_string(),
+            "git://config.com/repo.git".to_string(),
+            23456,
+            "/tmp/config_lookup".to_string(),
+            Some(config.id),
+        );
+        service_repo
+            .save(&mut service)
+            .await
+            .expect("Failed to save service");
+
+        let fetched = repo
+            .get_by_service_id(service.id)
+            .await
+            .expect("Failed to get config by service ID")
+            .expect("Config of the service not found");
+
+        assert_eq!(fetched, config);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_service_id_without_config() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceConfigRepository::new(&db);
+
+        let mut service = Service::new(
+            "no_config".to_string(),
+            "git://config.com/none.git".to_string(),
+            23457,
+            "/tmp/no_config".to_string(),
+            None,
+        );
+        ServiceRepository::new(&db)
+            .save(&mut service)
+            .await
+            .expect("Failed to save service");
+
+        let fetched = repo
+            .get_by_service_id(service.id)
+            .await
+            .expect("Failed to get config by service ID");
+        assert!(fetched.is_none(), "A service without config has none");
+
+        let missing = repo
+            .get_by_service_id(99999)
+            .await
+            .expect("Failed to get config of a missing service");
+        assert!(missing.is_none(), "A missing service has no config");
+    }
 }
@@ -60,6 +60,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
 
         dep_repo
@@ -110,6 +111,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dependency)
@@ -166,6 +168,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dependency_to_delete)
@@ -229,6 +232,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo.save(&mut dep1).await.expect("Failed to save dep1");
 
@@ -238,6 +242,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s3.id,
             tunnel_enabled: true,
+            optional: false,
         };
         dep_repo.save(&mut dep2).await.expect("Failed to save dep2");
 
@@ -247,6 +252,7 @@ mod tests {
             service_id: s2.id,
             dependent_service_id: s3.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo.save(&mut dep3).await.expect("Failed to save dep3");
 
@@ -315,6 +321,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dep1)
@@ -326,6 +333,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s3.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dep2)
@@ -337,6 +345,7 @@ mod tests {
             service_id: s2.id,
             dependent_service_id: s3.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dep_unaffected)
@@ -397,6 +406,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dep1)
@@ -409,6 +419,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s3.id,
             tunnel_enabled: true,
+            optional: false,
         };
         dep_repo
             .save(&mut dep2)
@@ -479,6 +490,7 @@ mod tests {
             service_id: s1.id,
             dependent_service_id: s2.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dep_repo
             .save(&mut dep1)
@@ -514,4 +526,199 @@ mod tests {
             "s2 should have no dependencies in response"
         );
     }
+
+    #[tokio::test]
+    /// Tests that deleting a service other services depend on cascades to the dependency rows.
+    ///
+    /// Both the rows where the deleted service is the parent and the rows where it is the
+    /// dependent must be removed, while unrelated dependencies are left untouched.
+    async fn test_delete_service_cascades_dependencies() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+
+        let (s1, s2) = setup_services_for_test(&service_repo).await;
+        let mut s3 = Service::new(
+            "test_service_dep_3".to_string(),
+            "git://test.com/repo3.git".to_string(),
+            10003,
+            "/tmp/service3".to_string(),
+            None,
+        );
+        service_repo
+            .save(&mut s3)
+            .await
+            .expect("Failed to save service3");
+
+        // s1 and s3 both depend on s2, and s2 depends on s3
+        let mut s1_on_s2 = ServiceDependency {
+            id: 0,
+            service_id: s1.id,
+            dependent_service_id: s2.id,
+            tunnel_enabled: false,
+            optional: false,
+        };
+        let mut s3_on_s2 = ServiceDependency {
+            id: 0,
+            service_id: s3.id,
+            dependent_service_id: s2.id,
+            tunnel_enabled: false,
+            optional: false,
+        };
+        let mut s2_on_s3 = ServiceDependency {
+            id: 0,
+            service_id: s2.id,
+            dependent_service_id: s3.id,
+            tunnel_enabled: false,
+            optional: false,
+        };
+        let mut s1_on_s3 = ServiceDependency {
+            id: 0,
+            service_id: s1.id,
+            dependent_service_id: s3.id,
+            tunnel_enabled: true,
+            optional: false,
+        };
+        for dependency in [&mut s1_on_s2, &mut s3_on_s2, &mut s2_on_s3, &mut s1_on_s3] {
+            dep_repo
+                .save(dependency)
+                .await
+                .expect("Failed to save dependency");
+        }
+
+        service_repo
+            .delete_by_id(s2.id)
+            .await
+            .expect("Failed to delete service s2");
+
+        for removed in [&s1_on_s2, &s3_on_s2, &s2_on_s3] {
+            assert!(
+                dep_repo
+                    .get_by_id(removed.id)
+                    .await
+                    .expect("Failed to get dependency by ID")
+                    .is_none(),
+                "Dependency {} referencing the deleted service should be cascaded",
+                removed.id
+            );
+        }
+
+        assert!(
+            dep_repo
+                .get_by_id(s1_on_s3.id)
+                .await
+                .expect("Failed to get dependency by ID")
+                .is_some(),
+            "Unrelated dependency should not be deleted"
+        );
+    }
+
+    #[tokio::test]
+    /// Tests the reverse dependency lookup.
+    ///
+    /// With s1 depending on s2, the dependents of s2 must contain s1 along with s1's service
+    /// details, while s1 itself has no dependents.
+    async fn test_list_dependents() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+
+        let (s1, s2) = setup_services_for_test(&service_repo).await;
+
+        // s1 -> s2
+        let mut dep = ServiceDependency {
+            id: 0,
+            service_id: s1.id,
+            dependent_service_id: s2.id,
+            tunnel_enabled: true,
+            optional: false,
+        };
+        dep_repo
+            .save(&mut dep)
+            .await
+            .expect("Failed to save dependency");
+
+        let dependents = dep_repo
+            .list_dependents(s2.id)
+            .await
+            .expect("Failed to list dependents for s2");
+
+        assert_eq!(dependents.len(), 1, "s2 should have 1 dependent");
+        let dependent = dependents.first().unwrap();
+        assert_eq!(dependent.id, dep.id);
+        assert_eq!(dependent.service_id, s1.id);
+        assert_eq!(dependent.name, s1.name);
+        assert_eq!(dependent.repo_url, s1.repo_url);
+        assert_eq!(dependent.port, s1.port);
+
+        let response = dep_repo
+            .get_dependents_response(s2.id, s2.name.clone())
+            .await
+            .expect("Failed to get dependents response for s2");
+
+        assert_eq!(response.service_name, s2.name);
+        assert_eq!(response.dependents.len(), 1);
+        let info = response.dependents.first().unwrap();
+        assert_eq!(info.id, s1.id);
+        assert_eq!(info.name, s1.name);
+        assert!(info.tunnel_enabled);
+
+        let s1_dependents = dep_repo
+            .list_dependents(s1.id)
+            .await
+            .expect("Failed to list dependents for s1");
+        assert!(s1_dependents.is_empty(), "s1 should have no dependents");
+    }
+
+    #[tokio::test]
+    /// Tests fetching a single dependency by both of its services.
+    ///
+    /// The dependency of s1 on s2 must carry its flags and s2's service details, while the
+    /// reverse direction doesn't exist.
+    async fn test_get_dependency() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+
+        let (s1, s2) = setup_services_for_test(&service_repo).await;
+
+        // s1 -> s2
+        let mut dep = ServiceDependency {
+            id: 0,
+            service_id: s1.id,
+            dependent_service_id: s2.id,
+            tunnel_enabled: true,
+            optional: true,
+        };
+        dep_repo
+            .save(&mut dep)
+            .await
+            .expect("Failed to save dependency");
+
+        let dependency = dep_repo
+            .get_dependency(s1.id, s2.id)
+            .await
+            .expect("Failed to get dependency of s1 on s2")
+            .expect("s1 should depend on s2");
+
+        assert_eq!(dependency.id, dep.id);
+        assert_eq!(dependency.service_id, s1.id);
+        assert_eq!(dependency.dependent_service_id, s2.id);
+        assert!(dependency.tunnel_enabled);
+        assert!(dependency.optional);
+        assert_eq!(dependency.name, s2.name);
+        assert_eq!(dependency.port, s2.port);
+
+        let reverse = dep_repo
+            .get_dependency(s2.id, s1.id)
+            .await
+            .expect("Failed to get dependency of s2 on s1");
+        assert!(reverse.is_none(), "s2 should not depend on s1");
+    }
 }
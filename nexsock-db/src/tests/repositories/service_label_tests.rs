@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::service::Model as Service;
+    use crate::repositories::{ServiceLabelRepository, ServiceRepository};
+    use crate::tests::common::setup_in_memory_db;
+    use nexsock_protocol::commands::manage_service::ServiceRef;
+    use std::collections::BTreeMap;
+
+    /// Builds a label map from `key=value` pairs.
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Saves a service named `name` and returns its id.
+    async fn save_service(repo: &ServiceRepository<'_>, name: &str, port: i64) -> i64 {
+        let mut service = Service::new(
+            name.to_string(),
+            format!("git://labels.com/{name}.git"),
+            port,
+            format!("/tmp/{name}"),
+            None,
+        );
+        repo.save(&mut service)
+            .await
+            .expect("Failed to save service for label test");
+
+        service.id
+    }
+
+    #[tokio::test]
+    /// Tests that setting labels replaces the previous ones and that they are reported in the
+    /// service status and list.
+    async fn test_set_and_get_labels() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+        let label_repo = ServiceLabelRepository::new(&db);
+
+        let id = save_service(&repo, "label_api", 12001).await;
+        assert!(label_repo
+            .get_labels(id)
+            .await
+            .expect("Failed to get labels")
+            .is_empty());
+
+        let initial = labels(&[("team", "payments"), ("env", "staging"), ("owner", "ana")]);
+        label_repo
+            .set_labels(id, &initial)
+            .await
+            .expect("Failed to set labels");
+        let stored = label_repo
+            .get_labels(id)
+            .await
+            .expect("Failed to get labels");
+        assert_eq!(stored, initial);
+
+        let replaced = labels(&[("team", "billing"), ("env", "staging")]);
+        label_repo
+            .set_labels(id, &replaced)
+            .await
+            .expect("Failed to replace labels");
+        let stored = label_repo
+            .get_labels(id)
+            .await
+            .expect("Failed to get labels");
+        assert_eq!(stored, replaced);
+
+        let status = repo
+            .get_status(&ServiceRef::Id(id))
+            .await
+            .expect("Failed to get service status");
+        assert_eq!(status.labels, replaced);
+
+        let listed = repo
+            .get_all_with_dependencies()
+            .await
+            .expect("Failed to list services");
+        assert_eq!(listed.services[0].labels, replaced);
+
+        assert!(label_repo
+            .set_labels(id, &labels(&[("", "empty")]))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    /// Tests that a label selector only matches the services carrying every one of its labels.
+    async fn test_find_by_labels() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+        let label_repo = ServiceLabelRepository::new(&db);
+
+        for (name, port, service_labels) in [
+            ("label_api", 13001, labels(&[("team", "payments"), ("env", "prod")])),
+            ("label_web", 13002, labels(&[("team", "frontend"), ("env", "prod")])),
+            ("label_worker", 13003, labels(&[("team", "payments"), ("env", "staging")])),
+            ("label_none", 13004, BTreeMap::new()),
+        ] {
+            let id = save_service(&repo, name, port).await;
+            label_repo
+                .set_labels(id, &service_labels)
+                .await
+                .expect("Failed to set labels");
+        }
+
+        let names_of = |services: Vec<Service>| {
+            services
+                .into_iter()
+                .map(|service| service.name)
+                .collect::<Vec<_>>()
+        };
+
+        let payments = repo
+            .find_by_labels(&labels(&[("team", "payments")]))
+            .await
+            .expect("Failed to find services by labels");
+        assert_eq!(names_of(payments), ["label_api", "label_worker"]);
+
+        let payments_prod = repo
+            .find_by_labels(&labels(&[("team", "payments"), ("env", "prod")]))
+            .await
+            .expect("Failed to find services by labels");
+        assert_eq!(names_of(payments_prod), ["label_api"]);
+
+        let unknown = repo
+            .find_by_labels(&labels(&[("team", "unknown")]))
+            .await
+            .expect("Failed to find services by labels");
+        assert!(unknown.is_empty());
+
+        let all = repo
+            .find_by_labels(&BTreeMap::new())
+            .await
+            .expect("Failed to find services by labels");
+        assert_eq!(all.len(), 4);
+
+        let listed = repo
+            .get_labeled_with_dependencies(&labels(&[("env", "prod")]))
+            .await
+            .expect("Failed to list services by labels");
+        let names = listed
+            .services
+            .iter()
+            .map(|service| service.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["label_api", "label_web"]);
+        assert_eq!(listed.services[1].labels["team"], "frontend");
+    }
+
+    #[tokio::test]
+    /// Tests that the labels of a service are removed together with it.
+    async fn test_labels_are_deleted_with_service() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+        let label_repo = ServiceLabelRepository::new(&db);
+
+        let id = save_service(&repo, "label_removed", 14001).await;
+        label_repo
+            .set_labels(id, &labels(&[("team", "payments")]))
+            .await
+            .expect("Failed to set labels");
+
+        repo.delete_by_id(id).await.expect("Failed to delete service");
+
+        assert!(label_repo
+            .get_labels(id)
+            .await
+            .expect("Failed to get labels")
+            .is_empty());
+    }
+}
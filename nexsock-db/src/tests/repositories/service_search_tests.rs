@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::service::Model as Service;
+    use crate::repositories::{ServiceLabelRepository, ServiceRepository};
+    use crate::tests::common::setup_in_memory_db;
+    use std::collections::BTreeMap;
+
+    /// Saves a service with the given repository URL and labels and returns its id.
+    async fn save_service(
+        repo: &ServiceRepository<'_>,
+        label_repo: &ServiceLabelRepository<'_>,
+        name: &str,
+        repo_url: &str,
+        port: i64,
+        labels: &[(&str, &str)],
+    ) -> i64 {
+        let mut service = Service::new(
+            name.to_string(),
+            repo_url.to_string(),
+            port,
+            format!("/tmp/{name}"),
+            None,
+        );
+        repo.save(&mut service)
+            .await
+            .expect("Failed to save service for search test");
+
+        let labels = labels
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        label_repo
+            .set_labels(service.id, &labels)
+            .await
+            .expect("Failed to set labels for search test");
+
+        service.id
+    }
+
+    /// Returns the names of the services found for `query`.
+    async fn searched_names(repo: &ServiceRepository<'_>, query: &str) -> Vec<String> {
+        repo.search(query)
+            .await
+            .expect("Failed to search services")
+            .into_iter()
+            .map(|service| service.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    /// Tests that the search matches each searchable field case-insensitively.
+    async fn test_search_matches_each_field() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+        let label_repo = ServiceLabelRepository::new(&db);
+
+        save_service(
+            &repo,
+            &label_repo,
+            "Billing_API",
+            "git://search.com/billing.git",
+            15001,
+            &[],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "frontend",
+            "git://search.com/WebShop.git",
+            15002,
+            &[],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "worker",
+            "git://search.com/worker.git",
+            15003,
+            &[("Team", "payments")],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "mailer",
+            "git://search.com/mailer.git",
+            15004,
+            &[("env", "STAGING")],
+        )
+        .await;
+
+        assert_eq!(searched_names(&repo, "billing_api").await, ["Billing_API"]);
+        assert_eq!(searched_names(&repo, "webshop").await, ["frontend"]);
+        assert_eq!(searched_names(&repo, "team").await, ["worker"]);
+        assert_eq!(searched_names(&repo, "staging").await, ["mailer"]);
+        assert!(searched_names(&repo, "unknown").await.is_empty());
+
+        // Wildcards in the query match themselves
+        assert_eq!(searched_names(&repo, "_").await, ["Billing_API"]);
+        assert!(searched_names(&repo, "%").await.is_empty());
+    }
+
+    #[tokio::test]
+    /// Tests that name matches rank before repository URL matches, which rank before label
+    /// matches, and that services keep the order they were added in within a group.
+    async fn test_search_ranks_by_field_priority() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+        let label_repo = ServiceLabelRepository::new(&db);
+
+        save_service(
+            &repo,
+            &label_repo,
+            "label_match",
+            "git://search.com/a.git",
+            16001,
+            &[("app", "orders")],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "url_match",
+            "git://search.com/orders.git",
+            16002,
+            &[],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "orders_api",
+            "git://search.com/b.git",
+            16003,
+            &[],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "orders_web",
+            "git://search.com/orders-web.git",
+            16004,
+            &[("app", "orders")],
+        )
+        .await;
+        save_service(
+            &repo,
+            &label_repo,
+            "unrelated",
+            "git://search.com/c.git",
+            16005,
+            &[],
+        )
+        .await;
+
+        assert_eq!(
+            searched_names(&repo, "ORDERS").await,
+            ["orders_api", "orders_web", "url_match", "label_match"]
+        );
+
+        let listed = repo
+            .search_with_dependencies("orders")
+            .await
+            .expect("Failed to search services");
+        assert_eq!(listed.services.len(), 4);
+        assert_eq!(listed.services[3].labels["app"], "orders");
+    }
+}
@@ -3,6 +3,7 @@ mod tests {
     use crate::models::service::{Model as Service, ServiceStatus};
     use crate::repositories::ServiceRepository;
     use crate::tests::common::setup_in_memory_db;
+    use crate::DatabaseError;
     use nexsock_protocol::commands::manage_service::ServiceRef;
 
     #[tokio::test]
@@ -131,6 +132,82 @@ mod tests {
         assert_eq!(fetched_service.status, ServiceStatus::Running);
     }
 
+    #[tokio::test]
+    /// Tests that two readers updating the same service can't overwrite each other.
+    ///
+    /// Both readers fetch the service at the same version, the first update succeeds and bumps the
+    /// version, and the second update is rejected with a version conflict instead of clobbering
+    /// the first one.
+    async fn test_concurrent_update_conflicts() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+
+        let mut service = Service::new(
+            "conflict_test".to_string(),
+            "git://conflict.com/repo.git".to_string(),
+            43210,
+            "/tmp/conflict_test".to_string(),
+            None,
+        );
+        repo.save(&mut service)
+            .await
+            .expect("Failed to save initial service");
+        assert_eq!(service.version, 0);
+
+        let mut first = repo.get_by_id(service.id).await.unwrap().unwrap();
+        let mut second = first.clone();
+
+        first.port = 43211;
+        repo.save(&mut first)
+            .await
+            .expect("First update should succeed");
+        assert_eq!(first.version, 1);
+
+        second.port = 43212;
+        let error = repo
+            .save(&mut second)
+            .await
+            .expect_err("Second update should conflict");
+
+        match error.downcast_ref::<DatabaseError>() {
+            Some(DatabaseError::VersionConflict {
+                id,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(*id, service.id);
+                assert_eq!(*expected, 0);
+                assert_eq!(*actual, 1);
+            }
+            other => panic!("Expected a version conflict, got {other:?}"),
+        }
+        assert_eq!(second.version, 0, "A failed update keeps the version");
+
+        let stored = repo.get_by_id(service.id).await.unwrap().unwrap();
+        assert_eq!(stored.port, 43211, "The first update must not be clobbered");
+        assert_eq!(stored.version, 1);
+
+        // Re-reading picks up the new version, after which updating works again
+        let mut reread = stored;
+        reread.port = 43212;
+        repo.save(&mut reread)
+            .await
+            .expect("Update after re-reading should succeed");
+        assert_eq!(reread.version, 2);
+
+        // Targeted updates bump the version as well
+        repo.update_git_branch(service.id, Some("main".to_string()))
+            .await
+            .expect("Failed to update git branch");
+        let error = repo.save(&mut reread).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<DatabaseError>(),
+            Some(DatabaseError::VersionConflict { actual: 3, .. })
+        ));
+    }
+
     #[tokio::test]
     /// Tests deleting a service by ID and verifies correct error handling for non-existent IDs.
     ///
@@ -461,4 +538,53 @@ mod tests {
             "Extracting ID from non-existent name should return an error"
         );
     }
+
+    #[tokio::test]
+    /// Tests that only the services tagged with a profile are found by it, in the order they were
+    /// added.
+    async fn test_find_by_profile() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let repo = ServiceRepository::new(&db);
+
+        for (name, port, profile) in [
+            ("profile_api", 11111, Some("backend")),
+            ("profile_web", 22222, Some("frontend")),
+            ("profile_worker", 33333, Some("backend")),
+            ("profile_none", 44444, None),
+        ] {
+            let mut service = Service::new(
+                name.to_string(),
+                format!("git://profile.com/{name}.git"),
+                port,
+                format!("/tmp/{name}"),
+                None,
+            );
+            service.profile = profile.map(str::to_string);
+            repo.save(&mut service)
+                .await
+                .expect("Failed to save service for profile test");
+        }
+
+        let backend = repo
+            .find_by_profile("backend")
+            .await
+            .expect("Failed to find services by profile");
+        let names = backend.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["profile_api", "profile_worker"]);
+
+        let listed = repo
+            .get_profile_with_dependencies("frontend")
+            .await
+            .expect("Failed to list services of profile");
+        assert_eq!(listed.services.len(), 1);
+        assert_eq!(listed.services[0].name, "profile_web");
+
+        let unknown = repo
+            .find_by_profile("unknown")
+            .await
+            .expect("Failed to find services by profile");
+        assert!(unknown.is_empty());
+    }
 }
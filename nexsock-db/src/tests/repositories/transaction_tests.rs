@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::service::Model as Service;
+    use crate::models::service_dependency::Model as ServiceDependency;
+    use crate::repositories::{ServiceDependencyRepository, ServiceRepository};
+    use crate::tests::common::setup_in_memory_db;
+    use crate::with_transaction;
+    use anyhow::anyhow;
+
+    /// Saves two services where the first depends on the second and returns them with the dependency.
+    async fn setup_dependent_services(
+        repo: &ServiceRepository<'_>,
+        dep_repo: &ServiceDependencyRepository<'_>,
+    ) -> (Service, Service, ServiceDependency) {
+        let mut service1 = Service::new(
+            "test_txn_service_1".to_string(),
+            "git://test.com/txn1.git".to_string(),
+            10101,
+            "/tmp/txn1".to_string(),
+            None,
+        );
+        repo.save(&mut service1)
+            .await
+            .expect("Failed to save service1");
+
+        let mut service2 = Service::new(
+            "test_txn_service_2".to_string(),
+            "git://test.com/txn2.git".to_string(),
+            10102,
+            "/tmp/txn2".to_string(),
+            None,
+        );
+        repo.save(&mut service2)
+            .await
+            .expect("Failed to save service2");
+
+        let mut dependency = ServiceDependency {
+            id: 0,
+            service_id: service1.id,
+            dependent_service_id: service2.id,
+            tunnel_enabled: false,
+            optional: false,
+        };
+        dep_repo
+            .save(&mut dependency)
+            .await
+            .expect("Failed to save dependency");
+
+        (service1, service2, dependency)
+    }
+
+    #[tokio::test]
+    /// Tests that a failure after the dependency delete rolls back the whole transaction,
+    /// leaving both the dependency and the service rows in place.
+    async fn test_with_transaction_rolls_back_on_error() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+
+        let (service, _, dependency) = setup_dependent_services(&service_repo, &dep_repo).await;
+        let service_id = service.id;
+        let dependency_id = dependency.id;
+
+        let result: anyhow::Result<()> = with_transaction(&db, move |txn| {
+            Box::pin(async move {
+                ServiceDependencyRepository::new(txn)
+                    .delete_many(vec![dependency_id])
+                    .await?;
+
+                Err(anyhow!("forced failure before deleting service `{service_id}`"))
+            })
+        })
+        .await;
+
+        assert!(result.is_err(), "Transaction should report the forced failure");
+
+        let fetched_service = service_repo
+            .get_by_id(service_id)
+            .await
+            .expect("Failed to get service by ID");
+        assert!(
+            fetched_service.is_some(),
+            "Service should not be deleted after rollback"
+        );
+
+        let fetched_dependency = dep_repo
+            .get_by_id(dependency_id)
+            .await
+            .expect("Failed to get dependency by ID");
+        assert!(
+            fetched_dependency.is_some(),
+            "Dependency delete should be rolled back"
+        );
+    }
+
+    #[tokio::test]
+    /// Tests that all writes made inside a successful transaction are committed.
+    async fn test_with_transaction_commits_on_success() {
+        let db = setup_in_memory_db()
+            .await
+            .expect("Failed to setup in-memory DB");
+        let service_repo = ServiceRepository::new(&db);
+        let dep_repo = ServiceDependencyRepository::new(&db);
+
+        let (service, _, dependency) = setup_dependent_services(&service_repo, &dep_repo).await;
+        let service_id = service.id;
+        let dependency_id = dependency.id;
+
+        with_transaction(&db, move |txn| {
+            Box::pin(async move {
+                ServiceDependencyRepository::new(txn)
+                    .delete_many(vec![dependency_id])
+                    .await?;
+                ServiceRepository::new(txn).delete_by_id(service_id).await
+            })
+        })
+        .await
+        .expect("Transaction should commit");
+
+        assert!(service_repo
+            .get_by_id(service_id)
+            .await
+            .expect("Failed to get service by ID")
+            .is_none());
+        assert!(dep_repo
+            .get_by_id(dependency_id)
+            .await
+            .expect("Failed to get dependency by ID")
+            .is_none());
+    }
+}
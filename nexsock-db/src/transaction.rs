@@ -0,0 +1,84 @@
+//! Helpers for running several repository operations atomically.
+
+use crate::retry::{is_busy, retry_while, RetryBoundary};
+use anyhow::Context;
+use sea_orm::{DatabaseTransaction, DbErr, TransactionTrait};
+use std::future::Future;
+use std::pin::Pin;
+use tracing::{debug, error};
+
+/// The future returned by the closure passed to [`with_transaction`].
+pub type TransactionFuture<'c, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'c>>;
+
+/// Runs `f` inside a database transaction.
+///
+/// The transaction is committed if `f` succeeds and rolled back if it returns an error, so either
+/// every write made through the provided [`DatabaseTransaction`] is persisted or none of them are.
+/// Repositories can be pointed at the transaction with their `new` constructor.
+///
+/// When `db` is a top-level connection, a transaction failing because the database is locked is
+/// rolled back and run again with another call of `f`, see [`RetryBoundary`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use nexsock_db::prelude::*;
+///
+/// with_transaction(get_db_connection(), |txn| {
+///     Box::pin(async move {
+///         ServiceDependencyRepository::new(txn).delete_many(dependency_ids.clone()).await?;
+///         ServiceRepository::new(txn).delete_by_id(service_id).await?;
+///         Ok(())
+///     })
+/// })
+/// .await?;
+/// ```
+pub async fn with_transaction<C, F, T>(db: &C, f: F) -> anyhow::Result<T>
+where
+    C: TransactionTrait + RetryBoundary,
+    F: for<'c> Fn(&'c DatabaseTransaction) -> TransactionFuture<'c, T>,
+{
+    if !C::IS_TOP_LEVEL {
+        return run_transaction(db, &f).await;
+    }
+
+    retry_while(
+        || run_transaction(db, &f),
+        |e: &anyhow::Error| {
+            e.chain()
+                .any(|cause| cause.downcast_ref::<DbErr>().is_some_and(is_busy))
+        },
+    )
+    .await
+}
+
+/// Runs `f` inside a single database transaction, see [`with_transaction`].
+async fn run_transaction<C, F, T>(db: &C, f: &F) -> anyhow::Result<T>
+where
+    C: TransactionTrait,
+    F: for<'c> Fn(&'c DatabaseTransaction) -> TransactionFuture<'c, T>,
+{
+    let txn = db
+        .begin()
+        .await
+        .context("Database error: Failed to begin transaction")?;
+
+    match f(&txn).await {
+        Ok(value) => {
+            txn.commit()
+                .await
+                .context("Database error: Failed to commit transaction")?;
+
+            Ok(value)
+        }
+        Err(e) => {
+            debug!(error = ?e, "Rolling back transaction");
+
+            if let Err(rollback_error) = txn.rollback().await {
+                error!(error = ?rollback_error, "Failed to roll back transaction");
+            }
+
+            Err(e)
+        }
+    }
+}
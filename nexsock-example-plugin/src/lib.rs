@@ -25,3 +25,4 @@ impl PreHook for ExamplePluginPreReader {
 }
 
 savefile_abi_export!(ExamplePluginPreReader, PreHook);
+nexsock_abi::export_abi_version!();
@@ -2,17 +2,61 @@ use crate::lua::{ScriptContext, SerializableLuaValue};
 use crate::{PluginResult, PLUGINS_DIR};
 use anyhow::{anyhow, Context};
 use derive_more::{AsMut, AsRef};
-use mlua::{Function, Lua, Value};
+use mlua::{Function, HookTriggers, Lua, Table, Value, VmState};
+use nexsock_config::PluginsConfig;
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Globals of the standard library available to sandboxed plugins. The rest, such as `io`,
+/// `require`, `load` and `debug`, is left out.
+const SANDBOXED_GLOBALS: [&str; 25] = [
+    "_VERSION",
+    "assert",
+    "error",
+    "getmetatable",
+    "ipairs",
+    "next",
+    "pairs",
+    "pcall",
+    "print",
+    "rawequal",
+    "rawget",
+    "rawlen",
+    "rawset",
+    "select",
+    "setmetatable",
+    "tonumber",
+    "tostring",
+    "type",
+    "xpcall",
+    "coroutine",
+    "math",
+    "string",
+    "table",
+    "utf8",
+    "shared",
+];
+
+/// Functions of `os` available to sandboxed plugins, the ones that only read the clock.
+const SANDBOXED_OS_FUNCTIONS: [&str; 4] = ["clock", "date", "difftime", "time"];
+
+/// Instructions a plugin runs between two checks of the call timeout.
+const TIMEOUT_CHECK_INSTRUCTIONS: u32 = 1000;
 
 /// Thread-safe Lua plugin manager
 #[derive(Debug, AsRef, AsMut)]
 pub struct LuaPluginManager {
-    lua: Mutex<Lua>,
-    plugins: Mutex<HashMap<PathBuf, ScriptContext>>,
+    /// Every script runs on a Lua state of its own, so calls into different scripts don't wait
+    /// on each other.
+    plugins: Mutex<HashMap<PathBuf, Arc<Mutex<ScriptContext>>>>,
     pub discovered: Mutex<HashMap<String, PathBuf>>,
+    /// The error of every script that failed to load, keyed by its path.
+    failed: Mutex<HashMap<PathBuf, String>>,
+    config: PluginsConfig,
 }
 
 // Implement Send and Sync explicitly to document thread-safety
@@ -20,14 +64,19 @@ unsafe impl Send for LuaPluginManager {}
 unsafe impl Sync for LuaPluginManager {}
 
 impl LuaPluginManager {
+    /// Creates a manager running every plugin with the full standard library.
     pub fn new() -> PluginResult<Self> {
-        let lua = Lua::new();
-        Self::setup_shared_environment(&lua)?;
+        Self::with_config(PluginsConfig::default())
+    }
 
+    /// Creates a manager that runs the plugins not trusted by `config` in a sandbox when
+    /// [`PluginsConfig::sandbox`] is enabled.
+    pub fn with_config(config: PluginsConfig) -> PluginResult<Self> {
         Ok(Self {
-            lua: Mutex::new(lua),
             plugins: Mutex::new(HashMap::new()),
             discovered: Mutex::new(HashMap::new()),
+            failed: Mutex::new(HashMap::new()),
+            config,
         })
     }
 
@@ -52,16 +101,81 @@ impl LuaPluginManager {
         Ok(())
     }
 
+    /// Builds the globals a sandboxed plugin falls back to, a copy of [`SANDBOXED_GLOBALS`] and an
+    /// `os` table with only [`SANDBOXED_OS_FUNCTIONS`].
+    fn sandboxed_globals(lua: &Lua) -> PluginResult<Table> {
+        let globals = lua.globals();
+        let sandbox = lua.create_table()?;
+
+        for name in SANDBOXED_GLOBALS {
+            sandbox.set(name, globals.get::<Value>(name)?)?;
+        }
+
+        let os: Table = globals.get("os")?;
+        let sandboxed_os = lua.create_table()?;
+        for name in SANDBOXED_OS_FUNCTIONS {
+            sandboxed_os.set(name, os.get::<Value>(name)?)?;
+        }
+        sandbox.set("os", sandboxed_os)?;
+
+        Ok(sandbox)
+    }
+
+    /// Whether the plugin at `path` runs in the sandbox, it does unless the sandbox is disabled or
+    /// its file name is listed in [`PluginsConfig::trusted`].
+    fn is_sandboxed(&self, path: &Path) -> bool {
+        let trusted = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| self.config.trusted.iter().any(|trusted| trusted == name));
+
+        self.config.sandbox && !trusted
+    }
+
+    /// Runs `f`, aborting the Lua code it runs on `lua` with an error once it takes longer than
+    /// [`PluginsConfig::call_timeout_ms`].
+    fn with_timeout<T>(&self, lua: &Lua, f: impl FnOnce() -> mlua::Result<T>) -> mlua::Result<T> {
+        if self.config.call_timeout_ms == 0 {
+            return f();
+        }
+
+        let timeout = Duration::from_millis(self.config.call_timeout_ms);
+        let deadline = Instant::now() + timeout;
+        let triggers = HookTriggers::new().every_nth_instruction(TIMEOUT_CHECK_INSTRUCTIONS);
+
+        lua.set_hook(triggers, move |_, _| {
+            if Instant::now() < deadline {
+                Ok(VmState::Continue)
+            } else {
+                Err(mlua::Error::runtime(format!(
+                    "Plugin call timed out after {} ms",
+                    timeout.as_millis()
+                )))
+            }
+        });
+
+        let result = f();
+        lua.remove_hook();
+
+        result
+    }
+
     pub fn discover_plugins(&self) -> PluginResult<HashMap<String, PathBuf>> {
+        Self::discover_plugins_in(&PLUGINS_DIR.join("lua"))
+    }
+
+    fn discover_plugins_in(dir: &Path) -> PluginResult<HashMap<String, PathBuf>> {
         let mut plugins = HashMap::new();
 
-        for entry in PLUGINS_DIR
-            .join("lua")
-            .read_dir()
-            .context("Failed to read directory")?
-        {
+        for entry in dir.read_dir().context("Failed to read directory")? {
             let entry = entry?;
             let path = entry.path();
+
+            // Skips the config files of the plugins, see `load_config`
+            if path.extension().and_then(|extension| extension.to_str()) != Some("lua") {
+                continue;
+            }
+
             let name = entry
                 .file_name()
                 .into_string()
@@ -74,78 +188,200 @@ impl LuaPluginManager {
         Ok(plugins)
     }
 
+    /// Loads every script in the Lua plugins directory.
+    ///
+    /// See [`load_plugins_from`](Self::load_plugins_from).
     pub async fn load_plugins(&self) -> PluginResult<()> {
-        let plugins = self.discover_plugins()?;
+        self.load_plugins_from(&PLUGINS_DIR.join("lua")).await
+    }
 
-        for (_, path) in plugins.iter() {
-            self.load_script(path)?;
-        }
+    /// Loads every script in `dir`.
+    ///
+    /// A script that fails to load is logged and skipped, so one broken plugin doesn't keep the
+    /// others from loading. Its error is kept and returned by [`load_errors`](Self::load_errors).
+    /// Only fails if `dir` can't be read.
+    pub async fn load_plugins_from(&self, dir: &Path) -> PluginResult<()> {
+        let mut plugins = Self::discover_plugins_in(dir)?;
+        let mut failed = HashMap::new();
+
+        plugins.retain(|name, path| match self.load_script(&*path) {
+            Ok(_) => true,
+            Err(error) => {
+                warn!(plugin = %name, error = %error, "Failed to load Lua plugin, skipping it");
+                failed.insert(path.clone(), error.to_string());
+                false
+            }
+        });
 
         *self.discovered.lock() = plugins;
+        *self.failed.lock() = failed;
         Ok(())
     }
 
+    /// Reads the config of the plugin at `path` from the file next to it with a `.toml`
+    /// extension, e.g. `deploy.toml` for `deploy.lua`. A plugin without a config file gets an
+    /// empty table.
+    fn load_config(path: &Path) -> PluginResult<SerializableLuaValue> {
+        let config_path = path.with_extension("toml");
+        if !config_path.exists() {
+            return Ok(SerializableLuaValue::Table(Vec::new()));
+        }
+
+        let contents = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read '{}'", config_path.display()))?;
+        let config = contents
+            .parse::<toml::Table>()
+            .with_context(|| format!("Failed to parse '{}'", config_path.display()))?;
+
+        Ok(toml::Value::Table(config).into())
+    }
+
+    /// Loads and evaluates the script at `path`.
+    ///
+    /// The script runs in its own environment with a `nexsock.config` table holding its config,
+    /// see [`load_config`](Self::load_config).
     pub fn load_script(&self, path: impl AsRef<Path>) -> PluginResult<PathBuf> {
-        let lua = self.lua.lock();
+        let config = Self::load_config(path.as_ref())?;
+
+        let lua = Lua::new();
+        Self::setup_shared_environment(&lua)?;
 
         // Create a new environment table
         let environment = lua.create_table()?;
 
-        // Set up environment metatable to fall back to global env
-        let globals = lua.globals();
+        // Set up environment metatable to fall back to global env, or its sandboxed copy
+        let globals = if self.is_sandboxed(path.as_ref()) {
+            Self::sandboxed_globals(&lua)?
+        } else {
+            lua.globals()
+        };
         let metatable = lua.create_table()?;
         metatable.set("__index", globals)?;
         environment.set_metatable(Some(metatable));
 
+        let nexsock = lua.create_table()?;
+        nexsock.set("config", config.to_lua_value(&lua)?)?;
+        environment.set("nexsock", nexsock)?;
+
         // Load and evaluate the script with the custom environment
-        let chunk = lua.load(path.as_ref()).set_name("Plugin");
-        chunk.set_environment(environment.clone()).eval::<()>()?;
+        let chunk = lua
+            .load(path.as_ref())
+            .set_name("Plugin")
+            .set_environment(environment.clone());
+        self.with_timeout(&lua, || chunk.eval::<()>())?;
 
         let path = path.as_ref().to_owned();
-        self.plugins
-            .lock()
-            .insert(path.clone(), ScriptContext { environment });
+        self.plugins.lock().insert(
+            path.clone(),
+            Arc::new(Mutex::new(ScriptContext { lua, environment })),
+        );
 
         Ok(path)
     }
 
+    /// Returns the loaded script at `script_path`.
+    fn script(&self, script_path: &Path) -> PluginResult<Arc<Mutex<ScriptContext>>> {
+        self.plugins
+            .lock()
+            .get(script_path)
+            .cloned()
+            .ok_or_else(|| anyhow!("Script not found: {}", script_path.display()))
+    }
+
     pub fn call_function(
         &self,
         script_path: &Path,
         fn_name: &str,
         args: Vec<SerializableLuaValue>,
     ) -> PluginResult<SerializableLuaValue> {
-        let lua = self.lua.lock();
-        let plugins = self.plugins.lock();
-
-        let context = plugins
-            .get(script_path)
-            .ok_or_else(|| anyhow!("Script not found: {}", script_path.display()))?;
+        let script = self.script(script_path)?;
+        let context = script.lock();
 
         let func: Function = context.environment.get(fn_name)?;
-        let args = SerializableLuaValue::into_args(args, &lua)?;
-        let result: Value = func.call(args)?;
+        let args = SerializableLuaValue::into_args(args, &context.lua)?;
+        let result: Value = self.with_timeout(&context.lua, || func.call(args))?;
 
         Ok(SerializableLuaValue::try_from(result)?)
     }
 
+    /// Calls `fn_name` on every loaded plugin at once and returns the result of each plugin
+    /// when all of them finished.
+    ///
+    /// Every call runs on a thread of its own, so a slow plugin doesn't hold up the others.
     pub fn call_function_on_all(
         &self,
         fn_name: &str,
         args: Vec<SerializableLuaValue>,
     ) -> PluginResult<Vec<(PathBuf, PluginResult<SerializableLuaValue>)>> {
-        let plugins = self.discovered.lock();
+        let paths = self.loaded_plugins();
+
+        let results = std::thread::scope(|scope| {
+            let calls = paths
+                .into_iter()
+                .map(|path| {
+                    let args = args.clone();
+                    let call = scope.spawn({
+                        let path = path.clone();
+                        move || self.call_function(&path, fn_name, args)
+                    });
+
+                    (path, call)
+                })
+                .collect::<Vec<_>>();
+
+            calls
+                .into_iter()
+                .map(|(path, call)| {
+                    let result = call
+                        .join()
+                        .unwrap_or_else(|_| Err(anyhow!("Plugin call panicked")));
+
+                    (path, result)
+                })
+                .collect()
+        });
 
-        let mut results = Vec::with_capacity(plugins.len());
+        Ok(results)
+    }
 
-        for (_, path) in plugins.iter() {
-            results.push((
-                path.clone(),
-                self.call_function(path, fn_name, args.clone()),
-            ));
-        }
+    /// Returns the paths of the loaded scripts, sorted.
+    pub fn loaded_plugins(&self) -> Vec<PathBuf> {
+        let mut paths = self.plugins.lock().keys().cloned().collect::<Vec<_>>();
+        paths.sort();
 
-        Ok(results)
+        paths
+    }
+
+    /// Returns the paths of the loaded scripts that define a function named `fn_name`, sorted.
+    pub fn plugins_with_function(&self, fn_name: &str) -> Vec<PathBuf> {
+        let mut paths = self
+            .plugins
+            .lock()
+            .iter()
+            .filter(|(_, script)| {
+                matches!(
+                    script.lock().environment.raw_get::<Value>(fn_name),
+                    Ok(Value::Function(_))
+                )
+            })
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        paths
+    }
+
+    /// Returns the paths of the scripts that failed to load with their error, sorted by path.
+    pub fn load_errors(&self) -> Vec<(PathBuf, String)> {
+        let mut errors = self
+            .failed
+            .lock()
+            .iter()
+            .map(|(path, error)| (path.clone(), error.clone()))
+            .collect::<Vec<_>>();
+        errors.sort();
+
+        errors
     }
 
     pub fn reload_plugin(&self, path: impl AsRef<Path>) -> PluginResult<()> {
@@ -154,11 +390,8 @@ impl LuaPluginManager {
     }
 
     pub fn get_plugin_functions(&self, script_path: &Path) -> PluginResult<Vec<String>> {
-        let plugins = self.plugins.lock();
-
-        let context = plugins
-            .get(script_path)
-            .ok_or_else(|| anyhow!("Script not found: {}", script_path.display()))?;
+        let script = self.script(script_path)?;
+        let context = script.lock();
 
         let mut functions = Vec::new();
         for pair in context.environment.pairs::<Value, Value>() {
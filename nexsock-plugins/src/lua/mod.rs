@@ -95,6 +95,52 @@ impl TryFrom<Value> for SerializableLuaValue {
     }
 }
 
+/// Converts JSON, such as the JSON form of a command payload, with objects becoming tables keyed
+/// by string.
+impl From<serde_json::Value> for SerializableLuaValue {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Self::Nil,
+            serde_json::Value::Bool(val) => Self::Boolean(val),
+            serde_json::Value::Number(val) => match val.as_i64() {
+                Some(val) => Self::Integer(val),
+                None => Self::Number(val.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(val) => Self::String(val),
+            serde_json::Value::Array(values) => {
+                Self::Array(values.into_iter().map(Self::from).collect())
+            }
+            serde_json::Value::Object(object) => Self::Table(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (Self::String(key), Self::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Converts TOML, such as the config file of a plugin. Datetimes become strings as Lua has no type
+/// for them.
+impl From<toml::Value> for SerializableLuaValue {
+    fn from(value: toml::Value) -> Self {
+        match value {
+            toml::Value::String(val) => Self::String(val),
+            toml::Value::Integer(val) => Self::Integer(val),
+            toml::Value::Float(val) => Self::Number(val),
+            toml::Value::Boolean(val) => Self::Boolean(val),
+            toml::Value::Datetime(val) => Self::String(val.to_string()),
+            toml::Value::Array(values) => Self::Array(values.into_iter().map(Self::from).collect()),
+            toml::Value::Table(table) => Self::Table(
+                table
+                    .into_iter()
+                    .map(|(key, value)| (Self::String(key), Self::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
 impl SerializableLuaValue {
     pub fn to_lua_value(&self, lua: &Lua) -> mlua::Result<Value> {
         Ok(match self {
@@ -142,7 +188,10 @@ impl SerializableLuaValue {
     }
 }
 
-#[derive(Debug, From)]
+/// A loaded script, with the Lua state of its own it runs on and the environment it was evaluated
+/// in.
+#[derive(Debug)]
 struct ScriptContext {
+    lua: Lua,
     environment: mlua::Table,
 }
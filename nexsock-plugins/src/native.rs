@@ -1,15 +1,64 @@
 use crate::PLUGINS_DIR;
+use anyhow::{bail, Context};
 use savefile_abi::{AbiConnection, AbiExportable};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{debug, error, info};
 
-#[tracing::instrument]
+/// Name of the function a native plugin exports the ABI version it was built against with,
+/// `nexsock_abi::export_abi_version!` defines it.
+pub const ABI_VERSION_SYMBOL: &str = "nexsock_abi_version";
+
+/// Reads the ABI version the native plugin at `path` was built against, `None` if it doesn't
+/// export one.
+pub fn read_abi_version(path: &Path) -> anyhow::Result<Option<u32>> {
+    // SAFETY: Opening the library runs its initializers, the same as loading the plugin does
+    let library = unsafe { libloading::Library::new(path) }
+        .with_context(|| format!("Failed to open '{}'", path.display()))?;
+
+    // SAFETY: `export_abi_version!` exports the symbol with this signature
+    let version = unsafe { library.get::<extern "C" fn() -> u32>(ABI_VERSION_SYMBOL.as_bytes()) }
+        .ok()
+        .map(|version| version());
+
+    Ok(version)
+}
+
+/// Checks that a plugin built against the ABI version `found` can be loaded by a daemon that
+/// expects version `expected`.
+pub fn check_abi_version(found: Option<u32>, expected: u32) -> anyhow::Result<()> {
+    match found {
+        Some(found) if found == expected => Ok(()),
+        Some(found) => {
+            bail!("Plugin was built against ABI version {found}, the daemon expects version {expected}")
+        }
+        None => bail!(
+            "Plugin doesn't export its ABI version, it was likely built against an older \
+             nexsock-abi. The daemon expects version {expected}"
+        ),
+    }
+}
+
+/// Loads the native plugins in the plugins directory.
+///
+/// See [`external_native_plugins_in`].
 pub async fn external_native_plugins<T: AbiExportable + ?Sized + 'static>(
+    abi_version: u32,
+) -> anyhow::Result<HashMap<PathBuf, AbiConnection<T>>> {
+    external_native_plugins_in(&PLUGINS_DIR.join("native"), abi_version).await
+}
+
+/// Loads the native plugins in `dir`.
+///
+/// Plugins not built against `abi_version` are skipped before loading them, calling into a
+/// plugin with another ABI could crash the daemon.
+#[tracing::instrument]
+pub async fn external_native_plugins_in<T: AbiExportable + ?Sized + 'static>(
+    dir: &Path,
+    abi_version: u32,
 ) -> anyhow::Result<HashMap<PathBuf, AbiConnection<T>>> {
     info!("Loading external native plugins...");
     let mut connections = HashMap::new();
-    let dir = &*PLUGINS_DIR.join("native");
 
     let mut read_dir = tokio::fs::read_dir(dir).await?;
 
@@ -19,6 +68,19 @@ pub async fn external_native_plugins<T: AbiExportable + ?Sized + 'static>(
         match path.extension() {
             Some(extension) if extension == "so" || extension == "dll" || extension == "dylib" => {
                 debug!(path = %path.display(), "Loading external native plugin");
+
+                if let Err(err) =
+                    read_abi_version(&path).and_then(|found| check_abi_version(found, abi_version))
+                {
+                    error!(
+                        path = %path.display(),
+                        error = %err,
+                        "Skipping incompatible external native plugin"
+                    );
+
+                    continue;
+                }
+
                 let connection =
                     match AbiConnection::<T>::load_shared_library(path.to_str().unwrap()) {
                         Ok(connection) => connection,
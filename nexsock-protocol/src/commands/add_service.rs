@@ -1,8 +1,11 @@
 use crate::commands::config::ServiceConfigPayload;
+use crate::commands::manage_service::StopSignal;
 use crate::service_command;
 use bincode::{Decode, Encode};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Default,
@@ -28,6 +31,118 @@ pub struct AddServicePayload {
     pub git_branch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_auth_type: Option<String>,
+    #[serde(default)]
+    pub stop_signal: StopSignal,
+    /// The profile grouping the service with others that are started and stopped together.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// The `key=value` labels to tag the service with.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+impl AddServicePayload {
+    /// Starts building the payload of a service, the fields other than the required ones are
+    /// left unset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nexsock_protocol::commands::add_service::AddServicePayload;
+    ///
+    /// let payload =
+    ///     AddServicePayload::builder("api", "https://example.com/api.git", 8080, "/srv/api")
+    ///         .git_branch("main")
+    ///         .label("team", "backend")
+    ///         .build();
+    ///
+    /// assert_eq!(payload.git_branch.as_deref(), Some("main"));
+    /// ```
+    pub fn builder(
+        name: impl Into<String>,
+        repo_url: impl Into<String>,
+        port: i64,
+        repo_path: impl Into<String>,
+    ) -> AddServicePayloadBuilder {
+        AddServicePayloadBuilder::new(name, repo_url, port, repo_path)
+    }
+}
+
+/// Builds an [`AddServicePayload`] from its required fields, with the optional ones set as needed.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct AddServicePayloadBuilder {
+    payload: AddServicePayload,
+}
+
+impl AddServicePayloadBuilder {
+    /// Creates a builder for a service without configuration, git settings, profile or labels,
+    /// stopped with the default [`StopSignal`].
+    pub fn new(
+        name: impl Into<String>,
+        repo_url: impl Into<String>,
+        port: i64,
+        repo_path: impl Into<String>,
+    ) -> Self {
+        Self {
+            payload: AddServicePayload {
+                name: name.into(),
+                repo_url: repo_url.into(),
+                port,
+                repo_path: repo_path.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn config(mut self, config: ServiceConfigPayload) -> Self {
+        self.payload.config = Some(config);
+        self
+    }
+
+    /// The branch the repository is checked out at.
+    pub fn git_branch(mut self, branch: impl Into<String>) -> Self {
+        self.payload.git_branch = Some(branch.into());
+        self
+    }
+
+    /// How the daemon authenticates with the repository, such as `ssh`.
+    pub fn git_auth_type(mut self, auth_type: impl Into<String>) -> Self {
+        self.payload.git_auth_type = Some(auth_type.into());
+        self
+    }
+
+    pub fn stop_signal(mut self, stop_signal: StopSignal) -> Self {
+        self.payload.stop_signal = stop_signal;
+        self
+    }
+
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.payload.profile = Some(profile.into());
+        self
+    }
+
+    /// Adds the `key=value` label, replacing the value of an existing `key`.
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.payload.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Replaces every label added so far with `labels`.
+    pub fn labels(mut self, labels: BTreeMap<String, String>) -> Self {
+        self.payload.labels = labels;
+        self
+    }
+
+    pub fn build(self) -> AddServicePayload {
+        self.payload
+    }
+}
+
+impl From<AddServicePayloadBuilder> for AddServicePayload {
+    fn from(builder: AddServicePayloadBuilder) -> Self {
+        builder.build()
+    }
 }
 
 service_command! {
@@ -38,7 +153,10 @@ service_command! {
         repo_path: String,
         config: Option<ServiceConfigPayload>,
         git_branch: Option<String>,
-        git_auth_type: Option<String>
+        git_auth_type: Option<String>,
+        stop_signal: StopSignal,
+        profile: Option<String>,
+        labels: BTreeMap<String, String>
     }
 }
 
@@ -71,4 +189,16 @@ impl AddServiceCommand {
     pub fn git_auth_type(&self) -> &Option<String> {
         &self.git_auth_type
     }
+
+    pub fn stop_signal(&self) -> StopSignal {
+        self.stop_signal
+    }
+
+    pub fn profile(&self) -> &Option<String> {
+        &self.profile
+    }
+
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
 }
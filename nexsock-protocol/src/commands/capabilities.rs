@@ -0,0 +1,50 @@
+use crate::commands::{Command, CommandPayload};
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+/// What a daemon supports, letting clients adapt to how it was built.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct Capabilities {
+    /// The version of the protocol the daemon writes in message headers.
+    pub protocol_version: u16,
+    /// The optional features the daemon was built with, such as `git`, `lua` or `native`.
+    pub features: Vec<String>,
+    /// The opcodes of the commands the daemon handles.
+    pub commands: Vec<u16>,
+}
+
+impl Capabilities {
+    /// Returns `true` if the daemon was built with `feature`.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|enabled| enabled == feature)
+    }
+
+    /// Returns `true` if the daemon handles `command`.
+    pub fn supports(&self, command: Command) -> bool {
+        self.commands.contains(&(command as u16))
+    }
+}
+
+service_command! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct CapabilitiesCommand<_, Capabilities> = Capabilities
+}
+
+try_from!(Capabilities => Capabilities);
@@ -21,16 +21,32 @@ service_command! {
     pub struct GetConfig<ServiceRef, ServiceConfigPayload> = GetConfig
 }
 
+service_command! {
+    pub struct GetConfigFile<ServiceRef, ConfigFile> = GetConfigFile
+}
+
+service_command! {
+    pub struct WriteConfigCommand<WriteConfigPayload, ()> = WriteConfig {
+        service: ServiceRef,
+        contents: String
+    }
+}
+
 service_command! {
     pub struct UpdateConfigCommand<ServiceConfigPayload, ()> = UpdateConfig {
         service: ServiceRef,
         filename: String,
         format: ConfigFormat,
-        run_command: String
+        run_command: String,
+        log_format: LogFormat,
+        strip_ansi: bool,
+        capture_output: bool,
+        log_timestamp_format: Option<String>
     }
 }
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Default,
@@ -50,11 +66,80 @@ pub struct ServiceConfigPayload {
     pub filename: String,
     pub format: ConfigFormat,
     pub run_command: String,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    #[serde(default)]
+    pub strip_ansi: bool,
+    /// Whether the daemon captures the output of the service for its logs, the service writes
+    /// straight to the daemon's stdio otherwise.
+    #[serde(default = "default_capture_output")]
+    pub capture_output: bool,
+    /// The [chrono format](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) of
+    /// the timestamp the service starts its lines with. Log entries are timestamped with it
+    /// instead of when the daemon read the line, lines without it keep the capture time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_timestamp_format: Option<String>,
 }
 
 try_from!(ServiceConfig => ServiceConfigPayload);
 
+/// Output is captured unless a service opts out.
+pub(crate) fn default_capture_output() -> bool {
+    true
+}
+
+/// The configuration of a service together with the contents of its configuration file.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ConfigFile {
+    pub config: ServiceConfigPayload,
+    /// The path the file is read from, `filename` in the repository of the service.
+    pub path: String,
+    /// The contents of the file, `None` if it doesn't exist.
+    pub contents: Option<String>,
+}
+
+try_from!(ConfigFile => ConfigFile);
+
+/// Replaces the configuration file of a service, the counterpart of [`GetConfigFile`].
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct WriteConfigPayload {
+    pub service: ServiceRef,
+    /// The new contents of the file, they must parse in the format of the configuration.
+    pub contents: String,
+}
+
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Copy,
@@ -181,3 +266,97 @@ impl TryGetable for ConfigFormat {
         }
     }
 }
+
+/// How the captured output of a service is parsed into leveled log entries.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Type,
+    Encode,
+    Decode,
+    Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum LogFormat {
+    /// Output is stored as-is without any level information.
+    #[default]
+    Raw,
+    /// Every line is a JSON object with a `level` field.
+    Json,
+    /// Levels are detected by looking for a level keyword such as `WARN` in each line.
+    Text,
+}
+
+impl From<String> for LogFormat {
+    fn from(value: String) -> Self {
+        match value.to_lowercase().as_str() {
+            "json" => Self::Json,
+            "text" => Self::Text,
+            _ => Self::Raw,
+        }
+    }
+}
+
+#[cfg(feature = "sea-orm")]
+impl ValueType for LogFormat {
+    /// Attempts to convert a `Value` into a `LogFormat` enum variant.
+    fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+        match v {
+            Value::String(Some(x)) => match x.as_str() {
+                "Raw" => Ok(Self::Raw),
+                "Json" => Ok(Self::Json),
+                "Text" => Ok(Self::Text),
+                _ => Err(ValueTypeErr),
+            },
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        String::from("LogFormat")
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::String
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::String(StringLen::None)
+    }
+}
+
+#[cfg(feature = "sea-orm")]
+impl From<LogFormat> for Value {
+    fn from(log_format: LogFormat) -> Self {
+        Value::String(Some(Box::new(log_format.to_string())))
+    }
+}
+
+#[cfg(feature = "sea-orm")]
+impl TryGetable for LogFormat {
+    /// Attempts to extract a `LogFormat` value from a database query result at the specified column index.
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let val: String = res.try_get_by(index)?;
+
+        match val.as_str() {
+            "Raw" => Ok(Self::Raw),
+            "Json" => Ok(Self::Json),
+            "Text" => Ok(Self::Text),
+            val => Err(TryGetError::DbErr(DbErr::Custom(format!(
+                "`{val}` is not a valid log format"
+            )))),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::commands::service_status::ServiceState;
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The value secrets are replaced with in a [`DebugDump`].
+pub const REDACTED: &str = "<redacted>";
+
+/// A snapshot of the internal state of the daemon, meant to be attached to bug reports.
+///
+/// Secrets such as the web API key and environment variables named like credentials are
+/// replaced with [`REDACTED`].
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct DebugDump {
+    /// The version of the daemon.
+    pub version: String,
+    /// The instance the daemon runs as, `None` for the default instance.
+    pub instance: Option<String>,
+    pub running_services: Vec<RunningServiceDump>,
+    /// The configuration the daemon runs with, as JSON.
+    pub config: String,
+    /// The Lua plugins the daemon loaded.
+    pub lua_plugins: Vec<String>,
+    /// The native plugins the daemon loaded.
+    pub native_plugins: Vec<String>,
+    /// The Lua plugins that failed to load, with the error they failed with.
+    #[serde(default)]
+    pub failed_lua_plugins: BTreeMap<String, String>,
+}
+
+/// The state of a process the daemon started.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct RunningServiceDump {
+    pub id: i64,
+    /// The id of the process group, `None` once the process was reaped.
+    pub pid: Option<u32>,
+    pub state: ServiceState,
+    /// The environment variables the service was started with.
+    pub env_vars: BTreeMap<String, String>,
+    /// The number of log entries buffered for the service.
+    pub buffered_log_entries: usize,
+}
+
+service_command! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct DebugDumpCommand<_, DebugDump> = DebugDump
+}
+
+try_from!(DebugDump => DebugDump);
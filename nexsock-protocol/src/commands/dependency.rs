@@ -1,4 +1,4 @@
-use crate::commands::dependency_info::DependencyInfo;
+use crate::commands::dependency_info::{DependencyDetails, DependencyInfo};
 use crate::commands::manage_service::ServiceRef;
 use crate::commands::CommandPayload;
 use crate::{service_command, try_from};
@@ -12,6 +12,7 @@ service_command! {
         service: ServiceRef,
         dependent_service: ServiceRef,
         tunnel_enabled: bool,
+        optional: bool,
     }
 }
 
@@ -26,7 +27,20 @@ service_command! {
     pub struct ListDependenciesCommand<ServiceRef, ListDependenciesResponse> = ListDependencies
 }
 
+service_command! {
+    pub struct ListDependentsCommand<ServiceRef, ListDependentsResponse> = ListDependents
+}
+
+service_command! {
+    pub struct GetDependencyInfoCommand<GetDependencyInfoPayload, DependencyDetails> = GetDependencyInfo {
+        service: ServiceRef,
+        dependent_service: ServiceRef,
+    }
+}
+
 try_from!(Dependencies => ListDependenciesResponse);
+try_from!(Dependents => ListDependentsResponse);
+try_from!(DependencyDetails => DependencyDetails);
 
 #[derive(
     Clone,
@@ -46,6 +60,9 @@ pub struct AddDependencyPayload {
     pub service: ServiceRef,
     pub dependent_service: ServiceRef,
     pub tunnel_enabled: bool,
+    /// Lets the service start even if the dependency isn't ready.
+    #[serde(default)]
+    pub optional: bool,
 }
 
 #[derive(
@@ -67,6 +84,26 @@ pub struct RemoveDependencyPayload {
     pub dependent_service: ServiceRef,
 }
 
+/// Selects the dependency of `service` on `dependent_service`.
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct GetDependencyInfoPayload {
+    pub service: ServiceRef,
+    pub dependent_service: ServiceRef,
+}
+
 #[cfg_attr(feature = "savefile", derive(Savefile))]
 #[derive(
     Clone,
@@ -86,3 +123,24 @@ pub struct ListDependenciesResponse {
     pub service_name: String,
     pub dependencies: Vec<DependencyInfo>,
 }
+
+/// The services that depend on `service_name`, the reverse of [`ListDependenciesResponse`].
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ListDependentsResponse {
+    pub service_name: String,
+    pub dependents: Vec<DependencyInfo>,
+}
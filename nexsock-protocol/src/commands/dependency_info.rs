@@ -5,6 +5,7 @@ use savefile::prelude::Savefile;
 use serde::{Deserialize, Serialize};
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Default,
@@ -24,4 +25,38 @@ pub struct DependencyInfo {
     pub name: String,
     pub tunnel_enabled: bool,
     pub state: ServiceState,
+    /// Whether the process of the dependency's service is currently running, and with it whether
+    /// its tunnel is usable. Unlike `state` this is not the recorded status but what the daemon
+    /// sees right now, it's only filled in when listing dependencies.
+    #[serde(default)]
+    pub dependent_state: ServiceState,
+    /// Whether the service may start without the dependency being ready.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// A single dependency of `service_name` along with the port of the dependency's service, for
+/// showing the details of one dependency.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct DependencyDetails {
+    pub service_name: String,
+    /// The dependency, with `dependent_state` filled in from the processes the daemon is running.
+    pub dependency: DependencyInfo,
+    /// The port the dependency's service runs on.
+    pub port: i64,
 }
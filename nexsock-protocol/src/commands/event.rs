@@ -0,0 +1,145 @@
+use crate::commands::manage_service::ServiceRef;
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Answered once, every matching event is then sent as another response to the same request
+service_command! {
+    pub struct SubscribeCommand<SubscribePayload, ()> = Subscribe {
+        service: Option<ServiceRef>,
+        kinds: Vec<ServiceEventKind>,
+        after_seq: Option<u64>
+    }
+}
+
+try_from!(Event => SequencedEvent);
+
+/// A [`ServiceEvent`] numbered in the order the daemon published it.
+///
+/// Sequence numbers start at 1 and increase by one for every published event, whether or not a
+/// subscriber receives it.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    pub event: ServiceEvent,
+}
+
+/// A change in the lifecycle of a service, published by the daemon as it happens.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
+pub enum ServiceEvent {
+    /// The service was started and survived its startup.
+    Started { service_id: i64 },
+    /// The service was stopped, or exited on its own with a success status.
+    Stopped { service_id: i64 },
+    /// The service exited on its own with a failure status.
+    Crashed {
+        service_id: i64,
+        /// The exit code of the service, `None` if it was killed by a signal.
+        exit_code: Option<i32>,
+    },
+}
+
+impl ServiceEvent {
+    /// The id of the service the event is about.
+    pub fn service_id(&self) -> i64 {
+        match self {
+            Self::Started { service_id }
+            | Self::Stopped { service_id }
+            | Self::Crashed { service_id, .. } => *service_id,
+        }
+    }
+
+    /// The kind of the event, without its data.
+    pub fn kind(&self) -> ServiceEventKind {
+        match self {
+            Self::Started { .. } => ServiceEventKind::Started,
+            Self::Stopped { .. } => ServiceEventKind::Stopped,
+            Self::Crashed { .. } => ServiceEventKind::Crashed,
+        }
+    }
+}
+
+impl Display for ServiceEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Started { service_id } => write!(f, "service {service_id} started"),
+            Self::Stopped { service_id } => write!(f, "service {service_id} stopped"),
+            Self::Crashed {
+                service_id,
+                exit_code: Some(code),
+            } => write!(f, "service {service_id} crashed with exit code {code}"),
+            Self::Crashed {
+                service_id,
+                exit_code: None,
+            } => write!(f, "service {service_id} was killed by a signal"),
+        }
+    }
+}
+
+/// The kinds of [`ServiceEvent`] a subscription can be limited to.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone, Copy, Debug, Ord, PartialOrd, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceEventKind {
+    Started,
+    Stopped,
+    Crashed,
+}
+
+impl FromStr for ServiceEventKind {
+    type Err = anyhow::Error;
+
+    /// Parses `started`, `stopped` or `crashed`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "started" => Ok(Self::Started),
+            "stopped" => Ok(Self::Stopped),
+            "crashed" => Ok(Self::Crashed),
+            _ => Err(anyhow::anyhow!(
+                "invalid event kind `{s}`, expected `started`, `stopped` or `crashed`"
+            )),
+        }
+    }
+}
+
+/// Which events a subscriber is sent.
+///
+/// Every event is sent when both filters are empty, `kinds` limits the events to those kinds and
+/// `service` to those of that service.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct SubscribePayload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceRef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<ServiceEventKind>,
+    /// Replays the matching events the daemon still buffers that came after the event with this
+    /// sequence number, before any new event.
+    ///
+    /// Pass the `seq` of the last event received to resubscribe without missing the events
+    /// published in between. `None` only sends the events published from now on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_seq: Option<u64>,
+}
@@ -0,0 +1,36 @@
+use crate::commands::list_services::ListServicesResponse;
+use crate::service_command;
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+service_command! {
+    pub struct ListByLabelsCommand<LabelSelectorPayload, ListServicesResponse> = ListByLabels {
+        labels: BTreeMap<String, String>
+    }
+}
+
+/// Selects the services carrying every one of the given labels.
+///
+/// Labels are free-form `key=value` metadata such as the owning team or the environment, a
+/// service has at most one value per key.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct LabelSelectorPayload {
+    pub labels: BTreeMap<String, String>,
+}
@@ -6,8 +6,10 @@ use derive_more::AsRef;
 #[cfg(feature = "savefile")]
 use savefile::prelude::Savefile;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Default,
@@ -43,6 +45,7 @@ impl<T: Into<ServiceInfo>> FromIterator<T> for ListServicesResponse {
 }
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Default,
@@ -63,4 +66,7 @@ pub struct ServiceInfo {
     pub state: ServiceState,
     pub port: i64,
     pub has_dependencies: bool,
+    /// The `key=value` labels of the service.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
 }
@@ -1,16 +1,34 @@
-use crate::service_command;
+use crate::commands::service_status::{ServiceConfig, ServiceStatus};
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use anyhow::Context;
 use bincode::{Decode, Encode};
 use derive_more::{Display, From, TryFrom};
 #[cfg(feature = "savefile")]
 use savefile::prelude::Savefile;
 use serde::{Deserialize, Serialize};
+use sqlx::Type;
 use std::collections::HashMap;
 use std::str::FromStr;
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "sea-orm")] {
+        use sea_orm::sea_query::{ArrayType, ValueType, ValueTypeErr};
+        use sea_orm::{ColIdx, ColumnType, DbErr, QueryResult, TryGetError, TryGetable, Value};
+        use sea_orm::prelude::StringLen;
+    }
+}
+
+/// Seconds a start waits for its [`ReadyCondition`] when the payload doesn't set a timeout.
+pub const DEFAULT_READY_TIMEOUT_SECS: u64 = 30;
+
 service_command! {
     pub struct StartServiceCommand<StartServicePayload, ()> = StartService {
         service: ServiceRef,
         env_vars: HashMap<String, String>,
+        wait_ready: Option<ReadyCondition>,
+        ready_timeout_secs: Option<u64>,
+        clone_missing: bool,
     }
 }
 
@@ -18,6 +36,9 @@ service_command! {
     pub struct RestartServiceCommand<StartServicePayload, ()> = RestartService {
         service: ServiceRef,
         env_vars: HashMap<String, String>,
+        wait_ready: Option<ReadyCondition>,
+        ready_timeout_secs: Option<u64>,
+        clone_missing: bool,
     }
 }
 
@@ -26,18 +47,235 @@ service_command! {
 }
 
 service_command! {
-    pub struct RemoveServiceCommand<ServiceRef, ()> = RemoveService
+    pub struct RemoveServiceCommand<RemoveServicePayload, RemovalPlan> = RemoveService {
+        service: ServiceRef,
+        dry_run: bool,
+        force: bool,
+    }
+}
+
+service_command! {
+    pub struct CloneServiceCommand<CloneServicePayload, ServiceStatus> = CloneService {
+        source: ServiceRef,
+        new_name: String,
+        new_port: i64,
+        new_repo_path: Option<String>,
+        copy_dependencies: bool,
+    }
 }
 
+try_from!(RemovalPlan => RemovalPlan);
+
 #[cfg_attr(feature = "savefile", derive(Savefile))]
 #[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
 pub struct StartServicePayload {
     #[serde(flatten)]
     pub service: ServiceRef,
     pub env_vars: HashMap<String, String>,
+    /// Makes the start block until the service is ready, `None` returns as soon as the process
+    /// is running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_ready: Option<ReadyCondition>,
+    /// Seconds to wait for `wait_ready` before the start fails, [`DEFAULT_READY_TIMEOUT_SECS`]
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_timeout_secs: Option<u64>,
+    /// Clones the repository of the service first if its `repo_path` doesn't exist, instead of
+    /// failing the start.
+    #[serde(default)]
+    pub clone_missing: bool,
+}
+
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct RemoveServicePayload {
+    #[serde(flatten)]
+    pub service: ServiceRef,
+    /// Only reports what removing the service would delete, without removing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Removes the service even if other services depend on it, along with their dependencies
+    /// on it.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// What removing a service deletes, or would delete when it's a dry run.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct RemovalPlan {
+    pub service_name: String,
+    /// The dependencies of the service and the dependencies other services have on it.
+    pub dependencies_to_delete: Vec<DependencyEdge>,
+    /// The configuration of the service, it's deleted along with it.
+    pub config_to_delete: Option<ServiceConfig>,
+    /// Whether the service is running and gets stopped first.
+    pub will_stop: bool,
+}
+
+/// A dependency of `service` on `dependent_service`.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct DependencyEdge {
+    pub service: String,
+    pub dependent_service: String,
+    pub tunnel_enabled: bool,
+}
+
+/// When a started service counts as ready.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode)]
+pub enum ReadyCondition {
+    /// The service accepts TCP connections on its port.
+    Port,
+    /// A line the service writes to stdout or stderr matches this regex.
+    LogMatch(String),
+    /// The service is still running after this many milliseconds.
+    Duration(u64),
+}
+
+impl FromStr for ReadyCondition {
+    type Err = anyhow::Error;
+
+    /// Parses `port`, `log:<regex>` or `duration:<milliseconds>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "port" => Ok(Self::Port),
+            Some(("log", pattern)) => Ok(Self::LogMatch(pattern.to_owned())),
+            Some(("duration", millis)) => millis
+                .parse()
+                .map(Self::Duration)
+                .map_err(|e| anyhow::anyhow!("invalid duration `{millis}`: {e}")),
+            _ => Err(anyhow::anyhow!(
+                "invalid ready condition `{s}`, expected `port`, `log:<regex>` or \
+                 `duration:<milliseconds>`"
+            )),
+        }
+    }
+}
+
+/// Copies `source` along with its configuration as a new service, e.g. to run a staging copy of
+/// it next to the original.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct CloneServicePayload {
+    pub source: ServiceRef,
+    pub new_name: String,
+    pub new_port: i64,
+    /// Where the repository of the clone is checked out, the `repo_path` of the source when
+    /// unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_repo_path: Option<String>,
+    /// Makes the clone depend on the same services as the source.
+    #[serde(default)]
+    pub copy_dependencies: bool,
+}
+
+/// The signal a service is sent first when it is stopped, it is killed if it doesn't exit in time.
+///
+/// There are no signals on Windows, every variant terminates the process right away there.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Type,
+    Encode,
+    Decode,
+    Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum StopSignal {
+    /// `SIGTERM`, what most services expect.
+    #[default]
+    Term,
+    /// `SIGINT`, as if Ctrl+C was pressed in the terminal of the service.
+    Int,
+    /// `SIGQUIT`.
+    Quit,
+    /// `SIGHUP`.
+    Hup,
+}
+
+impl From<String> for StopSignal {
+    fn from(value: String) -> Self {
+        let value = value.to_lowercase();
+
+        match value.strip_prefix("sig").unwrap_or(&value) {
+            "int" => Self::Int,
+            "quit" => Self::Quit,
+            "hup" => Self::Hup,
+            _ => Self::Term,
+        }
+    }
+}
+
+#[cfg(feature = "sea-orm")]
+impl ValueType for StopSignal {
+    /// Attempts to convert a `Value` into a `StopSignal` enum variant.
+    fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+        match v {
+            Value::String(Some(x)) => match x.as_str() {
+                "Term" => Ok(Self::Term),
+                "Int" => Ok(Self::Int),
+                "Quit" => Ok(Self::Quit),
+                "Hup" => Ok(Self::Hup),
+                _ => Err(ValueTypeErr),
+            },
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        String::from("StopSignal")
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::String
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::String(StringLen::None)
+    }
+}
+
+#[cfg(feature = "sea-orm")]
+impl From<StopSignal> for Value {
+    fn from(stop_signal: StopSignal) -> Self {
+        Value::String(Some(Box::new(stop_signal.to_string())))
+    }
+}
+
+#[cfg(feature = "sea-orm")]
+impl TryGetable for StopSignal {
+    /// Attempts to extract a `StopSignal` value from a database query result at the specified column index.
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let val: String = res.try_get_by(index)?;
+
+        match val.as_str() {
+            "Term" => Ok(Self::Term),
+            "Int" => Ok(Self::Int),
+            "Quit" => Ok(Self::Quit),
+            "Hup" => Ok(Self::Hup),
+            val => Err(TryGetError::DbErr(DbErr::Custom(format!(
+                "`{val}` is not a valid stop signal"
+            )))),
+        }
+    }
 }
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Debug,
@@ -68,11 +306,37 @@ impl Default for ServiceRef {
     }
 }
 
+/// Parses a reference as typed on the command line or in a URL.
+///
+/// A `name:` or `id:` prefix forces how the rest is read, so `name:42` references the service
+/// named `42`. Without a prefix, anything parsing as an integer is an id and the rest is a name.
+///
+/// # Errors
+///
+/// Returns an error if the rest of an `id:` reference isn't an integer.
+///
+/// # Examples
+///
+/// ```
+/// use nexsock_protocol::commands::manage_service::ServiceRef;
+///
+/// assert_eq!("42".parse::<ServiceRef>().unwrap(), ServiceRef::Id(42));
+/// assert_eq!(
+///     "name:42".parse::<ServiceRef>().unwrap(),
+///     ServiceRef::Name("42".to_string())
+/// );
+/// ```
 impl FromStr for ServiceRef {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(id) = s.parse() {
+        if let Some(name) = s.strip_prefix("name:") {
+            Ok(Self::Name(name.to_owned()))
+        } else if let Some(id) = s.strip_prefix("id:") {
+            id.parse()
+                .map(Self::Id)
+                .with_context(|| format!("`{id}` in `{s}` is not a service id"))
+        } else if let Ok(id) = s.parse() {
             Ok(Self::Id(id))
         } else {
             Ok(Self::Name(s.to_owned()))
@@ -0,0 +1,195 @@
+use crate::commands::config::{default_capture_output, ConfigFormat, LogFormat};
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+use derive_more::Display;
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+service_command! {
+    pub struct ApplyManifestCommand<ApplyManifestPayload, ApplyManifestResponse> = ApplyManifest {
+        manifest: ServiceManifest,
+        prune: bool
+    }
+}
+
+try_from!(ManifestApplied => ApplyManifestResponse);
+
+/// A declarative description of a set of services and the dependencies between them.
+///
+/// Services are matched against existing ones by name when the manifest is applied.
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ServiceManifest {
+    #[serde(default)]
+    pub services: Vec<ManifestService>,
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ManifestService {
+    pub name: String,
+    pub repo_url: String,
+    pub port: i64,
+    pub repo_path: String,
+    /// The service configuration, an existing configuration is left untouched when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<ManifestConfig>,
+    /// Names of the services this service depends on.
+    #[serde(default)]
+    pub dependencies: Vec<ManifestDependency>,
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ManifestConfig {
+    pub filename: String,
+    #[serde(default)]
+    pub format: ConfigFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_command: Option<String>,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    #[serde(default)]
+    pub strip_ansi: bool,
+    #[serde(default = "default_capture_output")]
+    pub capture_output: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_timestamp_format: Option<String>,
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ManifestDependency {
+    pub name: String,
+    #[serde(default)]
+    pub tunnel_enabled: bool,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ApplyManifestPayload {
+    pub manifest: ServiceManifest,
+    /// Remove services and dependencies that are not part of the manifest.
+    pub prune: bool,
+}
+
+/// A single change made while applying a manifest.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    Display,
+)]
+pub enum ManifestAction {
+    #[display("create service `{name}`")]
+    CreateService { name: String },
+    #[display("update service `{name}`")]
+    UpdateService { name: String },
+    #[display("remove service `{name}`")]
+    RemoveService { name: String },
+    #[display("add dependency `{service}` -> `{dependency}`")]
+    AddDependency { service: String, dependency: String },
+    #[display("update dependency `{service}` -> `{dependency}`")]
+    UpdateDependency { service: String, dependency: String },
+    #[display("remove dependency `{service}` -> `{dependency}`")]
+    RemoveDependency { service: String, dependency: String },
+}
+
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ApplyManifestResponse {
+    /// Every change that was made, empty if the manifest was already applied.
+    pub actions: Vec<ManifestAction>,
+}
+
+impl ApplyManifestResponse {
+    pub fn is_noop(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+/// Counters the daemon keeps about its own activity since it started.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct DaemonMetrics {
+    /// Commands handled, heartbeats excluded.
+    pub commands_handled: u64,
+    /// Commands answered with an error.
+    pub commands_failed: u64,
+    /// Services started successfully, restarts included.
+    pub services_started: u64,
+    /// Starts that failed, including processes that exited during startup.
+    pub start_failures: u64,
+    /// Services stopped, restarts included.
+    pub services_stopped: u64,
+    /// Services running when the metrics were exported.
+    pub running_services: u64,
+    /// Rejected changes of the state of a service, such as starting a running service.
+    #[serde(default)]
+    pub invalid_transitions: u64,
+}
+
+service_command! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct ExportMetricsCommand<_, DaemonMetrics> = ExportMetrics
+}
+
+try_from!(Metrics => DaemonMetrics);
@@ -0,0 +1,99 @@
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+service_command! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct MigrationStatusCommand<_, MigrationStatusResponse> = MigrationStatus
+}
+
+service_command! {
+    pub struct RollbackMigrationCommand<RollbackMigrationPayload, MigrationStatusResponse> = RollbackMigration {
+        steps: u32
+    }
+}
+
+try_from!(Migrations => MigrationStatusResponse);
+
+/// Reverts the last `steps` applied migrations, newest first.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct RollbackMigrationPayload {
+    pub steps: u32,
+}
+
+/// A database migration known to the daemon and whether it was applied.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct MigrationInfo {
+    /// The name of the migration, such as `m20220101_000001_create_base_service_tables`.
+    pub name: String,
+    /// When the migration was applied in seconds since the Unix epoch, `None` while pending.
+    pub applied_at: Option<i64>,
+}
+
+impl MigrationInfo {
+    /// Returns `true` if the migration was applied to the database.
+    pub fn is_applied(&self) -> bool {
+        self.applied_at.is_some()
+    }
+}
+
+/// Every migration known to the daemon, in the order they are applied.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct MigrationStatusResponse {
+    pub migrations: Vec<MigrationInfo>,
+}
+
+impl MigrationStatusResponse {
+    /// Returns the migrations that weren't applied yet.
+    pub fn pending(&self) -> impl Iterator<Item = &MigrationInfo> {
+        self.migrations
+            .iter()
+            .filter(|migration| !migration.is_applied())
+    }
+}
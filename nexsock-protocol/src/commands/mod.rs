@@ -1,32 +1,66 @@
 pub mod add_service;
+pub mod capabilities;
 pub mod config;
+pub mod debug_dump;
 pub mod dependency;
 pub mod dependency_info;
 pub mod error;
+pub mod event;
 pub mod extra;
 pub mod git;
+pub mod label;
 pub mod list_services;
 pub mod manage_service;
+pub mod manifest;
+pub mod metrics;
+pub mod migration;
+pub mod profile;
+pub mod search;
 pub mod service_status;
+pub mod stdin;
 pub mod stdout;
+pub mod validate;
 
 use crate::commands::add_service::AddServiceCommand;
-use crate::commands::config::{GetConfig, ServiceConfigPayload, UpdateConfigCommand};
+use crate::commands::capabilities::Capabilities;
+use crate::commands::config::{
+    ConfigFile, GetConfig, GetConfigFile, ServiceConfigPayload, UpdateConfigCommand,
+    WriteConfigCommand,
+};
+use crate::commands::debug_dump::{DebugDump, DebugDumpCommand};
 use crate::commands::dependency::{
-    AddDependencyCommand, ListDependenciesCommand, ListDependenciesResponse,
+    AddDependencyCommand, GetDependencyInfoCommand, ListDependenciesCommand,
+    ListDependenciesResponse, ListDependentsCommand, ListDependentsResponse,
     RemoveDependencyCommand,
 };
+use crate::commands::dependency_info::DependencyDetails;
 use crate::commands::error::ErrorPayload;
+use crate::commands::event::SequencedEvent;
 use crate::commands::git::{
     CheckoutCommand, GetRepoStatusCommand, GitCheckoutCommitCommand, GitListBranchesCommand,
     GitListBranchesResponse, GitLogCommand, GitLogResponse, GitPullCommand, RepoStatus,
 };
+use crate::commands::label::ListByLabelsCommand;
 use crate::commands::list_services::{ListServicesCommand, ListServicesResponse};
 use crate::commands::manage_service::{
-    RemoveServiceCommand, RestartServiceCommand, StartServiceCommand, StopServiceCommand,
+    CloneServiceCommand, RemovalPlan, RemoveServiceCommand, RestartServiceCommand,
+    StartServiceCommand, StopServiceCommand,
+};
+use crate::commands::manifest::{ApplyManifestCommand, ApplyManifestResponse};
+use crate::commands::metrics::{DaemonMetrics, ExportMetricsCommand};
+use crate::commands::migration::{
+    MigrationStatusCommand, MigrationStatusResponse, RollbackMigrationCommand,
+};
+use crate::commands::profile::{ListByProfileCommand, StartProfileCommand, StopProfileCommand};
+use crate::commands::search::SearchServicesCommand;
+use crate::commands::service_status::{
+    GetServiceState, GetServiceStatus, ServiceState, ServiceStatus,
 };
-use crate::commands::service_status::{GetServiceStatus, ServiceStatus};
-use crate::commands::stdout::GetServiceStdout;
+use crate::commands::stdin::WriteStdinCommand;
+use crate::commands::stdout::{
+    ClearLogsCommand, GetServiceLogsCommand, GetServiceStdout, ServiceStdout,
+};
+use crate::commands::validate::{ValidateServiceCommand, ValidationReport};
 use crate::service_command;
 use bincode::{Decode, Encode};
 use binrw::{BinRead, BinWrite};
@@ -71,15 +105,29 @@ pub enum Command {
     RemoveService = 6,
     ListServices = 7,
     GetServiceStdout = 8,
+    ApplyManifest = 9,
+    CloneService = 26,
+    GetServiceState = 13,
+    StartProfile = 14,
+    StopProfile = 15,
+    ListByProfile = 16,
+    ListByLabels = 17,
+    SearchServices = 18,
+    Subscribe = 19,
 
     // Configuration
     UpdateConfig = 10,
     GetConfig = 11,
+    ValidateService = 12,
+    GetConfigFile = 24,
+    WriteConfig = 25,
 
     // Dependency management
     AddDependency = 20,
     RemoveDependency = 21,
     ListDependencies = 22,
+    ListDependents = 23,
+    GetDependencyInfo = 53,
 
     // Repository operations
     CheckoutBranch = 30,
@@ -93,6 +141,18 @@ pub enum Command {
     Shutdown = 40,
     GetSystemStatus = 41,
     Ping = 42,
+    Heartbeat = 43,
+    HeartbeatAck = 44,
+    Capabilities = 45,
+    DebugDump = 46,
+    ExportMetrics = 47,
+    MigrationStatus = 48,
+    RollbackMigration = 49,
+
+    // Log management
+    GetServiceLogs = 50,
+    WriteStdin = 51,
+    ClearLogs = 52,
 
     // Extra commands that needs to be handled by a plugin
     Extra = 0xFF00,
@@ -112,20 +172,37 @@ service_command!(pub struct PingCommand<_, ()> = Ping);
 #[repr(u16)]
 pub enum CommandPayload {
     Status(ServiceStatus),
+    State(ServiceState),
     ListServices(ListServicesResponse),
+    ManifestApplied(ApplyManifestResponse),
 
     ServiceConfig(ServiceConfigPayload),
+    ConfigFile(ConfigFile),
+    Validation(ValidationReport),
 
     Dependencies(ListDependenciesResponse),
+    Dependents(ListDependentsResponse),
 
     GitLog(GitLogResponse),
     GitBranches(GitListBranchesResponse),
     GitStatus(RepoStatus),
 
     Stdout(String),
+    ServiceStdout(ServiceStdout),
+
+    Capabilities(Capabilities),
+    DebugDump(DebugDump),
+
+    Event(SequencedEvent),
 
     Error(ErrorPayload),
     Empty,
+
+    // New variants are only appended, the index of a variant is part of its encoding
+    Metrics(DaemonMetrics),
+    Migrations(MigrationStatusResponse),
+    DependencyDetails(DependencyDetails),
+    RemovalPlan(RemovalPlan),
 }
 
 try_from!(Empty => ());
@@ -136,21 +213,37 @@ try_from!(Empty => ());
 #[non_exhaustive]
 pub enum ServiceCommand {
     Stdout(GetServiceStdout),
+    Logs(GetServiceLogsCommand),
+    WriteStdin(WriteStdinCommand),
+    ClearLogs(ClearLogsCommand),
     Start(StartServiceCommand),
     Stop(StopServiceCommand),
     Restart(RestartServiceCommand),
     List(ListServicesCommand),
     Status(GetServiceStatus),
+    State(GetServiceState),
+    StartProfile(StartProfileCommand),
+    StopProfile(StopProfileCommand),
+    ListByProfile(ListByProfileCommand),
+    ListByLabels(ListByLabelsCommand),
+    Search(SearchServicesCommand),
 
     Add(AddServiceCommand),
     Remove(RemoveServiceCommand),
+    Clone(CloneServiceCommand),
+    ApplyManifest(ApplyManifestCommand),
 
     ConfigGet(GetConfig),
+    ConfigGetFile(GetConfigFile),
+    ConfigWrite(WriteConfigCommand),
     ConfigUpdate(UpdateConfigCommand),
+    Validate(ValidateServiceCommand),
 
     DependencyAdd(AddDependencyCommand),
     DependencyRemove(RemoveDependencyCommand),
     DependencyList(ListDependenciesCommand),
+    DependencyDependents(ListDependentsCommand),
+    DependencyInfo(GetDependencyInfoCommand),
 
     GitCheckout(CheckoutCommand),
     GitCheckoutCommit(GitCheckoutCommitCommand),
@@ -158,6 +251,11 @@ pub enum ServiceCommand {
     GitStatus(GetRepoStatusCommand),
     GitLog(GitLogCommand),
     GitListBranches(GitListBranchesCommand),
+
+    DebugDump(DebugDumpCommand),
+    ExportMetrics(ExportMetricsCommand),
+    MigrationStatus(MigrationStatusCommand),
+    RollbackMigration(RollbackMigrationCommand),
 }
 
 impl<T: Into<CommandPayload>> From<Option<T>> for CommandPayload {
@@ -0,0 +1,47 @@
+use crate::commands::list_services::ListServicesResponse;
+use crate::service_command;
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+service_command! {
+    pub struct StartProfileCommand<ProfilePayload, ()> = StartProfile {
+        profile: String
+    }
+}
+
+service_command! {
+    pub struct StopProfileCommand<ProfilePayload, ()> = StopProfile {
+        profile: String
+    }
+}
+
+service_command! {
+    pub struct ListByProfileCommand<ProfilePayload, ListServicesResponse> = ListByProfile {
+        profile: String
+    }
+}
+
+/// Names the profile a profile-scoped command runs on.
+///
+/// A profile is a label grouping services that are started and stopped together, unlike
+/// dependencies it doesn't imply any order between them.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ProfilePayload {
+    pub profile: String,
+}
@@ -0,0 +1,36 @@
+use crate::commands::list_services::ListServicesResponse;
+use crate::service_command;
+use bincode::{Decode, Encode};
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+service_command! {
+    pub struct SearchServicesCommand<SearchServicesPayload, ListServicesResponse> = SearchServices {
+        query: String
+    }
+}
+
+/// The text to search the services for.
+///
+/// The search is a case-insensitive substring match on the name, the repository URL and the
+/// label keys and values of the services. Services matching on their name are listed first,
+/// followed by the ones matching on their repository URL and then on their labels.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct SearchServicesPayload {
+    pub query: String,
+}
@@ -1,6 +1,6 @@
 use crate::commands::config::{ConfigFormat, ServiceConfigPayload};
 use crate::commands::dependency_info::DependencyInfo;
-use crate::commands::manage_service::ServiceRef;
+use crate::commands::manage_service::{ServiceRef, StopSignal};
 use crate::commands::CommandPayload;
 use crate::{service_command, try_from};
 use bincode::{Decode, Encode};
@@ -9,26 +9,20 @@ use derive_more::Display;
 use savefile::prelude::Savefile;
 use serde::{Deserialize, Serialize};
 use sqlx::Type;
+use std::collections::BTreeMap;
 
 service_command! {
     pub struct GetServiceStatus<ServiceRef, ServiceStatus> = GetServiceStatus
 }
 
+// Only the live state of the service, cheaper than `GetServiceStatus` for polling
+service_command! {
+    pub struct GetServiceState<ServiceRef, ServiceState> = GetServiceState
+}
+
 #[cfg_attr(feature = "savefile", derive(Savefile))]
-#[derive(
-    Clone,
-    Default,
-    Debug,
-    Ord,
-    PartialOrd,
-    PartialEq,
-    Eq,
-    Hash,
-    Serialize,
-    Deserialize,
-    Encode,
-    Decode,
-)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[derive(Clone, Default, Debug, PartialOrd, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct ServiceStatus {
     pub id: i64,
     pub name: String,
@@ -45,9 +39,32 @@ pub struct ServiceStatus {
     pub git_commit_hash: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_auth_type: Option<String>,
+    /// The exit code of the service the last time it exited during startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_exit_code: Option<i32>,
+    /// What the service wrote to stderr before it last exited during startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// The signal the service is sent first when it is stopped.
+    #[serde(default)]
+    pub stop_signal: StopSignal,
+    /// The profile the service belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// The `key=value` labels of the service.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    /// Share of one core the running service used over the last sampling interval, in percent.
+    /// Only reported on Linux.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<f32>,
+    /// Resident memory of the running service in bytes. Only reported on Linux.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
 }
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Default,
@@ -174,8 +191,10 @@ impl From<ServiceConfigPayload> for ServiceConfig {
 }
 
 try_from!(Status => ServiceStatus);
+try_from!(State => ServiceState);
 
 #[cfg_attr(feature = "savefile", derive(Savefile))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[derive(
     Clone,
     Copy,
@@ -0,0 +1,31 @@
+use crate::commands::manage_service::ServiceRef;
+use crate::service_command;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+service_command! {
+    pub struct WriteStdinCommand<WriteStdinPayload, ()> = WriteStdin {
+        service: ServiceRef,
+        data: Vec<u8>
+    }
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct WriteStdinPayload {
+    pub service: ServiceRef,
+    /// The bytes written to the stdin of the service, as they are.
+    pub data: Vec<u8>,
+}
@@ -1,6 +1,145 @@
 use crate::commands::manage_service::ServiceRef;
-use crate::service_command;
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+use derive_more::Display;
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 service_command! {
-    pub struct GetServiceStdout<ServiceRef, String> = GetServiceStdout
+    pub struct GetServiceStdout<GetServiceStdoutPayload, ServiceStdout> = GetServiceStdout {
+        service: ServiceRef,
+        after_seq: Option<u64>
+    }
+}
+
+try_from!(ServiceStdout => ServiceStdout);
+
+service_command! {
+    pub struct GetServiceLogsCommand<GetServiceLogsPayload, String> = GetServiceLogs {
+        service: ServiceRef,
+        min_level: Option<LogLevel>,
+        raw: bool
+    }
+}
+
+service_command! {
+    pub struct ClearLogsCommand<ServiceRef, ()> = ClearLogs
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct GetServiceStdoutPayload {
+    pub service: ServiceRef,
+    /// Only return entries captured after the entry with this sequence number.
+    ///
+    /// Pass the `last_seq` of the previous response to poll for new output. `None` returns
+    /// everything that is buffered.
+    #[serde(default)]
+    pub after_seq: Option<u64>,
+}
+
+/// Captured output of a service together with the cursor for the next poll.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ServiceStdout {
+    /// The captured lines, joined in the order they were written.
+    pub content: String,
+    /// The sequence number of the newest buffered entry, `0` if nothing was captured yet.
+    pub last_seq: u64,
+}
+
+/// Severity of a captured log line, ordered from least to most severe.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl FromStr for LogLevel {
+    type Err = anyhow::Error;
+
+    /// Parses a level name case-insensitively, accepting aliases such as `warning` and `fatal`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "trace" => Ok(Self::Trace),
+            "debug" => Ok(Self::Debug),
+            "info" | "information" => Ok(Self::Info),
+            "warn" | "warning" => Ok(Self::Warn),
+            "error" | "err" | "fatal" | "critical" => Ok(Self::Error),
+            level => anyhow::bail!("`{level}` is not a valid log level"),
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct GetServiceLogsPayload {
+    pub service: ServiceRef,
+    /// Only return log entries at or above this level.
+    ///
+    /// Entries without a detected level are always returned.
+    pub min_level: Option<LogLevel>,
+    /// Return lines as the service wrote them, even if the service strips ANSI escape sequences.
+    #[serde(default)]
+    pub raw: bool,
 }
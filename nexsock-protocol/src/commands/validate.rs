@@ -0,0 +1,112 @@
+use crate::commands::manage_service::ServiceRef;
+use crate::commands::CommandPayload;
+use crate::{service_command, try_from};
+use bincode::{Decode, Encode};
+use derive_more::Display;
+#[cfg(feature = "savefile")]
+use savefile::prelude::Savefile;
+use serde::{Deserialize, Serialize};
+
+service_command! {
+    pub struct ValidateServiceCommand<ServiceRef, ValidationReport> = ValidateService
+}
+
+try_from!(Validation => ValidationReport);
+
+/// The outcome of a single check of a validation.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    Display,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    #[default]
+    #[display("pass")]
+    Pass,
+    /// The service can start, but likely not the way it was meant to.
+    #[display("warn")]
+    Warn,
+    /// The service can't start until this is fixed.
+    #[display("fail")]
+    Fail,
+}
+
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    Display,
+)]
+#[display("[{status}] {check}: {message}")]
+pub struct CheckResult {
+    /// What was checked, such as `repo_path` or `port`.
+    pub check: String,
+    pub status: CheckStatus,
+    /// What was found, explaining the problem for warnings and failures.
+    pub message: String,
+}
+
+impl CheckResult {
+    pub fn new(check: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            check: check.into(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// The result of validating a service, made without changing anything.
+#[cfg_attr(feature = "savefile", derive(Savefile))]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Ord,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+)]
+pub struct ValidationReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl ValidationReport {
+    /// Returns the checks with the given status.
+    pub fn with_status(&self, status: CheckStatus) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(move |check| check.status == status)
+    }
+
+    /// Returns `true` if no check failed, warnings don't keep the service from starting.
+    pub fn is_valid(&self) -> bool {
+        self.with_status(CheckStatus::Fail).next().is_none()
+    }
+}
@@ -15,6 +15,8 @@ impl MessageFlags {
     pub const ENCRYPTED: MessageFlags = MessageFlags(1 << 1);
     pub const REQUIRES_ACK: MessageFlags = MessageFlags(1 << 2);
     pub const HAS_PAYLOAD: MessageFlags = MessageFlags(1 << 3);
+    /// The payload is followed by a big endian CRC32 of its bytes.
+    pub const CHECKSUMMED: MessageFlags = MessageFlags(1 << 4);
 
     pub fn contains(self, other: MessageFlags) -> bool {
         (self.0 & other.0) == other.0
@@ -85,4 +87,30 @@ pub struct MessageHeader {
     pub(crate) sequence_number: u32,
     #[brw(big)] // Explicitly set big endian
     pub(crate) flags: MessageFlags,
+    /// Correlates a response with the request that produced it, responses echo the id of the request
+    #[brw(big)] // Explicitly set big endian
+    pub(crate) request_id: u64,
+}
+
+impl MessageHeader {
+    /// Size of the magic bytes that prefix every header.
+    pub const MAGIC_LEN: usize = 4;
+
+    /// Size of the encoded header on the wire, including the magic bytes.
+    ///
+    /// This is the sum of the encoded field sizes and must be kept in sync with the struct,
+    /// `size_of::<MessageHeader>()` includes padding and can not be used for this.
+    pub const ENCODED_LEN: usize = Self::MAGIC_LEN + 2 + 2 + 4 + 4 + 2 + 8;
+
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    pub fn sequence_number(&self) -> u32 {
+        self.sequence_number
+    }
+
+    pub fn flags(&self) -> MessageFlags {
+        self.flags
+    }
 }
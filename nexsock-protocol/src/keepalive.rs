@@ -0,0 +1,100 @@
+//! Frame-level keepalive for long-lived connections.
+//!
+//! The side that owns a connection sends a [`Command::Heartbeat`](crate::commands::Command::Heartbeat)
+//! frame whenever the connection has been idle for a full interval, and the peer answers with a
+//! [`Command::HeartbeatAck`](crate::commands::Command::HeartbeatAck). Any inbound frame counts as
+//! proof of life, so a busy connection never sends heartbeats at all.
+
+use std::time::Duration;
+
+/// Settings controlling how often heartbeats are sent and when a peer is considered dead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepaliveConfig {
+    /// How long a connection may stay idle before a heartbeat is sent.
+    ///
+    /// A zero interval disables heartbeats entirely.
+    pub interval: Duration,
+
+    /// Number of consecutive unanswered heartbeats tolerated before disconnecting.
+    pub max_missed: u32,
+}
+
+impl KeepaliveConfig {
+    pub const fn new(interval: Duration, max_missed: u32) -> Self {
+        Self {
+            interval,
+            max_missed,
+        }
+    }
+
+    /// Returns a configuration that never sends heartbeats.
+    pub const fn disabled() -> Self {
+        Self::new(Duration::ZERO, 0)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30), 3)
+    }
+}
+
+/// What the connection should do after an idle interval elapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepaliveAction {
+    /// Send a heartbeat frame to the peer.
+    SendHeartbeat,
+    /// The peer missed too many heartbeats and should be dropped.
+    Disconnect,
+}
+
+/// Tracks outstanding heartbeats for a single connection.
+#[derive(Debug, Clone)]
+pub struct Keepalive {
+    config: KeepaliveConfig,
+    missed: u32,
+}
+
+impl Keepalive {
+    pub fn new(config: KeepaliveConfig) -> Self {
+        Self { config, missed: 0 }
+    }
+
+    pub fn config(&self) -> &KeepaliveConfig {
+        &self.config
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.config.interval
+    }
+
+    /// Number of heartbeats sent since the peer was last heard from.
+    pub fn missed(&self) -> u32 {
+        self.missed
+    }
+
+    /// Records that a frame was received from the peer, clearing any outstanding heartbeats.
+    pub fn record_activity(&mut self) {
+        self.missed = 0;
+    }
+
+    /// Called when a full interval passed without any inbound frame.
+    pub fn on_idle(&mut self) -> KeepaliveAction {
+        if self.missed >= self.config.max_missed {
+            KeepaliveAction::Disconnect
+        } else {
+            self.missed += 1;
+            KeepaliveAction::SendHeartbeat
+        }
+    }
+}
+
+impl From<KeepaliveConfig> for Keepalive {
+    fn from(config: KeepaliveConfig) -> Self {
+        Self::new(config)
+    }
+}
@@ -1,6 +1,7 @@
 pub mod commands;
 mod error;
 pub mod header;
+pub mod keepalive;
 mod macros;
 pub mod protocol;
 pub mod traits;
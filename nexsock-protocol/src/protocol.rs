@@ -9,10 +9,151 @@ use tracing::debug;
 #[cfg(debug_assertions)]
 use tracing::error;
 
-#[derive(Debug, Default)]
+/// The protocol version written in the headers of [`Protocol::default`].
+///
+/// Bump it whenever the layout of [`MessageHeader`] changes, headers of another version are
+/// rejected by [`Protocol::read_message`].
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// Version of the payload schema, written as the first byte of every payload.
+///
+/// Bump it whenever the encoding of an existing payload changes, so a peer built against another
+/// schema rejects the payload instead of mis-decoding it.
+pub const PAYLOAD_VERSION: u8 = 10;
+
+/// Error returned by [`Protocol::read_message`] when the stream ends in the middle of a message.
+///
+/// It is carried by an [`io::ErrorKind::InvalidData`] error, use [`TruncatedMessage::is`] to tell it
+/// apart from a clean disconnect between messages.
+#[derive(Debug, thiserror::Error)]
+#[error("message truncated, the stream ended before the whole message was read")]
+pub struct TruncatedMessage;
+
+impl TruncatedMessage {
+    /// Returns `true` if `error` reports a truncated message.
+    pub fn is(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<TruncatedMessage>())
+    }
+}
+
+impl From<TruncatedMessage> for io::Error {
+    fn from(value: TruncatedMessage) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
+
+/// Error returned by [`Protocol::read_message`] when the CRC32 trailing a checksummed payload
+/// doesn't match the payload, it was corrupted on the way.
+///
+/// It is carried by an [`io::ErrorKind::InvalidData`] error, use [`ChecksumMismatch::is`] to
+/// recognize it.
+#[derive(Debug, thiserror::Error)]
+#[error("payload checksum mismatch, expected {expected:#010x} got {actual:#010x}")]
+pub struct ChecksumMismatch {
+    pub expected: u32,
+    pub actual: u32,
+}
+
+impl ChecksumMismatch {
+    /// Returns `true` if `error` reports a checksum mismatch.
+    pub fn is(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<ChecksumMismatch>())
+    }
+}
+
+impl From<ChecksumMismatch> for io::Error {
+    fn from(value: ChecksumMismatch) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
+
+/// Error returned by [`Protocol::read_message`] when the header was written with another protocol
+/// version than the one of the reader, the peer runs an incompatible version of nexsock.
+///
+/// It is carried by an [`io::ErrorKind::InvalidData`] error, use [`ProtocolVersionMismatch::is`]
+/// to recognize it.
+#[derive(Debug, thiserror::Error)]
+#[error("protocol version mismatch, expected {expected} got {found}")]
+pub struct ProtocolVersionMismatch {
+    pub expected: u16,
+    pub found: u16,
+}
+
+impl ProtocolVersionMismatch {
+    /// Returns `true` if `error` reports a protocol version mismatch.
+    pub fn is(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<ProtocolVersionMismatch>())
+    }
+}
+
+impl From<ProtocolVersionMismatch> for io::Error {
+    fn from(value: ProtocolVersionMismatch) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
+
+/// Error returned by [`Protocol::read_payload`] when the payload was encoded with another
+/// [`PAYLOAD_VERSION`], the peer runs an incompatible version of nexsock.
+///
+/// It is carried by an [`io::ErrorKind::InvalidData`] error, use [`PayloadVersionMismatch::is`]
+/// to recognize it.
+#[derive(Debug, thiserror::Error)]
+#[error("payload version mismatch, expected {expected} got {found}")]
+pub struct PayloadVersionMismatch {
+    pub expected: u8,
+    pub found: u8,
+}
+
+impl PayloadVersionMismatch {
+    /// Returns `true` if `error` reports a payload version mismatch.
+    pub fn is(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<PayloadVersionMismatch>())
+    }
+}
+
+impl From<PayloadVersionMismatch> for io::Error {
+    fn from(value: PayloadVersionMismatch) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
+
+/// Fills `buf` with the next part of a message that already started, reporting the end of the
+/// stream as a [`TruncatedMessage`].
+async fn read_message_part<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    match reader.read_exact(buf).await {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(TruncatedMessage.into()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Debug)]
 pub struct Protocol {
     sequence: u32,
     version: u16,
+    /// Request id stamped on outgoing commands and responses.
+    request_id: u64,
+    /// Last id handed out by [`Protocol::next_request_id`].
+    last_request_id: u64,
+    /// Whether outgoing payloads are followed by a CRC32, see [`MessageFlags::CHECKSUMMED`].
+    checksums: bool,
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Self::new(PROTOCOL_VERSION)
+    }
 }
 
 impl Protocol {
@@ -20,18 +161,82 @@ impl Protocol {
         Self {
             sequence: 0,
             version,
+            request_id: 0,
+            last_request_id: 0,
+            checksums: false,
         }
     }
 
+    /// Appends a CRC32 to the payloads of the messages written from now on, so the reader can
+    /// reject corrupted frames.
+    ///
+    /// Checksummed messages are always verified when read, this only controls writing them. Only
+    /// enable it when the peer understands [`MessageFlags::CHECKSUMMED`].
+    pub fn with_checksums(mut self, checksums: bool) -> Self {
+        self.checksums = checksums;
+        self
+    }
+
+    /// The request id that will be written on the next outgoing command.
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    /// Sets the request id for outgoing commands, used by the daemon to echo the id of the request it is answering.
+    pub fn set_request_id(&mut self, request_id: u64) {
+        self.request_id = request_id;
+    }
+
+    /// Generates a new monotonically increasing request id and uses it for the following commands.
+    ///
+    /// Ids start at `1`, `0` is reserved for frames that don't belong to a request such as heartbeats.
+    pub fn next_request_id(&mut self) -> u64 {
+        self.last_request_id = self.last_request_id.wrapping_add(1).max(1);
+        self.request_id = self.last_request_id;
+        self.request_id
+    }
+
     #[tracing::instrument(level = "debug", skip(writer))]
     pub async fn write_command<W>(&mut self, writer: &mut W, command: Command) -> io::Result<()>
     where
         W: AsyncWrite + Unpin,
     {
-        self.write_message(writer, command, None::<&()>, MessageFlags::NONE)
+        self.write_message(
+            writer,
+            command,
+            None::<&()>,
+            MessageFlags::NONE,
+            self.request_id,
+        )
+        .await
+    }
+
+    /// Sends a keepalive heartbeat, the peer is expected to answer with [`Command::HeartbeatAck`].
+    #[tracing::instrument(level = "trace", skip(writer))]
+    pub async fn write_heartbeat<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        self.write_message(writer, Command::Heartbeat, None::<&()>, MessageFlags::NONE, 0)
             .await
     }
 
+    /// Acknowledges a heartbeat received from the peer.
+    #[tracing::instrument(level = "trace", skip(writer))]
+    pub async fn write_heartbeat_ack<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        self.write_message(
+            writer,
+            Command::HeartbeatAck,
+            None::<&()>,
+            MessageFlags::NONE,
+            0,
+        )
+        .await
+    }
+
     #[tracing::instrument(level = "debug", skip(writer))]
     pub async fn write_command_with_payload<W, T: Encode + Debug>(
         &mut self,
@@ -44,7 +249,7 @@ impl Protocol {
         W: AsyncWrite + Unpin,
     {
         let flags = flags | MessageFlags::HAS_PAYLOAD;
-        self.write_message(writer, command, Some(payload), flags)
+        self.write_message(writer, command, Some(payload), flags, self.request_id)
             .await
     }
 
@@ -55,25 +260,31 @@ impl Protocol {
         command: Command,
         payload: Option<&T>,
         flags: MessageFlags,
+        request_id: u64,
     ) -> io::Result<()>
     where
         W: AsyncWrite + Unpin,
     {
         // Serialize payload first to get length
         let payload_data = if let Some(payload) = payload {
-            let config = bincode::config::standard();
-            bincode::encode_to_vec(payload, config)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            Self::encode_payload(payload)?
         } else {
             Vec::new()
         };
 
+        let flags = if self.checksums && !payload_data.is_empty() {
+            flags | MessageFlags::CHECKSUMMED
+        } else {
+            flags
+        };
+
         let header = MessageHeader {
             version: self.version,
             command,
             payload_length: payload_data.len() as u32,
             sequence_number: self.next_sequence(),
             flags,
+            request_id,
         };
 
         // Create a cursor for writing the header
@@ -88,6 +299,11 @@ impl Protocol {
         // Write payload if present
         if !payload_data.is_empty() {
             writer.write_all(&payload_data).await?;
+
+            if flags.contains(MessageFlags::CHECKSUMMED) {
+                let checksum = crc32fast::hash(&payload_data);
+                writer.write_all(&checksum.to_be_bytes()).await?;
+            }
         }
 
         writer.flush().await?;
@@ -104,6 +320,14 @@ impl Protocol {
     ///
     /// Returns an error if the magic bytes are invalid, if header or payload deserialization fails, or if I/O operations fail.
     ///
+    /// When the reader is at its end before the message starts this is an [`io::ErrorKind::UnexpectedEof`]
+    /// error, the peer disconnected cleanly. When it ends after the message started the error is an
+    /// [`io::ErrorKind::InvalidData`] error carrying a [`TruncatedMessage`]. A checksummed payload
+    /// that doesn't match its checksum is an [`io::ErrorKind::InvalidData`] error carrying a
+    /// [`ChecksumMismatch`]. A header of another protocol version is an
+    /// [`io::ErrorKind::InvalidData`] error carrying a [`ProtocolVersionMismatch`], the rest of the
+    /// message is left unread since its layout is unknown.
+    ///
     /// # Examples
     ///
     /// ```
@@ -124,8 +348,12 @@ impl Protocol {
     where
         R: AsyncRead + Unpin,
     {
-        let mut magic = [0u8; 4];
-        reader.read_exact(&mut magic).await?;
+        let mut magic = [0u8; MessageHeader::MAGIC_LEN];
+        // Running out of input before the first byte is a clean disconnect between messages
+        if reader.read(&mut magic[..1]).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        read_message_part(reader, &mut magic[1..]).await?;
 
         if &magic != b"NEX\0" {
             return Err(io::Error::new(
@@ -134,10 +362,21 @@ impl Protocol {
             ));
         }
 
-        let mut header_bytes = vec![0u8; size_of::<MessageHeader>() - 2]; // Subtract magic bytes
-        reader.read_exact(&mut header_bytes).await?;
+        let mut header_bytes = [0u8; MessageHeader::ENCODED_LEN - MessageHeader::MAGIC_LEN];
+
+        // The version comes first, check it before reading a header that may be laid out differently
+        read_message_part(reader, &mut header_bytes[..2]).await?;
+        let version = u16::from_be_bytes([header_bytes[0], header_bytes[1]]);
+        if version != self.version {
+            return Err(ProtocolVersionMismatch {
+                expected: self.version,
+                found: version,
+            }
+            .into());
+        }
+        read_message_part(reader, &mut header_bytes[2..]).await?;
 
-        let mut full_header = Vec::with_capacity(size_of::<MessageHeader>());
+        let mut full_header = Vec::with_capacity(MessageHeader::ENCODED_LEN);
         full_header.extend_from_slice(&magic);
         full_header.extend_from_slice(&header_bytes);
 
@@ -146,7 +385,18 @@ impl Protocol {
 
         let payload = if header.flags.contains(MessageFlags::HAS_PAYLOAD) {
             let mut payload = vec![0u8; header.payload_length as usize];
-            reader.read_exact(&mut payload).await?;
+            read_message_part(reader, &mut payload).await?;
+
+            if header.flags.contains(MessageFlags::CHECKSUMMED) && !payload.is_empty() {
+                let mut checksum = [0u8; 4];
+                read_message_part(reader, &mut checksum).await?;
+
+                let expected = u32::from_be_bytes(checksum);
+                let actual = crc32fast::hash(&payload);
+                if expected != actual {
+                    return Err(ChecksumMismatch { expected, actual }.into());
+                }
+            }
 
             Some(payload)
         } else {
@@ -156,10 +406,32 @@ impl Protocol {
         Ok((header, payload))
     }
 
+    /// Encodes `payload` the way it is sent in a message, prefixed with [`PAYLOAD_VERSION`].
+    ///
+    /// A payload encoding to nothing stays empty, there is no schema to version.
+    pub fn encode_payload<T: Encode>(payload: &T) -> io::Result<Vec<u8>> {
+        let config = bincode::config::standard();
+        let encoded = bincode::encode_to_vec(payload, config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        if encoded.is_empty() {
+            return Ok(encoded);
+        }
+
+        let mut payload = Vec::with_capacity(encoded.len() + 1);
+        payload.push(PAYLOAD_VERSION);
+        payload.extend_from_slice(&encoded);
+
+        Ok(payload)
+    }
+
     /// Decodes a payload byte slice into an optional value of type `T`.
     ///
     /// Returns `Ok(Some(data))` if the payload is non-empty and successfully decoded, `Ok(None)` if the payload is empty, or an error if decoding fails.
     ///
+    /// The payload must start with the [`PAYLOAD_VERSION`] written by [`Protocol::encode_payload`],
+    /// a payload of another version is rejected with a [`PayloadVersionMismatch`] before decoding.
+    ///
     /// # Type Parameters
     ///
     /// * `T` - The type to decode the payload into. Must implement `Decode<()>`.
@@ -175,7 +447,15 @@ impl Protocol {
     pub fn read_payload<T: Decode<()>>(payload: &[u8]) -> io::Result<Option<T>> {
         let config = bincode::config::standard();
 
-        if !payload.is_empty() {
+        if let Some((&version, payload)) = payload.split_first() {
+            if version != PAYLOAD_VERSION {
+                return Err(PayloadVersionMismatch {
+                    expected: PAYLOAD_VERSION,
+                    found: version,
+                }
+                .into());
+            }
+
             let (data, size) = match bincode::decode_from_slice(payload, config) {
                 Ok(data) => data,
                 Err(e) => {
@@ -172,6 +172,7 @@ pub async fn create_service_with_dependencies(
             service_id: main_service.id,
             dependent_service_id: dep_service.id,
             tunnel_enabled: false,
+            optional: false,
         };
         dependency_repo.save(&mut dependency).await?;
     }
@@ -190,6 +190,9 @@ pub fn create_test_add_service_command(
         None,
         None,
         None,
+        Default::default(),
+        None,
+        Default::default(),
     )
 }
 
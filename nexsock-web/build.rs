@@ -1,6 +1,8 @@
 mod build_src;
 
-use build_src::{compile_typescript, create_tera_env, BuildError};
+use build_src::{
+    compile_typescript, create_tera_env, precompress_assets, report_bundle_sizes, BuildError,
+};
 
 fn main() {
     miette::set_hook(Box::new(|_| {
@@ -28,15 +30,8 @@ fn main() {
         #[cfg(debug_assertions)]
         println!("cargo:warning=Build error: {err:#?}");
 
-        if !matches!(err, BuildError::TypeScriptDiagnostic(..)) {
-            let mut output = String::new();
-
-            miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::none())
-                .render_report(&mut output, &err)
-                .expect("Failed to render miette report");
-
-            eprintln!("{output}");
-        }
+        // Rendered by the hook installed above, with the highlighted source of TypeScript errors
+        eprintln!("{:?}", miette::Report::new(err));
 
         panic!();
     }
@@ -46,9 +41,15 @@ fn run() -> Result<(), BuildError> {
     // Compile TypeScript/TSX first
     compile_typescript()?;
 
+    // Check the bundles against their size budget
+    report_bundle_sizes()?;
+
     // Create Tera environment
     create_tera_env()?;
 
+    // Compress the assets, including the freshly compiled TypeScript
+    precompress_assets()?;
+
     #[cfg(debug_assertions)]
     println!("cargo:warning=Template compilation successful");
 
@@ -0,0 +1,122 @@
+//! Sizes of the bundled assets and the budget they are checked against.
+//!
+//! Only depends on `std`, as the crate includes it in its tests to cover the build script.
+
+use std::fmt::Write;
+use std::path::Path;
+
+/// The budget of a single bundle if `NEXSOCK_WEB_BUNDLE_BUDGET` isn't set.
+pub const DEFAULT_BUDGET: u64 = 256 * 1024;
+
+/// A bundled asset and its size in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetSize {
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// The size of every bundle, sorted by path.
+#[derive(Debug)]
+pub struct BundleReport {
+    pub assets: Vec<AssetSize>,
+    pub total: u64,
+}
+
+impl BundleReport {
+    pub fn new(mut assets: Vec<AssetSize>) -> Self {
+        assets.sort_by(|a, b| a.path.cmp(&b.path));
+        let total = assets.iter().map(|asset| asset.bytes).sum();
+
+        Self { assets, total }
+    }
+
+    /// The bundles larger than `budget` bytes.
+    pub fn over_budget(&self, budget: u64) -> impl Iterator<Item = &AssetSize> {
+        self.assets.iter().filter(move |asset| asset.bytes > budget)
+    }
+
+    /// Renders the report as a table with a line per bundle and the total.
+    pub fn render(&self) -> String {
+        let width = self
+            .assets
+            .iter()
+            .map(|asset| asset.path.len())
+            .max()
+            .unwrap_or_default()
+            .max("total".len());
+
+        let mut report = String::new();
+        for asset in &self.assets {
+            let _ = writeln!(
+                report,
+                "{:<width$}  {}",
+                asset.path,
+                format_size(asset.bytes)
+            );
+        }
+        let _ = writeln!(report, "{:<width$}  {}", "total", format_size(self.total));
+
+        report
+    }
+}
+
+/// Whether `path` is a bundle produced by the build, rather than a source map or a compressed copy.
+pub fn is_bundle(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("js" | "css")
+    )
+}
+
+/// Formats `bytes` in KiB with one decimal, e.g. `12.5 KiB`.
+pub fn format_size(bytes: u64) -> String {
+    format!("{:.1} KiB", bytes as f64 / 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(path: &str, bytes: u64) -> AssetSize {
+        AssetSize {
+            path: path.to_string(),
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_report_sums_and_sorts_assets() {
+        let report = BundleReport::new(vec![
+            asset("js/main.js", 33_880),
+            asset("js/main.css", 5_408),
+            asset("style.css", 18_805),
+        ]);
+
+        assert_eq!(report.total, 58_093);
+        assert_eq!(report.assets[0].path, "js/main.css");
+        assert_eq!(report.assets[2].path, "style.css");
+
+        assert_eq!(
+            report.render(),
+            "js/main.css  5.3 KiB\njs/main.js   33.1 KiB\nstyle.css    18.4 KiB\ntotal        56.7 KiB\n"
+        );
+    }
+
+    #[test]
+    fn test_bundles_over_budget() {
+        let report = BundleReport::new(vec![asset("small.js", 1_024), asset("large.js", 4_096)]);
+
+        let over: Vec<_> = report.over_budget(2_048).collect();
+        assert_eq!(over, [&asset("large.js", 4_096)]);
+        assert_eq!(report.over_budget(4_096).count(), 0);
+        assert_eq!(report.over_budget(DEFAULT_BUDGET).count(), 0);
+    }
+
+    #[test]
+    fn test_only_bundles_are_counted() {
+        assert!(is_bundle(Path::new("public/js/main.js")));
+        assert!(is_bundle(Path::new("public/style.css")));
+        assert!(!is_bundle(Path::new("public/js/main.js.map")));
+        assert!(!is_bundle(Path::new("public/js/main.js.br")));
+    }
+}
@@ -223,55 +223,6 @@ pub struct TypeScriptCompilationErrors {
     pub summary: Option<String>,
 }
 
-impl TypeScriptCompilationErrors {
-    /// Manually print each error with source code and highlighting
-    pub fn print_errors(&self) {
-        eprintln!("\n╭─[TypeScript Compilation Failed]");
-        eprintln!(
-            "│ Found {} error{}",
-            self.errors.len(),
-            if self.errors.len() == 1 { "" } else { "s" }
-        );
-        eprintln!("╰─");
-
-        for (i, error) in self.errors.iter().enumerate() {
-            eprintln!(
-                "\n╭─[Error {}/{}]─────────────────────",
-                i + 1,
-                self.errors.len()
-            );
-
-            if let Some(file) = &error.file {
-                eprintln!("│ File: {file}");
-                if let (Some(line), Some(column)) = (error.line, error.column) {
-                    eprintln!("│ Location: {line}:{column}");
-                }
-            }
-
-            if let Some(error_code) = &error.error_code {
-                eprintln!("│ Code: {error_code}");
-            }
-
-            eprintln!("│ Message: {}", error.message);
-            eprintln!("│");
-
-            // Show source context with highlighting
-            if let Some(context) = &error.additional_context {
-                eprintln!("│ Source:");
-                for line in context.lines() {
-                    if line.starts_with('→') {
-                        eprintln!("│ \x1b[91m{line}\x1b[0m"); // Red for error line
-                    } else {
-                        eprintln!("│ \x1b[90m{line}\x1b[0m"); // Gray for context
-                    }
-                }
-            }
-
-            eprintln!("╰─");
-        }
-    }
-}
-
 // Keep the original TypeScriptError structure for compatibility
 #[derive(Debug, Error)]
 #[error("{kind_str} error: {message}")]
@@ -503,7 +454,7 @@ impl TypeScriptError {
         self
     }
 
-    // Convert TypeScriptError to a proper Diagnostic for display
+    // Convert TypeScriptError to a proper Diagnostic, rendered by the miette hook of the build script
     pub fn into_diagnostic(self) -> Box<dyn miette::Diagnostic + Send + Sync + 'static> {
         match self.parsed_errors.len() {
             0 => {
@@ -516,49 +467,12 @@ impl TypeScriptError {
                     Some(self.kind_str),
                 ))
             }
-            1 => {
-                // Single error - print it with custom formatting and return it
-                let error = self.parsed_errors.into_iter().next().unwrap();
-
-                eprintln!("\n╭─[TypeScript Error]─────────────────────");
-                if let Some(file) = &error.file {
-                    eprintln!("│ File: {file}");
-                    if let (Some(line), Some(column)) = (error.line, error.column) {
-                        eprintln!("│ Location: {line}:{column}");
-                    }
-                }
-                if let Some(error_code) = &error.error_code {
-                    eprintln!("│ Code: {error_code}");
-                }
-                eprintln!("│ Message: {}", error.message);
-                eprintln!("│");
-
-                if let Some(context) = &error.additional_context {
-                    eprintln!("│ Source:");
-                    for line in context.lines() {
-                        if line.starts_with('→') {
-                            eprintln!("│ \x1b[91m{line}\x1b[0m"); // Red for error line
-                        } else {
-                            eprintln!("│ \x1b[90m{line}\x1b[0m"); // Gray for context
-                        }
-                    }
-                }
-                eprintln!("╰─");
-
-                Box::new(error)
-            }
-            _ => {
-                // Multiple errors - wrap them and print them with miette formatting
-                let compilation_errors = TypeScriptCompilationErrors {
-                    summary: self.error_summary,
-                    errors: self.parsed_errors,
-                };
-
-                // Print the detailed errors to stderr immediately
-                compilation_errors.print_errors();
-
-                Box::new(compilation_errors)
-            }
+            // A single error is rendered with its source, several as related diagnostics
+            1 => Box::new(self.parsed_errors.into_iter().next().unwrap()),
+            _ => Box::new(TypeScriptCompilationErrors {
+                summary: self.error_summary,
+                errors: self.parsed_errors,
+            }),
         }
     }
 }
@@ -586,6 +500,21 @@ pub enum BuildError {
     #[diagnostic(transparent)]
     TypeScriptDiagnostic(#[from] Box<dyn miette::Diagnostic + Send + Sync + 'static>),
 
+    #[error("Bundle '{asset}' is {size} bytes, over the budget of {budget} bytes")]
+    #[diagnostic(
+        code(build::bundle_budget),
+        help("Shrink the bundle or raise the budget through NEXSOCK_WEB_BUNDLE_BUDGET, in bytes")
+    )]
+    BundleBudgetExceeded {
+        asset: String,
+        size: u64,
+        budget: u64,
+    },
+
+    #[error("NEXSOCK_WEB_BUNDLE_BUDGET must be a number of bytes, got '{0}'")]
+    #[diagnostic(code(build::invalid_bundle_budget))]
+    InvalidBundleBudget(String),
+
     #[error("I/O error: {0}")]
     #[diagnostic(code(build::io_error))]
     IoError(#[from] std::io::Error),
@@ -601,3 +530,58 @@ impl From<TypeScriptError> for BuildError {
         BuildError::TypeScriptDiagnostic(error.into_diagnostic())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "const a = 1;\n\nconst port: number = \"8080\";\nconsole.log(port);\n";
+
+    #[test]
+    fn test_tsc_error_is_parsed_with_its_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.ts");
+        std::fs::write(&file, SOURCE).unwrap();
+
+        let stdout = format!(
+            "{}(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n",
+            file.display()
+        );
+        let error = TypeScriptError::new(
+            TypeScriptErrorKind::TypeChecking,
+            "TypeScript type checking failed".to_string(),
+        )
+        .with_output(Some(stdout), None);
+
+        assert_eq!(error.parsed_errors.len(), 1);
+
+        let parsed = &error.parsed_errors[0];
+        assert_eq!(parsed.file.as_deref(), Some(file.to_str().unwrap()));
+        assert_eq!(parsed.line, Some(3));
+        assert_eq!(parsed.column, Some(7));
+        assert_eq!(parsed.error_code.as_deref(), Some("TS2322"));
+        assert_eq!(
+            parsed.message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+        // The label points at `port` on the third line
+        let label = parsed.label.unwrap();
+        assert_eq!(
+            &SOURCE[label.offset()..label.offset() + label.len()],
+            "port"
+        );
+
+        let BuildError::TypeScriptDiagnostic(diagnostic) = BuildError::from(error) else {
+            panic!("TypeScript errors must convert to a diagnostic");
+        };
+
+        let mut report = String::new();
+        miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::unicode_nocolor())
+            .render_report(&mut report, diagnostic.as_ref())
+            .unwrap();
+
+        assert!(report.contains("[TS2322]"), "{report}");
+        assert!(report.contains("app.ts:3:7"), "{report}");
+        assert!(report.contains("const port: number"), "{report}");
+    }
+}
@@ -1,7 +1,11 @@
+pub mod bundle_size;
 pub mod error;
+pub mod precompress;
 pub mod templates;
+pub mod ts_cache;
 pub mod typescript;
 
 pub use error::BuildError;
+pub use precompress::*;
 pub use templates::*;
 pub use typescript::*;
@@ -0,0 +1,78 @@
+use crate::BuildError;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Extensions of the assets worth compressing, images and fonts already are.
+const COMPRESSIBLE_EXTENSIONS: [&str; 6] = ["css", "html", "js", "json", "map", "svg"];
+
+/// Assets smaller than this gain nothing from compression.
+const MIN_SIZE: u64 = 1024;
+
+/// Writes a gzip (`.gz`) and brotli (`.br`) compressed copy next to every compressible asset in
+/// `public`, so they get embedded and served without compressing on every request.
+///
+/// Copies newer than their asset are kept, so unchanged assets don't trigger another build.
+pub fn precompress_assets() -> Result<(), BuildError> {
+    precompress_dir(Path::new("public"))
+}
+
+fn precompress_dir(dir: &Path) -> Result<(), BuildError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            precompress_dir(&path)?;
+        } else if is_compressible(&path)? {
+            precompress_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_compressible(path: &Path) -> Result<bool, BuildError> {
+    let compressible = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| COMPRESSIBLE_EXTENSIONS.contains(&extension));
+
+    Ok(compressible && fs::metadata(path)?.len() >= MIN_SIZE)
+}
+
+fn precompress_file(path: &Path) -> Result<(), BuildError> {
+    let modified = fs::metadata(path)?.modified()?;
+    let is_stale = |extension: &str| -> Result<bool, BuildError> {
+        let compressed = compressed_path(path, extension);
+
+        Ok(!compressed.exists() || fs::metadata(compressed)?.modified()? < modified)
+    };
+
+    if !is_stale("gz")? && !is_stale("br")? {
+        return Ok(());
+    }
+
+    let data = fs::read(path)?;
+
+    let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    gzip.write_all(&data)?;
+    fs::write(compressed_path(path, "gz"), gzip.finish()?)?;
+
+    let mut brotli = Vec::new();
+    {
+        let mut encoder = brotli::CompressorWriter::new(&mut brotli, 4096, 11, 22);
+        encoder.write_all(&data)?;
+    }
+    fs::write(compressed_path(path, "br"), brotli)?;
+
+    Ok(())
+}
+
+/// `public/js/main.js` is compressed to `public/js/main.js.gz`.
+fn compressed_path(path: &Path, extension: &str) -> std::path::PathBuf {
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(".");
+    compressed.push(extension);
+
+    compressed.into()
+}
@@ -0,0 +1,222 @@
+//! Content-hash caching of the TypeScript build.
+//!
+//! bun bundles everything reachable from `src-ts/main.ts` in one go, so the bundle is built again
+//! if any input changed and skipped entirely otherwise. The manifest maps the hash of every input
+//! to the hashes of the artifacts built from them.
+//!
+//! Only depends on `std`, as the crate includes it in its tests to cover the build script.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::hash::{DefaultHasher, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Whether a build could be skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// No input changed and the artifacts are as they were built, the build was skipped.
+    Hit,
+    /// The build ran.
+    Miss,
+}
+
+/// The hashes of the inputs and the artifacts of the last build.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Manifest {
+    inputs: BTreeMap<PathBuf, u64>,
+    outputs: BTreeMap<PathBuf, u64>,
+}
+
+impl Manifest {
+    /// Reads the manifest written by [`Manifest::write`], `None` if it's missing or malformed.
+    fn read(path: &Path) -> Option<Self> {
+        let mut manifest = Self::default();
+
+        for line in std::fs::read_to_string(path).ok()?.lines() {
+            let mut fields = line.splitn(3, ' ');
+            let (kind, hash, file) = (fields.next()?, fields.next()?, fields.next()?);
+            let hash = u64::from_str_radix(hash, 16).ok()?;
+
+            match kind {
+                "input" => manifest.inputs.insert(file.into(), hash),
+                "output" => manifest.outputs.insert(file.into(), hash),
+                _ => return None,
+            };
+        }
+
+        Some(manifest)
+    }
+
+    fn write(&self, path: &Path) -> io::Result<()> {
+        let mut contents = String::new();
+
+        for (kind, files) in [("input", &self.inputs), ("output", &self.outputs)] {
+            for (file, hash) in files {
+                let _ = writeln!(contents, "{kind} {hash:016x} {}", file.display());
+            }
+        }
+
+        std::fs::write(path, contents)
+    }
+}
+
+fn hash_file(path: &Path) -> io::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&std::fs::read(path)?);
+
+    Ok(hasher.finish())
+}
+
+fn hash_files<'a>(
+    paths: impl IntoIterator<Item = &'a PathBuf>,
+) -> io::Result<BTreeMap<PathBuf, u64>> {
+    paths
+        .into_iter()
+        .map(|path| Ok((path.clone(), hash_file(path)?)))
+        .collect()
+}
+
+/// Runs `build` unless the manifest at `manifest_path` shows it already ran with the current
+/// `inputs` and its artifacts are unchanged since.
+///
+/// After a build, the existing files of `outputs` are recorded as its artifacts.
+pub fn cached_build<E: From<io::Error>>(
+    manifest_path: &Path,
+    inputs: &[PathBuf],
+    outputs: &[PathBuf],
+    build: impl FnOnce() -> Result<(), E>,
+) -> Result<CacheStatus, E> {
+    let input_hashes = hash_files(inputs)?;
+
+    if let Some(manifest) = Manifest::read(manifest_path) {
+        let outputs_unchanged = !manifest.outputs.is_empty()
+            && hash_files(manifest.outputs.keys()).is_ok_and(|hashes| hashes == manifest.outputs);
+
+        if manifest.inputs == input_hashes && outputs_unchanged {
+            return Ok(CacheStatus::Hit);
+        }
+    }
+
+    // A failed build must not leave a manifest of the previous one behind
+    let _ = std::fs::remove_file(manifest_path);
+
+    build()?;
+
+    let manifest = Manifest {
+        inputs: input_hashes,
+        outputs: hash_files(outputs.iter().filter(|path| path.exists()))?,
+    };
+    manifest.write(manifest_path)?;
+
+    Ok(CacheStatus::Miss)
+}
+
+/// Collects every file below `dir`, sorted so the inputs are listed the same way every build.
+pub fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct Project {
+        dir: tempfile::TempDir,
+        builds: Cell<usize>,
+    }
+
+    impl Project {
+        fn new() -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(dir.path().join("src")).unwrap();
+            std::fs::write(dir.path().join("src/main.ts"), "console.log('hello');").unwrap();
+
+            Self {
+                dir,
+                builds: Cell::new(0),
+            }
+        }
+
+        fn path(&self, file: &str) -> PathBuf {
+            self.dir.path().join(file)
+        }
+
+        /// "Compiles" `src` by concatenating its files into `main.js`.
+        fn build(&self) -> CacheStatus {
+            let mut inputs = Vec::new();
+            collect_files(&self.path("src"), &mut inputs).unwrap();
+
+            cached_build::<io::Error>(
+                &self.path("manifest.txt"),
+                &inputs,
+                &[self.path("main.js"), self.path("main.css")],
+                || {
+                    self.builds.set(self.builds.get() + 1);
+
+                    let bundle = inputs
+                        .iter()
+                        .map(std::fs::read_to_string)
+                        .collect::<io::Result<String>>()?;
+                    std::fs::write(self.path("main.js"), bundle)
+                },
+            )
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_unchanged_inputs_skip_the_build() {
+        let project = Project::new();
+
+        assert_eq!(project.build(), CacheStatus::Miss);
+        assert_eq!(project.builds.get(), 1);
+
+        assert_eq!(project.build(), CacheStatus::Hit);
+        assert_eq!(project.builds.get(), 1);
+    }
+
+    #[test]
+    fn test_changed_inputs_are_built() {
+        let project = Project::new();
+        project.build();
+
+        std::fs::write(project.path("src/main.ts"), "console.log('changed');").unwrap();
+        assert_eq!(project.build(), CacheStatus::Miss);
+
+        std::fs::write(project.path("src/util.ts"), "export {};").unwrap();
+        assert_eq!(project.build(), CacheStatus::Miss);
+
+        assert_eq!(project.builds.get(), 3);
+        assert_eq!(project.build(), CacheStatus::Hit);
+    }
+
+    #[test]
+    fn test_changed_or_missing_outputs_are_built() {
+        let project = Project::new();
+        project.build();
+
+        std::fs::write(project.path("main.js"), "tampered").unwrap();
+        assert_eq!(project.build(), CacheStatus::Miss);
+
+        std::fs::remove_file(project.path("main.js")).unwrap();
+        assert_eq!(project.build(), CacheStatus::Miss);
+
+        assert_eq!(project.builds.get(), 3);
+        assert!(project.path("main.js").exists());
+    }
+}
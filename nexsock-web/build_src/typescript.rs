@@ -1,6 +1,8 @@
+use super::bundle_size::{AssetSize, BundleReport, DEFAULT_BUDGET};
 use super::error::{TypeScriptError, TypeScriptErrorKind};
+use super::ts_cache::{cached_build, collect_files, CacheStatus};
 use crate::BuildError;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 pub fn ensure_node_modules_exists() -> Result<(), BuildError> {
@@ -288,13 +290,56 @@ pub fn compile_typescript() -> Result<(), BuildError> {
     // Generate component registry before compilation
     generate_component_registry()?;
 
-    check_types()?;
-
     if !Path::new("src-ts").exists() {
         println!("cargo:warning=No TypeScript source directory found, skipping TS compilation");
         return Ok(());
     }
 
+    let Some(out_dir) = std::env::var_os("OUT_DIR") else {
+        check_types()?;
+        return bundle_typescript();
+    };
+
+    let status = cached_build(
+        &Path::new(&out_dir).join("typescript_manifest.txt"),
+        &typescript_inputs()?,
+        &TYPESCRIPT_OUTPUTS.map(PathBuf::from),
+        || {
+            check_types()?;
+            bundle_typescript()
+        },
+    )?;
+
+    if status == CacheStatus::Hit {
+        println!("cargo:warning=TypeScript sources unchanged, skipping compilation");
+    }
+
+    Ok(())
+}
+
+/// The artifacts of [`bundle_typescript`], `main.css` only exists if the sources import CSS.
+const TYPESCRIPT_OUTPUTS: [&str; 3] = [
+    "public/js/main.js",
+    "public/js/main.js.map",
+    "public/js/main.css",
+];
+
+/// Every file the bundle is built from, the sources and the configuration of bun and TypeScript.
+fn typescript_inputs() -> Result<Vec<PathBuf>, BuildError> {
+    let mut inputs = Vec::new();
+    collect_files(Path::new("src-ts"), &mut inputs)?;
+
+    inputs.extend(
+        ["package.json", "tsconfig.json", "bun.lock"]
+            .map(PathBuf::from)
+            .into_iter()
+            .filter(|path| path.exists()),
+    );
+
+    Ok(inputs)
+}
+
+fn bundle_typescript() -> Result<(), BuildError> {
     // Build all TypeScript and TSX files
     let output = Command::new("bun")
         .args([
@@ -333,3 +378,66 @@ pub fn compile_typescript() -> Result<(), BuildError> {
     println!("cargo:warning=TypeScript/TSX bundling completed successfully");
     Ok(())
 }
+
+/// Writes the size of every bundle in `public` to `bundle_report.txt` in `OUT_DIR` and fails if
+/// one is larger than the budget, read in bytes from `NEXSOCK_WEB_BUNDLE_BUDGET`.
+pub fn report_bundle_sizes() -> Result<(), BuildError> {
+    println!("cargo:rerun-if-env-changed=NEXSOCK_WEB_BUNDLE_BUDGET");
+
+    let budget = match std::env::var("NEXSOCK_WEB_BUNDLE_BUDGET") {
+        Ok(budget) => budget
+            .trim()
+            .parse()
+            .map_err(|_| BuildError::InvalidBundleBudget(budget))?,
+        Err(_) => DEFAULT_BUDGET,
+    };
+
+    fn find_bundles(root: &Path, dir: &Path, assets: &mut Vec<AssetSize>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                find_bundles(root, &path, assets)?;
+            } else if super::bundle_size::is_bundle(&path) {
+                assets.push(AssetSize {
+                    path: path
+                        .strip_prefix(root)
+                        .unwrap_or(&path)
+                        .to_string_lossy()
+                        .replace('\\', "/"),
+                    bytes: std::fs::metadata(&path)?.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    let mut assets = Vec::new();
+    find_bundles(Path::new("public"), Path::new("public"), &mut assets)?;
+
+    let report = BundleReport::new(assets);
+
+    if let Some(out_dir) = std::env::var_os("OUT_DIR") {
+        std::fs::write(
+            Path::new(&out_dir).join("bundle_report.txt"),
+            report.render(),
+        )?;
+    }
+
+    #[cfg(debug_assertions)]
+    println!(
+        "cargo:warning=Bundled {} assets, {} in total",
+        report.assets.len(),
+        super::bundle_size::format_size(report.total)
+    );
+
+    if let Some(asset) = report.over_budget(budget).next() {
+        return Err(BuildError::BundleBudgetExceeded {
+            asset: asset.path.clone(),
+            size: asset.bytes,
+            budget,
+        });
+    }
+
+    Ok(())
+}
@@ -1,13 +1,36 @@
 use crate::{error::WebError, state::AppState};
+use bincode::Encode;
 use deadpool::managed::Object;
 use nexsock_client::ClientManager;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::CommandPayload;
+use nexsock_protocol::traits::ServiceCommand;
+use std::fmt::Debug;
 
+/// Takes a connection to the daemon from the pool, failing with
+/// [`WebError::DaemonCommunication`] if the daemon can't be reached.
 pub async fn get_client(state: &AppState) -> Result<Object<ClientManager>, WebError> {
     state.get().await.map_err(|error| {
-        WebError::internal(
-            format!("Failed to get daemon client: {error}"),
-            "daemon_client",
-            None::<std::io::Error>,
-        )
+        WebError::daemon_communication("get_client", None, "unreachable", error.to_string())
     })
 }
+
+/// Runs `command` for the `operation` on `service`, a command the daemon fails is reported as a
+/// [`WebError::ServiceOperation`].
+pub async fn execute_service_command<C>(
+    state: &AppState,
+    operation: &str,
+    service: &ServiceRef,
+    command: C,
+) -> Result<CommandPayload, WebError>
+where
+    C: ServiceCommand,
+    C::Input: Encode + Debug,
+{
+    let mut client = get_client(state).await?;
+
+    client
+        .execute_command(command)
+        .await
+        .map_err(|error| WebError::service_operation(operation, service.to_string(), None, error))
+}
@@ -1 +1,2 @@
 pub mod service;
+pub(crate) mod v1;
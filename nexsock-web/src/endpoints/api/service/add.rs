@@ -12,16 +12,8 @@ pub async fn add_service_endpoint(
     State(ref state): State<AppState>,
     Json(payload): Json<AddServicePayload>,
 ) -> Result<impl IntoResponse> {
-    let service_name = payload.name.clone();
-
-    add_service(state, payload).await.map_err(|error| {
+    add_service(state, payload).await.inspect_err(|error| {
         error!(error = %error, "failed to add service");
-
-        crate::error::WebError::internal(
-            format!("Failed to add service '{service_name}': {error}"),
-            "add_service",
-            Some(error),
-        )
     })?;
 
     Ok(StatusCode::CREATED)
@@ -13,32 +13,150 @@ pub struct ServiceListParams {
     partial: Option<bool>,
 }
 
+/// Renders the list of services, as the full page or only the list for HTMX requests.
+///
+/// Rendered lists are cached until the web UI changes a service, see [`RenderCache`].
+///
+/// [`RenderCache`]: crate::render_cache::RenderCache
 pub async fn get_services(
     State(ref state): State<AppState>,
     Query(params): Query<ServiceListParams>,
     headers: HeaderMap,
 ) -> crate::Result<Html<Vec<u8>>> {
-    let services_list = list_services(state).await?;
-
-    let mut buff = Vec::new();
-
     // Check if this is an HTMX request or explicit partial request
     let is_htmx_request = headers.get("HX-Request").is_some();
     let is_partial = params.partial.unwrap_or(false) || is_htmx_request;
 
+    let cache_key = if is_partial {
+        "services/partial"
+    } else {
+        "services"
+    };
+    if let Some(cached) = state.render_cache().get(cache_key) {
+        return Ok(Html(cached));
+    }
+
+    // Read before listing, so a change made while rendering isn't cached as the current state
+    let version = state.render_cache().version();
+    let services_list = list_services(state).await?;
+
+    let mut buff = Vec::new();
+
     if is_partial {
         // Return only the services list content
         services_list.render_to(&TERA, None, &mut buff)?;
-        return Ok(Html(buff));
-    }
+    } else {
+        // Otherwise, return the full page with services
+        let page = Page::new("Service Management".to_string());
+        let mut context = tera::Context::new();
+        context.insert("services_list", &services_list);
+        context.insert("is_service_page", &false);
 
-    // Otherwise, return the full page with services
-    let page = Page::new("Service Management".to_string());
-    let mut context = tera::Context::new();
-    context.insert("services_list", &services_list);
-    context.insert("is_service_page", &false);
+        page.render_to(&TERA, Some(context), &mut buff)?;
+    }
 
-    page.render_to(&TERA, Some(context), &mut buff)?;
+    state
+        .render_cache()
+        .insert(cache_key, version, buff.clone());
 
     Ok(Html(buff))
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::endpoints::api::service::start::start_service;
+    use crate::test_daemon::{fake_daemon_state, Received};
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use axum::routing::{get, post};
+    use axum::Router;
+    use nexsock_protocol::commands::error::ErrorPayload;
+    use nexsock_protocol::commands::list_services::{ListServicesResponse, ServiceInfo};
+    use nexsock_protocol::commands::service_status::ServiceState;
+    use nexsock_protocol::commands::{Command, CommandPayload};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tower::ServiceExt;
+
+    static STARTED: AtomicBool = AtomicBool::new(false);
+
+    /// A daemon managing only the `webapp` service, which is running once it was started.
+    fn respond(command: Command) -> Result<CommandPayload, ErrorPayload> {
+        match command {
+            Command::StartService => {
+                STARTED.store(true, Ordering::SeqCst);
+
+                Ok(CommandPayload::Empty)
+            }
+            _ => Ok(CommandPayload::ListServices(ListServicesResponse {
+                services: vec![ServiceInfo {
+                    id: 1,
+                    name: "webapp".to_string(),
+                    state: if STARTED.load(Ordering::SeqCst) {
+                        ServiceState::Running
+                    } else {
+                        ServiceState::Failed
+                    },
+                    port: 8080,
+                    has_dependencies: false,
+                    labels: Default::default(),
+                }],
+            })),
+        }
+    }
+
+    fn listed(received: &Received) -> usize {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(command, _)| matches!(command, Command::ListServices))
+            .count()
+    }
+
+    async fn send(app: &Router, request: Request<Body>) -> String {
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn list_request() -> Request<Body> {
+        Request::get("/services?partial=true")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_start_invalidates_cached_services_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, received) = fake_daemon_state(dir.path(), respond);
+        let app = Router::new()
+            .route("/services", get(get_services))
+            .route("/services/{service_id}/start", post(start_service))
+            .with_state(state.clone());
+
+        let body = send(&app, list_request()).await;
+        assert!(body.contains("Failed"), "{body}");
+
+        // The second request is served from the cache without asking the daemon
+        assert_eq!(send(&app, list_request()).await, body);
+        assert_eq!(listed(&received), 1);
+
+        let version = state.render_cache().version();
+        send(
+            &app,
+            Request::post("/services/webapp/start")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert!(state.render_cache().version() > version);
+
+        let body = send(&app, list_request()).await;
+        assert!(body.contains("Running"), "{body}");
+        assert_eq!(listed(&received), 2);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::WebError;
 use crate::services::nexsock_services::start;
 use crate::state::AppState;
 use axum::body::Bytes;
@@ -16,7 +17,7 @@ pub(crate) async fn start_service(
 
     // Parse the form data manually to handle multiple env_key/env_value pairs
     let body_str = String::from_utf8_lossy(&body);
-    let env_vars = parse_env_vars_from_form(&body_str);
+    let env_vars = parse_env_vars_from_form(&body_str)?;
 
     tracing::debug!("Parsed environment variables: {:?}", env_vars);
 
@@ -27,7 +28,9 @@ pub(crate) async fn start_service(
 
 /// Parse environment variables from form data
 /// Handles the format: env_key=KEY1&env_value=VALUE1&env_key=KEY2&env_value=VALUE2
-fn parse_env_vars_from_form(body: &str) -> HashMap<String, String> {
+///
+/// Pairs with an empty key are skipped, any other key must be a valid variable name.
+fn parse_env_vars_from_form(body: &str) -> crate::Result<HashMap<String, String>> {
     let mut env_vars = HashMap::new();
     let mut keys = Vec::new();
     let mut values = Vec::new();
@@ -46,9 +49,21 @@ fn parse_env_vars_from_form(body: &str) -> HashMap<String, String> {
 
     // Pair up keys and values
     for (key, value) in keys.iter().zip(values.iter()) {
-        if !key.is_empty() {
-            env_vars.insert(key.clone(), value.clone());
+        if key.is_empty() {
+            continue;
         }
+
+        if !is_valid_env_key(key) {
+            return Err(WebError::form_validation(
+                "env_key",
+                key.as_str(),
+                "a letter or `_` followed by letters, digits or `_`",
+                Some(body.to_string()),
+                None,
+            ));
+        }
+
+        env_vars.insert(key.clone(), value.clone());
     }
 
     tracing::debug!("Form body: {}", body);
@@ -56,5 +71,44 @@ fn parse_env_vars_from_form(body: &str) -> HashMap<String, String> {
     tracing::debug!("Values: {:?}", values);
     tracing::debug!("Final env_vars: {:?}", env_vars);
 
-    env_vars
+    Ok(env_vars)
+}
+
+/// Whether `key` can be used as an environment variable name, e.g. `DATABASE_URL`.
+fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_vars_parsed_from_form() {
+        let env_vars = parse_env_vars_from_form(
+            "env_key=PORT&env_value=8080&env_key=&env_value=ignored&env_key=_URL&env_value=a%3Db",
+        )
+        .unwrap();
+
+        assert_eq!(env_vars.len(), 2);
+        assert_eq!(env_vars["PORT"], "8080");
+        assert_eq!(env_vars["_URL"], "a=b");
+    }
+
+    #[test]
+    fn test_invalid_env_keys_rejected() {
+        for key in ["1PORT", "MY-VAR", "WITH%20SPACE", "%3D"] {
+            let body = format!("env_key={key}&env_value=x");
+
+            assert!(
+                parse_env_vars_from_form(&body).is_err(),
+                "`{key}` was accepted"
+            );
+        }
+    }
 }
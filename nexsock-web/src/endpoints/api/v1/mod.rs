@@ -0,0 +1,245 @@
+//! Versioned JSON REST API over the daemon commands.
+//!
+//! Every endpoint runs a single daemon command and answers with JSON:
+//!
+//! | Route                                     | Command            | Success                 |
+//! |-------------------------------------------|--------------------|-------------------------|
+//! | `GET /api/v1/services`                    | `ListServices`     | `200` with the services |
+//! | `POST /api/v1/services`                   | `AddService`       | `201`                   |
+//! | `GET /api/v1/services/{service}`          | `GetServiceStatus` | `200` with the status   |
+//! | `DELETE /api/v1/services/{service}`       | `RemoveService`    | `204`                   |
+//! | `POST /api/v1/services/{service}:start`   | `StartService`     | `204`                   |
+//! | `POST /api/v1/services/{service}:stop`    | `StopService`      | `204`                   |
+//! | `POST /api/v1/services/{service}:restart` | `RestartService`   | `204`                   |
+//!
+//! `{service}` is a service id or name. `:start` and `:restart` take an optional
+//! `{"env_vars": {...}}` body. Failures are answered with `{"error": {"message": "..."}}`: `400`
+//! for malformed requests, `404` for unknown actions, `502` when the daemon fails the command and
+//! `503` when the daemon can't be reached.
+//!
+//! The OpenAPI description of these routes is served at `GET /api/openapi.json`.
+
+mod error;
+mod openapi;
+mod services;
+
+use crate::state::AppState;
+use axum::routing::get;
+use axum::Router;
+
+/// The routes of the REST API, to be merged into the app.
+pub(crate) fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/v1/services",
+            get(services::list_services).post(services::add_service),
+        )
+        .route(
+            "/api/v1/services/{service}",
+            get(services::get_service)
+                .delete(services::remove_service)
+                .post(services::service_action),
+        )
+        .route("/api/openapi.json", get(openapi::openapi_json))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_daemon::{fake_daemon_state, Received};
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request, StatusCode};
+    use nexsock_protocol::commands::error::ErrorPayload;
+    use nexsock_protocol::commands::list_services::{ListServicesResponse, ServiceInfo};
+    use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+    use nexsock_protocol::commands::service_status::ServiceState;
+    use nexsock_protocol::commands::{Command, CommandPayload};
+    use nexsock_protocol::protocol::Protocol;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use tower::ServiceExt;
+
+    /// Builds the API against a fake daemon answering with `respond`.
+    fn api(
+        dir: &Path,
+        respond: fn(Command) -> Result<CommandPayload, ErrorPayload>,
+    ) -> (Router, Received) {
+        let (state, received) = fake_daemon_state(dir, respond);
+
+        (router().with_state(state), received)
+    }
+
+    async fn send(app: Router, request: Request<Body>) -> (StatusCode, Option<Value>) {
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        let json = (!body.is_empty()).then(|| serde_json::from_slice(&body).unwrap());
+
+        (status, json)
+    }
+
+    fn post(uri: &str, body: impl Into<Body>) -> Request<Body> {
+        Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.into())
+            .unwrap()
+    }
+
+    fn decode_start(payload: &Option<Vec<u8>>) -> StartServicePayload {
+        Protocol::read_payload(payload.as_deref().unwrap())
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_services_returns_service_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let (app, received) = api(dir.path(), |_| {
+            Ok(CommandPayload::ListServices(ListServicesResponse {
+                services: vec![ServiceInfo {
+                    id: 1,
+                    name: "webapp".to_string(),
+                    state: ServiceState::Running,
+                    port: 8080,
+                    has_dependencies: true,
+                    labels: BTreeMap::from([("team".to_string(), "web".to_string())]),
+                }],
+            }))
+        });
+
+        let request = Request::get("/api/v1/services").body(Body::empty()).unwrap();
+        let (status, body) = send(app, request).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            Some(json!({
+                "services": [{
+                    "id": 1,
+                    "name": "webapp",
+                    "state": "Running",
+                    "port": 8080,
+                    "has_dependencies": true,
+                    "labels": { "team": "web" },
+                }]
+            }))
+        );
+
+        let received = received.lock().unwrap();
+        assert!(matches!(received[..], [(Command::ListServices, _)]));
+    }
+
+    #[tokio::test]
+    async fn test_start_service_sends_env_vars() {
+        let dir = tempfile::tempdir().unwrap();
+        let (app, received) = api(dir.path(), |_| Ok(CommandPayload::Empty));
+
+        let request = post(
+            "/api/v1/services/webapp:start",
+            r#"{"env_vars": {"PORT": "8080"}}"#,
+        );
+        let (status, body) = send(app.clone(), request).await;
+
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert_eq!(body, None);
+
+        // The body is optional
+        let (status, _) = send(app, post("/api/v1/services/7:start", Body::empty())).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let received = received.lock().unwrap();
+        let [(Command::StartService, first), (Command::StartService, second)] = &received[..]
+        else {
+            panic!("expected two start commands");
+        };
+
+        let first = decode_start(first);
+        assert_eq!(first.service, ServiceRef::Name("webapp".to_string()));
+        assert_eq!(first.env_vars.get("PORT").map(String::as_str), Some("8080"));
+
+        let second = decode_start(second);
+        assert_eq!(second.service, ServiceRef::Id(7));
+        assert!(second.env_vars.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_start_service_reports_daemon_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let (app, _) = api(dir.path(), |_| {
+            Err(ErrorPayload {
+                code: 8,
+                message: "service `webapp` not found".to_string(),
+                details: None,
+            })
+        });
+
+        let (status, body) = send(app, post("/api/v1/services/webapp:start", "")).await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(
+            body,
+            Some(json!({
+                "error": { "message": "Server error 8: service `webapp` not found" }
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_service_rejects_bad_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let (app, received) = api(dir.path(), |_| Ok(CommandPayload::Empty));
+
+        let (status, body) = send(app.clone(), post("/api/v1/services/webapp:launch", "")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.unwrap()["error"]["message"].is_string());
+
+        let (status, _) = send(app, post("/api/v1/services/webapp:start", "{")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_openapi_lists_service_endpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let (app, received) = api(dir.path(), |_| Ok(CommandPayload::Empty));
+
+        let request = Request::get("/api/openapi.json").body(Body::empty()).unwrap();
+        let (status, body) = send(app, request).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let schema = body.unwrap();
+
+        let paths = schema["paths"].as_object().unwrap();
+        for path in [
+            "/api/v1/services",
+            "/api/v1/services/{service}",
+            "/api/v1/services/{service}:start",
+            "/api/v1/services/{service}:stop",
+            "/api/v1/services/{service}:restart",
+        ] {
+            assert!(paths.contains_key(path), "missing path `{path}`");
+        }
+
+        assert_eq!(
+            schema["paths"]["/api/v1/services"]["get"]["responses"]["200"]["content"]
+                ["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ListServicesResponse"
+        );
+        assert_eq!(
+            schema["paths"]["/api/v1/services/{service}"]["get"]["responses"]["200"]["content"]
+                ["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ServiceStatus"
+        );
+
+        let schemas = schema["components"]["schemas"].as_object().unwrap();
+        for model in ["ListServicesResponse", "ServiceInfo", "ServiceStatus", "ErrorResponse"] {
+            assert!(schemas.contains_key(model), "missing schema `{model}`");
+        }
+
+        // Serving the schema doesn't talk to the daemon
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
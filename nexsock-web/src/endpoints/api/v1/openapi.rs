@@ -0,0 +1,38 @@
+use super::error::ErrorResponse;
+use super::services::{self, StartBody};
+use axum::Json;
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::list_services::ListServicesResponse;
+use nexsock_protocol::commands::service_status::ServiceStatus;
+use utoipa::OpenApi;
+
+/// The OpenAPI description of the REST API, the payload schemas are derived from the protocol
+/// types the daemon speaks.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "nexsock", description = "Manage nexsock services over HTTP"),
+    paths(
+        services::list_services,
+        services::add_service,
+        services::get_service,
+        services::remove_service,
+        services::start_service,
+        services::stop_service,
+        services::restart_service,
+    ),
+    components(schemas(
+        ListServicesResponse,
+        ServiceStatus,
+        AddServicePayload,
+        StartBody,
+        ErrorResponse,
+    )),
+    tags((name = "services", description = "Managing and running services"))
+)]
+pub(crate) struct ApiDoc;
+
+/// `GET /api/openapi.json`
+#[tracing::instrument]
+pub(crate) async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
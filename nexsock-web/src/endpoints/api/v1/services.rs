@@ -0,0 +1,291 @@
+use super::error::{ApiError, ErrorResponse};
+use crate::state::AppState;
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use bincode::Encode;
+use nexsock_protocol::commands::add_service::{AddServiceCommand, AddServicePayload};
+use nexsock_protocol::commands::list_services::{ListServicesCommand, ListServicesResponse};
+use nexsock_protocol::commands::manage_service::{
+    RemoveServiceCommand, RestartServiceCommand, ServiceRef, StartServiceCommand,
+    StartServicePayload, StopServiceCommand,
+};
+use nexsock_protocol::commands::service_status::{GetServiceStatus, ServiceStatus};
+use nexsock_protocol::commands::CommandPayload;
+use nexsock_protocol::traits::ServiceCommand;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+type Result<T, E = ApiError> = std::result::Result<T, E>;
+
+/// The body accepted by the `:start` and `:restart` actions, the body itself is optional.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub(crate) struct StartBody {
+    /// Environment variables set for this run of the service.
+    env_vars: HashMap<String, String>,
+}
+
+/// `GET /api/v1/services`
+#[utoipa::path(
+    get,
+    path = "/api/v1/services",
+    tag = "services",
+    responses(
+        (status = 200, description = "Every managed service", body = ListServicesResponse),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub(crate) async fn list_services(
+    State(ref state): State<AppState>,
+) -> Result<Json<ListServicesResponse>> {
+    let payload = execute(state, ListServicesCommand::new()).await?;
+
+    expect_payload(payload).map(Json)
+}
+
+/// `POST /api/v1/services`, the body is the JSON form of [`AddServicePayload`].
+#[utoipa::path(
+    post,
+    path = "/api/v1/services",
+    tag = "services",
+    request_body = AddServicePayload,
+    responses(
+        (status = 201, description = "The service was added"),
+        (status = 400, description = "The body is not a valid service", body = ErrorResponse),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(state, body))]
+pub(crate) async fn add_service(
+    State(ref state): State<AppState>,
+    body: Bytes,
+) -> Result<StatusCode> {
+    let payload = parse_body::<AddServicePayload>(&body)?;
+
+    execute_change(state, AddServiceCommand::from(payload)).await?;
+
+    Ok(StatusCode::CREATED)
+}
+
+/// `GET /api/v1/services/{service}`
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service}",
+    tag = "services",
+    params(("service" = String, Path, description = "Id or name of the service")),
+    responses(
+        (status = 200, description = "The status of the service", body = ServiceStatus),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub(crate) async fn get_service(
+    State(ref state): State<AppState>,
+    Path(service): Path<String>,
+) -> Result<Json<ServiceStatus>> {
+    let service = parse_service_ref(&service)?;
+    let payload = execute(state, GetServiceStatus::new(service)).await?;
+
+    expect_payload(payload).map(Json)
+}
+
+/// `DELETE /api/v1/services/{service}`
+#[utoipa::path(
+    delete,
+    path = "/api/v1/services/{service}",
+    tag = "services",
+    params(("service" = String, Path, description = "Id or name of the service")),
+    responses(
+        (status = 204, description = "The service was removed"),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub(crate) async fn remove_service(
+    State(ref state): State<AppState>,
+    Path(service): Path<String>,
+) -> Result<StatusCode> {
+    let service = parse_service_ref(&service)?;
+
+    execute_change(state, RemoveServiceCommand::new(service, false, false)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /api/v1/services/{service}:{action}`, where the action is `start`, `stop` or `restart`.
+///
+/// The router can't match a parameter followed by a literal within one segment, so the whole
+/// segment is captured and split on its last `:`.
+#[tracing::instrument(skip(state, body))]
+pub(crate) async fn service_action(
+    State(ref state): State<AppState>,
+    Path(segment): Path<String>,
+    body: Bytes,
+) -> Result<StatusCode> {
+    let Some((service, action)) = segment.rsplit_once(':') else {
+        return Err(ApiError::not_found(format!(
+            "expected `{segment}:<action>`, one of `start`, `stop` or `restart`"
+        )));
+    };
+
+    let service = parse_service_ref(service)?;
+
+    match action {
+        "start" => start_service(state, service, &body).await,
+        "stop" => stop_service(state, service).await,
+        "restart" => restart_service(state, service, &body).await,
+        action => Err(ApiError::not_found(format!(
+            "unknown action `{action}`, expected `start`, `stop` or `restart`"
+        ))),
+    }
+}
+
+/// `POST /api/v1/services/{service}:start`
+#[utoipa::path(
+    post,
+    path = "/api/v1/services/{service}:start",
+    tag = "services",
+    params(("service" = String, Path, description = "Id or name of the service")),
+    request_body(content = Option<StartBody>, description = "Optional environment variables"),
+    responses(
+        (status = 204, description = "The service was started"),
+        (status = 400, description = "The body is not valid", body = ErrorResponse),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn start_service(
+    state: &AppState,
+    service: ServiceRef,
+    body: &[u8],
+) -> Result<StatusCode> {
+    let StartBody { env_vars } = parse_optional_body(body)?;
+    let payload = StartServicePayload {
+        service,
+        env_vars,
+        ..Default::default()
+    };
+
+    execute_change(state, StartServiceCommand::from(payload)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /api/v1/services/{service}:stop`
+#[utoipa::path(
+    post,
+    path = "/api/v1/services/{service}:stop",
+    tag = "services",
+    params(("service" = String, Path, description = "Id or name of the service")),
+    responses(
+        (status = 204, description = "The service was stopped"),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn stop_service(state: &AppState, service: ServiceRef) -> Result<StatusCode> {
+    execute_change(state, StopServiceCommand::new(service)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /api/v1/services/{service}:restart`
+#[utoipa::path(
+    post,
+    path = "/api/v1/services/{service}:restart",
+    tag = "services",
+    params(("service" = String, Path, description = "Id or name of the service")),
+    request_body(content = Option<StartBody>, description = "Optional environment variables"),
+    responses(
+        (status = 204, description = "The service was restarted"),
+        (status = 400, description = "The body is not valid", body = ErrorResponse),
+        (status = 502, description = "The daemon failed the command", body = ErrorResponse),
+        (status = 503, description = "The daemon can't be reached", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn restart_service(
+    state: &AppState,
+    service: ServiceRef,
+    body: &[u8],
+) -> Result<StatusCode> {
+    let StartBody { env_vars } = parse_optional_body(body)?;
+    let payload = StartServicePayload {
+        service,
+        env_vars,
+        ..Default::default()
+    };
+
+    execute_change(state, RestartServiceCommand::from(payload)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Runs `command` on a pooled daemon connection.
+async fn execute<C>(state: &AppState, command: C) -> Result<CommandPayload>
+where
+    C: ServiceCommand,
+    C::Input: Encode + Debug,
+{
+    let mut client = state.get().await.map_err(|error| {
+        ApiError::unavailable(format!("Failed to get daemon client: {error}"))
+    })?;
+
+    client
+        .execute_command(command)
+        .await
+        .map_err(|error| ApiError::bad_gateway(error.to_string()))
+}
+
+/// Like [`execute`], for commands changing the state of the daemon, so the cached pages get
+/// rendered again.
+async fn execute_change<C>(state: &AppState, command: C) -> Result<CommandPayload>
+where
+    C: ServiceCommand,
+    C::Input: Encode + Debug,
+{
+    let payload = execute(state, command).await?;
+    state.render_cache().invalidate();
+
+    Ok(payload)
+}
+
+/// Unwraps the response payload a command is expected to answer with.
+fn expect_payload<T>(payload: CommandPayload) -> Result<T>
+where
+    T: TryFrom<CommandPayload, Error = anyhow::Error>,
+{
+    T::try_from(payload).map_err(|error| ApiError::bad_gateway(error.to_string()))
+}
+
+fn parse_service_ref(service: &str) -> Result<ServiceRef> {
+    if service.is_empty() {
+        return Err(ApiError::bad_request("the service reference is empty"));
+    }
+
+    ServiceRef::from_str(service).map_err(|error| ApiError::bad_request(error.to_string()))
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    serde_json::from_slice(body)
+        .map_err(|error| ApiError::bad_request(format!("invalid request body: {error}")))
+}
+
+/// Like [`parse_body`], but an empty body is read as the default value.
+fn parse_optional_body<T: DeserializeOwned + Default>(body: &[u8]) -> Result<T> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+
+    parse_body(body)
+}
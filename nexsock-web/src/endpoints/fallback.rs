@@ -1,17 +1,30 @@
 use crate::embedded::Public;
 use crate::error::WebError;
 use axum::body::Body;
-use axum::http::{header, HeaderValue, Response};
+use axum::http::{header, HeaderMap, HeaderValue, Response};
 use axum::response::IntoResponse;
 
-pub async fn static_handler(uri: axum::http::Uri) -> Response<Body> {
+/// Encodings the build precompresses assets with, in order of preference, with the extension of
+/// the compressed copy.
+const PRECOMPRESSED: [(&str, &str); 2] = [("br", "br"), ("gzip", "gz")];
+
+/// Serves the embedded assets, preferring a copy precompressed at build time when the client
+/// accepts its encoding.
+pub async fn static_handler(uri: axum::http::Uri, headers: HeaderMap) -> Response<Body> {
     let path = uri.path().trim_start_matches('/');
 
     match Public::get(path) {
         Some(content) => {
             let mime = mime_guess::from_path(path).first_or_octet_stream();
 
-            Response::builder()
+            let precompressed = PRECOMPRESSED
+                .iter()
+                .filter(|(encoding, _)| accepts_encoding(&headers, encoding))
+                .find_map(|(encoding, extension)| {
+                    Public::get(&format!("{path}.{extension}")).map(|file| (*encoding, file))
+                });
+
+            let mut response = Response::builder()
                 .header(
                     header::CONTENT_TYPE,
                     HeaderValue::from_str(mime.as_ref()).unwrap(),
@@ -20,8 +33,17 @@ pub async fn static_handler(uri: axum::http::Uri) -> Response<Body> {
                     header::CACHE_CONTROL,
                     HeaderValue::from_static("public, max-age=3600, stale-while-revalidate=86400"),
                 )
-                .body(Body::from(content.data.to_vec()))
-                .unwrap()
+                .header(header::VARY, HeaderValue::from_static("accept-encoding"));
+
+            let data = match precompressed {
+                Some((encoding, file)) => {
+                    response = response.header(header::CONTENT_ENCODING, encoding);
+                    file.data
+                }
+                None => content.data,
+            };
+
+            response.body(Body::from(data.to_vec())).unwrap()
         }
         None => {
             // For true 404s (non-static files), return a proper 404 WebError
@@ -34,3 +56,92 @@ pub async fn static_handler(uri: axum::http::Uri) -> Response<Body> {
         }
     }
 }
+
+/// Whether the `Accept-Encoding` header lists `encoding` without disabling it through `q=0`.
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|accepted| {
+            let mut params = accepted.split(';').map(str::trim);
+            let name = params.next().unwrap_or_default();
+            let quality = params
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|quality| quality.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            name.eq_ignore_ascii_case(encoding) && quality > 0.0
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use std::io::Read;
+    use tower::ServiceExt;
+
+    async fn get_asset(path: &str, accept_encoding: Option<&str>) -> Response<Body> {
+        let app: Router = Router::new().route("/{*path}", get(static_handler));
+
+        let mut request = Request::get(path);
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_brotli_variant_served_when_accepted() {
+        let response = get_asset("/style.css", Some("gzip, br")).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/css");
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut decompressed = Vec::new();
+        brotli::Decompressor::new(&body[..], 4096)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+
+        assert_eq!(
+            decompressed,
+            Public::get("style.css").unwrap().data.as_ref()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_uncompressed_asset_served_without_accepted_encoding() {
+        for accept_encoding in [None, Some("identity"), Some("br;q=0")] {
+            let response = get_asset("/style.css", accept_encoding).await;
+
+            assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(body, Public::get("style.css").unwrap().data.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_accepts_encoding() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip;q=0.5, BR, deflate;q=0"),
+        );
+
+        assert!(accepts_encoding(&headers, "gzip"));
+        assert!(accepts_encoding(&headers, "br"));
+        assert!(!accepts_encoding(&headers, "deflate"));
+        assert!(!accepts_encoding(&headers, "zstd"));
+    }
+}
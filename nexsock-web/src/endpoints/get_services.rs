@@ -5,7 +5,7 @@ use crate::templates::TERA;
 use crate::traits::RenderTemplate;
 use axum::extract::{Path, Query, State};
 use axum::http::HeaderMap;
-use axum::response::Html;
+use axum::response::{Html, IntoResponse, Response};
 use nexsock_protocol::commands::manage_service::ServiceRef;
 use serde::Deserialize;
 use std::str::FromStr;
@@ -23,18 +23,24 @@ pub struct ServiceParams {
 /// Otherwise, returns the full page with navigation and layout.
 ///
 /// # Returns
-/// An HTML response containing the rendered Nexsock service.
+/// An HTML response containing the rendered Nexsock service, or the `404 Not Found` page if the
+/// daemon doesn't manage the service.
 ///
 /// # Errors
-/// Returns an error if the service reference is invalid, the service cannot be found, or rendering fails.
+/// Returns an error if the service reference is invalid, the daemon fails to look up the service, or rendering fails.
 pub async fn get_nexsock_service(
     State(ref state): State<AppState>,
     Path(service_ref): Path<String>,
     Query(params): Query<ServiceParams>,
     headers: HeaderMap,
-) -> crate::Result<Html<String>> {
+) -> crate::Result<Response> {
     let service_ref = ServiceRef::from_str(service_ref.as_str())?;
-    let service = find::find_service(state, service_ref.clone()).await?;
+    let service = match find::find_service(state, service_ref).await {
+        Ok(service) => service,
+        // This is a page, so a missing service gets the not found page whatever the client accepts
+        Err(error) if error.is_not_found() => return Ok(error.into_html_response()),
+        Err(error) => return Err(error),
+    };
 
     // Check if this is an HTMX request or explicit partial request
     let is_htmx_request = headers.get("HX-Request").is_some();
@@ -47,7 +53,7 @@ pub async fn get_nexsock_service(
         context.insert("is_service_page", &true);
 
         let rendered = TERA.render("service_page.html", &context)?;
-        return Ok(Html(rendered));
+        return Ok(Html(rendered).into_response());
     }
 
     // Otherwise, return a full page with the service content
@@ -56,5 +62,88 @@ pub async fn get_nexsock_service(
     context.insert("service", &service);
     context.insert("is_service_page", &true);
 
-    Ok(Html(page.render(&TERA, Some(context))?))
+    Ok(Html(page.render(&TERA, Some(context))?).into_response())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::middleware::render_html_errors;
+    use crate::test_daemon::fake_daemon_state;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use nexsock_protocol::commands::error::ErrorPayload;
+    use nexsock_protocol::commands::list_services::{ListServicesResponse, ServiceInfo};
+    use nexsock_protocol::commands::service_status::ServiceState;
+    use nexsock_protocol::commands::{Command, CommandPayload};
+    use tower::ServiceExt;
+
+    /// A daemon managing only the `webapp` service, whose status it fails to get.
+    fn respond(command: Command) -> Result<CommandPayload, ErrorPayload> {
+        match command {
+            Command::ListServices => Ok(CommandPayload::ListServices(ListServicesResponse {
+                services: vec![ServiceInfo {
+                    id: 1,
+                    name: "webapp".to_string(),
+                    state: ServiceState::Failed,
+                    port: 8080,
+                    has_dependencies: false,
+                    labels: Default::default(),
+                }],
+            })),
+            _ => Err(ErrorPayload {
+                code: 8,
+                message: "Service not found".to_string(),
+                details: None,
+            }),
+        }
+    }
+
+    /// Requests `uri` the way a browser navigating to the page does.
+    async fn get_page(uri: &str) -> (StatusCode, String) {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _) = fake_daemon_state(dir.path(), respond);
+        let app = Router::new()
+            .route("/services/{id}", get(get_nexsock_service))
+            .layer(axum::middleware::from_fn(render_html_errors))
+            .with_state(state);
+
+        let request = Request::get(uri)
+            .header(header::ACCEPT, "text/html")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_missing_service_renders_not_found_page() {
+        for uri in ["/services/404", "/services/missing"] {
+            let (status, body) = get_page(uri).await;
+
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert!(body.contains("not-found-container"), "{body}");
+            assert!(body.contains("was not found"), "{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_existing_service_is_a_server_error() {
+        // `webapp` exists, so the failed status is an error of the daemon and not a missing service
+        let (status, body) = get_page("/services/webapp").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!body.contains("not-found-container"), "{body}");
+    }
 }
@@ -1,4 +1,5 @@
 use crate::components::git_view::{GitBranchesView, GitLogView, GitSectionView};
+use crate::extractors::Json;
 use crate::services::nexsock_services::git;
 use crate::state::AppState;
 use crate::templates::TERA;
@@ -31,6 +32,58 @@ pub struct GitQuery {
     limit: Option<usize>,
 }
 
+/// A template of [`render_batch`], named after its `/api/templates/*` route with the query
+/// parameters of that route, e.g. `{"template": "git-log", "params": {"service": "api"}}`.
+#[derive(Deserialize)]
+#[serde(tag = "template", content = "params", rename_all = "kebab-case")]
+pub enum TemplateRequest {
+    EnvVarPair(EnvVarQuery),
+    ConfigSection(ServiceQuery),
+    ConfigModal(ServiceQuery),
+    ConfigModalContent(ServiceQuery),
+    GitSection(ServiceQuery),
+    GitStatus(serde_json::Value),
+    GitModal(ServiceQuery),
+    GitBranches(GitQuery),
+    GitLog(GitQuery),
+}
+
+/// Renders several templates in one request, answering with the rendered fragments in the order
+/// they were requested.
+///
+/// Fails as a whole if any of the templates fails to render.
+#[tracing::instrument(skip_all, err)]
+pub async fn render_batch(
+    State(state): State<AppState>,
+    Json(requests): Json<Vec<TemplateRequest>>,
+) -> Result<Json<Vec<String>>> {
+    let mut fragments = Vec::with_capacity(requests.len());
+
+    for request in requests {
+        let Html(fragment) = match request {
+            TemplateRequest::EnvVarPair(params) => env_var_pair(Query(params)).await?,
+            TemplateRequest::ConfigSection(params) => config_section(Query(params)).await?,
+            TemplateRequest::ConfigModal(params) => config_modal(Query(params)).await?,
+            TemplateRequest::ConfigModalContent(params) => {
+                config_modal_content(Query(params)).await?
+            }
+            TemplateRequest::GitSection(params) => {
+                git_section(State(state.clone()), Query(params)).await?
+            }
+            TemplateRequest::GitStatus(params) => git_status_template(Query(params)).await?,
+            TemplateRequest::GitModal(params) => git_modal(Query(params)).await?,
+            TemplateRequest::GitBranches(params) => {
+                git_branches(State(state.clone()), Query(params)).await?
+            }
+            TemplateRequest::GitLog(params) => git_log(State(state.clone()), Query(params)).await?,
+        };
+
+        fragments.push(fragment);
+    }
+
+    Ok(Json(fragments))
+}
+
 /// Returns HTML template for a new environment variable pair
 /// This is used by HTMX to dynamically add new environment variable inputs
 pub async fn env_var_pair(Query(params): Query<EnvVarQuery>) -> Result<Html<String>> {
@@ -146,18 +199,7 @@ pub async fn git_branches(
     })?;
     let include_remote = false; // Default to local branches for cleaner UI
 
-    let branches_response = git::list_branches(state, service_ref, include_remote)
-        .await
-        .map_err(|error| {
-            WebError::internal(
-                format!(
-                    "Git list branches failed for '{}': {}",
-                    params.service, error
-                ),
-                "git_list_branches",
-                None::<std::io::Error>,
-            )
-        })?;
+    let branches_response = git::list_branches(state, service_ref, include_remote).await?;
     let show_all = params.show_all.unwrap_or(false);
     let limit = params.limit.unwrap_or(10);
 
@@ -186,15 +228,7 @@ pub async fn git_log(
         )
     })?;
 
-    let log_response = git::get_log(state, service_ref, None, None)
-        .await
-        .map_err(|error| {
-            WebError::internal(
-                format!("Git log failed for '{}': {}", params.service, error),
-                "git_log",
-                None::<std::io::Error>,
-            )
-        })?;
+    let log_response = git::get_log(state, service_ref, None, None).await?;
     let show_all = params.show_all.unwrap_or(false);
     let limit = params.limit.unwrap_or(5);
 
@@ -220,3 +254,75 @@ pub async fn git_modal(Query(params): Query<ServiceQuery>) -> Result<Html<String
     let html = render_template_to_string(&TERA, "git-modal.html", &context)?;
     Ok(Html(html))
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_daemon::fake_daemon_state;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use nexsock_protocol::commands::error::ErrorPayload;
+    use nexsock_protocol::commands::{Command, CommandPayload};
+    use tower::ServiceExt;
+
+    fn respond(_: Command) -> std::result::Result<CommandPayload, ErrorPayload> {
+        Err(ErrorPayload {
+            code: 1,
+            message: "Unexpected command".to_string(),
+            details: None,
+        })
+    }
+
+    async fn post_batch(body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let dir = tempfile::tempdir().unwrap();
+        let (state, _) = fake_daemon_state(dir.path(), respond);
+        let app = Router::new()
+            .route("/api/templates/batch", post(render_batch))
+            .with_state(state);
+
+        let request = Request::post("/api/templates/batch")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_batch_renders_fragments_in_order() {
+        let (status, body) = post_batch(json!([
+            {"template": "env-var-pair", "params": {"key": "PORT", "value": "8080"}},
+            {"template": "config-section", "params": {"service": "webapp"}},
+        ]))
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+
+        let fragments = body.as_array().unwrap();
+        assert_eq!(fragments.len(), 2);
+
+        let env_var_pair = fragments[0].as_str().unwrap();
+        assert!(env_var_pair.contains("class=\"env-var-pair\""));
+        assert!(env_var_pair.contains("value=\"PORT\""));
+
+        let config_section = fragments[1].as_str().unwrap();
+        assert!(config_section.contains("id=\"config-section-webapp\""));
+    }
+
+    #[tokio::test]
+    async fn test_batch_rejects_unknown_templates() {
+        let (status, body) = post_batch(json!([
+            {"template": "env-var-pair", "params": {}},
+            {"template": "missing", "params": {}},
+        ]))
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "JSON_PARSE_ERROR");
+    }
+}
@@ -167,39 +167,33 @@ impl WebError {
         }
     }
 
-    /// Create a daemon communication error
-    pub fn daemon_communication<E>(
+    /// Create a daemon communication error, `source` may also be an `anyhow::Error` or a message
+    pub fn daemon_communication(
         operation: impl Into<String>,
         daemon_address: Option<String>,
         connection_state: impl Into<String>,
-        source: E,
-    ) -> Self
-    where
-        E: std::error::Error + Send + Sync + 'static,
-    {
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
         Self::DaemonCommunication {
             operation: operation.into(),
             daemon_address,
             connection_state: connection_state.into(),
-            source: Box::new(source),
+            source: source.into(),
         }
     }
 
-    /// Create a service operation error
-    pub fn service_operation<E>(
+    /// Create a service operation error, `source` may also be an `anyhow::Error` or a message
+    pub fn service_operation(
         operation: impl Into<String>,
         service_name: impl Into<String>,
         service_status: Option<String>,
-        source: E,
-    ) -> Self
-    where
-        E: std::error::Error + Send + Sync + 'static,
-    {
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
         Self::ServiceOperation {
             operation: operation.into(),
             service_name: service_name.into(),
             service_status,
-            source: Box::new(source),
+            source: source.into(),
         }
     }
 
@@ -220,7 +214,7 @@ impl WebError {
         }
     }
 
-    /// Create an not found error
+    /// Create a not found error
     pub fn not_found<E>(
         message: impl Into<String>,
         component: impl Into<String>,
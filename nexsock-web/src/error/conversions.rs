@@ -0,0 +1,17 @@
+use super::types::WebError;
+
+impl From<anyhow::Error> for WebError {
+    fn from(error: anyhow::Error) -> Self {
+        WebError::internal(
+            error.to_string(),
+            "anyhow_conversion",
+            None::<std::io::Error>,
+        )
+    }
+}
+
+impl From<tera::Error> for WebError {
+    fn from(error: tera::Error) -> Self {
+        WebError::template_render("unknown", None, None::<&serde_json::Value>, error)
+    }
+}
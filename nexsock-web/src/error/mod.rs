@@ -1,10 +1,10 @@
 #![allow(unused_imports)]
 
 mod constructors;
-mod legacy;
+mod conversions;
 mod response;
 mod span_utils;
 mod types;
 
-pub use legacy::*;
+pub use response::RenderableError;
 pub use types::*;
@@ -2,15 +2,63 @@ use super::types::WebError;
 use crate::templates::TERA;
 use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
 use serde_json::json;
+use std::sync::Arc;
 use tera::Context;
 
+/// The body of an error response, `{"error": {"code": "...", "message": "..."}}`.
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: ErrorDetails,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorDetails {
+    /// A stable identifier of the kind of error, such as `SERVICE_OPERATION_ERROR`.
+    code: &'static str,
+    message: String,
+}
+
+/// The error an error response was created from, kept in the response extensions so
+/// [`render_html_errors`](crate::middleware::render_html_errors) can render it as a page.
+#[derive(Debug, Clone)]
+pub struct RenderableError(Arc<WebError>);
+
+impl RenderableError {
+    /// Renders the error as the rich HTML error page with `status`.
+    pub fn into_html_response(self, status: StatusCode) -> Response {
+        (status, create_rich_error_html(&self.0)).into_response()
+    }
+}
+
 impl IntoResponse for WebError {
     fn into_response(self) -> Response {
-        let html = create_rich_error_html(&self);
         let status_code = determine_status_code(&self);
+        let body = ErrorBody {
+            error: ErrorDetails {
+                code: get_error_code(&self),
+                message: self.to_string(),
+            },
+        };
 
-        (status_code, html).into_response()
+        let mut response = (status_code, Json(body)).into_response();
+        response
+            .extensions_mut()
+            .insert(RenderableError(Arc::new(self)));
+
+        response
+    }
+}
+
+impl WebError {
+    /// Renders the error as an HTML page with its status, the not found page for errors that are
+    /// [not found](WebError::is_not_found) and the rich error page otherwise.
+    pub fn into_html_response(self) -> Response {
+        let status_code = determine_status_code(&self);
+
+        (status_code, create_rich_error_html(&self)).into_response()
     }
 }
 
@@ -19,9 +67,9 @@ fn determine_status_code(error: &WebError) -> StatusCode {
         WebError::JsonParse(_) => StatusCode::BAD_REQUEST,
         WebError::JsonSerialize { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         WebError::TemplateRender(_) => StatusCode::INTERNAL_SERVER_ERROR,
-        WebError::FormValidation(_) => StatusCode::BAD_REQUEST,
-        WebError::QueryParameter(_) => StatusCode::BAD_REQUEST,
-        WebError::ServiceReference { .. } => StatusCode::BAD_REQUEST,
+        WebError::FormValidation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        WebError::QueryParameter(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        WebError::ServiceReference { .. } => StatusCode::UNPROCESSABLE_ENTITY,
         WebError::DaemonCommunication { .. } => StatusCode::BAD_GATEWAY,
         WebError::ServiceOperation { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         WebError::HttpRequest { .. } => StatusCode::BAD_GATEWAY,
@@ -32,6 +80,12 @@ fn determine_status_code(error: &WebError) -> StatusCode {
 }
 
 fn create_rich_error_html(error: &WebError) -> Html<String> {
+    if error.is_not_found() {
+        if let Some(html) = create_not_found_html(error) {
+            return html;
+        }
+    }
+
     let mut miette_output = String::new();
     if miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::none())
         .render_report(&mut miette_output, error)
@@ -102,6 +156,19 @@ fn create_rich_error_html(error: &WebError) -> Html<String> {
     }
 }
 
+/// Renders the not found page for `error`, `None` if the template fails to render so the rich
+/// error page is shown instead.
+fn create_not_found_html(error: &WebError) -> Option<Html<String>> {
+    let context = json!({
+        "error_code": get_error_code(error),
+        "error_message": error.to_string(),
+    });
+
+    TERA.render("not_found.html", &Context::from_value(context).ok()?)
+        .ok()
+        .map(Html)
+}
+
 fn get_error_code(error: &WebError) -> &'static str {
     match error {
         WebError::JsonParse(_) => "JSON_PARSE_ERROR",
@@ -115,6 +182,7 @@ fn get_error_code(error: &WebError) -> &'static str {
         WebError::HttpRequest { .. } => "HTTP_REQUEST_ERROR",
         WebError::FileSystem { .. } => "FILESYSTEM_ERROR",
         WebError::Configuration { .. } => "CONFIGURATION_ERROR",
+        WebError::Internal { .. } if error.is_not_found() => "NOT_FOUND",
         WebError::Internal { .. } => "INTERNAL_ERROR",
     }
 }
@@ -173,3 +241,155 @@ fn html_escape(text: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&#x27;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use axum::http::header;
+    use serde_json::Value;
+    use std::convert::Infallible;
+
+    fn json_error() -> serde_json::Error {
+        serde_json::from_str::<Value>("{").unwrap_err()
+    }
+
+    fn io_error() -> std::io::Error {
+        std::io::Error::other("boom")
+    }
+
+    async fn response_of(error: WebError) -> (StatusCode, Value) {
+        let response = error.into_response();
+        let status = response.status();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/json",
+            "errors should be answered with JSON"
+        );
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_errors_map_to_status_and_json_body() {
+        let cases = [
+            (
+                WebError::json_parse("request body", "{", json_error()),
+                StatusCode::BAD_REQUEST,
+                "JSON_PARSE_ERROR",
+            ),
+            (
+                WebError::json_serialize("response", "ServiceStatus", json_error()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "JSON_SERIALIZE_ERROR",
+            ),
+            (
+                WebError::template_render(
+                    "missing.html",
+                    None,
+                    None::<&Value>,
+                    tera::Error::msg("boom"),
+                ),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "TEMPLATE_RENDER_ERROR",
+            ),
+            (
+                WebError::form_validation("env_key", "1PORT", "a variable name", None, None),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "FORM_VALIDATION_ERROR",
+            ),
+            (
+                WebError::query_parameter("limit", "ten", "a number", None, io_error()),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "QUERY_PARAMETER_ERROR",
+            ),
+            (
+                WebError::service_reference("", "/services/", io_error()),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "SERVICE_REFERENCE_ERROR",
+            ),
+            (
+                WebError::daemon_communication("get_client", None, "unreachable", "refused"),
+                StatusCode::BAD_GATEWAY,
+                "DAEMON_COMMUNICATION_ERROR",
+            ),
+            (
+                WebError::service_operation("start", "web", None, anyhow::anyhow!("failed")),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "SERVICE_OPERATION_ERROR",
+            ),
+            (
+                WebError::HttpRequest {
+                    method: "GET".to_string(),
+                    url: "http://localhost".to_string(),
+                    status_code: None,
+                    response_body: None,
+                    source: Box::new(io_error()),
+                },
+                StatusCode::BAD_GATEWAY,
+                "HTTP_REQUEST_ERROR",
+            ),
+            (
+                WebError::FileSystem {
+                    operation: "read".to_string(),
+                    file_path: "/tmp/missing".to_string(),
+                    source: io_error(),
+                },
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "FILESYSTEM_ERROR",
+            ),
+            (
+                WebError::Configuration {
+                    component: "web".to_string(),
+                    config_key: None,
+                    config_value: None,
+                    source: Box::new(io_error()),
+                },
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "CONFIGURATION_ERROR",
+            ),
+            (
+                WebError::internal("boom", "test", None::<Infallible>),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INTERNAL_ERROR",
+            ),
+            (
+                WebError::not_found("Service 'web' was not found", "test", None::<Infallible>),
+                StatusCode::NOT_FOUND,
+                "NOT_FOUND",
+            ),
+        ];
+
+        for (error, expected_status, expected_code) in cases {
+            let message = error.to_string();
+            let (status, body) = response_of(error).await;
+
+            assert_eq!(status, expected_status, "{message}");
+            assert_eq!(
+                body,
+                json!({ "error": { "code": expected_code, "message": message } })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_response_keeps_error_for_html_rendering() {
+        let response = WebError::not_found("gone", "test", None::<Infallible>).into_response();
+        let error = response
+            .extensions()
+            .get::<RenderableError>()
+            .cloned()
+            .expect("the error should be kept in the extensions");
+
+        let html = error.into_html_response(StatusCode::NOT_FOUND);
+
+        assert_eq!(html.status(), StatusCode::NOT_FOUND);
+        assert!(html.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+    }
+}
@@ -117,6 +117,13 @@ pub enum WebError {
     },
 }
 
+impl WebError {
+    /// Returns `true` for errors answered with `404 Not Found`, such as an unknown service.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::Internal { status_code, .. } if *status_code == StatusCode::NOT_FOUND)
+    }
+}
+
 /// Boxed JSON parsing error details
 #[derive(Debug, Error, Diagnostic)]
 #[error("JSON parsing failed in {context}")]
@@ -1,3 +1,10 @@
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../build_src/error.rs"]
+mod build_error;
+#[cfg(test)]
+#[path = "../build_src/bundle_size.rs"]
+mod bundle_size;
 mod components;
 mod daemon_client;
 mod embedded;
@@ -5,19 +12,23 @@ mod endpoints;
 mod error;
 mod extractors;
 mod middleware;
+mod render_cache;
 mod services;
 mod state;
 pub(crate) mod templates;
+#[cfg(all(test, unix))]
+mod test_daemon;
 mod traits;
+#[cfg(test)]
+#[path = "../build_src/ts_cache.rs"]
+mod ts_cache;
 
 use crate::endpoints::api::service::get::get_services;
 use crate::endpoints::fallback::static_handler;
 use anyhow::Context;
-use axum::handler::Handler;
 use axum::http::{Request, Response};
 use axum::routing::{delete, post};
 use axum::{routing::get, Router};
-use axum_response_cache::CacheLayer;
 use endpoints::get_services::get_nexsock_service;
 use endpoints::index;
 use state::AppState;
@@ -38,7 +49,8 @@ pub async fn app() -> anyhow::Result<Router> {
         .deflate(true)
         .gzip(true)
         .zstd(true);
-    let cache = CacheLayer::with_lifespan(60).add_response_headers();
+    let cors = middleware::cors_layer(state.config().web());
+    let auth = middleware::ApiKeyAuth::new(state.config().web());
 
     Ok(Router::new()
         .route("/", get(index::index_html))
@@ -93,6 +105,11 @@ pub async fn app() -> anyhow::Result<Router> {
             get(endpoints::templates::git_branches),
         )
         .route("/api/templates/git-log", get(endpoints::templates::git_log))
+        .route(
+            "/api/templates/batch",
+            post(endpoints::templates::render_batch),
+        )
+        .merge(endpoints::api::v1::router())
         // Git endpoints
         .route(
             "/api/services/{service_id}/git/status",
@@ -118,8 +135,14 @@ pub async fn app() -> anyhow::Result<Router> {
             "/api/services/{service_id}/git/pull",
             post(endpoints::api::service::git::git_pull),
         )
-        .fallback(static_handler.layer(cache))
+        .fallback(static_handler)
+        .layer(axum::middleware::from_fn(middleware::render_html_errors))
         .layer(compression_layer)
+        .layer(axum::middleware::from_fn_with_state(
+            auth,
+            middleware::require_api_key,
+        ))
+        .layer(cors)
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(|request: &Request<_>| {
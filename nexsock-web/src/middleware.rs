@@ -1,10 +1,15 @@
-use axum::extract::Request;
-use axum::http::StatusCode;
+use axum::extract::{Request, State};
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
-use tracing::error;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use nexsock_config::WebConfig;
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{error, warn};
 
-use crate::error::WebError;
+use crate::error::{RenderableError, WebError};
 
 /// Global error handler middleware that catches any unhandled errors and converts them to WebError
 #[allow(dead_code)]
@@ -87,3 +92,436 @@ pub async fn handle_panic(err: Box<dyn std::any::Any + Send + 'static>) -> impl
         None::<std::convert::Infallible>,
     )
 }
+
+/// Middleware rendering error responses as the rich HTML error page of the web UI.
+///
+/// Errors are answered with a JSON body. Requests made by htmx, or by a browser that accepts
+/// `text/html`, get the HTML page instead since that is what the UI shows.
+pub async fn render_html_errors(request: Request, next: Next) -> Response {
+    let wants_html = wants_html(request.headers());
+    let mut response = next.run(request).await;
+
+    if !wants_html {
+        return response;
+    }
+
+    match response.extensions_mut().remove::<RenderableError>() {
+        Some(error) => error.into_html_response(response.status()),
+        None => response,
+    }
+}
+
+/// Whether the request was made by htmx or accepts an HTML response.
+fn wants_html(headers: &HeaderMap) -> bool {
+    headers.contains_key("HX-Request")
+        || headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .is_some_and(|accept| accept.contains("text/html"))
+}
+
+/// Builds the CORS policy of the web server from the `web` section of the config.
+///
+/// Reads with `GET` and `HEAD` are allowed from every origin in `cors_origins`, or from any origin
+/// if it contains `*`. Every other method must be listed in `cors_methods` and is only allowed from
+/// origins listed by name. With the default config no origin is allowed, which keeps browsers to
+/// same-origin requests.
+pub fn cors_layer(config: &WebConfig) -> CorsLayer {
+    let any_origin = config.cors_origins.iter().any(|origin| origin == "*");
+
+    let origins = config
+        .cors_origins
+        .iter()
+        .filter(|origin| *origin != "*")
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(origin) => Some(origin),
+            Err(_) => {
+                warn!(origin, "Ignoring invalid CORS origin");
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let methods = config
+        .cors_methods
+        .iter()
+        .filter_map(|method| match Method::from_bytes(method.to_uppercase().as_bytes()) {
+            Ok(method) => Some(method),
+            Err(_) => {
+                warn!(method, "Ignoring invalid CORS method");
+                None
+            }
+        })
+        .filter(|method| !is_read(method))
+        .collect::<Vec<_>>();
+
+    let allowed_methods = [Method::GET, Method::HEAD]
+        .into_iter()
+        .chain(methods.iter().cloned())
+        .collect::<Vec<_>>();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(move |origin, parts| {
+            let method = requested_method(parts);
+
+            if is_read(&method) {
+                any_origin || origins.contains(origin)
+            } else {
+                origins.contains(origin) && methods.contains(&method)
+            }
+        }))
+        .allow_methods(allowed_methods)
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+}
+
+fn is_read(method: &Method) -> bool {
+    *method == Method::GET || *method == Method::HEAD
+}
+
+/// The method of the request a CORS check is for, preflight requests name it in a header.
+fn requested_method(parts: &Parts) -> Method {
+    if parts.method != Method::OPTIONS {
+        return parts.method.clone();
+    }
+
+    parts
+        .headers
+        .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+        .and_then(|method| Method::from_bytes(method.as_bytes()).ok())
+        .unwrap_or(Method::OPTIONS)
+}
+
+/// The API key the web server requires, taken from the `web` section of the config.
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth {
+    api_key: Option<Arc<str>>,
+    public_reads: bool,
+}
+
+impl ApiKeyAuth {
+    pub fn new(config: &WebConfig) -> Self {
+        Self {
+            api_key: config.api_key.as_deref().map(Arc::from),
+            public_reads: config.public_reads,
+        }
+    }
+}
+
+/// Whether `headers` carry the key, either as a bearer token or as the basic auth password.
+fn is_authorized(api_key: &str, headers: &HeaderMap) -> bool {
+    let Some(authorization) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    let provided = match authorization.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => {
+            token.trim().as_bytes().to_vec()
+        }
+        Some((scheme, credentials)) if scheme.eq_ignore_ascii_case("basic") => {
+            let Ok(credentials) = BASE64_STANDARD.decode(credentials.trim()) else {
+                return false;
+            };
+
+            // The user name is ignored, only the password has to match
+            match credentials.iter().position(|byte| *byte == b':') {
+                Some(colon) => credentials[colon + 1..].to_vec(),
+                None => return false,
+            }
+        }
+        _ => return false,
+    };
+
+    constant_time_eq(&provided, api_key.as_bytes())
+}
+
+/// Middleware rejecting requests without the configured API key with `401 Unauthorized`.
+///
+/// Requests that change state always need the key, `GET` and `HEAD` requests only if
+/// `public_reads` is disabled. Every request passes when no key is configured.
+pub async fn require_api_key(
+    State(auth): State<ApiKeyAuth>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(api_key) = auth.api_key.as_deref() else {
+        return next.run(request).await;
+    };
+
+    let public = auth.public_reads && is_read(request.method());
+
+    if public || is_authorized(api_key, request.headers()) {
+        return next.run(request).await;
+    }
+
+    warn!(method = %request.method(), uri = %request.uri(), "Rejected unauthorized request");
+
+    (
+        StatusCode::UNAUTHORIZED,
+        [(
+            header::WWW_AUTHENTICATE,
+            r#"Basic realm="nexsock", charset="UTF-8""#,
+        )],
+        "A valid API key is required",
+    )
+        .into_response()
+}
+
+/// Compares `a` and `b` in time depending only on their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn app(cors_origins: &[&str], cors_methods: &[&str]) -> Router {
+        let config = WebConfig {
+            cors_origins: cors_origins.iter().map(ToString::to_string).collect(),
+            cors_methods: cors_methods.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+
+        Router::new()
+            .route("/api/v1/services", get(|| async {}).post(|| async {}))
+            .layer(cors_layer(&config))
+    }
+
+    async fn preflight(app: Router, origin: &str, method: &str) -> Response {
+        let request = axum::http::Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/v1/services")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, method)
+            .body(Body::empty())
+            .unwrap();
+
+        app.oneshot(request).await.unwrap()
+    }
+
+    fn allowed_origin(response: &Response) -> Option<&str> {
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|origin| origin.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_preflight_allows_configured_origin_and_methods() {
+        let app = app(&["https://dash.example.com"], &["post", "DELETE"]);
+
+        let response = preflight(app, "https://dash.example.com", "POST").await;
+
+        assert!(response.status().is_success());
+        assert_eq!(allowed_origin(&response), Some("https://dash.example.com"));
+
+        let methods = response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS]
+            .to_str()
+            .unwrap();
+        for method in ["GET", "HEAD", "POST", "DELETE"] {
+            assert!(methods.contains(method), "`{method}` missing from `{methods}`");
+        }
+
+        let headers = response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap();
+        assert_eq!(headers, "content-type,authorization");
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_only_allows_reads() {
+        let app = app(&["*", "https://dash.example.com"], &["POST"]);
+
+        let response = preflight(app.clone(), "https://other.example.com", "GET").await;
+        assert_eq!(allowed_origin(&response), Some("https://other.example.com"));
+
+        // Mutating requests need the origin to be listed by name
+        let response = preflight(app.clone(), "https://other.example.com", "POST").await;
+        assert_eq!(allowed_origin(&response), None);
+
+        let response = preflight(app.clone(), "https://dash.example.com", "POST").await;
+        assert_eq!(allowed_origin(&response), Some("https://dash.example.com"));
+
+        // Methods that aren't configured are refused even for listed origins
+        let response = preflight(app, "https://dash.example.com", "DELETE").await;
+        assert_eq!(allowed_origin(&response), None);
+    }
+
+    #[tokio::test]
+    async fn test_default_config_refuses_cross_origin_requests() {
+        let app = app(&[], &[]);
+
+        let response = preflight(app.clone(), "https://dash.example.com", "GET").await;
+        assert_eq!(allowed_origin(&response), None);
+
+        let request = axum::http::Request::get("/api/v1/services")
+            .header(header::ORIGIN, "https://dash.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(allowed_origin(&response), None);
+    }
+
+    fn protected_app(api_key: Option<&str>, public_reads: bool) -> Router {
+        let config = WebConfig {
+            api_key: api_key.map(ToString::to_string),
+            public_reads,
+            ..Default::default()
+        };
+
+        Router::new()
+            .route(
+                "/api/v1/services",
+                get(|| async { "listed" }).post(|| async { "started" }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                ApiKeyAuth::new(&config),
+                require_api_key,
+            ))
+    }
+
+    async fn call(app: &Router, method: Method, authorization: Option<&str>) -> Response {
+        let mut request = axum::http::Request::builder()
+            .method(method)
+            .uri("/api/v1/services");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn basic(credentials: &str) -> String {
+        format!("Basic {}", BASE64_STANDARD.encode(credentials))
+    }
+
+    #[tokio::test]
+    async fn test_mutating_request_without_valid_key_is_unauthorized() {
+        let app = protected_app(Some("s3cret"), true);
+
+        for authorization in [
+            None,
+            Some("Bearer wrong"),
+            Some("Bearer s3cret-but-longer"),
+            Some(basic("admin:wrong").as_str()),
+            Some("Basic not-base64"),
+            Some("Token s3cret"),
+        ] {
+            let response = call(&app, Method::POST, authorization).await;
+
+            assert_eq!(
+                response.status(),
+                StatusCode::UNAUTHORIZED,
+                "{authorization:?} was accepted"
+            );
+            assert_eq!(
+                response.headers()[header::WWW_AUTHENTICATE],
+                r#"Basic realm="nexsock", charset="UTF-8""#
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_valid_key_reaches_handler() {
+        let app = protected_app(Some("s3cret"), true);
+
+        let response = call(&app, Method::POST, Some("Bearer s3cret")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, "started");
+
+        let response = call(&app, Method::POST, Some(&basic("admin:s3cret"))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, "started");
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_public_unless_disabled() {
+        let app = protected_app(Some("s3cret"), true);
+        let response = call(&app, Method::GET, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, "listed");
+
+        let app = protected_app(Some("s3cret"), false);
+        let response = call(&app, Method::GET, None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = call(&app, Method::GET, Some("bearer s3cret")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_no_key_configured_allows_everything() {
+        let app = protected_app(None, false);
+
+        let response = call(&app, Method::POST, None).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, "started");
+    }
+
+    async fn missing_service() -> Result<(), WebError> {
+        Err(WebError::not_found(
+            "Service 'web' was not found",
+            "test",
+            None::<std::convert::Infallible>,
+        ))
+    }
+
+    async fn content_type_of(headers: &[(&str, &str)]) -> (StatusCode, String) {
+        let app = Router::new()
+            .route("/services/web", get(missing_service))
+            .layer(axum::middleware::from_fn(render_html_errors));
+
+        let mut request = axum::http::Request::builder().uri("/services/web");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let content_type = response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        (response.status(), content_type)
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_json_unless_html_is_wanted() {
+        let (status, content_type) = content_type_of(&[]).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(content_type, "application/json");
+
+        let (status, content_type) = content_type_of(&[("HX-Request", "true")]).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(content_type.starts_with("text/html"), "{content_type}");
+
+        let (status, content_type) =
+            content_type_of(&[("Accept", "text/html,application/xhtml+xml")]).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(content_type.starts_with("text/html"), "{content_type}");
+    }
+}
@@ -0,0 +1,135 @@
+//! Caching of rendered pages, invalidated whenever the web UI changes the state of the daemon.
+//!
+//! Every cached page is tagged with the state version it was rendered at. Mutating operations
+//! such as starting a service bump the version, so the next request renders the page again
+//! instead of serving what was cached before the change. Changes made outside the web UI, for
+//! example through the CLI, are picked up once the cached page expires.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// How long a page is served from the cache if the web UI doesn't change anything.
+const DEFAULT_LIFESPAN: Duration = Duration::from_secs(60);
+
+/// A page rendered at `version`.
+struct CachedPage {
+    version: u64,
+    rendered_at: Instant,
+    body: Vec<u8>,
+}
+
+/// Rendered pages keyed by name, such as the full or partial services list.
+pub(crate) struct RenderCache {
+    version: AtomicU64,
+    lifespan: Duration,
+    pages: RwLock<HashMap<String, CachedPage>>,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::with_lifespan(DEFAULT_LIFESPAN)
+    }
+}
+
+impl RenderCache {
+    /// Creates an empty cache serving pages for at most `lifespan`.
+    pub(crate) fn with_lifespan(lifespan: Duration) -> Self {
+        Self {
+            version: AtomicU64::new(0),
+            lifespan,
+            pages: RwLock::default(),
+        }
+    }
+
+    /// The current state version, bumped by every [`invalidate`](Self::invalidate).
+    pub(crate) fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Returns the page cached under `key` if it was rendered at the current version and hasn't
+    /// expired yet.
+    pub(crate) fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let version = self.version();
+        let pages = self.pages.read().unwrap_or_else(|error| error.into_inner());
+
+        pages
+            .get(key)
+            .filter(|page| page.version == version && page.rendered_at.elapsed() < self.lifespan)
+            .map(|page| page.body.clone())
+    }
+
+    /// Caches `body` under `key` as rendered at `version`.
+    ///
+    /// A page rendered before the last invalidation is dropped, as it may show the old state.
+    pub(crate) fn insert(&self, key: impl Into<String>, version: u64, body: Vec<u8>) {
+        let mut pages = self
+            .pages
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+
+        if version != self.version() {
+            return;
+        }
+
+        pages.insert(
+            key.into(),
+            CachedPage {
+                version,
+                rendered_at: Instant::now(),
+                body,
+            },
+        );
+    }
+
+    /// Bumps the state version, so every page cached so far gets rendered again.
+    pub(crate) fn invalidate(&self) {
+        let mut pages = self
+            .pages
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+
+        self.version.fetch_add(1, Ordering::AcqRel);
+        pages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalidate_busts_cached_pages() {
+        let cache = RenderCache::default();
+
+        let version = cache.version();
+        cache.insert("services", version, b"before".to_vec());
+        assert_eq!(cache.get("services").as_deref(), Some(&b"before"[..]));
+
+        cache.invalidate();
+
+        assert_eq!(cache.version(), version + 1);
+        assert!(cache.get("services").is_none());
+    }
+
+    #[test]
+    fn test_pages_rendered_before_invalidation_are_not_cached() {
+        let cache = RenderCache::default();
+
+        let version = cache.version();
+        cache.invalidate();
+        cache.insert("services", version, b"stale".to_vec());
+
+        assert!(cache.get("services").is_none());
+    }
+
+    #[test]
+    fn test_pages_expire_after_lifespan() {
+        let cache = RenderCache::with_lifespan(Duration::ZERO);
+
+        cache.insert("services", cache.version(), b"expired".to_vec());
+
+        assert!(cache.get("services").is_none());
+    }
+}
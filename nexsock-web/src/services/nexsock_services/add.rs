@@ -9,15 +9,14 @@ pub async fn add_service(
 ) -> Result<(), WebError> {
     let mut client = get_client(state).await?;
 
+    let service_name = add_service_payload.name.clone();
     let command: AddServiceCommand = add_service_payload.into();
 
-    client.execute_command(command).await.map_err(|error| {
-        WebError::internal(
-            format!("Failed to execute add_service command: {error}"),
-            "add_service",
-            None::<std::io::Error>,
-        )
-    })?;
+    client
+        .execute_command(command)
+        .await
+        .map_err(|error| WebError::service_operation("add", service_name, None, error))?;
+    state.render_cache().invalidate();
 
     Ok(())
 }
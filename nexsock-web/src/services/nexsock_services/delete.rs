@@ -1,15 +1,18 @@
-use crate::daemon_client::get_client;
+use crate::daemon_client::execute_service_command;
+use crate::error::WebError;
 use crate::state::AppState;
 use nexsock_protocol::commands::manage_service::{RemoveServiceCommand, ServiceRef};
 
 /// Removes the given service so it no longer gets managed by the daemon
 #[tracing::instrument(skip(state))]
-pub async fn remove_service_inner(state: &AppState, service_ref: ServiceRef) -> anyhow::Result<()> {
-    let mut client = get_client(state).await?;
+pub async fn remove_service_inner(
+    state: &AppState,
+    service_ref: ServiceRef,
+) -> Result<(), WebError> {
+    let command = RemoveServiceCommand::new(service_ref.clone(), false, false);
 
-    client
-        .execute_command(RemoveServiceCommand::new(service_ref))
-        .await?;
+    execute_service_command(state, "remove", &service_ref, command).await?;
+    state.render_cache().invalidate();
 
     Ok(())
 }
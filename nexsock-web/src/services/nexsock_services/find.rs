@@ -1,27 +1,69 @@
 use crate::components::service_status::ServiceStatusView;
-use crate::daemon_client::get_client;
+use crate::daemon_client::{execute_service_command, get_client};
+use crate::error::WebError;
 use crate::state::AppState;
-use anyhow::bail;
+use nexsock_protocol::commands::list_services::ListServicesCommand;
 use nexsock_protocol::commands::manage_service::ServiceRef;
 use nexsock_protocol::commands::service_status::GetServiceStatus;
+use nexsock_protocol::commands::CommandPayload;
 
 /// Gets more detailed information about the service.
+///
+/// Fails with a [not found](WebError::is_not_found) error if the daemon doesn't manage the
+/// service.
 #[tracing::instrument(skip(state))]
 pub async fn find_service(
     state: &AppState,
     service_ref: ServiceRef,
-) -> anyhow::Result<ServiceStatusView> {
-    let mut client = get_client(state).await?;
+) -> Result<ServiceStatusView, WebError> {
+    let command = GetServiceStatus::new(service_ref.clone());
 
-    let res = client
-        .execute_command(GetServiceStatus::new(service_ref))
-        .await?;
+    let res = match execute_service_command(state, "status", &service_ref, command).await {
+        Ok(res) => res,
+        Err(error) => {
+            // The daemon answers a missing service like any other failure, so check if it exists
+            return if service_exists(state, &service_ref).await? {
+                Err(error)
+            } else {
+                Err(service_not_found(&service_ref))
+            };
+        }
+    };
 
     if res.is_status() {
         let service = res.unwrap_status();
 
         Ok(ServiceStatusView::new(service))
     } else {
-        bail!("service not found")
+        Err(service_not_found(&service_ref))
     }
 }
+
+/// Whether the daemon manages a service matching `service_ref`.
+async fn service_exists(state: &AppState, service_ref: &ServiceRef) -> Result<bool, WebError> {
+    let mut client = get_client(state).await?;
+
+    let res = client
+        .execute_command(ListServicesCommand::new())
+        .await
+        .map_err(|error| {
+            WebError::daemon_communication("list_services", None, "connected", error)
+        })?;
+
+    let CommandPayload::ListServices(list) = res else {
+        return Ok(false);
+    };
+
+    Ok(list.services.iter().any(|service| match service_ref {
+        ServiceRef::Id(id) => service.id == *id,
+        ServiceRef::Name(name) => service.name == *name,
+    }))
+}
+
+fn service_not_found(service_ref: &ServiceRef) -> WebError {
+    WebError::not_found(
+        format!("Service '{service_ref}' was not found"),
+        "find_service",
+        None::<std::convert::Infallible>,
+    )
+}
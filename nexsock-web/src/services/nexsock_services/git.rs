@@ -1,6 +1,6 @@
-use crate::daemon_client::get_client;
+use crate::daemon_client::execute_service_command;
+use crate::error::WebError;
 use crate::state::AppState;
-use anyhow::anyhow;
 use nexsock_protocol::commands::git::*;
 use nexsock_protocol::commands::manage_service::ServiceRef;
 
@@ -9,17 +9,19 @@ use nexsock_protocol::commands::manage_service::ServiceRef;
 pub async fn get_repo_status(
     state: &AppState,
     service_ref: ServiceRef,
-) -> anyhow::Result<RepoStatus> {
-    let mut client = get_client(state).await?;
-
-    let res = client
-        .execute_command(GetRepoStatusCommand::new(service_ref))
-        .await?;
+) -> Result<RepoStatus, WebError> {
+    let command = GetRepoStatusCommand::new(service_ref.clone());
+    let res = execute_service_command(state, "git status", &service_ref, command).await?;
 
     if res.is_git_status() {
         Ok(res.unwrap_git_status())
     } else {
-        Err(anyhow!("Failed to get repository status"))
+        Err(WebError::service_operation(
+            "git status",
+            service_ref.to_string(),
+            None,
+            "Failed to get repository status",
+        ))
     }
 }
 
@@ -29,17 +31,19 @@ pub async fn list_branches(
     state: &AppState,
     service_ref: ServiceRef,
     include_remote: bool,
-) -> anyhow::Result<GitListBranchesResponse> {
-    let mut client = get_client(state).await?;
-
-    let res = client
-        .execute_command(GitListBranchesCommand::new(service_ref, include_remote))
-        .await?;
+) -> Result<GitListBranchesResponse, WebError> {
+    let command = GitListBranchesCommand::new(service_ref.clone(), include_remote);
+    let res = execute_service_command(state, "git branches", &service_ref, command).await?;
 
     if res.is_git_branches() {
         Ok(res.unwrap_git_branches())
     } else {
-        Err(anyhow!("Failed to list branches"))
+        Err(WebError::service_operation(
+            "git branches",
+            service_ref.to_string(),
+            None,
+            "Failed to list branches",
+        ))
     }
 }
 
@@ -50,17 +54,19 @@ pub async fn get_log(
     service_ref: ServiceRef,
     max_count: Option<usize>,
     branch: Option<String>,
-) -> anyhow::Result<GitLogResponse> {
-    let mut client = get_client(state).await?;
-
-    let res = client
-        .execute_command(GitLogCommand::new(service_ref, max_count, branch))
-        .await?;
+) -> Result<GitLogResponse, WebError> {
+    let command = GitLogCommand::new(service_ref.clone(), max_count, branch);
+    let res = execute_service_command(state, "git log", &service_ref, command).await?;
 
     if res.is_git_log() {
         Ok(res.unwrap_git_log())
     } else {
-        Err(anyhow!("Failed to get git log"))
+        Err(WebError::service_operation(
+            "git log",
+            service_ref.to_string(),
+            None,
+            "Failed to get git log",
+        ))
     }
 }
 
@@ -71,24 +77,19 @@ pub async fn checkout_branch(
     service_ref: ServiceRef,
     branch: String,
     create: bool,
-) -> anyhow::Result<()> {
-    let mut client = get_client(state).await?;
-
+) -> Result<(), WebError> {
     let command = if create {
         // For creating branches, we'll use the checkout command with create flag
         // We need to check how the daemon handles branch creation
-        CheckoutCommand::new(service_ref, branch)
+        CheckoutCommand::new(service_ref.clone(), branch)
     } else {
-        CheckoutCommand::new(service_ref, branch)
+        CheckoutCommand::new(service_ref.clone(), branch)
     };
 
-    let res = client.execute_command(command).await?;
+    execute_service_command(state, "git checkout", &service_ref, command).await?;
+    state.render_cache().invalidate();
 
-    if res.is_error() {
-        Err(anyhow!(res.unwrap_error().message))
-    } else {
-        Ok(())
-    }
+    Ok(())
 }
 
 /// Checkout a specific commit for a service
@@ -97,32 +98,22 @@ pub async fn checkout_commit(
     state: &AppState,
     service_ref: ServiceRef,
     commit_hash: String,
-) -> anyhow::Result<()> {
-    let mut client = get_client(state).await?;
+) -> Result<(), WebError> {
+    let command = GitCheckoutCommitCommand::new(service_ref.clone(), commit_hash);
 
-    let res = client
-        .execute_command(GitCheckoutCommitCommand::new(service_ref, commit_hash))
-        .await?;
+    execute_service_command(state, "git checkout", &service_ref, command).await?;
+    state.render_cache().invalidate();
 
-    if res.is_error() {
-        Err(anyhow!(res.unwrap_error().message))
-    } else {
-        Ok(())
-    }
+    Ok(())
 }
 
 /// Pull latest changes for a service
 #[tracing::instrument(skip(state))]
-pub async fn pull(state: &AppState, service_ref: ServiceRef) -> anyhow::Result<()> {
-    let mut client = get_client(state).await?;
+pub async fn pull(state: &AppState, service_ref: ServiceRef) -> Result<(), WebError> {
+    let command = GitPullCommand::new(service_ref.clone());
 
-    let res = client
-        .execute_command(GitPullCommand::new(service_ref))
-        .await?;
+    execute_service_command(state, "git pull", &service_ref, command).await?;
+    state.render_cache().invalidate();
 
-    if res.is_error() {
-        Err(anyhow!(res.unwrap_error().message))
-    } else {
-        Ok(())
-    }
+    Ok(())
 }
@@ -1,16 +1,22 @@
 use crate::components::service_basic::ServiceBasic;
 use crate::components::services_list::ServicesList;
 use crate::daemon_client::get_client;
+use crate::error::WebError;
 use crate::state::AppState;
 use nexsock_protocol::commands::list_services::ListServicesCommand;
 use tracing::error;
 
 /// Lists all managed services.
 #[tracing::instrument(skip(state))]
-pub async fn list_services(state: &AppState) -> anyhow::Result<ServicesList> {
+pub async fn list_services(state: &AppState) -> Result<ServicesList, WebError> {
     let mut client = get_client(state).await?;
 
-    let res = client.execute_command(ListServicesCommand::new()).await?;
+    let res = client
+        .execute_command(ListServicesCommand::new())
+        .await
+        .map_err(|error| {
+            WebError::daemon_communication("list_services", None, "connected", error)
+        })?;
 
     if res.is_list_services() {
         let services = res.unwrap_list_services();
@@ -1,6 +1,6 @@
-use crate::daemon_client::get_client;
+use crate::daemon_client::execute_service_command;
+use crate::error::WebError;
 use crate::state::AppState;
-use anyhow::anyhow;
 use nexsock_protocol::commands::manage_service::{ServiceRef, StartServiceCommand};
 use std::collections::HashMap;
 
@@ -10,16 +10,11 @@ pub async fn start_service_inner(
     state: &AppState,
     service_ref: ServiceRef,
     env_vars: HashMap<String, String>,
-) -> anyhow::Result<()> {
-    let mut client = get_client(state).await?;
+) -> Result<(), WebError> {
+    let command = StartServiceCommand::new(service_ref.clone(), env_vars, None, None, false);
 
-    let res = client
-        .execute_command(StartServiceCommand::new(service_ref, env_vars))
-        .await?;
+    execute_service_command(state, "start", &service_ref, command).await?;
+    state.render_cache().invalidate();
 
-    if res.is_error() {
-        Err(anyhow!(res.unwrap_error().message))
-    } else {
-        Ok(())
-    }
+    Ok(())
 }
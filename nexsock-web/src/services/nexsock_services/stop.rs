@@ -1,20 +1,15 @@
-use crate::daemon_client::get_client;
+use crate::daemon_client::execute_service_command;
+use crate::error::WebError;
 use crate::state::AppState;
-use anyhow::anyhow;
 use nexsock_protocol::commands::manage_service::{ServiceRef, StopServiceCommand};
 
 /// Stops the service
 #[tracing::instrument(skip(state))]
-pub async fn stop_service_inner(state: &AppState, service_ref: ServiceRef) -> anyhow::Result<()> {
-    let mut client = get_client(state).await?;
+pub async fn stop_service_inner(state: &AppState, service_ref: ServiceRef) -> Result<(), WebError> {
+    let command = StopServiceCommand::new(service_ref.clone());
 
-    let res = client
-        .execute_command(StopServiceCommand::new(service_ref))
-        .await?;
+    execute_service_command(state, "stop", &service_ref, command).await?;
+    state.render_cache().invalidate();
 
-    if res.is_error() {
-        Err(anyhow!(res.unwrap_error().message))
-    } else {
-        Ok(())
-    }
+    Ok(())
 }
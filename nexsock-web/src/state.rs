@@ -1,7 +1,9 @@
+use crate::render_cache::RenderCache;
 use deadpool::managed::Pool;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use nexsock_client::ClientManager;
 use nexsock_config::NexsockConfig;
+use std::sync::Arc;
 
 #[derive(Clone, AsRef, AsMut, Deref, DerefMut)]
 pub struct AppState {
@@ -9,18 +11,33 @@ pub struct AppState {
     #[deref]
     #[deref_mut]
     client_pool: Pool<ClientManager>,
+    render_cache: Arc<RenderCache>,
 }
 
 impl AppState {
     pub async fn new() -> anyhow::Result<Self> {
-        let config = NexsockConfig::new()?;
-        let manager = ClientManager::new()?;
+        Self::from_config(NexsockConfig::new()?)
+    }
+
+    /// Creates the state for a daemon reached through the socket in `config`.
+    pub fn from_config(config: NexsockConfig) -> anyhow::Result<Self> {
+        let manager = ClientManager::from_config(config.clone());
 
         let client_pool = Pool::builder(manager).max_size(10).build()?;
 
         Ok(Self {
             config,
             client_pool,
+            render_cache: Arc::default(),
         })
     }
+
+    pub fn config(&self) -> &NexsockConfig {
+        &self.config
+    }
+
+    /// The rendered pages, shared by every clone of the state.
+    pub(crate) fn render_cache(&self) -> &RenderCache {
+        &self.render_cache
+    }
 }
@@ -0,0 +1,98 @@
+//! A fake daemon for tests of the endpoints, answering commands over a Unix socket.
+
+use crate::state::AppState;
+use nexsock_config::NexsockConfig;
+use nexsock_protocol::commands::error::ErrorPayload;
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::header::MessageFlags;
+use nexsock_protocol::protocol::Protocol;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::net::UnixListener;
+
+pub(crate) type Received = Arc<Mutex<Vec<(Command, Option<Vec<u8>>)>>>;
+
+/// Serves the daemon protocol on `socket`, answering every command other than `Ping` with
+/// `respond` and recording the commands it receives.
+fn spawn_daemon(
+    socket: &Path,
+    respond: fn(Command) -> Result<CommandPayload, ErrorPayload>,
+) -> Received {
+    let listener = UnixListener::bind(socket).unwrap();
+    let received = Received::default();
+
+    let recorder = received.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let recorder = recorder.clone();
+
+            tokio::spawn(async move {
+                let (mut reader, mut writer) = stream.into_split();
+                let mut protocol = Protocol::default();
+
+                while let Ok((header, payload)) = protocol.read_message(&mut reader).await {
+                    protocol.set_request_id(header.request_id());
+
+                    let response = if matches!(header.command, Command::Ping) {
+                        Ok(CommandPayload::Empty)
+                    } else {
+                        recorder.lock().unwrap().push((header.command, payload));
+                        respond(header.command)
+                    };
+
+                    let result = match response {
+                        Ok(CommandPayload::Empty) => {
+                            protocol.write_command(&mut writer, Command::Success).await
+                        }
+                        Ok(payload) => {
+                            protocol
+                                .write_command_with_payload(
+                                    &mut writer,
+                                    Command::Success,
+                                    &payload,
+                                    MessageFlags::HAS_PAYLOAD,
+                                )
+                                .await
+                        }
+                        Err(error) => {
+                            protocol
+                                .write_command_with_payload(
+                                    &mut writer,
+                                    Command::Error,
+                                    &error,
+                                    MessageFlags::HAS_PAYLOAD,
+                                )
+                                .await
+                        }
+                    };
+
+                    if result.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    received
+}
+
+/// Creates the app state for a fake daemon answering with `respond`, its socket and config are
+/// written to `dir`.
+pub(crate) fn fake_daemon_state(
+    dir: &Path,
+    respond: fn(Command) -> Result<CommandPayload, ErrorPayload>,
+) -> (AppState, Received) {
+    let socket = dir.join("nexsock.sock");
+    std::fs::write(
+        dir.join("config.toml"),
+        format!("socket = {:?}\n", socket.display().to_string()),
+    )
+    .unwrap();
+
+    let received = spawn_daemon(&socket, respond);
+    let config = NexsockConfig::from_file(Some(dir)).unwrap();
+    let state = AppState::from_config(config).unwrap();
+
+    (state, received)
+}
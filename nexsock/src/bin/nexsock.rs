@@ -1,12 +1,22 @@
-use anyhow::{bail, Context};
+#![deny(clippy::dbg_macro)]
+
+use anyhow::bail;
 use clap::Parser;
-use nexsock::cli::{Cli, Commands, ToolCommands};
+use nexsock::capabilities::ensure_git_support;
+use nexsock::cli::{Cli, Commands, DbCommands, ToolCommands};
 use nexsock::commands::create_command;
+use nexsock::confirm::confirm;
+use nexsock::events::print_events;
+use nexsock::man::generate_man_pages;
+use nexsock::output::format_payload;
+use nexsock::resolve::resolve_services;
+use nexsock::socket::daemon_socket;
+use nexsock::stdin::forward_stdin;
+use nexsock::top::print_top;
 use nexsock_client::Client;
-use nexsock_config::NexsockConfig;
+use nexsock_protocol::commands::event::SubscribePayload;
+use nexsock_protocol::commands::validate::CheckStatus;
 use nexsock_protocol::commands::{CommandPayload, ServiceCommand};
-#[cfg(windows)]
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use tracing::warn;
 
 #[cfg(feature = "jemalloc")]
@@ -44,7 +54,7 @@ pub fn handle_tool_commands(command: ToolCommands) -> anyhow::Result<()> {
 ///
 /// Parses command-line arguments, loads configuration, determines the appropriate socket or address,
 /// connects to the nexsock service, and executes the requested command. Handles both service and tool-related commands,
-/// printing command output or debugging information as appropriate.
+/// printing the command output.
 ///
 /// # Errors
 ///
@@ -58,12 +68,19 @@ pub fn handle_tool_commands(command: ToolCommands) -> anyhow::Result<()> {
 /// tokio::runtime::Runtime::new().unwrap().block_on(main()).unwrap();
 /// ```
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
-
     // Parse command line arguments
     let cli = Cli::parse();
 
+    // Logs go to stderr so stdout only holds the output of the command
+    tracing_subscriber::fmt()
+        .with_max_level(cli.log_level())
+        .with_writer(std::io::stderr)
+        .init();
+
+    if let Commands::GenerateMan { out_dir } = &cli.command {
+        return generate_man_pages(out_dir);
+    }
+
     if cli.command.is_tools() {
         let command = cli.command;
 
@@ -73,38 +90,58 @@ async fn main() -> anyhow::Result<()> {
         };
     }
 
-    let config = NexsockConfig::new()?;
-
-    #[cfg(unix)]
-    let socket = if let Some(socket) = cli.socket {
-        socket
-    } else {
-        config
-            .socket()
-            .clone()
-            .try_unwrap_path()
-            .context("Expected `socket` to be a path to the socket file")?
-    };
+    if let Commands::Db {
+        command: DbCommands::Rollback { steps, yes: false },
+    } = &cli.command
+    {
+        let action = match steps {
+            1 => "roll back the last database migration".to_string(),
+            steps => format!("roll back the last {steps} database migrations"),
+        };
 
-    #[cfg(windows)]
-    let socket = if let Some(addr) = cli.address {
-        addr
-    } else {
-        let port = config
-            .socket()
-            .clone()
-            .try_unwrap_port()
-            .context("Expected `socket` to be a integer port number")?;
-
-        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port)
-    };
+        if !confirm(&action)? {
+            bail!("Rollback cancelled");
+        }
+    }
+
+    let config = cli.load_config()?;
+
+    let socket = daemon_socket(&cli, &config)?;
 
     let mut client = Client::connect(socket).await?;
 
+    if !cli.no_resolve {
+        resolve_services(&mut client, &cli.command.service_refs()).await?;
+    }
+
+    if cli.command.is_git() {
+        ensure_git_support(&mut client).await?;
+    }
+
+    if cli.command.is_top() {
+        return print_top(&mut client).await;
+    }
+
+    if let Commands::Stdin { service } = &cli.command {
+        return forward_stdin(&mut client, service).await;
+    }
+
+    if let Commands::Events { service, kinds } = cli.command {
+        let payload = SubscribePayload {
+            service,
+            kinds,
+            after_seq: None,
+        };
+
+        return print_events(&mut client, payload).await;
+    }
+
     let command = create_command(cli.command)?;
 
     let response = match command {
         ServiceCommand::Stdout(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::Logs(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ClearLogs(cmd) => client.execute_command(cmd).await?,
 
         ServiceCommand::Start(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::Stop(cmd) => client.execute_command(cmd).await?,
@@ -112,16 +149,29 @@ async fn main() -> anyhow::Result<()> {
 
         ServiceCommand::List(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::Status(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::State(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::StartProfile(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::StopProfile(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ListByProfile(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ListByLabels(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::Search(cmd) => client.execute_command(cmd).await?,
 
         ServiceCommand::Add(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::Remove(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::Clone(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ApplyManifest(cmd) => client.execute_command(cmd).await?,
 
         ServiceCommand::ConfigGet(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ConfigGetFile(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ConfigWrite(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::ConfigUpdate(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::Validate(cmd) => client.execute_command(cmd).await?,
 
         ServiceCommand::DependencyAdd(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::DependencyRemove(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::DependencyList(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::DependencyDependents(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::DependencyInfo(cmd) => client.execute_command(cmd).await?,
 
         ServiceCommand::GitCheckout(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::GitStatus(cmd) => client.execute_command(cmd).await?,
@@ -130,14 +180,44 @@ async fn main() -> anyhow::Result<()> {
         ServiceCommand::GitLog(cmd) => client.execute_command(cmd).await?,
         ServiceCommand::GitListBranches(cmd) => client.execute_command(cmd).await?,
 
+        ServiceCommand::DebugDump(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::ExportMetrics(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::MigrationStatus(cmd) => client.execute_command(cmd).await?,
+        ServiceCommand::RollbackMigration(cmd) => client.execute_command(cmd).await?,
+
         _ => bail!("Unknown command"),
     };
 
     match response {
         CommandPayload::Stdout(log) => print!("{log}"),
-        res => {
-            dbg!(res);
+        CommandPayload::State(state) => println!("{state}"),
+        CommandPayload::ConfigFile(file) => match file.contents {
+            Some(contents) => print!("{contents}"),
+            None => bail!("Config file `{}` does not exist", file.path),
+        },
+        CommandPayload::ServiceStdout(output) => {
+            print!("{}", output.content);
+            eprintln!("last seq: {}", output.last_seq);
+        }
+        CommandPayload::ManifestApplied(response) if response.is_noop() => {
+            println!("Manifest is already applied, nothing to do");
+        }
+        CommandPayload::ManifestApplied(response) => {
+            for action in response.actions {
+                println!("{action}");
+            }
+        }
+        CommandPayload::Validation(report) => {
+            for check in &report.checks {
+                println!("{check}");
+            }
+
+            let failed = report.with_status(CheckStatus::Fail).count();
+            if failed > 0 {
+                bail!("{failed} of {} checks failed", report.checks.len());
+            }
         }
+        res => print!("{}", format_payload(&res)),
     }
 
     Ok(())
@@ -0,0 +1,86 @@
+//! Client side checks against the capabilities of the daemon.
+//!
+//! Daemons built without a feature answer its commands with an `Unsupported` error that doesn't
+//! say much. Asking the daemon what it supports first lets the CLI refuse such commands with an
+//! error that explains why.
+
+use anyhow::bail;
+use nexsock_client::Client;
+use nexsock_protocol::commands::capabilities::{Capabilities, CapabilitiesCommand};
+use tracing::debug;
+
+/// Fetches the capabilities of the daemon.
+///
+/// Returns `None` for daemons that predate the capabilities command.
+pub async fn daemon_capabilities(client: &mut Client) -> anyhow::Result<Option<Capabilities>> {
+    match client.execute_command(CapabilitiesCommand::new()).await {
+        Ok(payload) => Capabilities::try_from(payload).map(Some),
+        Err(error) => {
+            debug!(error = %error, "Daemon did not report its capabilities");
+            Ok(None)
+        }
+    }
+}
+
+/// Checks that the daemon behind `client` supports git before a git command is sent to it.
+///
+/// # Errors
+///
+/// Returns an error if the capabilities can't be decoded or the daemon lacks git support, see
+/// [`check_git_support`].
+pub async fn ensure_git_support(client: &mut Client) -> anyhow::Result<()> {
+    match daemon_capabilities(client).await? {
+        Some(capabilities) => check_git_support(&capabilities),
+        // The git command itself fails if an older daemon doesn't support it
+        None => Ok(()),
+    }
+}
+
+/// Checks that `capabilities` include the `git` feature.
+///
+/// # Errors
+///
+/// Returns an error explaining that the daemon was built without git support.
+///
+/// # Examples
+///
+/// ```
+/// use nexsock::capabilities::check_git_support;
+/// use nexsock_protocol::commands::capabilities::Capabilities;
+///
+/// let capabilities = Capabilities {
+///     features: vec!["git".to_string()],
+///     ..Default::default()
+/// };
+///
+/// assert!(check_git_support(&capabilities).is_ok());
+/// ```
+pub fn check_git_support(capabilities: &Capabilities) -> anyhow::Result<()> {
+    if !capabilities.has_feature("git") {
+        bail!("this daemon was built without git support, git commands are unavailable");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexsock_protocol::commands::Command;
+
+    #[test]
+    fn test_daemon_without_git_is_reported() {
+        let capabilities = Capabilities {
+            protocol_version: 0,
+            features: vec!["lua".to_string(), "native".to_string()],
+            commands: vec![Command::Ping as u16, Command::ListServices as u16],
+        };
+
+        let error = check_git_support(&capabilities).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "this daemon was built without git support, git commands are unavailable"
+        );
+    }
+}
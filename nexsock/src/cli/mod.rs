@@ -1,29 +1,59 @@
 mod concurrent;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
 pub use concurrent::*;
 use derive_more::IsVariant;
 // Git commands are handled in commands.rs
-use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_config::{instance_from_env, validate_instance, NexsockConfig};
+use nexsock_protocol::commands::event::ServiceEventKind;
+use nexsock_protocol::commands::manage_service::{ReadyCondition, ServiceRef};
+use nexsock_protocol::commands::stdout::LogLevel;
 use std::collections::HashMap;
 #[cfg(windows)]
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
+use tracing::level_filters::LevelFilter;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     /// Socket path to use to communicate with the daemon
+    ///
+    /// Overrides the `NEXSOCK_SOCKET` environment variable, which overrides the config
     #[cfg(unix)]
     #[arg(short, long)]
     pub socket: Option<PathBuf>,
 
     /// Tcp address to use to communicate with the daemon
+    ///
+    /// Overrides the `NEXSOCK_SOCKET` environment variable, which overrides the config
     #[cfg(windows)]
     #[arg(short, long)]
     pub address: Option<SocketAddr>,
 
+    /// Directory holding the `config.toml` to use instead of the default config directory
+    #[arg(long, global = true, value_parser = parse_config_dir)]
+    pub config: Option<PathBuf>,
+
+    /// Name of the daemon instance to talk to, selecting its socket
+    ///
+    /// Overrides the `NEXSOCK_INSTANCE` environment variable
+    #[arg(long, global = true, value_parser = parse_instance)]
+    pub instance: Option<String>,
+
+    /// Skip checking that the referenced services exist before sending the command
+    #[arg(long, global = true)]
+    pub no_resolve: bool,
+
+    /// Log more, `-v` for info, `-vv` for debug and `-vvv` for trace, logs are written to stderr
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only log errors
+    #[arg(short, long)]
+    pub quiet: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -34,20 +64,37 @@ pub enum Commands {
     Start {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
         /// Environment variables in KEY=VALUE format
         #[arg(short, long, value_delimiter = ',')]
         env: Vec<String>,
+
+        /// Wait until the service is ready before returning
+        ///
+        /// One of `port` (the service accepts connections on its port), `log:<regex>` (a line of
+        /// its output matches) or `duration:<milliseconds>` (it is still running after that long)
+        #[arg(long, value_name = "CONDITION", value_parser = ReadyCondition::from_str)]
+        wait: Option<ReadyCondition>,
+
+        /// Seconds to wait for the service to become ready, defaults to 30
+        #[arg(long, value_name = "SECS", requires = "wait")]
+        wait_timeout: Option<u64>,
+
+        /// Clone the repository of the service first if its path doesn't exist
+        #[arg(long)]
+        clone: bool,
     },
 
     /// Stop a service
     Stop {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
     },
@@ -56,7 +103,8 @@ pub enum Commands {
     Restart {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
@@ -68,13 +116,49 @@ pub enum Commands {
     },
 
     /// List all services
-    List,
+    List {
+        /// Only list the services carrying this label, may be repeated to require several
+        #[arg(short, long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
+        labels: Vec<(String, String)>,
+    },
+
+    /// Search services by name, repository URL and labels
+    ///
+    /// Name matches are listed first, then repository URL matches and then label matches
+    Search {
+        /// Text to search for, matched case-insensitively anywhere in the fields
+        query: String,
+    },
 
     /// Get status of a service
     Status {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+    },
+
+    /// Print only the state of a service, cheaper than `status` for polling
+    State {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+    },
+
+    /// Check whether a service could be started without starting it
+    ///
+    /// Checks the repository path, port, run command, config file and dependencies of the
+    /// service, exiting with an error if any check fails
+    Validate {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
     },
@@ -83,11 +167,63 @@ pub enum Commands {
     Stdout {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+
+        /// Only show lines captured after this sequence number
+        ///
+        /// The sequence number of the newest line is printed to stderr after the output
+        #[arg(short, long)]
+        after_seq: Option<u64>,
+    },
+
+    /// Get the logs of a service, optionally filtered by level
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Logs {
+        #[command(subcommand)]
+        command: Option<LogsCommands>,
+
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(required = true, value_parser = ServiceRef::from_str)]
+        service: Option<ServiceRef>,
+
+        /// Only show entries at or above this level (trace, debug, info, warn, error)
+        ///
+        /// Lines without a detected level are always shown
+        #[arg(short, long, value_parser = LogLevel::from_str)]
+        min_level: Option<LogLevel>,
+
+        /// Show lines as the service wrote them, including stripped ANSI escape sequences
+        #[arg(long)]
+        raw: bool,
+    },
+
+    /// Send the stdin of this command to a running service, line by line until end of input
+    Stdin {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
     },
 
+    /// Print service lifecycle events as they happen, until interrupted
+    Events {
+        /// Only print the events of this service, by name or id
+        #[arg(short, long, value_parser = ServiceRef::from_str)]
+        service: Option<ServiceRef>,
+
+        /// Only print events of this kind, `started`, `stopped` or `crashed`. Can be repeated
+        #[arg(short, long = "kind", value_name = "KIND", value_parser = ServiceEventKind::from_str)]
+        kinds: Vec<ServiceEventKind>,
+    },
+
     /// Add a new service
     Add {
         /// Name of the service to add
@@ -117,15 +253,72 @@ pub enum Commands {
         /// Git authentication type (ssh_agent, token, none)
         #[arg(long, default_value = "ssh_agent")]
         git_auth: String,
+
+        /// Signal sent to stop the service before it is killed (term, int, quit, hup)
+        #[arg(long, default_value = "term")]
+        stop_signal: String,
+
+        /// Profile grouping the service with others that are started and stopped together
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Label to tag the service with, may be repeated
+        #[arg(short, long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
+        labels: Vec<(String, String)>,
     },
 
     /// Remove a service
     Remove {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
+
+        /// Only show what would be deleted, without removing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Remove the service even if other services depend on it, removing their dependencies
+        /// on it as well
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Copy a service and its configuration under a new name
+    Clone {
+        /// The name or id of the service to copy.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        source: ServiceRef,
+
+        /// Name of the copy
+        new_name: String,
+
+        /// Port number the copy runs on
+        #[arg(long)]
+        port: i64,
+
+        /// Path to the repository of the copy, the one of the source by default
+        #[arg(long)]
+        repo_path: Option<String>,
+
+        /// Make the copy depend on the same services as the source
+        #[arg(long)]
+        dependencies: bool,
+    },
+
+    /// Create or update services and dependencies from a TOML or YAML manifest
+    Apply {
+        /// Path to the manifest file, the format is picked from its extension
+        manifest: PathBuf,
+
+        /// Remove services and dependencies that are not part of the manifest
+        #[arg(long)]
+        prune: bool,
     },
 
     /// Update service configuration
@@ -140,6 +333,12 @@ pub enum Commands {
         command: DependencyCommands,
     },
 
+    /// Start, stop and list the services of a profile
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+
     /// Git operations
     Git {
         #[command(subcommand)]
@@ -151,24 +350,56 @@ pub enum Commands {
         #[command(subcommand)]
         command: ToolCommands,
     },
+
+    /// Show the CPU and memory usage of the running services
+    Top,
+
+    /// Print a snapshot of the daemon's internal state to attach to bug reports, secrets are
+    /// redacted. The daemon only answers when `server.debug_dump` is enabled
+    DebugDump,
+
+    /// Show the counters the daemon keeps about the commands it handled and the services it
+    /// started and stopped
+    Metrics,
+
+    /// Inspect the daemon's database and roll back its migrations
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
+    /// Write man pages for nexsock and all of its subcommands
+    #[command(hide = true)]
+    GenerateMan {
+        /// Directory the pages are written to, created if it does not exist
+        out_dir: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum ConfigCommands {
     /// Get service configuration
+    #[command(visible_alias = "show")]
     Get {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
+
+        /// Print the contents of the configuration file in the repository of the service
+        /// instead of the configuration
+        #[arg(long)]
+        contents: bool,
     },
 
     /// Update service configuration
     Update {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
@@ -177,12 +408,44 @@ pub enum ConfigCommands {
         filename: String,
 
         /// Configuration format (env, properties)
-        #[arg(short, long, default_value = "env")]
+        #[arg(short = 'F', long, default_value = "env")]
         format: String,
 
         /// Configuration file path
         #[arg(short, long)]
         run_command: String,
+
+        /// How the service output is parsed into log levels (raw, json, text)
+        #[arg(short, long, default_value = "raw")]
+        log_format: String,
+
+        /// Remove ANSI escape sequences such as colors from the captured output
+        #[arg(long)]
+        strip_ansi: bool,
+
+        /// Let the service write straight to the daemon's stdout and stderr instead of capturing
+        /// its output, for services that manage their own logging
+        #[arg(long)]
+        no_capture_output: bool,
+
+        /// Timestamp the log entries with the timestamp the service starts its lines with, given
+        /// as a chrono format such as `%Y-%m-%dT%H:%M:%S%.f%:z`
+        #[arg(long)]
+        log_timestamp_format: Option<String>,
+    },
+
+    /// Replace the configuration file in the repository of the service, the contents must parse
+    /// in the format of its configuration
+    Write {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+
+        /// Local file holding the new contents
+        file: PathBuf,
     },
 }
 
@@ -192,32 +455,40 @@ pub enum DependencyCommands {
     Add {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
         /// The name or id of the dependant service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         dependent: ServiceRef,
 
         /// Enable tunneling
         #[arg(short, long)]
         tunnel: bool,
+
+        /// Start the service even if the dependency isn't ready
+        #[arg(long)]
+        optional: bool,
     },
 
     /// Remove a dependency
     Remove {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
         /// The name or id of the dependant service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         dependent: ServiceRef,
     },
@@ -226,19 +497,99 @@ pub enum DependencyCommands {
     List {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+    },
+
+    /// List services that depend on a service
+    Dependents {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+    },
+
+    /// Show the details of a dependency
+    Info {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        service: ServiceRef,
+
+        /// The name or id of the dependant service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
+        #[arg(value_parser = ServiceRef::from_str)]
+        dependent: ServiceRef,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LogsCommands {
+    /// Empty the buffered logs of a running service without restarting it
+    Clear {
+        /// The name or id of a service.
+        ///
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
     },
 }
 
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    /// Start every service in a profile that isn't running yet
+    Start {
+        /// The name of the profile, as set with `nexsock add --profile`
+        profile: String,
+    },
+
+    /// Stop every running service in a profile
+    Stop {
+        /// The name of the profile, as set with `nexsock add --profile`
+        profile: String,
+    },
+
+    /// List the services in a profile
+    List {
+        /// The name of the profile, as set with `nexsock add --profile`
+        profile: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// List the database migrations with when they were applied, or if they are still pending
+    Migrations,
+
+    /// Revert the most recently applied database migrations, newest first
+    Rollback {
+        /// How many migrations to revert
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        steps: u32,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
 #[derive(Subcommand, IsVariant)]
 pub enum GitCommands {
     /// Checkout a branch
     Checkout {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
@@ -254,7 +605,8 @@ pub enum GitCommands {
     CheckoutCommit {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
@@ -266,7 +618,8 @@ pub enum GitCommands {
     Pull {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
     },
@@ -275,7 +628,8 @@ pub enum GitCommands {
     Status {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
     },
@@ -284,7 +638,8 @@ pub enum GitCommands {
     Log {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
@@ -301,7 +656,8 @@ pub enum GitCommands {
     Branches {
         /// The name or id of a service.
         ///
-        /// The Parser will consider it a name if it fails to parse the text as an integer
+        /// The Parser will consider it a name if it fails to parse the text as an integer,
+        /// prefix it with `name:` or `id:` to choose, e.g. `name:42` for a service named `42`
         #[arg(value_parser = ServiceRef::from_str)]
         service: ServiceRef,
 
@@ -403,9 +759,142 @@ impl FromStr for ToolType {
     }
 }
 
+/// Parses the `--config` flag, making sure it names an existing directory.
+fn parse_config_dir(s: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(s);
+
+    if !path.exists() {
+        return Err(format!("config directory `{s}` does not exist"));
+    }
+    if !path.is_dir() {
+        return Err(format!(
+            "`{s}` is not a directory, pass the directory containing `config.toml`"
+        ));
+    }
+
+    Ok(path)
+}
+
+fn parse_instance(s: &str) -> Result<String, String> {
+    validate_instance(s).map_err(|e| e.to_string())?;
+
+    Ok(s.to_string())
+}
+
+/// Parses a `--label` flag in the `key=value` form, the value may be empty but the key may not.
+fn parse_label(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("label `{s}` is not in the `key=value` form")),
+    }
+}
+
 // Note: Git command conversion is handled directly in commands.rs
 
+impl Commands {
+    /// Returns the existing services the command refers to.
+    ///
+    /// Services that are created by the command, such as the name given to `add`, are not
+    /// included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nexsock::cli::Commands;
+    /// use nexsock_protocol::commands::manage_service::{ReadyCondition, ServiceRef};
+    ///
+    /// let command = Commands::Stop {
+    ///     service: ServiceRef::Name("webapp".to_string()),
+    /// };
+    /// assert_eq!(command.service_refs(), vec![&ServiceRef::Name("webapp".to_string())]);
+    /// assert!(Commands::List { labels: Vec::new() }.service_refs().is_empty());
+    /// ```
+    pub fn service_refs(&self) -> Vec<&ServiceRef> {
+        match self {
+            Commands::Start { service, .. }
+            | Commands::Stop { service }
+            | Commands::Restart { service, .. }
+            | Commands::Status { service }
+            | Commands::State { service }
+            | Commands::Validate { service }
+            | Commands::Stdout { service, .. }
+            | Commands::Stdin { service }
+            | Commands::Remove { service, .. } => vec![service],
+            Commands::Clone { source, .. } => vec![source],
+            Commands::Logs {
+                command: Some(LogsCommands::Clear { service }),
+                ..
+            } => vec![service],
+            Commands::Logs { service, .. } => service.iter().collect(),
+            Commands::Events { service, .. } => service.iter().collect(),
+            Commands::Config { command } => match command {
+                ConfigCommands::Get { service, .. }
+                | ConfigCommands::Update { service, .. }
+                | ConfigCommands::Write { service, .. } => vec![service],
+            },
+            Commands::Dependency { command } => match command {
+                DependencyCommands::Add {
+                    service, dependent, ..
+                }
+                | DependencyCommands::Remove { service, dependent }
+                | DependencyCommands::Info { service, dependent } => vec![service, dependent],
+                DependencyCommands::List { service }
+                | DependencyCommands::Dependents { service } => vec![service],
+            },
+            Commands::Git { command } => match command {
+                GitCommands::Checkout { service, .. }
+                | GitCommands::CheckoutCommit { service, .. }
+                | GitCommands::Pull { service, .. }
+                | GitCommands::Status { service }
+                | GitCommands::Log { service, .. }
+                | GitCommands::Branches { service, .. } => vec![service],
+            },
+            Commands::List { .. }
+            | Commands::Search { .. }
+            | Commands::Top
+            | Commands::DebugDump
+            | Commands::Metrics
+            | Commands::Db { .. }
+            | Commands::Add { .. }
+            | Commands::Apply { .. }
+            | Commands::Profile { .. }
+            | Commands::Tools { .. }
+            | Commands::GenerateMan { .. } => Vec::new(),
+        }
+    }
+}
+
 impl Cli {
+    /// Loads the configuration from the `--config` directory, or the default config directory
+    /// when the flag is not set, with the defaults of the `--instance` instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration can't be loaded.
+    pub fn load_config(&self) -> anyhow::Result<NexsockConfig> {
+        let instance = match &self.instance {
+            Some(instance) => Some(instance.clone()),
+            None => instance_from_env()?,
+        };
+
+        Ok(NexsockConfig::for_instance(self.config.as_deref(), instance.as_deref())?)
+    }
+
+    /// The most verbose level to log, warnings by default, raised by `--verbose` and lowered to
+    /// errors only by `--quiet`.
+    pub fn log_level(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::ERROR;
+        }
+
+        match self.verbose {
+            0 => LevelFilter::WARN,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
     /// Parses a list of environment variable strings in `KEY=VALUE` format into a map.
     ///
     /// Ignores entries that do not contain an '=' character.
@@ -434,3 +923,177 @@ impl Cli {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_flag_selects_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[server]\ncleanup_interval = 123\n",
+        )
+        .unwrap();
+
+        let dir_arg = dir.path().to_str().unwrap();
+        let cli = Cli::try_parse_from(["nexsock", "list", "--config", dir_arg]).unwrap();
+
+        assert_eq!(cli.config.as_deref(), Some(dir.path()));
+        assert_eq!(cli.load_config().unwrap().server().cleanup_interval, 123);
+    }
+
+    #[test]
+    fn test_config_flag_rejects_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+
+        let error = Cli::try_parse_from(["nexsock", "--config", missing.to_str().unwrap(), "list"])
+            .err()
+            .unwrap();
+
+        assert!(error.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_instance_flag_rejects_path_names() {
+        let cli = Cli::try_parse_from(["nexsock", "list", "--instance", "staging"]).unwrap();
+        assert_eq!(cli.instance.as_deref(), Some("staging"));
+
+        let error = Cli::try_parse_from(["nexsock", "--instance", "../staging", "list"])
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("Invalid instance name"));
+    }
+
+    #[test]
+    fn test_start_wait_parses_ready_conditions() {
+        let wait_of = |condition: &str| {
+            let cli = Cli::try_parse_from(["nexsock", "start", "web", "--wait", condition]).unwrap();
+            match cli.command {
+                Commands::Start { wait, .. } => wait,
+                _ => unreachable!(),
+            }
+        };
+
+        assert_eq!(wait_of("port"), Some(ReadyCondition::Port));
+        assert_eq!(
+            wait_of("log:listening on \\d+"),
+            Some(ReadyCondition::LogMatch("listening on \\d+".to_string()))
+        );
+        assert_eq!(wait_of("duration:1500"), Some(ReadyCondition::Duration(1500)));
+
+        assert!(Cli::try_parse_from(["nexsock", "start", "web", "--wait", "socket"]).is_err());
+        assert!(Cli::try_parse_from(["nexsock", "start", "web", "--wait-timeout", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_list_label_flags_parse_key_value_pairs() {
+        let cli =
+            Cli::try_parse_from(["nexsock", "list", "--label", "team=payments", "-l", "env="])
+                .unwrap();
+        let Commands::List { labels } = cli.command else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            labels,
+            [
+                ("team".to_string(), "payments".to_string()),
+                ("env".to_string(), String::new())
+            ]
+        );
+
+        for label in ["team", "=payments"] {
+            let error = Cli::try_parse_from(["nexsock", "list", "--label", label])
+                .err()
+                .unwrap();
+            assert!(error.to_string().contains("`key=value`"), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_logs_clear_parses_as_subcommand() {
+        let cli = Cli::try_parse_from(["nexsock", "logs", "clear", "api"]).unwrap();
+        let Commands::Logs {
+            command: Some(LogsCommands::Clear { service }),
+            ..
+        } = cli.command
+        else {
+            unreachable!()
+        };
+        assert_eq!(service, ServiceRef::Name("api".to_string()));
+
+        let cli = Cli::try_parse_from(["nexsock", "logs", "api", "--raw"]).unwrap();
+        let Commands::Logs {
+            command: None,
+            service,
+            raw,
+            ..
+        } = cli.command
+        else {
+            unreachable!()
+        };
+        assert_eq!(service, Some(ServiceRef::Name("api".to_string())));
+        assert!(raw);
+
+        assert!(Cli::try_parse_from(["nexsock", "logs"]).is_err());
+    }
+
+    #[test]
+    fn test_config_show_parses_contents_flag() {
+        let cli = Cli::try_parse_from(["nexsock", "config", "show", "api", "--contents"]).unwrap();
+        let Commands::Config {
+            command: ConfigCommands::Get { service, contents },
+        } = cli.command
+        else {
+            unreachable!()
+        };
+
+        assert_eq!(service, ServiceRef::Name("api".to_string()));
+        assert!(contents);
+    }
+
+    #[test]
+    fn test_verbosity_flags_set_log_level() {
+        let level_of = |flags: &[&str]| {
+            let args = ["nexsock"]
+                .into_iter()
+                .chain(flags.iter().copied())
+                .chain(["list"]);
+            Cli::try_parse_from(args).unwrap().log_level()
+        };
+
+        assert_eq!(level_of(&[]), LevelFilter::WARN);
+        assert_eq!(level_of(&["-v"]), LevelFilter::INFO);
+        assert_eq!(level_of(&["-vv"]), LevelFilter::DEBUG);
+        assert_eq!(level_of(&["-vvv"]), LevelFilter::TRACE);
+        assert_eq!(level_of(&["--verbose", "--verbose"]), LevelFilter::DEBUG);
+        assert_eq!(level_of(&["-q"]), LevelFilter::ERROR);
+
+        assert!(Cli::try_parse_from(["nexsock", "-v", "-q", "list"]).is_err());
+    }
+
+    #[test]
+    fn test_db_rollback_parses_steps() {
+        let rollback_of = |flags: &[&str]| {
+            let args = ["nexsock", "db", "rollback"]
+                .into_iter()
+                .chain(flags.iter().copied());
+            let Commands::Db {
+                command: DbCommands::Rollback { steps, yes },
+            } = Cli::try_parse_from(args).unwrap().command
+            else {
+                unreachable!()
+            };
+            (steps, yes)
+        };
+
+        assert_eq!(rollback_of(&[]), (1, false));
+        assert_eq!(rollback_of(&["--steps", "3", "--yes"]), (3, true));
+        assert_eq!(rollback_of(&["-y"]), (1, true));
+
+        assert!(Cli::try_parse_from(["nexsock", "db", "rollback", "--steps", "0"]).is_err());
+    }
+}
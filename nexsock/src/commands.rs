@@ -1,23 +1,43 @@
-use crate::cli::{Cli, Commands, ConfigCommands, DependencyCommands, GitCommands};
+use crate::cli::{
+    Cli, Commands, ConfigCommands, DbCommands, DependencyCommands, GitCommands, LogsCommands,
+    ProfileCommands,
+};
+use anyhow::Context;
 use nexsock_protocol::commands::add_service::AddServiceCommand;
 use nexsock_protocol::commands::config::{
-    ConfigFormat, GetConfig, ServiceConfigPayload, UpdateConfigCommand,
+    ConfigFormat, GetConfig, GetConfigFile, LogFormat, ServiceConfigPayload, UpdateConfigCommand,
+    WriteConfigCommand,
 };
+use nexsock_protocol::commands::debug_dump::DebugDumpCommand;
 use nexsock_protocol::commands::dependency::{
-    AddDependencyCommand, ListDependenciesCommand, RemoveDependencyCommand,
+    AddDependencyCommand, GetDependencyInfoCommand, ListDependenciesCommand, ListDependentsCommand,
+    RemoveDependencyCommand,
 };
 use nexsock_protocol::commands::git::{
     CheckoutCommand, GetRepoStatusCommand, GitCheckoutCommitCommand, GitListBranchesCommand,
     GitLogCommand, GitPullCommand,
 };
+use nexsock_protocol::commands::label::ListByLabelsCommand;
 use nexsock_protocol::commands::list_services::ListServicesCommand;
 use nexsock_protocol::commands::manage_service::{
-    RemoveServiceCommand, RestartServiceCommand, ServiceRef, StartServiceCommand,
-    StopServiceCommand,
+    CloneServiceCommand, RemoveServiceCommand, RestartServiceCommand, ServiceRef,
+    StartServiceCommand, StopServiceCommand, StopSignal,
+};
+use nexsock_protocol::commands::manifest::{ApplyManifestCommand, ServiceManifest};
+use nexsock_protocol::commands::metrics::ExportMetricsCommand;
+use nexsock_protocol::commands::migration::{MigrationStatusCommand, RollbackMigrationCommand};
+use nexsock_protocol::commands::profile::{
+    ListByProfileCommand, StartProfileCommand, StopProfileCommand,
 };
-use nexsock_protocol::commands::service_status::GetServiceStatus;
-use nexsock_protocol::commands::stdout::GetServiceStdout;
+use nexsock_protocol::commands::search::SearchServicesCommand;
+use nexsock_protocol::commands::service_status::{GetServiceState, GetServiceStatus};
+use nexsock_protocol::commands::stdout::{
+    ClearLogsCommand, GetServiceLogsCommand, GetServiceStdout,
+};
+use nexsock_protocol::commands::validate::ValidateServiceCommand;
 use nexsock_protocol::commands::ServiceCommand;
+use std::collections::BTreeMap;
+use std::path::Path;
 
 /// Converts a parsed CLI command into the corresponding service command.
 ///
@@ -33,30 +53,68 @@ use nexsock_protocol::commands::ServiceCommand;
 /// use nexsock::cli::Commands;
 /// use nexsock::commands::create_command;
 ///
-/// let cli_command = Commands::List;
+/// let cli_command = Commands::List { labels: Vec::new() };
 /// let cmd = create_command(cli_command).unwrap();
 /// // `cmd` is a `ServiceCommand` that lists all services.
 /// ```
 pub fn create_command(cli: Commands) -> anyhow::Result<ServiceCommand> {
     match cli {
-        Commands::Stdout { service } => Ok(GetServiceStdout::new(service).into()),
+        Commands::Stdout { service, after_seq } => {
+            Ok(GetServiceStdout::new(service, after_seq).into())
+        }
 
-        Commands::Start { service, env } => {
+        Commands::Logs {
+            command: Some(LogsCommands::Clear { service }),
+            ..
+        } => Ok(ClearLogsCommand::new(service).into()),
+
+        Commands::Logs {
+            service: Some(service),
+            min_level,
+            raw,
+            ..
+        } => Ok(GetServiceLogsCommand::new(service, min_level, raw).into()),
+
+        Commands::Start {
+            service,
+            env,
+            wait,
+            wait_timeout,
+            clone,
+        } => {
             let env_vars = Cli::parse_env_vars(env);
-            Ok(StartServiceCommand::new(service, env_vars).into())
+            Ok(StartServiceCommand::new(service, env_vars, wait, wait_timeout, clone).into())
         }
 
         Commands::Stop { service } => Ok(StopServiceCommand::new(service).into()),
 
         Commands::Restart { service, env } => {
             let env_vars = Cli::parse_env_vars(env);
-            Ok(RestartServiceCommand::new(service, env_vars).into())
+            Ok(RestartServiceCommand::new(service, env_vars, None, None, false).into())
+        }
+
+        Commands::List { labels } if labels.is_empty() => Ok(ListServicesCommand::new().into()),
+        Commands::List { labels } => {
+            Ok(ListByLabelsCommand::new(labels.into_iter().collect::<BTreeMap<_, _>>()).into())
         }
 
-        Commands::List => Ok(ListServicesCommand::new().into()),
+        Commands::Search { query } => Ok(SearchServicesCommand::new(query).into()),
+
+        Commands::DebugDump => Ok(DebugDumpCommand::new().into()),
+
+        Commands::Metrics => Ok(ExportMetricsCommand::new().into()),
+
+        Commands::Db { command } => match command {
+            DbCommands::Migrations => Ok(MigrationStatusCommand::new().into()),
+            DbCommands::Rollback { steps, .. } => Ok(RollbackMigrationCommand::new(steps).into()),
+        },
 
         Commands::Status { service } => Ok(GetServiceStatus::new(service).into()),
 
+        Commands::State { service } => Ok(GetServiceState::new(service).into()),
+
+        Commands::Validate { service } => Ok(ValidateServiceCommand::new(service).into()),
+
         Commands::Add {
             name,
             repo_url,
@@ -66,6 +124,9 @@ pub fn create_command(cli: Commands) -> anyhow::Result<ServiceCommand> {
             run_command,
             git_branch,
             git_auth,
+            stop_signal,
+            profile,
+            labels,
         } => {
             let config = if let Some(config_path) = config {
                 let format = if config_path.extension().and_then(|s| s.to_str()) == Some("env") {
@@ -83,6 +144,10 @@ pub fn create_command(cli: Commands) -> anyhow::Result<ServiceCommand> {
                         .to_string(),
                     format,
                     run_command: run_command.unwrap_or_default(),
+                    log_format: LogFormat::default(),
+                    strip_ansi: false,
+                    capture_output: true,
+                    log_timestamp_format: None,
                 })
             } else {
                 None
@@ -102,23 +167,69 @@ pub fn create_command(cli: Commands) -> anyhow::Result<ServiceCommand> {
                 config,
                 git_branch,
                 git_auth_type,
+                StopSignal::from(stop_signal),
+                profile,
+                labels.into_iter().collect::<BTreeMap<_, _>>(),
             )
             .into())
         }
 
-        Commands::Remove { service } => Ok(RemoveServiceCommand::new(service).into()),
+        Commands::Remove {
+            service,
+            dry_run,
+            force,
+        } => Ok(RemoveServiceCommand::new(service, dry_run, force).into()),
+
+        Commands::Clone {
+            source,
+            new_name,
+            port,
+            repo_path,
+            dependencies,
+        } => Ok(CloneServiceCommand::new(source, new_name, port, repo_path, dependencies).into()),
+
+        Commands::Apply { manifest, prune } => {
+            let manifest = load_manifest(&manifest)?;
+
+            Ok(ApplyManifestCommand::new(manifest, prune).into())
+        }
 
         Commands::Config { command } => match command {
-            ConfigCommands::Get { service } => Ok(GetConfig::new(service).into()),
+            ConfigCommands::Get {
+                service,
+                contents: true,
+            } => Ok(GetConfigFile::new(service).into()),
+            ConfigCommands::Get { service, .. } => Ok(GetConfig::new(service).into()),
             ConfigCommands::Update {
                 service,
                 filename,
                 format,
                 run_command,
+                log_format,
+                strip_ansi,
+                no_capture_output,
+                log_timestamp_format,
             } => {
                 let format = ConfigFormat::from(format);
+                let log_format = LogFormat::from(log_format);
 
-                Ok(UpdateConfigCommand::new(service, filename, format, run_command).into())
+                Ok(UpdateConfigCommand::new(
+                    service,
+                    filename,
+                    format,
+                    run_command,
+                    log_format,
+                    strip_ansi,
+                    !no_capture_output,
+                    log_timestamp_format,
+                )
+                .into())
+            }
+            ConfigCommands::Write { service, file } => {
+                let contents = std::fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read `{}`", file.display()))?;
+
+                Ok(WriteConfigCommand::new(service, contents).into())
             }
         },
 
@@ -127,13 +238,26 @@ pub fn create_command(cli: Commands) -> anyhow::Result<ServiceCommand> {
                 service,
                 dependent,
                 tunnel,
-            } => Ok(AddDependencyCommand::new(service, dependent, tunnel).into()),
+                optional,
+            } => Ok(AddDependencyCommand::new(service, dependent, tunnel, optional).into()),
             DependencyCommands::Remove { service, dependent } => {
                 Ok(RemoveDependencyCommand::new(service, dependent).into())
             }
             DependencyCommands::List { service } => {
                 Ok(ListDependenciesCommand::new(service).into())
             }
+            DependencyCommands::Dependents { service } => {
+                Ok(ListDependentsCommand::new(service).into())
+            }
+            DependencyCommands::Info { service, dependent } => {
+                Ok(GetDependencyInfoCommand::new(service, dependent).into())
+            }
+        },
+
+        Commands::Profile { command } => match command {
+            ProfileCommands::Start { profile } => Ok(StartProfileCommand::new(profile).into()),
+            ProfileCommands::Stop { profile } => Ok(StopProfileCommand::new(profile).into()),
+            ProfileCommands::List { profile } => Ok(ListByProfileCommand::new(profile).into()),
         },
 
         Commands::Git { command } => match command {
@@ -159,3 +283,12 @@ pub fn create_command(cli: Commands) -> anyhow::Result<ServiceCommand> {
         _ => Err(anyhow::anyhow!("invalid command")),
     }
 }
+
+/// Reads a service manifest from disk, picking TOML or YAML based on the file extension.
+fn load_manifest(path: &Path) -> anyhow::Result<ServiceManifest> {
+    config::Config::builder()
+        .add_source(config::File::from(path))
+        .build()
+        .and_then(|manifest| manifest.try_deserialize())
+        .with_context(|| format!("Failed to load manifest `{}`", path.display()))
+}
@@ -0,0 +1,63 @@
+//! Confirmation prompts guarding destructive commands such as `nexsock db rollback`.
+
+use anyhow::{bail, Context};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Asks on stderr whether to continue with `action` and reads the answer from stdin.
+///
+/// # Errors
+///
+/// Returns an error if stdin isn't a terminal, as nobody can answer the prompt then, or if the
+/// answer can't be read.
+pub fn confirm(action: &str) -> anyhow::Result<bool> {
+    if !io::stdin().is_terminal() {
+        bail!("Refusing to {action} without confirmation, pass `--yes` to skip the prompt");
+    }
+
+    confirm_with(io::stdin().lock(), io::stderr(), action)
+}
+
+/// Writes the prompt for `action` to `output` and returns whether the line read from `input`
+/// answers yes. Anything but `y` or `yes` declines.
+fn confirm_with(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    action: &str,
+) -> anyhow::Result<bool> {
+    write!(output, "Are you sure you want to {action}? [y/N] ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input
+        .read_line(&mut answer)
+        .context("Failed to read the confirmation")?;
+
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(input: &str) -> bool {
+        confirm_with(input.as_bytes(), Vec::new(), "roll back 1 migration").unwrap()
+    }
+
+    #[test]
+    fn test_confirm_accepts_yes() {
+        assert!(answer("y\n"));
+        assert!(answer("YES\n"));
+        assert!(answer("  yes  \n"));
+    }
+
+    #[test]
+    fn test_confirm_declines_anything_else() {
+        assert!(!answer("\n"));
+        assert!(!answer("n\n"));
+        assert!(!answer("yep\n"));
+        assert!(!answer(""));
+    }
+}
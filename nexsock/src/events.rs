@@ -0,0 +1,60 @@
+//! The `nexsock events` stream of service lifecycle events.
+
+use nexsock_client::Client;
+use nexsock_protocol::commands::event::{ServiceEvent, SubscribePayload};
+use std::collections::HashMap;
+
+/// Prints the events matching `payload` as the daemon sends them, until it closes the connection.
+///
+/// The services are listed once before subscribing to show events with the name of their
+/// service.
+///
+/// # Errors
+///
+/// Returns an error if the services can't be listed, the subscription is refused, e.g. for an
+/// unknown service, or the connection to the daemon is lost.
+pub async fn print_events(client: &mut Client, payload: SubscribePayload) -> anyhow::Result<()> {
+    let names = client
+        .list_services()
+        .await?
+        .services
+        .into_iter()
+        .map(|service| (service.id, service.name))
+        .collect::<HashMap<_, _>>();
+
+    client.subscribe(payload).await?;
+
+    loop {
+        let event = client.next_event().await?;
+        println!("{}", format_event(&event.event, &names));
+    }
+}
+
+/// Formats `event` on a single line, naming the service if it is in `names`.
+///
+/// # Examples
+///
+/// ```
+/// use nexsock::events::format_event;
+/// use nexsock_protocol::commands::event::ServiceEvent;
+/// use std::collections::HashMap;
+///
+/// let names = HashMap::from([(1, "api".to_string())]);
+///
+/// let started = ServiceEvent::Started { service_id: 1 };
+/// assert_eq!(format_event(&started, &names), "api (1) started");
+///
+/// let crashed = ServiceEvent::Crashed { service_id: 2, exit_code: Some(3) };
+/// assert_eq!(format_event(&crashed, &names), "service 2 crashed with exit code 3");
+/// ```
+pub fn format_event(event: &ServiceEvent, names: &HashMap<i64, String>) -> String {
+    let Some(name) = names.get(&event.service_id()) else {
+        return event.to_string();
+    };
+
+    let service = format!("service {}", event.service_id());
+    let described = event.to_string();
+    let what = described.strip_prefix(&service).unwrap_or(&described);
+
+    format!("{name} ({}){what}", event.service_id())
+}
@@ -1,2 +1,13 @@
+#![deny(clippy::dbg_macro)]
+
+pub mod capabilities;
 pub mod cli;
 pub mod commands;
+pub mod confirm;
+pub mod events;
+pub mod man;
+pub mod output;
+pub mod resolve;
+pub mod socket;
+pub mod stdin;
+pub mod top;
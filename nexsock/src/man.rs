@@ -0,0 +1,52 @@
+//! Man page generation for packaging.
+
+use crate::cli::Cli;
+use anyhow::Context;
+use clap::CommandFactory;
+use std::path::Path;
+
+/// Writes roff man pages for `nexsock` and every visible subcommand to `out_dir`.
+///
+/// Pages are named after the command path, e.g. `nexsock.1`, `nexsock-start.1` and
+/// `nexsock-config-update.1`. Hidden commands don't get a page.
+///
+/// # Errors
+///
+/// Returns an error if `out_dir` can't be created or a page can't be written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use nexsock::man::generate_man_pages;
+///
+/// generate_man_pages("target/man").unwrap();
+/// ```
+pub fn generate_man_pages(out_dir: impl AsRef<Path>) -> anyhow::Result<()> {
+    let out_dir = out_dir.as_ref();
+
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("failed to create `{}`", out_dir.display()))?;
+
+    clap_mangen::generate_to(Cli::command(), out_dir)
+        .with_context(|| format!("failed to write man pages to `{}`", out_dir.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_written_per_subcommand() {
+        let dir = tempfile::tempdir().unwrap();
+
+        generate_man_pages(dir.path()).unwrap();
+
+        let start = std::fs::read_to_string(dir.path().join("nexsock-start.1")).unwrap();
+        assert!(!start.is_empty());
+        assert!(start.contains("Start a service"));
+
+        assert!(dir.path().join("nexsock.1").exists());
+        assert!(dir.path().join("nexsock-config-update.1").exists());
+        assert!(!dir.path().join("nexsock-generate-man.1").exists());
+    }
+}
@@ -0,0 +1,297 @@
+//! Printing the responses that have no dedicated output in the CLI.
+
+use chrono::DateTime;
+use nexsock_protocol::commands::debug_dump::DebugDump;
+use nexsock_protocol::commands::manage_service::RemovalPlan;
+use nexsock_protocol::commands::metrics::DaemonMetrics;
+use nexsock_protocol::commands::migration::MigrationStatusResponse;
+use nexsock_protocol::commands::CommandPayload;
+use std::fmt::Write as _;
+
+/// Formats `payload` for printing, an empty payload formats as an empty string.
+///
+/// # Examples
+///
+/// ```
+/// use nexsock::output::format_payload;
+/// use nexsock_protocol::commands::CommandPayload;
+///
+/// assert_eq!(format_payload(&CommandPayload::Empty), "");
+/// ```
+pub fn format_payload(payload: &CommandPayload) -> String {
+    match payload {
+        CommandPayload::Empty => String::new(),
+        CommandPayload::DebugDump(dump) => format_debug_dump(dump),
+        CommandPayload::Metrics(metrics) => format_metrics(metrics),
+        CommandPayload::Migrations(status) => format_migrations(status),
+        CommandPayload::RemovalPlan(plan) => format_removal_plan(plan),
+        payload => format!("{payload:#?}\n"),
+    }
+}
+
+/// Formats `dump` as one section per part of the daemon state.
+pub fn format_debug_dump(dump: &DebugDump) -> String {
+    let mut out = format!("version: {}\n", dump.version);
+    let _ = writeln!(out, "instance: {}", dump.instance.as_deref().unwrap_or("default"));
+
+    let _ = writeln!(out, "\nrunning services: {}", dump.running_services.len());
+    for service in &dump.running_services {
+        let pid = service
+            .pid
+            .map_or_else(|| "-".to_string(), |pid| pid.to_string());
+        let _ = writeln!(
+            out,
+            "  {} (pid {pid}, {}, {} buffered log entries)",
+            service.id, service.state, service.buffered_log_entries
+        );
+
+        for (name, value) in &service.env_vars {
+            let _ = writeln!(out, "    {name}={value}");
+        }
+    }
+
+    for (title, plugins) in [
+        ("lua plugins", &dump.lua_plugins),
+        ("native plugins", &dump.native_plugins),
+    ] {
+        let _ = writeln!(out, "\n{title}: {}", plugins.len());
+        for plugin in plugins {
+            let _ = writeln!(out, "  {plugin}");
+        }
+    }
+
+    if !dump.failed_lua_plugins.is_empty() {
+        let _ = writeln!(
+            out,
+            "\nfailed lua plugins: {}",
+            dump.failed_lua_plugins.len()
+        );
+        for (plugin, error) in &dump.failed_lua_plugins {
+            let _ = writeln!(out, "  {plugin}: {error}");
+        }
+    }
+
+    let _ = writeln!(out, "\nconfig:\n{}", dump.config);
+
+    out
+}
+
+/// Formats `metrics` as one counter per line.
+pub fn format_metrics(metrics: &DaemonMetrics) -> String {
+    let counters = [
+        ("commands handled", metrics.commands_handled),
+        ("commands failed", metrics.commands_failed),
+        ("services started", metrics.services_started),
+        ("start failures", metrics.start_failures),
+        ("services stopped", metrics.services_stopped),
+        ("running services", metrics.running_services),
+        ("invalid state transitions", metrics.invalid_transitions),
+    ];
+
+    let mut out = String::new();
+    for (name, value) in counters {
+        let _ = writeln!(out, "{name}: {value}");
+    }
+
+    out
+}
+
+/// Formats `status` as one migration per line with when it was applied, or `pending`.
+pub fn format_migrations(status: &MigrationStatusResponse) -> String {
+    let width = status
+        .migrations
+        .iter()
+        .map(|migration| migration.name.len())
+        .max()
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    for migration in &status.migrations {
+        let state = match migration.applied_at {
+            Some(at) => match DateTime::from_timestamp(at, 0) {
+                Some(at) => format!("applied {}", at.format("%Y-%m-%d %H:%M:%S UTC")),
+                None => format!("applied {at}"),
+            },
+            None => "pending".to_string(),
+        };
+
+        let _ = writeln!(out, "{:<width$}  {state}", migration.name);
+    }
+
+    out
+}
+
+/// Formats `plan` as what removing the service deletes, one dependency per line.
+pub fn format_removal_plan(plan: &RemovalPlan) -> String {
+    let mut out = format!("service: {}\n", plan.service_name);
+    let _ = writeln!(
+        out,
+        "stops the running service: {}",
+        if plan.will_stop { "yes" } else { "no" }
+    );
+
+    let _ = writeln!(out, "dependencies: {}", plan.dependencies_to_delete.len());
+    for dependency in &plan.dependencies_to_delete {
+        let tunnel = if dependency.tunnel_enabled {
+            " (tunnel)"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "  {} -> {}{tunnel}",
+            dependency.service, dependency.dependent_service
+        );
+    }
+
+    let config = plan
+        .config_to_delete
+        .as_ref()
+        .and_then(|config| config.filename.as_deref())
+        .unwrap_or("none");
+    let _ = writeln!(out, "config: {config}");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexsock_protocol::commands::debug_dump::RunningServiceDump;
+    use nexsock_protocol::commands::manage_service::DependencyEdge;
+    use nexsock_protocol::commands::migration::MigrationInfo;
+    use nexsock_protocol::commands::service_status::{ServiceConfig, ServiceState};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_format_payload_does_not_print_debug_locations() {
+        let output = format_payload(&CommandPayload::Stdout("hello".to_string()));
+
+        assert_eq!(output, "Stdout(\n    \"hello\",\n)\n");
+        // `dbg!` prefixes its output with the file and line it was called from
+        assert!(!output.contains(".rs:"));
+    }
+
+    #[test]
+    fn test_format_debug_dump_sections() {
+        let dump = DebugDump {
+            version: "0.1.0".to_string(),
+            instance: Some("staging".to_string()),
+            running_services: vec![RunningServiceDump {
+                id: 3,
+                pid: Some(42),
+                state: ServiceState::Running,
+                env_vars: BTreeMap::from([("API_TOKEN".to_string(), "<redacted>".to_string())]),
+                buffered_log_entries: 7,
+            }],
+            config: "{}".to_string(),
+            lua_plugins: vec!["plugins/hello.lua".to_string()],
+            native_plugins: Vec::new(),
+            failed_lua_plugins: BTreeMap::from([(
+                "plugins/broken.lua".to_string(),
+                "syntax error".to_string(),
+            )]),
+        };
+
+        assert_eq!(
+            format_debug_dump(&dump),
+            "version: 0.1.0\n\
+             instance: staging\n\
+             \n\
+             running services: 1\n  \
+               3 (pid 42, Running, 7 buffered log entries)\n    \
+                 API_TOKEN=<redacted>\n\
+             \n\
+             lua plugins: 1\n  \
+               plugins/hello.lua\n\
+             \n\
+             native plugins: 0\n\
+             \n\
+             failed lua plugins: 1\n  \
+               plugins/broken.lua: syntax error\n\
+             \n\
+             config:\n\
+             {}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_metrics_lists_every_counter() {
+        let metrics = DaemonMetrics {
+            commands_handled: 12,
+            commands_failed: 2,
+            services_started: 3,
+            start_failures: 1,
+            services_stopped: 2,
+            running_services: 1,
+            invalid_transitions: 1,
+        };
+
+        assert_eq!(
+            format_metrics(&metrics),
+            "commands handled: 12\n\
+             commands failed: 2\n\
+             services started: 3\n\
+             start failures: 1\n\
+             services stopped: 2\n\
+             running services: 1\n\
+             invalid state transitions: 1\n"
+        );
+    }
+
+    #[test]
+    fn test_format_migrations_shows_applied_and_pending() {
+        let status = MigrationStatusResponse {
+            migrations: vec![
+                MigrationInfo {
+                    name: "m20220101_000001_create_tables".to_string(),
+                    applied_at: Some(1_760_000_000),
+                },
+                MigrationInfo {
+                    name: "m20261015_000002_add_column".to_string(),
+                    applied_at: None,
+                },
+            ],
+        };
+
+        assert_eq!(
+            format_migrations(&status),
+            "m20220101_000001_create_tables  applied 2025-10-09 08:53:20 UTC\n\
+             m20261015_000002_add_column     pending\n"
+        );
+    }
+
+    #[test]
+    fn test_format_removal_plan_lists_what_gets_deleted() {
+        let plan = RemovalPlan {
+            service_name: "database".to_string(),
+            dependencies_to_delete: vec![
+                DependencyEdge {
+                    service: "database".to_string(),
+                    dependent_service: "cache".to_string(),
+                    tunnel_enabled: false,
+                },
+                DependencyEdge {
+                    service: "app".to_string(),
+                    dependent_service: "database".to_string(),
+                    tunnel_enabled: true,
+                },
+            ],
+            config_to_delete: Some(ServiceConfig {
+                filename: Some("database.env".to_string()),
+                ..Default::default()
+            }),
+            will_stop: true,
+        };
+
+        assert_eq!(
+            format_removal_plan(&plan),
+            "service: database\n\
+             stops the running service: yes\n\
+             dependencies: 2\n  \
+               database -> cache\n  \
+               app -> database (tunnel)\n\
+             config: database.env\n"
+        );
+    }
+}
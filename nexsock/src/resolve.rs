@@ -0,0 +1,184 @@
+//! Client side validation of service references.
+//!
+//! [`ServiceRef::from_str`](std::str::FromStr) treats anything that is not an integer as a name,
+//! so a mistyped name is only reported by the daemon once the real command runs. Resolving the
+//! references against the list of services first lets the CLI fail early and suggest the name
+//! that was most likely meant.
+
+use anyhow::bail;
+use nexsock_client::Client;
+use nexsock_protocol::commands::list_services::ListServicesResponse;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+
+/// Checks that every service in `services` exists, fetching the service list from the daemon.
+///
+/// Does nothing, and sends nothing, when `services` is empty.
+///
+/// # Errors
+///
+/// Returns an error if the service list can't be fetched or a service does not exist, see
+/// [`check_services`].
+pub async fn resolve_services(
+    client: &mut Client,
+    services: &[&ServiceRef],
+) -> anyhow::Result<()> {
+    if services.is_empty() {
+        return Ok(());
+    }
+
+    let known = client.list_services().await?;
+
+    check_services(services, &known)
+}
+
+/// Checks that every service in `services` is part of `known`.
+///
+/// # Errors
+///
+/// Returns an error naming the first service that does not exist. For names, the error suggests
+/// the closest known name if one is similar enough.
+///
+/// # Examples
+///
+/// ```
+/// use nexsock::resolve::check_services;
+/// use nexsock_protocol::commands::list_services::{ListServicesResponse, ServiceInfo};
+/// use nexsock_protocol::commands::manage_service::ServiceRef;
+///
+/// let known = ListServicesResponse {
+///     services: vec![ServiceInfo {
+///         id: 1,
+///         name: "webapp".to_string(),
+///         ..Default::default()
+///     }],
+/// };
+///
+/// assert!(check_services(&[&ServiceRef::Id(1)], &known).is_ok());
+///
+/// let error = check_services(&[&ServiceRef::Name("wepapp".to_string())], &known).unwrap_err();
+/// assert_eq!(error.to_string(), "no such service `wepapp`, did you mean `webapp`?");
+/// ```
+pub fn check_services(
+    services: &[&ServiceRef],
+    known: &ListServicesResponse,
+) -> anyhow::Result<()> {
+    for service in services {
+        match service {
+            ServiceRef::Id(id) => {
+                if !known.services.iter().any(|info| info.id == *id) {
+                    bail!("no service with id `{id}`");
+                }
+            }
+            ServiceRef::Name(name) => {
+                if known.services.iter().any(|info| info.name == *name) {
+                    continue;
+                }
+
+                let names = known.services.iter().map(|info| info.name.as_str());
+                match suggest(name, names) {
+                    Some(suggestion) => {
+                        bail!("no such service `{name}`, did you mean `{suggestion}`?")
+                    }
+                    None => bail!("no such service `{name}`"),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the name in `candidates` closest to `name`, if it is close enough to be a likely typo.
+///
+/// Names are compared case-insensitively by edit distance. A candidate is only suggested if at
+/// most a third of its characters differ, which keeps unrelated names from being suggested.
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.to_lowercase();
+
+    candidates
+        .into_iter()
+        .map(|candidate| (candidate, edit_distance(&name, &candidate.to_lowercase())))
+        .filter(|(candidate, distance)| *distance <= candidate.chars().count().max(3) / 3)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// The Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a) in a.chars().enumerate() {
+        current[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexsock_protocol::commands::list_services::ServiceInfo;
+
+    fn known() -> ListServicesResponse {
+        ["webapp", "api-gateway", "postgres", "redis"]
+            .into_iter()
+            .zip(1..)
+            .map(|(name, id)| ServiceInfo {
+                id,
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_near_miss_name_suggests_existing_service() {
+        let service = ServiceRef::Name("api-gatway".to_string());
+
+        let error = check_services(&[&service], &known()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "no such service `api-gatway`, did you mean `api-gateway`?"
+        );
+    }
+
+    #[test]
+    fn test_existing_services_pass() {
+        let name = ServiceRef::Name("postgres".to_string());
+        let id = ServiceRef::Id(4);
+
+        assert!(check_services(&[&name, &id], &known()).is_ok());
+    }
+
+    #[test]
+    fn test_unrelated_name_has_no_suggestion() {
+        let service = ServiceRef::Name("frontend".to_string());
+
+        let error = check_services(&[&service], &known()).unwrap_err();
+
+        assert_eq!(error.to_string(), "no such service `frontend`");
+    }
+
+    #[test]
+    fn test_unknown_id_is_rejected() {
+        let error = check_services(&[&ServiceRef::Id(42)], &known()).unwrap_err();
+
+        assert_eq!(error.to_string(), "no service with id `42`");
+    }
+
+    #[test]
+    fn test_suggestion_ignores_case() {
+        assert_eq!(suggest("Redis", ["webapp", "redis"]), Some("redis"));
+        assert_eq!(suggest("REDISS", ["webapp", "redis"]), Some("redis"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Selection of the daemon socket the CLI connects to.
+//!
+//! The socket is taken from the first of these that is set:
+//! 1. the `--socket` flag (`--address` on Windows)
+//! 2. the [`SOCKET_ENV`] environment variable, a socket path on Unix and an address on Windows
+//! 3. the `socket` setting of the config
+
+use crate::cli::Cli;
+use anyhow::Context;
+use nexsock_config::{NexsockConfig, SocketRef};
+use std::ffi::OsString;
+#[cfg(windows)]
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+#[cfg(unix)]
+use std::path::PathBuf;
+
+/// Environment variable overriding the socket from the config.
+pub const SOCKET_ENV: &str = "NEXSOCK_SOCKET";
+
+/// Returns the socket of the daemon to connect to.
+///
+/// An empty [`SOCKET_ENV`] is treated as unset.
+///
+/// # Errors
+///
+/// Returns an error if the socket comes from the config and is a port.
+#[cfg(unix)]
+pub fn daemon_socket(cli: &Cli, config: &NexsockConfig) -> anyhow::Result<PathBuf> {
+    resolve_socket(
+        cli.socket.clone(),
+        std::env::var_os(SOCKET_ENV),
+        config.socket(),
+    )
+}
+
+/// Returns the socket of the daemon to connect to.
+///
+/// An empty [`SOCKET_ENV`] is treated as unset.
+///
+/// # Errors
+///
+/// Returns an error if [`SOCKET_ENV`] is not a valid address, or if the socket comes from the
+/// config and is a path.
+#[cfg(windows)]
+pub fn daemon_socket(cli: &Cli, config: &NexsockConfig) -> anyhow::Result<SocketAddr> {
+    resolve_socket(cli.address, std::env::var_os(SOCKET_ENV), config.socket())
+}
+
+#[cfg(unix)]
+fn resolve_socket(
+    flag: Option<PathBuf>,
+    env: Option<OsString>,
+    config: &SocketRef,
+) -> anyhow::Result<PathBuf> {
+    if let Some(socket) = flag {
+        return Ok(socket);
+    }
+
+    if let Some(socket) = env.filter(|socket| !socket.is_empty()) {
+        return Ok(PathBuf::from(socket));
+    }
+
+    config
+        .clone()
+        .try_unwrap_path()
+        .context("Expected `socket` to be a path to the socket file")
+}
+
+#[cfg(windows)]
+fn resolve_socket(
+    flag: Option<SocketAddr>,
+    env: Option<OsString>,
+    config: &SocketRef,
+) -> anyhow::Result<SocketAddr> {
+    if let Some(addr) = flag {
+        return Ok(addr);
+    }
+
+    if let Some(addr) = env.filter(|addr| !addr.is_empty()) {
+        let addr = addr.to_string_lossy();
+
+        return addr
+            .parse()
+            .with_context(|| format!("`{SOCKET_ENV}` is not a valid address: `{addr}`"));
+    }
+
+    let port = config
+        .clone()
+        .try_unwrap_port()
+        .context("Expected `socket` to be a integer port number")?;
+
+    Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn config_socket() -> SocketRef {
+        SocketRef::Path(PathBuf::from("/run/nexsock/config.sock"))
+    }
+
+    #[test]
+    fn test_flag_overrides_env_and_config() {
+        let socket = resolve_socket(
+            Some(PathBuf::from("/tmp/flag.sock")),
+            Some(OsString::from("/tmp/env.sock")),
+            &config_socket(),
+        )
+        .unwrap();
+
+        assert_eq!(socket, PathBuf::from("/tmp/flag.sock"));
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        let socket = resolve_socket(
+            None,
+            Some(OsString::from("/tmp/env.sock")),
+            &config_socket(),
+        )
+        .unwrap();
+
+        assert_eq!(socket, PathBuf::from("/tmp/env.sock"));
+    }
+
+    #[test]
+    fn test_config_used_without_flag_or_env() {
+        let socket = resolve_socket(None, None, &config_socket()).unwrap();
+        assert_eq!(socket, PathBuf::from("/run/nexsock/config.sock"));
+
+        // An empty variable counts as unset
+        let socket = resolve_socket(None, Some(OsString::new()), &config_socket()).unwrap();
+        assert_eq!(socket, PathBuf::from("/run/nexsock/config.sock"));
+
+        assert!(resolve_socket(None, None, &SocketRef::Port(50505)).is_err());
+    }
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_precedence() {
+        let config = SocketRef::Port(50505);
+        let flag: SocketAddr = "127.0.0.1:1000".parse().unwrap();
+        let env = || Some(OsString::from("127.0.0.1:2000"));
+
+        assert_eq!(resolve_socket(Some(flag), env(), &config).unwrap(), flag);
+        assert_eq!(
+            resolve_socket(None, env(), &config).unwrap(),
+            "127.0.0.1:2000".parse::<SocketAddr>().unwrap()
+        );
+        assert_eq!(
+            resolve_socket(None, None, &config).unwrap(),
+            "127.0.0.1:50505".parse::<SocketAddr>().unwrap()
+        );
+        assert!(resolve_socket(None, Some(OsString::from("nope")), &config).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! The `nexsock stdin` forwarding of the CLI's stdin to a running service.
+
+use nexsock_client::Client;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::stdin::WriteStdinCommand;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Sends every line read from stdin, with its newline, to the stdin of `service` until stdin
+/// ends. A last line without a newline is sent as is.
+///
+/// # Errors
+///
+/// Returns an error if stdin can't be read or the daemon fails to write a line, e.g. because
+/// the service stopped or closed its stdin.
+pub async fn forward_stdin(client: &mut Client, service: &ServiceRef) -> anyhow::Result<()> {
+    let mut stdin = BufReader::new(tokio::io::stdin());
+
+    loop {
+        let mut line = Vec::new();
+        if stdin.read_until(b'\n', &mut line).await? == 0 {
+            return Ok(());
+        }
+
+        client
+            .execute_command(WriteStdinCommand::new(service.clone(), line))
+            .await?;
+    }
+}
@@ -0,0 +1,86 @@
+//! The `nexsock top` resource overview of the running services.
+
+use nexsock_client::Client;
+use nexsock_protocol::commands::service_status::{ServiceState, ServiceStatus};
+use std::fmt::Write as _;
+
+/// Prints the CPU and memory usage of every running service.
+///
+/// # Errors
+///
+/// Returns an error if the services or their status can't be fetched from the daemon.
+pub async fn print_top(client: &mut Client) -> anyhow::Result<()> {
+    let services = client.list_services().await?;
+
+    let mut statuses = Vec::new();
+    for service in services.services {
+        if service.state != ServiceState::Running {
+            continue;
+        }
+
+        statuses.push(client.status(service.id).await?);
+    }
+
+    print!("{}", format_top(&statuses));
+
+    Ok(())
+}
+
+/// Formats `statuses` as a table of their CPU and memory usage, `-` where the daemon didn't
+/// report it.
+///
+/// # Examples
+///
+/// ```
+/// use nexsock::top::format_top;
+/// use nexsock_protocol::commands::service_status::ServiceStatus;
+///
+/// let status = ServiceStatus {
+///     name: "web".to_string(),
+///     cpu_percent: Some(12.5),
+///     memory_bytes: Some(64 * 1024 * 1024),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     format_top(&[status]),
+///     "NAME                        CPU%     MEMORY\n\
+///      web                         12.5   64.0 MiB\n"
+/// );
+/// ```
+pub fn format_top(statuses: &[ServiceStatus]) -> String {
+    let mut table = format!("{:<24} {:>7} {:>10}\n", "NAME", "CPU%", "MEMORY");
+
+    for status in statuses {
+        let cpu = status
+            .cpu_percent
+            .map_or_else(|| "-".to_string(), |cpu| format!("{cpu:.1}"));
+        let memory = status.memory_bytes.map_or_else(|| "-".to_string(), format_bytes);
+
+        let _ = writeln!(table, "{:<24} {cpu:>7} {memory:>10}", status.name);
+    }
+
+    table
+}
+
+/// Formats `bytes` with a binary unit, such as `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{value:.1} {unit}")
+}
@@ -1,4 +1,7 @@
+#![deny(clippy::dbg_macro)]
+
 use clap::Parser;
+use nexsock_config::{validate_instance, INSTANCE_ENV};
 use nexsockd::tracing;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
@@ -21,6 +24,22 @@ pub struct App {
     /// The number of seconds the app will run for before shutting down
     #[clap(short, long, default_value_t = 5)]
     timeout: u64,
+    /// Name of the instance to run, keeping its socket and database apart from other instances
+    ///
+    /// Overrides the `NEXSOCK_INSTANCE` environment variable
+    #[clap(long, value_parser = parse_instance)]
+    instance: Option<String>,
+    /// Start without applying pending database migrations, for deploys that migrate separately
+    ///
+    /// Pending migrations are logged, list them with `nexsock db migrations`
+    #[clap(long)]
+    skip_migrations: bool,
+}
+
+fn parse_instance(s: &str) -> Result<String, String> {
+    validate_instance(s).map_err(|e| e.to_string())?;
+
+    Ok(s.to_string())
 }
 
 /// Entry point for the nexsockd daemon service application.
@@ -43,10 +62,17 @@ fn main() -> nexsockd::prelude::Result<()> {
 
     // We dont really care to much if the env file is loaded or not
     dotenvy::dotenv().ok();
-    let _guards = tracing()?;
     let app = App::parse();
 
-    tokio::runtime::Builder::new_multi_thread()
+    // The config is loaded from the environment the first time it is used, starting with the
+    // logging setup
+    if let Some(instance) = &app.instance {
+        std::env::set_var(INSTANCE_ENV, instance);
+    }
+
+    let _guards = tracing()?;
+
+    let result = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
@@ -60,9 +86,15 @@ fn main() -> nexsockd::prelude::Result<()> {
 
             if app.dry_run {
                 println!("[+] dry-run");
-                nexsockd::timed_run_daemon(Duration::from_secs(app.timeout)).await
+                nexsockd::timed_run_daemon(Duration::from_secs(app.timeout), !app.skip_migrations)
+                    .await
             } else {
-                nexsockd::run_daemon().await
+                nexsockd::run_daemon(!app.skip_migrations).await
             }
-        })
+        });
+
+    #[cfg(feature = "otel")]
+    nexsockd::shutdown_telemetry();
+
+    result
 }
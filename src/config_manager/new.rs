@@ -4,12 +4,20 @@
 //! functionality, providing database-backed configuration storage and retrieval.
 
 use crate::prelude::*;
+use crate::service_manager::validate::parse_config_file;
 use crate::traits::configuration_management::ConfigurationManagement;
 use anyhow::anyhow;
 use nexsock_db::prelude::*;
-use nexsock_protocol::commands::config::ServiceConfigPayload;
+use nexsock_protocol::commands::config::{ConfigFile, ServiceConfigPayload, WriteConfigPayload};
 use nexsock_protocol::commands::manage_service::ServiceRef;
+use std::io::{ErrorKind, Write as _};
+use std::path::{Component, Path};
 use std::sync::LazyLock;
+use tokio::io::AsyncReadExt;
+
+/// The largest configuration file returned by [`ConfigurationManagement::get_config_file`] or
+/// written by [`ConfigurationManagement::write_config_file`], in bytes.
+pub(crate) const MAX_CONFIG_FILE_SIZE: u64 = 1024 * 1024;
 
 /// Configuration manager for service configuration operations.
 ///
@@ -27,8 +35,8 @@ use std::sync::LazyLock;
 /// // manager.update_config(&config_payload).await?;
 /// ```
 pub struct ConfigManager {
-    service_repository: ServiceRepository<'static>,
-    config_repository: ServiceConfigRepository<'static>,
+    service_repository: ServiceRepository<'static, ManagedConnection>,
+    config_repository: ServiceConfigRepository<'static, ManagedConnection>,
 }
 
 impl ConfigManager {
@@ -96,6 +104,10 @@ impl ConfigurationManagement for ConfigManager {
             filename,
             format,
             run_command,
+            log_format,
+            strip_ansi,
+            capture_output,
+            log_timestamp_format,
         } = payload;
 
         let mut service_model = self
@@ -115,11 +127,22 @@ impl ConfigurationManagement for ConfigManager {
             existing.filename = filename.clone();
             existing.format = *format;
             existing.run_command = Some(run_command.clone());
+            existing.log_format = *log_format;
+            existing.strip_ansi = *strip_ansi;
+            existing.capture_output = *capture_output;
+            existing.log_timestamp_format = log_timestamp_format.clone();
 
             existing
         } else {
             // Create new config
-            ServiceConfig::new(filename.clone(), *format, Some(run_command.clone()))
+            let mut config =
+                ServiceConfig::new(filename.clone(), *format, Some(run_command.clone()));
+            config.log_format = *log_format;
+            config.strip_ansi = *strip_ansi;
+            config.capture_output = *capture_output;
+            config.log_timestamp_format = log_timestamp_format.clone();
+
+            config
         };
 
         // Save the config
@@ -167,4 +190,130 @@ impl ConfigurationManagement for ConfigManager {
 
         Ok(config.to_payload(payload.clone()))
     }
+
+    /// Retrieves the configuration of a service with the contents of its configuration file.
+    ///
+    /// Files larger than [`MAX_CONFIG_FILE_SIZE`] are refused rather than sent to the client.
+    async fn get_config_file(&self, payload: &ServiceRef) -> Result<ConfigFile> {
+        let service_model = self
+            .service_repository
+            .get_by_service_ref(payload)
+            .await?
+            .ok_or_else(|| anyhow!("No service found"))?;
+
+        let config = self.get_config(payload).await?;
+        let path = Path::new(&service_model.repo_path).join(&config.filename);
+
+        let contents = read_config_file(&path).await?;
+
+        Ok(ConfigFile {
+            config,
+            path: path.to_string_lossy().into_owned(),
+            contents,
+        })
+    }
+
+    /// Replaces the configuration file of a service after checking its new contents parse.
+    ///
+    /// The contents are written to a temporary file next to the configuration file, which is
+    /// then renamed over it. Filenames that are absolute or contain `..` are refused, so only files
+    /// inside the repository can be written.
+    async fn write_config_file(&self, payload: &WriteConfigPayload) -> Result<()> {
+        let WriteConfigPayload { service, contents } = payload;
+
+        let service_model = self
+            .service_repository
+            .get_by_service_ref(service)
+            .await?
+            .ok_or_else(|| anyhow!("No service found"))?;
+
+        let config = self.get_config(service).await?;
+        let filename = Path::new(&config.filename);
+        if filename.is_absolute()
+            || filename
+                .components()
+                .any(|component| component == Component::ParentDir)
+        {
+            return Err(anyhow!(
+                "Config filename `{}` must be relative to the repository and can't contain `..`",
+                config.filename
+            )
+            .into());
+        }
+        let path = Path::new(&service_model.repo_path).join(filename);
+
+        if contents.len() as u64 > MAX_CONFIG_FILE_SIZE {
+            return Err(anyhow!(
+                "Config file contents are larger than the limit of {MAX_CONFIG_FILE_SIZE} bytes"
+            )
+            .into());
+        }
+
+        parse_config_file(contents, config.format).map_err(|problem| {
+            anyhow!(
+                "Config file contents are not a valid {} file: {problem}",
+                config.format
+            )
+        })?;
+
+        let contents = contents.clone();
+        tokio::task::spawn_blocking(move || write_atomically(&path, contents.as_bytes())).await??;
+
+        Ok(())
+    }
+}
+
+/// Reads the configuration file at `path`, `None` if it doesn't exist.
+///
+/// Files larger than [`MAX_CONFIG_FILE_SIZE`] or that aren't valid UTF-8 are an error.
+async fn read_config_file(path: &Path) -> Result<Option<String>> {
+    let file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Reading one byte past the limit tells a file at the limit from a larger one
+    let mut contents = Vec::new();
+    file.take(MAX_CONFIG_FILE_SIZE + 1)
+        .read_to_end(&mut contents)
+        .await?;
+
+    if contents.len() as u64 > MAX_CONFIG_FILE_SIZE {
+        return Err(anyhow!(
+            "Config file `{}` is larger than the limit of {MAX_CONFIG_FILE_SIZE} bytes",
+            path.display()
+        )
+        .into());
+    }
+
+    let contents = String::from_utf8(contents)
+        .map_err(|_| anyhow!("Config file `{}` is not valid UTF-8", path.display()))?;
+
+    Ok(Some(contents))
+}
+
+/// Replaces the file at `path` with `contents` through a temporary file renamed over it, so the
+/// file holds either its old or its new contents.
+///
+/// The temporary file gets the permissions of the file it replaces, and is removed again if
+/// anything fails.
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("Config file `{}` has no parent directory", path.display()))?;
+
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(contents)?;
+    file.as_file().sync_all()?;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) => file.as_file().set_permissions(metadata.permissions())?,
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    file.persist(path).map_err(|e| e.error)?;
+
+    Ok(())
 }
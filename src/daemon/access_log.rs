@@ -0,0 +1,200 @@
+//! Structured access log for client commands.
+//!
+//! When `server.access_log` is configured, every command handled by a connection is appended to
+//! that file as a single JSON object per line, recording who sent it, which service it targeted,
+//! how long it took and whether it succeeded. Keepalive frames are not logged.
+
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::config::{ServiceConfigPayload, WriteConfigPayload};
+use nexsock_protocol::commands::dependency::{
+    AddDependencyPayload, GetDependencyInfoPayload, RemoveDependencyPayload,
+};
+use nexsock_protocol::commands::event::SubscribePayload;
+use nexsock_protocol::commands::git::{
+    CheckoutPayload, GitCheckoutCommitPayload, GitListBranchesPayload, GitLogPayload,
+    GitPullPayload,
+};
+use nexsock_protocol::commands::manage_service::{
+    CloneServicePayload, RemoveServicePayload, ServiceRef, StartServicePayload,
+};
+use nexsock_protocol::commands::stdin::WriteStdinPayload;
+use nexsock_protocol::commands::stdout::{GetServiceLogsPayload, GetServiceStdoutPayload};
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::protocol::Protocol;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Handle to the access log file, shared by every connection of the daemon.
+#[derive(Debug, Clone)]
+pub struct AccessLog {
+    file: Arc<Mutex<File>>,
+}
+
+impl AccessLog {
+    /// Opens the access log at `path` for appending, creating it and its parent directories if
+    /// needed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    /// Appends `entry` as a single line.
+    ///
+    /// Failing to write is logged rather than returned, so a full disk never fails a command.
+    pub(crate) fn record(&self, entry: &AccessLogEntry) {
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize access log entry");
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        // A single write keeps lines from concurrent connections from interleaving
+        if let Err(e) = self.file.lock().write_all(&line) {
+            warn!(error = %e, "Failed to write access log entry");
+        }
+    }
+}
+
+/// Whether a logged command succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AccessOutcome {
+    Success,
+    Error,
+}
+
+/// A single line of the access log.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AccessLogEntry {
+    /// When the command finished, in RFC 3339 format.
+    pub(crate) timestamp: String,
+    pub(crate) request_id: u64,
+    pub(crate) command: String,
+    /// The service the command targeted, if it targets one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) service: Option<String>,
+    /// The client that sent the command, `uid`/`pid` on Unix and the remote address on Windows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) peer: Option<String>,
+    pub(crate) duration_ms: f64,
+    pub(crate) outcome: AccessOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+impl AccessLogEntry {
+    pub(crate) fn new(
+        command: Command,
+        request_id: u64,
+        service: Option<ServiceRef>,
+        peer: Option<String>,
+        duration: Duration,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            request_id,
+            command: format!("{command:?}"),
+            service: service.map(|service| service.to_string()),
+            peer,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            outcome: if error.is_some() {
+                AccessOutcome::Error
+            } else {
+                AccessOutcome::Success
+            },
+            error,
+        }
+    }
+}
+
+/// Returns the service targeted by `command`, decoding it from the request payload.
+///
+/// Returns `None` for commands that don't target a single service or whose payload is malformed.
+pub(crate) fn target_service(command: Command, payload: Option<&[u8]>) -> Option<ServiceRef> {
+    fn decode<T: bincode::Decode<()>>(payload: &[u8]) -> Option<T> {
+        Protocol::read_payload(payload).ok().flatten()
+    }
+
+    let payload = payload?;
+
+    match command {
+        Command::StartService | Command::RestartService => {
+            decode::<StartServicePayload>(payload).map(|payload| payload.service)
+        }
+        Command::StopService
+        | Command::GetServiceStatus
+        | Command::GetServiceState
+        | Command::GetConfig
+        | Command::GetConfigFile
+        | Command::ValidateService
+        | Command::ClearLogs
+        | Command::ListDependencies
+        | Command::ListDependents
+        | Command::GetRepoStatus => decode::<ServiceRef>(payload),
+        Command::GetServiceStdout => {
+            decode::<GetServiceStdoutPayload>(payload).map(|payload| payload.service)
+        }
+        Command::GetServiceLogs => {
+            decode::<GetServiceLogsPayload>(payload).map(|payload| payload.service)
+        }
+        Command::CloneService => {
+            decode::<CloneServicePayload>(payload).map(|payload| payload.source)
+        }
+        Command::WriteStdin => decode::<WriteStdinPayload>(payload).map(|payload| payload.service),
+        Command::Subscribe => {
+            decode::<SubscribePayload>(payload).and_then(|payload| payload.service)
+        }
+        Command::RemoveService => {
+            decode::<RemoveServicePayload>(payload).map(|payload| payload.service)
+        }
+        Command::AddService => {
+            decode::<AddServicePayload>(payload).map(|payload| ServiceRef::Name(payload.name))
+        }
+        Command::UpdateConfig => {
+            decode::<ServiceConfigPayload>(payload).map(|payload| payload.service)
+        }
+        Command::WriteConfig => {
+            decode::<WriteConfigPayload>(payload).map(|payload| payload.service)
+        }
+        Command::AddDependency => {
+            decode::<AddDependencyPayload>(payload).map(|payload| payload.service)
+        }
+        Command::RemoveDependency => {
+            decode::<RemoveDependencyPayload>(payload).map(|payload| payload.service)
+        }
+        Command::GetDependencyInfo => {
+            decode::<GetDependencyInfoPayload>(payload).map(|payload| payload.service)
+        }
+        Command::CheckoutBranch => {
+            decode::<CheckoutPayload>(payload).map(|payload| payload.service)
+        }
+        Command::GitCheckoutCommit => {
+            decode::<GitCheckoutCommitPayload>(payload).map(|payload| payload.service)
+        }
+        Command::GitPull => decode::<GitPullPayload>(payload).map(|payload| payload.service),
+        Command::GitLog => decode::<GitLogPayload>(payload).map(|payload| payload.service),
+        Command::GitListBranches => {
+            decode::<GitListBranchesPayload>(payload).map(|payload| payload.service)
+        }
+        _ => None,
+    }
+}
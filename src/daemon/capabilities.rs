@@ -0,0 +1,88 @@
+//! The capabilities the daemon reports for [`Command::Capabilities`].
+//!
+//! Clients use them to find out how the daemon was built, e.g. the CLI refuses git subcommands
+//! when the daemon was built without the `git` feature instead of sending commands it can't run.
+
+use nexsock_protocol::commands::capabilities::Capabilities;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::protocol::PROTOCOL_VERSION;
+
+/// The commands handled in every build.
+const COMMANDS: [Command; 43] = [
+    Command::StartService,
+    Command::StopService,
+    Command::RestartService,
+    Command::GetServiceStatus,
+    Command::GetServiceState,
+    Command::StartProfile,
+    Command::StopProfile,
+    Command::ListByProfile,
+    Command::ListByLabels,
+    Command::SearchServices,
+    Command::Subscribe,
+    Command::AddService,
+    Command::RemoveService,
+    Command::CloneService,
+    Command::ListServices,
+    Command::GetServiceStdout,
+    Command::ApplyManifest,
+    Command::UpdateConfig,
+    Command::GetConfig,
+    Command::GetConfigFile,
+    Command::WriteConfig,
+    Command::ValidateService,
+    Command::AddDependency,
+    Command::RemoveDependency,
+    Command::ListDependencies,
+    Command::ListDependents,
+    Command::GetDependencyInfo,
+    Command::Shutdown,
+    Command::GetSystemStatus,
+    Command::Ping,
+    Command::Heartbeat,
+    Command::HeartbeatAck,
+    Command::Capabilities,
+    Command::DebugDump,
+    Command::ExportMetrics,
+    Command::MigrationStatus,
+    Command::RollbackMigration,
+    Command::GetServiceLogs,
+    Command::WriteStdin,
+    Command::ClearLogs,
+    Command::Extra,
+    Command::Success,
+    Command::Error,
+];
+
+/// The commands only handled when the daemon is built with the `git` feature.
+#[cfg(feature = "git")]
+const GIT_COMMANDS: [Command; 6] = [
+    Command::CheckoutBranch,
+    Command::GetRepoStatus,
+    Command::GitCheckoutCommit,
+    Command::GitPull,
+    Command::GitLog,
+    Command::GitListBranches,
+];
+
+/// Returns the capabilities of this build of the daemon.
+pub(crate) fn capabilities() -> Capabilities {
+    let mut features = Vec::new();
+
+    #[cfg(feature = "git")]
+    features.push("git".to_string());
+
+    // Plugins are always enabled in the daemon
+    features.push("lua".to_string());
+    features.push("native".to_string());
+
+    let commands = COMMANDS.iter();
+    #[cfg(feature = "git")]
+    let commands = commands.chain(GIT_COMMANDS.iter());
+
+    Capabilities {
+        protocol_version: PROTOCOL_VERSION,
+        features,
+        commands: commands.map(|command| *command as u16).collect(),
+    }
+}
@@ -1,29 +1,53 @@
+use crate::daemon::access_log::{target_service, AccessLog, AccessLogEntry};
+use crate::daemon::capabilities::capabilities;
+use crate::daemon::debug_dump::debug_dump;
+use crate::daemon::json_rpc::{self, Response, RpcError};
 use crate::error;
-use crate::statics::{CONFIG_MANAGER, DEPENDENCY_MANAGER, PRE_HOOKS, SERVICE_MANAGER};
+use crate::events::EventSubscription;
+use crate::statics::{
+    CONFIG_MANAGER, DEPENDENCY_MANAGER, METRICS, PRE_HOOKS, SERVICE_MANAGER, SERVICE_REPOSITORY,
+};
 use crate::traits::configuration_management::ConfigurationManagement;
 use crate::traits::dependency_management::DependencyManagement;
 #[cfg(feature = "git")]
 use crate::traits::git_management::GitManagement;
+use crate::traits::process_manager::ProcessManager;
 use crate::traits::service_management::ServiceManagement;
 use bincode::{Decode, Encode};
 use cfg_if::cfg_if;
+use futures::FutureExt as _;
 use nexsock_abi::PreHook;
+use nexsock_config::NEXSOCK_CONFIG;
+use nexsock_db::{get_db_connection, migration_status, rollback_migrations};
 use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
 use nexsock_protocol::commands::error::ErrorPayload;
+use nexsock_protocol::commands::event::SubscribePayload;
 use nexsock_protocol::commands::extra::ExtraCommandPayload;
 #[cfg(feature = "git")]
 use nexsock_protocol::commands::git::{
     CheckoutPayload, GitCheckoutCommitPayload, GitListBranchesPayload, GitLogPayload,
     GitPullPayload,
 };
+use nexsock_protocol::commands::migration::RollbackMigrationPayload;
+use nexsock_protocol::commands::service_status::ServiceState;
 use nexsock_protocol::commands::{Command, CommandPayload};
 use nexsock_protocol::header::MessageFlags;
-use nexsock_protocol::protocol::Protocol;
+use nexsock_protocol::keepalive::{Keepalive, KeepaliveAction, KeepaliveConfig};
+use nexsock_protocol::protocol::{Protocol, TruncatedMessage};
+use serde_json::Value;
 use std::fmt::Debug;
 use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::io::{AsyncRead, AsyncWrite, BufReader, BufWriter};
-use tracing::{debug, info, warn};
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter,
+};
+use tokio::select;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep, timeout, Instant};
+use tracing::{debug, info, info_span, warn, Instrument};
 
 cfg_if! {
     if #[cfg(unix)] {
@@ -38,6 +62,7 @@ cfg_if! {
 /// * Command processing
 /// * Protocol communication
 /// * Plugin execution
+/// * Keepalive heartbeats while the connection is idle
 ///
 /// # Type Parameters
 ///
@@ -48,6 +73,111 @@ pub struct Connection<R, W> {
     writer: BufWriter<W>,
     protocol: Protocol,
     lua_plugin_manager: Arc<LuaPluginManager>,
+    keepalive: Keepalive,
+    /// How long the client may go without sending a frame, zero disables the timeout.
+    idle_timeout: Duration,
+    /// Slot in the daemon's connection limit, released when the connection is dropped.
+    permit: Option<OwnedSemaphorePermit>,
+    /// Where handled commands are recorded, if access logging is enabled.
+    access_log: Option<AccessLog>,
+    /// Identifies the client in the access log.
+    peer: Option<String>,
+    /// Whether the client speaks JSON-RPC instead of the native protocol.
+    json_rpc: bool,
+    /// Whether the client may request a [`Command::DebugDump`].
+    debug_dump: bool,
+    /// Events the connection streams to the client once a [`Command::Subscribe`] was answered.
+    subscription: Option<EventSubscription>,
+}
+
+/// Caps the number of client connections the daemon handles at once.
+///
+/// Every admitted connection holds a permit until it is dropped, connections arriving while all
+/// permits are taken are refused with [`reject_connection`] instead of being queued.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionLimit {
+    max: u32,
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl ConnectionLimit {
+    /// Creates a limit of `max` concurrent connections, `0` creates a limit that admits everyone.
+    pub(crate) fn new(max: u32) -> Self {
+        let permits = (max > 0).then(|| Arc::new(Semaphore::new(max as usize)));
+
+        Self { max, permits }
+    }
+
+    /// The maximum number of concurrent connections, `0` if there is no limit.
+    pub(crate) fn max(&self) -> u32 {
+        self.max
+    }
+
+    /// Reserves a slot for a new connection.
+    ///
+    /// Returns `Ok(None)` when there is no limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`error::Error::TooManyConnections`] if every slot is taken.
+    pub(crate) fn try_acquire(&self) -> error::Result<Option<OwnedSemaphorePermit>> {
+        let Some(permits) = &self.permits else {
+            return Ok(None);
+        };
+
+        permits
+            .clone()
+            .try_acquire_owned()
+            .map(Some)
+            .map_err(|_| error::Error::TooManyConnections { max: self.max })
+    }
+}
+
+/// Refuses a client connection by sending it a [`Command::Error`] explaining that the connection
+/// limit was reached, then closing the stream.
+pub(crate) async fn reject_connection<W>(mut writer: W, max_connections: u32) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let error = error::Error::TooManyConnections {
+        max: max_connections,
+    };
+    let error_payload = ErrorPayload {
+        code: error.kind(),
+        message: error.to_string(),
+        details: None,
+    };
+
+    Protocol::default()
+        .write_command_with_payload(
+            &mut writer,
+            Command::Error,
+            &error_payload,
+            MessageFlags::HAS_PAYLOAD,
+        )
+        .await?;
+
+    writer.shutdown().await
+}
+
+/// Refuses a JSON-RPC client the same way [`reject_connection`] refuses native clients, with a
+/// JSON-RPC error response that has a `null` id.
+pub(crate) async fn reject_json_rpc_connection<W>(
+    mut writer: W,
+    max_connections: u32,
+) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let error = error::Error::TooManyConnections {
+        max: max_connections,
+    };
+
+    let mut line = serde_json::to_vec(&Response::error(Value::Null, (&error).into()))?;
+    line.push(b'\n');
+
+    writer.write_all(&line).await?;
+    writer.shutdown().await
 }
 
 impl Connection<OwnedReadHalf, OwnedWriteHalf> {
@@ -60,11 +190,44 @@ impl Connection<OwnedReadHalf, OwnedWriteHalf> {
     /// ```ignore
     /// let stream = get_platform_stream(); // Returns a TcpStream or UnixStream depending on platform
     /// let lua_plugin_manager = Arc::new(LuaPluginManager::new());
-    /// let connection = Connection::new(stream, lua_plugin_manager);
+    /// let connection = Connection::new(stream, lua_plugin_manager, KeepaliveConfig::default());
     /// ```
-    pub fn new(stream: Stream, lua_plugin_manager: Arc<LuaPluginManager>) -> Self {
+    pub fn new(
+        stream: Stream,
+        lua_plugin_manager: Arc<LuaPluginManager>,
+        keepalive: KeepaliveConfig,
+    ) -> Self {
+        #[cfg(unix)]
+        let peer = stream.peer_cred().ok().map(|cred| match cred.pid() {
+            Some(pid) => format!("uid={} pid={pid}", cred.uid()),
+            None => format!("uid={}", cred.uid()),
+        });
+        #[cfg(windows)]
+        let peer = stream.peer_addr().ok().map(|addr| addr.to_string());
+
         let (reader, writer) = stream.into_split();
 
+        let mut connection = Self::from_parts(reader, writer, lua_plugin_manager, keepalive);
+        connection.peer = peer;
+
+        connection
+    }
+}
+
+impl<R, W> Connection<R, W>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    /// Creates a `Connection` from an already split reader and writer.
+    ///
+    /// Both halves are wrapped in 8 KB buffers, the same way [`Connection::new`] does for sockets.
+    pub(crate) fn from_parts(
+        reader: R,
+        writer: W,
+        lua_plugin_manager: Arc<LuaPluginManager>,
+        keepalive: KeepaliveConfig,
+    ) -> Self {
         let reader = BufReader::with_capacity(8 * 1024, reader);
         let writer = BufWriter::with_capacity(8 * 1024, writer);
         let protocol = Protocol::default();
@@ -74,15 +237,56 @@ impl Connection<OwnedReadHalf, OwnedWriteHalf> {
             writer,
             protocol,
             lua_plugin_manager,
+            keepalive: Keepalive::new(keepalive),
+            idle_timeout: Duration::ZERO,
+            permit: None,
+            access_log: None,
+            peer: None,
+            json_rpc: false,
+            debug_dump: false,
+            subscription: None,
         }
     }
-}
 
-impl<R, W> Connection<R, W>
-where
-    R: AsyncRead + Unpin,
-    W: AsyncWrite + Unpin,
-{
+    /// Speaks JSON-RPC with the client instead of the native protocol, see [`json_rpc`].
+    pub(crate) fn with_json_rpc(mut self, json_rpc: bool) -> Self {
+        self.json_rpc = json_rpc;
+        self
+    }
+
+    /// Records every command handled by this connection in `access_log`.
+    pub(crate) fn with_access_log(mut self, access_log: Option<AccessLog>) -> Self {
+        self.access_log = access_log;
+        self
+    }
+
+    /// Closes the connection once the client has not sent a frame for `idle_timeout`, or has
+    /// stalled that long in the middle of sending one. A zero timeout disables this.
+    pub(crate) fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Answers [`Command::DebugDump`] instead of rejecting it with
+    /// [`error::Error::DebugDumpDisabled`].
+    pub(crate) fn with_debug_dump(mut self, debug_dump: bool) -> Self {
+        self.debug_dump = debug_dump;
+        self
+    }
+
+    /// Follows the payloads sent to the client with a CRC32, see [`Protocol::with_checksums`].
+    pub(crate) fn with_checksums(mut self, checksums: bool) -> Self {
+        self.protocol = self.protocol.with_checksums(checksums);
+        self
+    }
+
+    /// Ties the connection to a slot of a [`ConnectionLimit`], freeing it once the connection is
+    /// dropped.
+    pub(crate) fn with_permit(mut self, permit: Option<OwnedSemaphorePermit>) -> Self {
+        self.permit = permit;
+        self
+    }
+
     /// Handles the client connection.
     ///
     /// Processes client requests until the connection is closed or an error occurs.
@@ -103,6 +307,12 @@ where
     ///
     /// Processes each message by delegating to `handle_single_message`. Exits cleanly on client disconnect, or returns an error on other I/O failures.
     ///
+    /// While no message arrives the connection sends a heartbeat every keepalive interval, and drops the
+    /// client once it has left more heartbeats unanswered than the configured limit.
+    ///
+    /// Independently of heartbeats, the connection is closed once the client has sent nothing for
+    /// the idle timeout, or has taken longer than that to finish sending a message.
+    ///
     /// # Returns
     ///
     /// Returns `Ok(())` if the client disconnects normally, or an error if a non-recoverable I/O error occurs.
@@ -120,17 +330,86 @@ where
     pub async fn handle(&mut self) -> error::Result<()> {
         info!("handling request");
 
+        if self.json_rpc {
+            return self.handle_json_rpc().await;
+        }
+
+        let keepalive_enabled = self.keepalive.config().is_enabled();
+        let idle_timer = sleep(self.keepalive.interval());
+        tokio::pin!(idle_timer);
+
+        let idle_timeout_enabled = !self.idle_timeout.is_zero();
+        let idle_deadline = sleep(self.idle_timeout);
+        tokio::pin!(idle_deadline);
+
         // Keep handling messages until the client disconnects
         loop {
-            match self.handle_single_message().await {
-                Ok(_) => continue,
-                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                    info!("Client disconnected");
+            select! {
+                // `fill_buf` is cancel safe, so no partially read frame is lost when the timer fires
+                readable = async { self.reader.fill_buf().await.map(|buf| !buf.is_empty()) } => {
+                    match readable {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            info!("Client disconnected");
+                            break;
+                        }
+                        Err(e) => {
+                            debug!(error = ?e, "Error waiting for message");
+                            return Err(e.into());
+                        }
+                    }
+
+                    self.keepalive.record_activity();
+
+                    match self.handle_single_message().await {
+                        Ok(_) => {}
+                        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                            info!("Client disconnected");
+                            break;
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+                            warn!(
+                                timeout = ?self.idle_timeout,
+                                "Client stalled while sending a message, disconnecting"
+                            );
+                            break;
+                        }
+                        Err(e) if TruncatedMessage::is(&e) => {
+                            warn!("Client disconnected in the middle of a message");
+                            return Err(e.into());
+                        }
+                        Err(e) => {
+                            debug!(error = ?e, "Error handling message");
+                            return Err(e.into());
+                        }
+                    }
+
+                    idle_timer.as_mut().reset(Instant::now() + self.keepalive.interval());
+                    idle_deadline.as_mut().reset(Instant::now() + self.idle_timeout);
+                }
+                _ = &mut idle_deadline, if idle_timeout_enabled => {
+                    warn!(timeout = ?self.idle_timeout, "Client idle for too long, disconnecting");
                     break;
                 }
-                Err(e) => {
-                    debug!(error = ?e, "Error handling message");
-                    return Err(e.into());
+                _ = &mut idle_timer, if keepalive_enabled => {
+                    match self.keepalive.on_idle() {
+                        KeepaliveAction::SendHeartbeat => {
+                            debug!(
+                                missed = self.keepalive.missed(),
+                                "Connection idle, sending heartbeat"
+                            );
+                            self.protocol.write_heartbeat(&mut self.writer).await?;
+                        }
+                        KeepaliveAction::Disconnect => {
+                            warn!(
+                                missed = self.keepalive.missed(),
+                                "Client stopped answering heartbeats, disconnecting"
+                            );
+                            break;
+                        }
+                    }
+
+                    idle_timer.as_mut().reset(Instant::now() + self.keepalive.interval());
                 }
             }
         }
@@ -152,33 +431,239 @@ where
     /// conn.handle_single_message().await?;
     /// ```
     async fn handle_single_message(&mut self) -> io::Result<()> {
-        // Read the incoming message
-        let (header, payload) = self.protocol.read_message(&mut self.reader).await?;
-
-        debug!(
-            command = ?header.command,
-            payload = %if payload.is_some() { "yes" } else { "no" },
-        );
-
-        // Handle the command
-        match self.handle_command(header.command, payload).await {
-            Ok(response) => {
-                if response.is_empty() {
-                    self.send_success().await?;
-                } else {
-                    self.send_success_with_payload(&response).await?;
+        // Read the incoming message, a client may not hold the connection with a half sent frame
+        let read = self.protocol.read_message(&mut self.reader);
+        let (header, payload) = if self.idle_timeout.is_zero() {
+            read.await?
+        } else {
+            timeout(self.idle_timeout, read).await.map_err(|_| {
+                io::Error::new(io::ErrorKind::TimedOut, "timed out reading message")
+            })??
+        };
+
+        // Keepalive frames are answered here and never reach the command handlers
+        match header.command {
+            Command::Heartbeat => return self.protocol.write_heartbeat_ack(&mut self.writer).await,
+            Command::HeartbeatAck => return Ok(()),
+            _ => {}
+        }
+
+        let request_id = header.request_id();
+        let span = info_span!("request", request_id, command = ?header.command);
+
+        // Responses echo the id of the request they answer
+        self.protocol.set_request_id(request_id);
+
+        async {
+            debug!(
+                payload = %if payload.is_some() { "yes" } else { "no" },
+            );
+
+            let service = self
+                .access_log
+                .as_ref()
+                .and_then(|_| target_service(header.command, payload.as_deref()));
+            let started = Instant::now();
+
+            // Handle the command
+            let result = self.handle_command(header.command, payload).await;
+
+            if let Some(access_log) = &self.access_log {
+                access_log.record(&AccessLogEntry::new(
+                    header.command,
+                    request_id,
+                    service,
+                    self.peer.clone(),
+                    started.elapsed(),
+                    result.as_ref().err().map(ToString::to_string),
+                ));
+            }
+
+            match result {
+                Ok(response) => {
+                    if response.is_empty() {
+                        self.send_success().await?;
+                    } else {
+                        self.send_success_with_payload(&response).await?;
+                    }
+
+                    if let Some(subscription) = self.subscription.take() {
+                        self.stream_events(subscription).await?;
+                    }
+                }
+                Err(e) => {
+                    warn!(error = ?e, "Command failed");
+
+                    self.send_error(e).await?;
                 }
             }
-            Err(e) => {
-                warn!(error = ?e, "Command failed");
 
-                self.send_error(e).await?;
+            Ok(())
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Sends every event of `subscription` as another response to the subscribe request, until the
+    /// client disconnects or the daemon shuts down.
+    ///
+    /// The connection is taken by the subscription, it sends no heartbeats and the idle timeout
+    /// doesn't apply. Heartbeats of the client are still answered, any other message is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::ErrorKind::UnexpectedEof`] error once the client disconnected, or any
+    /// other error reading from or writing to the client.
+    async fn stream_events(&mut self, mut subscription: EventSubscription) -> io::Result<()> {
+        debug!("Streaming events to the client");
+
+        let mut shutdown_signal = SERVICE_MANAGER.shutdown_signal();
+
+        loop {
+            select! {
+                _ = shutdown_signal.wait_for(|shutdown| *shutdown).map(drop) => {
+                    debug!("Shutdown was signaled, ending the subscription");
+                    return Ok(());
+                }
+                event = subscription.recv() => {
+                    let Some(event) = event else {
+                        debug!("Event bus closed, ending the subscription");
+                        return Ok(());
+                    };
+
+                    self.send_success_with_payload(&CommandPayload::Event(event)).await?;
+                }
+                // `fill_buf` is cancel safe, so no partially read frame is lost when an event arrives
+                readable = async { self.reader.fill_buf().await.map(|buf| !buf.is_empty()) } => {
+                    if !readable? {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+
+                    let (header, _) = self.protocol.read_message(&mut self.reader).await?;
+                    match header.command {
+                        Command::Heartbeat => {
+                            self.protocol.write_heartbeat_ack(&mut self.writer).await?;
+                        }
+                        Command::HeartbeatAck => {}
+                        command => warn!(?command, "Ignoring a command sent while subscribed"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handles a JSON-RPC client, answering one request line at a time until it disconnects.
+    ///
+    /// JSON-RPC clients get no heartbeats, the idle timeout still applies between requests.
+    async fn handle_json_rpc(&mut self) -> error::Result<()> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            let mut reader = (&mut self.reader).take(json_rpc::MAX_REQUEST_LEN);
+            let read = reader.read_line(&mut line);
+            let read = if self.idle_timeout.is_zero() {
+                read.await?
+            } else {
+                match timeout(self.idle_timeout, read).await {
+                    Ok(read) => read?,
+                    Err(_) => {
+                        warn!(
+                            timeout = ?self.idle_timeout,
+                            "Client idle for too long, disconnecting"
+                        );
+                        break;
+                    }
+                }
+            };
+
+            if read == 0 {
+                info!("Client disconnected");
+                break;
+            }
+
+            if !line.ends_with('\n') && read as u64 == json_rpc::MAX_REQUEST_LEN {
+                warn!(
+                    max = json_rpc::MAX_REQUEST_LEN,
+                    "JSON-RPC request too long, disconnecting"
+                );
+
+                let error = RpcError::new(json_rpc::INVALID_REQUEST, "request too long");
+                self.send_json_rpc(&Response::error(Value::Null, error)).await?;
+                break;
+            }
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = self.handle_json_rpc_request(line.trim()).await {
+                self.send_json_rpc(&response).await?;
             }
         }
 
         Ok(())
     }
 
+    /// Runs a single JSON-RPC request, returning the response unless it is a notification.
+    async fn handle_json_rpc_request(&mut self, line: &str) -> Option<Response> {
+        let request = match json_rpc::parse_request(line) {
+            Ok(request) => request,
+            Err(response) => return Some(response),
+        };
+
+        let id = request.id.clone();
+        let (command, payload) = match json_rpc::encode_request(&request.method, request.params) {
+            Ok(encoded) => encoded,
+            Err(error) => return id.map(|id| Response::error(id, error)),
+        };
+
+        let request_id = id.as_ref().and_then(Value::as_u64).unwrap_or_default();
+        let span = info_span!("request", request_id, command = ?command);
+
+        async move {
+            let service = self
+                .access_log
+                .as_ref()
+                .and_then(|_| target_service(command, payload.as_deref()));
+            let started = Instant::now();
+
+            let result = self.handle_command(command, payload).await;
+
+            if let Some(access_log) = &self.access_log {
+                access_log.record(&AccessLogEntry::new(
+                    command,
+                    request_id,
+                    service,
+                    self.peer.clone(),
+                    started.elapsed(),
+                    result.as_ref().err().map(ToString::to_string),
+                ));
+            }
+
+            let id = id?;
+            Some(match result {
+                Ok(payload) => Response::result(id, payload),
+                Err(e) => {
+                    warn!(error = ?e, "Command failed");
+
+                    Response::error(id, (&e).into())
+                }
+            })
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn send_json_rpc(&mut self, response: &Response) -> io::Result<()> {
+        let mut line = serde_json::to_vec(response)?;
+        line.push(b'\n');
+
+        self.writer.write_all(&line).await?;
+        self.writer.flush().await
+    }
+
     #[tracing::instrument(skip(self, payload))]
     /// Handles a single protocol command by dispatching it to the appropriate service, configuration, dependency, or plugin manager.
     ///
@@ -202,6 +687,18 @@ where
         &mut self,
         command: Command,
         payload: Option<Vec<u8>>,
+    ) -> error::Result<CommandPayload> {
+        let result = self.dispatch_command(command, payload).await;
+        METRICS.record_command(result.is_ok());
+
+        result
+    }
+
+    /// Runs `command` on the manager responsible for it, see [`Connection::handle_command`].
+    async fn dispatch_command(
+        &mut self,
+        command: Command,
+        payload: Option<Vec<u8>>,
     ) -> error::Result<CommandPayload> {
         let pre_hooks = &PRE_HOOKS;
 
@@ -209,14 +706,38 @@ where
             plugin.pre_command(&command);
         });
 
+        self.run_lua_pre_command(command, payload.as_deref());
+
         match command {
             Command::GetServiceStdout => {
                 let payload = Self::read_req_payload(payload)?;
 
                 let res = SERVICE_MANAGER.get_stdout(&payload).await?;
 
+                Ok(CommandPayload::ServiceStdout(res))
+            }
+
+            Command::GetServiceLogs => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let res = SERVICE_MANAGER.get_logs(&payload).await?;
+
                 Ok(CommandPayload::Stdout(res))
             }
+            Command::WriteStdin => {
+                let payload = Self::read_req_payload(payload)?;
+
+                SERVICE_MANAGER.write_stdin(&payload).await?;
+
+                Ok(CommandPayload::Empty)
+            }
+            Command::ClearLogs => {
+                let payload = Self::read_req_payload(payload)?;
+
+                SERVICE_MANAGER.clear_logs(&payload).await?;
+
+                Ok(CommandPayload::Empty)
+            }
 
             Command::StartService => {
                 let payload = Self::read_req_payload(payload)?;
@@ -250,6 +771,80 @@ where
 
                 Ok(CommandPayload::Status(status))
             }
+            Command::GetServiceState => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let state = SERVICE_MANAGER.get_state(&payload).await?;
+
+                Ok(CommandPayload::State(state))
+            }
+            Command::StartProfile => {
+                let payload = Self::read_req_payload(payload)?;
+
+                SERVICE_MANAGER.start_profile(&payload).await?;
+
+                Ok(CommandPayload::Empty)
+            }
+            Command::StopProfile => {
+                let payload = Self::read_req_payload(payload)?;
+
+                SERVICE_MANAGER.stop_profile(&payload).await?;
+
+                Ok(CommandPayload::Empty)
+            }
+            Command::ListByProfile => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let services = SERVICE_MANAGER.list_by_profile(&payload).await?;
+
+                Ok(CommandPayload::ListServices(services))
+            }
+            Command::ListByLabels => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let services = SERVICE_MANAGER.list_by_labels(&payload).await?;
+
+                Ok(CommandPayload::ListServices(services))
+            }
+            Command::SearchServices => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let services = SERVICE_MANAGER.search_services(&payload).await?;
+
+                Ok(CommandPayload::ListServices(services))
+            }
+            Command::Subscribe => {
+                let payload: SubscribePayload = Self::read_req_payload(payload)?;
+
+                let service_id = match payload.service {
+                    Some(service) => Some(
+                        SERVICE_REPOSITORY
+                            .get_by_service_ref(&service)
+                            .await?
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("No Service with reference `{service}`")
+                            })?
+                            .id,
+                    ),
+                    None => None,
+                };
+
+                self.subscription = Some(EventSubscription::new(
+                    SERVICE_MANAGER.event_bus(),
+                    service_id,
+                    payload.kinds,
+                    payload.after_seq,
+                ));
+
+                Ok(CommandPayload::Empty)
+            }
+            Command::ValidateService => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let report = SERVICE_MANAGER.validate(&payload).await?;
+
+                Ok(CommandPayload::Validation(report))
+            }
             Command::AddService => {
                 let payload = Self::read_req_payload(payload)?;
 
@@ -260,9 +855,23 @@ where
             Command::RemoveService => {
                 let payload = Self::read_req_payload(payload)?;
 
-                SERVICE_MANAGER.remove_service(&payload).await?;
+                let plan = SERVICE_MANAGER.remove_service(&payload).await?;
 
-                Ok(CommandPayload::Empty)
+                Ok(CommandPayload::RemovalPlan(plan))
+            }
+            Command::CloneService => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let status = SERVICE_MANAGER.clone_service(&payload).await?;
+
+                Ok(CommandPayload::Status(status))
+            }
+            Command::ApplyManifest => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let response = SERVICE_MANAGER.apply_manifest(&payload).await?;
+
+                Ok(CommandPayload::ManifestApplied(response))
             }
             Command::ListServices => {
                 let services = SERVICE_MANAGER.get_all().await?;
@@ -284,6 +893,20 @@ where
 
                 Ok(CommandPayload::ServiceConfig(config))
             }
+            Command::GetConfigFile => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let file = CONFIG_MANAGER.get_config_file(&payload).await?;
+
+                Ok(CommandPayload::ConfigFile(file))
+            }
+            Command::WriteConfig => {
+                let payload = Self::read_req_payload(payload)?;
+
+                CONFIG_MANAGER.write_config_file(&payload).await?;
+
+                Ok(CommandPayload::Empty)
+            }
 
             Command::AddDependency => {
                 let payload = Self::read_req_payload(payload)?;
@@ -306,6 +929,20 @@ where
 
                 Ok(CommandPayload::Dependencies(deps))
             }
+            Command::ListDependents => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let dependents = DEPENDENCY_MANAGER.list_dependents(&payload).await?;
+
+                Ok(CommandPayload::Dependents(dependents))
+            }
+            Command::GetDependencyInfo => {
+                let payload = Self::read_req_payload(payload)?;
+
+                let details = DEPENDENCY_MANAGER.get_dependency_info(&payload).await?;
+
+                Ok(CommandPayload::DependencyDetails(details))
+            }
 
             #[cfg(feature = "git")]
             Command::CheckoutBranch => {
@@ -337,18 +974,7 @@ where
                 let payload = Self::read_req_payload(payload)?;
                 let repo_info = SERVICE_MANAGER.git_status(&payload).await?;
 
-                // Convert GitRepoInfo to RepoStatus
-                let status = nexsock_protocol::commands::git::RepoStatus {
-                    current_branch: repo_info.current_branch,
-                    current_commit: repo_info.current_commit,
-                    remote_url: repo_info.remote_url,
-                    is_dirty: repo_info.is_dirty,
-                    branches: repo_info.branches,
-                    ahead_count: repo_info.ahead_count,
-                    behind_count: repo_info.behind_count,
-                };
-
-                Ok(CommandPayload::GitStatus(status))
+                Ok(CommandPayload::GitStatus(repo_info.into()))
             }
 
             #[cfg(feature = "git")]
@@ -362,22 +988,8 @@ where
                     )
                     .await?;
 
-                // Convert GitCommit to GitCommitInfo
-                let commit_infos = commits
-                    .into_iter()
-                    .map(|commit| nexsock_protocol::commands::git::GitCommitInfo {
-                        hash: commit.hash,
-                        short_hash: commit.short_hash,
-                        author_name: commit.author_name,
-                        author_email: commit.author_email,
-                        timestamp: commit.timestamp,
-                        message: commit.message,
-                        full_message: commit.full_message,
-                    })
-                    .collect();
-
                 let response = nexsock_protocol::commands::git::GitLogResponse {
-                    commits: commit_infos,
+                    commits: commits.into_iter().map(Into::into).collect(),
                 };
 
                 Ok(CommandPayload::GitLog(response))
@@ -435,13 +1047,53 @@ where
             Command::Shutdown => Ok(CommandPayload::Empty),
             Command::GetSystemStatus => Ok(CommandPayload::Empty),
             Command::Ping => Ok(CommandPayload::Empty),
+            Command::Capabilities => Ok(CommandPayload::Capabilities(capabilities())),
+            Command::ExportMetrics => {
+                let running = SERVICE_MANAGER
+                    .running_services()
+                    .iter()
+                    .filter(|entry| matches!(entry.state, ServiceState::Running))
+                    .count();
+
+                Ok(CommandPayload::Metrics(METRICS.snapshot(running as u64)))
+            }
+            Command::MigrationStatus => Ok(CommandPayload::Migrations(
+                migration_status(get_db_connection()).await?,
+            )),
+            Command::RollbackMigration => {
+                let payload: RollbackMigrationPayload = Self::read_req_payload(payload)?;
+                let db = get_db_connection().pool().map_err(anyhow::Error::from)?;
+
+                Ok(CommandPayload::Migrations(
+                    rollback_migrations(&db, payload.steps).await?,
+                ))
+            }
+            Command::DebugDump => {
+                if !self.debug_dump {
+                    return Err(error::Error::DebugDumpDisabled);
+                }
+
+                let dump = debug_dump(
+                    &NEXSOCK_CONFIG,
+                    SERVICE_MANAGER.running_services(),
+                    self.lua_plugin_manager.loaded_plugins(),
+                    self.lua_plugin_manager.load_errors(),
+                    PRE_HOOKS.keys().map(PathBuf::as_path),
+                );
+
+                Ok(CommandPayload::DebugDump(dump))
+            }
 
             Command::Extra => {
-                let _payload: ExtraCommandPayload = Self::read_req_payload(payload)?;
+                let payload: ExtraCommandPayload = Self::read_req_payload(payload)?;
+                let payload = serde_json::to_value(payload).map_err(anyhow::Error::from)?;
 
-                let results = self
-                    .lua_plugin_manager
-                    .call_function_on_all("handle_command", vec![])?;
+                // Plugin calls block their thread until they return or time out
+                let lua_plugin_manager = Arc::clone(&self.lua_plugin_manager);
+                let results = tokio::task::spawn_blocking(move || {
+                    lua_plugin_manager.call_function_on_all("handle_command", vec![payload.into()])
+                })
+                .await??;
 
                 for (path, result) in results {
                     match result {
@@ -467,6 +1119,32 @@ where
         }
     }
 
+    /// Calls `pre_command(command, payload)` on the Lua plugins that define it, with the name of
+    /// the command and the JSON form of its payload, see [`json_rpc::payload_params`].
+    ///
+    /// A failing hook is logged and doesn't fail the command.
+    fn run_lua_pre_command(&self, command: Command, payload: Option<&[u8]>) {
+        let plugins = self.lua_plugin_manager.plugins_with_function("pre_command");
+        if plugins.is_empty() {
+            return;
+        }
+
+        let args = vec![
+            SerializableLuaValue::String(format!("{command:?}")),
+            json_rpc::payload_params(command, payload)
+                .map_or(SerializableLuaValue::Nil, SerializableLuaValue::from),
+        ];
+
+        for path in plugins {
+            if let Err(error) =
+                self.lua_plugin_manager
+                    .call_function(&path, "pre_command", args.clone())
+            {
+                warn!(path = ?path, error = %error, "Error running the pre_command hook of a script");
+            }
+        }
+    }
+
     /// Decodes and returns a request payload of the expected type.
     ///
     /// Returns an error if the payload is missing or cannot be decoded.
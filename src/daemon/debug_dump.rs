@@ -0,0 +1,118 @@
+//! The snapshot of the internal state the daemon returns for [`Command::DebugDump`].
+//!
+//! The dump is only returned when `server.debug_dump` is enabled. It holds the config and the
+//! environment of the running services, values that look like secrets are replaced with
+//! [`REDACTED`] before they leave the daemon.
+//!
+//! [`Command::DebugDump`]: nexsock_protocol::commands::Command::DebugDump
+
+use crate::service_manager::ServiceProcess;
+use dashmap::DashMap;
+use nexsock_config::{AppConfig, NexsockConfig};
+use nexsock_protocol::commands::debug_dump::{DebugDump, RunningServiceDump, REDACTED};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Parts of environment variable names that mark their value as a secret.
+const SECRET_NAME_PARTS: [&str; 8] = [
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "KEY",
+    "CREDENTIAL",
+    "AUTH",
+    "PRIVATE",
+];
+
+/// Returns `true` if the environment variable `name` likely holds a secret.
+pub(crate) fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// Builds the dump from the state of the daemon.
+pub(crate) fn debug_dump<'a>(
+    config: &NexsockConfig,
+    running_services: &DashMap<i64, ServiceProcess>,
+    lua_plugins: Vec<PathBuf>,
+    failed_lua_plugins: Vec<(PathBuf, String)>,
+    native_plugins: impl IntoIterator<Item = &'a Path>,
+) -> DebugDump {
+    let mut running_services = running_services
+        .iter()
+        .map(|entry| {
+            let process = entry.value();
+
+            RunningServiceDump {
+                id: *entry.key(),
+                pid: process.process.id(),
+                state: process.state,
+                env_vars: redact_env_vars(&process.env_vars),
+                // Reports 0 rather than waiting on a log task that holds the buffer
+                buffered_log_entries: process.stdout_logs.try_lock().map_or(0, |logs| logs.len()),
+            }
+        })
+        .collect::<Vec<_>>();
+    running_services.sort_by_key(|service| service.id);
+
+    DebugDump {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        instance: config.instance().map(ToString::to_string),
+        running_services,
+        config: redacted_config(config),
+        lua_plugins: lua_plugins
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+        native_plugins: native_plugins
+            .into_iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+        failed_lua_plugins: failed_lua_plugins
+            .into_iter()
+            .map(|(path, error)| (path.display().to_string(), error))
+            .collect(),
+    }
+}
+
+fn redact_env_vars<'a>(
+    env_vars: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> BTreeMap<String, String> {
+    env_vars
+        .into_iter()
+        .map(|(name, value)| {
+            let value = if is_secret_name(name) {
+                REDACTED.to_string()
+            } else {
+                value.clone()
+            };
+
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Serializes the config with the web API key redacted.
+fn redacted_config(config: &NexsockConfig) -> String {
+    let mut web = config.web().clone();
+    if web.api_key.is_some() {
+        web.api_key = Some(REDACTED.to_string());
+    }
+
+    let config = AppConfig {
+        socket: config.socket().clone(),
+        log_str: config.log_str.clone(),
+        server: config.server().clone(),
+        database: config.database().clone(),
+        web,
+        plugins: config.plugins().clone(),
+    };
+
+    serde_json::to_string_pretty(&config).unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to serialize the config for the debug dump");
+        String::new()
+    })
+}
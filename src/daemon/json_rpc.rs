@@ -0,0 +1,361 @@
+//! JSON-RPC 2.0 codec for the daemon commands.
+//!
+//! When `server.json_rpc_socket` is configured the daemon listens on a second socket that speaks
+//! JSON-RPC instead of the native binary protocol. Every request is a single JSON object on its
+//! own line and is answered with a single line holding the response. Notifications, requests
+//! without an `id`, are executed without a response. Batches are not supported.
+//!
+//! Methods are the snake case names of the [`Command`]s, e.g. `list_services` or
+//! `get_service_status`, and `params` holds the JSON form of the command payload. Results are the
+//! JSON form of the response payload, `null` for commands without one.
+//!
+//! ```text
+//! --> {"jsonrpc": "2.0", "id": 1, "method": "get_service_status", "params": {"Name": "webapp"}}
+//! <-- {"jsonrpc": "2.0", "id": 1, "result": {"id": 1, "name": "webapp", ...}}
+//! ```
+
+use crate::error;
+use bincode::{Decode, Encode};
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::config::{ServiceConfigPayload, WriteConfigPayload};
+use nexsock_protocol::commands::dependency::{
+    AddDependencyPayload, GetDependencyInfoPayload, RemoveDependencyPayload,
+};
+use nexsock_protocol::commands::extra::ExtraCommandPayload;
+use nexsock_protocol::commands::git::{
+    CheckoutPayload, GitCheckoutCommitPayload, GitListBranchesPayload, GitLogPayload,
+    GitPullPayload,
+};
+use nexsock_protocol::commands::label::LabelSelectorPayload;
+use nexsock_protocol::commands::manage_service::{
+    CloneServicePayload, RemoveServicePayload, ServiceRef, StartServicePayload,
+};
+use nexsock_protocol::commands::manifest::ApplyManifestPayload;
+use nexsock_protocol::commands::migration::RollbackMigrationPayload;
+use nexsock_protocol::commands::profile::ProfilePayload;
+use nexsock_protocol::commands::search::SearchServicesPayload;
+use nexsock_protocol::commands::stdin::WriteStdinPayload;
+use nexsock_protocol::commands::stdout::{GetServiceLogsPayload, GetServiceStdoutPayload};
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::protocol::Protocol;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// The longest request line accepted, longer requests close the connection.
+pub(crate) const MAX_REQUEST_LEN: u64 = 1024 * 1024;
+
+/// Error code for requests that are not valid JSON.
+pub(crate) const PARSE_ERROR: i64 = -32700;
+/// Error code for JSON that is not a valid request object.
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+/// Error code for unknown methods.
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+/// Error code for params that don't match the payload of the method.
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+/// Error code for results that could not be converted to JSON.
+pub(crate) const INTERNAL_ERROR: i64 = -32603;
+/// Error code for commands that failed in the daemon, the daemon error kind is in `data.kind`.
+pub(crate) const COMMAND_FAILED: i64 = -32000;
+
+/// The methods that can be called and the commands they run.
+const METHODS: [(&str, Command); 42] = [
+    ("start_service", Command::StartService),
+    ("stop_service", Command::StopService),
+    ("restart_service", Command::RestartService),
+    ("get_service_status", Command::GetServiceStatus),
+    ("get_service_state", Command::GetServiceState),
+    ("start_profile", Command::StartProfile),
+    ("stop_profile", Command::StopProfile),
+    ("list_by_profile", Command::ListByProfile),
+    ("list_by_labels", Command::ListByLabels),
+    ("search_services", Command::SearchServices),
+    ("add_service", Command::AddService),
+    ("remove_service", Command::RemoveService),
+    ("clone_service", Command::CloneService),
+    ("list_services", Command::ListServices),
+    ("get_service_stdout", Command::GetServiceStdout),
+    ("get_service_logs", Command::GetServiceLogs),
+    ("write_stdin", Command::WriteStdin),
+    ("clear_logs", Command::ClearLogs),
+    ("apply_manifest", Command::ApplyManifest),
+    ("update_config", Command::UpdateConfig),
+    ("get_config", Command::GetConfig),
+    ("get_config_file", Command::GetConfigFile),
+    ("write_config", Command::WriteConfig),
+    ("validate_service", Command::ValidateService),
+    ("add_dependency", Command::AddDependency),
+    ("remove_dependency", Command::RemoveDependency),
+    ("list_dependencies", Command::ListDependencies),
+    ("list_dependents", Command::ListDependents),
+    ("get_dependency_info", Command::GetDependencyInfo),
+    ("checkout_branch", Command::CheckoutBranch),
+    ("get_repo_status", Command::GetRepoStatus),
+    ("git_checkout_commit", Command::GitCheckoutCommit),
+    ("git_pull", Command::GitPull),
+    ("git_log", Command::GitLog),
+    ("git_list_branches", Command::GitListBranches),
+    ("get_system_status", Command::GetSystemStatus),
+    ("ping", Command::Ping),
+    ("capabilities", Command::Capabilities),
+    ("debug_dump", Command::DebugDump),
+    ("export_metrics", Command::ExportMetrics),
+    ("migration_status", Command::MigrationStatus),
+    ("rollback_migration", Command::RollbackMigration),
+];
+
+/// A JSON-RPC request.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Request {
+    pub(crate) jsonrpc: String,
+    pub(crate) method: String,
+    #[serde(default)]
+    pub(crate) params: Option<Value>,
+    /// `None` for notifications. An explicit `null` id is kept, such requests are still answered.
+    #[serde(default, deserialize_with = "present")]
+    pub(crate) id: Option<Value>,
+}
+
+/// Deserializes a field that may be `null`, distinguishing it from a missing field.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// A JSON-RPC response, holding either a result or an error.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Response {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+    id: Value,
+}
+
+/// The error member of a failed JSON-RPC response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct RpcError {
+    pub(crate) code: i64,
+    pub(crate) message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) data: Option<Value>,
+}
+
+impl RpcError {
+    pub(crate) fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<&error::Error> for RpcError {
+    fn from(error: &error::Error) -> Self {
+        Self {
+            code: COMMAND_FAILED,
+            message: error.to_string(),
+            data: Some(serde_json::json!({ "kind": error.kind() })),
+        }
+    }
+}
+
+impl Response {
+    /// A successful response carrying the JSON form of `payload`.
+    pub(crate) fn result(id: Value, payload: CommandPayload) -> Self {
+        match payload_to_json(payload) {
+            Ok(result) => Self {
+                jsonrpc: "2.0",
+                result: Some(result),
+                error: None,
+                id,
+            },
+            Err(e) => Self::error(id, RpcError::new(INTERNAL_ERROR, e.to_string())),
+        }
+    }
+
+    /// A failed response, `id` is `null` when the request id could not be read.
+    pub(crate) fn error(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+/// Parses a single request line.
+///
+/// # Errors
+///
+/// Returns the response to send back if the line is not valid JSON or not a valid request.
+pub(crate) fn parse_request(line: &str) -> Result<Request, Response> {
+    let value = serde_json::from_str::<Value>(line).map_err(|e| {
+        Response::error(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))
+    })?;
+
+    // Answer invalid requests with their id when it can be found
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+
+    let request = serde_json::from_value::<Request>(value).map_err(|e| {
+        Response::error(id.clone(), RpcError::new(INVALID_REQUEST, e.to_string()))
+    })?;
+
+    if request.jsonrpc != "2.0" {
+        let message = format!("unsupported JSON-RPC version `{}`", request.jsonrpc);
+        return Err(Response::error(id, RpcError::new(INVALID_REQUEST, message)));
+    }
+
+    Ok(request)
+}
+
+/// Returns the command `method` runs along with its params encoded as the command payload.
+///
+/// # Errors
+///
+/// Returns [`METHOD_NOT_FOUND`] for unknown methods and [`INVALID_PARAMS`] if `params` don't
+/// match the payload of the command.
+pub(crate) fn encode_request(
+    method: &str,
+    params: Option<Value>,
+) -> Result<(Command, Option<Vec<u8>>), RpcError> {
+    let command = METHODS
+        .iter()
+        .find(|(name, _)| *name == method)
+        .map(|(_, command)| *command)
+        .ok_or_else(|| RpcError::new(METHOD_NOT_FOUND, format!("unknown method `{method}`")))?;
+
+    let payload = match command {
+        Command::StartService | Command::RestartService => {
+            encode_params::<StartServicePayload>(params)?
+        }
+        Command::StopService
+        | Command::GetServiceStatus
+        | Command::GetServiceState
+        | Command::GetConfig
+        | Command::GetConfigFile
+        | Command::ValidateService
+        | Command::ClearLogs
+        | Command::ListDependencies
+        | Command::ListDependents
+        | Command::GetRepoStatus => encode_params::<ServiceRef>(params)?,
+        Command::GetServiceStdout => encode_params::<GetServiceStdoutPayload>(params)?,
+        Command::GetServiceLogs => encode_params::<GetServiceLogsPayload>(params)?,
+        Command::WriteStdin => encode_params::<WriteStdinPayload>(params)?,
+        Command::StartProfile | Command::StopProfile | Command::ListByProfile => {
+            encode_params::<ProfilePayload>(params)?
+        }
+        Command::ListByLabels => encode_params::<LabelSelectorPayload>(params)?,
+        Command::SearchServices => encode_params::<SearchServicesPayload>(params)?,
+        Command::RemoveService => encode_params::<RemoveServicePayload>(params)?,
+        Command::CloneService => encode_params::<CloneServicePayload>(params)?,
+        Command::AddService => encode_params::<AddServicePayload>(params)?,
+        Command::ApplyManifest => encode_params::<ApplyManifestPayload>(params)?,
+        Command::UpdateConfig => encode_params::<ServiceConfigPayload>(params)?,
+        Command::WriteConfig => encode_params::<WriteConfigPayload>(params)?,
+        Command::AddDependency => encode_params::<AddDependencyPayload>(params)?,
+        Command::RemoveDependency => encode_params::<RemoveDependencyPayload>(params)?,
+        Command::GetDependencyInfo => encode_params::<GetDependencyInfoPayload>(params)?,
+        Command::CheckoutBranch => encode_params::<CheckoutPayload>(params)?,
+        Command::GitCheckoutCommit => encode_params::<GitCheckoutCommitPayload>(params)?,
+        Command::GitPull => encode_params::<GitPullPayload>(params)?,
+        Command::GitLog => encode_params::<GitLogPayload>(params)?,
+        Command::GitListBranches => encode_params::<GitListBranchesPayload>(params)?,
+        Command::RollbackMigration => encode_params::<RollbackMigrationPayload>(params)?,
+        _ => None,
+    };
+
+    Ok((command, payload))
+}
+
+/// Reads `params` as `T` and encodes it the way the native protocol sends payloads.
+fn encode_params<T>(params: Option<Value>) -> Result<Option<Vec<u8>>, RpcError>
+where
+    T: DeserializeOwned + Encode,
+{
+    let params = params.ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing params"))?;
+    let payload = serde_json::from_value::<T>(params)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+
+    Protocol::encode_payload(&payload)
+        .map(Some)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+/// Decodes the payload of `command` to its JSON form, the reverse of [`encode_request`].
+///
+/// Returns `None` for commands without a payload and payloads that can't be decoded.
+pub(crate) fn payload_params(command: Command, payload: Option<&[u8]>) -> Option<Value> {
+    let payload = payload?;
+
+    match command {
+        Command::StartService | Command::RestartService => {
+            decode_params::<StartServicePayload>(payload)
+        }
+        Command::StopService
+        | Command::GetServiceStatus
+        | Command::GetServiceState
+        | Command::GetConfig
+        | Command::GetConfigFile
+        | Command::ValidateService
+        | Command::ClearLogs
+        | Command::ListDependencies
+        | Command::ListDependents
+        | Command::GetRepoStatus => decode_params::<ServiceRef>(payload),
+        Command::GetServiceStdout => decode_params::<GetServiceStdoutPayload>(payload),
+        Command::GetServiceLogs => decode_params::<GetServiceLogsPayload>(payload),
+        Command::WriteStdin => decode_params::<WriteStdinPayload>(payload),
+        Command::StartProfile | Command::StopProfile | Command::ListByProfile => {
+            decode_params::<ProfilePayload>(payload)
+        }
+        Command::ListByLabels => decode_params::<LabelSelectorPayload>(payload),
+        Command::SearchServices => decode_params::<SearchServicesPayload>(payload),
+        Command::RemoveService => decode_params::<RemoveServicePayload>(payload),
+        Command::CloneService => decode_params::<CloneServicePayload>(payload),
+        Command::AddService => decode_params::<AddServicePayload>(payload),
+        Command::ApplyManifest => decode_params::<ApplyManifestPayload>(payload),
+        Command::UpdateConfig => decode_params::<ServiceConfigPayload>(payload),
+        Command::WriteConfig => decode_params::<WriteConfigPayload>(payload),
+        Command::AddDependency => decode_params::<AddDependencyPayload>(payload),
+        Command::RemoveDependency => decode_params::<RemoveDependencyPayload>(payload),
+        Command::GetDependencyInfo => decode_params::<GetDependencyInfoPayload>(payload),
+        Command::CheckoutBranch => decode_params::<CheckoutPayload>(payload),
+        Command::GitCheckoutCommit => decode_params::<GitCheckoutCommitPayload>(payload),
+        Command::GitPull => decode_params::<GitPullPayload>(payload),
+        Command::GitLog => decode_params::<GitLogPayload>(payload),
+        Command::GitListBranches => decode_params::<GitListBranchesPayload>(payload),
+        Command::RollbackMigration => decode_params::<RollbackMigrationPayload>(payload),
+        Command::Extra => decode_params::<ExtraCommandPayload>(payload),
+        _ => None,
+    }
+}
+
+/// Decodes a payload sent over the native protocol as `T` and converts it to JSON.
+fn decode_params<T>(payload: &[u8]) -> Option<Value>
+where
+    T: Decode<()> + Serialize,
+{
+    let payload = Protocol::read_payload::<T>(payload).ok().flatten()?;
+
+    serde_json::to_value(payload).ok()
+}
+
+/// Converts a response payload to the JSON result of a request.
+///
+/// Payloads are returned without the name of their [`CommandPayload`] variant and
+/// [`CommandPayload::Empty`] becomes `null`.
+pub(crate) fn payload_to_json(payload: CommandPayload) -> serde_json::Result<Value> {
+    if payload.is_empty() {
+        return Ok(Value::Null);
+    }
+
+    // Newtype variants serialize as `{ "Variant": value }`
+    Ok(match serde_json::to_value(payload)? {
+        Value::Object(object) if object.len() == 1 => {
+            object.into_iter().next().map_or(Value::Null, |(_, value)| value)
+        }
+        value => value,
+    })
+}
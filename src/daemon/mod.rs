@@ -8,11 +8,14 @@ use crate::prelude::*;
 use anyhow::Context;
 use cfg_if::cfg_if;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tokio::select;
+use tracing::{debug, info, warn};
 
 use nexsock_config::traits::SocketBind;
 use nexsock_config::SocketRef;
 use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use std::time::Duration;
 
 cfg_if! {
     if #[cfg(unix)] {
@@ -27,9 +30,14 @@ cfg_if! {
     }
 }
 
+pub mod access_log;
+pub(crate) mod capabilities;
+pub(crate) mod debug_dump;
 pub mod connection;
+pub mod json_rpc;
 pub mod server;
 
+use access_log::AccessLog;
 pub use connection::*;
 use nexsock_config::NEXSOCK_CONFIG;
 pub use server::*;
@@ -62,6 +70,16 @@ pub use server::*;
 pub struct Daemon {
     listener: Arc<Listener>,
     lua_plugin_manager: Arc<LuaPluginManager>,
+    keepalive: KeepaliveConfig,
+    idle_timeout: Duration,
+    connection_limit: ConnectionLimit,
+    access_log: Option<AccessLog>,
+    /// Listener for JSON-RPC clients, if enabled.
+    json_rpc_listener: Option<Arc<Listener>>,
+    /// Whether clients may request a debug dump.
+    debug_dump: bool,
+    /// Whether the payloads sent to clients are checksummed.
+    checksums: bool,
 }
 
 impl Daemon {
@@ -87,7 +105,7 @@ impl Daemon {
     /// This function will return an error if:
     /// * Socket binding fails
     /// * Plugin manager initialization fails
-    /// * Plugin loading fails
+    /// * The Lua plugins directory can't be read
     ///
     /// # Platform-specific behavior
     ///
@@ -95,7 +113,7 @@ impl Daemon {
     ///
     /// Initializes a new daemon instance, binding the socket listener and loading Lua plugins.
     ///
-    /// On Unix, removes any existing socket file before binding a Unix domain socket. On Windows, binds a TCP socket. Loads all available Lua plugins into the plugin manager. Returns an error if socket binding or plugin loading fails.
+    /// On Unix, removes any existing socket file before binding a Unix domain socket. On Windows, binds a TCP socket. Loads all available Lua plugins into the plugin manager, a plugin that fails to load is logged and skipped. Returns an error if socket binding fails or the plugins directory can't be read.
     ///
     /// # Examples
     ///
@@ -111,8 +129,8 @@ impl Daemon {
         let config = &*NEXSOCK_CONFIG;
         let listener = Self::get_listener(config.socket()).await?;
 
-        let lua_plugin_manager =
-            LuaPluginManager::new().context("failed to load the plugin manager")?;
+        let lua_plugin_manager = LuaPluginManager::with_config(config.plugins().clone())
+            .context("failed to load the plugin manager")?;
 
         lua_plugin_manager
             .load_plugins()
@@ -121,9 +139,37 @@ impl Daemon {
 
         let lua_plugin_manager = Arc::new(lua_plugin_manager);
 
+        let server = config.server();
+        let keepalive = KeepaliveConfig::new(
+            Duration::from_secs(server.heartbeat_interval),
+            server.max_missed_heartbeats,
+        );
+
+        let idle_timeout = Duration::from_secs(server.idle_timeout);
+        let connection_limit = ConnectionLimit::new(server.max_connections);
+
+        let access_log = server
+            .access_log
+            .as_deref()
+            .map(AccessLog::open)
+            .transpose()
+            .context("failed to open the access log")?;
+
+        let json_rpc_listener = match &server.json_rpc_socket {
+            Some(socket) => Some(Self::get_listener(socket).await?),
+            None => None,
+        };
+
         Ok(Self {
             listener,
             lua_plugin_manager,
+            keepalive,
+            idle_timeout,
+            connection_limit,
+            access_log,
+            json_rpc_listener,
+            debug_dump: server.debug_dump,
+            checksums: server.checksums,
         })
     }
 
@@ -202,6 +248,12 @@ impl Daemon {
     ///
     /// Waits for a client to connect to the daemon's socket listener, then wraps the accepted stream and the Lua plugin manager in a `Connection`.
     ///
+    /// Clients connecting while `max_connections` connections are already open are sent an error
+    /// and disconnected, and the daemon keeps waiting for the next client.
+    ///
+    /// When a JSON-RPC socket is configured, clients of both sockets are accepted and count
+    /// towards the same connection limit.
+    ///
     /// # Returns
     /// A `Connection` representing the accepted client stream and associated plugin manager.
     ///
@@ -218,11 +270,53 @@ impl Daemon {
     /// # }
     /// ```
     pub async fn accept(&self) -> Result<Connection<OwnedReadHalf, OwnedWriteHalf>> {
-        let (stream, addr) = self.listener.accept().await?;
+        loop {
+            let json_rpc_accept = async {
+                match &self.json_rpc_listener {
+                    Some(listener) => listener.accept().await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            let ((stream, addr), json_rpc) = select! {
+                accepted = self.listener.accept() => (accepted?, false),
+                accepted = json_rpc_accept => (accepted?, true),
+            };
 
-        debug!(address = ?addr, "Accepted new connection");
+            let permit = match self.connection_limit.try_acquire() {
+                Ok(permit) => permit,
+                Err(e) => {
+                    warn!(address = ?addr, error = %e, "Rejecting connection");
 
-        Ok(Connection::new(stream, self.lua_plugin_manager.clone()))
+                    let max_connections = self.connection_limit.max();
+                    tokio::spawn(async move {
+                        let rejected = if json_rpc {
+                            reject_json_rpc_connection(stream, max_connections).await
+                        } else {
+                            reject_connection(stream, max_connections).await
+                        };
+
+                        if let Err(e) = rejected {
+                            debug!(error = %e, "Failed to notify rejected client");
+                        }
+                    });
+
+                    continue;
+                }
+            };
+
+            debug!(address = ?addr, json_rpc, "Accepted new connection");
+
+            return Ok(
+                Connection::new(stream, self.lua_plugin_manager.clone(), self.keepalive)
+                    .with_idle_timeout(self.idle_timeout)
+                    .with_access_log(self.access_log.clone())
+                    .with_json_rpc(json_rpc)
+                    .with_debug_dump(self.debug_dump)
+                    .with_checksums(self.checksums)
+                    .with_permit(permit),
+            );
+        }
     }
 
     /// Gracefully shuts down the daemon.
@@ -243,7 +337,7 @@ impl Daemon {
     ///
     /// Shuts down the daemon and performs platform-specific cleanup.
     ///
-    /// On Unix, removes the socket file if it exists. On Windows, closes the TCP listener by dropping it.
+    /// On Unix, removes the socket files if they exist. On Windows, closes the TCP listener by dropping it.
     ///
     /// # Examples
     ///
@@ -260,9 +354,13 @@ impl Daemon {
         info!("Shutting down daemon...");
 
         #[cfg(unix)]
-        if let SocketRef::Path(path) = NEXSOCK_CONFIG.socket() {
-            if path.exists() {
-                fs::remove_file(path)?;
+        let json_rpc_socket = NEXSOCK_CONFIG.server().json_rpc_socket.as_ref();
+        #[cfg(unix)]
+        for socket in [Some(NEXSOCK_CONFIG.socket()), json_rpc_socket] {
+            if let Some(SocketRef::Path(path)) = socket {
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
             }
         }
 
@@ -1,18 +1,28 @@
+use crate::daemon::Connection;
 use crate::error::Result;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::resources::RESOURCE_SAMPLE_INTERVAL;
 use crate::statics::SERVICE_MANAGER;
 use crate::traits::VecExt;
 use crate::{daemon::Daemon, traits::process_manager::ProcessManager};
 use futures::future::join_all;
+use futures::FutureExt as _;
 use nexsock_config::{NexsockConfig, NEXSOCK_CONFIG};
 use parking_lot::Mutex;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::signal::ctrl_c;
-use tokio::sync::oneshot;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
 use tokio::task::JoinHandle;
-use tokio::time::{sleep, Instant};
-use tokio::{join, select, task, try_join};
-use tracing::{error, info};
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+use tokio::{join, select, try_join};
+use tracing::{debug, error, info};
+
+/// The shortest time between two cleanups of finished connections and exited services.
+const MIN_CLEANUP_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Server implementation for the Nexsock daemon.
 ///
@@ -104,7 +114,7 @@ impl DaemonServer {
     /// and concurrently shuts down the daemon and all managed services. Returns an error if any
     /// shutdown step fails.
     pub async fn shutdown(&mut self) -> Result<()> {
-        self.complete_connections().await?;
+        Self::complete_connections(&self.connections).await?;
 
         self.config.save()?;
 
@@ -120,11 +130,11 @@ impl DaemonServer {
     ///
     /// ```ignore
     /// // Inside an async context with a DaemonServer instance:
-    /// server.complete_connections().await?;
+    /// DaemonServer::complete_connections(&server.connections).await?;
     /// ```
-    async fn complete_connections(&mut self) -> Result<()> {
+    async fn complete_connections(connections: &Arc<Mutex<Vec<JoinHandle<()>>>>) -> Result<()> {
         let connections = {
-            let mut connections_guard = self.connections.lock();
+            let mut connections_guard = connections.lock();
             let connections = std::mem::take(&mut *connections_guard);
             drop(connections_guard);
 
@@ -202,7 +212,9 @@ impl DaemonServer {
     /// * Performs periodic cleanup
     /// * Handles shutdown signals
     ///
-    /// The server will run until it receives a shutdown signal (Ctrl+C).
+    /// The server will run until it receives Ctrl+C, `SIGTERM` on Unix, or shutdown is signaled
+    /// through [`ProcessManager::signal_shutdown`]. It then waits for the open connections,
+    /// stops every running service and removes its sockets.
     ///
     /// # Returns
     ///
@@ -213,17 +225,9 @@ impl DaemonServer {
     /// # Errors
     ///
     /// This function will return an error if:
-    /// * Connection acceptance fails critically
+    /// * The signal handlers can't be installed
     /// * Shutdown operations fail
     ///
-    /// Runs the main server loop and the background cleanup task concurrently, coordinating their shutdown.
-    ///
-    /// Starts the server task to accept connections and the cleanup task to periodically remove completed connections and old services. Waits for either task to complete, ensuring graceful shutdown when triggered.
-    ///
-    /// # Returns
-    ///
-    /// Returns `Ok(())` if the server shuts down cleanly, or an error if any task fails.
-    ///
     /// # Examples
     ///
     /// ```ignore
@@ -231,123 +235,158 @@ impl DaemonServer {
     /// # tokio_test::block_on(async {
     /// let mut server = DaemonServer::new().await.unwrap();
     /// server.run().await.unwrap();
-    /// # server.shutdown().await.unwrap();
     /// # });
     /// ```
     pub async fn run(&mut self) -> Result<()> {
-        let (cleanup_stop_tx, cleanup_stop_rx) = oneshot::channel::<()>();
+        let daemon = &self.daemon;
 
-        let cleanup_task = self.cleanup_task(cleanup_stop_rx);
-        let server_future = self.server_task(cleanup_stop_tx);
-
-        select! {
-            res = server_future => res?,
-            res = cleanup_task => res??,
-        }
+        serve(
+            &SERVICE_MANAGER,
+            move || daemon.accept(),
+            termination_requested(),
+            self.cleanup_interval,
+            &self.connections,
+        )
+        .await?;
 
-        Ok(())
+        self.config.save()?;
+        self.daemon.clone().shutdown().await
     }
+}
 
-    /// Runs the main server loop, accepting new connections and handling shutdown signals.
-    ///
-    /// Accepts incoming connections from the daemon, spawning a new asynchronous task for each connection handler and tracking their join handles. On receiving a Ctrl-C signal, initiates a graceful shutdown by signaling the cleanup task to stop and awaiting shutdown procedures.
-    ///
-    /// # Returns
-    /// Returns `Ok(())` if the server loop exits cleanly, or an error if shutdown or signaling fails.
-    ///
-    /// # Examples
-    ///
-    /// ```ignore
-    /// # use nexsockd::daemon::server::DaemonServer;
-    /// # use tokio::sync::oneshot;
-    /// # async fn example(mut server: DaemonServer) {
-    /// let (tx, _rx) = oneshot::channel();
-    /// let result = server.server_task(tx).await;
-    /// assert!(result.is_ok());
-    /// # }
-    /// ```
-    async fn server_task(&mut self, cleanup_stop_tx: oneshot::Sender<()>) -> Result<()> {
-        loop {
-            select! {
-                conn = self.daemon.accept() => {
-                    match conn {
-                        Ok(mut connection) => {
-                            let handle = tokio::spawn(async move {
-                                if let Err(e) = connection.handle().await {
-                                    error!(error = ?e, "Connection error");
-                                }
-                            });
-
-                            let mut connections_guard = self.connections.lock();
-                            connections_guard.push(handle);
+/// The event loop behind [`DaemonServer::run`], generic over where connections come from.
+///
+/// A single `select!` drives every lifecycle concern of the daemon:
+/// * Accepted connections are handled on their own task and tracked in `connections`
+/// * Finished connections and exited services are cleaned up every `cleanup_interval`
+/// * The resource usage of the running services is sampled every [`RESOURCE_SAMPLE_INTERVAL`]
+/// * Services that exit are reaped as soon as the daemon receives `SIGCHLD`, see
+///   [`ProcessManager::reap_exited`]
+///
+/// Returns once `shutdown` completes or shutdown is signaled through
+/// [`ProcessManager::signal_shutdown`], after the open connections finished and every running
+/// service was stopped.
+///
+/// # Errors
+///
+/// Returns an error if the `SIGCHLD` handler can't be installed or stopping the services fails.
+pub(crate) async fn serve<A, F, R, W>(
+    manager: &ServiceManager,
+    mut accept: A,
+    shutdown: impl Future<Output = ()>,
+    cleanup_interval: Duration,
+    connections: &Arc<Mutex<Vec<JoinHandle<()>>>>,
+) -> Result<()>
+where
+    A: FnMut() -> F,
+    F: Future<Output = Result<Connection<R, W>>>,
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    // A zero period would make `interval_at` panic
+    let cleanup_interval = cleanup_interval.max(MIN_CLEANUP_INTERVAL);
+    let mut cleanup = interval_at(Instant::now() + cleanup_interval, cleanup_interval);
+    cleanup.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut sample = interval_at(
+        Instant::now() + RESOURCE_SAMPLE_INTERVAL,
+        RESOURCE_SAMPLE_INTERVAL,
+    );
+    sample.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut shutdown_signal = manager.shutdown_signal();
+    let mut child_exits = ChildExits::new()?;
+
+    tokio::pin!(shutdown);
+
+    loop {
+        select! {
+            conn = accept() => match conn {
+                Ok(mut connection) => {
+                    let handle = tokio::spawn(async move {
+                        if let Err(e) = connection.handle().await {
+                            error!(error = ?e, "Connection error");
                         }
-                        Err(e) => error!(error = ?e, "Accept error"),
-                    }
-                }
-                _ = ctrl_c() => {
-                    info!("Got Ctrl-C, shutting down");
+                    });
 
-                    let _ = cleanup_stop_tx.send(());
-                    self.shutdown().await?;
-                    break;
+                    connections.lock().push(handle);
+                }
+                Err(e) => error!(error = ?e, "Accept error"),
+            },
+            () = &mut shutdown => {
+                info!("Got a termination signal, shutting down");
+                break;
+            }
+            _ = shutdown_signal.wait_for(|shutdown| *shutdown).map(drop) => {
+                info!("Shutdown was signaled, shutting down");
+                break;
+            }
+            _ = cleanup.tick() => {
+                let (_, service_clean_res) = join!(
+                    DaemonServer::cleanup_completed_connections(connections),
+                    manager.clean_old()
+                );
+
+                if let Err(e) = service_clean_res {
+                    error!(error = ?e, "Error during service cleanup");
                 }
             }
+            _ = sample.tick() => manager.sample_resources().await,
+            () = child_exits.recv() => {
+                let reaped = manager.reap_exited();
+                debug!(reaped, "Received SIGCHLD");
+            }
         }
-
-        Ok(())
     }
 
-    /// Spawns a background task that periodically cleans up completed connection handlers and old services.
-    ///
-    /// The task runs until it receives a stop signal via the provided oneshot receiver. Cleanup occurs at the configured interval, and the task sleeps briefly between checks to avoid busy waiting. Errors during service cleanup are logged.
-    ///
-    /// # Examples
-    ///
-    /// ```ignore
-    /// # use nexsockd::prelude::DaemonServer;
-    /// # async fn example() {
-    /// let (tx, rx) = tokio::sync::oneshot::channel();
-    /// let server = DaemonServer::new().await.unwrap();
-    /// let handle = server.cleanup_task(rx);
-    /// // ... later, signal shutdown:
-    /// tx.send(()).unwrap();
-    /// handle.await.unwrap().unwrap();
-    /// # }
-    /// ```
-    fn cleanup_task(&self, mut cleanup_stop_rx: oneshot::Receiver<()>) -> JoinHandle<Result<()>> {
-        let connections_arc = Arc::clone(&self.connections);
-        let cleanup_interval = self.cleanup_interval;
-
-        task::spawn(async move {
-            let mut last_cleanup = Instant::now();
-
-            loop {
-                // Check if we've been asked to stop
-                if cleanup_stop_rx.try_recv().is_ok() {
-                    info!("Cleanup task received stop signal");
-                    break;
-                }
+    // Wakes up every task still waiting on the signal when a termination signal stopped us
+    manager.signal_shutdown();
 
-                // Check if it's time to clean up
-                if last_cleanup.elapsed() >= cleanup_interval {
-                    let (_, service_clean_res) = join!(
-                        Self::cleanup_completed_connections(&connections_arc),
-                        SERVICE_MANAGER.clean_old()
-                    );
+    DaemonServer::complete_connections(connections).await?;
+    manager.kill_all().await
+}
 
-                    if let Err(e) = service_clean_res {
-                        error!(error = ?e, "Error during service cleanup");
-                    }
+/// Resolves once the daemon is asked to terminate, by Ctrl+C or on Unix by `SIGTERM`.
+async fn termination_requested() {
+    #[cfg(unix)]
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            select! {
+                _ = ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
 
-                    last_cleanup = Instant::now();
-                }
+            return;
+        }
+        Err(e) => error!(error = ?e, "Failed to listen for SIGTERM, only Ctrl+C stops the daemon"),
+    }
 
-                // Sleep to avoid busy waiting
-                sleep(Duration::from_millis(100)).await;
-            }
+    let _ = ctrl_c().await;
+}
 
-            info!("Cleanup task completed");
-            Result::<()>::Ok(())
+/// Notifies the event loop whenever a child process of the daemon exits.
+///
+/// Never fires on platforms without `SIGCHLD`, exited services are then only noticed by the
+/// periodic cleanup.
+struct ChildExits {
+    #[cfg(unix)]
+    signal: Signal,
+}
+
+impl ChildExits {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            #[cfg(unix)]
+            signal: signal(SignalKind::child())?,
         })
     }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if self.signal.recv().await.is_some() {
+            return;
+        }
+
+        std::future::pending().await
+    }
 }
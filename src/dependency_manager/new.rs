@@ -3,14 +3,22 @@
 //! This module contains the concrete implementation of dependency management
 //! functionality, providing database-backed dependency tracking and operations.
 
+use crate::service_manager::ServiceProcess;
+use crate::statics::SERVICE_MANAGER;
 use crate::traits::dependency_management::DependencyManagement;
+use crate::traits::process_manager::ProcessManager;
 use anyhow::anyhow;
+use dashmap::try_result::TryResult;
+use dashmap::DashMap;
 use nexsock_db::prelude::*;
 use nexsock_protocol::commands::dependency::{
-    AddDependencyPayload, ListDependenciesResponse, RemoveDependencyPayload,
+    AddDependencyPayload, GetDependencyInfoPayload, ListDependenciesResponse,
+    ListDependentsResponse, RemoveDependencyPayload,
 };
+use nexsock_protocol::commands::dependency_info::{DependencyDetails, DependencyInfo};
 use nexsock_protocol::commands::manage_service::ServiceRef;
-use std::sync::LazyLock;
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::sync::{Arc, LazyLock};
 
 /// Dependency manager for service dependency operations.
 ///
@@ -29,8 +37,9 @@ use std::sync::LazyLock;
 /// manager.add_dependency(&dependency_payload).await?;
 /// ```
 pub struct DependencyManager {
-    service_repository: ServiceRepository<'static>,
-    dependency_repository: ServiceDependencyRepository<'static>,
+    service_repository: ServiceRepository<'static, ManagedConnection>,
+    dependency_repository: ServiceDependencyRepository<'static, ManagedConnection>,
+    running_services: Arc<DashMap<i64, ServiceProcess>>,
 }
 
 impl DependencyManager {
@@ -54,10 +63,33 @@ impl DependencyManager {
     pub const fn new_const() -> LazyLock<Self> {
         LazyLock::new(Default::default)
     }
+
+    /// Creates a dependency manager that reports the live state of dependencies from
+    /// `running_services` instead of the processes of the global service manager.
+    pub(crate) fn with_running_services(
+        running_services: Arc<DashMap<i64, ServiceProcess>>,
+    ) -> Self {
+        Self {
+            service_repository: ServiceRepository::new_from_static(),
+            dependency_repository: ServiceDependencyRepository::new_from_static(),
+            running_services,
+        }
+    }
+
+    /// Returns the state of the process running `service_id`, `Stopped` if it isn't running.
+    fn live_state(&self, service_id: i64) -> ServiceState {
+        match self.running_services.try_get(&service_id) {
+            TryResult::Present(process) => process.state,
+            TryResult::Absent => ServiceState::Stopped,
+            // Only tracked processes can be locked
+            TryResult::Locked => ServiceState::Running,
+        }
+    }
 }
 
 impl Default for DependencyManager {
-    /// Creates a new `DependencyManager` with repositories initialized from static contexts.
+    /// Creates a new `DependencyManager` with repositories initialized from static contexts,
+    /// sharing the running processes of the global service manager.
     ///
     /// # Examples
     ///
@@ -65,10 +97,7 @@ impl Default for DependencyManager {
     /// let manager = DependencyManager::default();
     /// ```
     fn default() -> Self {
-        Self {
-            service_repository: ServiceRepository::new_from_static(),
-            dependency_repository: ServiceDependencyRepository::new_from_static(),
-        }
+        Self::with_running_services(SERVICE_MANAGER.running_services().clone())
     }
 }
 
@@ -88,6 +117,7 @@ impl DependencyManagement for DependencyManager {
     ///     service: ServiceRef::Name("service-a".to_string()),
     ///     dependent_service: ServiceRef::Name("service-b".to_string()),
     ///     tunnel_enabled: false,
+    ///     optional: false,
     /// };
     /// dependency_manager.add_dependency(&payload).await?;
     /// ```
@@ -96,6 +126,7 @@ impl DependencyManagement for DependencyManager {
             service,
             dependent_service,
             tunnel_enabled,
+            optional,
         } = payload;
 
         let parent_service_id = self
@@ -112,6 +143,7 @@ impl DependencyManagement for DependencyManager {
             service_id: parent_service_id,
             dependent_service_id,
             tunnel_enabled: *tunnel_enabled,
+            optional: *optional,
         };
 
         self.dependency_repository.save(&mut dependency).await?;
@@ -170,6 +202,9 @@ impl DependencyManagement for DependencyManager {
 
     /// Retrieves a structured list of dependencies for the specified service.
     ///
+    /// Each dependency carries the live state of its service in `dependent_state`, taken from
+    /// the processes the daemon is running.
+    ///
     /// Returns an error if the service cannot be found by the provided reference.
     ///
     /// # Examples
@@ -193,9 +228,94 @@ impl DependencyManagement for DependencyManager {
         let service_id = service.id;
         let name = service.name;
 
-        self.dependency_repository
+        let mut response = self
+            .dependency_repository
             .get_dependencies_response(service_id, name)
+            .await?;
+
+        for dependency in &mut response.dependencies {
+            dependency.dependent_state = self.live_state(dependency.id);
+        }
+
+        Ok(response)
+    }
+
+    /// Retrieves the services that depend on the specified service.
+    ///
+    /// Returns an error if the service cannot be found by the provided reference.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let manager = DependencyManager::default();
+    /// let dependents = manager.list_dependents(&ServiceRef::Name("database".to_string())).await?;
+    /// ```
+    async fn list_dependents(
+        &self,
+        payload: &ServiceRef,
+    ) -> crate::error::Result<ListDependentsResponse> {
+        let service = self
+            .service_repository
+            .get_by_service_ref(payload)
+            .await?
+            .ok_or_else(|| anyhow!("No service with this name or id"))?;
+
+        self.dependency_repository
+            .get_dependents_response(service.id, service.name)
             .await
             .map_err(Into::into)
     }
+
+    /// Retrieves the details of the dependency of one service on another.
+    ///
+    /// The dependency carries the live state of its service in `dependent_state`, like
+    /// [`list_dependencies`](Self::list_dependencies) reports it.
+    ///
+    /// Returns an error if either service cannot be found or there is no such dependency.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let manager = DependencyManager::default();
+    /// let payload = GetDependencyInfoPayload {
+    ///     service: ServiceRef::Name("app".to_string()),
+    ///     dependent_service: ServiceRef::Name("database".to_string()),
+    /// };
+    /// let details = manager.get_dependency_info(&payload).await?;
+    /// ```
+    async fn get_dependency_info(
+        &self,
+        payload: &GetDependencyInfoPayload,
+    ) -> crate::error::Result<DependencyDetails> {
+        let GetDependencyInfoPayload {
+            service,
+            dependent_service,
+        } = payload;
+
+        let service = self
+            .service_repository
+            .get_by_service_ref(service)
+            .await?
+            .ok_or_else(|| anyhow!("No service with this name or id"))?;
+        let dependent_service_id = self
+            .service_repository
+            .extract_valid_id_from_ref(dependent_service)
+            .await?;
+
+        let dependency = self
+            .dependency_repository
+            .get_dependency(service.id, dependent_service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No dependency found for this service"))?;
+
+        let port = dependency.port;
+        let mut dependency = DependencyInfo::from(dependency);
+        dependency.dependent_state = self.live_state(dependency.id);
+
+        Ok(DependencyDetails {
+            service_name: service.name,
+            dependency,
+            port,
+        })
+    }
 }
@@ -7,7 +7,9 @@
 //! The [`Error`](crate::Error) enum uses [`thiserror`] for automatic error trait implementations and
 //! provides error kind classification for programmatic error handling.
 
+use crate::service_manager::StartupFailure;
 use nexsock_config::NexsockConfigError;
+use nexsock_protocol::commands::service_status::ServiceState;
 use std::borrow::Cow;
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -64,6 +66,52 @@ pub enum Error {
     LockError,
     #[error(transparent)]
     Dotenv(#[from] dotenvy::Error),
+    #[error("Too many connections, the daemon handles at most {max} at a time")]
+    TooManyConnections { max: u32 },
+    #[error("{}", startup_failure_message(.exit_code, .stderr))]
+    StartupFailed {
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+    #[error("The repository path `{path}` of service `{service}` does not exist")]
+    MissingRepoPath { service: String, path: String },
+    #[error("The debug dump is disabled, set `server.debug_dump = true` in the daemon config")]
+    DebugDumpDisabled,
+    #[error("The stdin of service `{service}` is closed")]
+    StdinClosed { service: String },
+    #[error("Port {port} is still in use after the service was stopped")]
+    PortNotFreed { port: u16 },
+    #[error("Service `{service}` is a dependency of {dependents}, force the removal to remove it anyway")]
+    HasDependents { service: String, dependents: String },
+    #[error("Service can't go from {from} to {to}")]
+    InvalidStateTransition {
+        from: ServiceState,
+        to: ServiceState,
+    },
+}
+
+/// Describes a service that exited during startup, followed by what it wrote to stderr.
+fn startup_failure_message(exit_code: &Option<i32>, stderr: &str) -> String {
+    let mut message = match exit_code {
+        Some(code) => format!("Service exited during startup with exit code {code}"),
+        None => "Service was killed by a signal during startup".to_string(),
+    };
+
+    if !stderr.is_empty() {
+        message.push_str(":\n");
+        message.push_str(stderr);
+    }
+
+    message
+}
+
+impl From<StartupFailure> for Error {
+    fn from(failure: StartupFailure) -> Self {
+        Self::StartupFailed {
+            exit_code: failure.exit_code,
+            stderr: failure.stderr,
+        }
+    }
 }
 
 impl Error {
@@ -84,6 +132,9 @@ impl Error {
     /// - `10` - Payload parsing errors
     /// - `11` - Configuration errors
     /// - `13` - Channel send errors
+    /// - `14` - Connection limit reached
+    /// - `15` - Service exited during startup
+    /// - `16` - The repository path of a service is missing
     ///
     /// Returns a numeric code representing the type of error.
     ///
@@ -112,6 +163,14 @@ impl Error {
             Error::FailedToGetPayload => 10,
             Error::Config(_) => 11,
             Error::OneShotSend(_) => 13,
+            Error::TooManyConnections { .. } => 14,
+            Error::StartupFailed { .. } => 15,
+            Error::MissingRepoPath { .. } => 16,
+            Error::DebugDumpDisabled => 17,
+            Error::StdinClosed { .. } => 18,
+            Error::PortNotFreed { .. } => 19,
+            Error::HasDependents { .. } => 20,
+            Error::InvalidStateTransition { .. } => 21,
             _ => 0xFFFF,
         }
     }
@@ -0,0 +1,193 @@
+//! # Service Lifecycle Events
+//!
+//! The [`EventBus`] the service manager publishes a [`ServiceEvent`] to whenever a service is
+//! started, stopped or crashes. Anything that has to react to the lifecycle of the services
+//! subscribes to it instead of polling their state.
+//!
+//! Every event is numbered, and the most recent ones are kept so a subscriber that reconnects
+//! can resume after the last event it received.
+
+use nexsock_protocol::commands::event::{SequencedEvent, ServiceEvent, ServiceEventKind};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{trace, warn};
+
+/// How many events are buffered for a subscriber before it lags behind and misses the oldest, and
+/// how many recent events are kept for replay.
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Broadcasts every published [`ServiceEvent`] to all current subscribers.
+///
+/// Each event is numbered with the next sequence number, starting at 1. A subscriber only
+/// receives the events published after it subscribed, unless it subscribes with
+/// [`EventBus::subscribe_after`] which first replays the buffered events after a sequence number.
+/// One that falls more than [`EVENT_CHANNEL_CAPACITY`] events behind gets
+/// [`RecvError::Lagged`](broadcast::error::RecvError::Lagged) and skips ahead to the oldest
+/// buffered event.
+///
+/// # Examples
+///
+/// ```ignore
+/// let bus = EventBus::default();
+/// let mut events = bus.subscribe();
+///
+/// bus.publish(ServiceEvent::Started { service_id: 1 });
+/// assert_eq!(events.recv().await?.event, ServiceEvent::Started { service_id: 1 });
+/// ```
+#[derive(Debug, Clone)]
+pub(crate) struct EventBus {
+    sender: broadcast::Sender<SequencedEvent>,
+    /// The most recent events, oldest first, kept for replay.
+    history: Arc<Mutex<VecDeque<SequencedEvent>>>,
+    capacity: usize,
+}
+
+impl EventBus {
+    /// Creates a bus buffering up to `capacity` events per subscriber and for replay.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    pub(crate) fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            sender,
+            history: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Publishes `event` to every current subscriber, returning how many there are.
+    ///
+    /// Publishing without any subscribers is not an error, the event is only kept for replay.
+    pub(crate) fn publish(&self, event: ServiceEvent) -> usize {
+        // Held while sending so events are broadcast in the order of their sequence numbers
+        let mut history = self.history.lock();
+
+        let seq = history.back().map_or(1, |last| last.seq + 1);
+        let event = SequencedEvent { seq, event };
+        trace!(?event, "Publishing service event");
+
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Subscribes to the events published from now on.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<SequencedEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribes to the events published from now on, also returning the buffered events that
+    /// came after the event numbered `after_seq`, oldest first.
+    ///
+    /// No event is both replayed and received, nor lost between the two. Events that were
+    /// already dropped from the buffer can't be replayed.
+    pub(crate) fn subscribe_after(
+        &self,
+        after_seq: u64,
+    ) -> (Vec<SequencedEvent>, broadcast::Receiver<SequencedEvent>) {
+        let history = self.history.lock();
+
+        if let Some(oldest) = history.front() {
+            if oldest.seq > after_seq.saturating_add(1) {
+                warn!(
+                    after_seq,
+                    oldest = oldest.seq,
+                    "Events to replay were dropped from the buffer"
+                );
+            }
+        }
+
+        let replay = history
+            .iter()
+            .filter(|event| event.seq > after_seq)
+            .cloned()
+            .collect();
+
+        (replay, self.sender.subscribe())
+    }
+
+    /// The number of current subscribers.
+    pub(crate) fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(EVENT_CHANNEL_CAPACITY)
+    }
+}
+
+/// The events of an [`EventBus`] a client subscribed to, limited to one service or to some kinds.
+#[derive(Debug)]
+pub(crate) struct EventSubscription {
+    /// Buffered events still to be replayed before the received ones.
+    replay: VecDeque<SequencedEvent>,
+    events: broadcast::Receiver<SequencedEvent>,
+    /// Only events of this service are received, events of every service when `None`.
+    service_id: Option<i64>,
+    /// Only events of these kinds are received, events of every kind when empty.
+    kinds: Vec<ServiceEventKind>,
+}
+
+impl EventSubscription {
+    /// Subscribes to the events of `bus` matching the filters.
+    ///
+    /// With `after_seq`, the buffered events after that sequence number are replayed first.
+    pub(crate) fn new(
+        bus: &EventBus,
+        service_id: Option<i64>,
+        kinds: Vec<ServiceEventKind>,
+        after_seq: Option<u64>,
+    ) -> Self {
+        let (replay, events) = match after_seq {
+            Some(after_seq) => bus.subscribe_after(after_seq),
+            None => (Vec::new(), bus.subscribe()),
+        };
+
+        Self {
+            replay: replay.into(),
+            events,
+            service_id,
+            kinds,
+        }
+    }
+
+    /// Returns `true` if `event` passes the filters of the subscription.
+    pub(crate) fn matches(&self, event: &ServiceEvent) -> bool {
+        self.service_id
+            .is_none_or(|service_id| service_id == event.service_id())
+            && (self.kinds.is_empty() || self.kinds.contains(&event.kind()))
+    }
+
+    /// Waits for the next matching event, `None` once the bus is gone.
+    ///
+    /// Replayed events come first. Events missed by lagging behind are skipped, cancelling the
+    /// wait loses no event.
+    pub(crate) async fn recv(&mut self) -> Option<SequencedEvent> {
+        while let Some(event) = self.replay.pop_front() {
+            if self.matches(&event.event) {
+                return Some(event);
+            }
+        }
+
+        loop {
+            match self.events.recv().await {
+                Ok(event) if self.matches(&event.event) => return Some(event),
+                Ok(_) => {}
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed, "Event subscriber lagged behind, skipping events");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Git repository types and data structures.
 
+use nexsock_protocol::commands::git::{GitCommitInfo, RepoStatus};
 use serde::{Deserialize, Serialize};
 
 /// Git repository information and state.
@@ -177,3 +178,54 @@ impl GitCommit {
         }
     }
 }
+
+// The conversions destructure every field, so a field added on either side fails to compile
+// until it's mapped here.
+
+impl From<GitRepoInfo> for RepoStatus {
+    fn from(repo_info: GitRepoInfo) -> Self {
+        let GitRepoInfo {
+            current_branch,
+            current_commit,
+            remote_url,
+            is_dirty,
+            branches,
+            ahead_count,
+            behind_count,
+        } = repo_info;
+
+        Self {
+            current_branch,
+            current_commit,
+            remote_url,
+            is_dirty,
+            branches,
+            ahead_count,
+            behind_count,
+        }
+    }
+}
+
+impl From<GitCommit> for GitCommitInfo {
+    fn from(commit: GitCommit) -> Self {
+        let GitCommit {
+            hash,
+            short_hash,
+            author_name,
+            author_email,
+            timestamp,
+            message,
+            full_message,
+        } = commit;
+
+        Self {
+            hash,
+            short_hash,
+            author_name,
+            author_email,
+            timestamp,
+            message,
+            full_message,
+        }
+    }
+}
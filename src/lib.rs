@@ -12,33 +12,41 @@
 
 #![allow(rustdoc::private_intra_doc_links)]
 #![allow(rustdoc::redundant_explicit_links)]
+#![deny(clippy::dbg_macro)]
 
 mod config_manager;
 pub mod daemon;
 mod dependency_manager;
 pub mod error;
+mod events;
 pub mod git;
+mod metrics;
 //mod models;
 mod plugins;
 pub mod prelude;
 mod service_manager;
 mod statics;
+#[cfg(feature = "otel")]
+mod telemetry;
 mod test;
 pub mod traits;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "otel")]
+pub use telemetry::shutdown as shutdown_telemetry;
+
 use crate::daemon::server::DaemonServer;
 use futures::TryFutureExt;
 use nexsock_config::NEXSOCK_CONFIG;
-use nexsock_db::initialize_db;
+use nexsock_db::{initialize_db, migration_status, DEFAULT_HEALTH_CHECK_INTERVAL};
 use prelude::*;
 use std::time::Duration;
 use tokio::time::timeout;
 use tokio::try_join;
-use tosic_utils::logging::{FilterConfig, StdoutLayerConfig, TracingSubscriberBuilder};
-use tracing::{error, info};
+use tosic_utils::logging::{StdoutLayerConfig, TracingSubscriberBuilder};
+use tracing::{error, info, warn};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::layer;
@@ -73,22 +81,18 @@ fn tracing_std_layer() -> StdoutLayerConfig {
         .with_span_events(FmtSpan::CLOSE)
 }
 
-/// Creates an environment-based filter for tracing output.
-///
-/// The filter respects the `RUST_LOG` environment variable and uses
-/// default filtering configuration.
-///
-/// # Returns
+/// Creates the filter for tracing output.
 ///
-/// ```ignore
+/// The `RUST_LOG` environment variable takes precedence, when it is unset the
+/// `log_str` from the config is used.
 fn tracing_env_filter() -> EnvFilter {
-    FilterConfig::default().use_env(true).build()
+    NEXSOCK_CONFIG.to_env_filter()
 }
 
 /// Initializes the global tracing subscriber with configured layers and filters.
 ///
 /// Sets up a non-blocking tracing subscriber that outputs to stdout with:
-/// - Environment-based filtering (respects `RUST_LOG`)
+/// - Filtering from `RUST_LOG`, falling back to the configured `log_str`
 /// - Compact formatting with thread names and line numbers
 /// - Span event tracking
 /// - Non-blocking I/O to prevent log contention
@@ -117,7 +121,9 @@ fn tracing_env_filter() -> EnvFilter {
 ///
 /// Initializes the global tracing subscriber with non-blocking stdout logging.
 ///
-/// Configures tracing to output logs to stdout with compact formatting, thread names, line numbers, log levels, and span close event tracking. Applies environment-based filtering. Returns a vector of `WorkerGuard` objects that must be kept alive to ensure logging remains active.
+/// Configures tracing to output logs to stdout with compact formatting, thread names, line numbers, log levels, and span close event tracking. Applies `RUST_LOG` filtering, or the configured `log_str` when it is unset. Returns a vector of `WorkerGuard` objects that must be kept alive to ensure logging remains active.
+///
+/// With the `otel` feature the spans are also exported to `server.otlp_endpoint` when it is set, see [`shutdown_telemetry`] to flush them.
 ///
 /// # Returns
 /// A vector of `WorkerGuard` objects for maintaining the logging output.
@@ -135,7 +141,7 @@ fn tracing_env_filter() -> EnvFilter {
 pub fn tracing() -> Result<Vec<WorkerGuard>> {
     let (log_writer, guard) = tracing_appender::non_blocking(std::io::stdout());
 
-    TracingSubscriberBuilder::new()
+    let builder = TracingSubscriberBuilder::new()
         .with_filter(tracing_env_filter())
         .with_layer(
             layer()
@@ -147,13 +153,25 @@ pub fn tracing() -> Result<Vec<WorkerGuard>> {
                 .with_level(true)
                 .with_span_events(FmtSpan::CLOSE)
                 .compact(),
-        )
-        .init()
-        .map_err(Into::into)
-        .map(|mut guards| {
-            guards.push(guard);
-            guards
-        })
+        );
+
+    // Spans are also exported to a collector when an endpoint is configured
+    #[cfg(feature = "otel")]
+    let builder = match &NEXSOCK_CONFIG.server().otlp_endpoint {
+        Some(endpoint) => {
+            let provider = telemetry::otlp_tracer_provider(endpoint)?;
+            let layer = telemetry::layer(&provider);
+            telemetry::install(provider);
+
+            builder.with_layer(layer)
+        }
+        None => builder,
+    };
+
+    builder.init().map_err(Into::into).map(|mut guards| {
+        guards.push(guard);
+        guards
+    })
 }
 
 /// Sets up the daemon server with database initialization and server creation.
@@ -162,7 +180,10 @@ pub fn tracing() -> Result<Vec<WorkerGuard>> {
 /// 1. Database connection and migration execution
 /// 2. Daemon server instantiation
 ///
-/// Both operations run in parallel using `try_join!` for optimal startup performance.
+/// Both operations run in parallel using `try_join!` for optimal startup performance. The
+/// migrations are skipped when `run_migrations` is `false`, pending ones are then only logged.
+/// Once connected, the database is checked periodically and reconnected if it becomes
+/// unavailable.
 ///
 /// # Returns
 ///
@@ -178,21 +199,34 @@ pub fn tracing() -> Result<Vec<WorkerGuard>> {
 /// * Server socket binding fails
 /// * Plugin manager initialization fails
 #[tracing::instrument(err)]
-async fn setup() -> Result<DaemonServer> {
+async fn setup(run_migrations: bool) -> Result<DaemonServer> {
     let db_url = NEXSOCK_CONFIG.database().path.display().to_string();
 
-    let (_, server) = try_join!(
-        initialize_db(db_url, true).map_err(Error::from),
+    let (db, server) = try_join!(
+        initialize_db(db_url, run_migrations).map_err(Error::from),
         DaemonServer::new()
     )?;
 
+    if !run_migrations {
+        let pending = migration_status(db).await?.pending().count();
+        if pending > 0 {
+            warn!(
+                pending,
+                "Migrations were skipped, the database has pending migrations"
+            );
+        }
+    }
+
+    db.spawn_health_check(DEFAULT_HEALTH_CHECK_INTERVAL);
+
     Ok(server)
 }
 
 /// Runs the default server implementation alongside the migrations.
 ///
 /// This is the main entry point for running the Nexsock daemon. It:
-/// 1. Sets up the daemon server (database + socket binding)
+/// 1. Sets up the daemon server (database + socket binding), applying pending migrations if
+///    `run_migrations` is set
 /// 2. Runs the server until completion or error
 /// 3. Handles graceful shutdown on errors
 ///
@@ -229,7 +263,7 @@ async fn setup() -> Result<DaemonServer> {
 /// # use tokio::time::timeout;
 /// # match timeout(Duration::new(0, 0),
 /// // Runs the Nexsock daemon server and blocks the current thread until it is stopped either via shutdown or a critical error occurs.
-/// run_daemon()
+/// run_daemon(true)
 /// # ).await {
 /// # Ok(res) => res,
 /// # Err(_) => Ok(()),
@@ -237,8 +271,8 @@ async fn setup() -> Result<DaemonServer> {
 /// # Ok(())
 /// # }
 /// ```
-pub async fn run_daemon() -> Result<()> {
-    let mut server = setup().await?;
+pub async fn run_daemon(run_migrations: bool) -> Result<()> {
+    let mut server = setup(run_migrations).await?;
 
     match server.run().await {
         Ok(_) => info!("Server completed successfully!"),
@@ -259,6 +293,7 @@ pub async fn run_daemon() -> Result<()> {
 /// # Arguments
 ///
 /// * `duration` - Maximum time to allow the daemon to run
+/// * `run_migrations` - Whether pending database migrations are applied on startup
 ///
 /// # Returns
 ///
@@ -280,12 +315,12 @@ pub async fn run_daemon() -> Result<()> {
 ///
 /// # tokio_test::block_on(async {
 /// // Run daemon for maximum 30 seconds
-/// let result = timed_run_daemon(Duration::from_secs(0)).await;
+/// let result = timed_run_daemon(Duration::from_secs(0), true).await;
 /// # });
 /// ```
 #[inline]
-pub async fn timed_run_daemon(duration: Duration) -> Result<()> {
-    match timeout(duration, run_daemon()).await {
+pub async fn timed_run_daemon(duration: Duration, run_migrations: bool) -> Result<()> {
+    match timeout(duration, run_daemon(run_migrations)).await {
         Ok(res) => res,
         Err(_) => Ok(()),
     }
@@ -0,0 +1,88 @@
+//! # Daemon Metrics
+//!
+//! Counters the daemon keeps about its own activity, returned for
+//! [`Command::ExportMetrics`](nexsock_protocol::commands::Command::ExportMetrics).
+//!
+//! The counters only ever grow while the daemon runs, the number of running services is read
+//! from the service manager when the metrics are exported.
+
+use nexsock_protocol::commands::metrics::DaemonMetrics;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The counters behind [`DaemonMetrics`], updated as commands are handled and services are
+/// started and stopped.
+///
+/// # Examples
+///
+/// ```ignore
+/// let metrics = Metrics::new();
+/// metrics.record_command(true);
+///
+/// assert_eq!(metrics.snapshot(0).commands_handled, 1);
+/// ```
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    commands_handled: AtomicU64,
+    commands_failed: AtomicU64,
+    services_started: AtomicU64,
+    start_failures: AtomicU64,
+    services_stopped: AtomicU64,
+    invalid_transitions: AtomicU64,
+}
+
+impl Metrics {
+    /// Creates metrics with every counter at zero.
+    pub(crate) const fn new() -> Self {
+        Self {
+            commands_handled: AtomicU64::new(0),
+            commands_failed: AtomicU64::new(0),
+            services_started: AtomicU64::new(0),
+            start_failures: AtomicU64::new(0),
+            services_stopped: AtomicU64::new(0),
+            invalid_transitions: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a handled command, and a failed one unless it `succeeded`.
+    pub(crate) fn record_command(&self, succeeded: bool) {
+        self.commands_handled.fetch_add(1, Ordering::Relaxed);
+
+        if !succeeded {
+            self.commands_failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Counts a start of a service, as a failure unless it `succeeded`.
+    pub(crate) fn record_start(&self, succeeded: bool) {
+        let counter = if succeeded {
+            &self.services_started
+        } else {
+            &self.start_failures
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a service stopped on request.
+    pub(crate) fn record_stop(&self) {
+        self.services_stopped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a rejected change of the state of a service.
+    pub(crate) fn record_invalid_transition(&self) {
+        self.invalid_transitions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current value of the counters along with the number of `running_services`.
+    pub(crate) fn snapshot(&self, running_services: u64) -> DaemonMetrics {
+        DaemonMetrics {
+            commands_handled: self.commands_handled.load(Ordering::Relaxed),
+            commands_failed: self.commands_failed.load(Ordering::Relaxed),
+            services_started: self.services_started.load(Ordering::Relaxed),
+            start_failures: self.start_failures.load(Ordering::Relaxed),
+            services_stopped: self.services_stopped.load(Ordering::Relaxed),
+            invalid_transitions: self.invalid_transitions.load(Ordering::Relaxed),
+            running_services,
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! # Service Log Parsing
+//!
+//! Turns the output of a service process into [`LogEntry`] values. Output is split into logical
+//! lines first, then the level of each line is detected according to the service's configured
+//! [`LogFormat`]:
+//! - `Raw` keeps every line as-is without a level
+//! - `Json` reads the `level`, `lvl` or `severity` field of JSON lines
+//! - `Text` looks for a level keyword such as `WARN` or `error` in plain text lines
+//!
+//! Services that enable `strip_ansi` have escape sequences such as colors removed from their
+//! lines, the original line is kept alongside for clients that ask for the raw output.
+//!
+//! Entries are timestamped with when the daemon read the line, unless the service configures a
+//! `log_timestamp_format` and the line starts with a timestamp in that format.
+//!
+//! Stored entries are numbered so clients can poll for new output by passing back the sequence
+//! number of the last entry they received. The numbering lasts as long as the process, clearing
+//! the buffered entries doesn't restart it.
+
+use crate::service_manager::LogEntry;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use nexsock_protocol::commands::config::LogFormat;
+use nexsock_protocol::commands::stdout::LogLevel;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::ops::Deref;
+use std::sync::LazyLock;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+
+/// The number of entries kept per process, older entries are dropped first.
+pub(crate) const MAX_LOG_ENTRIES: usize = 10_000;
+
+/// Matches the first level keyword of a plain text log line.
+static TEXT_LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(trace|debug|info|warn(?:ing)?|error|err|fatal|critical)\b")
+        .expect("text level pattern is valid")
+});
+
+/// The JSON fields checked, in order, for the level of a structured log line.
+const JSON_LEVEL_FIELDS: [&str; 3] = ["level", "lvl", "severity"];
+
+/// How the output of a service is turned into log entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogSettings {
+    /// The format used to detect the level of each line.
+    pub(crate) format: LogFormat,
+    /// Whether ANSI escape sequences are removed from stored lines.
+    pub(crate) strip_ansi: bool,
+    /// Whether the output is captured at all, the service inherits the daemon's stdout and stderr
+    /// otherwise and no entries are stored.
+    pub(crate) capture: bool,
+    /// The chrono format of the timestamp lines start with, see [`parse_timestamp`].
+    pub(crate) timestamp_format: Option<String>,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::default(),
+            strip_ansi: false,
+            capture: true,
+            timestamp_format: None,
+        }
+    }
+}
+
+/// Reads `reader` until EOF, sending every line it contains to `tx`.
+///
+/// Lines keep their trailing newline. A line split across several reads is reassembled before it
+/// is sent, and trailing content without a newline is sent once the reader is exhausted. Invalid
+/// UTF-8 is replaced rather than dropped.
+///
+/// Only the first `max_line_bytes` of a line are kept, the rest is dropped while reading so a
+/// single huge line can't exhaust memory. Truncated lines end with a `…[truncated N bytes]`
+/// marker counting the dropped bytes.
+///
+/// Returns early if reading fails or the receiver is dropped.
+pub(crate) async fn read_log_lines<R>(reader: R, tx: mpsc::Sender<String>, max_line_bytes: usize)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    let mut truncated = 0;
+
+    loop {
+        let available = match reader.fill_buf().await {
+            Ok([]) | Err(_) => break,
+            Ok(available) => available,
+        };
+
+        let newline = available.iter().position(|&byte| byte == b'\n');
+        let content_end = newline.unwrap_or(available.len());
+        let take = content_end.min(max_line_bytes.saturating_sub(line.len()));
+
+        line.extend_from_slice(&available[..take]);
+        truncated += content_end - take;
+        reader.consume(newline.map_or(content_end, |newline| newline + 1));
+
+        if newline.is_some() {
+            let content = finish_line(&mut line, &mut truncated, true);
+
+            if tx.send(content).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    if !line.is_empty() || truncated > 0 {
+        let _ = tx.send(finish_line(&mut line, &mut truncated, false)).await;
+    }
+}
+
+/// Turns the bytes read of a line into its content, resetting `line` and `truncated` for the next
+/// one.
+fn finish_line(line: &mut Vec<u8>, truncated: &mut usize, newline: bool) -> String {
+    if *truncated > 0 {
+        // A character cut in half by the limit is dropped entirely instead of being replaced
+        if let Err(error) = std::str::from_utf8(line) {
+            if error.error_len().is_none() {
+                *truncated += line.len() - error.valid_up_to();
+                line.truncate(error.valid_up_to());
+            }
+        }
+    }
+
+    let mut content = String::from_utf8_lossy(line).into_owned();
+    if *truncated > 0 {
+        let _ = write!(content, "…[truncated {truncated} bytes]");
+    }
+    if newline {
+        content.push('\n');
+    }
+
+    line.clear();
+    *truncated = 0;
+
+    content
+}
+
+/// Builds the entry for a single line of output according to `settings`.
+///
+/// The level and timestamp are always detected on the line without escape sequences, so colored
+/// level keywords are recognized even when the stored line keeps its colors.
+pub(crate) fn parse_line(settings: &LogSettings, content: String) -> LogEntry {
+    let stripped = match strip_ansi(&content) {
+        Cow::Borrowed(_) => None,
+        Cow::Owned(stripped) => Some(stripped),
+    };
+    let line = stripped.as_deref().unwrap_or(&content);
+
+    let level = detect_level(settings.format, line);
+    let timestamp = settings
+        .timestamp_format
+        .as_deref()
+        .and_then(|format| parse_timestamp(format, line))
+        .unwrap_or_else(Utc::now);
+
+    let (content, raw) = match stripped {
+        Some(stripped) if settings.strip_ansi => (stripped, Some(content)),
+        _ => (content, None),
+    };
+
+    LogEntry {
+        seq: 0,
+        timestamp,
+        content,
+        raw,
+        level,
+    }
+}
+
+/// Parses the timestamp `line` starts with according to the chrono `format`, `None` if it
+/// doesn't start with one.
+///
+/// Timestamps without a UTC offset in the format are read as UTC.
+pub(crate) fn parse_timestamp(format: &str, line: &str) -> Option<DateTime<Utc>> {
+    if let Ok((timestamp, _)) = DateTime::parse_and_remainder(line, format) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_and_remainder(line, format)
+        .ok()
+        .map(|(timestamp, _)| timestamp.and_utc())
+}
+
+/// The buffered output of a service process, readable as the [`VecDeque`] of its entries.
+#[derive(Debug, Default)]
+pub(crate) struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    /// The sequence number of the last entry pushed, kept when the entries are cleared.
+    last_seq: u64,
+}
+
+impl LogBuffer {
+    /// Creates an empty buffer with room for `capacity` entries.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            last_seq: 0,
+        }
+    }
+
+    /// Removes every entry, entries pushed afterwards are numbered after the removed ones.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Deref for LogBuffer {
+    type Target = VecDeque<LogEntry>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+/// Appends `entry` to `logs`, numbering it after every entry pushed before.
+///
+/// Entries beyond [`MAX_LOG_ENTRIES`] are dropped from the front. Only the oldest entries are ever
+/// dropped, so the newest entry always carries the highest sequence number handed out so far.
+pub(crate) fn push_log_entry(logs: &mut LogBuffer, mut entry: LogEntry) {
+    logs.last_seq += 1;
+    entry.seq = logs.last_seq;
+    logs.entries.push_back(entry);
+
+    while logs.entries.len() > MAX_LOG_ENTRIES {
+        logs.entries.pop_front();
+    }
+}
+
+/// Returns the entries captured after `after_seq` along with the sequence number of the newest
+/// entry pushed, `0` when nothing was pushed yet.
+///
+/// A cursor ahead of the newest entry can only come from an earlier process of the service,
+/// whose numbering started over when it was restarted, so every entry is returned in that case.
+pub(crate) fn entries_after(
+    logs: &LogBuffer,
+    after_seq: Option<u64>,
+) -> (impl Iterator<Item = &LogEntry>, u64) {
+    let last_seq = logs.last_seq;
+
+    let start = match after_seq {
+        Some(after_seq) if after_seq <= last_seq => {
+            logs.partition_point(|entry| entry.seq <= after_seq)
+        }
+        _ => 0,
+    };
+
+    (logs.range(start..), last_seq)
+}
+
+/// Removes ANSI escape sequences from `line`, borrowing it unchanged when it contains none.
+///
+/// Handles CSI sequences such as colors and cursor moves (`ESC [ ... final`), OSC sequences such
+/// as window titles and hyperlinks (`ESC ] ... BEL` or `ESC ] ... ESC \`) and two byte escapes.
+pub(crate) fn strip_ansi(line: &str) -> Cow<'_, str> {
+    if !line.contains('\x1b') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut output = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameter and intermediate bytes, terminated by a final byte in `@..=~`
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or by the string terminator `ESC \`
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Any other escape is ESC followed by a single character
+            _ => {}
+        }
+    }
+
+    Cow::Owned(output)
+}
+
+/// Detects the level of a single log line, returning `None` when it has no recognizable level.
+pub(crate) fn detect_level(format: LogFormat, line: &str) -> Option<LogLevel> {
+    match format {
+        LogFormat::Raw => None,
+        LogFormat::Json => {
+            let value = serde_json::from_str::<serde_json::Value>(line.trim()).ok()?;
+
+            JSON_LEVEL_FIELDS
+                .iter()
+                .find_map(|field| value.get(field)?.as_str())
+                .and_then(|level| level.parse().ok())
+        }
+        LogFormat::Text => TEXT_LEVEL
+            .captures(line)
+            .and_then(|captures| captures[1].parse().ok()),
+    }
+}
+
+/// Joins the content of the entries at or above `min_level`.
+///
+/// Entries without a detected level are always kept, so unparsed output is never hidden. With
+/// `raw` set, lines are returned as the service wrote them, including any stripped escape
+/// sequences.
+pub(crate) fn render_logs<'a>(
+    entries: impl IntoIterator<Item = &'a LogEntry>,
+    min_level: Option<LogLevel>,
+    raw: bool,
+) -> String {
+    entries
+        .into_iter()
+        .filter(|entry| match (entry.level, min_level) {
+            (Some(level), Some(min_level)) => level >= min_level,
+            _ => true,
+        })
+        .map(|entry| match &entry.raw {
+            Some(original) if raw => original.as_str(),
+            _ => entry.content.as_str(),
+        })
+        .collect()
+}
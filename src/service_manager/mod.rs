@@ -9,18 +9,47 @@
 
 #![allow(dead_code)]
 
+pub(crate) mod log_parser;
 pub(crate) mod new;
+pub(crate) mod process_group;
+pub(crate) mod ready;
+pub(crate) mod resources;
+pub(crate) mod run_command;
+pub(crate) mod state;
+pub(crate) mod validate;
 
 use command_group::AsyncGroupChild;
+use log_parser::LogBuffer;
+use nexsock_protocol::commands::manage_service::StopSignal;
 use nexsock_protocol::commands::service_status::ServiceState;
-use std::collections::{HashMap, VecDeque};
+use nexsock_protocol::commands::stdout::LogLevel;
+use resources::{ResourceSample, ResourceUsage};
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::process::{ChildStderr, ChildStdin, ChildStdout};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Instant};
 use tracing::warn;
 
+/// How long a started process has to stay alive before its startup counts as successful.
+pub(crate) const STARTUP_WINDOW: Duration = Duration::from_millis(500);
+
+/// How many lines of stderr are kept to explain a failed startup.
+pub(crate) const STARTUP_STDERR_LINES: usize = 20;
+
+/// How often the process is checked during the startup window.
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// How many lines of output are buffered for [`ServiceProcess::output`] subscribers.
+pub(crate) const OUTPUT_CHANNEL_CAPACITY: usize = 256;
+
+/// How long the stderr reader gets to catch up once the process exited.
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
 /// Represents a running service process with its associated resources and state.
 ///
 /// This struct encapsulates all the information and resources associated with a
@@ -55,31 +84,65 @@ where
     /// The current state of the service process.
     pub(crate) state: ServiceState,
 
+    /// When the process was spawned.
+    pub(crate) started_at: Instant,
+
+    /// The signal the process is sent first when it is stopped.
+    pub(crate) stop_signal: StopSignal,
+
     /// Environment variables that were set when the process was spawned.
     pub(crate) env_vars: HashMap<String, String>,
 
     /// Optional stdout stream from the process.
     pub(crate) stdout: Option<Out>,
 
-    /// Optional stdin stream to the process.
-    pub(crate) stdin: Option<In>,
+    /// Stdin stream to the process, `None` once it was closed.
+    ///
+    /// Shared so writing to it doesn't hold the entry of the process in the running services.
+    pub(crate) stdin: Arc<Mutex<Option<In>>>,
 
     /// Optional stderr stream from the process.
     pub(crate) stderr: Option<Err>,
 
     /// Circular buffer storing collected stdout logs with timestamps.
     /// Limited to prevent memory exhaustion from long-running processes.
-    pub(crate) stdout_logs: Arc<Mutex<VecDeque<LogEntry>>>,
+    pub(crate) stdout_logs: Arc<Mutex<LogBuffer>>,
 
     /// Handles for the background tasks collecting and processing logs.
     /// Tuple contains (log processing task, stdout reading task).
     pub(crate) log_task_handle: Option<(tokio::task::JoinHandle<()>, tokio::task::JoinHandle<()>)>,
+
+    /// The first [`STARTUP_STDERR_LINES`] lines the process wrote to stderr.
+    pub(crate) startup_stderr: Arc<Mutex<Vec<String>>>,
+
+    /// Handles for the background tasks reading stderr.
+    /// Tuple contains (line forwarding task, stderr reading task).
+    pub(crate) stderr_task_handle: Option<(JoinHandle<()>, JoinHandle<()>)>,
+
+    /// Every captured line of stdout and stderr, as it is read.
+    pub(crate) output: broadcast::Sender<String>,
+
+    /// The last resource sample of the process group, compared against by the next one.
+    pub(crate) resource_sample: Option<ResourceSample>,
+
+    /// The resources the process group used between the last two samples, `None` until it was
+    /// sampled twice or where sampling isn't supported.
+    pub(crate) resources: Option<ResourceUsage>,
+}
+
+/// Why a process exited before its startup window was over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StartupFailure {
+    /// The exit code of the process, `None` if it was killed by a signal.
+    pub(crate) exit_code: Option<i32>,
+    /// The first lines the process wrote to stderr.
+    pub(crate) stderr: String,
 }
 
 /// Represents a single log entry from a service process.
 ///
 /// Each log entry contains a timestamp indicating when the log was captured
-/// and the actual log content. Log entries are stored in a circular buffer
+/// and a single line of the captured output. Log entries are stored in a circular buffer
 /// to maintain a history of process output while preventing memory exhaustion.
 ///
 /// # Examples
@@ -89,17 +152,31 @@ where
 /// use chrono::Utc;
 ///
 /// let entry = LogEntry {
+///     seq: 1,
 ///     timestamp: Utc::now(),
 ///     content: "Server started on port 3000".to_string(),
+///     raw: None,
+///     level: None,
 /// };
 /// ```
 #[derive(Debug, Clone)]
 pub(crate) struct LogEntry {
-    /// The UTC timestamp when this log entry was captured.
+    /// Position of this entry in the output of the process, starting at 1 and increasing by one
+    /// for every captured line. Assigned when the entry is stored.
+    pub(crate) seq: u64,
+
+    /// The UTC timestamp when this log entry was captured, or the one the line starts with when the
+    /// service configures a `log_timestamp_format`.
     pub(crate) timestamp: chrono::DateTime<chrono::Utc>,
 
-    /// The actual log content captured from the process stdout.
+    /// One line of output captured from the process stdout, including its newline.
     pub(crate) content: String,
+
+    /// The line as the process wrote it, if escape sequences were stripped from `content`.
+    pub(crate) raw: Option<String>,
+
+    /// The level detected for this entry, if the service's log format exposes one.
+    pub(crate) level: Option<LogLevel>,
 }
 
 impl ServiceProcess {
@@ -154,4 +231,76 @@ impl ServiceProcess {
             None => Ok(self.state),
         }
     }
+
+    /// Whether the process has been starting for `timeout` or longer, a zero `timeout` never
+    /// expires.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// if process.startup_timed_out(Duration::from_secs(120)) {
+    ///     process.state = ServiceState::Failed;
+    /// }
+    /// ```
+    pub(crate) fn startup_timed_out(&self, timeout: Duration) -> bool {
+        self.state == ServiceState::Starting
+            && !timeout.is_zero()
+            && self.started_at.elapsed() >= timeout
+    }
+
+    /// Waits up to `window` for the process to exit, returning why it did if it exits in time.
+    ///
+    /// Returns `None` if the process is still running once the window is over. A process that
+    /// exits within the window failed to start even if it exited successfully, a service is
+    /// expected to keep running.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system call to check the process status fails.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// if let Some(failure) = process.wait_for_startup(STARTUP_WINDOW).await? {
+    ///     return Err(failure.into());
+    /// }
+    /// ```
+    pub(crate) async fn wait_for_startup(
+        &mut self,
+        window: Duration,
+    ) -> crate::error::Result<Option<StartupFailure>> {
+        let deadline = Instant::now() + window;
+
+        let status = loop {
+            if let Some(status) = self.process.try_wait()? {
+                break status;
+            }
+
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+
+            sleep(STARTUP_POLL_INTERVAL).await;
+        };
+
+        self.state = if status.success() {
+            ServiceState::Stopped
+        } else {
+            ServiceState::Failed
+        };
+
+        // The reader stops once the pipe is closed, which a child of the process may keep open
+        if let Some((forward_task, read_task)) = self.stderr_task_handle.take() {
+            if timeout(STDERR_DRAIN_TIMEOUT, forward_task).await.is_err() {
+                read_task.abort();
+            }
+        }
+
+        let stderr = self.startup_stderr.lock().await.concat();
+
+        Ok(Some(StartupFailure {
+            exit_code: status.code(),
+            stderr: stderr.trim_end().to_string(),
+        }))
+    }
 }
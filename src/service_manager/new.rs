@@ -3,7 +3,16 @@
 //! This module contains the concrete implementation of service management
 //! functionality, providing process lifecycle management and service operations.
 
-use super::ServiceProcess;
+use super::log_parser::LogSettings;
+use super::ready::{wait_for_port, wait_until_ready, ReadyCheck, Readiness};
+use super::run_command::{render_run_command, RunCommandVars};
+use super::state::{is_valid_transition, validate_transition};
+use super::validate::{
+    check_config_file, check_dependencies, check_port, check_repo_path, check_run_command,
+};
+use super::{ServiceProcess, StartupFailure, STARTUP_WINDOW};
+use crate::events::EventBus;
+use crate::statics::METRICS;
 use crate::traits::git_management::GitManagement;
 use crate::traits::process_manager::{FullProcessManager, ProcessManager};
 use crate::traits::service_management::ServiceManagement;
@@ -11,18 +20,34 @@ use anyhow::anyhow;
 use dashmap::try_result::TryResult;
 use dashmap::DashMap;
 use nexsock_db::prelude::{
-    Service, ServiceConfig, ServiceConfigRepository, ServiceDependencyRepository, ServiceRepository,
+    apply_manifest, with_transaction, ManagedConnection, Service, ServiceConfig,
+    ServiceConfigRepository, ServiceDependency, ServiceDependencyRepository,
+    ServiceLabelRepository, ServiceRepository,
 };
 use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::event::ServiceEvent;
+use nexsock_protocol::commands::label::LabelSelectorPayload;
 use nexsock_protocol::commands::list_services::ListServicesResponse;
-use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::manage_service::{
+    CloneServicePayload, DependencyEdge, RemovalPlan, RemoveServicePayload, ServiceRef,
+    StartServicePayload, DEFAULT_READY_TIMEOUT_SECS,
+};
+use nexsock_protocol::commands::manifest::{
+    ApplyManifestPayload, ApplyManifestResponse, ManifestAction,
+};
+use nexsock_protocol::commands::profile::ProfilePayload;
+use nexsock_protocol::commands::search::SearchServicesPayload;
 use nexsock_protocol::commands::service_status::{ServiceState, ServiceStatus};
+use nexsock_protocol::commands::validate::ValidationReport;
 use port_selector::is_free_tcp;
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::{Arc, LazyLock};
-use tokio::sync::broadcast;
-use tracing::{debug, warn};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
 
 /// Service manager for lifecycle operations and process management.
 ///
@@ -45,10 +70,11 @@ use tracing::{debug, warn};
 #[derive(Debug)]
 pub struct ServiceManager {
     running_services: Arc<DashMap<i64, ServiceProcess>>,
-    shutdown_tx: broadcast::Sender<()>,
-    service_repository: ServiceRepository<'static>,
-    dependency_repository: ServiceDependencyRepository<'static>,
-    config_repository: ServiceConfigRepository<'static>,
+    shutdown_tx: watch::Sender<bool>,
+    event_bus: EventBus,
+    service_repository: ServiceRepository<'static, ManagedConnection>,
+    dependency_repository: ServiceDependencyRepository<'static, ManagedConnection>,
+    config_repository: ServiceConfigRepository<'static, ManagedConnection>,
 }
 
 impl ServiceManager {
@@ -72,12 +98,317 @@ impl ServiceManager {
     pub const fn new_const() -> LazyLock<Self> {
         LazyLock::new(Default::default)
     }
+
+    /// Samples the CPU and memory usage of every running service, reported by
+    /// [`get_status`](ServiceManagement::get_status) once a service was sampled twice.
+    ///
+    /// The samples are read from `/proc` on a blocking thread, on platforms other than Linux
+    /// nothing is sampled.
+    pub(crate) async fn sample_resources(&self) {
+        let running_services = self.running_services.clone();
+
+        let sampled = tokio::task::spawn_blocking(move || {
+            for mut process in running_services.iter_mut() {
+                process.sample_resources();
+            }
+        })
+        .await;
+
+        if let Err(e) = sampled {
+            warn!(error = ?e, "Failed to sample the resources of the running services");
+        }
+    }
+
+    /// Records the outcome of a failed start on the service, `None` for both clears it.
+    ///
+    /// Failing to record it is logged but never hides the outcome of the start from the caller.
+    async fn record_startup_failure(
+        &self,
+        service_id: i64,
+        exit_code: Option<i32>,
+        error: Option<String>,
+    ) {
+        if let Err(e) = self
+            .service_repository
+            .update_startup_failure(service_id, exit_code, error)
+            .await
+        {
+            warn!(service_id, error = ?e, "Failed to record the startup failure");
+        }
+    }
+
+    /// Records why the process of a service exited during startup, returning the error the start
+    /// fails with.
+    async fn startup_failed(&self, service_id: i64, failure: StartupFailure) -> crate::Error {
+        warn!(service_id, exit_code = ?failure.exit_code, "Service exited during startup");
+
+        self.record_startup_failure(service_id, failure.exit_code, Some(failure.stderr.clone()))
+            .await;
+
+        failure.into()
+    }
+
+    /// Handles a service whose `repo_path` doesn't exist, cloning its repository when
+    /// `clone_missing` is set and the daemon was built with git support.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingRepoPath`](crate::error::Error::MissingRepoPath) if the repository
+    /// isn't cloned, or the error of the clone.
+    #[cfg_attr(not(feature = "git"), allow(unused_variables))]
+    async fn prepare_missing_repo(
+        &self,
+        service: &Service,
+        clone_missing: bool,
+    ) -> crate::error::Result<()> {
+        #[cfg(feature = "git")]
+        if clone_missing && !service.repo_url.is_empty() {
+            info!(
+                service = %service.name,
+                path = %service.repo_path,
+                "Cloning the missing repository"
+            );
+
+            return self.git_ensure_repo(&ServiceRef::Id(service.id)).await;
+        }
+
+        Err(crate::Error::MissingRepoPath {
+            service: service.name.clone(),
+            path: service.repo_path.clone(),
+        })
+    }
+
+    /// Logs a warning when other services depend on the given service.
+    ///
+    /// Used before stopping a service so it's visible in the logs which services may break as a
+    /// result. Lookup failures are logged but never abort the operation.
+    async fn warn_if_dependents(&self, service: &Service, action: &str) {
+        match self.dependency_repository.list_dependents(service.id).await {
+            Ok(dependents) if !dependents.is_empty() => {
+                let names = dependents
+                    .iter()
+                    .map(|dependent| dependent.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                warn!(
+                    service = %service.name,
+                    dependents = %names,
+                    "{action} a service that other services depend on"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                debug!(service = %service.name, error = ?e, "Failed to look up dependents");
+            }
+        }
+    }
+
+    /// Waits up to `timeout_secs` for every tunnel enabled dependency of the service to listen on
+    /// its port, so the service doesn't connect through a tunnel to a dependency that is still
+    /// starting.
+    ///
+    /// An optional dependency that isn't ready in time is logged and the service starts without
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first required dependency that isn't ready in time.
+    async fn wait_for_tunnel_dependencies(
+        &self,
+        service_id: i64,
+        timeout_secs: u64,
+    ) -> crate::error::Result<()> {
+        let timeout = Duration::from_secs(timeout_secs);
+        let dependencies = self
+            .dependency_repository
+            .get_by_service_id(service_id)
+            .await?;
+
+        for dependency in dependencies
+            .iter()
+            .filter(|dependency| dependency.tunnel_enabled)
+        {
+            debug!(service_id, dependency = %dependency.name, "Waiting for the dependency");
+
+            if wait_for_port(dependency.port as u16, timeout).await {
+                continue;
+            }
+
+            if !dependency.optional {
+                return Err(anyhow!(
+                    "Dependency `{}` was not ready within {timeout_secs} seconds",
+                    dependency.name
+                )
+                .into());
+            }
+
+            warn!(
+                service_id,
+                dependency = %dependency.name,
+                "Optional dependency was not ready within {timeout_secs} seconds, starting without it"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Starts the service, see [`ServiceManagement::start`].
+    async fn start_service(&self, payload: &StartServicePayload) -> crate::error::Result<()> {
+        let StartServicePayload {
+            service,
+            env_vars,
+            wait_ready,
+            ready_timeout_secs,
+            clone_missing,
+        } = payload;
+
+        let service = self
+            .service_repository
+            .get_by_service_ref(service)
+            .await?
+            .ok_or(anyhow!("No Service found with reference {}", service))?;
+
+        let service_id = service.id;
+        let port = service.port as u16;
+
+        validate_transition(
+            service_id,
+            self.get_service_state(service_id),
+            ServiceState::Starting,
+        )?;
+
+        if !is_free_tcp(port) {
+            return Err(anyhow!("Port is already in use").into());
+        }
+
+        // Resolved before spawning so an invalid condition doesn't leave a process behind
+        let ready_check = wait_ready
+            .as_ref()
+            .map(|condition| ReadyCheck::new(condition, port))
+            .transpose()?;
+
+        // Get the full service info including config
+        let service = self
+            .service_repository
+            .get_detailed_by_id(service_id)
+            .await?;
+
+        let config = service
+            .config
+            .ok_or_else(|| anyhow!("Service has no configuration"))?;
+
+        let run_command = config
+            .run_command
+            .ok_or_else(|| anyhow!("Service has no run command"))?;
+
+        if !std::path::Path::new(&service.service.repo_path).is_dir() {
+            self.prepare_missing_repo(&service.service, *clone_missing).await?;
+        }
+
+        let run_command = render_run_command(
+            &run_command,
+            &RunCommandVars {
+                port,
+                repo_path: &service.service.repo_path,
+                name: &service.service.name,
+            },
+        )?;
+
+        let timeout_secs = ready_timeout_secs.unwrap_or(DEFAULT_READY_TIMEOUT_SECS);
+        self.wait_for_tunnel_dependencies(service_id, timeout_secs)
+            .await?;
+
+        let had_startup_failure =
+            service.service.last_exit_code.is_some() || service.service.last_error.is_some();
+        let path = service.service.repo_path;
+
+        let started = Instant::now();
+        let mut service_process = self
+            .spawn_service_process(
+                service_id,
+                path,
+                &run_command,
+                env_vars.clone(),
+                LogSettings {
+                    format: config.log_format,
+                    strip_ansi: config.strip_ansi,
+                    capture: config.capture_output,
+                    timestamp_format: config.log_timestamp_format,
+                },
+            )
+            .await?;
+        service_process.stop_signal = service.service.stop_signal;
+        let output = service_process.output.subscribe();
+
+        if let Some(failure) = service_process.wait_for_startup(STARTUP_WINDOW).await? {
+            return Err(self.startup_failed(service_id, failure).await);
+        }
+
+        if let Some(check) = ready_check {
+            let timeout = Duration::from_secs(timeout_secs);
+
+            match wait_until_ready(&mut service_process, &check, output, started, timeout).await? {
+                Readiness::Ready => debug!(service_id, "Service is ready"),
+                Readiness::Exited(failure) => {
+                    return Err(self.startup_failed(service_id, failure).await);
+                }
+                Readiness::TimedOut => {
+                    warn!(service_id, timeout_secs, "Service was not ready in time");
+
+                    if let Err(e) = self.cleanup_process(service_id, &mut service_process).await {
+                        warn!(service_id, error = ?e, "Failed to stop the service");
+                    }
+
+                    return Err(
+                        anyhow!("Service was not ready within {timeout_secs} seconds").into(),
+                    );
+                }
+            }
+        }
+
+        if had_startup_failure {
+            self.record_startup_failure(service_id, None, None).await;
+        }
+
+        self.running_services.insert(service_id, service_process);
+        self.event_bus.publish(ServiceEvent::Started { service_id });
+
+        debug!(service_manager = ?self);
+
+        Ok(())
+    }
+
+    /// Looks up the services of `profile`, an empty profile is an error.
+    async fn profile_services(&self, profile: &str) -> crate::error::Result<Vec<Service>> {
+        let services = self.service_repository.find_by_profile(profile).await?;
+
+        if services.is_empty() {
+            return Err(anyhow!("No services in profile `{profile}`").into());
+        }
+
+        Ok(services)
+    }
+}
+
+/// Fails with every service of `profile` the `action` failed for, one per line.
+fn profile_result(profile: &str, action: &str, failures: Vec<String>) -> crate::error::Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "Failed to {action} {} of the services in profile `{profile}`:\n{}",
+        failures.len(),
+        failures.join("\n")
+    )
+    .into())
 }
 
 impl Default for ServiceManager {
     /// Creates a new `ServiceManager` instance with empty service state and initialized repositories.
     ///
-    /// Initializes the running services map, shutdown broadcast channel, and static-backed repositories for services, dependencies, and configurations.
+    /// Initializes the running services map, shutdown channel, and static-backed repositories for services, dependencies, and configurations.
     ///
     /// # Examples
     ///
@@ -86,10 +417,11 @@ impl Default for ServiceManager {
     /// assert!(manager.running_services().is_empty());
     /// ```
     fn default() -> Self {
-        let (shutdown_tx, _) = broadcast::channel(1);
+        let (shutdown_tx, _) = watch::channel(false);
         Self {
             running_services: Arc::new(DashMap::new()),
             shutdown_tx,
+            event_bus: EventBus::default(),
             service_repository: ServiceRepository::new_from_static(),
             dependency_repository: ServiceDependencyRepository::new_from_static(),
             config_repository: ServiceConfigRepository::new_from_static(),
@@ -113,18 +445,31 @@ impl ProcessManager for ServiceManager {
         &self.running_services
     }
 
-    /// Returns a reference to the broadcast channel sender used for shutdown signaling.
+    /// Returns a reference to the watch channel sender used for shutdown signaling.
     ///
     /// # Examples
     ///
     /// ```ignore
     /// let manager = ServiceManager::default();
-    /// let sender = manager.shutdown_tx();
-    /// sender.send(()).unwrap();
+    /// let mut shutdown = manager.shutdown_signal();
+    /// manager.signal_shutdown();
+    /// assert!(*shutdown.borrow_and_update());
     /// ```
-    fn shutdown_tx(&self) -> &broadcast::Sender<()> {
+    fn shutdown_tx(&self) -> &watch::Sender<bool> {
         &self.shutdown_tx
     }
+
+    /// Returns the bus the lifecycle events of the services are published to.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let manager = ServiceManager::default();
+    /// let mut events = manager.event_bus().subscribe();
+    /// ```
+    fn event_bus(&self) -> &EventBus {
+        &self.event_bus
+    }
 }
 
 impl ServiceManagement for ServiceManager {
@@ -133,59 +478,35 @@ impl ServiceManagement for ServiceManager {
     ///
     /// Checks that the service exists, is not already running, and that its configured port is available. Retrieves the service's configuration and run command, then spawns the service process and tracks it as running.
     ///
+    /// A process that exits within [`STARTUP_WINDOW`] failed to start, its exit code and the first lines of its stderr are returned in the error and recorded as `last_exit_code` and `last_error` of the service. A successful start clears them.
+    ///
+    /// A missing `repo_path` fails the start with [`Error::MissingRepoPath`](crate::error::Error::MissingRepoPath), unless `clone_missing` is set and the repository can be cloned.
+    ///
+    /// The `{port}`, `{repo_path}` and `{name}` placeholders of the run command are replaced with the values of the service, see [`render_run_command`].
+    ///
+    /// With `wait_ready` set the start only returns once the [`ReadyCondition`](nexsock_protocol::commands::manage_service::ReadyCondition) holds. A process that isn't ready within `ready_timeout_secs` ([`DEFAULT_READY_TIMEOUT_SECS`] when unset) is stopped and the start fails.
+    ///
+    /// Dependencies with a tunnel enabled have to listen on their port before the process is spawned, the start waits up to the same timeout for them.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the service does not exist, is already running, lacks configuration or a run command, or if the port is in use.
+    /// Returns an error if the service does not exist, [`Error::InvalidStateTransition`](crate::error::Error::InvalidStateTransition) if it is already starting, running or stopping, an error if it lacks configuration or a run command, if the run command uses an unknown placeholder, if the port is in use, if the ready pattern is invalid, if a required tunnel enabled dependency or the service isn't ready in time, or [`Error::StartupFailed`](crate::error::Error::StartupFailed) if the process exits during startup.
     ///
     /// # Examples
     ///
     /// ```ignore
-    /// let payload = StartServicePayload { service: ServiceRef::Id(42), env_vars: None };
+    /// let payload = StartServicePayload {
+    ///     service: ServiceRef::Id(42),
+    ///     wait_ready: Some(ReadyCondition::Port),
+    ///     ..Default::default()
+    /// };
     /// service_manager.start(&payload).await?;
     /// ```
     async fn start(&self, payload: &StartServicePayload) -> crate::error::Result<()> {
-        let StartServicePayload { service, env_vars } = payload;
-
-        let service = self
-            .service_repository
-            .get_by_service_ref(service)
-            .await?
-            .ok_or(anyhow!("No Service found with reference {}", service))?;
-
-        let service_id = service.id;
+        let result = self.start_service(payload).await;
+        METRICS.record_start(result.is_ok());
 
-        if !is_free_tcp(service.port as u16) {
-            return Err(anyhow!("Port is already in use").into());
-        }
-
-        // Check current state
-        if matches!(self.get_service_state(service_id), ServiceState::Running) {
-            return Err(anyhow!("Service is already running").into());
-        }
-
-        // Get the full service info including config
-        let service = self
-            .service_repository
-            .get_detailed_by_id(service_id)
-            .await?;
-
-        let run_command = service
-            .config
-            .ok_or_else(|| anyhow!("Service has no configuration"))?
-            .run_command
-            .ok_or_else(|| anyhow!("Service has no run command"))?;
-
-        let path = service.service.repo_path;
-
-        let service_process = self
-            .spawn_service_process(service_id, path, &run_command, env_vars.clone())
-            .await?;
-
-        self.running_services.insert(service_id, service_process);
-
-        debug!(service_manager = ?self);
-
-        Ok(())
+        result
     }
 
     #[tracing::instrument]
@@ -207,6 +528,8 @@ impl ServiceManagement for ServiceManager {
             .await?
             .ok_or_else(|| anyhow!("No Service with reference `{payload}`"))?;
 
+        self.warn_if_dependents(&service, "Stopping").await;
+
         self.kill_service_process(service.id).await?;
 
         Ok(())
@@ -226,7 +549,7 @@ impl ServiceManagement for ServiceManager {
     /// ```ignore
     /// let payload = StartServicePayload {
     ///     service: ServiceRef::from_id(42),
-    ///     env_vars: HashMap::new(),
+    ///     ..Default::default()
     /// };
     /// service_manager.restart(&payload).await?;
     /// ```
@@ -260,6 +583,9 @@ impl ServiceManagement for ServiceManager {
             let payload = StartServicePayload {
                 service: payload.service.clone(),
                 env_vars,
+                wait_ready: payload.wait_ready.clone(),
+                ready_timeout_secs: payload.ready_timeout_secs,
+                clone_missing: payload.clone_missing,
             };
 
             // Now stop and start without holding any references
@@ -276,7 +602,7 @@ impl ServiceManagement for ServiceManager {
     /// If a configuration is provided, it is saved and associated with the new service. The service record is then created with the specified Git and configuration details and persisted in the service repository.
     ///
     /// # Parameters
-    /// - `payload`: Contains the service's name, repository information, port, optional configuration, Git branch, and authentication type.
+    /// - `payload`: Contains the service's name, repository information, port, optional configuration, Git branch, authentication type, the signal it is stopped with, and its profile.
     ///
     /// # Errors
     /// Returns an error if saving the configuration or service record fails.
@@ -292,115 +618,419 @@ impl ServiceManagement for ServiceManager {
     ///     config: None,
     ///     git_branch: Some("main".to_string()),
     ///     git_auth_type: Some(GitAuthType::SshAgent),
+    ///     stop_signal: StopSignal::Int,
+    ///     profile: Some("backend".to_string()),
+    ///     labels: BTreeMap::from([("team".to_string(), "payments".to_string())]),
     /// };
     /// service_manager.add_service(&payload).await?;
     /// ```
     async fn add_service(&self, payload: &AddServicePayload) -> crate::error::Result<()> {
-        let AddServicePayload {
-            name,
-            repo_url,
-            port,
-            repo_path,
-            config,
-            git_branch,
-            git_auth_type,
-        } = payload;
-
-        let id = if let Some(config) = config {
-            let mut config_record = ServiceConfig::new(
-                config.filename.to_owned(),
-                config.format,
-                if config.run_command.is_empty() {
-                    None
+        let payload = payload.clone();
+
+        // The config and service rows are written together so a failed insert leaves no orphaned config
+        with_transaction(self.service_repository.connection(), move |txn| {
+            let AddServicePayload {
+                name,
+                repo_url,
+                port,
+                repo_path,
+                config,
+                git_branch,
+                git_auth_type,
+                stop_signal,
+                profile,
+                labels,
+            } = payload.clone();
+
+            Box::pin(async move {
+                let id = if let Some(config) = config {
+                    let mut config_record = ServiceConfig::new(
+                        config.filename,
+                        config.format,
+                        if config.run_command.is_empty() {
+                            None
+                        } else {
+                            Some(config.run_command)
+                        },
+                    );
+                    config_record.log_format = config.log_format;
+                    config_record.strip_ansi = config.strip_ansi;
+                    config_record.capture_output = config.capture_output;
+                    config_record.log_timestamp_format = config.log_timestamp_format;
+                    ServiceConfigRepository::new(txn)
+                        .save(&mut config_record)
+                        .await?;
+                    Some(config_record.id)
                 } else {
-                    Some(config.run_command.to_owned())
-                },
-            );
-            self.config_repository.save(&mut config_record).await?;
-            Some(config_record.id)
+                    None
+                };
+
+                let mut record = Service::new_with_git(
+                    name,
+                    repo_url,
+                    port,
+                    repo_path,
+                    id,
+                    nexsock_db::models::service::GitParams {
+                        branch: git_branch,
+                        commit_hash: None, // git_commit_hash will be set when repository is cloned
+                        auth_type: git_auth_type,
+                    },
+                );
+                record.stop_signal = stop_signal;
+                record.profile = profile;
+
+                ServiceRepository::new(txn).save(&mut record).await?;
+                ServiceLabelRepository::new(txn)
+                    .set_labels(record.id, &labels)
+                    .await
+            })
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    /// Copies a service under a new name and port, see [`ServiceManagement::clone_service`].
+    ///
+    /// The service row, its config, labels and optionally its dependencies are copied in one
+    /// transaction, so a failure midway leaves no partial clone behind.
+    async fn clone_service(
+        &self,
+        payload: &CloneServicePayload,
+    ) -> crate::error::Result<ServiceStatus> {
+        let CloneServicePayload {
+            source,
+            new_name,
+            new_port,
+            new_repo_path,
+            copy_dependencies,
+        } = payload.clone();
+
+        let service = self
+            .service_repository
+            .get_by_service_ref(&source)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find service with `{source}`"))?;
+
+        let config = match service.config_id {
+            Some(config_id) => self.config_repository.get_by_id(config_id).await?,
+            None => None,
+        };
+        let labels = ServiceLabelRepository::new_from_static()
+            .get_labels(service.id)
+            .await?;
+        let dependencies = if copy_dependencies {
+            self.dependency_repository
+                .get_by_service_id(service.id)
+                .await?
         } else {
-            None
+            Vec::new()
         };
 
-        let mut record = Service::new_with_git(
-            name.to_owned(),
-            repo_url.to_owned(),
-            *port,
-            repo_path.to_owned(),
-            id,
-            nexsock_db::models::service::GitParams {
-                branch: git_branch.clone(),
-                commit_hash: None, // git_commit_hash will be set when repository is cloned
-                auth_type: git_auth_type.clone(),
-            },
-        );
+        let clone_id = with_transaction(self.service_repository.connection(), move |txn| {
+            // The transaction is run again if the database is locked, every run gets its own copies
+            let config = config.clone();
+            let service = service.clone();
+            let new_name = new_name.clone();
+            let new_repo_path = new_repo_path.clone();
+            let labels = labels.clone();
+            let dependencies = dependencies.clone();
+
+            Box::pin(async move {
+                let config_id = match config {
+                    Some(mut config) => {
+                        config.id = 0;
+                        ServiceConfigRepository::new(txn).save(&mut config).await?;
+                        Some(config.id)
+                    }
+                    None => None,
+                };
+
+                let mut record = Service::new_with_git(
+                    new_name,
+                    service.repo_url,
+                    new_port,
+                    new_repo_path.unwrap_or(service.repo_path),
+                    config_id,
+                    nexsock_db::models::service::GitParams {
+                        branch: service.git_branch,
+                        commit_hash: service.git_commit_hash,
+                        auth_type: service.git_auth_type,
+                    },
+                );
+                record.stop_signal = service.stop_signal;
+                record.profile = service.profile;
+
+                ServiceRepository::new(txn).save(&mut record).await?;
+                ServiceLabelRepository::new(txn)
+                    .set_labels(record.id, &labels)
+                    .await?;
+
+                let dependency_repository = ServiceDependencyRepository::new(txn);
+                for dependency in dependencies {
+                    dependency_repository
+                        .save(&mut ServiceDependency {
+                            id: 0,
+                            service_id: record.id,
+                            dependent_service_id: dependency.dependent_service_id,
+                            tunnel_enabled: dependency.tunnel_enabled,
+                            optional: dependency.optional,
+                        })
+                        .await?;
+                }
 
-        self.service_repository.save(&mut record).await?;
+                Ok(record.id)
+            })
+        })
+        .await?;
 
-        Ok(())
+        self.get_status(&ServiceRef::Id(clone_id)).await
     }
 
     #[tracing::instrument]
-    /// Removes a service and its associated resources.
+    /// Removes a service and its associated resources, returning the [`RemovalPlan`] of what was
+    /// deleted.
     ///
-    /// Stops the service if it is running or starting, deletes all related dependencies, removes the service record from the repository, and deletes its configuration if present.
+    /// Stops the service if it is running or starting, removes the service record from the repository, and deletes its configuration if present.
+    /// Dependency rows referencing the service, either as the parent or as the dependent, are cascaded by the database.
+    /// The database rows are removed in a single transaction, if any delete fails none of them are removed.
+    ///
+    /// A service other services depend on is only removed when `force` is set, the dependencies
+    /// of those services on it are then removed as well. A dry run returns the plan without
+    /// removing anything.
     ///
     /// # Arguments
     ///
-    /// * `payload` - Reference to the service to be removed.
+    /// * `payload` - Reference to the service to be removed and how to remove it.
     ///
     /// # Errors
     ///
-    /// Returns an error if the service does not exist or if any step in the removal process fails.
+    /// Returns [`Error::HasDependents`](crate::error::Error::HasDependents) if other services
+    /// depend on the service and `force` isn't set, or an error if the service does not exist or if any step in the removal process fails.
     ///
     /// # Examples
     ///
     /// ```ignore
-    /// let service_ref = ServiceRef::from_id(42);
-    /// service_manager.remove_service(&service_ref).await?;
+    /// let payload = RemoveServicePayload {
+    ///     service: ServiceRef::from_id(42),
+    ///     ..Default::default()
+    /// };
+    /// let plan = service_manager.remove_service(&payload).await?;
     /// ```
-    async fn remove_service(&self, payload: &ServiceRef) -> crate::error::Result<()> {
+    async fn remove_service(
+        &self,
+        payload: &RemoveServicePayload,
+    ) -> crate::error::Result<RemovalPlan> {
+        let RemoveServicePayload {
+            service,
+            dry_run,
+            force,
+        } = payload;
+
         let service = self
             .service_repository
-            .get_by_service_ref(payload)
+            .get_by_service_ref(service)
             .await?
-            .ok_or_else(|| anyhow!("Could not find service with `{payload}`"))?;
+            .ok_or_else(|| anyhow!("Could not find service with `{service}`"))?;
+
+        let dependents = self
+            .dependency_repository
+            .list_dependents(service.id)
+            .await?;
+        if !dependents.is_empty() {
+            let names = dependents
+                .iter()
+                .map(|dependent| dependent.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if !force {
+                return Err(crate::Error::HasDependents {
+                    service: service.name,
+                    dependents: names,
+                });
+            }
+
+            if !dry_run {
+                warn!(
+                    service = %service.name,
+                    dependents = %names,
+                    "Removing a service that other services depend on"
+                );
+            }
+        }
+
+        let plan = self.plan_removal(&ServiceRef::Id(service.id)).await?;
+        if *dry_run {
+            return Ok(plan);
+        }
 
         let service_id = service.id;
         let config_id = service.config_id;
+        let dependent_ids = dependents
+            .iter()
+            .map(|dependent| dependent.id)
+            .collect::<Vec<_>>();
 
         // First stop if running
-        match self.get_service_state(service_id) {
-            ServiceState::Running | ServiceState::Starting => {
-                self.kill_service_process(service_id).await?;
-            }
-            _ => {}
+        if is_valid_transition(self.get_service_state(service_id), ServiceState::Stopping) {
+            self.kill_service_process(service_id).await?;
         }
 
-        // Get dependencies in one go and collect IDs immediately
-        let dependency_ids: Vec<_> = self
+        // All rows are removed in one transaction so a failure midway doesn't leave orphans behind
+        with_transaction(self.service_repository.connection(), move |txn| {
+            let dependent_ids = dependent_ids.clone();
+
+            Box::pin(async move {
+                // The dependencies of forcibly removed dependents are removed explicitly
+                let dependency_repository = ServiceDependencyRepository::new(txn);
+                for id in dependent_ids {
+                    dependency_repository.delete_by_id(id).await?;
+                }
+
+                // Dependency rows of the service itself are removed by the database through `ON DELETE CASCADE`
+                ServiceRepository::new(txn).delete_by_id(service_id).await?;
+
+                // Handle config deletion if exists
+                if let Some(config_id) = config_id {
+                    ServiceConfigRepository::new(txn)
+                        .delete_by_id(config_id)
+                        .await?;
+                }
+
+                Ok(())
+            })
+        })
+        .await?;
+
+        Ok(plan)
+    }
+
+    #[tracing::instrument]
+    /// Lists what removing the service deletes: the dependency rows in both directions and its
+    /// configuration, along with whether it's stopped first.
+    ///
+    /// Nothing is changed, the plan is read from the database and the running processes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service does not exist or the lookups fail.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let plan = service_manager.plan_removal(&ServiceRef::from_id(42)).await?;
+    /// println!("{} dependencies would be deleted", plan.dependencies_to_delete.len());
+    /// ```
+    async fn plan_removal(&self, payload: &ServiceRef) -> crate::error::Result<RemovalPlan> {
+        let service = self
+            .service_repository
+            .get_by_service_ref(payload)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find service with `{payload}`"))?;
+
+        let dependencies = self
             .dependency_repository
-            .get_by_service_id(service_id)
+            .get_by_service_id(service.id)
+            .await?
+            .into_iter()
+            .map(|dependency| DependencyEdge {
+                service: service.name.clone(),
+                dependent_service: dependency.name,
+                tunnel_enabled: dependency.tunnel_enabled,
+            });
+        let dependents = self
+            .dependency_repository
+            .list_dependents(service.id)
             .await?
             .into_iter()
-            .map(|dep| dep.id)
-            .collect();
+            .map(|dependent| DependencyEdge {
+                service: dependent.name,
+                dependent_service: service.name.clone(),
+                tunnel_enabled: dependent.tunnel_enabled,
+            });
+        let dependencies_to_delete = dependencies.chain(dependents).collect();
+
+        let config_to_delete = match service.config_id {
+            Some(config_id) => self
+                .config_repository
+                .get_by_id(config_id)
+                .await?
+                .map(Into::into),
+            None => None,
+        };
 
-        if !dependency_ids.is_empty() {
-            self.dependency_repository
-                .delete_many(dependency_ids)
-                .await?;
-        }
+        let will_stop =
+            is_valid_transition(self.get_service_state(service.id), ServiceState::Stopping);
 
-        // Then remove from database
-        self.service_repository.delete_by_id(service_id).await?;
+        Ok(RemovalPlan {
+            service_name: service.name,
+            dependencies_to_delete,
+            config_to_delete,
+            will_stop,
+        })
+    }
 
-        // Handle config deletion if exists
-        if let Some(config_id) = config_id {
-            self.config_repository.delete_by_id(config_id).await?;
+    #[tracing::instrument(skip(payload))]
+    /// Applies a service manifest, upserting its services and dependencies in one transaction.
+    ///
+    /// When `prune` is set, the services that are not listed in the manifest are removed and,
+    /// once the transaction is committed, stopped if they are running. A manifest that fails to
+    /// apply leaves every service running.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if applying the manifest fails, in which case no database changes are
+    /// kept. A pruned service that can't be stopped is only logged since its rows are already
+    /// gone.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let payload = ApplyManifestPayload { manifest, prune: false };
+    /// let response = service_manager.apply_manifest(&payload).await?;
+    /// ```
+    async fn apply_manifest(
+        &self,
+        payload: &ApplyManifestPayload,
+    ) -> crate::error::Result<ApplyManifestResponse> {
+        // Ids of the services by name, pruned services can't be looked up once they're removed
+        let ids = if payload.prune {
+            self.service_repository
+                .get_all()
+                .await?
+                .into_iter()
+                .map(|service| (service.name, service.id))
+                .collect::<HashMap<_, _>>()
+        } else {
+            HashMap::new()
+        };
+
+        let response = apply_manifest(
+            self.service_repository.connection(),
+            &payload.manifest,
+            payload.prune,
+        )
+        .await?;
+
+        let pruned = response.actions.iter().filter_map(|action| match action {
+            ManifestAction::RemoveService { name } => ids.get(name).copied(),
+            _ => None,
+        });
+
+        for service_id in pruned {
+            if !is_valid_transition(self.get_service_state(service_id), ServiceState::Stopping) {
+                continue;
+            }
+
+            if let Err(e) = self.kill_service_process(service_id).await {
+                warn!(%service_id, error = %e, "Failed to stop a service pruned by the manifest");
+            }
         }
 
-        Ok(())
+        Ok(response)
     }
 
     #[tracing::instrument]
@@ -422,9 +1052,79 @@ impl ServiceManagement for ServiceManager {
 
         service_status.state = self.get_service_state(service_status.id);
 
+        if let TryResult::Present(process) = self.running_services.try_get(&service_status.id) {
+            if let Some(usage) = process.resources {
+                service_status.cpu_percent = Some(usage.cpu_percent);
+                service_status.memory_bytes = Some(usage.memory_bytes);
+            }
+        }
+
         Ok(service_status)
     }
 
+    #[tracing::instrument]
+    /// Returns the live state of the service, only looking the service up in the database when
+    /// it is not running or referenced by name.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let state = manager.get_state(&ServiceRef::Name("web".to_string())).await?;
+    /// assert_eq!(state, ServiceState::Running);
+    /// ```
+    async fn get_state(&self, payload: &ServiceRef) -> crate::error::Result<ServiceState> {
+        if let ServiceRef::Id(id) = payload {
+            if self.running_services.contains_key(id) {
+                return Ok(self.get_service_state(*id));
+            }
+        }
+
+        let service = self
+            .service_repository
+            .get_by_service_ref(payload)
+            .await?
+            .ok_or_else(|| anyhow!("No Service with reference `{payload}`"))?;
+
+        Ok(self.get_service_state(service.id))
+    }
+
+    #[tracing::instrument]
+    /// Runs the checks of [`validate`](super::validate) against the service, reading its state
+    /// from the database and the running services.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let report = manager.validate(&ServiceRef::Name("web".to_string())).await?;
+    /// for check in report.with_status(CheckStatus::Fail) {
+    ///     println!("{check}");
+    /// }
+    /// ```
+    async fn validate(&self, payload: &ServiceRef) -> crate::error::Result<ValidationReport> {
+        let service = self
+            .service_repository
+            .get_by_service_ref(payload)
+            .await?
+            .ok_or_else(|| anyhow!("No Service with reference `{payload}`"))?;
+
+        let record = self.service_repository.get_detailed_by_id(service.id).await?;
+        let config = record.config.as_ref();
+        let running = matches!(self.get_service_state(service.id), ServiceState::Running);
+
+        let mut checks = vec![
+            check_repo_path(&service),
+            check_port(service.port, running),
+            check_run_command(&service, config),
+        ];
+        checks.extend(check_config_file(&service, config).await);
+        checks.push(
+            check_dependencies(&service, &self.service_repository, &self.dependency_repository)
+                .await?,
+        );
+
+        Ok(ValidationReport { checks })
+    }
+
     #[tracing::instrument]
     /// Retrieves all services along with their dependencies and updates each service's state to reflect its current runtime status.
     ///
@@ -448,6 +1148,163 @@ impl ServiceManagement for ServiceManager {
 
         Ok(services)
     }
+
+    #[tracing::instrument]
+    /// Starts every service in the profile that isn't running yet, in the order they were added.
+    ///
+    /// Every service is attempted, the error lists the ones that failed to start.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let payload = ProfilePayload {
+    ///     profile: "backend".to_string(),
+    /// };
+    /// service_manager.start_profile(&payload).await?;
+    /// ```
+    async fn start_profile(&self, payload: &ProfilePayload) -> crate::error::Result<()> {
+        let profile = &payload.profile;
+        let mut failures = Vec::new();
+
+        for service in self.profile_services(profile).await? {
+            let state = self.get_service_state(service.id);
+            if !is_valid_transition(state, ServiceState::Starting) {
+                debug!(%profile, service = %service.name, %state, "Service can't be started");
+                continue;
+            }
+
+            let start = StartServicePayload {
+                service: ServiceRef::Id(service.id),
+                ..Default::default()
+            };
+
+            if let Err(e) = self.start(&start).await {
+                warn!(%profile, service = %service.name, error = %e, "Failed to start service");
+                failures.push(format!("{}: {e}", service.name));
+            }
+        }
+
+        profile_result(profile, "start", failures)
+    }
+
+    #[tracing::instrument]
+    /// Stops every running service in the profile, in the reverse order they were added.
+    ///
+    /// Every service is attempted, the error lists the ones that failed to stop.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let payload = ProfilePayload {
+    ///     profile: "backend".to_string(),
+    /// };
+    /// service_manager.stop_profile(&payload).await?;
+    /// ```
+    async fn stop_profile(&self, payload: &ProfilePayload) -> crate::error::Result<()> {
+        let profile = &payload.profile;
+        let mut failures = Vec::new();
+
+        for service in self.profile_services(profile).await?.into_iter().rev() {
+            if !is_valid_transition(self.get_service_state(service.id), ServiceState::Stopping) {
+                continue;
+            }
+
+            if let Err(e) = self.stop(&ServiceRef::Id(service.id)).await {
+                warn!(%profile, service = %service.name, error = %e, "Failed to stop service");
+                failures.push(format!("{}: {e}", service.name));
+            }
+        }
+
+        profile_result(profile, "stop", failures)
+    }
+
+    #[tracing::instrument]
+    /// Retrieves the services in the profile with their current runtime state.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let payload = ProfilePayload {
+    ///     profile: "backend".to_string(),
+    /// };
+    /// let response = service_manager.list_by_profile(&payload).await?;
+    /// ```
+    async fn list_by_profile(
+        &self,
+        payload: &ProfilePayload,
+    ) -> crate::error::Result<ListServicesResponse> {
+        let mut services = self
+            .service_repository
+            .get_profile_with_dependencies(&payload.profile)
+            .await?;
+
+        services.services.par_iter_mut().for_each(|service| {
+            service.state = self.get_service_state(service.id);
+        });
+
+        Ok(services)
+    }
+
+    #[tracing::instrument]
+    /// Retrieves the services carrying every label of the selector with their current runtime
+    /// state.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let payload = LabelSelectorPayload {
+    ///     labels: BTreeMap::from([("team".to_string(), "payments".to_string())]),
+    /// };
+    /// let response = service_manager.list_by_labels(&payload).await?;
+    /// ```
+    async fn list_by_labels(
+        &self,
+        payload: &LabelSelectorPayload,
+    ) -> crate::error::Result<ListServicesResponse> {
+        let mut services = self
+            .service_repository
+            .get_labeled_with_dependencies(&payload.labels)
+            .await?;
+
+        services.services.par_iter_mut().for_each(|service| {
+            service.state = self.get_service_state(service.id);
+        });
+
+        Ok(services)
+    }
+
+    #[tracing::instrument]
+    /// Searches the services for the query with their current runtime state, an empty query is
+    /// an error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let payload = SearchServicesPayload {
+    ///     query: "api".to_string(),
+    /// };
+    /// let response = service_manager.search_services(&payload).await?;
+    /// ```
+    async fn search_services(
+        &self,
+        payload: &SearchServicesPayload,
+    ) -> crate::error::Result<ListServicesResponse> {
+        let query = payload.query.trim();
+        if query.is_empty() {
+            return Err(anyhow!("The search query must not be empty").into());
+        }
+
+        let mut services = self
+            .service_repository
+            .search_with_dependencies(query)
+            .await?;
+
+        services.services.par_iter_mut().for_each(|service| {
+            service.state = self.get_service_state(service.id);
+        });
+
+        Ok(services)
+    }
 }
 
 #[cfg(feature = "git")]
@@ -0,0 +1,60 @@
+//! The processes in the process group of a service, read from `/proc`.
+//!
+//! Processes a service forked stay in its process group after the service itself exited and can
+//! keep its port bound. Listing the group is only supported on Linux.
+
+use super::resources::ProcStat;
+
+/// Returns the processes in process group `pgid` that are still running, `None` where the group
+/// can't be listed.
+///
+/// Exited processes nobody reaped yet are left out, they hold no resources besides their pid.
+pub(crate) fn live_members(pgid: u32) -> Option<Vec<u32>> {
+    let members = members(pgid)?
+        .into_iter()
+        .filter(|stat| stat.state != 'Z')
+        .map(|stat| stat.pid)
+        .collect();
+
+    Some(members)
+}
+
+/// Returns every process in process group `pgid`, `None` where the group can't be listed.
+#[cfg(target_os = "linux")]
+pub(crate) fn members(pgid: u32) -> Option<Vec<ProcStat>> {
+    use super::resources::parse_stat;
+    use std::fs;
+
+    let mut members = Vec::new();
+
+    for entry in fs::read_dir("/proc").ok()?.flatten() {
+        let path = entry.path();
+        let is_pid = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.bytes().all(|byte| byte.is_ascii_digit()));
+
+        if !is_pid {
+            continue;
+        }
+
+        // Processes can exit while the group is walked
+        let Some(stat) = fs::read_to_string(path.join("stat"))
+            .ok()
+            .and_then(|line| parse_stat(&line))
+        else {
+            continue;
+        };
+
+        if stat.pgrp == pgid {
+            members.push(stat);
+        }
+    }
+
+    Some(members)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn members(_pgid: u32) -> Option<Vec<ProcStat>> {
+    None
+}
@@ -0,0 +1,148 @@
+//! Waiting for a started service to become ready.
+//!
+//! A start with a [`ReadyCondition`] only returns once the condition holds, the process exits or
+//! the timeout runs out. The condition is checked every [`READY_POLL_INTERVAL`].
+
+use super::{ServiceProcess, StartupFailure};
+use anyhow::Context as _;
+use nexsock_protocol::commands::manage_service::ReadyCondition;
+use regex::Regex;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use tokio::time::{sleep, Instant};
+
+/// How often the ready condition is checked.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A [`ReadyCondition`] resolved against the service it is checked for.
+#[derive(Debug, Clone)]
+pub(crate) enum ReadyCheck {
+    /// Connect to `127.0.0.1` on this port.
+    Port(u16),
+    /// Match the lines written by the process.
+    LogMatch(Regex),
+    /// The process has been running this long.
+    Duration(Duration),
+}
+
+/// How waiting for a service to become ready ended.
+#[derive(Debug)]
+pub(crate) enum Readiness {
+    Ready,
+    /// The process exited before it was ready.
+    Exited(StartupFailure),
+    TimedOut,
+}
+
+impl ReadyCheck {
+    /// Resolves `condition` for a service listening on `port`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the regex of a [`ReadyCondition::LogMatch`] is invalid.
+    pub(crate) fn new(condition: &ReadyCondition, port: u16) -> crate::error::Result<Self> {
+        Ok(match condition {
+            ReadyCondition::Port => Self::Port(port),
+            ReadyCondition::LogMatch(pattern) => {
+                let regex = Regex::new(pattern)
+                    .with_context(|| format!("Invalid ready pattern `{pattern}`"))?;
+
+                Self::LogMatch(regex)
+            }
+            ReadyCondition::Duration(millis) => Self::Duration(Duration::from_millis(*millis)),
+        })
+    }
+}
+
+/// Waits up to `timeout` for `check` to hold for `process`.
+///
+/// `output` has to be subscribed to [`ServiceProcess::output`] before the process had the chance
+/// to write anything past what is kept in its buffers, the buffered lines are matched first.
+/// `started` is when the process was spawned.
+///
+/// # Errors
+///
+/// Returns an error if the system call to check the process status fails.
+pub(crate) async fn wait_until_ready(
+    process: &mut ServiceProcess,
+    check: &ReadyCheck,
+    mut output: broadcast::Receiver<String>,
+    started: Instant,
+    timeout: Duration,
+) -> crate::error::Result<Readiness> {
+    let deadline = Instant::now() + timeout;
+
+    if let ReadyCheck::LogMatch(regex) = check {
+        if matches_buffered_output(process, regex).await {
+            return Ok(Readiness::Ready);
+        }
+    }
+
+    loop {
+        if let Some(failure) = process.wait_for_startup(Duration::ZERO).await? {
+            return Ok(Readiness::Exited(failure));
+        }
+
+        let ready = match check {
+            ReadyCheck::Port(port) => is_listening(*port).await,
+            ReadyCheck::LogMatch(regex) => matches_new_output(&mut output, regex),
+            ReadyCheck::Duration(duration) => started.elapsed() >= *duration,
+        };
+
+        if ready {
+            return Ok(Readiness::Ready);
+        }
+
+        if Instant::now() >= deadline {
+            return Ok(Readiness::TimedOut);
+        }
+
+        sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// Waits up to `timeout` for something to listen on `port`, `false` if nothing did in time.
+///
+/// Used for services that aren't started by this wait, such as the dependencies of a service.
+pub(crate) async fn wait_for_port(port: u16, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if is_listening(port).await {
+            return true;
+        }
+
+        if Instant::now() >= deadline {
+            return false;
+        }
+
+        sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+async fn is_listening(port: u16) -> bool {
+    TcpStream::connect(("127.0.0.1", port)).await.is_ok()
+}
+
+/// Matches the lines the process wrote before `output` was subscribed.
+async fn matches_buffered_output(process: &ServiceProcess, regex: &Regex) -> bool {
+    let stdout = process.stdout_logs.lock().await;
+    if stdout.iter().any(|entry| regex.is_match(entry.content.trim_end())) {
+        return true;
+    }
+
+    let stderr = process.startup_stderr.lock().await;
+    stderr.iter().any(|line| regex.is_match(line.trim_end()))
+}
+
+/// Matches the lines received on `output` since the last check.
+fn matches_new_output(output: &mut broadcast::Receiver<String>, regex: &Regex) -> bool {
+    loop {
+        match output.try_recv() {
+            Ok(line) if regex.is_match(line.trim_end()) => return true,
+            Ok(_) | Err(TryRecvError::Lagged(_)) => continue,
+            Err(TryRecvError::Empty | TryRecvError::Closed) => return false,
+        }
+    }
+}
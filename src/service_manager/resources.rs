@@ -0,0 +1,163 @@
+//! CPU and memory usage of running services, read from `/proc`.
+//!
+//! The usage of a service covers every process in its process group. CPU usage is the share of
+//! one core used between two samples, so a busy multithreaded service can report over 100%.
+//! Sampling is only supported on Linux, elsewhere the usage stays unknown.
+
+use super::ServiceProcess;
+use std::time::{Duration, Instant};
+
+/// How often the daemon samples the resources of running services.
+pub(crate) const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Clock ticks per second the kernel reports process times in, `USER_HZ` is 100 on every
+/// architecture Linux exposes it for.
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// The resources a service used over the last sampling interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ResourceUsage {
+    /// Share of one core used, in percent.
+    pub(crate) cpu_percent: f32,
+    /// Resident memory of the process group in bytes.
+    pub(crate) memory_bytes: u64,
+}
+
+/// The CPU time a process group had used at a point in time.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResourceSample {
+    /// User and system time of the processes in clock ticks.
+    pub(crate) cpu_ticks: u64,
+    pub(crate) taken_at: Instant,
+}
+
+/// The fields of a `/proc/<pid>/stat` line used for sampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProcStat {
+    pub(crate) pid: u32,
+    /// The state of the process, `Z` once it exited but wasn't reaped yet.
+    pub(crate) state: char,
+    /// The process group of the process.
+    pub(crate) pgrp: u32,
+    /// Time spent in user mode, in clock ticks.
+    pub(crate) utime: u64,
+    /// Time spent in kernel mode, in clock ticks.
+    pub(crate) stime: u64,
+}
+
+/// Parses a `/proc/<pid>/stat` line.
+///
+/// The command name is wrapped in parentheses and may itself hold spaces and parentheses, the
+/// fields are read from after its last `)`.
+pub(crate) fn parse_stat(line: &str) -> Option<ProcStat> {
+    let (pid, rest) = line.split_once(" (")?;
+    let (_, fields) = rest.rsplit_once(") ")?;
+
+    // `fields` starts at the state, the third field of the line
+    let fields = fields.split_whitespace().collect::<Vec<_>>();
+    let field = |number: usize| fields.get(number - 3).copied();
+
+    Some(ProcStat {
+        pid: pid.trim().parse().ok()?,
+        state: field(3)?.chars().next()?,
+        pgrp: field(5)?.parse().ok()?,
+        utime: field(14)?.parse().ok()?,
+        stime: field(15)?.parse().ok()?,
+    })
+}
+
+/// Parses the resident set size in pages out of a `/proc/<pid>/statm` line.
+pub(crate) fn parse_statm_resident_pages(line: &str) -> Option<u64> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Computes the CPU usage between `previous` and `current`.
+pub(crate) fn cpu_percent(previous: ResourceSample, current: ResourceSample) -> f32 {
+    let elapsed = current.taken_at.duration_since(previous.taken_at).as_secs_f64();
+    if elapsed <= 0.0 {
+        return 0.0;
+    }
+
+    let ticks = current.cpu_ticks.saturating_sub(previous.cpu_ticks) as f64;
+
+    (ticks / CLOCK_TICKS_PER_SECOND / elapsed * 100.0) as f32
+}
+
+impl ServiceProcess {
+    /// Samples the resources of the process group, updating [`ServiceProcess::resources`] once
+    /// there is a previous sample to compare against.
+    pub(crate) fn sample_resources(&mut self) {
+        let Some(pgid) = self.process.id() else {
+            return;
+        };
+
+        let Some((cpu_ticks, memory_bytes)) = sys::sample_group(pgid) else {
+            self.resources = None;
+            return;
+        };
+
+        let sample = ResourceSample {
+            cpu_ticks,
+            taken_at: Instant::now(),
+        };
+
+        if let Some(previous) = self.resource_sample.replace(sample) {
+            self.resources = Some(ResourceUsage {
+                cpu_percent: cpu_percent(previous, sample),
+                memory_bytes,
+            });
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use crate::service_manager::process_group::members;
+    use super::parse_statm_resident_pages;
+    use std::fs;
+    use std::sync::LazyLock;
+
+    /// Used when the page size can't be read.
+    const DEFAULT_PAGE_SIZE: u64 = 4096;
+
+    static PAGE_SIZE: LazyLock<u64> = LazyLock::new(|| {
+        // The first mapping of the daemon is backed by pages of the base size
+        fs::read_to_string("/proc/self/smaps")
+            .ok()
+            .and_then(|smaps| {
+                smaps
+                    .lines()
+                    .find_map(|line| line.strip_prefix("KernelPageSize:"))
+                    .and_then(|size| size.trim().trim_end_matches("kB").trim().parse().ok())
+            })
+            .map_or(DEFAULT_PAGE_SIZE, |kilobytes: u64| kilobytes * 1024)
+    });
+
+    /// Sums the CPU ticks and resident memory of the processes in process group `pgid`.
+    pub(super) fn sample_group(pgid: u32) -> Option<(u64, u64)> {
+        let members = members(pgid)?;
+        if members.is_empty() {
+            return None;
+        }
+
+        let mut cpu_ticks = 0;
+        let mut resident_pages = 0;
+
+        for stat in members {
+            cpu_ticks += stat.utime + stat.stime;
+            resident_pages += fs::read_to_string(format!("/proc/{}/statm", stat.pid))
+                .ok()
+                .and_then(|line| parse_statm_resident_pages(&line))
+                .unwrap_or(0);
+        }
+
+        Some((cpu_ticks, resident_pages * *PAGE_SIZE))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    pub(super) fn sample_group(_pgid: u32) -> Option<(u64, u64)> {
+        None
+    }
+}
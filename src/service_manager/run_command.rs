@@ -0,0 +1,115 @@
+//! Placeholder substitution for service run commands.
+//!
+//! A run command may refer to the service it starts with `{port}`, `{repo_path}` and `{name}`,
+//! which are replaced right before the process is spawned. Braces that don't hold a bare
+//! identifier are left alone so shell syntax such as `${PORT}`, `{a,b}` or `awk '{print $1}'`
+//! keeps working, `{{` and `}}` write a literal brace.
+//!
+//! Substituted values are quoted as a single shell word when they contain anything but plain
+//! path characters, a placeholder never adds shell syntax of its own and shouldn't be quoted.
+
+use anyhow::anyhow;
+
+/// The placeholders a run command may use.
+const PLACEHOLDERS: [&str; 3] = ["port", "repo_path", "name"];
+
+/// The values substituted into a run command.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RunCommandVars<'a> {
+    /// Substituted for `{port}`.
+    pub(crate) port: u16,
+    /// Substituted for `{repo_path}`.
+    pub(crate) repo_path: &'a str,
+    /// Substituted for `{name}`.
+    pub(crate) name: &'a str,
+}
+
+impl RunCommandVars<'_> {
+    fn get(&self, placeholder: &str) -> Option<String> {
+        match placeholder {
+            "port" => Some(self.port.to_string()),
+            "repo_path" => Some(shell_quote(self.repo_path)),
+            "name" => Some(shell_quote(self.name)),
+            _ => None,
+        }
+    }
+}
+
+/// Replaces the placeholders in `template` with `vars`.
+///
+/// # Errors
+///
+/// Returns an error if `template` uses a placeholder other than `{port}`, `{repo_path}` or
+/// `{name}`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let vars = RunCommandVars { port: 8080, repo_path: "/srv/web", name: "web" };
+/// let command = render_run_command("./serve --port {port}", &vars)?;
+/// assert_eq!(command, "./serve --port 8080");
+/// ```
+pub(crate) fn render_run_command(
+    template: &str,
+    vars: &RunCommandVars<'_>,
+) -> crate::error::Result<String> {
+    let mut command = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        let (before, from_brace) = rest.split_at(index);
+        command.push_str(before);
+
+        // `{{` and `}}` are escaped braces
+        if from_brace.starts_with("{{") || from_brace.starts_with("}}") {
+            command.push_str(&from_brace[..1]);
+            rest = &from_brace[2..];
+            continue;
+        }
+
+        let placeholder = from_brace
+            .strip_prefix('{')
+            .and_then(|inner| inner.split_once('}'))
+            .map(|(inner, _)| inner)
+            .filter(|inner| !before.ends_with('$') && is_identifier(inner));
+
+        let Some(placeholder) = placeholder else {
+            command.push_str(&from_brace[..1]);
+            rest = &from_brace[1..];
+            continue;
+        };
+
+        let value = vars.get(placeholder).ok_or_else(|| {
+            anyhow!(
+                "Unknown placeholder `{{{placeholder}}}` in run command, expected one of {}",
+                PLACEHOLDERS.map(|known| format!("`{{{known}}}`")).join(", ")
+            )
+        })?;
+
+        command.push_str(&value);
+        rest = &from_brace[placeholder.len() + 2..];
+    }
+
+    command.push_str(rest);
+
+    Ok(command)
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Quotes `value` as a single shell word unless it only holds characters the shell leaves alone.
+fn shell_quote(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c));
+
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
@@ -0,0 +1,52 @@
+//! The changes of [`ServiceState`] a service may go through.
+//!
+//! A service goes from `Stopped` through `Starting` to `Running` and back through `Stopping`,
+//! and can fail in any state it has a process in. Anything else, such as starting a service that
+//! is already running, is rejected with [`Error::InvalidStateTransition`].
+
+use crate::error::Error;
+use crate::statics::METRICS;
+use nexsock_protocol::commands::service_status::ServiceState;
+use tracing::warn;
+
+/// Whether a service in the `from` state may move to `to`.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert!(is_valid_transition(ServiceState::Stopped, ServiceState::Starting));
+/// assert!(!is_valid_transition(ServiceState::Running, ServiceState::Starting));
+/// ```
+pub(crate) fn is_valid_transition(from: ServiceState, to: ServiceState) -> bool {
+    use ServiceState::*;
+
+    matches!(
+        (from, to),
+        (Stopped | Failed, Starting | Running)
+            | (Starting, Running | Stopping | Stopped | Failed)
+            | (Running, Stopping | Stopped | Failed)
+            | (Stopping, Stopped | Failed)
+            | (Failed, Stopped)
+    )
+}
+
+/// Checks that the service `service_id` may move from `from` to `to`.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidStateTransition`] if the transition isn't allowed, it's logged and
+/// counted in the daemon metrics.
+pub(crate) fn validate_transition(
+    service_id: i64,
+    from: ServiceState,
+    to: ServiceState,
+) -> crate::error::Result<()> {
+    if is_valid_transition(from, to) {
+        return Ok(());
+    }
+
+    warn!(service_id, %from, %to, "Rejected invalid service state transition");
+    METRICS.record_invalid_transition();
+
+    Err(Error::InvalidStateTransition { from, to })
+}
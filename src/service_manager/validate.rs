@@ -0,0 +1,352 @@
+//! The checks run when validating a service, see
+//! [`ServiceManagement::validate`](crate::traits::service_management::ServiceManagement::validate).
+//!
+//! Every check only reads, the repository isn't cloned and the service isn't started, so a
+//! service can be validated at any time. Each check reports on its own and a failing check never
+//! keeps the others from running.
+
+use super::run_command::{render_run_command, RunCommandVars};
+use nexsock_db::prelude::{
+    ManagedConnection, Service, ServiceConfig, ServiceDependencyRepository, ServiceRepository,
+};
+use nexsock_protocol::commands::config::ConfigFormat;
+use nexsock_protocol::commands::validate::{CheckResult, CheckStatus};
+use port_selector::is_free_tcp;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+/// Checks that the repository of the service is checked out, or can be cloned.
+pub(crate) fn check_repo_path(service: &Service) -> CheckResult {
+    const CHECK: &str = "repo_path";
+
+    if Path::new(&service.repo_path).is_dir() {
+        return CheckResult::new(
+            CHECK,
+            CheckStatus::Pass,
+            format!("`{}` exists", service.repo_path),
+        );
+    }
+
+    if service.repo_url.is_empty() {
+        return CheckResult::new(
+            CHECK,
+            CheckStatus::Fail,
+            format!(
+                "`{}` does not exist and the service has no repository URL",
+                service.repo_path
+            ),
+        );
+    }
+
+    if cfg!(feature = "git") {
+        CheckResult::new(
+            CHECK,
+            CheckStatus::Warn,
+            format!(
+                "`{}` does not exist, start the service with `--clone` to clone `{}`",
+                service.repo_path, service.repo_url
+            ),
+        )
+    } else {
+        CheckResult::new(
+            CHECK,
+            CheckStatus::Fail,
+            format!(
+                "`{}` does not exist and the daemon was built without git support to clone it",
+                service.repo_path
+            ),
+        )
+    }
+}
+
+/// Checks that the port of the service is valid and free, `running` services hold their own
+/// port.
+pub(crate) fn check_port(port: i64, running: bool) -> CheckResult {
+    const CHECK: &str = "port";
+
+    // Port 0 would let the OS pick any port, which no client could find
+    let port = match u16::try_from(port) {
+        Ok(port) if port != 0 => port,
+        _ => {
+            return CheckResult::new(
+                CHECK,
+                CheckStatus::Fail,
+                format!("{port} is not a valid port"),
+            )
+        }
+    };
+
+    if running {
+        CheckResult::new(
+            CHECK,
+            CheckStatus::Pass,
+            format!("{port} is used by the running service"),
+        )
+    } else if is_free_tcp(port) {
+        CheckResult::new(CHECK, CheckStatus::Pass, format!("{port} is free"))
+    } else {
+        CheckResult::new(CHECK, CheckStatus::Fail, format!("{port} is already in use"))
+    }
+}
+
+/// Checks that the service has a run command that renders.
+pub(crate) fn check_run_command(service: &Service, config: Option<&ServiceConfig>) -> CheckResult {
+    const CHECK: &str = "run_command";
+
+    let Some(config) = config else {
+        return CheckResult::new(CHECK, CheckStatus::Fail, "the service has no configuration");
+    };
+
+    let Some(run_command) = config
+        .run_command
+        .as_deref()
+        .filter(|command| !command.trim().is_empty())
+    else {
+        return CheckResult::new(CHECK, CheckStatus::Fail, "the service has no run command");
+    };
+
+    let vars = RunCommandVars {
+        port: service.port as u16,
+        repo_path: &service.repo_path,
+        name: &service.name,
+    };
+
+    match render_run_command(run_command, &vars) {
+        Ok(command) => CheckResult::new(CHECK, CheckStatus::Pass, format!("`{command}`")),
+        Err(e) => CheckResult::new(CHECK, CheckStatus::Fail, e.to_string()),
+    }
+}
+
+/// Checks that the config file of the service parses in its format, relative file names are
+/// looked up in the repository of the service.
+///
+/// Returns `None` when the service has no config file.
+pub(crate) async fn check_config_file(
+    service: &Service,
+    config: Option<&ServiceConfig>,
+) -> Option<CheckResult> {
+    const CHECK: &str = "config_file";
+
+    let config = config.filter(|config| !config.filename.is_empty())?;
+    let path = Path::new(&service.repo_path).join(&config.filename);
+
+    let result = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => match parse_config_file(&contents, config.format) {
+            Ok(entries) => CheckResult::new(
+                CHECK,
+                CheckStatus::Pass,
+                format!("`{}` holds {entries} entries", path.display()),
+            ),
+            Err(e) => CheckResult::new(
+                CHECK,
+                CheckStatus::Fail,
+                format!("`{}` is not a valid {} file: {e}", path.display(), config.format),
+            ),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => CheckResult::new(
+            CHECK,
+            CheckStatus::Warn,
+            format!("`{}` does not exist", path.display()),
+        ),
+        Err(e) => CheckResult::new(
+            CHECK,
+            CheckStatus::Fail,
+            format!("`{}` can't be read: {e}", path.display()),
+        ),
+    };
+
+    Some(result)
+}
+
+/// Checks that the dependencies of the service, and theirs, don't form a cycle and can be
+/// started.
+///
+/// # Errors
+///
+/// Returns an error if the dependencies can't be read from the database.
+pub(crate) async fn check_dependencies(
+    service: &Service,
+    services: &ServiceRepository<'_, ManagedConnection>,
+    dependencies: &ServiceDependencyRepository<'_, ManagedConnection>,
+) -> crate::error::Result<CheckResult> {
+    const CHECK: &str = "dependencies";
+
+    let mut names = HashMap::from([(service.id, service.name.clone())]);
+    let mut graph = HashMap::new();
+    let mut queue = VecDeque::from([service.id]);
+
+    while let Some(id) = queue.pop_front() {
+        if graph.contains_key(&id) {
+            continue;
+        }
+
+        let edges = dependencies.get_by_service_id(id).await?;
+        let targets = edges
+            .into_iter()
+            .map(|dependency| {
+                names.insert(dependency.dependent_service_id, dependency.name);
+                queue.push_back(dependency.dependent_service_id);
+                dependency.dependent_service_id
+            })
+            .collect::<Vec<_>>();
+
+        graph.insert(id, targets);
+    }
+
+    if let Some(cycle) = find_cycle(&graph, service.id) {
+        let cycle = cycle
+            .iter()
+            .map(|id| format!("`{}`", names[id]))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        return Ok(CheckResult::new(
+            CHECK,
+            CheckStatus::Fail,
+            format!("the dependencies form a cycle: {cycle}"),
+        ));
+    }
+
+    let mut not_startable = Vec::new();
+    for &id in graph.keys().filter(|&&id| id != service.id) {
+        let record = services.get_detailed_by_id(id).await?;
+        let has_run_command = record
+            .config
+            .and_then(|config| config.run_command)
+            .is_some_and(|command| !command.trim().is_empty());
+
+        if !has_run_command {
+            not_startable.push(format!("`{}`", names[&id]));
+        }
+    }
+    not_startable.sort();
+
+    let count = graph.len() - 1;
+    let result = if !not_startable.is_empty() {
+        CheckResult::new(
+            CHECK,
+            CheckStatus::Warn,
+            format!("{} have no run command", not_startable.join(", ")),
+        )
+    } else if count == 0 {
+        CheckResult::new(CHECK, CheckStatus::Pass, "the service has no dependencies")
+    } else {
+        CheckResult::new(
+            CHECK,
+            CheckStatus::Pass,
+            format!("every dependency can be started ({count} in total)"),
+        )
+    };
+
+    Ok(result)
+}
+
+/// Returns the services of a cycle reachable from `start`, the first service repeated at the end.
+fn find_cycle(graph: &HashMap<i64, Vec<i64>>, start: i64) -> Option<Vec<i64>> {
+    fn visit(
+        graph: &HashMap<i64, Vec<i64>>,
+        id: i64,
+        path: &mut Vec<i64>,
+        done: &mut Vec<i64>,
+    ) -> Option<Vec<i64>> {
+        if let Some(position) = path.iter().position(|&visited| visited == id) {
+            let mut cycle = path[position..].to_vec();
+            cycle.push(id);
+            return Some(cycle);
+        }
+
+        if done.contains(&id) {
+            return None;
+        }
+
+        path.push(id);
+        for &next in graph.get(&id).into_iter().flatten() {
+            if let Some(cycle) = visit(graph, next, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.push(id);
+
+        None
+    }
+
+    visit(graph, start, &mut Vec::new(), &mut Vec::new())
+}
+
+/// Parses a config file, returning the number of entries it holds or the first problem found.
+///
+/// `Env` files hold `KEY=value` lines, optionally prefixed with `export`. `Properties` files
+/// separate keys from values with `=`, `:` or whitespace and continue values ending in `\` on the
+/// next line. Both skip blank lines and comments.
+pub(crate) fn parse_config_file(contents: &str, format: ConfigFormat) -> Result<usize, String> {
+    let mut entries = 0;
+    let mut continued = false;
+
+    for (index, line) in contents.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+
+        if continued {
+            continued = format == ConfigFormat::Properties && ends_with_continuation(line);
+            continue;
+        }
+
+        match format {
+            ConfigFormat::Env => {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| format!("line {number} is not a `KEY=value` pair"))?;
+
+                if !is_env_name(key) {
+                    return Err(format!("line {number} has the invalid name `{key}`"));
+                }
+
+                if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') {
+                    if value.len() < 2 || !value.ends_with(quote) {
+                        return Err(format!("line {number} has an unterminated {quote} quote"));
+                    }
+                }
+            }
+            ConfigFormat::Properties => {
+                if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                    continue;
+                }
+
+                let key = line
+                    .split(|c: char| c == '=' || c == ':' || c.is_whitespace())
+                    .next()
+                    .unwrap_or_default();
+
+                if key.is_empty() {
+                    return Err(format!("line {number} has no key"));
+                }
+
+                continued = ends_with_continuation(line);
+            }
+        }
+
+        entries += 1;
+    }
+
+    Ok(entries)
+}
+
+fn is_env_name(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether a properties line ends in an odd number of backslashes, continuing it.
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
@@ -9,10 +9,11 @@
 
 use crate::config_manager::new::ConfigManager;
 use crate::dependency_manager::new::DependencyManager;
+use crate::metrics::Metrics;
 use crate::service_manager::new::ServiceManager;
 use futures::executor::block_on;
-use nexsock_abi::PreHooks;
-use nexsock_db::prelude::ServiceRepository;
+use nexsock_abi::{PreHooks, ABI_VERSION};
+use nexsock_db::prelude::{ManagedConnection, ServiceRepository};
 use nexsock_plugins::native::external_native_plugins;
 use std::sync::LazyLock;
 
@@ -20,7 +21,8 @@ use std::sync::LazyLock;
 ///
 /// Provides thread-safe access to service CRUD operations and queries.
 /// Initialized lazily on first access.
-pub static SERVICE_REPOSITORY: LazyLock<ServiceRepository> = ServiceRepository::new_const();
+pub static SERVICE_REPOSITORY: LazyLock<ServiceRepository<'static, ManagedConnection>> =
+    ServiceRepository::new_const();
 
 /// Global service manager for lifecycle operations (start, stop, restart).
 ///
@@ -40,14 +42,21 @@ pub static CONFIG_MANAGER: LazyLock<ConfigManager> = ConfigManager::new_const();
 /// and resolution. Thread-safe for concurrent access.
 pub static DEPENDENCY_MANAGER: LazyLock<DependencyManager> = DependencyManager::new_const();
 
+/// Global counters of the commands handled and the services started and stopped.
+///
+/// Updated by client connections and the service manager, exported with
+/// [`Command::ExportMetrics`](nexsock_protocol::commands::Command::ExportMetrics).
+pub static METRICS: Metrics = Metrics::new();
+
 /// Pre-hook plugins loaded from external native plugin sources.
 ///
 /// These plugins are executed before various daemon operations to provide
-/// extensibility. Loaded synchronously during daemon startup using `block_on`.
+/// extensibility. Loaded synchronously during daemon startup using `block_on`, plugins built
+/// against another [`ABI_VERSION`] are skipped.
 ///
 /// # Panics
 ///
 /// Panics during daemon startup if external native plugins fail to load.
 pub static PRE_HOOKS: LazyLock<PreHooks> = LazyLock::new(|| {
-    block_on(external_native_plugins()).expect("Failed to load external native plugins")
+    block_on(external_native_plugins(ABI_VERSION)).expect("Failed to load external native plugins")
 });
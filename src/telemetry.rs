@@ -0,0 +1,68 @@
+//! # OpenTelemetry Export
+//!
+//! When the daemon is built with the `otel` feature and `server.otlp_endpoint` is set, the spans
+//! of the daemon are exported to an OTLP collector next to the stdout logs. This covers the
+//! spans of the handled commands, the git operations and the spawned services.
+//!
+//! Spans are exported in batches from a background thread, [`shutdown`] flushes the ones still
+//! pending before the daemon exits.
+
+use anyhow::Context;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use std::sync::OnceLock;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// The service name the spans are exported under.
+const SERVICE_NAME: &str = "nexsockd";
+
+/// The provider of the exporting layer, kept to flush it on shutdown.
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// Creates a provider exporting spans in batches to the OTLP/HTTP `endpoint`.
+///
+/// # Errors
+///
+/// Returns an error if the exporter can't be created, e.g. when `endpoint` is not a valid URL.
+pub(crate) fn otlp_tracer_provider(endpoint: &str) -> anyhow::Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .with_context(|| format!("failed to create the OTLP exporter for `{endpoint}`"))?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build())
+}
+
+/// Creates a layer turning the spans of `S` into OpenTelemetry spans of `provider`.
+pub(crate) fn layer<S>(provider: &SdkTracerProvider) -> OpenTelemetryLayer<S, SdkTracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+}
+
+/// Keeps `provider` to flush it in [`shutdown`], only the first installed provider is kept.
+pub(crate) fn install(provider: SdkTracerProvider) {
+    if TRACER_PROVIDER.set(provider).is_err() {
+        tracing::warn!("An OTLP exporter is already installed");
+    }
+}
+
+/// Exports the spans that are still pending and stops the exporter.
+///
+/// Does nothing when no exporter was installed.
+pub fn shutdown() {
+    if let Some(provider) = TRACER_PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to flush the pending spans: {e}");
+        }
+    }
+}
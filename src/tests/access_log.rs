@@ -0,0 +1,75 @@
+use super::common::*;
+use crate::daemon::access_log::AccessLog;
+use crate::daemon::Connection;
+use anyhow::Result;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::header::MessageFlags;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{duplex, split};
+
+#[tokio::test]
+async fn test_start_command_is_access_logged() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let log_path = env.test_env.temp_dir.path().join("logs/access.log");
+
+    let (mut client, server) = duplex(8 * 1024);
+    let (reader, writer) = split(server);
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+    let access_log = AccessLog::open(&log_path)?;
+
+    let mut connection = Connection::from_parts(
+        reader,
+        writer,
+        lua_plugin_manager,
+        KeepaliveConfig::disabled(),
+    )
+    .with_access_log(Some(access_log));
+    let handle = tokio::spawn(async move { connection.handle().await });
+
+    let mut protocol = Protocol::default();
+    let request_id = protocol.next_request_id();
+    let payload = StartServicePayload {
+        service: ServiceRef::Name("access-logged".to_string()),
+        env_vars: HashMap::new(),
+        ..Default::default()
+    };
+    protocol
+        .write_command_with_payload(
+            &mut client,
+            Command::StartService,
+            &payload,
+            MessageFlags::HAS_PAYLOAD,
+        )
+        .await?;
+
+    // The service does not exist, so the daemon answers with an error
+    let (header, _) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Error));
+
+    // Heartbeats are not commands and must not show up in the log
+    protocol.write_heartbeat(&mut client).await?;
+    protocol.read_message(&mut client).await?;
+
+    drop(client);
+    handle.await??;
+
+    let contents = std::fs::read_to_string(&log_path)?;
+    let lines = contents.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "Expected one entry, got {contents:?}");
+
+    let entry: serde_json::Value = serde_json::from_str(lines[0])?;
+    assert_eq!(entry["command"], "StartService");
+    assert_eq!(entry["service"], "access-logged");
+    assert_eq!(entry["request_id"], request_id);
+    assert_eq!(entry["outcome"], "error");
+    assert!(entry["error"].as_str().is_some_and(|e| !e.is_empty()));
+    assert!(entry["duration_ms"].as_f64().is_some_and(|ms| ms >= 0.0));
+    assert!(chrono::DateTime::parse_from_rfc3339(entry["timestamp"].as_str().unwrap()).is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::config::ServiceConfigPayload;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StopSignal};
+use std::collections::BTreeMap;
+
+#[test]
+fn test_builder_with_required_fields_only() {
+    let payload =
+        AddServicePayload::builder("api", "https://github.com/test/api.git", 8080, "/srv/api")
+            .build();
+
+    assert_eq!(payload.name, "api");
+    assert_eq!(payload.repo_url, "https://github.com/test/api.git");
+    assert_eq!(payload.port, 8080);
+    assert_eq!(payload.repo_path, "/srv/api");
+    assert_eq!(payload.config, None);
+    assert_eq!(payload.git_branch, None);
+    assert_eq!(payload.git_auth_type, None);
+    assert_eq!(payload.stop_signal, StopSignal::Term);
+    assert_eq!(payload.profile, None);
+    assert!(payload.labels.is_empty());
+}
+
+#[test]
+fn test_builder_with_every_field() {
+    let config = ServiceConfigPayload {
+        service: ServiceRef::Name("api".to_string()),
+        filename: "api.env".to_string(),
+        run_command: "cargo run".to_string(),
+        ..Default::default()
+    };
+
+    let payload: AddServicePayload =
+        AddServicePayload::builder("api", "https://github.com/test/api.git", 8080, "/srv/api")
+            .config(config.clone())
+            .git_branch("develop")
+            .git_auth_type("ssh")
+            .stop_signal(StopSignal::Int)
+            .profile("backend")
+            .label("team", "payments")
+            .label("env", "staging")
+            .label("env", "prod")
+            .into();
+
+    assert_eq!(payload.config, Some(config));
+    assert_eq!(payload.git_branch.as_deref(), Some("develop"));
+    assert_eq!(payload.git_auth_type.as_deref(), Some("ssh"));
+    assert_eq!(payload.stop_signal, StopSignal::Int);
+    assert_eq!(payload.profile.as_deref(), Some("backend"));
+    assert_eq!(
+        payload.labels,
+        BTreeMap::from([
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "payments".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_builder_labels_replace_added_labels() {
+    let labels = BTreeMap::from([("tier".to_string(), "gold".to_string())]);
+
+    let payload =
+        AddServicePayload::builder("web", "https://github.com/test/web.git", 3000, "/srv")
+            .label("team", "frontend")
+            .labels(labels.clone())
+            .build();
+
+    assert_eq!(payload.labels, labels);
+}
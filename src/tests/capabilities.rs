@@ -0,0 +1,38 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::capabilities::Capabilities;
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::{Protocol, PROTOCOL_VERSION};
+
+#[tokio::test]
+async fn test_capabilities_reflect_compiled_features() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    protocol.next_request_id();
+    protocol.write_command(&mut client, Command::Capabilities).await?;
+
+    let (header, payload) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Success));
+
+    let payload = Protocol::read_payload::<CommandPayload>(&payload.unwrap())?.unwrap();
+    let capabilities = Capabilities::try_from(payload)?;
+
+    assert_eq!(capabilities.protocol_version, PROTOCOL_VERSION);
+    assert!(capabilities.has_feature("lua"));
+    assert!(capabilities.has_feature("native"));
+    assert_eq!(capabilities.has_feature("git"), cfg!(feature = "git"));
+
+    assert!(capabilities.supports(Command::Ping));
+    assert!(capabilities.supports(Command::Capabilities));
+    assert!(capabilities.supports(Command::StartService));
+    assert_eq!(capabilities.supports(Command::GitPull), cfg!(feature = "git"));
+    assert_eq!(capabilities.supports(Command::GitLog), cfg!(feature = "git"));
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::FullProcessManager;
+use anyhow::Result;
+use std::collections::HashMap;
+
+const PRINTS_A_LINE: &str = "echo 'hello from the service'";
+
+#[tokio::test]
+async fn test_captured_output_lands_in_the_log_buffer() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let mut process = manager
+        .spawn_service_process(
+            1,
+            env.test_env.temp_dir.path(),
+            PRINTS_A_LINE,
+            HashMap::new(),
+            LogSettings::default(),
+        )
+        .await?;
+    process.process.wait().await?;
+
+    // Both tasks finish once the output of the exited process is drained
+    let (log_task, stdout_task) = process.log_task_handle.take().expect("output is captured");
+    stdout_task.await?;
+    log_task.await?;
+
+    let logs = process.stdout_logs.lock().await;
+    let lines: Vec<_> = logs.iter().map(|entry| entry.content.as_str()).collect();
+    assert_eq!(lines, vec!["hello from the service\n"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_uncaptured_output_is_not_buffered() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let mut process = manager
+        .spawn_service_process(
+            1,
+            env.test_env.temp_dir.path(),
+            PRINTS_A_LINE,
+            HashMap::new(),
+            LogSettings {
+                capture: false,
+                ..LogSettings::default()
+            },
+        )
+        .await?;
+    process.process.wait().await?;
+
+    assert!(process.log_task_handle.is_none());
+    assert!(process.stderr_task_handle.is_none());
+    assert!(process.stdout_logs.lock().await.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::header::{MessageFlags, MessageHeader};
+use nexsock_protocol::protocol::{ChecksumMismatch, Protocol};
+
+const PAYLOAD: &str = "a payload that travels over a flaky tunnel";
+
+/// Encodes a message carrying [`PAYLOAD`], checksummed if `checksums` is set.
+async fn encoded_message(checksums: bool) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    Protocol::default()
+        .with_checksums(checksums)
+        .write_command_with_payload(
+            &mut bytes,
+            Command::Ping,
+            &PAYLOAD.to_string(),
+            MessageFlags::NONE,
+        )
+        .await?;
+
+    Ok(bytes)
+}
+
+#[tokio::test]
+async fn test_checksummed_message_round_trips() -> Result<()> {
+    let bytes = encoded_message(true).await?;
+    let plain = encoded_message(false).await?;
+
+    // The checksum trails the payload
+    assert_eq!(bytes.len(), plain.len() + 4);
+
+    let mut reader = bytes.as_slice();
+    let (header, payload) = Protocol::default().read_message(&mut reader).await?;
+
+    assert!(header.flags().contains(MessageFlags::CHECKSUMMED));
+    assert!(reader.is_empty(), "The checksum should be consumed");
+
+    let payload = Protocol::read_payload::<String>(&payload.unwrap_or_default())?;
+    assert_eq!(payload.as_deref(), Some(PAYLOAD));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_corrupted_payload_fails_checksum() -> Result<()> {
+    let mut bytes = encoded_message(true).await?;
+    bytes[MessageHeader::ENCODED_LEN + 5] ^= 0x01;
+
+    let mut reader = bytes.as_slice();
+    let err = Protocol::default()
+        .read_message(&mut reader)
+        .await
+        .expect_err("A corrupted payload should be rejected");
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(ChecksumMismatch::is(&err), "{err}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_messages_are_not_checksummed_by_default() -> Result<()> {
+    let bytes = encoded_message(false).await?;
+
+    let mut reader = bytes.as_slice();
+    let (header, _) = Protocol::default().read_message(&mut reader).await?;
+
+    assert!(!header.flags().contains(MessageFlags::CHECKSUMMED));
+    assert!(reader.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use crate::traits::service_management::ServiceManagement;
+use anyhow::{bail, Result};
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::service_status::ServiceState;
+use nexsock_protocol::commands::stdin::WriteStdinPayload;
+use nexsock_protocol::commands::stdout::GetServiceStdoutPayload;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+fn stdout_payload(service_id: i64) -> GetServiceStdoutPayload {
+    GetServiceStdoutPayload {
+        service: ServiceRef::Id(service_id),
+        after_seq: None,
+    }
+}
+
+/// Polls the buffered output until it is `expected`, lines are stored shortly after being read.
+async fn wait_for_stdout(manager: &ServiceManager, service_id: i64, expected: &str) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    loop {
+        let content = manager
+            .get_stdout(&stdout_payload(service_id))
+            .await?
+            .content;
+        if content == expected {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            bail!("Expected the output {expected:?}, got {content:?}");
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_clear_logs_empties_buffer_of_running_service() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let id = save_service_with_command(&env, "clear-logs", "exec cat").await?;
+
+    let process = manager
+        .spawn_service_process(
+            id,
+            env.test_env.temp_dir.path(),
+            "echo 'first'; echo 'second'; exec cat",
+            HashMap::new(),
+            LogSettings::default(),
+        )
+        .await?;
+    manager.running_services().insert(id, process);
+
+    wait_for_stdout(&manager, id, "first\nsecond\n").await?;
+
+    manager.clear_logs(&ServiceRef::Id(id)).await?;
+
+    let stdout = manager.get_stdout(&stdout_payload(id)).await?;
+    assert_eq!(stdout.content, "");
+    assert_eq!(stdout.last_seq, 2);
+    assert_eq!(
+        manager.get_state(&ServiceRef::Id(id)).await?,
+        ServiceState::Running
+    );
+
+    // The process keeps running and its new output is captured again
+    manager
+        .write_stdin(&WriteStdinPayload {
+            service: ServiceRef::Id(id),
+            data: b"third\n".to_vec(),
+        })
+        .await?;
+    wait_for_stdout(&manager, id, "third\n").await?;
+    assert_eq!(manager.get_stdout(&stdout_payload(id)).await?.last_seq, 3);
+
+    let (_, mut process) = manager.running_services().remove(&id).unwrap();
+    process.process.kill().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clear_logs_of_stopped_service_fails() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let id = save_service_with_command(&env, "clear-logs-stopped", "exec cat").await?;
+
+    let error = manager.clear_logs(&ServiceRef::Id(id)).await.unwrap_err();
+    assert_eq!(error.to_string(), "Service is not running");
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use super::common::*;
+use anyhow::Result;
+use futures::{StreamExt, TryStreamExt};
+use nexsock_client::{Client, LogLine};
+use nexsock_protocol::commands::event::{SequencedEvent, ServiceEvent, SubscribePayload};
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use std::collections::HashMap;
+use std::pin::pin;
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// Prints two lines right away and a third one once it has been running for a while.
+const PRINTS_LINES: &str = "echo 'line 1'; echo 'line 2'; sleep 0.5; echo 'line 3'; exec sleep 30";
+
+#[tokio::test]
+async fn test_subscribe_events_streams_lifecycle_events() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "stream-events", "exec sleep 30").await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut subscriber = Client::connect(&env.test_env.socket_path).await?;
+    let payload = SubscribePayload {
+        service: Some(ServiceRef::Id(service_id)),
+        ..SubscribePayload::default()
+    };
+    let events = subscriber.subscribe_events(payload).await?;
+
+    let mut client = Client::connect(&env.test_env.socket_path).await?;
+    client.start(service_id, HashMap::new()).await?;
+    client.stop(service_id).await?;
+
+    let events: Vec<SequencedEvent> =
+        timeout(Duration::from_secs(10), events.take(2).try_collect()).await??;
+    assert_eq!(
+        events
+            .into_iter()
+            .map(|event| event.event)
+            .collect::<Vec<_>>(),
+        vec![
+            ServiceEvent::Started { service_id },
+            ServiceEvent::Stopped { service_id },
+        ]
+    );
+
+    daemon.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_events_refused_for_unknown_service() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut subscriber = Client::connect(&env.test_env.socket_path).await?;
+    let payload = SubscribePayload {
+        service: Some(ServiceRef::Name("stream-missing".to_string())),
+        ..SubscribePayload::default()
+    };
+    assert!(subscriber.subscribe_events(payload).await.is_err());
+
+    daemon.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_follow_stdout_streams_buffered_and_new_lines() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "stream-stdout", PRINTS_LINES).await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut client = Client::connect(&env.test_env.socket_path).await?;
+    client.start(service_id, HashMap::new()).await?;
+
+    let mut follower = Client::connect(&env.test_env.socket_path).await?;
+    let mut lines = pin!(follower.follow_stdout(ServiceRef::Id(service_id)));
+
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let line = timeout(Duration::from_secs(10), lines.try_next()).await??;
+        received.push(line.expect("the stream only ends on errors"));
+    }
+
+    let expected = ["line 1", "line 2", "line 3"].map(|content| LogLine {
+        content: content.to_string(),
+    });
+    assert_eq!(received, expected);
+
+    client.stop(service_id).await?;
+    daemon.abort();
+
+    Ok(())
+}
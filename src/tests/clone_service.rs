@@ -0,0 +1,108 @@
+use super::common::*;
+use crate::statics::SERVICE_MANAGER;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{CloneServicePayload, ServiceRef};
+use nexsock_testing::generate_test_port;
+
+#[tokio::test]
+async fn test_clone_duplicates_the_config() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, _) = save_app_and_database(&env, "clone-config", true, true).await?;
+    let port = generate_test_port();
+
+    let clone = SERVICE_MANAGER
+        .clone_service(&CloneServicePayload {
+            source: ServiceRef::Id(app),
+            new_name: "clone-config-staging".to_string(),
+            new_port: port,
+            new_repo_path: Some("/srv/staging".to_string()),
+            copy_dependencies: false,
+        })
+        .await?;
+    let source = SERVICE_MANAGER.get_status(&ServiceRef::Id(app)).await?;
+
+    assert_ne!(clone.id, source.id);
+    assert_eq!(clone.name, "clone-config-staging");
+    assert_eq!(clone.port, port);
+    assert_eq!(clone.repo_path, "/srv/staging");
+    assert_eq!(clone.repo_url, source.repo_url);
+    assert!(clone.dependencies.is_empty());
+
+    let clone_config = clone.config.unwrap();
+    let source_config = source.config.unwrap();
+    assert_ne!(clone_config.id, source_config.id);
+    assert_eq!(clone_config.filename, source_config.filename);
+    assert_eq!(clone_config.run_command.as_deref(), Some("echo app"));
+
+    // Editing the clone leaves the source as it was
+    let configs = ServiceConfigRepository::new_from_static();
+    let mut config = configs.get_by_id(clone_config.id.unwrap()).await?.unwrap();
+    config.run_command = Some("echo staging".to_string());
+    configs.save(&mut config).await?;
+
+    let source = SERVICE_MANAGER.get_status(&ServiceRef::Id(app)).await?;
+    assert_eq!(
+        source.config.unwrap().run_command.as_deref(),
+        Some("echo app")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clone_copies_dependencies_when_asked() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, database) = save_app_and_database(&env, "clone-deps", true, true).await?;
+
+    let clone = SERVICE_MANAGER
+        .clone_service(&CloneServicePayload {
+            source: ServiceRef::Name("clone-deps-app".to_string()),
+            new_name: "clone-deps-staging".to_string(),
+            new_port: generate_test_port(),
+            new_repo_path: None,
+            copy_dependencies: true,
+        })
+        .await?;
+
+    let source = SERVICE_MANAGER.get_status(&ServiceRef::Id(app)).await?;
+    assert_eq!(clone.repo_path, source.repo_path);
+
+    let [dependency] = clone.dependencies.as_slice() else {
+        panic!("expected one dependency, got {:?}", clone.dependencies);
+    };
+    assert_eq!(dependency.id, database);
+    assert!(dependency.tunnel_enabled);
+    assert!(dependency.optional);
+
+    // Removing the dependency of the clone keeps the one of the source
+    let dependencies = ServiceDependencyRepository::new_from_static();
+    let clone_dependencies = dependencies.get_by_service_id(clone.id).await?;
+    assert_eq!(clone_dependencies.len(), 1);
+    dependencies.delete_by_id(clone_dependencies[0].id).await?;
+
+    assert_eq!(dependencies.get_by_service_id(app).await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clone_with_a_taken_name_fails() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, _) = save_app_and_database(&env, "clone-taken", true, true).await?;
+
+    let result = SERVICE_MANAGER
+        .clone_service(&CloneServicePayload {
+            source: ServiceRef::Id(app),
+            new_name: "clone-taken-database".to_string(),
+            new_port: generate_test_port(),
+            new_repo_path: None,
+            copy_dependencies: true,
+        })
+        .await;
+
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -1,5 +1,16 @@
+use crate::daemon::{reject_connection, Connection, ConnectionLimit};
 use anyhow::Result;
-use nexsock_testing::{init_test_tracing, TestEnvironment};
+use nexsock_db::prelude::*;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_protocol::commands::config::ConfigFormat;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_testing::{generate_test_port, init_test_tracing, TestEnvironment};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{duplex, split, DuplexStream};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::task::JoinHandle;
 
 pub struct DaemonTestEnvironment {
     pub test_env: TestEnvironment,
@@ -16,3 +27,129 @@ impl DaemonTestEnvironment {
         Ok(Self { test_env })
     }
 }
+
+/// Runs a daemon [`Connection`] over an in-memory stream and returns the client end of it.
+pub fn spawn_test_connection(
+    keepalive: KeepaliveConfig,
+) -> Result<(JoinHandle<crate::error::Result<()>>, DuplexStream)> {
+    spawn_test_connection_with_idle_timeout(keepalive, Duration::ZERO)
+}
+
+/// Runs a daemon [`Connection`] like [`spawn_test_connection`] that closes once the client sent
+/// nothing for `idle_timeout`, a zero timeout never closes it.
+pub fn spawn_test_connection_with_idle_timeout(
+    keepalive: KeepaliveConfig,
+    idle_timeout: Duration,
+) -> Result<(JoinHandle<crate::error::Result<()>>, DuplexStream)> {
+    let (client, server) = duplex(8 * 1024);
+    let (reader, writer) = split(server);
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+
+    let mut connection = Connection::from_parts(reader, writer, lua_plugin_manager, keepalive)
+        .with_idle_timeout(idle_timeout);
+    let handle = tokio::spawn(async move { connection.handle().await });
+
+    Ok((handle, client))
+}
+
+/// Connects a client through `limit` the same way `Daemon::accept` does.
+///
+/// Returns the handle of the connection task when the client was admitted, or `None` when it was
+/// rejected for exceeding the limit.
+pub async fn connect_with_limit(
+    limit: &ConnectionLimit,
+    keepalive: KeepaliveConfig,
+) -> Result<(Option<JoinHandle<crate::error::Result<()>>>, DuplexStream)> {
+    let (client, server) = duplex(8 * 1024);
+    let (reader, writer) = split(server);
+
+    let permit = match limit.try_acquire() {
+        Ok(permit) => permit,
+        Err(_) => {
+            reject_connection(writer, limit.max()).await?;
+            return Ok((None, client));
+        }
+    };
+
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+    let mut connection = Connection::from_parts(reader, writer, lua_plugin_manager, keepalive)
+        .with_permit(permit);
+    let handle = tokio::spawn(async move { connection.handle().await });
+
+    Ok((Some(handle), client))
+}
+
+/// Serves every client connecting to the socket of `env` with a daemon [`Connection`].
+#[cfg(unix)]
+pub fn spawn_daemon(env: &DaemonTestEnvironment) -> Result<JoinHandle<Result<()>>> {
+    let listener = UnixListener::bind(&env.test_env.socket_path)?;
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let mut connection = Connection::new(
+                stream,
+                lua_plugin_manager.clone(),
+                KeepaliveConfig::disabled(),
+            );
+
+            tokio::spawn(async move { connection.handle().await });
+        }
+    }))
+}
+
+/// Saves a service called `name` that runs `run_command` in the temporary directory of `env`,
+/// returning its id.
+pub async fn save_service_with_command(
+    env: &DaemonTestEnvironment,
+    name: &str,
+    run_command: &str,
+) -> Result<i64> {
+    let mut config = ServiceConfig::new(
+        format!("{name}-config"),
+        ConfigFormat::Env,
+        Some(run_command.to_string()),
+    );
+    ServiceConfigRepository::new_from_static()
+        .save(&mut config)
+        .await?;
+
+    let mut service = Service::new(
+        name.to_string(),
+        format!("https://github.com/test/{name}.git"),
+        generate_test_port(),
+        env.test_env.temp_dir.path().to_string_lossy().to_string(),
+        Some(config.id),
+    );
+    ServiceRepository::new_from_static()
+        .save(&mut service)
+        .await?;
+
+    Ok(service.id)
+}
+
+/// Saves `{prefix}-app` depending on `{prefix}-database` with the given dependency flags,
+/// returning their ids.
+pub async fn save_app_and_database(
+    env: &DaemonTestEnvironment,
+    prefix: &str,
+    tunnel_enabled: bool,
+    optional: bool,
+) -> Result<(i64, i64)> {
+    let app = save_service_with_command(env, &format!("{prefix}-app"), "echo app").await?;
+    let database = save_service_with_command(env, &format!("{prefix}-database"), "true").await?;
+
+    let mut dependency = ServiceDependency {
+        id: 0,
+        service_id: app,
+        dependent_service_id: database,
+        tunnel_enabled,
+        optional,
+    };
+    ServiceDependencyRepository::new_from_static()
+        .save(&mut dependency)
+        .await?;
+
+    Ok((app, database))
+}
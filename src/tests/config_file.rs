@@ -0,0 +1,236 @@
+use super::common::*;
+use crate::config_manager::new::MAX_CONFIG_FILE_SIZE;
+use crate::statics::CONFIG_MANAGER;
+use crate::traits::configuration_management::ConfigurationManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::config::WriteConfigPayload;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use std::path::Path;
+
+/// Lists the temporary files left next to the config files in `dir`.
+fn temp_files(dir: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.starts_with(".tmp") {
+            names.push(name);
+        }
+    }
+
+    Ok(names)
+}
+
+#[tokio::test]
+async fn test_config_file_contents_are_returned() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-file-present", "true").await?;
+
+    let path = env
+        .test_env
+        .temp_dir
+        .path()
+        .join("config-file-present-config");
+    std::fs::write(&path, "PORT=8080\nLOG_LEVEL=debug\n")?;
+
+    let file = CONFIG_MANAGER
+        .get_config_file(&ServiceRef::Id(service_id))
+        .await?;
+
+    assert_eq!(file.config.filename, "config-file-present-config");
+    assert_eq!(file.path, path.to_string_lossy());
+    assert_eq!(
+        file.contents.as_deref(),
+        Some("PORT=8080\nLOG_LEVEL=debug\n")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_config_file_has_no_contents() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    save_service_with_command(&env, "config-file-missing", "true").await?;
+
+    let file = CONFIG_MANAGER
+        .get_config_file(&ServiceRef::Name("config-file-missing".to_string()))
+        .await?;
+
+    assert_eq!(file.config.filename, "config-file-missing-config");
+    assert_eq!(file.contents, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_oversized_config_file_is_refused() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-file-oversized", "true").await?;
+
+    let path = env
+        .test_env
+        .temp_dir
+        .path()
+        .join("config-file-oversized-config");
+
+    // A file right at the limit is still returned
+    std::fs::write(&path, vec![b'a'; MAX_CONFIG_FILE_SIZE as usize])?;
+    let file = CONFIG_MANAGER
+        .get_config_file(&ServiceRef::Id(service_id))
+        .await?;
+    assert_eq!(
+        file.contents.map(|contents| contents.len() as u64),
+        Some(MAX_CONFIG_FILE_SIZE)
+    );
+
+    std::fs::write(&path, vec![b'a'; MAX_CONFIG_FILE_SIZE as usize + 1])?;
+    let error = CONFIG_MANAGER
+        .get_config_file(&ServiceRef::Id(service_id))
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("larger than the limit"),
+        "{error}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_config_file_is_replaced() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-write", "true").await?;
+
+    let dir = env.test_env.temp_dir.path();
+    let path = dir.join("config-write-config");
+    std::fs::write(&path, "PORT=8080\n")?;
+
+    CONFIG_MANAGER
+        .write_config_file(&WriteConfigPayload {
+            service: ServiceRef::Id(service_id),
+            contents: "PORT=9090\nexport LOG_LEVEL=\"info\"\n".to_string(),
+        })
+        .await?;
+
+    assert_eq!(
+        std::fs::read_to_string(&path)?,
+        "PORT=9090\nexport LOG_LEVEL=\"info\"\n"
+    );
+    assert!(temp_files(dir)?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_config_is_rejected_before_writing() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-write-invalid", "true").await?;
+
+    let dir = env.test_env.temp_dir.path();
+    let path = dir.join("config-write-invalid-config");
+    std::fs::write(&path, "PORT=8080\n")?;
+
+    let error = CONFIG_MANAGER
+        .write_config_file(&WriteConfigPayload {
+            service: ServiceRef::Id(service_id),
+            contents: "PORT=9090\nnot a pair\n".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("line 2"), "{error}");
+
+    assert_eq!(std::fs::read_to_string(&path)?, "PORT=8080\n");
+    assert!(temp_files(dir)?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_write_leaves_no_partial_file() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-write-failed", "true").await?;
+
+    // A directory in place of the file makes the final rename fail after the contents were written
+    let dir = env.test_env.temp_dir.path();
+    let path = dir.join("config-write-failed-config");
+    std::fs::create_dir(&path)?;
+
+    let result = CONFIG_MANAGER
+        .write_config_file(&WriteConfigPayload {
+            service: ServiceRef::Id(service_id),
+            contents: "PORT=9090\n".to_string(),
+        })
+        .await;
+    assert!(result.is_err());
+
+    assert!(path.is_dir());
+    assert!(temp_files(dir)?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_config_filename_outside_the_repository_is_refused() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-write-escape", "true").await?;
+
+    let service = ServiceRepository::new_from_static()
+        .get_by_id(service_id)
+        .await?
+        .expect("The service was just saved");
+    let configs = ServiceConfigRepository::new_from_static();
+    let mut config = configs
+        .get_by_id(service.config_id.expect("The service has a config"))
+        .await?
+        .expect("The config was just saved");
+
+    for filename in ["../config-write-escape", "/tmp/config-write-escape"] {
+        config.filename = filename.to_string();
+        configs.save(&mut config).await?;
+
+        let error = CONFIG_MANAGER
+            .write_config_file(&WriteConfigPayload {
+                service: ServiceRef::Id(service_id),
+                contents: "PORT=9090\n".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("can't contain `..`"), "{error}");
+    }
+
+    assert!(!Path::new("/tmp/config-write-escape").exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_config_file_keeps_its_permissions() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "config-write-mode", "true").await?;
+
+    let path = env
+        .test_env
+        .temp_dir
+        .path()
+        .join("config-write-mode-config");
+    std::fs::write(&path, "PORT=8080\n")?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640))?;
+
+    CONFIG_MANAGER
+        .write_config_file(&WriteConfigPayload {
+            service: ServiceRef::Id(service_id),
+            contents: "PORT=9090\n".to_string(),
+        })
+        .await?;
+
+    assert_eq!(std::fs::read_to_string(&path)?, "PORT=9090\n");
+    assert_eq!(
+        std::fs::metadata(&path)?.permissions().mode() & 0o777,
+        0o640
+    );
+
+    Ok(())
+}
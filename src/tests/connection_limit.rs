@@ -0,0 +1,68 @@
+use super::common::*;
+use crate::daemon::ConnectionLimit;
+use anyhow::Result;
+use nexsock_protocol::commands::error::ErrorPayload;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Sends a ping and returns the command the daemon answered with.
+async fn ping<S: AsyncRead + AsyncWrite + Unpin>(client: &mut S) -> Result<Command> {
+    let mut protocol = Protocol::default();
+    protocol.write_command(client, Command::Ping).await?;
+
+    let (header, _) = protocol.read_message(client).await?;
+
+    Ok(header.command)
+}
+
+#[tokio::test]
+async fn test_connections_beyond_limit_are_refused() -> Result<()> {
+    let limit = ConnectionLimit::new(2);
+
+    let (first_handle, mut first) = connect_with_limit(&limit, KeepaliveConfig::disabled()).await?;
+    let (second_handle, mut second) =
+        connect_with_limit(&limit, KeepaliveConfig::disabled()).await?;
+    assert!(first_handle.is_some() && second_handle.is_some());
+
+    let (rejected_handle, mut rejected) =
+        connect_with_limit(&limit, KeepaliveConfig::disabled()).await?;
+    assert!(rejected_handle.is_none(), "Third connection should be refused");
+
+    let (header, payload) = Protocol::default().read_message(&mut rejected).await?;
+    assert!(matches!(header.command, Command::Error));
+
+    let error: ErrorPayload = Protocol::read_payload(&payload.unwrap_or_default())?
+        .expect("Rejection should carry an error payload");
+    assert_eq!(error.code, 14);
+    assert!(error.message.contains("at most 2"), "{}", error.message);
+
+    // Admitted connections are unaffected by the rejection
+    assert!(matches!(ping(&mut first).await?, Command::Success));
+    assert!(matches!(ping(&mut second).await?, Command::Success));
+
+    // Closing a connection frees its slot for the next client
+    drop(first);
+    first_handle.unwrap().await??;
+
+    let (third_handle, mut third) = connect_with_limit(&limit, KeepaliveConfig::disabled()).await?;
+    assert!(third_handle.is_some(), "A freed slot should admit a new client");
+    assert!(matches!(ping(&mut third).await?, Command::Success));
+
+    drop(second);
+    drop(third);
+    second_handle.unwrap().await??;
+    third_handle.unwrap().await??;
+
+    Ok(())
+}
+
+#[test]
+fn test_zero_disables_connection_limit() {
+    let limit = ConnectionLimit::new(0);
+
+    for _ in 0..1_000 {
+        assert!(limit.try_acquire().unwrap().is_none());
+    }
+}
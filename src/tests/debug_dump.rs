@@ -0,0 +1,145 @@
+use super::common::*;
+use crate::daemon::connection::Connection;
+use crate::daemon::debug_dump::{debug_dump, is_secret_name};
+use anyhow::Result;
+use dashmap::DashMap;
+use nexsock_config::NEXSOCK_CONFIG;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_protocol::commands::debug_dump::{DebugDump, REDACTED};
+use nexsock_protocol::commands::error::ErrorPayload;
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::sync::Arc;
+use tokio::io::{duplex, split};
+
+#[tokio::test]
+async fn test_debug_dump_is_rejected_by_default() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    protocol.next_request_id();
+    protocol.write_command(&mut client, Command::DebugDump).await?;
+
+    let (header, payload) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Error));
+
+    let error: ErrorPayload = Protocol::read_payload(&payload.unwrap_or_default())?
+        .expect("Rejection should carry an error payload");
+    assert_eq!(error.code, 17);
+    assert!(error.message.contains("server.debug_dump"), "{}", error.message);
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_debug_dump_returns_the_daemon_state() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (mut client, server) = duplex(64 * 1024);
+    let (reader, writer) = split(server);
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+
+    let mut connection =
+        Connection::from_parts(reader, writer, lua_plugin_manager, KeepaliveConfig::disabled())
+            .with_debug_dump(true);
+    let handle = tokio::spawn(async move { connection.handle().await });
+
+    let mut protocol = Protocol::default();
+    protocol.next_request_id();
+    protocol.write_command(&mut client, Command::DebugDump).await?;
+
+    let (header, payload) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Success));
+
+    let payload = Protocol::read_payload::<CommandPayload>(&payload.unwrap())?.unwrap();
+    let dump = DebugDump::try_from(payload)?;
+
+    assert_eq!(dump.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(dump.instance.as_deref(), NEXSOCK_CONFIG.instance());
+    assert!(dump.running_services.is_empty());
+    assert!(dump.lua_plugins.is_empty());
+    assert!(dump.failed_lua_plugins.is_empty());
+
+    let config: serde_json::Value = serde_json::from_str(&dump.config)?;
+    for field in ["socket", "log_str", "server", "database", "web"] {
+        assert!(config.get(field).is_some(), "missing `{field}` in {config}");
+    }
+    assert!(config["server"].get("debug_dump").is_some());
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_debug_dump_redacts_secret_env_vars() -> Result<()> {
+    use crate::service_manager::ServiceProcess;
+    use command_group::AsyncCommandGroup;
+    use nexsock_protocol::commands::service_status::ServiceState;
+    use std::collections::HashMap;
+    use tokio::sync::{broadcast, Mutex};
+
+    let process = tokio::process::Command::new("sleep")
+        .arg("30")
+        .kill_on_drop(true)
+        .group_spawn()?;
+    let pid = process.id();
+
+    let env_vars = HashMap::from([
+        ("PORT".to_string(), "8080".to_string()),
+        ("DATABASE_PASSWORD".to_string(), "hunter2".to_string()),
+        ("github_token".to_string(), "ghp_secret".to_string()),
+    ]);
+
+    let running_services = DashMap::new();
+    running_services.insert(
+        7,
+        ServiceProcess {
+            process,
+            state: ServiceState::Running,
+            started_at: tokio::time::Instant::now(),
+            stop_signal: Default::default(),
+            env_vars,
+            stdout: None,
+            stdin: Default::default(),
+            stderr: None,
+            stdout_logs: Default::default(),
+            log_task_handle: None,
+            startup_stderr: Arc::new(Mutex::new(Vec::new())),
+            stderr_task_handle: None,
+            output: broadcast::channel(16).0,
+            resource_sample: None,
+            resources: None,
+        },
+    );
+
+    let dump = debug_dump(&NEXSOCK_CONFIG, &running_services, Vec::new(), Vec::new(), []);
+
+    let service = &dump.running_services[0];
+    assert_eq!(service.id, 7);
+    assert_eq!(service.pid, pid);
+    assert_eq!(service.state, ServiceState::Running);
+    assert_eq!(service.buffered_log_entries, 0);
+    assert_eq!(service.env_vars["PORT"], "8080");
+    assert_eq!(service.env_vars["DATABASE_PASSWORD"], REDACTED);
+    assert_eq!(service.env_vars["github_token"], REDACTED);
+
+    Ok(())
+}
+
+#[test]
+fn test_is_secret_name() {
+    for name in ["API_KEY", "SECRET", "aws_secret_access_key", "AUTH_HEADER", "SSH_PRIVATE"] {
+        assert!(is_secret_name(name), "{name}");
+    }
+
+    for name in ["PORT", "RUST_LOG", "DATABASE_URL", "HOME"] {
+        assert!(!is_secret_name(name), "{name}");
+    }
+}
@@ -0,0 +1,155 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+
+/// Saves a service running `sleep`, returning its id and port.
+async fn save_service(env: &DaemonTestEnvironment, name: &str) -> Result<(i64, u16)> {
+    let id = save_service_with_command(env, name, "exec sleep 30").await?;
+    let service = ServiceRepository::new_from_static()
+        .get_by_id(id)
+        .await?
+        .expect("The service was just saved");
+
+    Ok((id, service.port as u16))
+}
+
+async fn add_tunnel_dependency(
+    service_id: i64,
+    dependent_service_id: i64,
+    optional: bool,
+) -> Result<()> {
+    let mut dependency = ServiceDependency {
+        id: 0,
+        service_id,
+        dependent_service_id,
+        tunnel_enabled: true,
+        optional,
+    };
+    ServiceDependencyRepository::new_from_static()
+        .save(&mut dependency)
+        .await?;
+
+    Ok(())
+}
+
+fn start_payload(id: i64, timeout_secs: u64) -> StartServicePayload {
+    StartServicePayload {
+        service: ServiceRef::Id(id),
+        ready_timeout_secs: Some(timeout_secs),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_start_waits_for_tunnel_dependency() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (app, _) = save_service(&env, "dep-ready-app").await?;
+    let (database, database_port) = save_service(&env, "dep-ready-database").await?;
+    add_tunnel_dependency(app, database, false).await?;
+
+    // The database is slow to open its port
+    let listener = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        TcpListener::bind(("127.0.0.1", database_port)).await
+    });
+
+    let started = Instant::now();
+    manager.start(&start_payload(app, 5)).await?;
+
+    assert!(started.elapsed() >= Duration::from_millis(800));
+    let listener = listener.await??;
+    assert_eq!(
+        manager.get_status(&ServiceRef::Id(app)).await?.state,
+        ServiceState::Running
+    );
+
+    drop(listener);
+    manager.stop(&ServiceRef::Id(app)).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_fails_when_tunnel_dependency_not_ready() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (app, _) = save_service(&env, "dep-unready-app").await?;
+    let (database, _) = save_service(&env, "dep-unready-database").await?;
+    add_tunnel_dependency(app, database, false).await?;
+
+    let error = manager.start(&start_payload(app, 1)).await.unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Dependency `dep-unready-database` was not ready within 1 seconds"
+    );
+    // The service itself is never spawned
+    assert_eq!(
+        manager.get_status(&ServiceRef::Id(app)).await?.state,
+        ServiceState::Stopped
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_proceeds_when_optional_dependency_not_ready() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (app, _) = save_service(&env, "dep-optional-app").await?;
+    let (database, database_port) = save_service(&env, "dep-optional-database").await?;
+    let (cache, _) = save_service(&env, "dep-optional-cache").await?;
+    add_tunnel_dependency(app, database, false).await?;
+    add_tunnel_dependency(app, cache, true).await?;
+
+    // Only the required database is ready
+    let listener = TcpListener::bind(("127.0.0.1", database_port)).await?;
+
+    manager.start(&start_payload(app, 1)).await?;
+
+    assert_eq!(
+        manager.get_status(&ServiceRef::Id(app)).await?.state,
+        ServiceState::Running
+    );
+
+    drop(listener);
+    manager.stop(&ServiceRef::Id(app)).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_fails_when_required_dependency_not_ready() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (app, _) = save_service(&env, "dep-required-app").await?;
+    let (database, _) = save_service(&env, "dep-required-database").await?;
+    let (cache, cache_port) = save_service(&env, "dep-required-cache").await?;
+    add_tunnel_dependency(app, database, false).await?;
+    add_tunnel_dependency(app, cache, true).await?;
+
+    // Only the optional cache is ready
+    let listener = TcpListener::bind(("127.0.0.1", cache_port)).await?;
+
+    let error = manager.start(&start_payload(app, 1)).await.unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Dependency `dep-required-database` was not ready within 1 seconds"
+    );
+    assert_eq!(
+        manager.get_status(&ServiceRef::Id(app)).await?.state,
+        ServiceState::Stopped
+    );
+
+    drop(listener);
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use super::common::*;
+use crate::dependency_manager::new::DependencyManager;
+use crate::service_manager::ServiceProcess;
+use crate::traits::dependency_management::DependencyManagement;
+use anyhow::Result;
+use command_group::AsyncCommandGroup;
+use dashmap::DashMap;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::dependency::{AddDependencyPayload, GetDependencyInfoPayload};
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::service_status::ServiceState;
+use nexsock_testing::generate_test_port;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, Mutex};
+
+async fn save_service(name: &str) -> Result<i64> {
+    save_service_on_port(name, generate_test_port()).await
+}
+
+async fn save_service_on_port(name: &str, port: i64) -> Result<i64> {
+    let mut service = Service::new(
+        name.to_string(),
+        format!("https://github.com/test/{name}.git"),
+        port,
+        format!("/tmp/{name}"),
+        None,
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    Ok(service.id)
+}
+
+/// A long running process registered the way the service manager tracks started services.
+fn running_process() -> Result<ServiceProcess> {
+    let process = tokio::process::Command::new("sleep")
+        .arg("30")
+        .kill_on_drop(true)
+        .group_spawn()?;
+
+    Ok(ServiceProcess {
+        process,
+        state: ServiceState::Running,
+        started_at: tokio::time::Instant::now(),
+        stop_signal: Default::default(),
+        env_vars: HashMap::new(),
+        stdout: None,
+        stdin: Default::default(),
+        stderr: None,
+        stdout_logs: Default::default(),
+        log_task_handle: None,
+        startup_stderr: Arc::new(Mutex::new(Vec::new())),
+        stderr_task_handle: None,
+        output: broadcast::channel(16).0,
+        resource_sample: None,
+        resources: None,
+    })
+}
+
+#[tokio::test]
+async fn test_list_dependencies_reports_live_state() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+
+    let app = save_service("dep-state-app").await?;
+    let database = save_service("dep-state-database").await?;
+    let cache = save_service("dep-state-cache").await?;
+
+    let running_services = Arc::new(DashMap::new());
+    let manager = DependencyManager::with_running_services(running_services.clone());
+
+    for (dependency, tunnel_enabled) in [(database, true), (cache, false)] {
+        manager
+            .add_dependency(&AddDependencyPayload {
+                service: ServiceRef::Id(app),
+                dependent_service: ServiceRef::Id(dependency),
+                tunnel_enabled,
+                optional: false,
+            })
+            .await?;
+    }
+
+    // Only the database is running
+    running_services.insert(database, running_process()?);
+
+    let response = manager.list_dependencies(&ServiceRef::Id(app)).await?;
+    assert_eq!(response.service_name, "dep-state-app");
+    assert_eq!(response.dependencies.len(), 2);
+
+    let state_of = |id| {
+        response
+            .dependencies
+            .iter()
+            .find(|dependency| dependency.id == id)
+            .map(|dependency| dependency.dependent_state)
+    };
+    assert_eq!(state_of(database), Some(ServiceState::Running));
+    assert_eq!(state_of(cache), Some(ServiceState::Stopped));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_dependency_info_matches_the_configured_dependency() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+
+    let app = save_service("dep-info-app").await?;
+    let port = generate_test_port();
+    let database = save_service_on_port("dep-info-database", port).await?;
+    let cache = save_service("dep-info-cache").await?;
+
+    let running_services = Arc::new(DashMap::new());
+    let manager = DependencyManager::with_running_services(running_services.clone());
+
+    manager
+        .add_dependency(&AddDependencyPayload {
+            service: ServiceRef::Id(app),
+            dependent_service: ServiceRef::Id(database),
+            tunnel_enabled: true,
+            optional: true,
+        })
+        .await?;
+    running_services.insert(database, running_process()?);
+
+    let details = manager
+        .get_dependency_info(&GetDependencyInfoPayload {
+            service: ServiceRef::Name("dep-info-app".to_string()),
+            dependent_service: ServiceRef::Name("dep-info-database".to_string()),
+        })
+        .await?;
+
+    assert_eq!(details.service_name, "dep-info-app");
+    assert_eq!(details.port, port);
+    assert_eq!(details.dependency.id, database);
+    assert_eq!(details.dependency.name, "dep-info-database");
+    assert!(details.dependency.tunnel_enabled);
+    assert!(details.dependency.optional);
+    assert_eq!(details.dependency.dependent_state, ServiceState::Running);
+
+    // The app doesn't depend on the cache
+    let missing = manager
+        .get_dependency_info(&GetDependencyInfoPayload {
+            service: ServiceRef::Id(app),
+            dependent_service: ServiceRef::Id(cache),
+        })
+        .await;
+    assert!(missing.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::events::{EventBus, EventSubscription};
+use anyhow::Result;
+use nexsock_protocol::commands::event::{SequencedEvent, ServiceEvent, ServiceEventKind};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+#[tokio::test]
+async fn test_every_subscriber_receives_the_events() -> Result<()> {
+    let bus = EventBus::default();
+    let mut first = bus.subscribe();
+    let mut second = bus.subscribe();
+    assert_eq!(bus.subscriber_count(), 2);
+
+    let events = [
+        ServiceEvent::Started { service_id: 1 },
+        ServiceEvent::Crashed {
+            service_id: 2,
+            exit_code: Some(1),
+        },
+        ServiceEvent::Stopped { service_id: 1 },
+    ];
+
+    for event in events.clone() {
+        assert_eq!(bus.publish(event), 2);
+    }
+
+    for subscriber in [&mut first, &mut second] {
+        for (seq, expected) in (1..).zip(&events) {
+            let event = subscriber.recv().await?;
+            assert_eq!(event.seq, seq);
+            assert_eq!(&event.event, expected);
+        }
+        assert_eq!(subscriber.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribers_only_receive_later_events() -> Result<()> {
+    let bus = EventBus::default();
+
+    // Nobody is listening yet, the event is dropped
+    assert_eq!(bus.publish(ServiceEvent::Started { service_id: 1 }), 0);
+
+    let mut events = bus.subscribe();
+    bus.publish(ServiceEvent::Stopped { service_id: 1 });
+
+    assert_eq!(
+        events.recv().await?,
+        SequencedEvent {
+            seq: 2,
+            event: ServiceEvent::Stopped { service_id: 1 },
+        }
+    );
+    assert_eq!(events.try_recv(), Err(TryRecvError::Empty));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lagging_subscriber_skips_the_oldest_events() -> Result<()> {
+    let bus = EventBus::new(2);
+    let mut events = bus.subscribe();
+
+    for service_id in 1..=3 {
+        bus.publish(ServiceEvent::Started { service_id });
+    }
+
+    assert_eq!(events.recv().await, Err(RecvError::Lagged(1)));
+    assert_eq!(events.recv().await?.event.service_id(), 2);
+    assert_eq!(events.recv().await?.event.service_id(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resubscribing_replays_the_missed_events() -> Result<()> {
+    let bus = EventBus::default();
+    let mut events = bus.subscribe();
+
+    bus.publish(ServiceEvent::Started { service_id: 1 });
+    let last_seen = events.recv().await?.seq;
+    drop(events);
+
+    bus.publish(ServiceEvent::Stopped { service_id: 1 });
+    bus.publish(ServiceEvent::Started { service_id: 2 });
+
+    let (replay, mut events) = bus.subscribe_after(last_seen);
+    bus.publish(ServiceEvent::Stopped { service_id: 2 });
+
+    assert_eq!(
+        replay,
+        [
+            SequencedEvent {
+                seq: 2,
+                event: ServiceEvent::Stopped { service_id: 1 },
+            },
+            SequencedEvent {
+                seq: 3,
+                event: ServiceEvent::Started { service_id: 2 },
+            },
+        ]
+    );
+    // Events published after resubscribing are received, not replayed
+    assert_eq!(events.recv().await?.seq, 4);
+    assert_eq!(events.try_recv(), Err(TryRecvError::Empty));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_is_limited_to_the_buffered_events() -> Result<()> {
+    let bus = EventBus::new(2);
+
+    for service_id in 1..=4 {
+        bus.publish(ServiceEvent::Started { service_id });
+    }
+
+    let (replay, _events) = bus.subscribe_after(0);
+    let seqs: Vec<_> = replay.iter().map(|event| event.seq).collect();
+    assert_eq!(seqs, [3, 4]);
+
+    let (replay, _events) = bus.subscribe_after(4);
+    assert!(replay.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscription_filters_replayed_events() -> Result<()> {
+    let bus = EventBus::default();
+
+    bus.publish(ServiceEvent::Started { service_id: 1 });
+    bus.publish(ServiceEvent::Started { service_id: 2 });
+    bus.publish(ServiceEvent::Stopped { service_id: 1 });
+
+    let mut subscription =
+        EventSubscription::new(&bus, Some(1), vec![ServiceEventKind::Stopped], Some(0));
+    bus.publish(ServiceEvent::Stopped { service_id: 2 });
+    bus.publish(ServiceEvent::Stopped { service_id: 1 });
+
+    assert_eq!(subscription.recv().await.map(|event| event.seq), Some(3));
+    assert_eq!(subscription.recv().await.map(|event| event.seq), Some(5));
+
+    Ok(())
+}
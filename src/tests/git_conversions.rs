@@ -0,0 +1,65 @@
+use crate::git::{GitCommit, GitRepoInfo};
+use nexsock_protocol::commands::git::{GitCommitInfo, RepoStatus};
+
+#[test]
+fn test_repo_info_converts_to_repo_status() {
+    let repo_info = GitRepoInfo {
+        current_branch: Some("feature".to_string()),
+        current_commit: "0123456789abcdef".to_string(),
+        remote_url: "https://github.com/test/api.git".to_string(),
+        is_dirty: true,
+        branches: vec!["main".to_string(), "feature".to_string()],
+        ahead_count: Some(2),
+        behind_count: Some(5),
+    };
+
+    let status = RepoStatus::from(repo_info.clone());
+
+    assert_eq!(status.current_branch, repo_info.current_branch);
+    assert_eq!(status.current_commit, repo_info.current_commit);
+    assert_eq!(status.remote_url, repo_info.remote_url);
+    assert!(status.is_dirty);
+    assert_eq!(status.branches, repo_info.branches);
+    assert_eq!(status.ahead_count, Some(2));
+    assert_eq!(status.behind_count, Some(5));
+}
+
+#[test]
+fn test_untracked_repo_info_keeps_missing_counts() {
+    let repo_info = GitRepoInfo::new(
+        None,
+        "0123456789abcdef".to_string(),
+        "https://github.com/test/api.git".to_string(),
+        false,
+    );
+
+    let status: RepoStatus = repo_info.into();
+
+    assert_eq!(status.current_branch, None);
+    assert_eq!(status.ahead_count, None);
+    assert_eq!(status.behind_count, None);
+}
+
+#[test]
+fn test_commit_converts_to_commit_info() {
+    let commit = GitCommit::new(
+        "0123456789abcdef".to_string(),
+        "Alice".to_string(),
+        "alice@example.com".to_string(),
+        "2024-06-01T12:34:56Z".to_string(),
+        "Add the API\n\nWith a longer description".to_string(),
+    );
+
+    assert_eq!(
+        GitCommitInfo::from(commit),
+        GitCommitInfo {
+            hash: "0123456789abcdef".to_string(),
+            short_hash: "0123456".to_string(),
+            author_name: "Alice".to_string(),
+            author_email: "alice@example.com".to_string(),
+            timestamp: "2024-06-01T12:34:56Z".to_string(),
+            message: "Add the API".to_string(),
+            full_message: "Add the API\n\nWith a longer description".to_string(),
+        }
+    );
+}
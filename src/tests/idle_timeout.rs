@@ -0,0 +1,86 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+use tokio::time::Instant;
+
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Asserts that the daemon closed its end of `client`.
+async fn assert_closed(client: &mut DuplexStream) -> Result<()> {
+    let mut buf = [0u8; 16];
+    assert_eq!(
+        client.read(&mut buf).await?,
+        0,
+        "Connection should be closed"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_silent_client_is_disconnected() -> Result<()> {
+    let (handle, mut client) =
+        spawn_test_connection_with_idle_timeout(KeepaliveConfig::disabled(), IDLE_TIMEOUT)?;
+    let started = Instant::now();
+
+    handle.await??;
+
+    assert!(started.elapsed() >= IDLE_TIMEOUT);
+    assert_closed(&mut client).await
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_activity_resets_idle_timeout() -> Result<()> {
+    let (handle, mut client) =
+        spawn_test_connection_with_idle_timeout(KeepaliveConfig::disabled(), IDLE_TIMEOUT)?;
+    let mut protocol = Protocol::default();
+
+    // Each ping arrives before the timeout, so the connection outlives several timeouts
+    for _ in 0..3 {
+        tokio::time::sleep(IDLE_TIMEOUT / 2).await;
+
+        protocol.write_command(&mut client, Command::Ping).await?;
+        let (header, _) = protocol.read_message(&mut client).await?;
+        assert!(matches!(header.command, Command::Success));
+    }
+
+    assert!(!handle.is_finished());
+
+    let started = Instant::now();
+    handle.await??;
+
+    assert!(started.elapsed() >= IDLE_TIMEOUT);
+    assert_closed(&mut client).await
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stalled_message_is_disconnected() -> Result<()> {
+    let (handle, mut client) =
+        spawn_test_connection_with_idle_timeout(KeepaliveConfig::disabled(), IDLE_TIMEOUT)?;
+
+    // Send the start of a frame and never finish it
+    client.write_all(&[0x4E]).await?;
+    client.flush().await?;
+
+    handle.await??;
+
+    assert_closed(&mut client).await
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_zero_idle_timeout_keeps_connection_open() -> Result<()> {
+    let (handle, mut client) =
+        spawn_test_connection_with_idle_timeout(KeepaliveConfig::disabled(), Duration::ZERO)?;
+
+    tokio::time::sleep(IDLE_TIMEOUT * 10).await;
+    assert!(!handle.is_finished());
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use super::common::*;
+use crate::daemon::Connection;
+use anyhow::Result;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{duplex, split, AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::task::JoinHandle;
+
+/// Runs a JSON-RPC [`Connection`] over an in-memory stream and returns the client end of it.
+fn spawn_json_rpc_connection() -> Result<(JoinHandle<crate::error::Result<()>>, DuplexStream)> {
+    let (client, server) = duplex(8 * 1024);
+    let (reader, writer) = split(server);
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+
+    let mut connection = Connection::from_parts(
+        reader,
+        writer,
+        lua_plugin_manager,
+        KeepaliveConfig::disabled(),
+    )
+    .with_json_rpc(true);
+    let handle = tokio::spawn(async move { connection.handle().await });
+
+    Ok((handle, client))
+}
+
+/// Sends `request` as a single line and reads the response line.
+async fn call(client: &mut BufReader<DuplexStream>, request: &str) -> Result<Value> {
+    client.get_mut().write_all(format!("{request}\n").as_bytes()).await?;
+
+    let mut line = String::new();
+    client.read_line(&mut line).await?;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[tokio::test]
+async fn test_list_services_returns_result_envelope() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, client) = spawn_json_rpc_connection()?;
+    let mut client = BufReader::new(client);
+
+    let response = call(
+        &mut client,
+        r#"{"jsonrpc": "2.0", "id": 1, "method": "list_services"}"#,
+    )
+    .await?;
+
+    assert_eq!(response["jsonrpc"], "2.0");
+    assert_eq!(response["id"], 1);
+    assert!(response["result"]["services"].is_array(), "{response}");
+    assert!(response.get("error").is_none());
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_errors_use_json_rpc_codes() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, client) = spawn_json_rpc_connection()?;
+    let mut client = BufReader::new(client);
+
+    let response = call(&mut client, "not json").await?;
+    assert_eq!(response["error"]["code"], -32700);
+    assert_eq!(response["id"], Value::Null);
+
+    let response = call(
+        &mut client,
+        r#"{"jsonrpc": "2.0", "id": "a", "method": "launch_rockets"}"#,
+    )
+    .await?;
+    assert_eq!(response["error"]["code"], -32601);
+    assert_eq!(response["id"], "a");
+
+    let response = call(
+        &mut client,
+        r#"{"jsonrpc": "2.0", "id": 2, "method": "get_service_status", "params": [1, 2]}"#,
+    )
+    .await?;
+    assert_eq!(response["error"]["code"], -32602);
+
+    // The params are valid, the daemon fails the command itself
+    let response = call(
+        &mut client,
+        r#"{"jsonrpc": "2.0", "id": 3, "method": "get_service_status", "params": {"Name": "missing"}}"#,
+    )
+    .await?;
+    assert_eq!(response["error"]["code"], -32000);
+    assert!(response["error"]["data"]["kind"].is_u64());
+    assert!(response.get("result").is_none());
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_notifications_are_not_answered() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, client) = spawn_json_rpc_connection()?;
+    let mut client = BufReader::new(client);
+
+    client
+        .get_mut()
+        .write_all(b"{\"jsonrpc\": \"2.0\", \"method\": \"ping\"}\n")
+        .await?;
+
+    // The first line read back answers the request that followed the notification
+    let response = call(
+        &mut client,
+        r#"{"jsonrpc": "2.0", "id": 7, "method": "ping"}"#,
+    )
+    .await?;
+    assert_eq!(response, json!({ "jsonrpc": "2.0", "id": 7, "result": null }));
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const INTERVAL: Duration = Duration::from_secs(5);
+
+#[tokio::test(start_paused = true)]
+async fn test_heartbeats_emitted_at_interval() -> Result<()> {
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::new(INTERVAL, 3))?;
+    let mut protocol = Protocol::default();
+
+    for _ in 0..3 {
+        let started = Instant::now();
+        let (header, payload) = protocol.read_message(&mut client).await?;
+
+        assert!(matches!(header.command, Command::Heartbeat));
+        assert!(payload.is_none());
+        assert_eq!(started.elapsed(), INTERVAL);
+
+        protocol.write_heartbeat_ack(&mut client).await?;
+    }
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_missed_heartbeat_ack_disconnects() -> Result<()> {
+    let max_missed = 2;
+    let (handle, mut client) =
+        spawn_test_connection(KeepaliveConfig::new(INTERVAL, max_missed))?;
+    let mut protocol = Protocol::default();
+    let started = Instant::now();
+
+    // Read the heartbeats but never acknowledge them
+    for _ in 0..max_missed {
+        let (header, _) = protocol.read_message(&mut client).await?;
+        assert!(matches!(header.command, Command::Heartbeat));
+    }
+
+    handle.await??;
+    assert_eq!(started.elapsed(), INTERVAL * (max_missed + 1));
+
+    // The daemon side has been dropped, so the stream reports EOF
+    let err = protocol.read_message(&mut client).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::label::LabelSelectorPayload;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_testing::generate_test_port;
+use std::collections::BTreeMap;
+
+fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Returns the names of the services the `key=value` pairs select.
+async fn listed_names(manager: &ServiceManager, pairs: &[(&str, &str)]) -> Result<Vec<String>> {
+    let selector = LabelSelectorPayload {
+        labels: labels(pairs),
+    };
+    let listed = manager.list_by_labels(&selector).await?;
+
+    Ok(listed
+        .services
+        .into_iter()
+        .map(|service| service.name)
+        .collect())
+}
+
+#[tokio::test]
+async fn test_added_labels_filter_the_service_list() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    for (name, service_labels) in [
+        ("labels-api", labels(&[("team", "labels-payments"), ("env", "prod")])),
+        ("labels-web", labels(&[("team", "labels-frontend"), ("env", "prod")])),
+        ("labels-worker", labels(&[("team", "labels-payments"), ("env", "staging")])),
+    ] {
+        manager
+            .add_service(&AddServicePayload {
+                name: name.to_string(),
+                repo_url: format!("https://github.com/test/{name}.git"),
+                port: generate_test_port(),
+                repo_path: env.test_env.temp_dir.path().to_string_lossy().to_string(),
+                labels: service_labels,
+                ..Default::default()
+            })
+            .await?;
+    }
+
+    assert_eq!(
+        listed_names(&manager, &[("team", "labels-payments")]).await?,
+        ["labels-api", "labels-worker"]
+    );
+    assert_eq!(
+        listed_names(&manager, &[("team", "labels-payments"), ("env", "prod")]).await?,
+        ["labels-api"]
+    );
+    assert!(listed_names(&manager, &[("team", "labels-unknown")]).await?.is_empty());
+
+    let status = manager
+        .get_status(&ServiceRef::Name("labels-web".to_string()))
+        .await?;
+    assert_eq!(
+        status.labels,
+        labels(&[("team", "labels-frontend"), ("env", "prod")])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::service_manager::log_parser::read_log_lines;
+use nexsock_config::ServerConfig;
+use tokio::sync::mpsc;
+
+/// Runs the line reader over a stream that yields `chunks` one read at a time, with the default
+/// line length limit.
+pub async fn collect_lines(chunks: &[&[u8]]) -> Vec<String> {
+    let max_line_bytes = ServerConfig::default().max_log_line_bytes as usize;
+
+    collect_lines_with_limit(chunks, max_line_bytes).await
+}
+
+/// Runs the line reader over a stream that yields `chunks` one read at a time.
+async fn collect_lines_with_limit(chunks: &[&[u8]], max_line_bytes: usize) -> Vec<String> {
+    let mut builder = tokio_test::io::Builder::new();
+    for chunk in chunks {
+        builder.read(chunk);
+    }
+
+    let (tx, mut rx) = mpsc::channel(100);
+    read_log_lines(builder.build(), tx, max_line_bytes).await;
+
+    let mut lines = Vec::new();
+    while let Some(line) = rx.recv().await {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[tokio::test]
+async fn test_lines_split_across_reads_are_reassembled() {
+    let lines = collect_lines(&[
+        b"server listen",
+        b"ing on :3000\nfirst request\nsecond",
+        b" request\n",
+        b"\n",
+        b"no trailing newline",
+    ])
+    .await;
+
+    assert_eq!(
+        lines,
+        vec![
+            "server listening on :3000\n",
+            "first request\n",
+            "second request\n",
+            "\n",
+            "no trailing newline",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_overlong_lines_are_truncated() {
+    // The line is read in two chunks, the limit is hit in the first
+    let first = "x".repeat(25);
+    let second = format!("{}\nnext\n", "x".repeat(15));
+    let lines = collect_lines_with_limit(&[first.as_bytes(), second.as_bytes()], 16).await;
+
+    assert_eq!(
+        lines,
+        vec![
+            format!("{}…[truncated 24 bytes]\n", "x".repeat(16)),
+            "next\n".to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_truncation_keeps_whole_characters() {
+    // The limit falls in the middle of the two byte `é`
+    let lines = collect_lines_with_limit(&["abcé tail".as_bytes()], 4).await;
+
+    assert_eq!(lines, vec!["abc…[truncated 7 bytes]"]);
+}
+
+#[tokio::test]
+async fn test_default_limit_truncates_multi_megabyte_line() {
+    let blob = format!("{}\n", "A".repeat(3 * 1024 * 1024));
+    let lines = collect_lines(&[blob.as_bytes()]).await;
+
+    let kept = ServerConfig::default().max_log_line_bytes as usize;
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with(&"A".repeat(kept)));
+    assert!(lines[0].ends_with(&format!("…[truncated {} bytes]\n", 3 * 1024 * 1024 - kept)));
+}
+
+#[tokio::test]
+async fn test_invalid_utf8_is_replaced() {
+    let lines = collect_lines(&[b"bad \xff byte\n"]).await;
+
+    assert_eq!(lines, vec!["bad \u{FFFD} byte\n"]);
+}
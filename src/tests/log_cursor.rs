@@ -0,0 +1,112 @@
+use crate::service_manager::log_parser::{
+    entries_after, parse_line, push_log_entry, LogBuffer, LogSettings, MAX_LOG_ENTRIES,
+};
+
+/// Stores every line of `lines` in `logs` the way the log collection task does.
+fn capture(logs: &mut LogBuffer, lines: &[&str]) {
+    for line in lines {
+        push_log_entry(logs, parse_line(&LogSettings::default(), line.to_string()));
+    }
+}
+
+/// Polls `logs` the way a client would, returning the new output and the next cursor.
+fn poll(logs: &LogBuffer, after_seq: Option<u64>) -> (String, u64) {
+    let (entries, last_seq) = entries_after(logs, after_seq);
+    let output = entries.map(|entry| entry.content.as_str()).collect();
+
+    (output, last_seq)
+}
+
+#[test]
+fn test_repeated_polls_return_only_new_lines() {
+    let mut logs = LogBuffer::default();
+
+    assert_eq!(poll(&logs, None), (String::new(), 0));
+
+    capture(&mut logs, &["first\n", "second\n"]);
+    let (output, cursor) = poll(&logs, None);
+    assert_eq!(output, "first\nsecond\n");
+    assert_eq!(cursor, 2);
+
+    // Nothing new has been written
+    assert_eq!(poll(&logs, Some(cursor)), (String::new(), 2));
+
+    capture(&mut logs, &["third\n"]);
+    let (output, cursor) = poll(&logs, Some(cursor));
+    assert_eq!(output, "third\n");
+    assert_eq!(cursor, 3);
+
+    capture(&mut logs, &["fourth\n", "fifth\n"]);
+    let (output, cursor) = poll(&logs, Some(cursor));
+    assert_eq!(output, "fourth\nfifth\n");
+    assert_eq!(cursor, 5);
+}
+
+#[test]
+fn test_polling_never_duplicates_lines() {
+    let mut logs = LogBuffer::default();
+    let mut cursor = None;
+    let mut received = Vec::new();
+
+    for batch in 0..50 {
+        let lines = (0..batch % 4)
+            .map(|line| format!("{batch}-{line}\n"))
+            .collect::<Vec<_>>();
+        capture(
+            &mut logs,
+            &lines.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+
+        let (entries, last_seq) = entries_after(&logs, cursor);
+        received.extend(entries.map(|entry| entry.seq));
+        cursor = Some(last_seq);
+    }
+
+    let expected = (1..=logs.len() as u64).collect::<Vec<_>>();
+    assert_eq!(received, expected);
+}
+
+#[test]
+fn test_sequence_survives_buffer_rotation() {
+    let mut logs = LogBuffer::default();
+    for line in 0..MAX_LOG_ENTRIES + 5 {
+        capture(&mut logs, &[&format!("{line}\n")]);
+    }
+
+    assert_eq!(logs.len(), MAX_LOG_ENTRIES);
+    assert_eq!(logs.front().map(|entry| entry.seq), Some(6));
+
+    let last = (MAX_LOG_ENTRIES + 5) as u64;
+    let (output, cursor) = poll(&logs, Some(last - 2));
+    assert_eq!(output, format!("{}\n{}\n", last - 2, last - 1));
+    assert_eq!(cursor, last);
+
+    // A cursor older than the buffer returns everything that is still stored
+    let (entries, _) = entries_after(&logs, Some(1));
+    assert_eq!(entries.count(), MAX_LOG_ENTRIES);
+}
+
+#[test]
+fn test_cursor_from_previous_process_returns_everything() {
+    let mut logs = LogBuffer::default();
+    capture(&mut logs, &["restarted\n"]);
+
+    assert_eq!(poll(&logs, Some(500)), ("restarted\n".to_string(), 1));
+}
+
+#[test]
+fn test_sequence_survives_clearing() {
+    let mut logs = LogBuffer::default();
+    capture(&mut logs, &["first\n", "second\n"]);
+    let (_, cursor) = poll(&logs, None);
+
+    logs.clear();
+    assert_eq!(poll(&logs, Some(cursor)), (String::new(), 2));
+
+    // Lines written after the clear are returned to a client still holding its old cursor
+    capture(&mut logs, &["third\n", "fourth\n"]);
+    assert_eq!(
+        poll(&logs, Some(cursor)),
+        ("third\nfourth\n".to_string(), 4)
+    );
+}
@@ -0,0 +1,200 @@
+use super::log_collection::collect_lines;
+use crate::service_manager::log_parser::{parse_line, render_logs, strip_ansi, LogSettings};
+use crate::service_manager::LogEntry;
+use chrono::{DateTime, Utc};
+use nexsock_protocol::commands::config::LogFormat;
+use nexsock_protocol::commands::stdout::LogLevel;
+
+/// Splits `chunks` into lines and parses every line with `settings`.
+async fn parse_with(settings: LogSettings, chunks: &[&[u8]]) -> Vec<LogEntry> {
+    collect_lines(chunks)
+        .await
+        .into_iter()
+        .map(|line| parse_line(&settings, line))
+        .collect()
+}
+
+/// Splits `chunks` into lines and parses every line as `format`, keeping escape sequences.
+async fn parse(format: LogFormat, chunks: &[&[u8]]) -> Vec<LogEntry> {
+    let settings = LogSettings {
+        format,
+        strip_ansi: false,
+        ..LogSettings::default()
+    };
+
+    parse_with(settings, chunks).await
+}
+
+#[tokio::test]
+async fn test_json_logs_filtered_by_min_level() {
+    // Lines are split across chunks the same way reads from a pipe would split them
+    let entries = parse(
+        LogFormat::Json,
+        &[
+            b"{\"level\":\"DEBUG\",\"msg\":\"connecting\"}\n{\"level\":\"info\",",
+            b"\"msg\":\"listening\"}\n{\"severity\":\"warning\",\"msg\":\"slow query\"}\n",
+            b"not json at all\n",
+            b"{\"lvl\":\"error\",\"msg\":\"connection lost\"}",
+        ],
+    )
+    .await;
+
+    let levels = entries.iter().map(|entry| entry.level).collect::<Vec<_>>();
+    assert_eq!(
+        levels,
+        vec![
+            Some(LogLevel::Debug),
+            Some(LogLevel::Info),
+            Some(LogLevel::Warn),
+            None,
+            Some(LogLevel::Error),
+        ]
+    );
+
+    assert_eq!(
+        render_logs(&entries, Some(LogLevel::Warn), false),
+        "{\"severity\":\"warning\",\"msg\":\"slow query\"}\n\
+         not json at all\n\
+         {\"lvl\":\"error\",\"msg\":\"connection lost\"}"
+    );
+    assert_eq!(render_logs(&entries, None, false).lines().count(), 5);
+}
+
+#[tokio::test]
+async fn test_text_logs_use_level_keywords() {
+    let entries = parse(
+        LogFormat::Text,
+        &[
+            b"2026-10-14T10:00:00Z INFO server: started\n",
+            b"2026-10-14T10:00:01Z WARN pool: 9/10 connections in use\n",
+            b"[ERROR] request failed\nplain line\n",
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        render_logs(&entries, Some(LogLevel::Error), false),
+        "[ERROR] request failed\nplain line\n"
+    );
+}
+
+#[tokio::test]
+async fn test_raw_logs_are_never_filtered() {
+    let entries = parse(LogFormat::Raw, &[b"ERROR half a li", b"ne\nDEBUG more\n"]).await;
+
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry.level.is_none()));
+    assert_eq!(
+        render_logs(&entries, Some(LogLevel::Error), false),
+        "ERROR half a line\nDEBUG more\n"
+    );
+}
+
+#[test]
+fn test_strip_ansi_removes_escape_sequences() {
+    // SGR colors and resets
+    assert_eq!(
+        strip_ansi("\x1b[1;33mWARN\x1b[0m pool nearly exhausted\n"),
+        "WARN pool nearly exhausted\n"
+    );
+    assert_eq!(strip_ansi("\x1b[38;5;208morange\x1b[m"), "orange");
+
+    // Cursor moves and line clearing used by progress bars
+    assert_eq!(
+        strip_ansi("\x1b[2K\x1b[1Gbuilding \x1b[3A[42/100]\x1b[?25l"),
+        "building [42/100]"
+    );
+
+    // OSC hyperlinks and two byte escapes
+    assert_eq!(
+        strip_ansi("\x1b]8;;https://example.com\x07docs\x1b]8;;\x1b\\ \x1b7saved\x1b8"),
+        "docs saved"
+    );
+
+    assert!(matches!(
+        strip_ansi("plain line\n"),
+        std::borrow::Cow::Borrowed("plain line\n")
+    ));
+}
+
+#[tokio::test]
+async fn test_stripped_logs_keep_raw_form() {
+    let settings = LogSettings {
+        format: LogFormat::Text,
+        strip_ansi: true,
+        ..LogSettings::default()
+    };
+    let entries = parse_with(
+        settings,
+        &[b"\x1b[32mINFO\x1b[0m ready\n\x1b[31mERROR\x1b[0m failed\n"],
+    )
+    .await;
+
+    assert_eq!(
+        render_logs(&entries, Some(LogLevel::Error), false),
+        "ERROR failed\n"
+    );
+    assert_eq!(
+        render_logs(&entries, Some(LogLevel::Error), true),
+        "\x1b[31mERROR\x1b[0m failed\n"
+    );
+}
+
+#[tokio::test]
+async fn test_colored_levels_detected_without_stripping() {
+    let entries = parse(LogFormat::Text, &[b"\x1b[33mWARN\x1b[0m slow\n"]).await;
+
+    assert_eq!(entries[0].level, Some(LogLevel::Warn));
+    assert_eq!(entries[0].content, "\x1b[33mWARN\x1b[0m slow\n");
+    assert!(entries[0].raw.is_none());
+}
+
+#[tokio::test]
+async fn test_embedded_timestamp_used_as_entry_time() {
+    let settings = LogSettings {
+        timestamp_format: Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()),
+        ..LogSettings::default()
+    };
+    let entries = parse_with(
+        settings,
+        &[b"2026-10-15T09:30:00.250+02:00 INFO ready\n\x1b[2m2026-10-15T09:30:01+02:00\x1b[0m done\n"],
+    )
+    .await;
+
+    assert_eq!(
+        entries[0].timestamp,
+        "2026-10-15T07:30:00.250Z".parse::<DateTime<Utc>>().unwrap()
+    );
+    assert_eq!(
+        entries[1].timestamp,
+        "2026-10-15T07:30:01Z".parse::<DateTime<Utc>>().unwrap()
+    );
+    // The line itself is stored as it was written
+    assert_eq!(
+        entries[0].content,
+        "2026-10-15T09:30:00.250+02:00 INFO ready\n"
+    );
+}
+
+#[tokio::test]
+async fn test_lines_without_timestamp_use_capture_time() {
+    let settings = LogSettings {
+        timestamp_format: Some("%Y-%m-%d %H:%M:%S".to_string()),
+        ..LogSettings::default()
+    };
+
+    let before = Utc::now();
+    let entries = parse_with(
+        settings,
+        &[b"2026-10-15 09:30:00 started\nlistening on 8080\n"],
+    )
+    .await;
+    let after = Utc::now();
+
+    // A format without an offset is read as UTC
+    assert_eq!(
+        entries[0].timestamp,
+        "2026-10-15T09:30:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+    assert!((before..=after).contains(&entries[1].timestamp));
+}
@@ -0,0 +1,80 @@
+use super::common::*;
+use crate::daemon::connection::Connection;
+use anyhow::Result;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::header::MessageFlags;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{duplex, split};
+
+const PLUGIN: &str = r#"
+local started = nil
+
+function pre_command(command, payload)
+    if command == "StartService" then
+        started = payload.Name .. " with PORT=" .. payload.env_vars.PORT
+    end
+end
+
+function started_service()
+    return started
+end
+"#;
+
+#[tokio::test]
+async fn test_lua_pre_command_receives_the_payload() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let dir = tempfile::tempdir()?;
+    let plugin = dir.path().join("inspect.lua");
+    std::fs::write(&plugin, PLUGIN)?;
+
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+    lua_plugin_manager.load_plugins_from(dir.path()).await?;
+
+    let (mut client, server) = duplex(64 * 1024);
+    let (reader, writer) = split(server);
+    let mut connection = Connection::from_parts(
+        reader,
+        writer,
+        lua_plugin_manager.clone(),
+        KeepaliveConfig::disabled(),
+    );
+    let handle = tokio::spawn(async move { connection.handle().await });
+
+    let payload = StartServicePayload {
+        service: ServiceRef::Name("webapp".to_string()),
+        env_vars: HashMap::from([("PORT".to_string(), "8080".to_string())]),
+        ..Default::default()
+    };
+
+    let mut protocol = Protocol::default();
+    protocol.next_request_id();
+    protocol
+        .write_command_with_payload(
+            &mut client,
+            Command::StartService,
+            &payload,
+            MessageFlags::NONE,
+        )
+        .await?;
+
+    // The service doesn't exist, the hook runs before the command fails all the same
+    let (header, _) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Error));
+
+    let started = lua_plugin_manager.call_function(&plugin, "started_service", Vec::new())?;
+    assert!(
+        matches!(&started, SerializableLuaValue::String(started) if started == "webapp with PORT=8080"),
+        "{started:?}"
+    );
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
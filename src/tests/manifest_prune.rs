@@ -0,0 +1,50 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_protocol::commands::manifest::{
+    ApplyManifestPayload, ManifestService, ServiceManifest,
+};
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_failed_prune_leaves_services_running() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let service_id = save_service_with_command(&env, "prune-running", "exec sleep 30").await?;
+
+    let process = manager
+        .spawn_service_process(
+            service_id,
+            env.test_env.temp_dir.path(),
+            "exec sleep 30",
+            HashMap::new(),
+            LogSettings::default(),
+        )
+        .await?;
+    manager.running_services().insert(service_id, process);
+
+    // Listing a service twice makes the manifest invalid, so nothing may be pruned
+    let declared = ManifestService {
+        name: "prune-declared".to_string(),
+        ..Default::default()
+    };
+    let result = manager
+        .apply_manifest(&ApplyManifestPayload {
+            manifest: ServiceManifest {
+                services: vec![declared.clone(), declared],
+            },
+            prune: true,
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(manager.get_service_state(service_id), ServiceState::Running);
+
+    manager.kill_service_process(service_id).await?;
+
+    Ok(())
+}
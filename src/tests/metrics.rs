@@ -0,0 +1,103 @@
+use super::common::*;
+use anyhow::Result;
+use bincode::Encode;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::metrics::DaemonMetrics;
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::header::MessageFlags;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::fmt::Debug;
+use tokio::io::DuplexStream;
+
+/// Sends `command` with `payload`, returning the command of the answer.
+async fn send<T: Encode + Debug>(
+    protocol: &mut Protocol,
+    client: &mut DuplexStream,
+    command: Command,
+    payload: &T,
+) -> Result<Command> {
+    protocol
+        .write_command_with_payload(client, command, payload, MessageFlags::HAS_PAYLOAD)
+        .await?;
+    let (header, _) = protocol.read_message(client).await?;
+
+    Ok(header.command)
+}
+
+/// Exports the metrics of the daemon.
+async fn export_metrics(
+    protocol: &mut Protocol,
+    client: &mut DuplexStream,
+) -> Result<DaemonMetrics> {
+    protocol
+        .write_command(client, Command::ExportMetrics)
+        .await?;
+    let (header, payload) = protocol.read_message(client).await?;
+    assert!(matches!(header.command, Command::Success));
+
+    let payload = Protocol::read_payload::<CommandPayload>(&payload.unwrap())?.unwrap();
+
+    DaemonMetrics::try_from(payload)
+}
+
+// The counters are shared with the tests running alongside, so they are only checked to have
+// grown by at least the activity of this test
+#[tokio::test]
+async fn test_metrics_count_commands_and_lifecycle() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "metrics-api", "exec sleep 30").await?;
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    let before = export_metrics(&mut protocol, &mut client).await?;
+
+    for _ in 0..3 {
+        protocol.write_command(&mut client, Command::Ping).await?;
+        let (header, _) = protocol.read_message(&mut client).await?;
+        assert!(matches!(header.command, Command::Success));
+    }
+
+    let missing = ServiceRef::Name("metrics-missing".to_string());
+    let answer = send(
+        &mut protocol,
+        &mut client,
+        Command::GetServiceStatus,
+        &missing,
+    )
+    .await?;
+    assert!(matches!(answer, Command::Error));
+
+    let start = StartServicePayload {
+        service: ServiceRef::Id(service_id),
+        ..Default::default()
+    };
+    let answer = send(&mut protocol, &mut client, Command::StartService, &start).await?;
+    assert!(matches!(answer, Command::Success));
+
+    let running = export_metrics(&mut protocol, &mut client).await?;
+    assert!(running.running_services >= 1);
+
+    let answer = send(
+        &mut protocol,
+        &mut client,
+        Command::StopService,
+        &start.service,
+    )
+    .await?;
+    assert!(matches!(answer, Command::Success));
+
+    let after = export_metrics(&mut protocol, &mut client).await?;
+
+    // 3 pings, the failed status, the start, the stop and the export in between
+    assert!(after.commands_handled >= before.commands_handled + 7);
+    assert!(after.commands_failed > before.commands_failed);
+    assert!(after.services_started > before.services_started);
+    assert!(after.services_stopped > before.services_stopped);
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
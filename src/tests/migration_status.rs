@@ -0,0 +1,32 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::migration::MigrationStatusResponse;
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+
+#[tokio::test]
+async fn test_migration_status_lists_applied_migrations() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    protocol
+        .write_command(&mut client, Command::MigrationStatus)
+        .await?;
+    let (header, payload) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Success));
+
+    let payload = Protocol::read_payload::<CommandPayload>(&payload.unwrap())?.unwrap();
+    let status = MigrationStatusResponse::try_from(payload)?;
+
+    // The test database is migrated when it is initialized
+    assert!(!status.migrations.is_empty());
+    assert_eq!(status.pending().count(), 0);
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
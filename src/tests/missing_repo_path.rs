@@ -0,0 +1,114 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::path::Path;
+
+/// Saves a service that keeps running from `repo_path`, cloned from `repo_url`.
+async fn save_service(
+    env: &DaemonTestEnvironment,
+    name: &str,
+    repo_url: &str,
+    repo_path: &Path,
+) -> Result<i64> {
+    let id = save_service_with_command(env, name, "exec sleep 30").await?;
+
+    let repository = ServiceRepository::new_from_static();
+    let mut service = repository
+        .get_by_id(id)
+        .await?
+        .expect("The service was just saved");
+    service.repo_url = repo_url.to_string();
+    service.repo_path = repo_path.to_string_lossy().to_string();
+    repository.save(&mut service).await?;
+
+    Ok(id)
+}
+
+#[tokio::test]
+async fn test_start_reports_missing_repo_path() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let repo_path = env.test_env.temp_dir.path().join("missing");
+    let id = save_service(&env, "missing-repo", "", &repo_path).await?;
+
+    let payload = StartServicePayload {
+        service: ServiceRef::Id(id),
+        // There is nothing to clone from, so the flag can't help
+        clone_missing: true,
+        ..Default::default()
+    };
+    let error = manager.start(&payload).await.unwrap_err();
+
+    assert!(matches!(error, crate::Error::MissingRepoPath { .. }));
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "The repository path `{}` of service `missing-repo` does not exist",
+            repo_path.display()
+        )
+    );
+    assert_eq!(manager.get_status(&payload.service).await?.state, ServiceState::Stopped);
+
+    Ok(())
+}
+
+/// Creates a repository with one commit in `path` to clone services from.
+#[cfg(all(unix, feature = "git"))]
+async fn init_git_fixture(path: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(path).await?;
+    tokio::fs::write(path.join("README.md"), "fixture\n").await?;
+
+    for args in [
+        &["init", "--quiet"][..],
+        &["add", "README.md"],
+        &["commit", "--quiet", "-m", "Initial commit"],
+    ] {
+        let output = tokio::process::Command::new("git")
+            .args(["-c", "user.name=nexsock", "-c", "user.email=nexsock@localhost"])
+            .args(args)
+            .current_dir(path)
+            .output()
+            .await?;
+
+        anyhow::ensure!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[tokio::test]
+async fn test_start_clones_missing_repo() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let remote = env.test_env.temp_dir.path().join("remote");
+    init_git_fixture(&remote).await?;
+
+    let repo_path = env.test_env.temp_dir.path().join("clone");
+    let id = save_service(&env, "cloned-repo", &remote.to_string_lossy(), &repo_path).await?;
+
+    let payload = StartServicePayload {
+        service: ServiceRef::Id(id),
+        clone_missing: true,
+        ..Default::default()
+    };
+    manager.start(&payload).await?;
+
+    assert!(repo_path.join(".git").is_dir());
+    assert_eq!(tokio::fs::read_to_string(repo_path.join("README.md")).await?, "fixture\n");
+    assert_eq!(manager.get_status(&payload.service).await?.state, ServiceState::Running);
+
+    manager.stop(&payload.service).await?;
+
+    Ok(())
+}
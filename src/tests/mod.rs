@@ -1,4 +1,85 @@
+pub mod access_log;
+pub mod add_service_builder;
 pub mod basic_daemon;
+pub mod capabilities;
+#[cfg(unix)]
+pub mod capture_output;
+pub mod checksum;
+#[cfg(unix)]
+pub mod clear_logs;
+#[cfg(unix)]
+pub mod client_streams;
+pub mod clone_service;
 pub mod common;
+pub mod config_file;
+pub mod connection_limit;
+pub mod debug_dump;
+#[cfg(unix)]
+pub mod dependency_ready;
+#[cfg(unix)]
+pub mod dependency_state;
+pub mod event_bus;
+pub mod git_conversions;
+pub mod idle_timeout;
+pub mod json_rpc;
+pub mod labels;
+pub mod keepalive;
+pub mod log_collection;
+pub mod log_cursor;
+pub mod log_filter;
+pub mod lua_pre_command;
 pub mod managers_basic;
+#[cfg(unix)]
+pub mod manifest_prune;
+#[cfg(unix)]
+pub mod metrics;
+pub mod migration_status;
+pub mod missing_repo_path;
+pub mod native_plugin_version;
+pub mod payload_version;
+#[cfg(unix)]
+pub mod plugin_concurrency;
+pub mod plugin_config;
+pub mod plugin_loading;
+#[cfg(unix)]
+pub mod plugin_sandbox;
+pub mod plugin_timeout;
+pub mod port_free;
+#[cfg(target_os = "linux")]
+pub mod process_group;
+#[cfg(unix)]
+pub mod profile;
+#[cfg(unix)]
+pub mod ready_wait;
+#[cfg(unix)]
+pub mod removal_plan;
+pub mod remove_dependents;
+pub mod request_id;
+pub mod resources;
+pub mod run_command_template;
+pub mod search;
+#[cfg(target_os = "linux")]
+pub mod server_loop;
+#[cfg(unix)]
+pub mod subscribe;
 pub mod service_basic;
+pub mod service_ref;
+pub mod service_state;
+pub mod shutdown_signal;
+#[cfg(unix)]
+pub mod startup_failure;
+#[cfg(unix)]
+pub mod startup_timeout;
+pub mod state_transitions;
+#[cfg(unix)]
+pub mod stop_signal;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod truncated_message;
+#[cfg(unix)]
+pub mod typed_client;
+pub mod validate_service;
+#[cfg(unix)]
+pub mod write_stdin;
+#[cfg(target_os = "linux")]
+pub mod zombie_reap;
@@ -0,0 +1,81 @@
+use anyhow::{ensure, Result};
+use nexsock_abi::{PreHook, ABI_VERSION};
+use nexsock_plugins::native::{
+    check_abi_version, external_native_plugins_in, read_abi_version, ABI_VERSION_SYMBOL,
+};
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::{Path, PathBuf};
+
+/// Builds a library in `dir` that exports `abi_version` as its ABI version, returning its path.
+fn build_plugin_library(dir: &Path, abi_version: u32) -> Result<PathBuf> {
+    let source = dir.join("plugin.rs");
+    std::fs::write(
+        &source,
+        format!(
+            "#[no_mangle]\npub extern \"C\" fn {ABI_VERSION_SYMBOL}() -> u32 {{ {abi_version} }}\n"
+        ),
+    )?;
+
+    let path = dir.join(format!("{DLL_PREFIX}plugin{DLL_SUFFIX}"));
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let status = std::process::Command::new(rustc)
+        .args(["--crate-type", "cdylib", "--crate-name", "plugin", "-o"])
+        .arg(&path)
+        .arg(&source)
+        .status()?;
+    ensure!(status.success(), "Failed to build the test plugin library");
+
+    Ok(path)
+}
+
+#[test]
+fn test_matching_abi_version_is_accepted() {
+    assert!(check_abi_version(Some(ABI_VERSION), ABI_VERSION).is_ok());
+}
+
+#[test]
+fn test_mismatched_abi_version_is_rejected() {
+    let error = check_abi_version(Some(ABI_VERSION + 1), ABI_VERSION)
+        .expect_err("A plugin built against another ABI must be rejected");
+    let message = error.to_string();
+
+    assert!(
+        message.contains(&format!("ABI version {}", ABI_VERSION + 1)),
+        "{message}"
+    );
+    assert!(
+        message.contains(&format!("expects version {ABI_VERSION}")),
+        "{message}"
+    );
+
+    let error = check_abi_version(None, ABI_VERSION)
+        .expect_err("A plugin without an ABI version must be rejected");
+    assert!(error.to_string().contains("doesn't export"), "{error}");
+}
+
+#[test]
+fn test_file_that_is_not_a_library_is_rejected() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("plugin.so");
+    std::fs::write(&path, "not a shared library")?;
+
+    assert!(read_abi_version(&path).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_library_with_mismatched_abi_version_is_skipped() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = build_plugin_library(dir.path(), ABI_VERSION + 1)?;
+
+    assert_eq!(read_abi_version(&path)?, Some(ABI_VERSION + 1));
+
+    let plugins = external_native_plugins_in::<dyn PreHook>(dir.path(), ABI_VERSION).await?;
+    assert!(
+        plugins.is_empty(),
+        "The plugin must be skipped before loading it"
+    );
+
+    Ok(())
+}
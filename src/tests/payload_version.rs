@@ -0,0 +1,84 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::error::ErrorPayload;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::header::{MessageFlags, MessageHeader};
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::{PayloadVersionMismatch, Protocol, PAYLOAD_VERSION};
+use tokio::io::AsyncWriteExt;
+
+/// Encodes a payload and retags it with `version`.
+fn payload_with_version(version: u8) -> Result<Vec<u8>> {
+    let mut payload = Protocol::encode_payload(&ServiceRef::Name("versioned".to_string()))?;
+    payload[0] = version;
+
+    Ok(payload)
+}
+
+#[tokio::test]
+async fn test_payload_is_tagged_with_version() -> Result<()> {
+    let payload = Protocol::encode_payload(&ServiceRef::Id(7))?;
+    assert_eq!(payload[0], PAYLOAD_VERSION);
+
+    let decoded = Protocol::read_payload::<ServiceRef>(&payload)?;
+    assert!(matches!(decoded, Some(ServiceRef::Id(7))));
+
+    // Payloads encoding to nothing carry no version
+    assert!(Protocol::encode_payload(&())?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_payload_of_other_version_is_rejected() -> Result<()> {
+    for version in [PAYLOAD_VERSION - 1, PAYLOAD_VERSION + 1] {
+        let err = Protocol::read_payload::<ServiceRef>(&payload_with_version(version)?)
+            .expect_err("A payload of another version should be rejected");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(PayloadVersionMismatch::is(&err), "{err}");
+        assert!(
+            err.to_string().contains("payload version mismatch"),
+            "{err}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_daemon_rejects_payload_of_other_version() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    // Send a request as a client built against a newer schema would
+    let mut frame = Vec::new();
+    protocol
+        .write_command_with_payload(
+            &mut frame,
+            Command::GetServiceStatus,
+            &ServiceRef::Name("versioned".to_string()),
+            MessageFlags::NONE,
+        )
+        .await?;
+    frame[MessageHeader::ENCODED_LEN] = PAYLOAD_VERSION + 1;
+    client.write_all(&frame).await?;
+
+    let (header, payload) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Error));
+
+    let error: ErrorPayload = Protocol::read_payload(&payload.unwrap_or_default())?
+        .expect("Rejection should carry an error payload");
+    assert!(
+        error.message.contains("payload version mismatch"),
+        "{}",
+        error.message
+    );
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
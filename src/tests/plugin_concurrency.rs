@@ -0,0 +1,38 @@
+use anyhow::Result;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn test_plugins_are_called_concurrently() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let slow = dir.path().join("slow.lua");
+    let fast = dir.path().join("fast.lua");
+    std::fs::write(
+        &slow,
+        "function handle()\n    os.execute(\"sleep 0.8\")\n    return \"slow\"\nend\n",
+    )?;
+    std::fs::write(
+        &fast,
+        "function handle()\n    os.execute(\"sleep 0.4\")\n    return \"fast\"\nend\n",
+    )?;
+
+    let manager = LuaPluginManager::new()?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    let started = Instant::now();
+    let mut results = manager.call_function_on_all("handle", Vec::new())?;
+    let elapsed = started.elapsed();
+
+    // Run one after another the calls would take 1.2 seconds
+    assert!(elapsed >= Duration::from_millis(800), "{elapsed:?}");
+    assert!(elapsed < Duration::from_millis(1100), "{elapsed:?}");
+
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(results[0].0, fast);
+    assert!(matches!(&results[0].1, Ok(SerializableLuaValue::String(result)) if result == "fast"));
+    assert_eq!(results[1].0, slow);
+    assert!(matches!(&results[1].1, Ok(SerializableLuaValue::String(result)) if result == "slow"));
+
+    Ok(())
+}
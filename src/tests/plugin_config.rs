@@ -0,0 +1,66 @@
+use anyhow::Result;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
+
+#[tokio::test]
+async fn test_plugin_reads_its_config() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let plugin = dir.path().join("notify.lua");
+    let unconfigured = dir.path().join("unconfigured.lua");
+    std::fs::write(
+        &plugin,
+        "function describe()\n    \
+             local config = nexsock.config\n    \
+             return config.channel .. \":\" .. config.retries .. \":\" .. config.targets[2]\n\
+         end\n",
+    )?;
+    std::fs::write(
+        dir.path().join("notify.toml"),
+        "channel = \"#deploys\"\nretries = 3\ntargets = [\"web\", \"api\"]\n",
+    )?;
+    std::fs::write(
+        &unconfigured,
+        "function has_config()\n    return next(nexsock.config) ~= nil\nend\n",
+    )?;
+
+    let manager = LuaPluginManager::new()?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    // The config file isn't loaded as a plugin
+    assert_eq!(
+        manager.loaded_plugins(),
+        [plugin.clone(), unconfigured.clone()]
+    );
+    assert!(manager.load_errors().is_empty());
+
+    assert!(matches!(
+        manager.call_function(&plugin, "describe", Vec::new())?,
+        SerializableLuaValue::String(description) if description == "#deploys:3:api"
+    ));
+    assert!(matches!(
+        manager.call_function(&unconfigured, "has_config", Vec::new())?,
+        SerializableLuaValue::Boolean(false)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_plugin_config_fails_the_plugin() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let plugin = dir.path().join("broken.lua");
+    std::fs::write(&plugin, "function run()\n    return 1\nend\n")?;
+    std::fs::write(dir.path().join("broken.toml"), "channel = \n")?;
+
+    let manager = LuaPluginManager::new()?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    assert!(manager.loaded_plugins().is_empty());
+
+    let errors = manager.load_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, plugin);
+    assert!(errors[0].1.contains("broken.toml"), "{}", errors[0].1);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::daemon::debug_dump::debug_dump;
+use anyhow::Result;
+use dashmap::DashMap;
+use nexsock_config::NEXSOCK_CONFIG;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
+
+#[tokio::test]
+async fn test_broken_plugin_does_not_keep_others_from_loading() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let valid = dir.path().join("valid.lua");
+    let broken = dir.path().join("broken.lua");
+    std::fs::write(&valid, "function greet()\n    return \"hello\"\nend\n")?;
+    std::fs::write(&broken, "function greet(\n    return\n")?;
+
+    let manager = LuaPluginManager::new()?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    assert_eq!(manager.loaded_plugins(), [valid.clone()]);
+    assert!(matches!(
+        manager.call_function(&valid, "greet", Vec::new())?,
+        SerializableLuaValue::String(greeting) if greeting == "hello"
+    ));
+
+    let errors = manager.load_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, broken);
+    assert!(!errors[0].1.is_empty());
+
+    // Functions are only called on the plugins that loaded
+    let results = manager.call_function_on_all("greet", Vec::new())?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, valid);
+
+    let dump = debug_dump(
+        &NEXSOCK_CONFIG,
+        &DashMap::new(),
+        manager.loaded_plugins(),
+        manager.load_errors(),
+        [],
+    );
+    assert_eq!(dump.lua_plugins, [valid.display().to_string()]);
+    assert!(dump
+        .failed_lua_plugins
+        .contains_key(&broken.display().to_string()));
+
+    Ok(())
+}
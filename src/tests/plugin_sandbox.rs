@@ -0,0 +1,65 @@
+use anyhow::Result;
+use nexsock_config::PluginsConfig;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
+
+const PLUGIN: &str = "function run()\n    return os.execute(\"true\")\nend\n\n\
+                      function has_io()\n    return io ~= nil\nend\n\n\
+                      function now()\n    return os.time()\nend\n";
+
+#[tokio::test]
+async fn test_sandboxed_plugin_cannot_call_os_execute() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let sandboxed = dir.path().join("sandboxed.lua");
+    let trusted = dir.path().join("trusted.lua");
+    std::fs::write(&sandboxed, PLUGIN)?;
+    std::fs::write(&trusted, PLUGIN)?;
+
+    let manager = LuaPluginManager::with_config(PluginsConfig {
+        sandbox: true,
+        trusted: vec!["trusted.lua".to_string()],
+        ..PluginsConfig::default()
+    })?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    let error = manager
+        .call_function(&sandboxed, "run", Vec::new())
+        .expect_err("A sandboxed plugin must not run commands");
+    assert!(error.to_string().contains("execute"), "{error}");
+    assert!(matches!(
+        manager.call_function(&sandboxed, "has_io", Vec::new())?,
+        SerializableLuaValue::Boolean(false)
+    ));
+    assert!(matches!(
+        manager.call_function(&sandboxed, "now", Vec::new())?,
+        SerializableLuaValue::Integer(_)
+    ));
+
+    assert!(matches!(
+        manager.call_function(&trusted, "run", Vec::new())?,
+        SerializableLuaValue::Boolean(true)
+    ));
+    assert!(matches!(
+        manager.call_function(&trusted, "has_io", Vec::new())?,
+        SerializableLuaValue::Boolean(true)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_plugins_are_not_sandboxed_by_default() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let plugin = dir.path().join("plugin.lua");
+    std::fs::write(&plugin, PLUGIN)?;
+
+    let manager = LuaPluginManager::new()?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    assert!(matches!(
+        manager.call_function(&plugin, "run", Vec::new())?,
+        SerializableLuaValue::Boolean(true)
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use nexsock_config::PluginsConfig;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_plugins::lua::SerializableLuaValue;
+
+#[tokio::test]
+async fn test_looping_plugin_call_is_interrupted() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let looping = dir.path().join("looping.lua");
+    let valid = dir.path().join("valid.lua");
+    let looping_on_load = dir.path().join("looping_on_load.lua");
+    std::fs::write(&looping, "function handle()\n    while true do end\nend\n")?;
+    std::fs::write(&valid, "function handle()\n    return 1\nend\n")?;
+    std::fs::write(&looping_on_load, "while true do end\n")?;
+
+    let manager = LuaPluginManager::with_config(PluginsConfig {
+        call_timeout_ms: 100,
+        ..PluginsConfig::default()
+    })?;
+    manager.load_plugins_from(dir.path()).await?;
+
+    let errors = manager.load_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, looping_on_load);
+    assert!(errors[0].1.contains("timed out"), "{}", errors[0].1);
+
+    let mut results = manager.call_function_on_all("handle", Vec::new())?;
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(results.len(), 2);
+
+    let (path, result) = &results[0];
+    assert_eq!(path, &looping);
+    let error = result
+        .as_ref()
+        .expect_err("The looping call must be aborted");
+    assert!(error.to_string().contains("timed out"), "{error}");
+
+    let (path, result) = &results[1];
+    assert_eq!(path, &valid);
+    assert!(matches!(result, Ok(SerializableLuaValue::Integer(1))));
+
+    Ok(())
+}
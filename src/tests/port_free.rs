@@ -0,0 +1,65 @@
+use crate::error::Error;
+use crate::traits::process_manager::wait_for_port_free;
+use anyhow::Result;
+use std::net::TcpListener;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[tokio::test]
+async fn test_free_port_returns_immediately() -> Result<()> {
+    let port = TcpListener::bind(("0.0.0.0", 0))?.local_addr()?.port();
+
+    let started = Instant::now();
+    wait_for_port_free(port, Duration::from_secs(5)).await?;
+
+    assert!(
+        started.elapsed() < Duration::from_millis(100),
+        "A free port should not be waited on, took {:?}",
+        started.elapsed()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_port_freed_while_waiting_is_noticed() -> Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", 0))?;
+    let port = listener.local_addr()?.port();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(listener);
+    });
+
+    let started = Instant::now();
+    wait_for_port_free(port, Duration::from_secs(5)).await?;
+
+    // Well below the 500ms the port used to be polled at
+    assert!(
+        started.elapsed() < Duration::from_millis(400),
+        "The released port should be noticed quickly, took {:?}",
+        started.elapsed()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_port_still_in_use_times_out() -> Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", 0))?;
+    let port = listener.local_addr()?.port();
+
+    let started = Instant::now();
+    let result = wait_for_port_free(port, Duration::from_millis(200)).await;
+
+    assert!(
+        matches!(result, Err(Error::PortNotFreed { port: p }) if p == port),
+        "Expected PortNotFreed, got {result:?}"
+    );
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    drop(listener);
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::process_group::live_members;
+use crate::service_manager::resources::parse_stat;
+use crate::traits::process_manager::FullProcessManager;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Forks a child that ignores SIGTERM and outlives the script once it is stopped.
+const FORKS_A_CHILD: &str =
+    "(trap '' TERM; exec sleep 30) & echo $! > child.pid; while :; do sleep 0.1; done";
+
+/// Whether `pid` is running, exited processes nobody reaped yet count as gone.
+fn is_running(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|line| parse_stat(&line))
+        .is_some_and(|stat| stat.state != 'Z')
+}
+
+#[tokio::test]
+async fn test_stop_kills_the_children_of_a_service() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let path = env.test_env.temp_dir.path();
+
+    let mut process = manager
+        .spawn_service_process(1, path, FORKS_A_CHILD, HashMap::new(), LogSettings::default())
+        .await?;
+    let pid = process.process.id().unwrap();
+
+    let mut child = None;
+    for _ in 0..50 {
+        child = std::fs::read_to_string(path.join("child.pid"))
+            .ok()
+            .and_then(|pid| pid.trim().parse::<u32>().ok());
+        if child.is_some() {
+            break;
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+    let child = child.expect("the script should have written the pid of its child");
+
+    assert!(is_running(pid));
+    assert!(is_running(child));
+    assert_eq!(live_members(pid).map(|members| members.len() >= 2), Some(true));
+
+    manager.cleanup_process(1, &mut process).await?;
+
+    assert!(!is_running(pid));
+    assert!(!is_running(child), "the child of the service outlived it");
+    assert_eq!(live_members(pid), Some(Vec::new()));
+
+    Ok(())
+}
+
+#[test]
+fn test_live_members_of_unknown_group() {
+    assert_eq!(live_members(u32::MAX), Some(Vec::new()));
+}
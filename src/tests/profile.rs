@@ -0,0 +1,105 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::profile::ProfilePayload;
+use nexsock_protocol::commands::service_status::ServiceState;
+
+/// Saves a long running service in `profile`, returning its id.
+async fn save_service(
+    env: &DaemonTestEnvironment,
+    name: &str,
+    profile: Option<&str>,
+) -> Result<i64> {
+    let id = save_service_with_command(env, name, "exec sleep 30").await?;
+
+    let repository = ServiceRepository::new_from_static();
+    let mut service = repository
+        .get_by_id(id)
+        .await?
+        .expect("The service was just saved");
+    service.profile = profile.map(str::to_string);
+    repository.save(&mut service).await?;
+
+    Ok(id)
+}
+
+fn profile(name: &str) -> ProfilePayload {
+    ProfilePayload {
+        profile: name.to_string(),
+    }
+}
+
+async fn state(manager: &ServiceManager, id: i64) -> Result<ServiceState> {
+    Ok(manager.get_state(&ServiceRef::Id(id)).await?)
+}
+
+#[tokio::test]
+async fn test_profile_operations_only_affect_tagged_services() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let api = save_service(&env, "profile-api", Some("profile-backend")).await?;
+    let worker = save_service(&env, "profile-worker", Some("profile-backend")).await?;
+    let web = save_service(&env, "profile-web", Some("profile-frontend")).await?;
+    let untagged = save_service(&env, "profile-untagged", None).await?;
+
+    let listed = manager.list_by_profile(&profile("profile-backend")).await?;
+    let names = listed
+        .services
+        .iter()
+        .map(|service| service.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["profile-api", "profile-worker"]);
+
+    manager.start_profile(&profile("profile-backend")).await?;
+
+    assert_eq!(state(&manager, api).await?, ServiceState::Running);
+    assert_eq!(state(&manager, worker).await?, ServiceState::Running);
+    assert_eq!(state(&manager, web).await?, ServiceState::Stopped);
+    assert_eq!(state(&manager, untagged).await?, ServiceState::Stopped);
+
+    let listed = manager.list_by_profile(&profile("profile-backend")).await?;
+    assert!(listed
+        .services
+        .iter()
+        .all(|service| service.state == ServiceState::Running));
+
+    // Starting a profile again leaves its running services alone
+    manager.start_profile(&profile("profile-backend")).await?;
+
+    manager
+        .start(&StartServicePayload {
+            service: ServiceRef::Id(web),
+            ..Default::default()
+        })
+        .await?;
+
+    manager.stop_profile(&profile("profile-backend")).await?;
+
+    assert_eq!(state(&manager, api).await?, ServiceState::Stopped);
+    assert_eq!(state(&manager, worker).await?, ServiceState::Stopped);
+    assert_eq!(state(&manager, web).await?, ServiceState::Running);
+
+    manager.stop(&ServiceRef::Id(web)).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_profile() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    assert!(manager.start_profile(&profile("profile-unknown")).await.is_err());
+    assert!(manager.stop_profile(&profile("profile-unknown")).await.is_err());
+    assert!(manager
+        .list_by_profile(&profile("profile-unknown"))
+        .await?
+        .services
+        .is_empty());
+
+    Ok(())
+}
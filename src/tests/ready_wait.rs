@@ -0,0 +1,155 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{ReadyCondition, ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+
+/// Saves a service running `run_command`, returning its id and port.
+async fn save_service(
+    env: &DaemonTestEnvironment,
+    name: &str,
+    run_command: &str,
+) -> Result<(i64, u16)> {
+    let id = save_service_with_command(env, name, run_command).await?;
+    let service = ServiceRepository::new_from_static()
+        .get_by_id(id)
+        .await?
+        .expect("The service was just saved");
+
+    Ok((id, service.port as u16))
+}
+
+fn start_payload(id: i64, condition: ReadyCondition, timeout_secs: u64) -> StartServicePayload {
+    StartServicePayload {
+        service: ServiceRef::Id(id),
+        wait_ready: Some(condition),
+        ready_timeout_secs: Some(timeout_secs),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_start_waits_for_port() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (id, port) = save_service(&env, "ready-port", "exec sleep 30").await?;
+
+    // Starts listening once the startup window is over, the way a slow service would
+    let listener = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        TcpListener::bind(("127.0.0.1", port)).await
+    });
+
+    let started = Instant::now();
+    manager.start(&start_payload(id, ReadyCondition::Port, 5)).await?;
+
+    assert!(started.elapsed() >= Duration::from_millis(800));
+    let listener = listener.await??;
+    assert_eq!(manager.get_status(&ServiceRef::Id(id)).await?.state, ServiceState::Running);
+
+    drop(listener);
+    manager.stop(&ServiceRef::Id(id)).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_waits_for_log_match() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (id, _) = save_service(
+        &env,
+        "ready-log",
+        "sleep 1; echo 'loading' >&2; echo 'listening on 8080' >&2; exec sleep 30",
+    )
+    .await?;
+
+    let condition = ReadyCondition::LogMatch(r"^listening on \d+$".to_string());
+    let started = Instant::now();
+    manager.start(&start_payload(id, condition, 5)).await?;
+
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(manager.get_status(&ServiceRef::Id(id)).await?.state, ServiceState::Running);
+
+    manager.stop(&ServiceRef::Id(id)).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_waits_for_duration() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (id, _) = save_service(&env, "ready-duration", "exec sleep 30").await?;
+
+    let started = Instant::now();
+    manager.start(&start_payload(id, ReadyCondition::Duration(1200), 5)).await?;
+
+    assert!(started.elapsed() >= Duration::from_millis(1200));
+    assert_eq!(manager.get_status(&ServiceRef::Id(id)).await?.state, ServiceState::Running);
+
+    manager.stop(&ServiceRef::Id(id)).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_fails_when_not_ready_in_time() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (id, _) = save_service(&env, "ready-timeout", "exec sleep 30").await?;
+
+    let condition = ReadyCondition::LogMatch("never printed".to_string());
+    let error = manager.start(&start_payload(id, condition, 1)).await.unwrap_err();
+
+    assert_eq!(error.to_string(), "Service was not ready within 1 seconds");
+    // The process is stopped instead of being left running
+    assert_eq!(manager.get_status(&ServiceRef::Id(id)).await?.state, ServiceState::Stopped);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_reports_exit_while_waiting() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (id, _) = save_service(&env, "ready-exit", "sleep 1; echo 'crashed' >&2; exit 3").await?;
+
+    let error = manager
+        .start(&start_payload(id, ReadyCondition::Duration(5000), 10))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        crate::Error::StartupFailed {
+            exit_code: Some(3),
+            ..
+        }
+    ));
+    assert_eq!(
+        manager.get_status(&ServiceRef::Id(id)).await?.last_error.as_deref(),
+        Some("crashed")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_start_rejects_invalid_ready_pattern() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let (id, _) = save_service(&env, "ready-pattern", "exec sleep 30").await?;
+
+    let condition = ReadyCondition::LogMatch("(unclosed".to_string());
+    let error = manager.start(&start_payload(id, condition, 1)).await.unwrap_err();
+
+    assert_eq!(error.to_string(), "Invalid ready pattern `(unclosed`");
+    assert_eq!(manager.get_status(&ServiceRef::Id(id)).await?.state, ServiceState::Stopped);
+
+    Ok(())
+}
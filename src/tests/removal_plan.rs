@@ -0,0 +1,127 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_client::Client;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{
+    DependencyEdge, RemovalPlan, RemoveServiceCommand, ServiceRef,
+};
+
+async fn save_dependency(service_id: i64, dependent_service_id: i64, tunnel: bool) -> Result<()> {
+    let mut dependency = ServiceDependency {
+        id: 0,
+        service_id,
+        dependent_service_id,
+        tunnel_enabled: tunnel,
+        optional: false,
+    };
+    ServiceDependencyRepository::new_from_static()
+        .save(&mut dependency)
+        .await
+}
+
+/// `app` depends on `database` through a tunnel and `database` depends on `cache`.
+async fn save_services(env: &DaemonTestEnvironment) -> Result<(i64, i64, i64)> {
+    let app = save_service_with_command(env, "removal-plan-app", "true").await?;
+    let database = save_service_with_command(env, "removal-plan-database", "true").await?;
+    let cache = save_service_with_command(env, "removal-plan-cache", "true").await?;
+
+    save_dependency(app, database, true).await?;
+    save_dependency(database, cache, false).await?;
+
+    Ok((app, database, cache))
+}
+
+fn assert_plan_of_database(plan: &RemovalPlan) {
+    assert_eq!(plan.service_name, "removal-plan-database");
+    assert!(!plan.will_stop);
+
+    let mut dependencies = plan.dependencies_to_delete.clone();
+    dependencies.sort_by(|a, b| a.service.cmp(&b.service));
+    assert_eq!(
+        dependencies,
+        [
+            DependencyEdge {
+                service: "removal-plan-app".to_string(),
+                dependent_service: "removal-plan-database".to_string(),
+                tunnel_enabled: true,
+            },
+            DependencyEdge {
+                service: "removal-plan-database".to_string(),
+                dependent_service: "removal-plan-cache".to_string(),
+                tunnel_enabled: false,
+            },
+        ]
+    );
+
+    let config = plan.config_to_delete.as_ref().expect("config is deleted");
+    assert_eq!(
+        config.filename.as_deref(),
+        Some("removal-plan-database-config")
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_lists_artifacts_without_removing_them() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, database, _) = save_services(&env).await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut client = Client::connect(&env.test_env.socket_path).await?;
+    let plan = client
+        .execute(RemoveServiceCommand::new(
+            ServiceRef::Id(database),
+            true,
+            true,
+        ))
+        .await?;
+    assert_plan_of_database(&plan);
+
+    // Nothing was deleted
+    let service = ServiceRepository::new_from_static()
+        .get_by_id(database)
+        .await?
+        .expect("service is kept");
+    let config_id = service.config_id.expect("service has a config");
+    assert!(ServiceConfigRepository::new_from_static()
+        .get_by_id(config_id)
+        .await?
+        .is_some());
+
+    let dependencies = ServiceDependencyRepository::new_from_static();
+    assert_eq!(dependencies.get_by_service_id(database).await?.len(), 1);
+    assert_eq!(dependencies.get_by_service_id(app).await?.len(), 1);
+
+    daemon.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_removal_returns_what_was_deleted() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, database, _) = save_services(&env).await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut client = Client::connect(&env.test_env.socket_path).await?;
+    let plan = client
+        .execute(RemoveServiceCommand::new(
+            ServiceRef::Id(database),
+            false,
+            true,
+        ))
+        .await?;
+    assert_plan_of_database(&plan);
+
+    assert!(ServiceRepository::new_from_static()
+        .get_by_id(database)
+        .await?
+        .is_none());
+    assert!(ServiceDependencyRepository::new_from_static()
+        .get_by_service_id(app)
+        .await?
+        .is_empty());
+
+    daemon.abort();
+
+    Ok(())
+}
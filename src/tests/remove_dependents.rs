@@ -0,0 +1,85 @@
+use super::common::*;
+use crate::error::Error;
+use crate::statics::SERVICE_MANAGER;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::{RemoveServicePayload, ServiceRef};
+
+#[tokio::test]
+async fn test_removing_a_dependency_is_blocked() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, database) = save_app_and_database(&env, "remove-blocked", false, false).await?;
+
+    let result = SERVICE_MANAGER
+        .remove_service(&RemoveServicePayload {
+            service: ServiceRef::Id(database),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(
+        matches!(
+            result,
+            Err(Error::HasDependents { ref service, ref dependents })
+                if service == "remove-blocked-database" && dependents == "remove-blocked-app"
+        ),
+        "{result:?}"
+    );
+
+    // Nothing was removed
+    assert!(ServiceRepository::new_from_static()
+        .get_by_id(database)
+        .await?
+        .is_some());
+    assert_eq!(
+        ServiceDependencyRepository::new_from_static()
+            .list_dependents(database)
+            .await?
+            .len(),
+        1
+    );
+
+    // Services without dependents are removed as before
+    SERVICE_MANAGER
+        .remove_service(&RemoveServicePayload {
+            service: ServiceRef::Id(app),
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_forced_removal_removes_reverse_dependencies() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let (app, database) = save_app_and_database(&env, "remove-forced", false, false).await?;
+
+    let plan = SERVICE_MANAGER
+        .remove_service(&RemoveServicePayload {
+            service: ServiceRef::Id(database),
+            force: true,
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(plan.dependencies_to_delete.len(), 1);
+    assert_eq!(plan.dependencies_to_delete[0].service, "remove-forced-app");
+
+    assert!(ServiceRepository::new_from_static()
+        .get_by_id(database)
+        .await?
+        .is_none());
+
+    let dependencies = ServiceDependencyRepository::new_from_static();
+    assert!(dependencies.get_by_service_id(app).await?.is_empty());
+    assert!(dependencies.list_dependents(database).await?.is_empty());
+
+    // The dependent service itself is kept
+    assert!(ServiceRepository::new_from_static()
+        .get_by_id(app)
+        .await?
+        .is_some());
+
+    Ok(())
+}
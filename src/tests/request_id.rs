@@ -0,0 +1,58 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::{Protocol, ProtocolVersionMismatch, PROTOCOL_VERSION};
+
+#[tokio::test]
+async fn test_response_echoes_request_id() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    for _ in 0..3 {
+        let request_id = protocol.next_request_id();
+        protocol.write_command(&mut client, Command::Ping).await?;
+
+        let (header, _) = protocol.read_message(&mut client).await?;
+
+        assert!(matches!(header.command, Command::Success));
+        assert_eq!(header.request_id(), request_id);
+    }
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[test]
+fn test_request_ids_are_monotonic() {
+    let mut protocol = Protocol::default();
+
+    let first = protocol.next_request_id();
+    let second = protocol.next_request_id();
+
+    assert_eq!(first, 1);
+    assert!(second > first);
+    assert_eq!(protocol.request_id(), second);
+}
+
+#[tokio::test]
+async fn test_header_of_other_version_is_rejected() -> Result<()> {
+    // Headers written before the request id was added are laid out differently
+    let mut frame = Vec::new();
+    Protocol::new(PROTOCOL_VERSION - 1)
+        .write_command(&mut frame, Command::Ping)
+        .await?;
+
+    let err = Protocol::default()
+        .read_message(&mut frame.as_slice())
+        .await
+        .expect_err("A header of another version should be rejected");
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(ProtocolVersionMismatch::is(&err), "{err}");
+
+    Ok(())
+}
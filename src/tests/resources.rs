@@ -0,0 +1,96 @@
+use crate::service_manager::resources::{
+    cpu_percent, parse_stat, parse_statm_resident_pages, ProcStat, ResourceSample,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_parse_stat_line() {
+    // The command name holds the separators the parser has to skip over
+    let line = "4242 (my (odd) server) S 1 4242 4242 0 -1 4194560 1034 0 0 0 250 75 0 0 20 0 4 \
+                0 123456 104857600 2560 18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 \
+                0 0 0 0\n";
+
+    assert_eq!(
+        parse_stat(line),
+        Some(ProcStat {
+            pid: 4242,
+            state: 'S',
+            pgrp: 4242,
+            utime: 250,
+            stime: 75,
+        })
+    );
+
+    assert_eq!(parse_stat("4242 (truncated) S 1"), None);
+    assert_eq!(parse_stat(""), None);
+}
+
+#[test]
+fn test_parse_statm_line() {
+    assert_eq!(parse_statm_resident_pages("25600 2560 640 12 0 3072 0\n"), Some(2560));
+    assert_eq!(parse_statm_resident_pages("25600"), None);
+}
+
+#[test]
+fn test_cpu_percent_between_samples() {
+    let taken_at = Instant::now();
+    let previous = ResourceSample {
+        cpu_ticks: 1_000,
+        taken_at,
+    };
+
+    // 150 ticks are 1.5 seconds of CPU time, used within 2 seconds
+    let current = ResourceSample {
+        cpu_ticks: 1_150,
+        taken_at: taken_at + Duration::from_secs(2),
+    };
+    assert_eq!(cpu_percent(previous, current), 75.0);
+
+    assert_eq!(cpu_percent(previous, previous), 0.0);
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_sample_resources_of_running_process() -> anyhow::Result<()> {
+    use crate::service_manager::ServiceProcess;
+    use command_group::AsyncCommandGroup;
+    use nexsock_protocol::commands::service_status::ServiceState;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::{broadcast, Mutex};
+
+    let process = tokio::process::Command::new("sleep")
+        .arg("30")
+        .kill_on_drop(true)
+        .group_spawn()?;
+
+    let mut process = ServiceProcess {
+        process,
+        state: ServiceState::Running,
+        started_at: tokio::time::Instant::now(),
+        stop_signal: Default::default(),
+        env_vars: HashMap::new(),
+        stdout: None,
+        stdin: Default::default(),
+        stderr: None,
+        stdout_logs: Default::default(),
+        log_task_handle: None,
+        startup_stderr: Arc::new(Mutex::new(Vec::new())),
+        stderr_task_handle: None,
+        output: broadcast::channel(16).0,
+        resource_sample: None,
+        resources: None,
+    };
+
+    // The first sample has nothing to compare against
+    process.sample_resources();
+    assert!(process.resource_sample.is_some());
+    assert_eq!(process.resources, None);
+
+    process.sample_resources();
+    let usage = process.resources.expect("sampled twice");
+    assert!(usage.memory_bytes > 0);
+    assert!(usage.cpu_percent >= 0.0);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::run_command::{render_run_command, RunCommandVars};
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::config::ConfigFormat;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_testing::generate_test_port;
+
+const VARS: RunCommandVars<'static> = RunCommandVars {
+    port: 8080,
+    repo_path: "/srv/web-app",
+    name: "web-app",
+};
+
+#[test]
+fn test_placeholders_are_replaced() -> Result<()> {
+    let template = "cd {repo_path} && ./serve --name {name} --port {port}";
+    let command = render_run_command(template, &VARS)?;
+
+    assert_eq!(command, "cd /srv/web-app && ./serve --name web-app --port 8080");
+
+    Ok(())
+}
+
+#[test]
+fn test_shell_syntax_is_left_alone() -> Result<()> {
+    let template = "PORT=${PORT:-{port}} ls {a,b} | awk '{print $1}' && echo {{port}}";
+    let command = render_run_command(template, &VARS)?;
+
+    assert_eq!(command, "PORT=${PORT:-8080} ls {a,b} | awk '{print $1}' && echo {port}");
+
+    Ok(())
+}
+
+#[test]
+fn test_values_are_quoted_as_one_word() -> Result<()> {
+    let vars = RunCommandVars {
+        repo_path: "/srv/my app",
+        name: "it's; rm -rf /",
+        ..VARS
+    };
+    let command = render_run_command("cd {repo_path} && echo {name}", &vars)?;
+
+    assert_eq!(command, r"cd '/srv/my app' && echo 'it'\''s; rm -rf /'");
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_placeholder_errors() {
+    let error = render_run_command("./serve --host {host} --port {port}", &VARS).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Unknown placeholder `{host}` in run command, expected one of `{port}`, `{repo_path}`, \
+         `{name}`"
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_start_substitutes_placeholders() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let repo_path = env.test_env.temp_dir.path();
+
+    let mut config = ServiceConfig::new(
+        "template-config".to_string(),
+        ConfigFormat::Env,
+        Some("echo {name}:{port} > {repo_path}/spawned; exec sleep 30".to_string()),
+    );
+    ServiceConfigRepository::new_from_static().save(&mut config).await?;
+
+    let port = generate_test_port();
+    let mut service = Service::new(
+        "templated".to_string(),
+        "https://github.com/test/templated.git".to_string(),
+        port,
+        repo_path.to_string_lossy().to_string(),
+        Some(config.id),
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    let payload = StartServicePayload {
+        service: ServiceRef::Id(service.id),
+        ..Default::default()
+    };
+    manager.start(&payload).await?;
+
+    let spawned = tokio::fs::read_to_string(repo_path.join("spawned")).await?;
+    assert_eq!(spawned, format!("templated:{port}\n"));
+
+    manager.stop(&payload.service).await?;
+
+    Ok(())
+}
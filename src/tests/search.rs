@@ -0,0 +1,69 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::search::SearchServicesPayload;
+use nexsock_testing::generate_test_port;
+use std::collections::BTreeMap;
+
+/// Returns the names of the services found for `query`.
+async fn searched_names(manager: &ServiceManager, query: &str) -> Result<Vec<String>> {
+    let payload = SearchServicesPayload {
+        query: query.to_string(),
+    };
+    let found = manager.search_services(&payload).await?;
+
+    Ok(found
+        .services
+        .into_iter()
+        .map(|service| service.name)
+        .collect())
+}
+
+#[tokio::test]
+async fn test_search_ranks_name_url_and_label_matches() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    for (name, repo_url, labels) in [
+        (
+            "search-label",
+            "https://github.com/test/a.git",
+            [("app", "search-inventory")],
+        ),
+        (
+            "search-url",
+            "https://github.com/test/search-inventory.git",
+            [("app", "none")],
+        ),
+        (
+            "search-inventory",
+            "https://github.com/test/b.git",
+            [("app", "none")],
+        ),
+    ] {
+        manager
+            .add_service(&AddServicePayload {
+                name: name.to_string(),
+                repo_url: repo_url.to_string(),
+                port: generate_test_port(),
+                repo_path: env.test_env.temp_dir.path().to_string_lossy().to_string(),
+                labels: labels
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+                ..Default::default()
+            })
+            .await?;
+    }
+
+    assert_eq!(
+        searched_names(&manager, "Search-Inventory").await?,
+        ["search-inventory", "search-url", "search-label"]
+    );
+    assert!(searched_names(&manager, "search-unknown").await?.is_empty());
+    assert!(searched_names(&manager, "  ").await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use super::common::*;
+use crate::daemon::server::serve;
+use crate::daemon::Connection;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::resources::parse_stat;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_plugins::lua::manager::LuaPluginManager;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use nexsock_testing::generate_test_port;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{duplex, split, DuplexStream, ReadHalf, WriteHalf};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{timeout, Instant};
+
+type TestConnection = Connection<ReadHalf<DuplexStream>, WriteHalf<DuplexStream>>;
+
+/// Whether `pid` is running, exited processes nobody reaped yet count as gone.
+fn is_running(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|line| parse_stat(&line))
+        .is_some_and(|stat| stat.state != 'Z')
+}
+
+/// Starts a long running service called `name` on `manager`, returning its pid.
+async fn start_service(
+    manager: &ServiceManager,
+    env: &DaemonTestEnvironment,
+    name: &str,
+) -> Result<u32> {
+    let repo_path = env.test_env.temp_dir.path();
+
+    let mut service = Service::new(
+        name.to_string(),
+        String::new(),
+        generate_test_port(),
+        repo_path.to_string_lossy().to_string(),
+        None,
+    );
+    ServiceRepository::new_from_static()
+        .save(&mut service)
+        .await?;
+
+    let process = manager
+        .spawn_service_process(
+            service.id,
+            repo_path,
+            "exec sleep 30",
+            HashMap::new(),
+            LogSettings::default(),
+        )
+        .await?;
+    let pid = process.process.id().unwrap();
+    manager.running_services().insert(service.id, process);
+
+    Ok(pid)
+}
+
+/// Accepts the connections sent through the returned sender, waiting forever once it is dropped.
+fn channel_acceptor() -> (
+    mpsc::UnboundedSender<TestConnection>,
+    impl FnMut() -> futures::future::BoxFuture<'static, crate::error::Result<TestConnection>>,
+) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let rx = Arc::new(tokio::sync::Mutex::new(rx));
+
+    let accept = move || {
+        let rx = rx.clone();
+        let accepted: futures::future::BoxFuture<'static, _> = Box::pin(async move {
+            match rx.lock().await.recv().await {
+                Some(connection) => Ok(connection),
+                None => std::future::pending().await,
+            }
+        });
+
+        accepted
+    };
+
+    (tx, accept)
+}
+
+fn connect() -> Result<(TestConnection, DuplexStream)> {
+    let (client, server) = duplex(8 * 1024);
+    let (reader, writer) = split(server);
+    let lua_plugin_manager = Arc::new(LuaPluginManager::new()?);
+
+    let connection = Connection::from_parts(
+        reader,
+        writer,
+        lua_plugin_manager,
+        KeepaliveConfig::disabled(),
+    );
+
+    Ok((connection, client))
+}
+
+#[tokio::test]
+async fn test_shutdown_stops_the_loop_and_its_services() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let connections = Arc::new(Mutex::new(Vec::new()));
+    let pid = start_service(&manager, &env, "loop-shutdown").await?;
+
+    let (connection_tx, accept) = channel_acceptor();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let shutdown = async move {
+        let _ = shutdown_rx.await;
+    };
+
+    let client = async {
+        let (connection, mut client) = connect()?;
+        connection_tx.send(connection)?;
+
+        // The loop hands accepted connections to their own task
+        let mut protocol = Protocol::default();
+        protocol.write_command(&mut client, Command::Ping).await?;
+        let (header, _) = protocol.read_message(&mut client).await?;
+        assert!(matches!(header.command, Command::Success));
+        drop(client);
+
+        let _ = shutdown_tx.send(());
+        anyhow::Ok(Instant::now())
+    };
+
+    let loop_future = serve(
+        &manager,
+        accept,
+        shutdown,
+        Duration::from_secs(300),
+        &connections,
+    );
+    let (served, signaled_at) = timeout(Duration::from_secs(10), async {
+        tokio::join!(loop_future, client)
+    })
+    .await?;
+    served?;
+    let signaled_at = signaled_at?;
+
+    assert!(
+        signaled_at.elapsed() < Duration::from_secs(5),
+        "the loop took {:?} to stop",
+        signaled_at.elapsed()
+    );
+    assert!(manager.running_services().is_empty());
+    assert!(!is_running(pid), "the service outlived the loop");
+    assert!(connections.lock().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_signaled_shutdown_stops_the_loop() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let connections = Arc::new(Mutex::new(Vec::new()));
+    let pid = start_service(&manager, &env, "loop-signaled").await?;
+
+    let (_connection_tx, accept) = channel_acceptor();
+    let loop_future = serve(
+        &manager,
+        accept,
+        std::future::pending(),
+        Duration::from_secs(300),
+        &connections,
+    );
+
+    let signal = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        manager.signal_shutdown();
+    };
+
+    let (served, ()) = timeout(Duration::from_secs(10), async {
+        tokio::join!(loop_future, signal)
+    })
+    .await?;
+    served?;
+
+    assert!(manager.running_services().is_empty());
+    assert!(!is_running(pid), "the service outlived the loop");
+
+    Ok(())
+}
@@ -2,7 +2,8 @@ use super::common::*;
 use crate::statics::SERVICE_MANAGER;
 use crate::traits::service_management::ServiceManagement;
 use anyhow::Result;
-use nexsock_protocol::commands::{add_service::AddServicePayload, manage_service::ServiceRef};
+use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::manage_service::{RemoveServicePayload, ServiceRef};
 use nexsock_testing::generate_test_port;
 use tracing::{debug, error};
 
@@ -44,6 +45,9 @@ async fn test_service_creation_attempt() -> Result<()> {
         config: None,
         git_branch: None,
         git_auth_type: None,
+        stop_signal: Default::default(),
+        profile: None,
+        labels: Default::default(),
     };
 
     // Try to add a service (may succeed or fail in test environment)
@@ -59,8 +63,11 @@ async fn test_service_creation_attempt() -> Result<()> {
 
             if found {
                 // Clean up
-                let service_ref = ServiceRef::Name(service_name.to_string());
-                let _ = service_manager.remove_service(&service_ref).await;
+                let payload = RemoveServicePayload {
+                    service: ServiceRef::Name(service_name.to_string()),
+                    ..Default::default()
+                };
+                let _ = service_manager.remove_service(&payload).await;
             }
         }
         Err(err) => {
@@ -86,7 +93,12 @@ async fn test_service_error_handling() -> Result<()> {
     debug!(status_result = ?status_result, "Attempt to get status of non-existent service");
     assert!(status_result.is_err());
 
-    let remove_result = service_manager.remove_service(&service_ref).await;
+    let remove_result = service_manager
+        .remove_service(&RemoveServicePayload {
+            service: service_ref,
+            ..Default::default()
+        })
+        .await;
     debug!(remove_result = ?remove_result, "Attempt to remove non-existent service");
     assert!(remove_result.is_err());
 
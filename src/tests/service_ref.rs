@@ -0,0 +1,30 @@
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use std::str::FromStr;
+
+#[test]
+fn test_numbers_are_ids() {
+    assert_eq!(ServiceRef::from_str("42").unwrap(), ServiceRef::Id(42));
+    assert_eq!(
+        ServiceRef::from_str("api").unwrap(),
+        ServiceRef::Name("api".to_string())
+    );
+}
+
+#[test]
+fn test_name_prefix_forces_a_name() {
+    assert_eq!(
+        ServiceRef::from_str("name:42").unwrap(),
+        ServiceRef::Name("42".to_string())
+    );
+    // Only the first prefix is stripped
+    assert_eq!(
+        ServiceRef::from_str("name:id:42").unwrap(),
+        ServiceRef::Name("id:42".to_string())
+    );
+}
+
+#[test]
+fn test_id_prefix_forces_an_id() {
+    assert_eq!(ServiceRef::from_str("id:42").unwrap(), ServiceRef::Id(42));
+    assert!(ServiceRef::from_str("id:api").is_err());
+}
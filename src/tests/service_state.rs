@@ -0,0 +1,84 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::ProcessManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::service_status::ServiceState;
+use nexsock_testing::generate_test_port;
+
+#[tokio::test]
+async fn test_get_state_of_stopped_and_unknown_services() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let mut service = Service::new(
+        "state-stopped".to_string(),
+        String::new(),
+        generate_test_port(),
+        env.test_env.temp_dir.path().to_string_lossy().to_string(),
+        None,
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    assert_eq!(manager.get_state(&ServiceRef::Id(service.id)).await?, ServiceState::Stopped);
+    assert_eq!(
+        manager.get_state(&ServiceRef::Name("state-stopped".to_string())).await?,
+        ServiceState::Stopped
+    );
+
+    assert!(manager.get_state(&ServiceRef::Id(i64::MAX)).await.is_err());
+    assert!(manager
+        .get_state(&ServiceRef::Name("state-unknown".to_string()))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_get_state_of_running_service_skips_the_database() -> Result<()> {
+    use crate::service_manager::ServiceProcess;
+    use command_group::AsyncCommandGroup;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::{broadcast, Mutex};
+
+    let _env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let process = tokio::process::Command::new("sleep")
+        .arg("30")
+        .kill_on_drop(true)
+        .group_spawn()?;
+
+    // The id has no row in the database, so a lookup there would fail
+    let id = i64::MAX - 1;
+    manager.running_services().insert(
+        id,
+        ServiceProcess {
+            process,
+            state: ServiceState::Running,
+            started_at: tokio::time::Instant::now(),
+            stop_signal: Default::default(),
+            env_vars: HashMap::new(),
+            stdout: None,
+            stdin: Default::default(),
+            stderr: None,
+            stdout_logs: Default::default(),
+            log_task_handle: None,
+            startup_stderr: Arc::new(Mutex::new(Vec::new())),
+            stderr_task_handle: None,
+            output: broadcast::channel(16).0,
+            resource_sample: None,
+            resources: None,
+        },
+    );
+
+    assert_eq!(manager.get_state(&ServiceRef::Id(id)).await?, ServiceState::Running);
+    assert!(manager.get_status(&ServiceRef::Id(id)).await.is_err());
+
+    Ok(())
+}
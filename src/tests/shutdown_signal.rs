@@ -0,0 +1,54 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::ProcessManager;
+use anyhow::Result;
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn test_every_subscriber_observes_shutdown() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let tasks = (0..8)
+        .map(|_| {
+            let mut shutdown = manager.shutdown_signal();
+
+            tokio::spawn(async move {
+                let observed = shutdown.wait_for(|shutdown| *shutdown).await.is_ok();
+                observed
+            })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(manager.shutdown_tx().receiver_count(), 8);
+
+    // A burst of signals must not leave any subscriber behind
+    for _ in 0..4 {
+        manager.signal_shutdown();
+    }
+
+    for task in tasks {
+        assert!(timeout(Duration::from_secs(5), task).await??);
+    }
+    assert_eq!(manager.shutdown_tx().receiver_count(), 0);
+
+    // Subscribing after the signal still observes it
+    let mut late = manager.shutdown_signal();
+    assert!(*late.wait_for(|shutdown| *shutdown).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shutdown_is_not_signaled_by_default() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let mut shutdown = manager.shutdown_signal();
+    assert!(!*shutdown.borrow_and_update());
+
+    let waited = timeout(Duration::from_millis(50), shutdown.changed()).await;
+    assert!(waited.is_err(), "shutdown was signaled without calling `signal_shutdown`");
+
+    Ok(())
+}
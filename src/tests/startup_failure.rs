@@ -0,0 +1,59 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::config::ConfigFormat;
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::service_status::ServiceState;
+use nexsock_testing::generate_test_port;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_service_exiting_during_startup_reports_stderr() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let mut config = ServiceConfig::new(
+        "config".to_string(),
+        ConfigFormat::Env,
+        Some("echo 'listen: address already in use' >&2; exit 1".to_string()),
+    );
+    ServiceConfigRepository::new_from_static().save(&mut config).await?;
+
+    let mut service = Service::new(
+        "startup-failure".to_string(),
+        "https://github.com/test/startup-failure.git".to_string(),
+        generate_test_port(),
+        env.test_env.temp_dir.path().to_string_lossy().to_string(),
+        Some(config.id),
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    let payload = StartServicePayload {
+        service: ServiceRef::Id(service.id),
+        env_vars: HashMap::new(),
+        ..Default::default()
+    };
+    let error = manager.start(&payload).await.unwrap_err();
+
+    assert!(matches!(
+        error,
+        crate::Error::StartupFailed {
+            exit_code: Some(1),
+            ..
+        }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Service exited during startup with exit code 1:\nlisten: address already in use"
+    );
+
+    // The failure is kept on the service for later status requests
+    let status = manager.get_status(&ServiceRef::Id(service.id)).await?;
+    assert_eq!(status.state, ServiceState::Stopped);
+    assert_eq!(status.last_exit_code, Some(1));
+    assert_eq!(status.last_error.as_deref(), Some("listen: address already in use"));
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use anyhow::Result;
+use nexsock_config::NEXSOCK_CONFIG;
+use nexsock_protocol::commands::event::ServiceEvent;
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_services_starting_too_long_are_reaped() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let mut events = manager.event_bus().subscribe();
+    let startup_timeout = Duration::from_secs(NEXSOCK_CONFIG.server().startup_timeout);
+
+    for service_id in [1, 2] {
+        let mut process = manager
+            .spawn_service_process(
+                service_id,
+                env.test_env.temp_dir.path(),
+                "exec sleep 30",
+                HashMap::new(),
+                LogSettings::default(),
+            )
+            .await?;
+        process.state = ServiceState::Starting;
+
+        manager.running_services().insert(service_id, process);
+    }
+
+    // The first service has been starting for longer than the timeout
+    manager.running_services().get_mut(&1).unwrap().started_at -=
+        startup_timeout + Duration::from_secs(1);
+
+    manager.clean_old().await?;
+
+    assert!(manager.running_services().get(&1).is_none());
+    assert_eq!(
+        manager.running_services().get(&2).unwrap().state,
+        ServiceState::Starting
+    );
+    assert!(matches!(
+        events.recv().await?.event,
+        ServiceEvent::Crashed { service_id: 1, .. }
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::error::Error;
+use crate::service_manager::state::{is_valid_transition, validate_transition};
+use crate::statics::METRICS;
+use nexsock_protocol::commands::service_status::ServiceState;
+
+#[test]
+fn test_valid_transitions_pass() {
+    use ServiceState::*;
+
+    for (from, to) in [
+        (Stopped, Starting),
+        (Failed, Starting),
+        (Starting, Running),
+        (Starting, Failed),
+        (Running, Stopping),
+        (Running, Failed),
+        (Stopping, Stopped),
+        (Failed, Stopped),
+    ] {
+        assert!(is_valid_transition(from, to), "{from} -> {to}");
+        assert!(validate_transition(1, from, to).is_ok(), "{from} -> {to}");
+    }
+}
+
+#[test]
+fn test_invalid_transitions_return_a_typed_error() {
+    use ServiceState::*;
+
+    for (from, to) in [
+        (Running, Running),
+        (Running, Starting),
+        (Starting, Starting),
+        (Stopped, Stopping),
+        (Stopping, Starting),
+    ] {
+        let rejected = METRICS.snapshot(0).invalid_transitions;
+
+        assert!(!is_valid_transition(from, to), "{from} -> {to}");
+        assert!(matches!(
+            validate_transition(1, from, to),
+            Err(Error::InvalidStateTransition { from: f, to: t }) if f == from && t == to
+        ));
+        assert!(METRICS.snapshot(0).invalid_transitions > rejected);
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_starting_a_running_service_is_rejected() -> anyhow::Result<()> {
+    use super::common::*;
+    use crate::service_manager::new::ServiceManager;
+    use crate::service_manager::ServiceProcess;
+    use crate::traits::process_manager::ProcessManager;
+    use crate::traits::service_management::ServiceManagement;
+    use command_group::AsyncCommandGroup;
+    use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::{broadcast, Mutex};
+
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let service_id = save_service_with_command(&env, "transition-running", "sleep 30").await?;
+
+    let process = tokio::process::Command::new("sleep")
+        .arg("30")
+        .kill_on_drop(true)
+        .group_spawn()?;
+    manager.running_services().insert(
+        service_id,
+        ServiceProcess {
+            process,
+            state: ServiceState::Running,
+            started_at: tokio::time::Instant::now(),
+            stop_signal: Default::default(),
+            env_vars: HashMap::new(),
+            stdout: None,
+            stdin: Default::default(),
+            stderr: None,
+            stdout_logs: Default::default(),
+            log_task_handle: None,
+            startup_stderr: Arc::new(Mutex::new(Vec::new())),
+            stderr_task_handle: None,
+            output: broadcast::channel(16).0,
+            resource_sample: None,
+            resources: None,
+        },
+    );
+
+    let result = manager
+        .start(&StartServicePayload {
+            service: ServiceRef::Id(service_id),
+            ..Default::default()
+        })
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::InvalidStateTransition {
+            from: ServiceState::Running,
+            to: ServiceState::Starting,
+        })
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::ServiceProcess;
+use crate::traits::process_manager::FullProcessManager;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::StopSignal;
+use nexsock_testing::generate_test_port;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Only exits cleanly on SIGINT, SIGTERM is ignored.
+const SIGINT_ONLY: &str = "trap 'exit 0' INT; trap '' TERM; while :; do sleep 0.1; done";
+
+async fn spawn_sigint_only(
+    env: &DaemonTestEnvironment,
+    manager: &ServiceManager,
+    stop_signal: StopSignal,
+) -> Result<ServiceProcess> {
+    let path = env.test_env.temp_dir.path();
+    let mut process = manager
+        .spawn_service_process(1, path, SIGINT_ONLY, HashMap::new(), LogSettings::default())
+        .await?;
+    process.stop_signal = stop_signal;
+
+    // Gives the shell time to install its traps
+    sleep(Duration::from_millis(200)).await;
+
+    Ok(process)
+}
+
+#[tokio::test]
+async fn test_configured_signal_stops_service_gracefully() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let mut process = spawn_sigint_only(&env, &manager, StopSignal::Int).await?;
+
+    let started = Instant::now();
+    manager.cleanup_process(1, &mut process).await?;
+
+    let status = process.process.try_wait()?.expect("process should have exited");
+    assert!(status.success(), "{status:?}");
+    assert!(started.elapsed() < Duration::from_secs(5), "the process was killed");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ignored_signal_escalates_to_sigkill() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let mut process = spawn_sigint_only(&env, &manager, StopSignal::Term).await?;
+
+    manager.cleanup_process(1, &mut process).await?;
+
+    let status = process.process.try_wait()?.expect("process should have exited");
+    assert!(!status.success(), "{status:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stop_signal_is_stored_with_the_service() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let repository = ServiceRepository::new_from_static();
+
+    let mut service = Service::new(
+        "stop-signal".to_string(),
+        String::new(),
+        generate_test_port(),
+        env.test_env.temp_dir.path().to_string_lossy().to_string(),
+        None,
+    );
+    assert_eq!(service.stop_signal, StopSignal::Term);
+
+    service.stop_signal = StopSignal::Int;
+    repository.save(&mut service).await?;
+
+    let stored = repository.get_by_id(service.id).await?.unwrap();
+    assert_eq!(stored.stop_signal, StopSignal::Int);
+
+    Ok(())
+}
+
+#[test]
+fn test_stop_signal_from_string() {
+    for (name, signal) in [
+        ("int", StopSignal::Int),
+        ("SIGINT", StopSignal::Int),
+        ("quit", StopSignal::Quit),
+        ("hup", StopSignal::Hup),
+        ("term", StopSignal::Term),
+        ("unknown", StopSignal::Term),
+    ] {
+        assert_eq!(StopSignal::from(name.to_string()), signal, "{name}");
+    }
+}
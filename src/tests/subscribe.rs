@@ -0,0 +1,217 @@
+use super::common::*;
+use crate::statics::SERVICE_MANAGER;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_protocol::commands::event::{
+    SequencedEvent, ServiceEvent, ServiceEventKind, SubscribePayload,
+};
+use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
+use nexsock_protocol::commands::{Command, CommandPayload};
+use nexsock_protocol::header::MessageFlags;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use std::time::Duration;
+use tokio::io::DuplexStream;
+use tokio::time::timeout;
+
+/// Sends a subscribe request, returning the command of the answer.
+async fn subscribe(
+    protocol: &mut Protocol,
+    client: &mut DuplexStream,
+    payload: &SubscribePayload,
+) -> Result<Command> {
+    protocol.next_request_id();
+    protocol
+        .write_command_with_payload(
+            client,
+            Command::Subscribe,
+            payload,
+            MessageFlags::HAS_PAYLOAD,
+        )
+        .await?;
+
+    let (header, _) = protocol.read_message(client).await?;
+
+    Ok(header.command)
+}
+
+/// Reads the next event the daemon streams with its sequence number, failing if none arrives in
+/// time.
+async fn next_sequenced_event(
+    protocol: &mut Protocol,
+    client: &mut DuplexStream,
+) -> Result<SequencedEvent> {
+    let (header, payload) =
+        timeout(Duration::from_secs(10), protocol.read_message(client)).await??;
+    assert!(matches!(header.command, Command::Success));
+    assert_eq!(header.request_id(), protocol.request_id());
+
+    let payload = Protocol::read_payload::<CommandPayload>(&payload.unwrap())?.unwrap();
+
+    SequencedEvent::try_from(payload)
+}
+
+/// Reads the next event the daemon streams, failing if none arrives in time.
+async fn next_event(protocol: &mut Protocol, client: &mut DuplexStream) -> Result<ServiceEvent> {
+    Ok(next_sequenced_event(protocol, client).await?.event)
+}
+
+#[tokio::test]
+async fn test_subscriber_receives_events_of_started_service() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "subscribe-api", "exec sleep 30").await?;
+    let other_id = save_service_with_command(&env, "subscribe-other", "exec sleep 30").await?;
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    // Filtered on the service so events of services started by other tests don't interfere
+    let payload = SubscribePayload {
+        service: Some(ServiceRef::Name("subscribe-api".to_string())),
+        ..SubscribePayload::default()
+    };
+    let answer = subscribe(&mut protocol, &mut client, &payload).await?;
+    assert!(matches!(answer, Command::Success));
+
+    for id in [other_id, service_id] {
+        SERVICE_MANAGER
+            .start(&StartServicePayload {
+                service: ServiceRef::Id(id),
+                ..Default::default()
+            })
+            .await?;
+    }
+    SERVICE_MANAGER.stop(&ServiceRef::Id(service_id)).await?;
+    SERVICE_MANAGER.stop(&ServiceRef::Id(other_id)).await?;
+
+    assert_eq!(
+        next_event(&mut protocol, &mut client).await?,
+        ServiceEvent::Started { service_id }
+    );
+    assert_eq!(
+        next_event(&mut protocol, &mut client).await?,
+        ServiceEvent::Stopped { service_id }
+    );
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscriber_only_receives_subscribed_kinds() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "subscribe-kinds", "exec sleep 30").await?;
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    let payload = SubscribePayload {
+        service: Some(ServiceRef::Id(service_id)),
+        kinds: vec![ServiceEventKind::Stopped],
+        ..SubscribePayload::default()
+    };
+    let answer = subscribe(&mut protocol, &mut client, &payload).await?;
+    assert!(matches!(answer, Command::Success));
+
+    SERVICE_MANAGER
+        .start(&StartServicePayload {
+            service: ServiceRef::Id(service_id),
+            ..Default::default()
+        })
+        .await?;
+    SERVICE_MANAGER.stop(&ServiceRef::Id(service_id)).await?;
+
+    assert_eq!(
+        next_event(&mut protocol, &mut client).await?,
+        ServiceEvent::Stopped { service_id }
+    );
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribing_to_unknown_service_fails() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    let payload = SubscribePayload {
+        service: Some(ServiceRef::Name("subscribe-missing".to_string())),
+        ..SubscribePayload::default()
+    };
+    let answer = subscribe(&mut protocol, &mut client, &payload).await?;
+    assert!(matches!(answer, Command::Error));
+
+    // The connection keeps handling commands after a refused subscription
+    protocol.next_request_id();
+    protocol.write_command(&mut client, Command::Ping).await?;
+    let (header, _) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Success));
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resubscribing_with_cursor_replays_missed_events() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "subscribe-replay", "exec sleep 30").await?;
+    let service = ServiceRef::Id(service_id);
+    let start = StartServicePayload {
+        service: service.clone(),
+        ..Default::default()
+    };
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    let mut payload = SubscribePayload {
+        service: Some(service.clone()),
+        ..SubscribePayload::default()
+    };
+    let answer = subscribe(&mut protocol, &mut client, &payload).await?;
+    assert!(matches!(answer, Command::Success));
+
+    SERVICE_MANAGER.start(&start).await?;
+    let last_seen = next_sequenced_event(&mut protocol, &mut client).await?;
+    assert_eq!(last_seen.event, ServiceEvent::Started { service_id });
+
+    // The subscriber goes away while the service keeps changing
+    drop(client);
+    handle.await??;
+
+    SERVICE_MANAGER.stop(&service).await?;
+    SERVICE_MANAGER.start(&start).await?;
+    SERVICE_MANAGER.stop(&service).await?;
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    payload.after_seq = Some(last_seen.seq);
+    let answer = subscribe(&mut protocol, &mut client, &payload).await?;
+    assert!(matches!(answer, Command::Success));
+
+    let mut seq = last_seen.seq;
+    for expected in [
+        ServiceEvent::Stopped { service_id },
+        ServiceEvent::Started { service_id },
+        ServiceEvent::Stopped { service_id },
+    ] {
+        let event = next_sequenced_event(&mut protocol, &mut client).await?;
+        assert!(event.seq > seq);
+        assert_eq!(event.event, expected);
+        seq = event.seq;
+    }
+
+    drop(client);
+    handle.await??;
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use super::common::*;
+use crate::telemetry;
+use anyhow::Result;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::Protocol;
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Registry;
+
+#[tokio::test]
+async fn test_handled_command_is_exported_as_span() -> Result<()> {
+    let _env = DaemonTestEnvironment::new().await?;
+
+    // Spans are captured in memory instead of being sent to a collector
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = Registry::default().with(telemetry::layer(&provider));
+    // The test runtime is single threaded, so the connection task runs under this subscriber
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let mut protocol = Protocol::default();
+
+    protocol.write_command(&mut client, Command::Ping).await?;
+    let (header, _) = protocol.read_message(&mut client).await?;
+    assert!(matches!(header.command, Command::Success));
+
+    drop(client);
+    handle.await??;
+    provider.force_flush()?;
+
+    let spans = exporter.get_finished_spans()?;
+    let span = spans
+        .iter()
+        .find(|span| span.name == "handle_command")
+        .expect("The handled command should be exported");
+
+    let command = span
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key.as_str() == "command")
+        .map(|attribute| attribute.value.as_str().into_owned());
+    assert_eq!(command.as_deref(), Some("Ping"));
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use super::common::*;
+use anyhow::Result;
+use nexsock_protocol::commands::Command;
+use nexsock_protocol::header::{MessageFlags, MessageHeader};
+use nexsock_protocol::keepalive::KeepaliveConfig;
+use nexsock_protocol::protocol::{Protocol, TruncatedMessage};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::time::timeout;
+
+const HEADER_LEN: usize = MessageHeader::ENCODED_LEN;
+
+/// Encodes a message carrying a payload.
+async fn encoded_message() -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    Protocol::default()
+        .write_command_with_payload(
+            &mut bytes,
+            Command::Ping,
+            &"a payload long enough to cut short".to_string(),
+            MessageFlags::NONE,
+        )
+        .await?;
+
+    Ok(bytes)
+}
+
+/// Reads a single message from `bytes`, failing instead of hanging.
+async fn read_from(bytes: &[u8]) -> Result<std::io::Error> {
+    let mut reader = bytes;
+    let result = timeout(
+        Duration::from_secs(5),
+        Protocol::default().read_message(&mut reader),
+    )
+    .await?;
+
+    Ok(result.expect_err("Reading an incomplete message should fail"))
+}
+
+#[tokio::test]
+async fn test_empty_stream_is_a_clean_disconnect() -> Result<()> {
+    let err = read_from(&[]).await?;
+
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    assert!(!TruncatedMessage::is(&err));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_truncated_header_is_reported() -> Result<()> {
+    let bytes = encoded_message().await?;
+
+    for len in [1, HEADER_LEN / 2, HEADER_LEN - 1] {
+        let err = read_from(&bytes[..len]).await?;
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(TruncatedMessage::is(&err), "{len} bytes: {err}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_truncated_payload_is_reported() -> Result<()> {
+    let bytes = encoded_message().await?;
+    assert!(bytes.len() > HEADER_LEN + 1);
+
+    // The header declares the whole payload but only part of it follows
+    for len in [HEADER_LEN, HEADER_LEN + 1, bytes.len() - 1] {
+        let err = read_from(&bytes[..len]).await?;
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(TruncatedMessage::is(&err), "{len} bytes: {err}");
+    }
+
+    // The complete message still reads fine
+    let mut reader = bytes.as_slice();
+    let (header, payload) = Protocol::default().read_message(&mut reader).await?;
+    assert!(matches!(header.command, Command::Ping));
+    assert_eq!(
+        payload.map(|payload| payload.len()),
+        Some(bytes.len() - HEADER_LEN)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_connection_reports_truncated_message() -> Result<()> {
+    let (handle, mut client) = spawn_test_connection(KeepaliveConfig::disabled())?;
+    let bytes = encoded_message().await?;
+
+    // Send the header and part of the payload, then disconnect
+    client.write_all(&bytes[..HEADER_LEN + 1]).await?;
+    client.flush().await?;
+    drop(client);
+
+    let err = timeout(Duration::from_secs(5), handle)
+        .await??
+        .expect_err("A truncated message should end the connection with an error");
+    assert!(err.to_string().contains("message truncated"), "{err}");
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::common::*;
+use crate::statics::SERVICE_MANAGER;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use anyhow::Result;
+use nexsock_client::Client;
+use nexsock_protocol::commands::list_services::ListServicesResponse;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::service_status::{ServiceState, ServiceStatus};
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_typed_methods_return_typed_results() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let service_id = save_service_with_command(&env, "typed-client", "exec sleep 30").await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut client = Client::connect(&env.test_env.socket_path).await?;
+
+    let services: ListServicesResponse = client.list_services().await?;
+    assert!(services
+        .services
+        .iter()
+        .any(|service| service.id == service_id && service.name == "typed-client"));
+
+    let status: ServiceStatus = client.status("typed-client".to_string()).await?;
+    assert_eq!(status.id, service_id);
+    assert_eq!(status.state, ServiceState::Stopped);
+
+    client.start(service_id, HashMap::new()).await?;
+    assert_eq!(
+        SERVICE_MANAGER.get_service_state(service_id),
+        ServiceState::Running
+    );
+    assert_eq!(
+        client.status(service_id).await?.state,
+        ServiceState::Running
+    );
+
+    client.stop(ServiceRef::Id(service_id)).await?;
+    assert!(!SERVICE_MANAGER.running_services().contains_key(&service_id));
+
+    daemon.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_typed_methods_return_daemon_errors() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let daemon = spawn_daemon(&env)?;
+
+    let mut client = Client::connect(&env.test_env.socket_path).await?;
+
+    let missing = "typed-client-missing".to_string();
+    assert!(client.status(missing.clone()).await.is_err());
+    assert!(client.start(missing, HashMap::new()).await.is_err());
+
+    // The connection is still usable after an error
+    client.list_services().await?;
+
+    daemon.abort();
+
+    Ok(())
+}
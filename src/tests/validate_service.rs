@@ -0,0 +1,183 @@
+use super::common::*;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::validate::parse_config_file;
+use crate::traits::process_manager::ProcessManager;
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::config::ConfigFormat;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::validate::{CheckStatus, ValidationReport};
+use nexsock_testing::generate_test_port;
+use std::path::Path;
+
+/// Saves a service with a config file named `.env` and the given run command.
+async fn save_service(
+    name: &str,
+    repo_url: &str,
+    port: i64,
+    repo_path: &Path,
+    run_command: &str,
+) -> Result<i64> {
+    let mut config = ServiceConfig::new(
+        ".env".to_string(),
+        ConfigFormat::Env,
+        Some(run_command.to_string()),
+    );
+    ServiceConfigRepository::new_from_static().save(&mut config).await?;
+
+    let mut service = Service::new(
+        name.to_string(),
+        repo_url.to_string(),
+        port,
+        repo_path.to_string_lossy().to_string(),
+        Some(config.id),
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    Ok(service.id)
+}
+
+async fn add_dependency(service_id: i64, dependent_service_id: i64) -> Result<()> {
+    let mut dependency = ServiceDependency {
+        id: 0,
+        service_id,
+        dependent_service_id,
+        tunnel_enabled: false,
+        optional: false,
+    };
+    ServiceDependencyRepository::new_from_static()
+        .save(&mut dependency)
+        .await?;
+
+    Ok(())
+}
+
+fn checks_with(report: &ValidationReport, status: CheckStatus) -> Vec<&str> {
+    report
+        .with_status(status)
+        .map(|check| check.check.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_validate_passes_a_valid_service() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let repo_path = env.test_env.temp_dir.path().join("valid-app");
+    tokio::fs::create_dir_all(&repo_path).await?;
+    tokio::fs::write(
+        repo_path.join(".env"),
+        "# Settings of the app\nLOG_LEVEL=debug\nexport GREETING=\"hello world\"\n\n",
+    )
+    .await?;
+
+    let app = save_service(
+        "valid-app",
+        "",
+        generate_test_port(),
+        &repo_path,
+        "./serve --port {port}",
+    )
+    .await?;
+    let database = save_service(
+        "valid-database",
+        "",
+        generate_test_port(),
+        &repo_path,
+        "./database",
+    )
+    .await?;
+    add_dependency(app, database).await?;
+
+    let report = manager.validate(&ServiceRef::Id(app)).await?;
+
+    assert!(report.is_valid(), "{report:#?}");
+    assert_eq!(
+        checks_with(&report, CheckStatus::Pass),
+        ["repo_path", "port", "run_command", "config_file", "dependencies"]
+    );
+    assert_eq!(
+        report.checks[3].message,
+        format!("`{}` holds 2 entries", repo_path.join(".env").display())
+    );
+    assert_eq!(report.checks[4].message, "every dependency can be started (1 in total)");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_validate_reports_every_problem() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let port = generate_test_port();
+    let _listener = std::net::TcpListener::bind(("0.0.0.0", port as u16))?;
+
+    let repo_path = env.test_env.temp_dir.path().join("missing");
+    let app = save_service("broken-app", "", port, &repo_path, "./serve {bogus}").await?;
+    let worker = save_service(
+        "broken-worker",
+        "",
+        generate_test_port(),
+        &repo_path,
+        "./work",
+    )
+    .await?;
+    add_dependency(app, worker).await?;
+    add_dependency(worker, app).await?;
+
+    let report = manager.validate(&ServiceRef::Id(app)).await?;
+
+    assert!(!report.is_valid());
+    assert_eq!(
+        checks_with(&report, CheckStatus::Fail),
+        ["repo_path", "port", "run_command", "dependencies"]
+    );
+    assert_eq!(checks_with(&report, CheckStatus::Warn), ["config_file"]);
+
+    let message_of = |check: &str| {
+        report
+            .checks
+            .iter()
+            .find(|result| result.check == check)
+            .map(|result| result.message.clone())
+            .unwrap()
+    };
+    assert_eq!(message_of("port"), format!("{port} is already in use"));
+    assert!(message_of("run_command").contains("Unknown placeholder `{bogus}`"));
+    assert_eq!(
+        message_of("dependencies"),
+        "the dependencies form a cycle: `broken-app` -> `broken-worker` -> `broken-app`"
+    );
+
+    // Validating never starts the service
+    assert!(manager.running_services().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_file_formats() {
+    assert_eq!(parse_config_file("A=1\n# comment\nexport B='two'\n", ConfigFormat::Env), Ok(2));
+    assert_eq!(
+        parse_config_file("A=1\nnot a pair\n", ConfigFormat::Env),
+        Err("line 2 is not a `KEY=value` pair".to_string())
+    );
+    assert_eq!(
+        parse_config_file("1A=1\n", ConfigFormat::Env),
+        Err("line 1 has the invalid name `1A`".to_string())
+    );
+    assert_eq!(
+        parse_config_file("A=\"open\n", ConfigFormat::Env),
+        Err("line 1 has an unterminated \" quote".to_string())
+    );
+
+    let properties = "! comment\nname = web\npath: /srv/web\nhosts one,\\\n  two\nflag\n";
+    assert_eq!(parse_config_file(properties, ConfigFormat::Properties), Ok(4));
+    assert_eq!(
+        parse_config_file("=value\n", ConfigFormat::Properties),
+        Err("line 1 has no key".to_string())
+    );
+}
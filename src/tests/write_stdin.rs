@@ -0,0 +1,101 @@
+use super::common::*;
+use crate::error::Error;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use crate::traits::service_management::ServiceManagement;
+use anyhow::Result;
+use nexsock_db::prelude::*;
+use nexsock_protocol::commands::manage_service::ServiceRef;
+use nexsock_protocol::commands::stdin::WriteStdinPayload;
+use nexsock_testing::generate_test_port;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+
+fn payload(service_id: i64, data: &[u8]) -> WriteStdinPayload {
+    WriteStdinPayload {
+        service: ServiceRef::Id(service_id),
+        data: data.to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn test_written_stdin_appears_in_stdout() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let repo_path = env.test_env.temp_dir.path();
+
+    let mut service = Service::new(
+        "stdin-cat".to_string(),
+        String::new(),
+        generate_test_port(),
+        repo_path.to_string_lossy().to_string(),
+        None,
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    let process = manager
+        .spawn_service_process(
+            service.id,
+            repo_path,
+            "cat",
+            HashMap::new(),
+            LogSettings::default(),
+        )
+        .await?;
+    let mut output = process.output.subscribe();
+    manager.running_services().insert(service.id, process);
+
+    manager.write_stdin(&payload(service.id, b"hello from stdin\n")).await?;
+
+    let line = timeout(Duration::from_secs(5), output.recv()).await??;
+    assert_eq!(line, "hello from stdin\n");
+
+    // Once the process is gone its stdin is reported as closed
+    let (_, mut process) = manager.running_services().remove(&service.id).unwrap();
+    process.process.kill().await?;
+    process.process.wait().await?;
+    manager.running_services().insert(service.id, process);
+
+    let mut written = Ok(());
+    for _ in 0..10 {
+        written = manager.write_stdin(&payload(service.id, b"are you there?\n")).await;
+        if written.is_err() {
+            break;
+        }
+
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert!(
+        matches!(written, Err(Error::StdinClosed { ref service }) if service == "stdin-cat"),
+        "{written:?}"
+    );
+
+    // Later writes fail right away
+    let written = manager.write_stdin(&payload(service.id, b"again\n")).await;
+    assert!(matches!(written, Err(Error::StdinClosed { .. })), "{written:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_stdin_to_stopped_service_fails() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+
+    let mut service = Service::new(
+        "stdin-stopped".to_string(),
+        String::new(),
+        generate_test_port(),
+        env.test_env.temp_dir.path().to_string_lossy().to_string(),
+        None,
+    );
+    ServiceRepository::new_from_static().save(&mut service).await?;
+
+    let written = manager.write_stdin(&payload(service.id, b"hello\n")).await;
+    assert!(written.is_err());
+    assert!(!matches!(written, Err(Error::StdinClosed { .. })));
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use super::common::*;
+use crate::service_manager::log_parser::LogSettings;
+use crate::service_manager::new::ServiceManager;
+use crate::service_manager::resources::parse_stat;
+use crate::traits::process_manager::{FullProcessManager, ProcessManager};
+use anyhow::Result;
+use nexsock_protocol::commands::service_status::ServiceState;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Whether `pid` exited without anyone reaping it yet.
+fn is_zombie(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|line| parse_stat(&line))
+        .is_some_and(|stat| stat.state == 'Z')
+}
+
+async fn wait_for_zombie(pid: u32) -> bool {
+    for _ in 0..100 {
+        if is_zombie(pid) {
+            return true;
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    false
+}
+
+#[tokio::test]
+async fn test_exited_services_are_reaped() -> Result<()> {
+    let env = DaemonTestEnvironment::new().await?;
+    let manager = ServiceManager::default();
+    let path = env.test_env.temp_dir.path();
+
+    let commands = [
+        (1, "exec sleep 30"),
+        (2, "exec sleep 30"),
+        (3, "exit 0"),
+        (4, "exit 3"),
+    ];
+
+    let mut pids = Vec::new();
+    for (service_id, command) in commands {
+        let mut process = manager
+            .spawn_service_process(
+                service_id,
+                path,
+                command,
+                HashMap::new(),
+                LogSettings::default(),
+            )
+            .await?;
+        pids.push(process.process.id().unwrap());
+
+        if command.starts_with("exec sleep") {
+            process.process.start_kill()?;
+        }
+
+        manager.running_services().insert(service_id, process);
+    }
+
+    for &pid in &pids {
+        assert!(wait_for_zombie(pid), "process {pid} did not exit");
+    }
+
+    assert_eq!(manager.reap_exited(), commands.len());
+
+    for &pid in &pids {
+        assert!(!is_zombie(pid), "process {pid} is still a zombie");
+    }
+
+    let state = |service_id| manager.running_services().get(&service_id).unwrap().state;
+    assert_eq!(state(1), ServiceState::Failed);
+    assert_eq!(state(2), ServiceState::Failed);
+    assert_eq!(state(3), ServiceState::Stopped);
+    assert_eq!(state(4), ServiceState::Failed);
+
+    // Reaped services are left for the cleanup to remove, and not reaped twice
+    assert_eq!(manager.reap_exited(), 0);
+    manager.clean_old().await?;
+    assert!(manager.running_services().is_empty());
+
+    Ok(())
+}
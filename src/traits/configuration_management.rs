@@ -6,7 +6,7 @@
 //! Configuration management handles the persistence and retrieval of service
 //! configuration data including file paths, formats, and run commands.
 
-use nexsock_protocol::commands::config::ServiceConfigPayload;
+use nexsock_protocol::commands::config::{ConfigFile, ServiceConfigPayload, WriteConfigPayload};
 use nexsock_protocol::commands::manage_service::ServiceRef;
 
 /// Trait for managing service configurations.
@@ -89,4 +89,30 @@ pub trait ConfigurationManagement {
     /// * Database query operations fail
     /// * Configuration data is corrupted or invalid
     async fn get_config(&self, payload: &ServiceRef) -> crate::error::Result<ServiceConfigPayload>;
+
+    /// Retrieves the configuration for a service together with the contents of its
+    /// configuration file, read from `filename` in the repository of the service.
+    ///
+    /// A missing file is not an error, its contents are `None`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist or has no configuration
+    /// * The file is larger than the size limit or not valid UTF-8
+    /// * Reading the file fails
+    async fn get_config_file(&self, payload: &ServiceRef) -> crate::error::Result<ConfigFile>;
+
+    /// Replaces the configuration file of a service with `payload.contents`.
+    ///
+    /// The contents are checked to parse in the format of the configuration before anything is
+    /// written, and the file is replaced atomically so it never holds partially written contents.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist or has no configuration
+    /// * The contents don't parse or are larger than the size limit
+    /// * Writing the file fails, the previous file is then left untouched
+    async fn write_config_file(&self, payload: &WriteConfigPayload) -> crate::error::Result<()>;
 }
@@ -8,8 +8,10 @@
 //! with support for tunneling configuration between dependent services.
 
 use nexsock_protocol::commands::dependency::{
-    AddDependencyPayload, ListDependenciesResponse, RemoveDependencyPayload,
+    AddDependencyPayload, GetDependencyInfoPayload, ListDependenciesResponse,
+    ListDependentsResponse, RemoveDependencyPayload,
 };
+use nexsock_protocol::commands::dependency_info::DependencyDetails;
 use nexsock_protocol::commands::manage_service::ServiceRef;
 
 /// Trait for managing dependencies between services.
@@ -35,6 +37,7 @@ use nexsock_protocol::commands::manage_service::ServiceRef;
 ///         service: web_service.clone(),
 ///         dependent_service: db_service,
 ///         tunnel_enabled: false,
+///         optional: false,
 ///     };
 ///     manager.add_dependency(&dependency).await?;
 ///     
@@ -61,6 +64,7 @@ pub trait DependencyManagement {
     ///   - `service` - The service that has the dependency
     ///   - `dependent_service` - The service that must be available
     ///   - `tunnel_enabled` - Whether to enable network tunneling
+    ///   - `optional` - Whether the service may start without the dependency being ready
     ///
     /// # Returns
     ///
@@ -130,4 +134,53 @@ pub trait DependencyManagement {
         &self,
         payload: &ServiceRef,
     ) -> crate::error::Result<ListDependenciesResponse>;
+
+    /// Lists all services that depend on a service.
+    ///
+    /// This is the reverse lookup of [`list_dependencies`](Self::list_dependencies), useful
+    /// before stopping or removing a service that others rely on.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The service reference (by name or ID)
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ListDependentsResponse>`](crate::Result) which is:
+    /// * `Ok(ListDependentsResponse)` - List of services depending on the service
+    /// * `Err(Error)` - If the query operation fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * Database query operations fail
+    async fn list_dependents(
+        &self,
+        payload: &ServiceRef,
+    ) -> crate::error::Result<ListDependentsResponse>;
+
+    /// Gets the details of a single dependency.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The service and the service it depends on
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<DependencyDetails>`](crate::Result) which is:
+    /// * `Ok(DependencyDetails)` - The tunnel and optional flags of the dependency along with
+    ///   the live state and port of the dependency's service
+    /// * `Err(Error)` - If the query operation fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * Either service does not exist
+    /// * The dependency relationship does not exist
+    /// * Database query operations fail
+    async fn get_dependency_info(
+        &self,
+        payload: &GetDependencyInfoPayload,
+    ) -> crate::error::Result<DependencyDetails>;
 }
@@ -12,17 +12,39 @@ use anyhow::{anyhow, Context as _};
 use command_group::AsyncCommandGroup as _;
 use dashmap::DashMap;
 use futures::future::try_join_all;
+use nexsock_config::NEXSOCK_CONFIG;
+use nexsock_protocol::commands::event::ServiceEvent;
 use nexsock_protocol::commands::service_status::ServiceState;
 use port_selector::is_free_tcp;
-use std::collections::VecDeque;
+use std::process::Stdio;
 use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
-use tokio::io::AsyncReadExt;
-use tokio::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{watch, Mutex};
+use tokio::time::Instant;
 use tokio::{process::Command, sync::broadcast, time::sleep};
 use tracing::{debug, info, warn};
 
-use crate::service_manager::{LogEntry, ServiceProcess};
-use crate::statics::SERVICE_REPOSITORY;
+use crate::events::EventBus;
+use crate::service_manager::log_parser::{
+    parse_line, push_log_entry, read_log_lines, LogBuffer, LogSettings,
+};
+use crate::service_manager::{
+    process_group, ServiceProcess, OUTPUT_CHANNEL_CAPACITY, STARTUP_STDERR_LINES,
+};
+use crate::statics::{METRICS, SERVICE_REPOSITORY};
+
+/// How long the processes left in the group of a stopped service have to die after SIGKILL.
+const PROCESS_GROUP_KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the group of a stopped service is checked for processes left behind.
+const PROCESS_GROUP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Delay before the port of a stopped service is checked again the first time, doubled after
+/// every check.
+const PORT_FREE_INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Longest delay between two checks of the port of a stopped service.
+const PORT_FREE_MAX_BACKOFF: Duration = Duration::from_millis(500);
 
 /// Basic process management interface for service processes.
 ///
@@ -36,11 +58,12 @@ use crate::statics::SERVICE_REPOSITORY;
 /// use nexsockd::traits::process_manager::ProcessManager;
 /// use std::sync::Arc;
 /// use dashmap::DashMap;
-/// use tokio::sync::broadcast;
+/// use tokio::sync::watch;
 ///
 /// struct MyProcessManager {
 ///     processes: Arc<DashMap<i64, ServiceProcess>>,
-///     shutdown: broadcast::Sender<()>,
+///     shutdown: watch::Sender<bool>,
+///     events: EventBus,
 /// }
 ///
 /// impl ProcessManager for MyProcessManager {
@@ -48,9 +71,13 @@ use crate::statics::SERVICE_REPOSITORY;
 ///         &self.processes
 ///     }
 ///     
-///     fn shutdown_tx(&self) -> &broadcast::Sender<()> {
+///     fn shutdown_tx(&self) -> &watch::Sender<bool> {
 ///         &self.shutdown
 ///     }
+///
+///     fn event_bus(&self) -> &EventBus {
+///         &self.events
+///     }
 /// }
 ///
 /// // Use the manager
@@ -74,16 +101,44 @@ pub(crate) trait ProcessManager {
     /// A reference to an `Arc<DashMap<i64, ServiceProcess>>` containing all running processes.
     fn running_services(&self) -> &Arc<DashMap<i64, ServiceProcess>>;
 
-    /// Returns a reference to the shutdown broadcast sender.
+    /// Returns a reference to the shutdown sender.
     ///
-    /// This sender is used to coordinate shutdown operations across all processes
-    /// and components that need to be notified when the system is shutting down.
+    /// The channel holds `true` once the system is shutting down. Being a `watch` channel it only
+    /// keeps the latest state, so a subscriber can't lag behind or miss the signal, no matter when
+    /// it subscribes or how often shutdown is signaled.
     ///
     /// # Returns
     ///
-    /// A reference to the `broadcast::Sender<()>` for shutdown coordination.
-    #[allow(dead_code)]
-    fn shutdown_tx(&self) -> &broadcast::Sender<()>;
+    /// A reference to the `watch::Sender<bool>` for shutdown coordination.
+    fn shutdown_tx(&self) -> &watch::Sender<bool>;
+
+    /// Returns a reference to the bus the lifecycle events of the services are published to.
+    ///
+    /// A [`ServiceEvent::Stopped`] is published when a running service is killed and a
+    /// [`ServiceEvent::Stopped`] or [`ServiceEvent::Crashed`] when [`clean_old`](Self::clean_old)
+    /// finds a service that exited on its own.
+    fn event_bus(&self) -> &EventBus;
+
+    /// Subscribes to the shutdown signal.
+    ///
+    /// Tasks wait for shutdown with `shutdown_signal().wait_for(|shutdown| *shutdown)`, which
+    /// returns immediately if shutdown was already signaled.
+    fn shutdown_signal(&self) -> watch::Receiver<bool> {
+        self.shutdown_tx().subscribe()
+    }
+
+    /// Signals every subscriber of [`shutdown_signal`](Self::shutdown_signal) to shut down.
+    ///
+    /// Subscribing afterwards still observes the signal. Signaling more than once has no further
+    /// effect.
+    fn signal_shutdown(&self) {
+        let shutdown_tx = self.shutdown_tx();
+        let was_signaled = shutdown_tx.send_replace(true);
+
+        if !was_signaled {
+            debug!(subscribers = shutdown_tx.receiver_count(), "Signaled shutdown");
+        }
+    }
 
     /// Terminates all running service processes.
     ///
@@ -114,6 +169,9 @@ pub(crate) trait ProcessManager {
     /// that have failed, terminated unexpectedly, or are no longer responding.
     /// It's typically called periodically to maintain process registry health.
     ///
+    /// A service still starting after the configured `startup_timeout` hung during its
+    /// initialization, it's marked as failed and stopped.
+    ///
     /// # Returns
     ///
     /// Returns [`Result<()>`] which is:
@@ -129,6 +187,17 @@ pub(crate) trait ProcessManager {
     async fn clean_old(&self) -> crate::error::Result<()> {
         clean_old(self).await
     }
+
+    /// Reaps the running services that exited, returning how many were reaped.
+    ///
+    /// An exited process stays a zombie until it is waited on, which otherwise only happens once
+    /// it is stopped or [`clean_old`](Self::clean_old) runs. Reaped processes stay in the running
+    /// services with their state set to stopped or failed, until `clean_old` removes them.
+    ///
+    /// The daemon calls this whenever it receives `SIGCHLD`.
+    fn reap_exited(&self) -> usize {
+        reap_exited(self)
+    }
 }
 
 async fn kill_all<T: ProcessManager + ?Sized>(manager: &T) -> crate::error::Result<()> {
@@ -165,30 +234,46 @@ async fn cleanup_process<T: ProcessManager + ?Sized>(
         handle.1.abort();
     }
 
-    // First try graceful termination via SIGTERM
-    if let Err(e) = process.process.kill().await {
+    if let Some(handle) = process.stderr_task_handle.take() {
+        handle.0.abort();
+        handle.1.abort();
+    }
+
+    // The id is gone once the process was reaped, the group outlives it
+    let pgid = process.process.id();
+
+    // First ask the process to stop with its configured signal
+    if let Err(e) = send_stop_signal(process) {
         warn!(
-            "Failed to send SIGTERM to process {}: {}. Attempting SIGKILL...",
-            service_id, e
+            "Failed to send {} to process {}: {}. Attempting SIGKILL...",
+            process.stop_signal, service_id, e
         );
     } else {
-        debug!("Sent SIGTERM to process");
+        debug!(signal = %process.stop_signal, "Sent stop signal to process");
     }
 
     // Give the process a chance to terminate gracefully
     match tokio::time::timeout(Duration::from_secs(5), process.process.wait()).await {
-        Ok(Ok(_)) => {
-            info!("Process {} terminated gracefully", service_id);
-            return Ok(());
+        Ok(Ok(status)) => {
+            info!(exit_status = ?status, "Process {} terminated gracefully", service_id);
         }
         _ => {
             warn!(
                 "Process {} did not terminate gracefully, forcing SIGKILL",
                 service_id
             );
+
+            force_kill(service_id, process).await?;
         }
     }
 
+    match pgid {
+        Some(pgid) => kill_process_group(service_id, process, pgid).await,
+        None => Ok(()),
+    }
+}
+
+async fn force_kill(service_id: i64, process: &mut ServiceProcess) -> crate::error::Result<()> {
     // If still running, force kill
     if let Err(e) = process.process.start_kill() {
         warn!("Failed to force kill process {}: {}", service_id, e);
@@ -216,6 +301,67 @@ async fn cleanup_process<T: ProcessManager + ?Sized>(
     }
 }
 
+/// Kills what is left of process group `pgid` once the service itself exited and waits until
+/// every process in it is gone.
+///
+/// Processes the service forked aren't taken down with it and can keep its port bound. Where the
+/// group can't be listed the service exiting is taken as the whole group being gone.
+async fn kill_process_group(
+    service_id: i64,
+    process: &mut ServiceProcess,
+    pgid: u32,
+) -> crate::error::Result<()> {
+    let deadline = Instant::now() + PROCESS_GROUP_KILL_TIMEOUT;
+
+    loop {
+        let Some(members) = process_group::live_members(pgid) else {
+            return Ok(());
+        };
+
+        if members.is_empty() {
+            debug!(service_id, pgid, "Process group terminated");
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            warn!(service_id, pgid, ?members, "Process group outlived SIGKILL");
+            return Err(
+                anyhow!("Processes {members:?} of service {service_id} are still running").into(),
+            );
+        }
+
+        // Signaled every round as the remaining processes may still be forking
+        debug!(service_id, pgid, ?members, "Killing the rest of the process group");
+        if let Err(e) = process.process.start_kill() {
+            debug!(service_id, pgid, error = %e, "Failed to kill the process group");
+        }
+
+        sleep(PROCESS_GROUP_POLL_INTERVAL).await;
+    }
+}
+
+/// Sends the [`ServiceProcess::stop_signal`] to the process group of the service.
+#[cfg(unix)]
+fn send_stop_signal(process: &mut ServiceProcess) -> std::io::Result<()> {
+    use command_group::{Signal, UnixChildExt as _};
+    use nexsock_protocol::commands::manage_service::StopSignal;
+
+    let signal = match process.stop_signal {
+        StopSignal::Term => Signal::SIGTERM,
+        StopSignal::Int => Signal::SIGINT,
+        StopSignal::Quit => Signal::SIGQUIT,
+        StopSignal::Hup => Signal::SIGHUP,
+    };
+
+    process.process.signal(signal)
+}
+
+/// Windows has no signals to ask a process to stop, the process group is terminated right away.
+#[cfg(not(unix))]
+fn send_stop_signal(process: &mut ServiceProcess) -> std::io::Result<()> {
+    process.process.start_kill()
+}
+
 async fn kill_service_process<T: ProcessManager + ?Sized>(
     manager: &T,
     service_id: i64,
@@ -232,29 +378,53 @@ async fn kill_service_process<T: ProcessManager + ?Sized>(
             } else {
                 debug!("Successfully cleaned up service");
                 services.shrink_to_fit();
+                manager
+                    .event_bus()
+                    .publish(ServiceEvent::Stopped { service_id });
+                METRICS.record_stop();
             }
         }
     }
 
-    // Wait for port to be actually freed
-    let service = SERVICE_REPOSITORY
-        .get_by_id(service_id)
-        .await?
-        .ok_or_else(|| anyhow!("Service not found"))?;
+    // Wait for port to be actually freed, a service whose row was already removed (e.g. pruned by
+    // a manifest) has no port left to wait for
+    let Some(service) = SERVICE_REPOSITORY.get_by_id(service_id).await? else {
+        return Ok(());
+    };
+
+    // The process group is gone by now, the socket can still take a moment to be released
+    let timeout = Duration::from_secs(NEXSOCK_CONFIG.server().port_free_timeout);
+    wait_for_port_free(service.port as u16, timeout).await
+}
+
+/// Waits up to `timeout` for `port` to be free, checking it again with an exponential backoff.
+///
+/// Each delay is randomized between half and all of the current backoff, so services stopped
+/// together don't check their ports in lockstep.
+///
+/// # Errors
+///
+/// Returns [`Error::PortNotFreed`](crate::error::Error::PortNotFreed) if the port is still in use
+/// once `timeout` has passed.
+pub(crate) async fn wait_for_port_free(port: u16, timeout: Duration) -> crate::error::Result<()> {
+    let deadline = Instant::now() + timeout;
+    let mut backoff = PORT_FREE_INITIAL_BACKOFF;
 
-    // Poll for port availability with timeout
-    let port = service.port as u16;
-    let mut attempts = 0;
-    while attempts < 10 {
+    loop {
         if is_free_tcp(port) {
             return Ok(());
         }
-        sleep(Duration::from_millis(500)).await;
-        attempts += 1;
-    }
 
-    warn!("Port {} still in use after process termination", port);
-    Err(anyhow!("Failed to free port after service termination").into())
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            warn!(port, "Port still in use after process termination");
+            return Err(crate::error::Error::PortNotFreed { port });
+        }
+
+        let jittered = backoff.mul_f64(0.5 + fastrand::f64() / 2.0);
+        sleep(jittered.min(remaining)).await;
+        backoff = (backoff * 2).min(PORT_FREE_MAX_BACKOFF);
+    }
 }
 
 fn get_service_state<T: ProcessManager + ?Sized>(manager: &T, service_id: i64) -> ServiceState {
@@ -272,8 +442,38 @@ fn get_service_state<T: ProcessManager + ?Sized>(manager: &T, service_id: i64) -
     }
 }
 
+fn reap_exited<T: ProcessManager + ?Sized>(manager: &T) -> usize {
+    let mut reaped = 0;
+
+    for mut process in manager.running_services().iter_mut() {
+        // A reaped process keeps reporting its exit status, it was counted when it was reaped
+        if matches!(process.state, ServiceState::Stopped | ServiceState::Failed) {
+            continue;
+        }
+
+        let service_id = *process.key();
+        match process.process.try_wait() {
+            Ok(Some(status)) => {
+                debug!(service_id, ?status, "Reaped exited service");
+
+                process.state = if status.success() {
+                    ServiceState::Stopped
+                } else {
+                    ServiceState::Failed
+                };
+                reaped += 1;
+            }
+            Ok(None) => {}
+            Err(e) => debug!(service_id, error = %e, "Failed to check whether the service exited"),
+        }
+    }
+
+    reaped
+}
+
 async fn clean_old<T: ProcessManager + ?Sized>(manager: &T) -> crate::error::Result<()> {
     let services = manager.running_services();
+    let startup_timeout = Duration::from_secs(NEXSOCK_CONFIG.server().startup_timeout);
     let mut to_remove = Vec::new();
 
     for mut service in services.iter_mut() {
@@ -282,11 +482,16 @@ async fn clean_old<T: ProcessManager + ?Sized>(manager: &T) -> crate::error::Res
         // Check both status and process health
         let should_remove = match process.check_status().await {
             Ok(ServiceState::Failed) => true,
-            Ok(ServiceState::Starting) => {
-                // If process has been in Starting state too long, consider it failed
-                // You'd need to add a timestamp to ServiceProcess to implement this properly
-                false // TODO: Implement startup timeout check
+            Ok(ServiceState::Starting) if process.startup_timed_out(startup_timeout) => {
+                warn!(
+                    service_id = *service_id,
+                    timeout_secs = startup_timeout.as_secs(),
+                    "Service is still starting after the startup timeout, stopping it"
+                );
+                process.state = ServiceState::Failed;
+                true
             }
+            Ok(ServiceState::Starting) => false,
             Ok(ServiceState::Running) => false,
             Ok(_) => {
                 // Additional health check - verify process is still responding
@@ -308,15 +513,34 @@ async fn clean_old<T: ProcessManager + ?Sized>(manager: &T) -> crate::error::Res
     // Cleanup all identified processes
     for service_id in to_remove {
         if let Some(mut process) = services.remove(&service_id) {
+            let event = exit_event(service_id, &mut process.1);
+
             if let Err(e) = cleanup_process(manager, service_id, &mut process.1).await {
                 warn!("Failed to cleanup process {}: {}", service_id, e);
             }
+
+            manager.event_bus().publish(event);
         }
     }
 
     Ok(())
 }
 
+/// The event for a service [`clean_old`] removed, a crash unless it exited successfully.
+fn exit_event(service_id: i64, process: &mut ServiceProcess) -> ServiceEvent {
+    match process.process.try_wait() {
+        Ok(Some(status)) if status.success() => ServiceEvent::Stopped { service_id },
+        Ok(Some(status)) => ServiceEvent::Crashed {
+            service_id,
+            exit_code: status.code(),
+        },
+        _ => ServiceEvent::Crashed {
+            service_id,
+            exit_code: None,
+        },
+    }
+}
+
 #[tracing::instrument(skip(_manager, path), fields(path = %path.as_ref().display()), err, ret, level = "debug")]
 async fn spawn_service_process<T: ProcessManager + ?Sized>(
     _manager: &T,
@@ -324,15 +548,28 @@ async fn spawn_service_process<T: ProcessManager + ?Sized>(
     path: impl AsRef<Path>,
     run_command: &str,
     env_vars: HashMap<String, String>,
+    log_settings: LogSettings,
 ) -> crate::error::Result<ServiceProcess> {
+    // Services that manage their own logging write straight to our stdio instead
+    let output = || {
+        if log_settings.capture {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        }
+    };
+
     let mut command = Command::new("sh");
     command
         .arg("-c")
         .arg(run_command)
         .current_dir(path)
-        /*.stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .stdin(Stdio::piped())*/
+        // Captured to explain failed startups, the lines are still forwarded to our stderr
+        .stderr(output())
+        // Captured for `GetServiceStdout`, the lines are still forwarded to our stdout
+        .stdout(output())
+        // Kept open for `WriteStdin`
+        .stdin(Stdio::piped())
         .kill_on_drop(true);
 
     #[cfg(unix)]
@@ -357,66 +594,58 @@ async fn spawn_service_process<T: ProcessManager + ?Sized>(
     let mut service_process = ServiceProcess {
         process,
         state: ServiceState::Running,
+        started_at: Instant::now(),
+        stop_signal: Default::default(),
         env_vars,
         stdout,
-        stdin,
+        stdin: Arc::new(Mutex::new(stdin)),
         stderr,
-        stdout_logs: Arc::new(Mutex::new(VecDeque::with_capacity(10_00))),
+        stdout_logs: Arc::new(Mutex::new(LogBuffer::with_capacity(10_00))),
         log_task_handle: None,
+        startup_stderr: Arc::new(Mutex::new(Vec::with_capacity(STARTUP_STDERR_LINES))),
+        stderr_task_handle: None,
+        output: broadcast::channel(OUTPUT_CHANNEL_CAPACITY).0,
+        resource_sample: None,
+        resources: None,
     };
 
-    start_log_collection(&mut service_process).await?;
+    start_log_collection(&mut service_process, log_settings).await?;
+    start_stderr_collection(&mut service_process);
 
     Ok(service_process)
 }
 
-async fn start_log_collection(process: &mut ServiceProcess) -> crate::error::Result<()> {
+async fn start_log_collection(
+    process: &mut ServiceProcess,
+    log_settings: LogSettings,
+) -> crate::error::Result<()> {
     if process.stdout.is_none() {
         return Ok(());
     }
 
     // Take stdout ownership
-    let mut stdout = process.stdout.take().unwrap();
+    let stdout = process.stdout.take().unwrap();
 
     // Create a channel to send logs back to the main process
     let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(100);
 
-    // Start a task to read from stdout
-    let stdout_task = tokio::spawn(async move {
-        let mut buffer = [0u8; 1024];
-
-        loop {
-            match stdout.read(&mut buffer).await {
-                Ok(0) => break, // EOF
-                Ok(n) => {
-                    if let Ok(s) = String::from_utf8(buffer[0..n].to_vec()) {
-                        if tx.send(s).await.is_err() {
-                            break; // Receiver dropped
-                        }
-                    }
-                }
-                Err(_) => break, // Error reading
-            }
-        }
-    });
+    // Start a task to read lines from stdout
+    let stdout_task = tokio::spawn(read_log_lines(stdout, tx, max_log_line_bytes()));
 
     // Start a task to process received logs
     let logs = process.stdout_logs.clone();
+    let output = process.output.clone();
 
     let log_task = tokio::spawn(async move {
-        while let Some(content) = rx.recv().await {
-            let now = chrono::Utc::now();
-            let entry = LogEntry {
-                timestamp: now,
-                content,
-            };
-
-            // Add the log entry and maintain buffer size (e.g., keep last 10,000 entries)
-            let mut logs = logs.lock().await;
-            logs.push_back(entry);
-            while logs.len() > 10_000 {
-                logs.pop_front();
-            }
+        let mut daemon_stdout = tokio::io::stdout();
+
+        while let Some(line) = rx.recv().await {
+            let _ = daemon_stdout.write_all(line.as_bytes()).await;
+            let _ = output.send(line.clone());
+
+            let entry = parse_line(&log_settings, line);
+
+            push_log_entry(&mut *logs.lock().await, entry);
         }
     });
 
@@ -426,6 +655,41 @@ async fn start_log_collection(process: &mut ServiceProcess) -> crate::error::Res
     Ok(())
 }
 
+/// The configured length lines of service output are truncated to.
+fn max_log_line_bytes() -> usize {
+    usize::try_from(NEXSOCK_CONFIG.server().max_log_line_bytes).unwrap_or(usize::MAX)
+}
+
+/// Forwards the stderr of the process to the stderr of the daemon, keeping the first
+/// [`STARTUP_STDERR_LINES`] lines to explain a failed startup.
+fn start_stderr_collection(process: &mut ServiceProcess) {
+    let Some(stderr) = process.stderr.take() else {
+        return;
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(100);
+
+    let read_task = tokio::spawn(read_log_lines(stderr, tx, max_log_line_bytes()));
+
+    let startup_stderr = process.startup_stderr.clone();
+    let output = process.output.clone();
+    let forward_task = tokio::spawn(async move {
+        let mut daemon_stderr = tokio::io::stderr();
+
+        while let Some(line) = rx.recv().await {
+            let _ = daemon_stderr.write_all(line.as_bytes()).await;
+            let _ = output.send(line.clone());
+
+            let mut lines = startup_stderr.lock().await;
+            if lines.len() < STARTUP_STDERR_LINES {
+                lines.push(line);
+            }
+        }
+    });
+
+    process.stderr_task_handle = Some((forward_task, read_task));
+}
+
 /// Extended process management interface with detailed process control.
 ///
 /// This trait extends [`ProcessManager`] with additional methods for fine-grained
@@ -451,7 +715,9 @@ async fn start_log_collection(process: &mut ServiceProcess) -> crate::error::Res
 /// ) -> Result<(), Box<dyn std::error::Error>> {
 ///     // Spawn a new service process
 ///     let env_vars = HashMap::new();
-///     let process = manager.spawn_service_process(service_id, path, command, env_vars).await?;
+///     let process = manager
+///         .spawn_service_process(service_id, path, command, env_vars, LogSettings::default())
+///         .await?;
 ///     
 ///     // Check service state
 ///     let state = manager.get_service_state(service_id);
@@ -471,8 +737,10 @@ pub(crate) trait FullProcessManager: ProcessManager {
     /// Performs cleanup operations on a specific service process.
     ///
     /// This method handles the detailed cleanup of a service process including
-    /// stopping log collection tasks, attempting graceful termination, and
-    /// forcing termination if necessary. It's typically called when a service
+    /// stopping log collection tasks, attempting graceful termination with the
+    /// [`ServiceProcess::stop_signal`], and forcing termination if necessary.
+    /// Processes the service forked and left behind in its process group are
+    /// killed as well, on Linux it only succeeds once all of them are gone. It's typically called when a service
     /// needs to be stopped or has failed.
     ///
     /// # Arguments
@@ -492,6 +760,7 @@ pub(crate) trait FullProcessManager: ProcessManager {
     /// * Process termination fails after timeout
     /// * Force kill operations fail
     /// * Process state cannot be determined
+    /// * Processes of the process group are still running after SIGKILL
     #[allow(dead_code)]
     async fn cleanup_process(
         &self,
@@ -561,6 +830,7 @@ pub(crate) trait FullProcessManager: ProcessManager {
     /// * `path` - The working directory path for the process
     /// * `run_command` - The shell command to execute
     /// * `env_vars` - Environment variables to set for the process
+    /// * `log_settings` - Whether the process output is captured and how it is turned into log entries
     ///
     /// # Returns
     ///
@@ -590,7 +860,8 @@ pub(crate) trait FullProcessManager: ProcessManager {
     ///     1,
     ///     Path::new("/app"),
     ///     "npm start",
-    ///     env_vars
+    ///     env_vars,
+    ///     LogSettings::default(),
     /// ).await?;
     /// ```
     async fn spawn_service_process(
@@ -599,8 +870,9 @@ pub(crate) trait FullProcessManager: ProcessManager {
         path: impl AsRef<Path>,
         run_command: &str,
         env_vars: HashMap<String, String>,
+        log_settings: LogSettings,
     ) -> crate::error::Result<ServiceProcess> {
-        spawn_service_process(self, service_id, path, run_command, env_vars).await
+        spawn_service_process(self, service_id, path, run_command, env_vars, log_settings).await
     }
 }
 
@@ -8,13 +8,28 @@
 //! inheriting the basic process management capabilities. It handles the complete
 //! service lifecycle from registration to termination.
 
+use crate::error::Error;
+use crate::service_manager::log_parser::{entries_after, render_logs};
 use crate::traits::process_manager::ProcessManager;
 use anyhow::anyhow;
 use dashmap::try_result::TryResult;
 use nexsock_protocol::commands::add_service::AddServicePayload;
+use nexsock_protocol::commands::label::LabelSelectorPayload;
 use nexsock_protocol::commands::list_services::ListServicesResponse;
-use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
-use nexsock_protocol::commands::service_status::ServiceStatus;
+use nexsock_protocol::commands::manage_service::{
+    CloneServicePayload, RemovalPlan, RemoveServicePayload, ServiceRef, StartServicePayload,
+};
+use nexsock_protocol::commands::manifest::{ApplyManifestPayload, ApplyManifestResponse};
+use nexsock_protocol::commands::profile::ProfilePayload;
+use nexsock_protocol::commands::search::SearchServicesPayload;
+use nexsock_protocol::commands::service_status::{ServiceState, ServiceStatus};
+use nexsock_protocol::commands::stdin::WriteStdinPayload;
+use nexsock_protocol::commands::stdout::{
+    GetServiceLogsPayload, GetServiceStdoutPayload, ServiceStdout,
+};
+use nexsock_protocol::commands::validate::ValidationReport;
+use std::io;
+use tokio::io::AsyncWriteExt;
 
 /// Comprehensive service management interface extending process management.
 ///
@@ -33,6 +48,7 @@ use nexsock_protocol::commands::service_status::ServiceStatus;
 /// use nexsockd::traits::service_management::ServiceManagement;
 /// use nexsock_protocol::commands::manage_service::{ServiceRef, StartServicePayload};
 /// use nexsock_protocol::commands::add_service::AddServicePayload;
+/// use nexsock_protocol::commands::stdout::GetServiceLogsPayload;
 /// use std::collections::HashMap;
 ///
 /// async fn manage_webapp<T: ServiceManagement>(
@@ -53,6 +69,7 @@ use nexsock_protocol::commands::service_status::ServiceStatus;
 ///     let start_payload = StartServicePayload {
 ///         service: ServiceRef::Name(service_name.to_string()),
 ///         env_vars: HashMap::new(),
+///         ..Default::default()
 ///     };
 ///     manager.start(&start_payload).await?;
 ///     
@@ -61,7 +78,10 @@ use nexsock_protocol::commands::service_status::ServiceStatus;
 ///     println!("Service status: {:?}", status.state);
 ///     
 ///     // Get logs
-///     let logs = manager.get_stdout(&ServiceRef::Name(service_name.to_string())).await?;
+///     let logs = manager.get_logs(&GetServiceLogsPayload {
+///         service: ServiceRef::Name(service_name.to_string()),
+///         ..Default::default()
+///     }).await?;
 ///     println!("Service logs: {}", logs);
 ///     
 ///     Ok(())
@@ -175,30 +195,114 @@ pub(crate) trait ServiceManagement: ProcessManager {
     /// * Required fields are missing or invalid
     async fn add_service(&self, payload: &AddServicePayload) -> crate::error::Result<()>;
 
+    /// Copies a service under a new name and port.
+    ///
+    /// The clone gets its own copy of the configuration, so editing one doesn't affect the other.
+    /// Labels, profile and git settings are copied as well, and the dependencies of the source
+    /// when `copy_dependencies` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The service to clone and the name, port and optional repository path of
+    ///   the clone
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ServiceStatus>`] which is:
+    /// * `Ok(ServiceStatus)` - The status of the clone
+    /// * `Err(Error)` - If the clone operation fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * A service with the new name already exists
+    /// * Database operations fail, nothing is copied in that case
+    async fn clone_service(
+        &self,
+        payload: &CloneServicePayload,
+    ) -> crate::error::Result<ServiceStatus>;
+
     /// Removes a service from the system.
     ///
     /// This method removes a service from the database and performs complete
     /// cleanup including stopping the service if running, removing dependencies,
     /// and cleaning up associated configuration data.
     ///
+    /// A service that other services depend on is only removed when `force` is set, which
+    /// removes their dependencies on it as well. A dry run only returns what would be deleted.
+    ///
     /// # Arguments
     ///
-    /// * `payload` - The service reference (by name or ID) to remove
+    /// * `payload` - The service reference (by name or ID) to remove, and whether it's a dry run
+    ///   or forced
     ///
     /// # Returns
     ///
-    /// Returns [`Result<()>`] which is:
-    /// * `Ok(())` - Service removed successfully
+    /// Returns [`Result<RemovalPlan>`] which is:
+    /// * `Ok(RemovalPlan)` - What was removed, or would be for a dry run
     /// * `Err(Error)` - If the removal operation fails
     ///
     /// # Errors
     ///
     /// This method will return an error if:
     /// * The referenced service does not exist
+    /// * Other services depend on the service and `force` isn't set
     /// * The service cannot be stopped
     /// * Database operations fail
     /// * Dependency cleanup fails
-    async fn remove_service(&self, payload: &ServiceRef) -> crate::error::Result<()>;
+    async fn remove_service(
+        &self,
+        payload: &RemoveServicePayload,
+    ) -> crate::error::Result<RemovalPlan>;
+
+    /// Lists what [`remove_service`](Self::remove_service) would delete, without removing
+    /// anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The service reference (by name or ID) to plan the removal of
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<RemovalPlan>`] which is:
+    /// * `Ok(RemovalPlan)` - The dependencies and configuration that would be deleted and
+    ///   whether the service would be stopped
+    /// * `Err(Error)` - If the lookup fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * Database operations fail
+    async fn plan_removal(&self, payload: &ServiceRef) -> crate::error::Result<RemovalPlan>;
+
+    /// Applies a service manifest, creating and updating services to match it.
+    ///
+    /// All database changes are made in a single transaction. When pruning, services
+    /// that are not part of the manifest are stopped if running and then removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The manifest to apply and whether to prune unlisted services
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ApplyManifestResponse>`] which is:
+    /// * `Ok(ApplyManifestResponse)` - The list of changes that were made
+    /// * `Err(Error)` - If the manifest could not be applied
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The manifest lists a service more than once
+    /// * A dependency refers to an unknown service
+    /// * A service that is about to be pruned cannot be stopped
+    /// * Database operations fail
+    async fn apply_manifest(
+        &self,
+        payload: &ApplyManifestPayload,
+    ) -> crate::error::Result<ApplyManifestResponse>;
 
     /// Retrieves the current status of a service.
     ///
@@ -224,6 +328,52 @@ pub(crate) trait ServiceManagement: ProcessManager {
     /// * Status information is corrupted or incomplete
     async fn get_status(&self, payload: &ServiceRef) -> crate::error::Result<ServiceStatus>;
 
+    /// Retrieves only the current state of a service.
+    ///
+    /// Unlike [`get_status`](Self::get_status) this skips the detailed record with the config
+    /// and dependencies, which makes it cheap enough for frequent polling. Running services
+    /// referenced by ID are answered without touching the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The service reference (by name or ID)
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ServiceState>`] which is:
+    /// * `Ok(ServiceState)` - The live state of the service
+    /// * `Err(Error)` - If the state could not be retrieved
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * Database query operations fail
+    async fn get_state(&self, payload: &ServiceRef) -> crate::error::Result<ServiceState>;
+
+    /// Checks whether a service could be started, without starting it or changing anything.
+    ///
+    /// The repository path, port, run command, config file and dependencies are each checked
+    /// and reported as passed, failed or as a warning, see
+    /// [`service_manager::validate`](crate::service_manager::validate).
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The service reference (by name or ID)
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ValidationReport>`] which is:
+    /// * `Ok(ValidationReport)` - The outcome of every check, also when checks failed
+    /// * `Err(Error)` - If the service could not be validated
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * Database query operations fail
+    async fn validate(&self, payload: &ServiceRef) -> crate::error::Result<ValidationReport>;
+
     /// Retrieves a list of all services in the system.
     ///
     /// This method returns comprehensive information about all registered services
@@ -244,20 +394,135 @@ pub(crate) trait ServiceManagement: ProcessManager {
     /// * Dependency information cannot be retrieved
     async fn get_all(&self) -> crate::error::Result<ListServicesResponse>;
 
+    /// Starts every service in a profile that isn't running yet.
+    ///
+    /// The services are started one after another in the order they were added, a service
+    /// failing to start doesn't keep the others from being started.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The profile to start
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<()>`] which is:
+    /// * `Ok(())` - Every service in the profile is running
+    /// * `Err(Error)` - If the profile is empty or any of its services failed to start
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * No service belongs to the profile
+    /// * Any service of the profile fails to start, the error names all of them
+    /// * Database query operations fail
+    async fn start_profile(&self, payload: &ProfilePayload) -> crate::error::Result<()>;
+
+    /// Stops every running service in a profile.
+    ///
+    /// The services are stopped in the reverse order of [`start_profile`](Self::start_profile),
+    /// a service failing to stop doesn't keep the others from being stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The profile to stop
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<()>`] which is:
+    /// * `Ok(())` - No service in the profile is running
+    /// * `Err(Error)` - If the profile is empty or any of its services failed to stop
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * No service belongs to the profile
+    /// * Any service of the profile fails to stop, the error names all of them
+    /// * Database query operations fail
+    async fn stop_profile(&self, payload: &ProfilePayload) -> crate::error::Result<()>;
+
+    /// Retrieves the services in a profile, like [`get_all`](Self::get_all).
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The profile to list
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ListServicesResponse>`] which is:
+    /// * `Ok(ListServicesResponse)` - The services in the profile, empty for an unknown profile
+    /// * `Err(Error)` - If the retrieval operation fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * Database query operations fail
+    async fn list_by_profile(
+        &self,
+        payload: &ProfilePayload,
+    ) -> crate::error::Result<ListServicesResponse>;
+
+    /// Retrieves the services carrying every label of the selector, like
+    /// [`get_all`](Self::get_all).
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The `key=value` labels the services must all carry
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ListServicesResponse>`] which is:
+    /// * `Ok(ListServicesResponse)` - The matching services, all of them for an empty selector
+    /// * `Err(Error)` - If the retrieval operation fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * Database query operations fail
+    async fn list_by_labels(
+        &self,
+        payload: &LabelSelectorPayload,
+    ) -> crate::error::Result<ListServicesResponse>;
+
+    /// Searches the services by their name, repository URL and labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The text to search for, matched case-insensitively anywhere in the fields
+    ///
+    /// # Returns
+    ///
+    /// Returns [`Result<ListServicesResponse>`] which is:
+    /// * `Ok(ListServicesResponse)` - The matching services, name matches first, then
+    ///   repository URL matches and then label matches
+    /// * `Err(Error)` - If the search fails
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The query is empty
+    /// * Database query operations fail
+    async fn search_services(
+        &self,
+        payload: &SearchServicesPayload,
+    ) -> crate::error::Result<ListServicesResponse>;
+
     /// Retrieves the stdout logs for a running service.
     ///
     /// This method fetches the collected stdout output from a running service process.
     /// The logs are collected in real-time and stored in a circular buffer with a
     /// configurable maximum size to prevent memory exhaustion.
     ///
+    /// When `after_seq` is set only entries captured after that entry are returned, so a client
+    /// polling with the `last_seq` of the previous response receives every line exactly once.
+    ///
     /// # Arguments
     ///
-    /// * `payload` - The service reference (by name or ID)
+    /// * `payload` - The service reference (by name or ID) and the optional cursor
     ///
     /// # Returns
     ///
-    /// Returns [`Result<String>`] which is:
-    /// * `Ok(String)` - The collected stdout output as a concatenated string
+    /// Returns [`Result<ServiceStdout>`] which is:
+    /// * `Ok(ServiceStdout)` - The collected stdout output and the cursor for the next poll
     /// * `Err(Error)` - If log retrieval fails
     ///
     /// # Errors
@@ -271,33 +536,163 @@ pub(crate) trait ServiceManagement: ProcessManager {
     /// # Examples
     ///
     /// ```ignore
-    /// use nexsock_protocol::commands::manage_service::ServiceRef;
+    /// use nexsock_protocol::commands::stdout::GetServiceStdoutPayload;
     ///
-    /// let logs = manager.get_stdout(&ServiceRef::Name("webapp".to_string())).await?;
-    /// println!("Service output:\n{}", logs);
+    /// let mut payload = GetServiceStdoutPayload {
+    ///     service: ServiceRef::Name("webapp".to_string()),
+    ///     after_seq: None,
+    /// };
+    ///
+    /// let output = manager.get_stdout(&payload).await?;
+    /// print!("{}", output.content);
+    ///
+    /// // Only lines written since the previous call
+    /// payload.after_seq = Some(output.last_seq);
+    /// let output = manager.get_stdout(&payload).await?;
     /// ```
-    async fn get_stdout(&self, payload: &ServiceRef) -> crate::error::Result<String> {
-        let status = self.get_status(payload).await?;
+    async fn get_stdout(
+        &self,
+        payload: &GetServiceStdoutPayload,
+    ) -> crate::error::Result<ServiceStdout> {
+        let status = self.get_status(&payload.service).await?;
+
+        match self.running_services().try_get(&status.id) {
+            TryResult::Present(process) => {
+                let logs = process.stdout_logs.lock().await;
+                let (entries, last_seq) = entries_after(&logs, payload.after_seq);
+
+                Ok(ServiceStdout {
+                    content: entries.map(|entry| entry.content.as_str()).collect(),
+                    last_seq,
+                })
+            }
+            TryResult::Absent => Err(anyhow!("Service is not running").into()),
+            TryResult::Locked => Err(anyhow!("Service was locked, unable to get stdout").into()),
+        }
+    }
 
-        let process = self.running_services().try_get(&status.id);
+    /// Retrieves the captured output of a running service, filtered by log level.
+    ///
+    /// Entries below `min_level` are dropped. Entries without a detected level, which is every
+    /// entry of a service using the `raw` log format, are always included.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ServiceManagement::get_stdout`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use nexsock_protocol::commands::stdout::{GetServiceLogsPayload, LogLevel};
+    ///
+    /// let payload = GetServiceLogsPayload {
+    ///     service: ServiceRef::Name("webapp".to_string()),
+    ///     min_level: Some(LogLevel::Warn),
+    ///     raw: false,
+    /// };
+    /// let warnings = manager.get_logs(&payload).await?;
+    /// ```
+    async fn get_logs(&self, payload: &GetServiceLogsPayload) -> crate::error::Result<String> {
+        let status = self.get_status(&payload.service).await?;
 
-        let stdout = match process {
+        match self.running_services().try_get(&status.id) {
             TryResult::Present(process) => {
-                let stdout_logs = process.stdout_logs.lock().await;
-
-                // If no time filter, return all logs
-                stdout_logs
-                    .iter()
-                    .map(|entry| entry.content.clone())
-                    .collect::<Vec<String>>()
-                    .join("")
+                let logs = process.stdout_logs.lock().await;
+
+                Ok(render_logs(logs.iter(), payload.min_level, payload.raw))
             }
+            TryResult::Absent => Err(anyhow!("Service is not running").into()),
+            TryResult::Locked => Err(anyhow!("Service was locked, unable to get logs").into()),
+        }
+    }
+
+    /// Empties the buffered output of a running service, the process keeps running.
+    ///
+    /// Entries captured afterwards keep being numbered after the cleared ones, so clients polling
+    /// [`get_stdout`](Self::get_stdout) with their cursor receive everything written since the
+    /// clear. The daemon keeps no log files, only the buffer is cleared.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * The service is not currently running
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// manager.clear_logs(&ServiceRef::Name("webapp".to_string())).await?;
+    /// ```
+    async fn clear_logs(&self, service: &ServiceRef) -> crate::error::Result<()> {
+        let status = self.get_status(service).await?;
+
+        let logs = match self.running_services().try_get(&status.id) {
+            TryResult::Present(process) => process.stdout_logs.clone(),
             TryResult::Absent => return Err(anyhow!("Service is not running").into()),
             TryResult::Locked => {
-                return Err(anyhow!("Service was locked, unable to get stdout").into())
+                return Err(anyhow!("Service was locked, unable to clear logs").into())
             }
         };
 
-        Ok(stdout)
+        logs.lock().await.clear();
+
+        Ok(())
+    }
+
+    /// Writes bytes to the stdin of a running service, e.g. to answer a prompt.
+    ///
+    /// Once the process closed its stdin every further write fails with
+    /// [`Error::StdinClosed`](crate::error::Error::StdinClosed).
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if:
+    /// * The referenced service does not exist
+    /// * The service is not currently running
+    /// * The stdin of the service is closed
+    /// * Writing to the stdin fails
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use nexsock_protocol::commands::stdin::WriteStdinPayload;
+    ///
+    /// let payload = WriteStdinPayload {
+    ///     service: ServiceRef::Name("repl".to_string()),
+    ///     data: b"help\n".to_vec(),
+    /// };
+    /// manager.write_stdin(&payload).await?;
+    /// ```
+    async fn write_stdin(&self, payload: &WriteStdinPayload) -> crate::error::Result<()> {
+        let status = self.get_status(&payload.service).await?;
+
+        let stdin = match self.running_services().try_get(&status.id) {
+            TryResult::Present(process) => process.stdin.clone(),
+            TryResult::Absent => return Err(anyhow!("Service is not running").into()),
+            TryResult::Locked => {
+                return Err(anyhow!("Service was locked, unable to write to stdin").into())
+            }
+        };
+
+        let closed = || Error::StdinClosed {
+            service: status.name.clone(),
+        };
+
+        let mut stdin = stdin.lock().await;
+        let pipe = stdin.as_mut().ok_or_else(closed)?;
+
+        let written = match pipe.write_all(&payload.data).await {
+            Ok(()) => pipe.flush().await,
+            Err(e) => Err(e),
+        };
+
+        match written {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                *stdin = None;
+                Err(closed())
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 }